@@ -0,0 +1,64 @@
+//! Rendering hot paths: downscaling a color emoji bitmap, and drawing a
+//! long wrapped paragraph.
+//!
+//! `cargo bench --bench render --profile profiling`
+
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use tiny_skia::{FillRule, Paint, PathBuilder, Pixmap, Transform};
+use zenity_rs::{Canvas, Font};
+
+/// A 512×512 stand-in for a color emoji strike: an opaque disc with a
+/// gradient, antialiased against a transparent background.
+fn emoji_bitmap() -> Canvas {
+    let mut pixmap = Pixmap::new(512, 512).unwrap();
+    let mut paint = Paint {
+        anti_alias: true,
+        ..Paint::default()
+    };
+    for ring in 0..8 {
+        let r = 250.0 - ring as f32 * 30.0;
+        paint.set_color_rgba8(255, 200 - ring * 20, ring * 30, 255);
+        let path = PathBuilder::from_circle(256.0, 256.0, r).unwrap();
+        pixmap.fill_path(
+            &path,
+            &paint,
+            FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+    }
+    Canvas::from_png(&pixmap.encode_png().unwrap()).unwrap()
+}
+
+/// About a thousand glyphs of ordinary prose.
+fn paragraph() -> String {
+    let sentence = "The quick brown fox jumps over the lazy dog, then naps. ";
+    let mut text = String::new();
+    while text.chars().filter(|c| !c.is_whitespace()).count() < 1000 {
+        text.push_str(sentence);
+    }
+    text
+}
+
+fn scale_pixmap(c: &mut Criterion) {
+    let emoji = emoji_bitmap();
+    c.bench_function("scale_pixmap 512x512 to 20x20", |b| {
+        b.iter(|| black_box(&emoji).scaled(20, 20))
+    });
+    c.bench_function("scale_pixmap 512x512 to 64x64", |b| {
+        b.iter(|| black_box(&emoji).scaled(64, 64))
+    });
+}
+
+fn text(c: &mut Criterion) {
+    let font = Font::load(1.0);
+    let text = paragraph();
+    c.bench_function("render 1000-glyph paragraph", |b| {
+        b.iter(|| font.render(black_box(&text)).with_max_width(600.0).finish())
+    });
+}
+
+criterion_group!(benches, scale_pixmap, text);
+criterion_main!(benches);
@@ -0,0 +1,173 @@
+//! Embeds a small combo box in a question dialog via `custom_content`.
+//!
+//! Run with `cargo run --example custom_content`.
+
+use std::any::Any;
+
+use zenity_rs::{
+    Canvas, Colors, CursorPos, DialogResult, Font, MouseButton, Widget, WindowEvent, question,
+};
+
+const KEY_UP: u32 = 0xff52;
+const KEY_DOWN: u32 = 0xff54;
+
+const BASE_WIDTH: u32 = 220;
+const BASE_HEIGHT: u32 = 32;
+
+/// A minimal combo box: shows the selected option and cycles through the
+/// options on click or with the Up/Down arrow keys.
+struct ComboBox {
+    options: Vec<String>,
+    selected: usize,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    scale: f32,
+    font: Option<Font>,
+    cursor: CursorPos,
+}
+
+impl ComboBox {
+    fn new(options: &[&str]) -> Self {
+        Self {
+            options: options.iter().map(|s| s.to_string()).collect(),
+            selected: 0,
+            x: 0,
+            y: 0,
+            width: BASE_WIDTH,
+            height: BASE_HEIGHT,
+            scale: 1.0,
+            font: None,
+            cursor: CursorPos::default(),
+        }
+    }
+
+    fn selected(&self) -> &str {
+        &self.options[self.selected]
+    }
+
+    fn hovered(&self) -> bool {
+        let (cx, cy) = (self.cursor.x as i32, self.cursor.y as i32);
+        cx >= self.x
+            && cx < self.x + self.width as i32
+            && cy >= self.y
+            && cy < self.y + self.height as i32
+    }
+
+    fn step(&mut self, forward: bool) {
+        let len = self.options.len();
+        self.selected = if forward {
+            (self.selected + 1) % len
+        } else {
+            (self.selected + len - 1) % len
+        };
+    }
+}
+
+impl Widget for ComboBox {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn x(&self) -> i32 {
+        self.x
+    }
+
+    fn y(&self) -> i32 {
+        self.y
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+        self.width = (BASE_WIDTH as f32 * scale) as u32;
+        self.height = (BASE_HEIGHT as f32 * scale) as u32;
+        self.font = Some(Font::load(scale));
+    }
+
+    fn process_event(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::CursorMove(pos) => {
+                self.cursor = *pos;
+                false
+            }
+            WindowEvent::ButtonPress(MouseButton::Left, _) if self.hovered() => {
+                self.step(true);
+                true
+            }
+            WindowEvent::KeyPress(key) if key.keysym == KEY_DOWN => {
+                self.step(true);
+                true
+            }
+            WindowEvent::KeyPress(key) if key.keysym == KEY_UP => {
+                self.step(false);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn draw(&self, canvas: &mut Canvas, colors: &Colors) {
+        let radius = 4.0 * self.scale;
+        canvas.fill_rounded_rect(
+            self.x as f32,
+            self.y as f32,
+            self.width as f32,
+            self.height as f32,
+            radius,
+            colors.input_bg,
+        );
+        canvas.stroke_rounded_rect(
+            self.x as f32,
+            self.y as f32,
+            self.width as f32,
+            self.height as f32,
+            radius,
+            colors.input_border,
+            1.0,
+        );
+
+        let Some(font) = &self.font else {
+            return;
+        };
+        let pad = (8.0 * self.scale) as i32;
+        let label = font
+            .render(self.selected())
+            .with_color(colors.text)
+            .finish();
+        let ly = self.y + (self.height as i32 - label.height() as i32) / 2;
+        canvas.draw_canvas(&label, self.x + pad, ly);
+
+        let arrow = font.render("▾").with_color(colors.text).finish();
+        let ax = self.x + self.width as i32 - pad - arrow.width() as i32;
+        canvas.draw_canvas(&arrow, ax, ly);
+    }
+}
+
+fn main() -> Result<(), zenity_rs::Error> {
+    let combo = ComboBox::new(&["Default", "Work", "Gaming"]);
+
+    let (result, content) = question("Apply which profile?")
+        .title("Profiles")
+        .custom_content(Box::new(combo))
+        .show_with_content()?;
+
+    let content: Box<dyn Any> = content.expect("custom content is returned");
+    let combo = content
+        .downcast::<ComboBox>()
+        .expect("content is a ComboBox");
+
+    if let DialogResult::Button(0) = result {
+        println!("{}", combo.selected());
+    }
+    Ok(())
+}
@@ -0,0 +1,71 @@
+//! Walks through several dialog types using only the library, as an
+//! application embedding them instead of running `zenity-rs` would.
+//!
+//! ```sh
+//! cargo run --example dialogs
+//! ```
+
+use zenity_rs::{CalendarResult, EntryResult, FileSelectResult, ListResult, ScaleResult};
+
+fn main() -> Result<(), zenity_rs::Error> {
+    let name = match zenity_rs::entry()
+        .title("Trip planner")
+        .text("Who is travelling?")
+        .show()?
+    {
+        EntryResult::Text(name) => name,
+        result => std::process::exit(result.exit_code()),
+    };
+
+    let date = match zenity_rs::calendar()
+        .title("Trip planner")
+        .text("Leaving on:")
+        .show()?
+    {
+        result @ CalendarResult::Selected {
+            ..
+        } => result.to_string().unwrap_or_default(),
+        result => std::process::exit(result.exit_code()),
+    };
+
+    let destination = match zenity_rs::list()
+        .title("Trip planner")
+        .text("Going to:")
+        .column("City")
+        .column("Country")
+        .row(vec!["Lisbon".into(), "Portugal".into()])
+        .row(vec!["Kyoto".into(), "Japan".into()])
+        .row(vec!["Oaxaca".into(), "Mexico".into()])
+        .show()?
+    {
+        ListResult::Selected(cities) => cities.join(", "),
+        result => std::process::exit(result.exit_code()),
+    };
+
+    let nights = match zenity_rs::scale()
+        .title("Trip planner")
+        .text("Nights:")
+        .min_value(1)
+        .max_value(30)
+        .value(7)
+        .show()?
+    {
+        ScaleResult::Value(nights) => nights,
+        result => std::process::exit(result.exit_code()),
+    };
+
+    let itinerary = match zenity_rs::file_selection()
+        .title("Attach an itinerary (optional)")
+        .show()?
+    {
+        FileSelectResult::Selected(path) => path.display().to_string(),
+        _ => "none".to_string(),
+    };
+
+    let result = zenity_rs::info(&format!(
+        "{name} leaves for {destination} on {date} for {nights} nights.\nItinerary: {itinerary}"
+    ))
+    .title("Trip planner")
+    .show()?;
+    std::process::exit(result.exit_code());
+}
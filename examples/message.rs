@@ -0,0 +1,12 @@
+//! Shows a single message dialog, as an application embedding only the
+//! `message` feature would.
+//!
+//! ```sh
+//! cargo run --example message --no-default-features --features message,x11,wayland
+//! ```
+
+fn main() -> Result<(), zenity_rs::Error> {
+    let result = zenity_rs::question("Embedded dialogs work. Close this window?").show()?;
+    println!("{result:?}");
+    std::process::exit(result.exit_code());
+}
@@ -0,0 +1,77 @@
+//! Requests to bring the window back to the front, made from outside the
+//! event loop (for example by a second `--single-instance` process).
+//!
+//! A request stores its activation token and writes a byte to a pipe; the
+//! backends poll the read end next to their connection and, when it becomes
+//! readable, activate the window and emit [`WindowEvent::Activated`].
+//!
+//! [`WindowEvent::Activated`]: super::WindowEvent::Activated
+
+use std::{
+    os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+    sync::{Mutex, OnceLock},
+};
+
+/// A pending request; the inner value is the token, if the requester had one.
+static PENDING: Mutex<Option<Option<String>>> = Mutex::new(None);
+static WAKE_PIPE: OnceLock<Option<(OwnedFd, OwnedFd)>> = OnceLock::new();
+
+fn wake_pipe() -> Option<&'static (OwnedFd, OwnedFd)> {
+    WAKE_PIPE
+        .get_or_init(|| {
+            let mut fds = [0; 2];
+            let flags = libc::O_CLOEXEC | libc::O_NONBLOCK;
+            if unsafe { libc::pipe2(fds.as_mut_ptr(), flags) } != 0 {
+                return None;
+            }
+            Some(unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) })
+        })
+        .as_ref()
+}
+
+/// Asks the dialog to raise and focus its window. `token` is an
+/// xdg-activation token (Wayland) or startup ID (X11) from whoever asked,
+/// such as the launcher's `XDG_ACTIVATION_TOKEN`; a later request replaces
+/// an unhandled earlier one.
+///
+/// Safe to call from any thread, before or after the window exists.
+pub fn request_activation(token: Option<&str>) {
+    *PENDING.lock().unwrap_or_else(|e| e.into_inner()) = Some(token.map(str::to_string));
+    if let Some((_, write)) = wake_pipe() {
+        unsafe { libc::write(write.as_raw_fd(), [1u8].as_ptr().cast(), 1) };
+    }
+}
+
+/// The descriptor that becomes readable when a request is pending.
+pub(crate) fn wake_fd() -> Option<RawFd> {
+    wake_pipe().map(|(read, _)| read.as_raw_fd())
+}
+
+/// Takes the pending request, if any, and drains the wake pipe.
+pub(crate) fn take_request() -> Option<Option<String>> {
+    if let Some((read, _)) = wake_pipe() {
+        let mut buf = [0u8; 64];
+        while unsafe { libc::read(read.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len()) } > 0 {}
+    }
+    PENDING.lock().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+/// Waits up to `timeout_ms` (-1 for no limit) for `fd` to become readable
+/// or a request to come in. Returns whether `fd` is readable.
+pub(crate) fn poll(fd: RawFd, timeout_ms: i32) -> bool {
+    let mut fds = [
+        libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        },
+        // Negative descriptors are ignored
+        libc::pollfd {
+            fd: wake_fd().unwrap_or(-1),
+            events: libc::POLLIN,
+            revents: 0,
+        },
+    ];
+    let ret = unsafe { libc::poll(fds.as_mut_ptr(), 2, timeout_ms) };
+    ret > 0 && fds[0].revents != 0
+}
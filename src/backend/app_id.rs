@@ -0,0 +1,30 @@
+//! The application ID windows report (`--class`), which compositor and
+//! window manager rules match on: the Wayland app_id, or the X11 `WM_CLASS`.
+//!
+//! Like the [attachment](super::attach), the setting is process-wide and
+//! applied by [`create_window`] to every window it creates.
+//!
+//! [`create_window`]: super::create_window
+
+use std::sync::Mutex;
+
+use super::Window;
+use crate::error::Error;
+
+/// The application ID set with [`set_app_id`], if any.
+static APP_ID: Mutex<Option<String>> = Mutex::new(None);
+
+/// Makes the windows of dialogs shown from now on report `app_id` instead
+/// of "zenity".
+pub fn set_app_id(app_id: &str) {
+    *APP_ID.lock().unwrap_or_else(|e| e.into_inner()) = Some(app_id.to_string());
+}
+
+/// Applies the current setting to a newly created window, before it's shown.
+pub(crate) fn apply(window: &mut impl Window) -> Result<(), Error> {
+    let app_id = APP_ID.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    match app_id {
+        Some(app_id) => window.set_app_id(&app_id),
+        None => Ok(()),
+    }
+}
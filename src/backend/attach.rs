@@ -0,0 +1,47 @@
+//! Parenting dialogs to another application's window (`--attach`) and
+//! making them modal to it (`--modal`).
+//!
+//! The setting is process-wide and applied by [`create_window`] to every
+//! window it creates, so it covers any dialog type.
+//!
+//! [`create_window`]: super::create_window
+
+use std::sync::Mutex;
+
+use super::Window;
+use crate::error::Error;
+
+/// The parent handle, if any, and whether windows are modal.
+static ATTACHMENT: Mutex<(Option<String>, bool)> = Mutex::new((None, false));
+
+/// Stacks the windows of dialogs shown from now on above `parent`: an
+/// xdg-foreign exported handle on Wayland, or a window ID on X11. With
+/// `modal`, they are also marked modal and ask for keyboard focus.
+pub fn attach_to(parent: Option<&str>, modal: bool) {
+    *ATTACHMENT.lock().unwrap_or_else(|e| e.into_inner()) = (parent.map(str::to_string), modal);
+}
+
+/// Parses an X11 window ID, given in decimal or as `0x`-prefixed hex.
+pub fn x11_window_id(handle: &str) -> Option<u32> {
+    let handle = handle.trim();
+    let id = match handle
+        .strip_prefix("0x")
+        .or_else(|| handle.strip_prefix("0X"))
+    {
+        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+        None => handle.parse().ok()?,
+    };
+    (id != 0).then_some(id)
+}
+
+/// Applies the current setting to a newly created window.
+pub(crate) fn apply(window: &mut impl Window) -> Result<(), Error> {
+    let (parent, modal) = ATTACHMENT.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(parent) = parent {
+        window.set_parent(&parent)?;
+    }
+    if modal {
+        window.set_modal(true)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,230 @@
+//! A scripted stand-in for the display server, for testing dialogs without
+//! one.
+//!
+//! [`script`] sets what the next window created on the calling thread sees:
+//! its events in order, then a lost connection. Drawing and window requests
+//! all succeed and do nothing; the clipboard holds whatever the window itself
+//! copied.
+//!
+//! Scripts are mostly key presses, typing and clicks, which [`MockEvent`] has
+//! constructors for, along with the keysyms the dialogs respond to.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    io,
+};
+
+use super::{CursorPos, CursorShape, KeyEvent, Modifiers, MouseButton, Window, WindowEvent};
+use crate::{error::Error, render::Canvas, ui::geometry::Offset};
+
+// XKB keysyms, for MockEvent::key
+pub const KEY_BACKSPACE: u32 = crate::ui::KEY_BACKSPACE;
+pub const KEY_TAB: u32 = crate::ui::KEY_TAB;
+pub const KEY_RETURN: u32 = crate::ui::KEY_RETURN;
+pub const KEY_ESCAPE: u32 = crate::ui::KEY_ESCAPE;
+pub const KEY_HOME: u32 = crate::ui::KEY_HOME;
+pub const KEY_LEFT: u32 = crate::ui::KEY_LEFT;
+pub const KEY_UP: u32 = crate::ui::KEY_UP;
+pub const KEY_RIGHT: u32 = crate::ui::KEY_RIGHT;
+pub const KEY_DOWN: u32 = crate::ui::KEY_DOWN;
+pub const KEY_PAGE_UP: u32 = crate::ui::KEY_PAGE_UP;
+pub const KEY_PAGE_DOWN: u32 = crate::ui::KEY_PAGE_DOWN;
+pub const KEY_END: u32 = crate::ui::KEY_END;
+pub const KEY_DELETE: u32 = crate::ui::KEY_DELETE;
+pub const KEY_ISO_LEFT_TAB: u32 = crate::ui::KEY_ISO_LEFT_TAB;
+pub const KEY_A: u32 = crate::ui::KEY_A;
+pub const KEY_F: u32 = crate::ui::KEY_F;
+pub const KEY_L: u32 = crate::ui::KEY_L;
+
+/// One step of a [`script`].
+#[derive(Debug, Clone)]
+pub enum MockEvent {
+    /// The window receives this event.
+    Event(WindowEvent),
+    /// The connection breaks, the way a crashed compositor's would.
+    Disconnect,
+}
+
+impl MockEvent {
+    /// A press of `keysym` with no modifiers held.
+    pub fn key(keysym: u32) -> Self {
+        Self::key_with(keysym, Modifiers::empty())
+    }
+
+    /// A press of `keysym` with `modifiers` held.
+    pub fn key_with(keysym: u32, modifiers: Modifiers) -> Self {
+        Self::Event(WindowEvent::KeyPress(KeyEvent {
+            keysym,
+            modifiers,
+        }))
+    }
+
+    /// `text` typed a character at a time.
+    pub fn type_text(text: &str) -> Vec<Self> {
+        text.chars()
+            .map(|c| Self::Event(WindowEvent::TextInput(c)))
+            .collect()
+    }
+
+    /// The cursor moving to `(x, y)`.
+    pub fn move_to((x, y): (i16, i16)) -> Self {
+        Self::Event(WindowEvent::CursorMove(CursorPos {
+            x,
+            y,
+        }))
+    }
+
+    /// The left button going down wherever the cursor is.
+    pub fn mouse_down() -> Self {
+        Self::Event(WindowEvent::ButtonPress(
+            MouseButton::Left,
+            Modifiers::empty(),
+        ))
+    }
+
+    /// The left button coming back up.
+    pub fn mouse_up() -> Self {
+        Self::Event(WindowEvent::ButtonRelease(
+            MouseButton::Left,
+            Modifiers::empty(),
+        ))
+    }
+
+    /// A left click at `(x, y)`, moving there first.
+    pub fn click(at: (i16, i16)) -> [Self; 3] {
+        [Self::move_to(at), Self::mouse_down(), Self::mouse_up()]
+    }
+}
+
+thread_local! {
+    static SCRIPT: RefCell<Option<VecDeque<MockEvent>>> = const { RefCell::new(None) };
+    static WINDOW_SIZE: RefCell<Option<(u16, u16)>> = const { RefCell::new(None) };
+    static LIST_CACHE_HIT_RATE: Cell<Option<f64>> = const { Cell::new(None) };
+}
+
+/// Makes the next window created on this thread a mock one that plays
+/// `events`, then disconnects.
+pub fn script(events: impl IntoIterator<Item = MockEvent>) {
+    SCRIPT.with(|script| *script.borrow_mut() = Some(events.into_iter().collect()));
+}
+
+/// The size the last mock window made on this thread was asked for, as
+/// passed to the backend (logical, with the zoom applied).
+pub fn window_size() -> Option<(u16, u16)> {
+    WINDOW_SIZE.with(|size| *size.borrow())
+}
+
+/// The share of row lookups the last mock window's list found already
+/// rendered, as of its last draw; `None` if it drew no rows.
+pub fn list_cache_hit_rate() -> Option<f64> {
+    LIST_CACHE_HIT_RATE.with(Cell::get)
+}
+
+pub(crate) fn record_list_cache_hit_rate(rate: f64) {
+    LIST_CACHE_HIT_RATE.with(|hit_rate| hit_rate.set(Some(rate)));
+}
+
+/// The mock window [`script`] asked for, if any, `width` by `height`.
+pub(crate) fn take_window(width: u16, height: u16) -> Option<MockWindow> {
+    SCRIPT
+        .with(|script| script.borrow_mut().take())
+        .map(|events| {
+            WINDOW_SIZE.with(|size| *size.borrow_mut() = Some((width, height)));
+            LIST_CACHE_HIT_RATE.with(|hit_rate| hit_rate.set(None));
+            MockWindow {
+                events,
+                clipboard: None,
+            }
+        })
+}
+
+pub(crate) struct MockWindow {
+    events: VecDeque<MockEvent>,
+    /// Only this window's own copies; it starts out empty.
+    clipboard: Option<String>,
+}
+
+impl MockWindow {
+    fn next(&mut self) -> Result<WindowEvent, Error> {
+        match self.events.pop_front() {
+            Some(MockEvent::Event(event)) => Ok(event),
+            // Reported as the socket would, for the backend to classify
+            Some(MockEvent::Disconnect) | None => {
+                self.events.clear();
+                Err(io::Error::from(io::ErrorKind::BrokenPipe).into())
+            }
+        }
+    }
+}
+
+impl Window for MockWindow {
+    fn set_title(&mut self, _title: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn set_app_id(&mut self, _app_id: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn set_contents(&mut self, _canvas: &Canvas) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn set_contents_rects(
+        &mut self,
+        _canvas: &Canvas,
+        _rects: &[(u32, u32, u32, u32)],
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn show(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn wait_for_event(&mut self) -> Result<WindowEvent, Error> {
+        self.next()
+    }
+
+    fn poll_for_event(&mut self) -> Result<Option<WindowEvent>, Error> {
+        self.next().map(Some)
+    }
+
+    fn start_drag(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn scale_factor(&self) -> f32 {
+        super::DEFAULT_SCALE
+    }
+
+    fn set_cursor(&mut self, _shape: CursorShape) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn set_position(&mut self, _x: Offset, _y: Offset) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn write_clipboard(&mut self, text: &str) -> Result<(), Error> {
+        self.clipboard = Some(text.to_string());
+        Ok(())
+    }
+
+    fn read_clipboard(&mut self) -> Result<Option<String>, Error> {
+        Ok(self.clipboard.clone())
+    }
+
+    fn activate(&mut self, _token: Option<&str>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn set_parent(&mut self, _handle: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn set_modal(&mut self, _modal: bool) -> Result<(), Error> {
+        Ok(())
+    }
+}
@@ -1,15 +1,42 @@
+pub(crate) mod activation;
+pub(crate) mod app_id;
+pub(crate) mod attach;
+#[cfg(feature = "mock-backend")]
+pub mod mock;
 #[cfg(feature = "wayland")]
 pub(crate) mod wayland;
 #[cfg(feature = "x11")]
 pub(crate) mod x11;
 
+use std::{collections::VecDeque, time::Duration};
+
 use bitflags::bitflags;
 
-use crate::{error::Error, render::Canvas};
+use crate::{
+    error::Error,
+    render::Canvas,
+    ui::{geometry::Offset, zoom},
+};
 
 /// Default scale factor for rendering
 pub(crate) const DEFAULT_SCALE: f32 = 1.0;
 
+/// Pasted text is cut short after this many bytes.
+pub(crate) const MAX_CLIPBOARD_BYTES: usize = 1 << 20;
+
+/// How long to wait for another client to hand over the clipboard.
+pub(crate) const CLIPBOARD_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Decodes clipboard bytes, dropping a character the size limit cut in half.
+pub(crate) fn clipboard_text(mut bytes: Vec<u8>) -> String {
+    if let Err(e) = std::str::from_utf8(&bytes)
+        && e.error_len().is_none()
+    {
+        bytes.truncate(e.valid_up_to());
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
 /// Trait for connecting to a display server.
 pub(crate) trait DisplayConnection: Sized {
     type Window: Window;
@@ -26,11 +53,18 @@ pub(crate) enum CursorShape {
     Default,
     /// Text input (I-beam) cursor.
     Text,
+    /// Horizontal double arrow, for dragging a column boundary.
+    ColResize,
+    /// Pointing hand, over a link.
+    Pointer,
 }
 
 /// Trait for interacting with a window.
 pub(crate) trait Window {
     fn set_title(&mut self, title: &str) -> Result<(), Error>;
+    /// Sets the application ID window rules match on. Only takes effect
+    /// before the window is shown; backends without one ignore it.
+    fn set_app_id(&mut self, app_id: &str) -> Result<(), Error>;
     fn set_contents(&mut self, canvas: &Canvas) -> Result<(), Error>;
     /// Uploads only the given sub-rectangles of `canvas` to the window. Each
     /// rect is `(x, y, w, h)` in canvas (physical) coordinates. An empty slice
@@ -47,12 +81,33 @@ pub(crate) trait Window {
     fn start_drag(&mut self) -> Result<(), Error>;
     fn scale_factor(&self) -> f32;
     fn set_cursor(&mut self, shape: CursorShape) -> Result<(), Error>;
+    /// Requests a position for the window, resolving [`Offset::End`] against
+    /// the output size. Backends that can't position windows ignore it.
+    fn set_position(&mut self, x: Offset, y: Offset) -> Result<(), Error>;
+    /// Offers `text` as the clipboard contents. Other clients can paste it
+    /// for as long as the window exists.
+    fn write_clipboard(&mut self, text: &str) -> Result<(), Error>;
+    /// Returns the clipboard contents as text, cut short after
+    /// [`MAX_CLIPBOARD_BYTES`], or `None` if it holds no text. Gives up
+    /// after [`CLIPBOARD_TIMEOUT`] if the owner doesn't answer.
+    fn read_clipboard(&mut self) -> Result<Option<String>, Error>;
+    /// Raises and focuses the window, then emits [`WindowEvent::Activated`].
+    /// `token` is an activation token or startup ID from whoever asked; on
+    /// Wayland, without one the window requests its own, which compositors
+    /// may decline to honor.
+    fn activate(&mut self, token: Option<&str>) -> Result<(), Error>;
+    /// Stacks the window above another application's window and groups it
+    /// with it. `handle` is an xdg-foreign exported handle on Wayland or a
+    /// window ID on X11; one the backend can't use is ignored with a warning.
+    fn set_parent(&mut self, handle: &str) -> Result<(), Error>;
+    /// Marks the window modal to its parent and asks for keyboard focus.
+    fn set_modal(&mut self, modal: bool) -> Result<(), Error>;
 }
 
 /// Events that can be emitted by a window.
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
-pub(crate) enum WindowEvent {
+pub enum WindowEvent {
     CloseRequested,
     RedrawRequested,
     CursorEnter(CursorPos),
@@ -60,20 +115,81 @@ pub(crate) enum WindowEvent {
     CursorLeave,
     ButtonPress(MouseButton, Modifiers),
     ButtonRelease(MouseButton, Modifiers),
-    Scroll(ScrollDirection),
+    Scroll(ScrollDirection, Modifiers),
     KeyPress(KeyEvent),
     KeyRelease(KeyEvent),
     TextInput(char),
+    /// The window was brought back to the front while open, e.g. because the
+    /// dialog was launched again with the same `--single-instance` key.
+    Activated,
+}
+
+/// A FIFO of window events that collapses each run of consecutive
+/// [`WindowEvent::CursorMove`]s into the latest one.
+///
+/// High-rate mice can report a thousand motions a second; only the last
+/// position before anything else happens matters. Motion is never merged
+/// across other events, so presses and releases still see the position they
+/// happened at.
+#[derive(Debug, Default)]
+pub struct PendingEvents {
+    events: VecDeque<WindowEvent>,
+}
+
+impl PendingEvents {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push_back(&mut self, event: WindowEvent) {
+        if let WindowEvent::CursorMove(pos) = event
+            && let Some(WindowEvent::CursorMove(last)) = self.events.back_mut()
+        {
+            *last = pos;
+            return;
+        }
+        self.events.push_back(event);
+    }
+
+    pub fn pop_front(&mut self) -> Option<WindowEvent> {
+        self.events.pop_front()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Where the pointer ends up once the queued events are handled: `None`
+    /// if none of them moves it or it leaves the window last.
+    pub fn pending_motion(&self) -> Option<CursorPos> {
+        self.events
+            .iter()
+            .rev()
+            .find_map(|event| {
+                match event {
+                    WindowEvent::CursorMove(pos) | WindowEvent::CursorEnter(pos) => {
+                        Some(Some(*pos))
+                    }
+                    WindowEvent::CursorLeave => Some(None),
+                    _ => None,
+                }
+            })
+            .flatten()
+    }
 }
 
 #[derive(Debug, Clone, Copy, Default)]
-pub(crate) struct CursorPos {
+pub struct CursorPos {
     pub x: i16,
     pub y: i16,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum MouseButton {
+pub enum MouseButton {
     Left,
     Middle,
     Right,
@@ -81,7 +197,7 @@ pub(crate) enum MouseButton {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(dead_code)]
-pub(crate) enum ScrollDirection {
+pub enum ScrollDirection {
     Up,
     Down,
     Left,
@@ -89,14 +205,14 @@ pub(crate) enum ScrollDirection {
 }
 
 #[derive(Debug, Clone)]
-pub(crate) struct KeyEvent {
+pub struct KeyEvent {
     pub keysym: u32,
     pub modifiers: Modifiers,
 }
 
 bitflags! {
     #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
-    pub(crate) struct Modifiers: u8 {
+    pub struct Modifiers: u8 {
         const SHIFT = 0x01;
         const CTRL  = 0x02;
         const ALT   = 0x04;
@@ -105,11 +221,17 @@ bitflags! {
 }
 
 /// Type-erased window that can be either X11 or Wayland.
+///
+/// Windows are `Send`: a dialog may be built and shown on any thread, and
+/// several may run at once on different threads, each with its own
+/// connection.
 pub(crate) enum AnyWindow {
     #[cfg(feature = "x11")]
     X11(Box<x11::X11Window>),
     #[cfg(feature = "wayland")]
     Wayland(Box<wayland::WaylandWindow>),
+    #[cfg(feature = "mock-backend")]
+    Mock(Box<mock::MockWindow>),
 }
 
 impl Window for AnyWindow {
@@ -119,16 +241,32 @@ impl Window for AnyWindow {
             AnyWindow::X11(w) => w.set_title(title),
             #[cfg(feature = "wayland")]
             AnyWindow::Wayland(w) => w.set_title(title),
+            #[cfg(feature = "mock-backend")]
+            AnyWindow::Mock(w) => w.set_title(title),
         }
     }
 
-    fn set_contents(&mut self, canvas: &Canvas) -> Result<(), Error> {
+    fn set_app_id(&mut self, app_id: &str) -> Result<(), Error> {
         match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => w.set_app_id(app_id),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => w.set_app_id(app_id),
+            #[cfg(feature = "mock-backend")]
+            AnyWindow::Mock(w) => w.set_app_id(app_id),
+        }
+    }
+
+    fn set_contents(&mut self, canvas: &Canvas) -> Result<(), Error> {
+        let result = match self {
             #[cfg(feature = "x11")]
             AnyWindow::X11(w) => w.set_contents(canvas),
             #[cfg(feature = "wayland")]
             AnyWindow::Wayland(w) => w.set_contents(canvas),
-        }
+            #[cfg(feature = "mock-backend")]
+            AnyWindow::Mock(w) => w.set_contents(canvas),
+        };
+        result.map_err(connection_lost)
     }
 
     fn set_contents_rects(
@@ -136,12 +274,15 @@ impl Window for AnyWindow {
         canvas: &Canvas,
         rects: &[(u32, u32, u32, u32)],
     ) -> Result<(), Error> {
-        match self {
+        let result = match self {
             #[cfg(feature = "x11")]
             AnyWindow::X11(w) => w.set_contents_rects(canvas, rects),
             #[cfg(feature = "wayland")]
             AnyWindow::Wayland(w) => w.set_contents_rects(canvas, rects),
-        }
+            #[cfg(feature = "mock-backend")]
+            AnyWindow::Mock(w) => w.set_contents_rects(canvas, rects),
+        };
+        result.map_err(connection_lost)
     }
 
     fn show(&mut self) -> Result<(), Error> {
@@ -150,25 +291,33 @@ impl Window for AnyWindow {
             AnyWindow::X11(w) => w.show(),
             #[cfg(feature = "wayland")]
             AnyWindow::Wayland(w) => w.show(),
+            #[cfg(feature = "mock-backend")]
+            AnyWindow::Mock(w) => w.show(),
         }
     }
 
     fn wait_for_event(&mut self) -> Result<WindowEvent, Error> {
-        match self {
+        let result = match self {
             #[cfg(feature = "x11")]
             AnyWindow::X11(w) => w.wait_for_event(),
             #[cfg(feature = "wayland")]
             AnyWindow::Wayland(w) => w.wait_for_event(),
-        }
+            #[cfg(feature = "mock-backend")]
+            AnyWindow::Mock(w) => w.wait_for_event(),
+        };
+        result.map_err(connection_lost)
     }
 
     fn poll_for_event(&mut self) -> Result<Option<WindowEvent>, Error> {
-        match self {
+        let result = match self {
             #[cfg(feature = "x11")]
             AnyWindow::X11(w) => w.poll_for_event(),
             #[cfg(feature = "wayland")]
             AnyWindow::Wayland(w) => w.poll_for_event(),
-        }
+            #[cfg(feature = "mock-backend")]
+            AnyWindow::Mock(w) => w.poll_for_event(),
+        };
+        result.map_err(connection_lost)
     }
 
     fn start_drag(&mut self) -> Result<(), Error> {
@@ -177,16 +326,22 @@ impl Window for AnyWindow {
             AnyWindow::X11(w) => w.start_drag(),
             #[cfg(feature = "wayland")]
             AnyWindow::Wayland(w) => w.start_drag(),
+            #[cfg(feature = "mock-backend")]
+            AnyWindow::Mock(w) => w.start_drag(),
         }
     }
 
+    /// The display scale times the [zoom](crate::ui::zoom).
     fn scale_factor(&self) -> f32 {
-        match self {
+        let scale = match self {
             #[cfg(feature = "x11")]
             AnyWindow::X11(w) => w.scale_factor(),
             #[cfg(feature = "wayland")]
             AnyWindow::Wayland(w) => w.scale_factor(),
-        }
+            #[cfg(feature = "mock-backend")]
+            AnyWindow::Mock(w) => w.scale_factor(),
+        };
+        scale * zoom::zoom()
     }
 
     fn set_cursor(&mut self, shape: CursorShape) -> Result<(), Error> {
@@ -195,20 +350,134 @@ impl Window for AnyWindow {
             AnyWindow::X11(w) => w.set_cursor(shape),
             #[cfg(feature = "wayland")]
             AnyWindow::Wayland(w) => w.set_cursor(shape),
+            #[cfg(feature = "mock-backend")]
+            AnyWindow::Mock(w) => w.set_cursor(shape),
+        }
+    }
+
+    fn set_position(&mut self, x: Offset, y: Offset) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => w.set_position(x, y),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => w.set_position(x, y),
+            #[cfg(feature = "mock-backend")]
+            AnyWindow::Mock(w) => w.set_position(x, y),
+        }
+    }
+
+    fn write_clipboard(&mut self, text: &str) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => w.write_clipboard(text),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => w.write_clipboard(text),
+            #[cfg(feature = "mock-backend")]
+            AnyWindow::Mock(w) => w.write_clipboard(text),
+        }
+    }
+
+    fn read_clipboard(&mut self) -> Result<Option<String>, Error> {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => w.read_clipboard(),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => w.read_clipboard(),
+            #[cfg(feature = "mock-backend")]
+            AnyWindow::Mock(w) => w.read_clipboard(),
+        }
+    }
+
+    fn activate(&mut self, token: Option<&str>) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => w.activate(token),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => w.activate(token),
+            #[cfg(feature = "mock-backend")]
+            AnyWindow::Mock(w) => w.activate(token),
+        }
+    }
+
+    fn set_parent(&mut self, handle: &str) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => w.set_parent(handle),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => w.set_parent(handle),
+            #[cfg(feature = "mock-backend")]
+            AnyWindow::Mock(w) => w.set_parent(handle),
+        }
+    }
+
+    fn set_modal(&mut self, modal: bool) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => w.set_modal(modal),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => w.set_modal(modal),
+            #[cfg(feature = "mock-backend")]
+            AnyWindow::Mock(w) => w.set_modal(modal),
         }
     }
 }
 
+/// Reports an error that means the display connection is gone (EOF or a
+/// broken pipe on its socket, or a protocol error, which also ends it) as
+/// [`Error::DisplayLost`].
+fn connection_lost(e: Error) -> Error {
+    match e {
+        #[cfg(feature = "x11")]
+        Error::X11(crate::error::X11Error::Connection(_)) => Error::DisplayLost,
+        #[cfg(feature = "wayland")]
+        Error::Wayland(crate::error::WaylandError::Dispatch(_)) => Error::DisplayLost,
+        Error::Io(e)
+            if matches!(
+                e.kind(),
+                std::io::ErrorKind::BrokenPipe
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::NotConnected
+                    | std::io::ErrorKind::UnexpectedEof
+            ) =>
+        {
+            Error::DisplayLost
+        }
+        e => e,
+    }
+}
+
+// Fails to build if a backend starts holding something tied to one thread
+const _: () = {
+    const fn assert_send<T: Send>() {}
+    assert_send::<AnyWindow>();
+};
+
 /// Creates a window using the best available backend.
 /// Prefers Wayland, falls back to X11.
+///
+/// `width` and `height` are logical and grow with the [zoom](crate::ui::zoom).
 pub(crate) fn create_window(width: u16, height: u16) -> Result<AnyWindow, Error> {
+    let (width, height) = (zoom::zoomed(width), zoom::zoomed(height));
+
+    #[cfg(feature = "mock-backend")]
+    if let Some(window) = mock::take_window(width, height) {
+        return Ok(AnyWindow::Mock(Box::new(window)));
+    }
+
     #[cfg(feature = "wayland")]
-    if let Some(window) = try_wayland(width, height) {
+    if let Some(mut window) = try_wayland(width, height) {
+        app_id::apply(&mut window)?;
+        attach::apply(&mut window)?;
         return Ok(window);
     }
 
     #[cfg(feature = "x11")]
-    return try_x11(width, height);
+    return try_x11(width, height).and_then(|mut window| {
+        app_id::apply(&mut window)?;
+        attach::apply(&mut window)?;
+        Ok(window)
+    });
 
     #[cfg(not(any(feature = "x11", feature = "wayland")))]
     compile_error!("At least one of 'x11' or 'wayland' features must be enabled");
@@ -290,18 +559,26 @@ fn try_x11(width: u16, height: u16) -> Result<AnyWindow, Error> {
     Ok(AnyWindow::X11(Box::new(w)))
 }
 
+/// Serializes the `WAYLAND_DISPLAY` juggling in [`SocketGuard`], so dialogs
+/// opened on several threads at once don't race on the environment.
+#[cfg(feature = "wayland")]
+static SOCKET_ENV: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
 #[cfg(feature = "wayland")]
 struct SocketGuard {
     old_value: Option<std::ffi::OsString>,
+    _lock: std::sync::MutexGuard<'static, ()>,
 }
 
 #[cfg(feature = "wayland")]
 impl SocketGuard {
     fn new(path: &str) -> Self {
+        let lock = SOCKET_ENV.lock().unwrap_or_else(|e| e.into_inner());
         let old_value = std::env::var_os("WAYLAND_DISPLAY");
         unsafe { std::env::set_var("WAYLAND_DISPLAY", path) };
         Self {
             old_value,
+            _lock: lock,
         }
     }
 }
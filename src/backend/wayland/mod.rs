@@ -3,18 +3,23 @@
 mod shm;
 
 use std::{
-    collections::VecDeque,
-    os::fd::{AsFd, AsRawFd, FromRawFd, IntoRawFd},
+    io::{Read as _, Write as _},
+    os::fd::{AsFd, AsRawFd, FromRawFd, IntoRawFd, OwnedFd},
+    sync::Mutex,
     time::{Duration, Instant},
 };
 
 use kbvm::lookup::LookupTable;
 use wayland_client::{
-    Connection as WaylandConnection, Dispatch, EventQueue, QueueHandle, WEnum,
+    Connection as WaylandConnection, Dispatch, EventQueue, QueueHandle, WEnum, event_created_child,
     protocol::{
         wl_buffer::{self, WlBuffer},
         wl_callback::{self, WlCallback},
         wl_compositor::WlCompositor,
+        wl_data_device::{self, WlDataDevice},
+        wl_data_device_manager::WlDataDeviceManager,
+        wl_data_offer::{self, WlDataOffer},
+        wl_data_source::{self, WlDataSource},
         wl_keyboard::{self, WlKeyboard},
         wl_output::{self, WlOutput},
         wl_pointer::{self, WlPointer},
@@ -25,20 +30,33 @@ use wayland_client::{
         wl_surface::WlSurface,
     },
 };
-use wayland_protocols::xdg::shell::client::{
-    xdg_surface::{self, XdgSurface},
-    xdg_toplevel::{self, XdgToplevel},
-    xdg_wm_base::{self, XdgWmBase},
+use wayland_protocols::xdg::{
+    activation::v1::client::{
+        xdg_activation_token_v1::{self, XdgActivationTokenV1},
+        xdg_activation_v1::XdgActivationV1,
+    },
+    dialog::v1::client::{xdg_dialog_v1::XdgDialogV1, xdg_wm_dialog_v1::XdgWmDialogV1},
+    foreign::zv2::client::{
+        zxdg_imported_v2::{self, ZxdgImportedV2},
+        zxdg_importer_v2::ZxdgImporterV2,
+    },
+    shell::client::{
+        xdg_surface::{self, XdgSurface},
+        xdg_toplevel::{self, XdgToplevel},
+        xdg_wm_base::{self, XdgWmBase},
+    },
 };
 
 use self::shm::ShmPool;
 use super::{
-    CursorPos, CursorShape, DEFAULT_SCALE, DisplayConnection, KeyEvent, Modifiers, MouseButton,
-    ScrollDirection, Window, WindowEvent,
+    CLIPBOARD_TIMEOUT, CursorPos, CursorShape, DEFAULT_SCALE, DisplayConnection, KeyEvent,
+    MAX_CLIPBOARD_BYTES, Modifiers, MouseButton, PendingEvents, ScrollDirection, Window,
+    WindowEvent, activation, attach, clipboard_text,
 };
 use crate::{
     error::{Error, WaylandError},
     render::Canvas,
+    ui::geometry::Offset,
 };
 
 /// Wayland connection wrapper.
@@ -69,15 +87,29 @@ pub(super) struct WaylandState {
     xdg_wm_base: Option<XdgWmBase>,
     seat: Option<WlSeat>,
     output: Option<WlOutput>,
+    data_device_manager: Option<WlDataDeviceManager>,
+    activation: Option<XdgActivationV1>,
+    importer: Option<ZxdgImporterV2>,
+    wm_dialog: Option<XdgWmDialogV1>,
 
     // Input devices
     pointer: Option<WlPointer>,
     keyboard: Option<WlKeyboard>,
+    data_device: Option<WlDataDevice>,
+
+    // Clipboard state
+    /// Source we offered as the selection, and the text it serves.
+    clipboard_source: Option<(WlDataSource, String)>,
+    /// Offer for the current selection, which we must destroy when replaced.
+    selection_offer: Option<WlDataOffer>,
 
     // Window state
     surface: Option<WlSurface>,
     xdg_surface: Option<XdgSurface>,
     xdg_toplevel: Option<XdgToplevel>,
+    /// The other application's window we're attached to.
+    parent: Option<ZxdgImportedV2>,
+    dialog: Option<XdgDialogV1>,
 
     // Configuration state
     configured: bool,
@@ -102,7 +134,7 @@ pub(super) struct WaylandState {
     repeat_key: Option<RepeatKey>,
 
     // Events
-    pending_events: VecDeque<WindowEvent>,
+    pending_events: PendingEvents,
 }
 
 /// Tracks a held key for client-side repeat.
@@ -121,11 +153,20 @@ impl WaylandState {
             xdg_wm_base: None,
             seat: None,
             output: None,
+            data_device_manager: None,
+            activation: None,
+            importer: None,
+            wm_dialog: None,
             pointer: None,
             keyboard: None,
+            data_device: None,
+            clipboard_source: None,
+            selection_offer: None,
             surface: None,
             xdg_surface: None,
             xdg_toplevel: None,
+            parent: None,
+            dialog: None,
             configured: false,
             closed: false,
             output_scale: 1,
@@ -137,7 +178,7 @@ impl WaylandState {
             repeat_rate: 25,
             repeat_delay: 600,
             repeat_key: None,
-            pending_events: VecDeque::new(),
+            pending_events: PendingEvents::new(),
         }
     }
 
@@ -254,6 +295,9 @@ impl WaylandWindow {
         if let Some(seat) = &state.seat.clone() {
             state.pointer = Some(seat.get_pointer(&qh, ()));
             state.keyboard = Some(seat.get_keyboard(&qh, ()));
+            if let Some(manager) = &state.data_device_manager {
+                state.data_device = Some(manager.get_data_device(seat, &qh, ()));
+            }
         }
 
         // Create cursor theme and surface
@@ -281,6 +325,8 @@ impl WaylandWindow {
         let cursor_name = match self.current_cursor {
             CursorShape::Default => "default",
             CursorShape::Text => "text",
+            CursorShape::ColResize => "col-resize",
+            CursorShape::Pointer => "pointer",
         };
 
         if let Some(cursor) = self.cursor_theme.get_cursor(cursor_name) {
@@ -303,6 +349,24 @@ impl WaylandWindow {
             }
         }
     }
+
+    /// Asks the compositor to focus the window with `token`, or with a token
+    /// of our own if there is none.
+    fn request_focus(&self, token: Option<&str>) {
+        if let (Some(activation), Some(surface)) = (&self.state.activation, &self.state.surface) {
+            match token {
+                Some(token) => activation.activate(token.to_string(), surface),
+                None => {
+                    let request = activation.get_activation_token(&self.event_queue.handle(), ());
+                    request.set_surface(surface);
+                    if let Some(seat) = &self.state.seat {
+                        request.set_serial(self.state.last_serial, seat);
+                    }
+                    request.commit();
+                }
+            }
+        }
+    }
 }
 
 impl Window for WaylandWindow {
@@ -313,11 +377,21 @@ impl Window for WaylandWindow {
         Ok(())
     }
 
+    fn set_app_id(&mut self, app_id: &str) -> Result<(), Error> {
+        if let Some(toplevel) = &self.state.xdg_toplevel {
+            toplevel.set_app_id(app_id.to_string());
+        }
+        Ok(())
+    }
+
     fn set_contents(&mut self, canvas: &Canvas) -> Result<(), Error> {
         // Swizzle pixels directly into the SHM buffer (no intermediate Vec).
+        // A canvas sized with a zoomed scale can be a pixel off the buffer
         let stride = self.physical_width * 4;
+        let w = canvas.width().min(self.physical_width as u32);
+        let h = canvas.height().min(self.physical_height as u32);
         let dst = self.shm_pool.data_mut();
-        canvas.blit_argb_rect(0, 0, canvas.width(), canvas.height(), dst, stride as u32);
+        canvas.blit_argb_rect(0, 0, w, h, dst, stride as u32);
 
         // Attach buffer and damage the whole surface.
         if let Some(surface) = &self.state.surface {
@@ -338,8 +412,8 @@ impl Window for WaylandWindow {
         if rects.is_empty() {
             return Ok(());
         }
-        let cw = canvas.width();
-        let ch = canvas.height();
+        let cw = canvas.width().min(self.physical_width as u32);
+        let ch = canvas.height().min(self.physical_height as u32);
         let stride = (self.physical_width * 4) as u32;
 
         // Fallback to a full upload if a single rect already covers everything
@@ -384,6 +458,9 @@ impl Window for WaylandWindow {
 
     fn wait_for_event(&mut self) -> Result<WindowEvent, Error> {
         loop {
+            if let Some(token) = activation::take_request() {
+                self.activate(token.as_deref())?;
+            }
             if let Some(event) = self.state.pending_events.pop_front() {
                 return Ok(event);
             }
@@ -415,28 +492,22 @@ impl Window for WaylandWindow {
                 -1 // block indefinitely
             };
 
-            let fd = self.conn.as_fd().as_raw_fd();
-            let mut pollfd = libc::pollfd {
-                fd,
-                events: libc::POLLIN,
-                revents: 0,
-            };
-
-            let ret = unsafe { libc::poll(&mut pollfd, 1, timeout_ms) };
-
-            if ret > 0 {
+            if activation::poll(self.conn.as_fd().as_raw_fd(), timeout_ms) {
                 // Data available — read and dispatch
                 if let Some(guard) = self.event_queue.prepare_read() {
-                    let _ = guard.read();
+                    read_events(guard)?;
                 }
                 self.event_queue.dispatch_pending(&mut self.state)?;
             }
-            // ret == 0: timeout — loop will check repeat_key
-            // ret < 0: interrupted — loop again
+            // Otherwise a timeout (the loop checks repeat_key), an activation
+            // request, or an interruption — loop again
         }
     }
 
     fn poll_for_event(&mut self) -> Result<Option<WindowEvent>, Error> {
+        if let Some(token) = activation::take_request() {
+            self.activate(token.as_deref())?;
+        }
         if let Some(event) = self.state.pending_events.pop_front() {
             return Ok(Some(event));
         }
@@ -449,9 +520,8 @@ impl Window for WaylandWindow {
 
         // Try to prepare for reading new events
         if let Some(guard) = self.event_queue.prepare_read() {
-            // Try to read events - this may fail if no data is available
             // The guard is consumed by read() call, so we don't need to cancel it
-            let _ = guard.read();
+            read_events(guard)?;
         }
 
         self.event_queue.dispatch_pending(&mut self.state)?;
@@ -479,6 +549,169 @@ impl Window for WaylandWindow {
         self.conn.flush()?;
         Ok(())
     }
+
+    fn set_position(&mut self, _x: Offset, _y: Offset) -> Result<(), Error> {
+        // xdg-shell leaves placement to the compositor
+        static NOTED: std::sync::Once = std::sync::Once::new();
+        NOTED.call_once(|| {
+            eprintln!("zenity-rs: note: window position is not supported on Wayland, ignoring");
+        });
+        Ok(())
+    }
+
+    fn write_clipboard(&mut self, text: &str) -> Result<(), Error> {
+        let (Some(manager), Some(device)) =
+            (&self.state.data_device_manager, &self.state.data_device)
+        else {
+            return Ok(());
+        };
+        let source = manager.create_data_source(&self.event_queue.handle(), ());
+        for mime_type in CLIPBOARD_MIME_TYPES {
+            source.offer(mime_type.to_string());
+        }
+        device.set_selection(Some(&source), self.state.last_serial);
+        if let Some((old, _)) = self
+            .state
+            .clipboard_source
+            .replace((source, text.to_string()))
+        {
+            old.destroy();
+        }
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    fn read_clipboard(&mut self) -> Result<Option<String>, Error> {
+        if let Some((_, text)) = &self.state.clipboard_source {
+            return Ok(Some(text.clone()));
+        }
+        let Some(offer) = &self.state.selection_offer else {
+            return Ok(None);
+        };
+        let mime_type = wayland_client::Proxy::data::<OfferedTypes>(offer).and_then(|offered| {
+            let offered = offered.lock().unwrap();
+            CLIPBOARD_MIME_TYPES
+                .into_iter()
+                .find(|mime_type| offered.iter().any(|o| o == mime_type))
+        });
+        let Some(mime_type) = mime_type else {
+            return Ok(None);
+        };
+        let (read, write) = pipe()?;
+        offer.receive(mime_type.to_string(), write.as_fd());
+        self.conn.flush()?;
+        // The owner's copy of the write end is the only one left, so the
+        // read ends when it's done
+        drop(write);
+        Ok(Some(clipboard_text(read_pipe(read)?)))
+    }
+
+    fn activate(&mut self, token: Option<&str>) -> Result<(), Error> {
+        self.request_focus(token);
+        self.state.pending_events.push_back(WindowEvent::Activated);
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    fn set_parent(&mut self, handle: &str) -> Result<(), Error> {
+        if attach::x11_window_id(handle).is_some() {
+            eprintln!("zenity-rs: warning: can't attach to X11 window {handle} on Wayland");
+            return Ok(());
+        }
+        let (Some(importer), Some(surface)) = (&self.state.importer, &self.state.surface) else {
+            eprintln!("zenity-rs: warning: the compositor doesn't support attaching to windows");
+            return Ok(());
+        };
+        let parent = importer.import_toplevel(handle.to_string(), &self.event_queue.handle(), ());
+        parent.set_parent_of(surface);
+        if let Some(old) = self.state.parent.replace(parent) {
+            old.destroy();
+        }
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    fn set_modal(&mut self, modal: bool) -> Result<(), Error> {
+        if let (Some(wm_dialog), Some(toplevel)) = (&self.state.wm_dialog, &self.state.xdg_toplevel)
+        {
+            let dialog = self.state.dialog.get_or_insert_with(|| {
+                wm_dialog.get_xdg_dialog(toplevel, &self.event_queue.handle(), ())
+            });
+            if modal {
+                dialog.set_modal();
+            } else {
+                dialog.unset_modal();
+            }
+        }
+        if modal {
+            // Use the token we were launched with, if any
+            let token = std::env::var("XDG_ACTIVATION_TOKEN").ok();
+            self.request_focus(token.as_deref().filter(|token| !token.is_empty()));
+        }
+        self.conn.flush()?;
+        Ok(())
+    }
+}
+
+/// Text types offered for the clipboard, most specific first.
+const CLIPBOARD_MIME_TYPES: [&str; 4] = [
+    "text/plain;charset=utf-8",
+    "text/plain",
+    "UTF8_STRING",
+    "STRING",
+];
+
+/// Mime types a data offer was announced with.
+type OfferedTypes = Mutex<Vec<String>>;
+
+fn pipe() -> Result<(OwnedFd, OwnedFd), Error> {
+    let mut fds = [0; 2];
+    if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) })
+}
+
+/// Reads what a clipboard owner writes into `fd` until it closes it, up to
+/// [`MAX_CLIPBOARD_BYTES`] or [`CLIPBOARD_TIMEOUT`], whichever comes first.
+fn read_pipe(fd: OwnedFd) -> Result<Vec<u8>, Error> {
+    let deadline = Instant::now() + CLIPBOARD_TIMEOUT;
+    let mut file = std::fs::File::from(fd);
+    let mut data = Vec::new();
+    let mut buf = [0; 8192];
+    while data.len() < MAX_CLIPBOARD_BYTES {
+        let left = deadline.saturating_duration_since(Instant::now());
+        let mut pfd = libc::pollfd {
+            fd: file.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        if left.is_zero() || unsafe { libc::poll(&mut pfd, 1, left.as_millis() as i32 + 1) } <= 0 {
+            break;
+        }
+        match file.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => data.extend_from_slice(&buf[..n]),
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    data.truncate(MAX_CLIPBOARD_BYTES);
+    Ok(data)
+}
+
+/// Reads what the compositor sent. Nothing to read yet is fine; anything
+/// else, such as the socket closing, is returned.
+fn read_events(guard: wayland_client::backend::ReadEventsGuard) -> Result<(), Error> {
+    match guard.read() {
+        Ok(_) => Ok(()),
+        Err(wayland_client::backend::WaylandError::Io(e))
+            if e.kind() == std::io::ErrorKind::WouldBlock =>
+        {
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
 }
 
 // Registry handler - binds globals
@@ -510,6 +743,18 @@ impl Dispatch<WlRegistry, ()> for WaylandState {
                 "wl_seat" => {
                     state.seat = Some(registry.bind(name, version.min(9), qh, ()));
                 }
+                "wl_data_device_manager" => {
+                    state.data_device_manager = Some(registry.bind(name, version.min(3), qh, ()));
+                }
+                "xdg_activation_v1" => {
+                    state.activation = Some(registry.bind(name, version.min(1), qh, ()));
+                }
+                "zxdg_importer_v2" => {
+                    state.importer = Some(registry.bind(name, version.min(1), qh, ()));
+                }
+                "xdg_wm_dialog_v1" => {
+                    state.wm_dialog = Some(registry.bind(name, version.min(1), qh, ()));
+                }
                 "wl_output" => {
                     // Bind wl_output version 2+ to get scale events
                     if version >= 2 {
@@ -686,6 +931,181 @@ impl Dispatch<WlSeat, ()> for WaylandState {
     }
 }
 
+impl Dispatch<XdgActivationV1, ()> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &XdgActivationV1,
+        _: <XdgActivationV1 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+// A token we asked for to activate ourselves
+impl Dispatch<XdgActivationTokenV1, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        token: &XdgActivationTokenV1,
+        event: xdg_activation_token_v1::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let xdg_activation_token_v1::Event::Done {
+            token: value,
+        } = event
+        {
+            if let (Some(activation), Some(surface)) = (&state.activation, &state.surface) {
+                activation.activate(value, surface);
+            }
+            token.destroy();
+        }
+    }
+}
+
+impl Dispatch<ZxdgImporterV2, ()> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &ZxdgImporterV2,
+        _: <ZxdgImporterV2 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+// The window we're attached to went away, or the handle was never valid
+impl Dispatch<ZxdgImportedV2, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        imported: &ZxdgImportedV2,
+        event: zxdg_imported_v2::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let zxdg_imported_v2::Event::Destroyed = event {
+            imported.destroy();
+            state.parent = None;
+        }
+    }
+}
+
+impl Dispatch<XdgWmDialogV1, ()> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &XdgWmDialogV1,
+        _: <XdgWmDialogV1 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<XdgDialogV1, ()> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &XdgDialogV1,
+        _: <XdgDialogV1 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WlDataDeviceManager, ()> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &WlDataDeviceManager,
+        _: <WlDataDeviceManager as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WlDataDevice, ()> for WaylandState {
+    event_created_child!(WaylandState, WlDataDevice, [
+        wl_data_device::EVT_DATA_OFFER_OPCODE => (WlDataOffer, OfferedTypes::default()),
+    ]);
+
+    fn event(
+        state: &mut Self,
+        _: &WlDataDevice,
+        event: wl_data_device::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let wl_data_device::Event::Selection {
+            id,
+        } = event
+            && let Some(old) = std::mem::replace(&mut state.selection_offer, id)
+        {
+            old.destroy();
+        }
+    }
+}
+
+impl Dispatch<WlDataOffer, OfferedTypes> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &WlDataOffer,
+        event: wl_data_offer::Event,
+        offered: &OfferedTypes,
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let wl_data_offer::Event::Offer {
+            mime_type,
+        } = event
+        {
+            offered.lock().unwrap().push(mime_type);
+        }
+    }
+}
+
+impl Dispatch<WlDataSource, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        source: &WlDataSource,
+        event: wl_data_source::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            wl_data_source::Event::Send {
+                fd, ..
+            } => {
+                if let Some((current, text)) = &state.clipboard_source
+                    && current == source
+                {
+                    // Errors mean the reader went away; nothing to do about it
+                    let _ = std::fs::File::from(fd).write_all(text.as_bytes());
+                }
+            }
+            wl_data_source::Event::Cancelled => {
+                if state
+                    .clipboard_source
+                    .as_ref()
+                    .is_some_and(|(current, _)| current == source)
+                {
+                    state.clipboard_source = None;
+                }
+                source.destroy();
+            }
+            _ => {}
+        }
+    }
+}
+
 impl Dispatch<WlPointer, ()> for WaylandState {
     fn event(
         state: &mut Self,
@@ -772,9 +1192,10 @@ impl Dispatch<WlPointer, ()> for WaylandState {
                     } else {
                         ScrollDirection::Up
                     };
+                    let modifiers = convert_wayland_modifiers(state.modifier_mask);
                     state
                         .pending_events
-                        .push_back(WindowEvent::Scroll(direction));
+                        .push_back(WindowEvent::Scroll(direction, modifiers));
                 }
             }
             _ => {}
@@ -845,7 +1266,9 @@ impl Dispatch<WlKeyboard, ()> for WaylandState {
                             let ch: Option<char> = lookup.into_iter().flat_map(|p| p.char()).next();
 
                             let event = if let Some(c) = ch {
-                                if !c.is_control() && !modifiers.contains(Modifiers::CTRL) {
+                                if !c.is_control()
+                                    && !modifiers.intersects(Modifiers::CTRL | Modifiers::ALT)
+                                {
                                     WindowEvent::TextInput(c)
                                 } else {
                                     WindowEvent::KeyPress(KeyEvent {
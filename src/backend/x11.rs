@@ -1,17 +1,18 @@
 //! X11 backend implementation.
 
-use std::{ops::Deref, rc::Rc};
+use std::{ops::Deref, os::fd::AsRawFd, sync::Arc, time::Instant};
 
 use kbvm::{lookup::LookupTable, xkb::x11::KbvmX11Ext};
 use x11rb::{
     connection::Connection as X11rbConnection,
-    properties::WmSizeHints,
+    properties::{WmSizeHints, WmSizeHintsSpecification},
     protocol::{
         Event, shm,
         xproto::{
             self, AtomEnum, ClientMessageEvent, ConfigureWindowAux, ConnectionExt as _,
-            CreateWindowAux, EventMask, ImageFormat, KeyButMask, PropMode, StackMode, VisualClass,
-            WindowClass,
+            CreateWindowAux, EventMask, ImageFormat, KeyButMask, PropMode, Property,
+            SELECTION_NOTIFY_EVENT, SelectionNotifyEvent, SelectionRequestEvent, StackMode,
+            VisualClass, WindowClass,
         },
     },
     rust_connection::RustConnection,
@@ -19,17 +20,24 @@ use x11rb::{
 };
 
 use super::{
-    CursorPos, CursorShape, DisplayConnection, KeyEvent, Modifiers, MouseButton, ScrollDirection,
-    Window, WindowEvent,
+    CLIPBOARD_TIMEOUT, CursorPos, CursorShape, DisplayConnection, KeyEvent, MAX_CLIPBOARD_BYTES,
+    Modifiers, MouseButton, PendingEvents, ScrollDirection, Window, WindowEvent, activation,
+    attach, clipboard_text,
 };
 use crate::{
     error::{Error, X11Error},
     render::Canvas,
+    ui::geometry::Offset,
 };
 
 x11rb::atom_manager! {
     pub Atoms: AtomCookie {
         UTF8_STRING,
+        CLIPBOARD,
+        TARGETS,
+        INCR,
+        // Where the clipboard owner puts the text we ask for
+        XSEL_DATA,
 
         WM_PROTOCOLS,
         WM_DELETE_WINDOW,
@@ -39,6 +47,9 @@ x11rb::atom_manager! {
         _NET_WM_WINDOW_TYPE_DIALOG,
 
         _NET_WM_MOVERESIZE,
+        _NET_ACTIVE_WINDOW,
+        _NET_WM_STATE,
+        _NET_WM_STATE_MODAL,
     }
 }
 
@@ -48,7 +59,7 @@ enum WindowType {
 
 #[derive(Clone)]
 pub(crate) struct Connection {
-    inner: Rc<RustConnection>,
+    inner: Arc<RustConnection>,
     screen: usize,
 }
 
@@ -66,7 +77,7 @@ impl DisplayConnection for Connection {
     fn connect() -> Result<Self, Error> {
         let (conn, screen) = x11rb::connect(None)?;
         Ok(Self {
-            inner: Rc::new(conn),
+            inner: Arc::new(conn),
             screen,
         })
     }
@@ -77,12 +88,16 @@ impl DisplayConnection for Connection {
 }
 
 const MOVERESIZE_MOVE: u32 = 8;
+/// `_NET_ACTIVE_WINDOW` source indication for a normal application.
+const ACTIVE_SOURCE_APPLICATION: u32 = 1;
 const KEYCODE_ESC: u8 = 9;
 const WM_CLASS: &[u8] = b"zenity\0Zenity\0";
 
 // X11 cursor font character constants
 const XC_LEFT_PTR: u16 = 68; // Default arrow
 const XC_XTERM: u16 = 152; // Text I-beam
+const XC_SB_H_DOUBLE_ARROW: u16 = 108; // Horizontal resize
+const XC_HAND2: u16 = 60; // Pointing hand
 
 pub(crate) struct X11Window {
     atoms: Atoms,
@@ -92,12 +107,20 @@ pub(crate) struct X11Window {
     lookup_table: LookupTable,
     xkb_group: u8,
     cursor_text: xproto::Cursor,
+    cursor_col_resize: xproto::Cursor,
+    cursor_pointer: xproto::Cursor,
     current_cursor: CursorShape,
     /// Reusable buffer for ARGB pixel uploads via `PutImage` (the non-SHM fallback).
     upload_buf: Vec<u8>,
     /// Optional MIT-SHM shared memory segment for zero-copy pixel uploads.
     /// `None` when MIT-SHM is unavailable; we fall back to `PutImage` over the socket.
     shm: Option<X11Shm>,
+    /// Text we serve while owning the CLIPBOARD selection.
+    clipboard: Option<String>,
+    /// Converted events not yet handed out, with pointer motion coalesced.
+    pending_events: PendingEvents,
+    /// Whether the window is modal, and so asks for focus when shown.
+    modal: bool,
 }
 
 /// MIT-SHM shared memory segment for zero-copy pixel uploads to the X server.
@@ -408,6 +431,36 @@ impl X11Window {
             0xffff,
         )?;
 
+        let cursor_col_resize = conn.generate_id()?;
+        conn.create_glyph_cursor(
+            cursor_col_resize,
+            cursor_font,
+            cursor_font,
+            XC_SB_H_DOUBLE_ARROW,
+            XC_SB_H_DOUBLE_ARROW + 1,
+            0,
+            0,
+            0,
+            0xffff,
+            0xffff,
+            0xffff,
+        )?;
+
+        let cursor_pointer = conn.generate_id()?;
+        conn.create_glyph_cursor(
+            cursor_pointer,
+            cursor_font,
+            cursor_font,
+            XC_HAND2,
+            XC_HAND2 + 1,
+            0,
+            0,
+            0,
+            0xffff,
+            0xffff,
+            0xffff,
+        )?;
+
         conn.close_font(cursor_font)?;
 
         // Try to set up a MIT-SHM segment for fast uploads; falls back to None.
@@ -421,9 +474,14 @@ impl X11Window {
             lookup_table,
             xkb_group: 0,
             cursor_text,
+            cursor_col_resize,
+            cursor_pointer,
             current_cursor: CursorShape::Default,
             upload_buf: Vec::new(),
             shm,
+            clipboard: None,
+            pending_events: PendingEvents::new(),
+            modal: false,
         };
         win.set_class(WM_CLASS)?;
         win.set_window_type(WindowType::Dialog)?;
@@ -460,8 +518,132 @@ impl X11Window {
         Ok(())
     }
 
+    /// Reads and deletes the property a clipboard owner put the text in.
+    fn take_clipboard_property(&self) -> Result<xproto::GetPropertyReply, Error> {
+        Ok(self
+            .conn
+            .get_property(
+                true,
+                self.window,
+                self.atoms.XSEL_DATA,
+                AtomEnum::ANY,
+                0,
+                (MAX_CLIPBOARD_BYTES / 4) as u32,
+            )?
+            .reply()?)
+    }
+
+    /// Answers another client's request for our clipboard contents.
+    fn answer_selection_request(&self, req: &SelectionRequestEvent) -> Result<(), Error> {
+        // Obsolete clients pass no property and expect the target to be used
+        let property = if req.property == x11rb::NONE {
+            req.target
+        } else {
+            req.property
+        };
+        let text = self
+            .clipboard
+            .as_deref()
+            .filter(|_| req.selection == self.atoms.CLIPBOARD);
+
+        let served = match text {
+            Some(_) if req.target == self.atoms.TARGETS => {
+                self.conn.change_property32(
+                    PropMode::REPLACE,
+                    req.requestor,
+                    property,
+                    AtomEnum::ATOM,
+                    &[
+                        self.atoms.TARGETS,
+                        self.atoms.UTF8_STRING,
+                        AtomEnum::STRING.into(),
+                    ],
+                )?;
+                true
+            }
+            Some(text)
+                if req.target == self.atoms.UTF8_STRING
+                    || req.target == u32::from(AtomEnum::STRING) =>
+            {
+                self.conn.change_property8(
+                    PropMode::REPLACE,
+                    req.requestor,
+                    property,
+                    req.target,
+                    text.as_bytes(),
+                )?;
+                true
+            }
+            _ => false,
+        };
+
+        let notify = SelectionNotifyEvent {
+            response_type: SELECTION_NOTIFY_EVENT,
+            sequence: 0,
+            time: req.time,
+            requestor: req.requestor,
+            selection: req.selection,
+            target: req.target,
+            property: if served { property } else { x11rb::NONE },
+        };
+        self.conn
+            .send_event(false, req.requestor, EventMask::NO_EVENT, notify)?;
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    /// Asks the window manager to raise and focus the window. `timestamp` is
+    /// the time of the user action behind the request, or 0 if unknown.
+    fn request_active(&self, timestamp: u32) -> Result<(), Error> {
+        let root = self.conn.setup().roots[self.conn.screen].root;
+        self.conn.send_event(
+            false,
+            root,
+            EventMask::SUBSTRUCTURE_NOTIFY | EventMask::SUBSTRUCTURE_REDIRECT,
+            ClientMessageEvent::new(
+                32,
+                self.window,
+                self.atoms._NET_ACTIVE_WINDOW,
+                [ACTIVE_SOURCE_APPLICATION, timestamp, 0, 0, 0],
+            ),
+        )?;
+        self.conn.configure_window(
+            self.window,
+            &ConfigureWindowAux::new().stack_mode(StackMode::ABOVE),
+        )?;
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    /// Converts every event the server has already sent into
+    /// `pending_events`, without blocking.
+    fn read_pending(&mut self) -> Result<(), Error> {
+        while let Some(ev) = self.conn.poll_for_event()? {
+            if let Some(ev) = self.cvt_event(ev) {
+                self.pending_events.push_back(ev);
+            }
+        }
+        Ok(())
+    }
+
     fn cvt_event(&mut self, ev: Event) -> Option<WindowEvent> {
         Some(match ev {
+            Event::SelectionRequest(req) => {
+                let _ = self.answer_selection_request(&req);
+                return None;
+            }
+            Event::SelectionClear(clear) if clear.selection == self.atoms.CLIPBOARD => {
+                self.clipboard = None;
+                return None;
+            }
+            Event::ClientMessage(msg)
+                if msg.type_ == self.atoms._NET_ACTIVE_WINDOW && msg.window == self.window =>
+            {
+                // Sent to us rather than the root window: pass it on to the
+                // window manager, which does the raising
+                let _ = self.request_active(msg.data.as_data32()[1]);
+                WindowEvent::Activated
+            }
             Event::ClientMessage(msg) if msg.data.as_data32()[0] == self.atoms.WM_DELETE_WINDOW => {
                 WindowEvent::CloseRequested
             }
@@ -487,7 +669,7 @@ impl X11Window {
                 // Get character from lookup and emit TextInput for printable characters
                 let ch: Option<char> = lookup.into_iter().flat_map(|p| p.char()).next();
                 if let Some(c) = ch {
-                    if !c.is_control() && !modifiers.contains(Modifiers::CTRL) {
+                    if !c.is_control() && !modifiers.intersects(Modifiers::CTRL | Modifiers::ALT) {
                         return Some(WindowEvent::TextInput(c));
                     }
                 }
@@ -531,13 +713,11 @@ impl X11Window {
                 })
             }
             Event::ButtonPress(e) => {
+                let mods = convert_modifiers(e.state);
                 match e.detail {
-                    4 => return Some(WindowEvent::Scroll(ScrollDirection::Up)),
-                    5 => return Some(WindowEvent::Scroll(ScrollDirection::Down)),
-                    _ => {
-                        let mods = convert_modifiers(e.state);
-                        mouse_button(e.detail).map(|mb| WindowEvent::ButtonPress(mb, mods))?
-                    }
+                    4 => return Some(WindowEvent::Scroll(ScrollDirection::Up, mods)),
+                    5 => return Some(WindowEvent::Scroll(ScrollDirection::Down, mods)),
+                    _ => mouse_button(e.detail).map(|mb| WindowEvent::ButtonPress(mb, mods))?,
                 }
             }
             Event::ButtonRelease(e) => {
@@ -621,6 +801,11 @@ impl Window for X11Window {
         Ok(())
     }
 
+    fn set_app_id(&mut self, app_id: &str) -> Result<(), Error> {
+        // Instance and class name, each NUL-terminated
+        self.set_class(format!("{app_id}\0{app_id}\0").as_bytes())
+    }
+
     fn set_contents(&mut self, canvas: &Canvas) -> Result<(), Error> {
         // Fast path: shared memory upload (no socket bulk transfer).
         if let Some(shm) = self.shm.as_mut()
@@ -715,30 +900,31 @@ impl Window for X11Window {
             self.window,
             &ConfigureWindowAux::new().stack_mode(StackMode::ABOVE),
         )?;
+        if self.modal {
+            self.request_active(0)?;
+        }
         self.conn.flush()?;
         Ok(())
     }
 
     fn wait_for_event(&mut self) -> Result<WindowEvent, Error> {
         loop {
-            let ev = self.conn.wait_for_event()?;
-            if let Some(ev) = self.cvt_event(ev) {
+            if let Some(ev) = self.poll_for_event()? {
                 return Ok(ev);
             }
+            self.conn.flush()?;
+            // Sleep until the server sends something or an activation
+            // request comes in
+            activation::poll(self.conn.stream().as_raw_fd(), -1);
         }
     }
 
     fn poll_for_event(&mut self) -> Result<Option<WindowEvent>, Error> {
-        loop {
-            match self.conn.poll_for_event()? {
-                Some(ev) => {
-                    if let Some(ev) = self.cvt_event(ev) {
-                        return Ok(Some(ev));
-                    }
-                }
-                None => return Ok(None),
-            }
+        if let Some(token) = activation::take_request() {
+            self.activate(token.as_deref())?;
         }
+        self.read_pending()?;
+        Ok(self.pending_events.pop_front())
     }
 
     fn start_drag(&mut self) -> Result<(), Error> {
@@ -783,6 +969,8 @@ impl Window for X11Window {
         // (cursor = 0) so the compositor/WM can restore the themed default.
         let cursor_id: u32 = match shape {
             CursorShape::Text => self.cursor_text,
+            CursorShape::ColResize => self.cursor_col_resize,
+            CursorShape::Pointer => self.cursor_pointer,
             CursorShape::Default => 0, // clear the cursor attribute
         };
 
@@ -795,6 +983,153 @@ impl Window for X11Window {
         self.current_cursor = shape;
         Ok(())
     }
+
+    fn set_position(&mut self, x: Offset, y: Offset) -> Result<(), Error> {
+        let (screen_w, screen_h) = {
+            let screen = &self.conn.setup().roots[self.conn.screen];
+            (screen.width_in_pixels, screen.height_in_pixels)
+        };
+        let geom = self.conn.get_geometry(self.window)?.reply()?;
+        let px = x.resolve(screen_w.into(), geom.width.into());
+        let py = y.resolve(screen_h.into(), geom.height.into());
+
+        // Marking the position user-specified asks the WM not to place the
+        // window itself
+        WmSizeHints {
+            position: Some((WmSizeHintsSpecification::UserSpecified, px, py)),
+            max_size: Some((geom.width.into(), geom.height.into())),
+            min_size: Some((geom.width.into(), geom.height.into())),
+            ..Default::default()
+        }
+        .set_normal_hints(&self.conn.inner, self.window)?
+        .check()?;
+        self.conn
+            .configure_window(self.window, &ConfigureWindowAux::new().x(px).y(py))?;
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    fn write_clipboard(&mut self, text: &str) -> Result<(), Error> {
+        self.conn
+            .set_selection_owner(self.window, self.atoms.CLIPBOARD, x11rb::CURRENT_TIME)?;
+        self.clipboard = Some(text.to_string());
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    fn read_clipboard(&mut self) -> Result<Option<String>, Error> {
+        if let Some(text) = &self.clipboard {
+            return Ok(Some(text.clone()));
+        }
+        self.conn.convert_selection(
+            self.window,
+            self.atoms.CLIPBOARD,
+            self.atoms.UTF8_STRING,
+            self.atoms.XSEL_DATA,
+            x11rb::CURRENT_TIME,
+        )?;
+        self.conn.flush()?;
+
+        // Other events arriving meanwhile are kept for the event loop. Large
+        // texts come in chunks (INCR), collected here until the limit.
+        let deadline = Instant::now() + CLIPBOARD_TIMEOUT;
+        let mut chunks: Option<Vec<u8>> = None;
+        loop {
+            while let Some(ev) = self.conn.poll_for_event()? {
+                match ev {
+                    Event::SelectionNotify(notify) if notify.requestor == self.window => {
+                        if notify.property == x11rb::NONE {
+                            return Ok(None);
+                        }
+                        // Deleting the property also asks for the first chunk
+                        let reply = self.take_clipboard_property()?;
+                        if reply.type_ == self.atoms.INCR {
+                            chunks = Some(Vec::new());
+                            continue;
+                        }
+                        return Ok(Some(clipboard_text(reply.value)));
+                    }
+                    Event::PropertyNotify(notify)
+                        if chunks.is_some()
+                            && notify.window == self.window
+                            && notify.atom == self.atoms.XSEL_DATA
+                            && notify.state == Property::NEW_VALUE =>
+                    {
+                        let reply = self.take_clipboard_property()?;
+                        let data = chunks.as_mut().unwrap();
+                        let done = reply.value.is_empty();
+                        data.extend(reply.value);
+                        if done || data.len() >= MAX_CLIPBOARD_BYTES {
+                            data.truncate(MAX_CLIPBOARD_BYTES);
+                            return Ok(chunks.map(clipboard_text));
+                        }
+                    }
+                    ev => {
+                        if let Some(ev) = self.cvt_event(ev) {
+                            self.pending_events.push_back(ev);
+                        }
+                    }
+                }
+            }
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                // Whatever arrived of a chunked transfer
+                return Ok(chunks.map(clipboard_text));
+            }
+            activation::poll(self.conn.stream().as_raw_fd(), left.as_millis() as i32 + 1);
+        }
+    }
+
+    fn activate(&mut self, token: Option<&str>) -> Result<(), Error> {
+        self.request_active(token.map_or(0, startup_timestamp))?;
+        self.pending_events.push_back(WindowEvent::Activated);
+        Ok(())
+    }
+
+    fn set_parent(&mut self, handle: &str) -> Result<(), Error> {
+        let Some(parent) = attach::x11_window_id(handle) else {
+            eprintln!("zenity-rs: warning: can't attach to {handle:?}: expected an X11 window ID");
+            return Ok(());
+        };
+        self.conn
+            .change_property32(
+                PropMode::REPLACE,
+                self.window,
+                AtomEnum::WM_TRANSIENT_FOR,
+                AtomEnum::WINDOW,
+                &[parent],
+            )?
+            .check()?;
+        Ok(())
+    }
+
+    fn set_modal(&mut self, modal: bool) -> Result<(), Error> {
+        // Only set before mapping; the window manager reads it on map
+        let state: &[u32] = if modal {
+            &[self.atoms._NET_WM_STATE_MODAL]
+        } else {
+            &[]
+        };
+        self.conn
+            .change_property32(
+                PropMode::REPLACE,
+                self.window,
+                self.atoms._NET_WM_STATE,
+                AtomEnum::ATOM,
+                state,
+            )?
+            .check()?;
+        self.modal = modal;
+        Ok(())
+    }
+}
+
+/// The user action time in a startup notification ID (`..._TIME<n>`), or 0.
+fn startup_timestamp(startup_id: &str) -> u32 {
+    startup_id
+        .rsplit_once("_TIME")
+        .and_then(|(_, time)| time.parse().ok())
+        .unwrap_or(0)
 }
 
 fn mouse_button(detail: u8) -> Option<MouseButton> {
@@ -0,0 +1,613 @@
+//! A minimal D-Bus client: just enough of the wire protocol to call methods
+//! on the session bus.
+
+use std::{
+    ffi::OsStr,
+    io::{Read, Write},
+    os::{
+        linux::net::SocketAddrExt,
+        unix::{
+            ffi::OsStrExt,
+            net::{SocketAddr, UnixStream},
+        },
+    },
+    time::Duration,
+};
+
+use crate::error::Error;
+
+// Message types
+const METHOD_CALL: u8 = 1;
+const METHOD_RETURN: u8 = 2;
+const ERROR: u8 = 3;
+const SIGNAL: u8 = 4;
+
+// Header field codes
+const FIELD_PATH: u8 = 1;
+const FIELD_INTERFACE: u8 = 2;
+const FIELD_MEMBER: u8 = 3;
+const FIELD_ERROR_NAME: u8 = 4;
+const FIELD_REPLY_SERIAL: u8 = 5;
+const FIELD_DESTINATION: u8 = 6;
+const FIELD_SIGNATURE: u8 = 8;
+
+/// A connection to the session bus.
+pub(crate) struct Connection {
+    stream: UnixStream,
+    serial: u32,
+}
+
+impl Connection {
+    /// Connects to the bus named by `$DBUS_SESSION_BUS_ADDRESS`, falling back
+    /// to `$XDG_RUNTIME_DIR/bus`.
+    pub fn session() -> Result<Self, Error> {
+        let address = match std::env::var("DBUS_SESSION_BUS_ADDRESS") {
+            Ok(address) => address,
+            Err(_) => {
+                let runtime_dir = std::env::var("XDG_RUNTIME_DIR")
+                    .map_err(|_| Error::DBus("no session bus address".to_string()))?;
+                format!("unix:path={runtime_dir}/bus")
+            }
+        };
+        Self::open(&address)
+    }
+
+    /// Connects to the bus at `address`, a `;`-separated list of addresses
+    /// tried in turn.
+    pub fn open(address: &str) -> Result<Self, Error> {
+        let stream = address
+            .split(';')
+            .find_map(|address| connect(address).ok())
+            .ok_or_else(|| Error::DBus(format!("can't connect to session bus at {address:?}")))?;
+
+        let mut connection = Self {
+            stream,
+            serial: 0,
+        };
+        connection.authenticate()?;
+        connection.call(
+            "org.freedesktop.DBus",
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus",
+            "Hello",
+            &Body::new(),
+        )?;
+        Ok(connection)
+    }
+
+    /// Makes calls fail once a reply takes longer than `timeout`, rather
+    /// than waiting on a service forever. `None` waits as long as it takes.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) -> Result<(), Error> {
+        self.stream.set_read_timeout(timeout)?;
+        Ok(())
+    }
+
+    /// SASL EXTERNAL authentication with our uid.
+    fn authenticate(&mut self) -> Result<(), Error> {
+        let uid = unsafe { libc::getuid() }.to_string();
+        let hex: String = uid.bytes().map(|b| format!("{b:02x}")).collect();
+        self.stream
+            .write_all(format!("\0AUTH EXTERNAL {hex}\r\n").as_bytes())?;
+
+        let mut line = Vec::new();
+        let mut byte = [0u8];
+        while !line.ends_with(b"\r\n") {
+            self.stream.read_exact(&mut byte)?;
+            line.push(byte[0]);
+        }
+        if !line.starts_with(b"OK ") {
+            return Err(Error::DBus(
+                "session bus rejected authentication".to_string(),
+            ));
+        }
+        self.stream.write_all(b"BEGIN\r\n")?;
+        Ok(())
+    }
+
+    /// Calls a method and waits for its reply. Error replies become
+    /// [`Error::DBus`]; signals arriving in the meantime are dropped.
+    pub fn call(
+        &mut self,
+        destination: &str,
+        path: &str,
+        interface: &str,
+        member: &str,
+        body: &Body,
+    ) -> Result<Message, Error> {
+        self.serial += 1;
+        let serial = self.serial;
+
+        let mut header = Body::new();
+        header.byte(b'l');
+        header.byte(METHOD_CALL);
+        header.byte(0);
+        header.byte(1);
+        header.raw_u32(body.buf.len() as u32);
+        header.raw_u32(serial);
+        let start = header.begin_array(8);
+        let mut field = |code: u8, signature: &str, value: &str| {
+            header.align(8);
+            header.byte(code);
+            header.raw_signature(signature);
+            match signature {
+                "g" => header.raw_signature(value),
+                _ => header.raw_string(value),
+            }
+        };
+        field(FIELD_PATH, "o", path);
+        field(FIELD_INTERFACE, "s", interface);
+        field(FIELD_MEMBER, "s", member);
+        field(FIELD_DESTINATION, "s", destination);
+        if !body.signature.is_empty() {
+            field(FIELD_SIGNATURE, "g", &body.signature);
+        }
+        header.end_array(start);
+        header.align(8);
+        header.buf.extend_from_slice(&body.buf);
+        self.stream.write_all(&header.buf)?;
+
+        loop {
+            let message = self.read_message()?;
+            if message.reply_serial != Some(serial) {
+                continue;
+            }
+            return match message.kind {
+                METHOD_RETURN => Ok(message),
+                ERROR => {
+                    let name = message.error_name.clone().unwrap_or_default();
+                    let detail = if message.signature.starts_with('s') {
+                        message.reader().string().unwrap_or_default()
+                    } else {
+                        String::new()
+                    };
+                    Err(Error::DBus(format!("{member}: {name}: {detail}")))
+                }
+                _ => continue,
+            };
+        }
+    }
+
+    /// Waits for the signal `member` sent from `path`. A match rule for it
+    /// must have been added with `AddMatch`; other messages are dropped.
+    pub fn wait_signal(&mut self, path: &str, member: &str) -> Result<Message, Error> {
+        loop {
+            let message = self.read_message()?;
+            if message.kind == SIGNAL
+                && message.path.as_deref() == Some(path)
+                && message.member.as_deref() == Some(member)
+            {
+                return Ok(message);
+            }
+        }
+    }
+
+    fn read_message(&mut self) -> Result<Message, Error> {
+        let mut head = vec![0u8; 16];
+        self.stream.read_exact(&mut head)?;
+        let big_endian = head[0] == b'B';
+        let read_u32 = |b: &[u8]| {
+            let bytes = [b[0], b[1], b[2], b[3]];
+            if big_endian {
+                u32::from_be_bytes(bytes)
+            } else {
+                u32::from_le_bytes(bytes)
+            }
+        };
+        let body_len = read_u32(&head[4..]) as usize;
+        let fields_len = read_u32(&head[12..]) as usize;
+        let header_len = (16 + fields_len).next_multiple_of(8);
+        head.resize(header_len + body_len, 0);
+        self.stream.read_exact(&mut head[16..])?;
+
+        let mut message = Message {
+            kind: head[1],
+            reply_serial: None,
+            path: None,
+            member: None,
+            error_name: None,
+            signature: String::new(),
+            body: head.split_off(header_len),
+            big_endian,
+        };
+
+        // Header fields are an array of (code, variant) structs
+        let mut fields = Reader {
+            data: &head[..16 + fields_len],
+            pos: 16,
+            big_endian,
+        };
+        while fields.pos < 16 + fields_len {
+            fields.align(8);
+            let code = fields.byte()?;
+            let signature = fields.signature()?;
+            match signature.as_str() {
+                "u" => {
+                    let value = fields.u32()?;
+                    if code == FIELD_REPLY_SERIAL {
+                        message.reply_serial = Some(value);
+                    }
+                }
+                "s" | "o" => {
+                    let value = fields.string()?;
+                    match code {
+                        FIELD_PATH => message.path = Some(value),
+                        FIELD_MEMBER => message.member = Some(value),
+                        FIELD_ERROR_NAME => message.error_name = Some(value),
+                        _ => {}
+                    }
+                }
+                "g" => {
+                    let value = fields.signature()?;
+                    if code == FIELD_SIGNATURE {
+                        message.signature = value;
+                    }
+                }
+                _ => return Err(Error::DBus("unexpected header field".to_string())),
+            }
+        }
+        Ok(message)
+    }
+}
+
+/// Connects to one `transport:key=value,...` bus address.
+fn connect(address: &str) -> std::io::Result<UnixStream> {
+    let unsupported = || std::io::Error::other(format!("unsupported bus address {address:?}"));
+    let params = address.strip_prefix("unix:").ok_or_else(unsupported)?;
+    for param in params.split(',') {
+        match param.split_once('=') {
+            Some(("path", path)) => {
+                return UnixStream::connect(OsStr::from_bytes(&percent_decode(path)));
+            }
+            Some(("abstract", name)) => {
+                let addr = SocketAddr::from_abstract_name(percent_decode(name))?;
+                return UnixStream::connect_addr(&addr);
+            }
+            _ => {}
+        }
+    }
+    Err(unsupported())
+}
+
+/// Decodes `%XX` escapes, as in bus addresses and URIs.
+pub(crate) fn percent_decode(value: &str) -> Vec<u8> {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = (bytes[i] == b'%')
+            .then(|| value.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Marshalled method arguments along with their signature.
+#[derive(Default)]
+pub(crate) struct Body {
+    buf: Vec<u8>,
+    signature: String,
+}
+
+impl Body {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn u32(mut self, value: u32) -> Self {
+        self.signature.push('u');
+        self.raw_u32(value);
+        self
+    }
+
+    pub fn i32(mut self, value: i32) -> Self {
+        self.signature.push('i');
+        self.raw_u32(value as u32);
+        self
+    }
+
+    pub fn string(mut self, value: &str) -> Self {
+        self.signature.push('s');
+        self.raw_string(value);
+        self
+    }
+
+    pub fn string_array(mut self, values: &[&str]) -> Self {
+        self.signature.push_str("as");
+        let start = self.begin_array(4);
+        for value in values {
+            self.raw_string(value);
+        }
+        self.end_array(start);
+        self
+    }
+
+    /// An `a{sv}` dictionary with string values.
+    pub fn string_dict(self, entries: &[(&str, &str)]) -> Self {
+        let entries: Vec<_> = entries
+            .iter()
+            .map(|&(key, value)| (key, Variant::String(value)))
+            .collect();
+        self.dict(&entries)
+    }
+
+    /// An `a{sv}` dictionary.
+    pub fn dict(mut self, entries: &[(&str, Variant)]) -> Self {
+        self.signature.push_str("a{sv}");
+        let start = self.begin_array(8);
+        for (key, value) in entries {
+            self.align(8);
+            self.raw_string(key);
+            match value {
+                Variant::Bool(value) => {
+                    self.raw_signature("b");
+                    self.raw_u32(*value as u32);
+                }
+                Variant::String(value) => {
+                    self.raw_signature("s");
+                    self.raw_string(value);
+                }
+                Variant::Bytes(value) => {
+                    self.raw_signature("ay");
+                    self.raw_u32(value.len() as u32);
+                    self.buf.extend_from_slice(value);
+                }
+                Variant::Filters(filters) => {
+                    self.raw_signature("a(sa(us))");
+                    let start = self.begin_array(8);
+                    for (name, patterns) in *filters {
+                        self.align(8);
+                        self.raw_string(name);
+                        let start = self.begin_array(8);
+                        for (kind, pattern) in patterns {
+                            self.align(8);
+                            self.raw_u32(*kind);
+                            self.raw_string(pattern);
+                        }
+                        self.end_array(start);
+                    }
+                    self.end_array(start);
+                }
+            }
+        }
+        self.end_array(start);
+        self
+    }
+
+    fn align(&mut self, n: usize) {
+        let len = self.buf.len().next_multiple_of(n);
+        self.buf.resize(len, 0);
+    }
+
+    fn byte(&mut self, value: u8) {
+        self.buf.push(value);
+    }
+
+    fn raw_u32(&mut self, value: u32) {
+        self.align(4);
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn raw_string(&mut self, value: &str) {
+        self.raw_u32(value.len() as u32);
+        self.buf.extend_from_slice(value.as_bytes());
+        self.buf.push(0);
+    }
+
+    fn raw_signature(&mut self, value: &str) {
+        self.buf.push(value.len() as u8);
+        self.buf.extend_from_slice(value.as_bytes());
+        self.buf.push(0);
+    }
+
+    /// Writes a length placeholder and the padding before the first element
+    /// (present even for empty arrays). Returns the positions of both.
+    fn begin_array(&mut self, element_alignment: usize) -> (usize, usize) {
+        self.raw_u32(0);
+        let len_pos = self.buf.len() - 4;
+        self.align(element_alignment);
+        (len_pos, self.buf.len())
+    }
+
+    fn end_array(&mut self, (len_pos, start): (usize, usize)) {
+        let len = (self.buf.len() - start) as u32;
+        self.buf[len_pos..len_pos + 4].copy_from_slice(&len.to_le_bytes());
+    }
+}
+
+/// A value in an `a{sv}` dictionary.
+pub(crate) enum Variant<'a> {
+    Bool(bool),
+    String(&'a str),
+    /// `ay`, such as a NUL-terminated path.
+    Bytes(&'a [u8]),
+    /// `a(sa(us))`: named lists of `(kind, pattern)`, as file filters are.
+    Filters(&'a [(&'a str, Vec<(u32, &'a str)>)]),
+}
+
+/// A method return, error or signal received from the bus.
+pub(crate) struct Message {
+    kind: u8,
+    reply_serial: Option<u32>,
+    path: Option<String>,
+    member: Option<String>,
+    error_name: Option<String>,
+    signature: String,
+    body: Vec<u8>,
+    big_endian: bool,
+}
+
+impl Message {
+    /// Reads the body's arguments in order.
+    pub fn reader(&self) -> Reader<'_> {
+        Reader {
+            data: &self.body,
+            pos: 0,
+            big_endian: self.big_endian,
+        }
+    }
+}
+
+/// Unmarshals basic values, in order, from a message.
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    big_endian: bool,
+}
+
+impl Reader<'_> {
+    fn truncated() -> Error {
+        Error::DBus("truncated message".to_string())
+    }
+
+    fn align(&mut self, n: usize) {
+        self.pos = self.pos.next_multiple_of(n);
+    }
+
+    fn byte(&mut self) -> Result<u8, Error> {
+        let byte = *self.data.get(self.pos).ok_or_else(Self::truncated)?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    pub fn u32(&mut self) -> Result<u32, Error> {
+        self.align(4);
+        let bytes: [u8; 4] = self
+            .data
+            .get(self.pos..self.pos + 4)
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(Self::truncated)?;
+        self.pos += 4;
+        Ok(if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    pub fn string(&mut self) -> Result<String, Error> {
+        let len = self.u32()? as usize;
+        self.text(len)
+    }
+
+    /// A signature, such as the one starting each variant.
+    pub fn signature(&mut self) -> Result<String, Error> {
+        let len = self.byte()? as usize;
+        self.text(len)
+    }
+
+    /// An `a{sv}` dictionary, keeping the values that are string arrays
+    /// (`as`) and skipping the rest.
+    pub fn string_array_dict(&mut self) -> Result<Vec<(String, Vec<String>)>, Error> {
+        let len = self.u32()? as usize;
+        self.align(8);
+        let end = self.pos + len;
+        let mut entries = Vec::new();
+        while self.pos < end {
+            self.align(8);
+            let key = self.string()?;
+            let signature = self.signature()?;
+            if signature == "as" {
+                let len = self.u32()? as usize;
+                let end = self.pos + len;
+                let mut values = Vec::new();
+                while self.pos < end {
+                    values.push(self.string()?);
+                }
+                entries.push((key, values));
+            } else {
+                self.skip(&signature)?;
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Skips a value of the single complete type `signature`.
+    fn skip(&mut self, signature: &str) -> Result<(), Error> {
+        let bad = || Error::DBus(format!("unexpected signature {signature:?}"));
+        match signature.as_bytes().first().ok_or_else(bad)? {
+            b'y' => self.pos += 1,
+            b'n' | b'q' => self.pos = self.pos.next_multiple_of(2) + 2,
+            b'b' | b'i' | b'u' | b'h' => {
+                self.u32()?;
+            }
+            b'x' | b't' | b'd' => self.pos = self.pos.next_multiple_of(8) + 8,
+            b's' | b'o' => {
+                self.string()?;
+            }
+            b'g' => {
+                self.signature()?;
+            }
+            b'v' => {
+                let inner = self.signature()?;
+                self.skip(&inner)?;
+            }
+            b'a' => {
+                let len = self.u32()? as usize;
+                let element = signature.as_bytes().get(1).ok_or_else(bad)?;
+                self.align(alignment(*element));
+                self.pos += len;
+            }
+            b'(' | b'{' => {
+                self.align(8);
+                let mut fields = signature.get(1..signature.len() - 1).ok_or_else(bad)?;
+                while !fields.is_empty() {
+                    let len = type_len(fields.as_bytes()).ok_or_else(bad)?;
+                    self.skip(&fields[..len])?;
+                    fields = &fields[len..];
+                }
+            }
+            _ => return Err(bad()),
+        }
+        if self.pos > self.data.len() {
+            return Err(Self::truncated());
+        }
+        Ok(())
+    }
+
+    /// `len` bytes of text followed by a NUL.
+    fn text(&mut self, len: usize) -> Result<String, Error> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or_else(Self::truncated)?;
+        self.pos += len + 1;
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    }
+}
+
+/// The alignment of values whose type code is `code`.
+fn alignment(code: u8) -> usize {
+    match code {
+        b'n' | b'q' => 2,
+        b'b' | b'i' | b'u' | b'h' | b's' | b'o' | b'a' => 4,
+        b'x' | b't' | b'd' | b'(' | b'{' => 8,
+        _ => 1,
+    }
+}
+
+/// The length of the single complete type at the start of `signature`.
+fn type_len(signature: &[u8]) -> Option<usize> {
+    match signature.first()? {
+        b'a' => Some(1 + type_len(&signature[1..])?),
+        &open @ (b'(' | b'{') => {
+            let close = if open == b'(' { b')' } else { b'}' };
+            let mut len = 1;
+            while *signature.get(len)? != close {
+                len += type_len(&signature[len..])?;
+            }
+            Some(len + 1)
+        }
+        _ => Some(1),
+    }
+}
@@ -7,7 +7,12 @@ pub enum Error {
     #[cfg(feature = "wayland")]
     Wayland(WaylandError),
     NoDisplay,
+    /// The connection to the display server broke while a dialog was open.
+    DisplayLost,
     Io(std::io::Error),
+    /// A dialog was configured in a way it can't be shown.
+    InvalidConfig(String),
+    DBus(String),
 }
 
 #[cfg(feature = "x11")]
@@ -36,7 +41,10 @@ impl fmt::Display for Error {
             #[cfg(feature = "wayland")]
             Error::Wayland(e) => write!(f, "Wayland error: {e}"),
             Error::NoDisplay => write!(f, "no display server available"),
+            Error::DisplayLost => write!(f, "lost the connection to the display server"),
             Error::Io(e) => write!(f, "IO error: {e}"),
+            Error::InvalidConfig(e) => write!(f, "{e}"),
+            Error::DBus(e) => write!(f, "D-Bus error: {e}"),
         }
     }
 }
@@ -124,7 +132,14 @@ impl From<wayland_client::DispatchError> for Error {
 #[cfg(feature = "wayland")]
 impl From<wayland_client::backend::WaylandError> for Error {
     fn from(e: wayland_client::backend::WaylandError) -> Self {
-        // Convert to IO error since WaylandError is usually an IO issue
-        Error::Io(std::io::Error::other(e.to_string()))
+        match e {
+            wayland_client::backend::WaylandError::Io(e) => Error::Io(e),
+            // A protocol error ends the connection, like a failed dispatch
+            e => {
+                Error::Wayland(WaylandError::Dispatch(
+                    wayland_client::DispatchError::Backend(e),
+                ))
+            }
+        }
     }
 }
@@ -1,24 +1,69 @@
 //! zenity-rs - Display simple GUI dialogs from the command line.
 //!
 //! This library provides simple GUI dialogs for shell scripts and command-line tools.
+//!
+//! Each dialog type sits behind a Cargo feature (`message`, `entry`,
+//! `calendar`, `files`, `forms`, `list`, `progress`, `textinfo`, `scale`,
+//! plus `notify` for desktop notifications), all enabled by the default `cli`
+//! feature. Embedders that only need a few can turn off default features and
+//! pick them along with a backend (`x11`, `wayland`).
+
+// Some shared widget and backend code goes unused unless every dialog is built
+#![cfg_attr(not(feature = "cli"), allow(dead_code))]
 
 pub(crate) mod backend;
+pub(crate) mod dbus;
 pub mod error;
+#[cfg(feature = "portal")]
+pub(crate) mod portal;
 pub(crate) mod render;
 pub mod ui;
-
+pub(crate) mod xdg;
+
+#[cfg(feature = "mock-backend")]
+pub use backend::mock;
+pub use backend::{
+    CursorPos, KeyEvent, Modifiers, MouseButton, PendingEvents, ScrollDirection, WindowEvent,
+    activation::request_activation,
+    app_id::set_app_id,
+    attach::{attach_to, x11_window_id},
+};
 pub use error::Error;
+#[cfg(feature = "portal")]
+pub use portal::file_uri_path;
+pub use render::{
+    Align, Canvas, EllipsizeMode, Font, Rgba, RunStyle, TextLayout, TextRenderer, TextRun, rgb,
+};
+#[cfg(feature = "calendar")]
+pub use ui::calendar::{CalendarBuilder, CalendarResult};
+#[cfg(feature = "entry")]
+pub use ui::entry::{EntryBuilder, EntryResult, EntryValues};
+#[cfg(feature = "files")]
+pub use ui::file_select::{
+    FileFilter, FileSelectBuilder, FileSelectMode, FileSelectResult, FileSort, PathFormat,
+};
+#[cfg(feature = "forms")]
+pub use ui::forms::{FormField, FormsBuilder, FormsResult};
+#[cfg(feature = "list")]
+pub use ui::list::{ListBuilder, ListMode, ListResult, PrintColumn};
+#[cfg(feature = "message")]
+pub use ui::message::MessageBuilder;
+#[cfg(feature = "notify")]
+pub use ui::notification::NotificationBuilder;
+#[cfg(feature = "progress")]
+pub use ui::progress::{ProgressBuilder, ProgressResult};
+#[cfg(feature = "scale")]
+pub use ui::scale::{Orientation, ScaleBuilder, ScaleResult};
+#[cfg(feature = "textinfo")]
+pub use ui::text_info::{TextInfoBuilder, TextInfoResult};
 pub use ui::{
-    ButtonPreset, Colors, DialogResult, Icon, THEME_DARK, THEME_LIGHT,
-    calendar::{CalendarBuilder, CalendarResult},
-    entry::{EntryBuilder, EntryResult},
-    file_select::{FileFilter, FileSelectBuilder, FileSelectResult},
-    forms::{FormsBuilder, FormsResult},
-    list::{ListBuilder, ListMode, ListResult},
-    message::MessageBuilder,
-    progress::{ProgressBuilder, ProgressResult},
-    scale::{ScaleBuilder, ScaleResult},
-    text_info::{TextInfoBuilder, TextInfoResult},
+    ButtonOrder, ButtonPreset, Colors, DialogOutcome, DialogResult, Icon, THEME_DARK, THEME_LIGHT,
+    Theme,
+    date::WeekStart,
+    geometry::{Geometry, Offset, ParseGeometryError},
+    layout::LabelPlacement,
+    widgets::{Widget, selectable_text::SelectableText},
+    zoom::remember as remember_zoom,
 };
 
 /// Creates a new message dialog builder.
@@ -36,11 +81,13 @@ pub use ui::{
 ///     .show()
 ///     .unwrap();
 /// ```
+#[cfg(feature = "message")]
 pub fn message() -> MessageBuilder {
     MessageBuilder::new()
 }
 
 /// Creates an info dialog (shortcut for message with info icon).
+#[cfg(feature = "message")]
 pub fn info(text: &str) -> MessageBuilder {
     MessageBuilder::new()
         .text(text)
@@ -49,6 +96,7 @@ pub fn info(text: &str) -> MessageBuilder {
 }
 
 /// Creates a warning dialog (shortcut for message with warning icon).
+#[cfg(feature = "message")]
 pub fn warning(text: &str) -> MessageBuilder {
     MessageBuilder::new()
         .text(text)
@@ -57,6 +105,7 @@ pub fn warning(text: &str) -> MessageBuilder {
 }
 
 /// Creates an error dialog (shortcut for message with error icon).
+#[cfg(feature = "message")]
 pub fn error(text: &str) -> MessageBuilder {
     MessageBuilder::new()
         .text(text)
@@ -65,6 +114,7 @@ pub fn error(text: &str) -> MessageBuilder {
 }
 
 /// Creates a question dialog (shortcut for message with question icon and Yes/No buttons).
+#[cfg(feature = "message")]
 pub fn question(text: &str) -> MessageBuilder {
     MessageBuilder::new()
         .text(text)
@@ -73,46 +123,68 @@ pub fn question(text: &str) -> MessageBuilder {
 }
 
 /// Creates a new entry dialog builder.
+#[cfg(feature = "entry")]
 pub fn entry() -> EntryBuilder {
     EntryBuilder::new()
 }
 
 /// Creates a password entry dialog (entry with hidden text).
+#[cfg(feature = "entry")]
 pub fn password() -> EntryBuilder {
     EntryBuilder::new().hide_text(true)
 }
 
 /// Creates a new progress dialog builder.
+#[cfg(feature = "progress")]
 pub fn progress() -> ProgressBuilder {
     ProgressBuilder::new()
 }
 
 /// Creates a new file selection dialog builder.
+#[cfg(feature = "files")]
 pub fn file_select() -> FileSelectBuilder {
     FileSelectBuilder::new()
 }
 
+/// Creates a new file selection dialog builder (same as [`file_select`],
+/// named after `--file-selection`).
+#[cfg(feature = "files")]
+pub fn file_selection() -> FileSelectBuilder {
+    FileSelectBuilder::new()
+}
+
 /// Creates a new list selection dialog builder.
+#[cfg(feature = "list")]
 pub fn list() -> ListBuilder {
     ListBuilder::new()
 }
 
 /// Creates a new calendar date picker dialog builder.
+#[cfg(feature = "calendar")]
 pub fn calendar() -> CalendarBuilder {
     CalendarBuilder::new()
 }
 
 /// Creates a new text info dialog builder.
+#[cfg(feature = "textinfo")]
 pub fn text_info() -> TextInfoBuilder {
     TextInfoBuilder::new()
 }
 
 /// Creates a new scale dialog builder.
+#[cfg(feature = "scale")]
 pub fn scale() -> ScaleBuilder {
     ScaleBuilder::new()
 }
 
 /// Creates a new forms dialog builder.
+#[cfg(feature = "forms")]
 pub fn forms() -> FormsBuilder {
     FormsBuilder::new()
 }
+
+/// Creates a new desktop notification builder.
+#[cfg(feature = "notify")]
+pub fn notification() -> NotificationBuilder {
+    NotificationBuilder::new()
+}
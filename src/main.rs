@@ -1,30 +1,45 @@
 //! zenity-rs - Display simple GUI dialogs from the command line.
 
-use std::{io::IsTerminal, process::ExitCode};
+use std::{
+    fs::File,
+    io::{BufRead, IsTerminal, Read, Write},
+    os::{
+        fd::AsRawFd,
+        unix::net::{UnixListener, UnixStream},
+    },
+    path::PathBuf,
+    process::ExitCode,
+};
 
 use lexopt::prelude::*;
 use zenity_rs::{
-    ButtonPreset, CalendarResult, EntryResult, FileSelectResult, FormsResult, Icon, ListResult,
-    ProgressResult, ScaleResult, TextInfoResult, calendar, entry, file_select, forms, list,
-    message, password, progress, scale, text_info,
+    ButtonPreset, CalendarResult, EntryResult, FileSelectResult, FormField, FormsResult, Geometry,
+    Icon, LabelPlacement, ListResult, Offset, Orientation, PathFormat, PrintColumn, ProgressResult,
+    ScaleResult, TextInfoResult, Theme, WeekStart, attach_to, calendar, entry, file_select, forms,
+    list, message, notification, password, progress, request_activation, scale, set_app_id,
+    text_info, ui::date::days_in_month,
 };
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Exit code when --single-instance finds the key already held.
+const EXIT_ALREADY_RUNNING: i32 = 4;
+
 fn handle_message_result(
-    result: zenity_rs::DialogResult,
-    extra_buttons: &[String],
+    outcome: zenity_rs::DialogOutcome,
     default_cancel_index: Option<usize>,
+    switch_mode: bool,
 ) -> i32 {
-    match result {
+    let checked = outcome.checked;
+    let code = match outcome.result {
+        zenity_rs::DialogResult::Extra(_) => {
+            // Like zenity, report the clicked extra button by its label. With
+            // --switch they are the only answers, so none of them is a "no".
+            println!("{}", outcome.label.unwrap_or_default());
+            if switch_mode { 0 } else { 1 }
+        }
         zenity_rs::DialogResult::Button(idx) => {
-            if idx < extra_buttons.len() {
-                // Extra button clicked - labels are reversed in positioning
-                // so we need to reverse the index to get the correct label
-                let reversed_idx = extra_buttons.len() - 1 - idx;
-                println!("{}", extra_buttons[reversed_idx]);
-                1
-            } else if let Some(cancel_idx) = default_cancel_index {
+            if let Some(cancel_idx) = default_cancel_index {
                 if idx == cancel_idx {
                     // Default cancel button (or No button) clicked
                     1
@@ -39,7 +54,14 @@ fn handle_message_result(
         }
         zenity_rs::DialogResult::Closed => 1,
         zenity_rs::DialogResult::Timeout => 5,
+    };
+    // --checkbox state, unless the dialog went away on its own
+    if let Some(checked) = checked
+        && outcome.result != zenity_rs::DialogResult::Timeout
+    {
+        println!("{checked}");
     }
+    code
 }
 
 /// Read the dialog text from stdin, stripping trailing newlines so the result
@@ -56,6 +78,21 @@ fn read_stdin_text() -> String {
     buf
 }
 
+/// Reads list values from `reader`, one per line, or each ended by a NUL
+/// byte with `nul` so they can hold newlines.
+fn read_list_values(reader: impl BufRead, nul: bool) -> std::io::Result<Vec<String>> {
+    reader
+        .split(if nul { b'\0' } else { b'\n' })
+        .map(|value| {
+            let mut value = value?;
+            if !nul && value.last() == Some(&b'\r') {
+                value.pop();
+            }
+            Ok(String::from_utf8_lossy(&value).into_owned())
+        })
+        .collect()
+}
+
 fn get_icon(icon_name: &Option<String>, default: Icon) -> Icon {
     match icon_name {
         None => default,
@@ -93,11 +130,15 @@ fn apply_message_options(
     timeout: Option<u32>,
     width: Option<u32>,
     height: Option<u32>,
+    position: Option<(Offset, Offset)>,
     no_wrap: bool,
     no_markup: bool,
     ellipsize: bool,
     switch_mode: bool,
+    default_cancel: bool,
+    sound: bool,
     _extra_buttons: &[String],
+    checkbox: &str,
 ) -> zenity_rs::MessageBuilder {
     let mut builder = builder;
     if let Some(t) = timeout {
@@ -109,6 +150,9 @@ fn apply_message_options(
     if let Some(h) = height {
         builder = builder.height(h);
     }
+    if let Some((x, y)) = position {
+        builder = builder.position(x, y);
+    }
     if no_wrap {
         builder = builder.no_wrap(true);
     }
@@ -121,12 +165,889 @@ fn apply_message_options(
     if switch_mode {
         builder = builder.switch(true);
     }
+    if default_cancel {
+        builder = builder.default_cancel(true);
+    }
+    if sound {
+        builder = builder.sound(true);
+    }
+    if !checkbox.is_empty() {
+        builder = builder.checkbox(checkbox);
+    }
     for btn in _extra_buttons {
         builder = builder.extra_button(btn);
     }
     builder
 }
 
+/// A command-line option, for the help screens and for checking it applies
+/// to the chosen dialog type.
+struct CliOption {
+    /// Long name, without the leading dashes.
+    name: &'static str,
+    /// Placeholder for its value, or "" if it takes none.
+    value: &'static str,
+    /// Dialog types it applies to; empty for every type.
+    dialogs: &'static [DialogType],
+    /// Description; further lines continue it.
+    help: &'static str,
+}
+
+const fn option(
+    name: &'static str,
+    value: &'static str,
+    dialogs: &'static [DialogType],
+    help: &'static str,
+) -> CliOption {
+    CliOption {
+        name,
+        value,
+        dialogs,
+        help,
+    }
+}
+
+/// Every option the parser accepts besides the dialog types, in help order.
+const OPTIONS: &[CliOption] = &[
+    // General
+    option("title", "TEXT", &[], "Set the dialog title"),
+    option(
+        "text",
+        "TEXT",
+        &[],
+        "Set the dialog text/prompt\n(if omitted, read from stdin when piped)",
+    ),
+    option(
+        "width",
+        "N",
+        &[],
+        "Set the dialog width (minimum when --no-wrap is used)",
+    ),
+    option("height", "N", &[], "Set the dialog height"),
+    option(
+        "geometry",
+        "WxH+X+Y",
+        &[],
+        "Set the dialog size and/or position (negative offsets\n\
+         count from the right/bottom; position is X11 only)",
+    ),
+    option(
+        "timeout",
+        "N",
+        &[],
+        "Auto-close after N seconds (exit code 5)",
+    ),
+    option(
+        "separator",
+        "TEXT",
+        &[],
+        "Join multiple output values with TEXT, used as is\n\
+         (list, forms and file selection; default: |)",
+    ),
+    option(
+        "strict",
+        "",
+        &[],
+        "Fail on options that do not apply to the dialog type\n\
+         and on out-of-range values (default: warn and ignore/clamp)",
+    ),
+    option(
+        "single-instance",
+        "KEY",
+        &[],
+        "Exit with code 4 if a dialog with the same KEY is open,\n\
+         bringing that dialog to the front",
+    ),
+    option(
+        "single-instance-wait",
+        "",
+        &[],
+        "With --single-instance, wait for that dialog to close",
+    ),
+    option(
+        "attach",
+        "HANDLE",
+        &[],
+        "Stack the dialog above another window: an xdg-foreign\n\
+         exported handle (Wayland) or a window ID (X11)",
+    ),
+    option(
+        "modal",
+        "",
+        &[],
+        "Make the dialog modal to the --attach window and ask\nfor keyboard focus",
+    ),
+    option(
+        "class",
+        "ID",
+        &[],
+        "Set the Wayland app_id and X11 WM_CLASS window rules\n\
+         match on (default: zenity)",
+    ),
+    option("name", "ID", &[], "Same as --class"),
+    option(
+        "no-remember-zoom",
+        "",
+        &[],
+        "Don't start at or save the zoom chosen with\nCtrl+Plus/Minus/0",
+    ),
+    option(
+        "theme",
+        "WHICH",
+        &[],
+        "Use the light or dark colors, or auto to follow the\n\
+         system (default: auto, or the RASK_THEME variable)",
+    ),
+    option(
+        "help",
+        "",
+        &[],
+        "Print the usage and general options (also -h)",
+    ),
+    option(
+        "help-all",
+        "",
+        &[],
+        "Print the options of every dialog type",
+    ),
+    option("help-general", "", &[], "Print the general options"),
+    option("version", "", &[], "Print version information"),
+    // Message dialogs
+    option(
+        "no-wrap",
+        "",
+        MESSAGE_DIALOGS,
+        "Do not wrap text (width becomes minimum, content can expand)",
+    ),
+    option(
+        "icon",
+        "ICON",
+        ICON_DIALOGS,
+        "Set the icon: info, warning, error, question, an icon\nname or a PNG file",
+    ),
+    option("icon-name", "ICON", ICON_DIALOGS, "Same as --icon"),
+    option(
+        "icon-no-recolor",
+        "",
+        MESSAGE_DIALOGS,
+        "Keep a monochrome PNG icon's own color on dark themes",
+    ),
+    option(
+        "extra-button",
+        "TEXT",
+        MESSAGE_DIALOGS,
+        "Add an extra button (prints its label, exit code 1)",
+    ),
+    option(
+        "switch",
+        "",
+        MESSAGE_DIALOGS,
+        "Suppress OK/Cancel buttons, only show extra buttons\n\
+         (prints the clicked label, exit code 0)",
+    ),
+    option(
+        "ok-label",
+        "TEXT",
+        MESSAGE_DIALOGS,
+        "Set the label of the OK button",
+    ),
+    option(
+        "cancel-label",
+        "TEXT",
+        MESSAGE_DIALOGS,
+        "Set the label of the Cancel button",
+    ),
+    option(
+        "no-markup",
+        "",
+        MARKUP_DIALOGS,
+        "Do not enable pango markup (for compatibility)",
+    ),
+    option(
+        "ellipsize",
+        "",
+        MESSAGE_DIALOGS,
+        "Cut long lines short with \"…\" instead of wrapping",
+    ),
+    option(
+        "default-cancel",
+        "",
+        DEFAULT_CANCEL_DIALOGS,
+        "Make Enter choose Cancel/No instead of OK/Yes",
+    ),
+    option(
+        "sound",
+        "",
+        MESSAGE_DIALOGS,
+        "Play the dialog's event sound from the sound theme",
+    ),
+    // Entry
+    option(
+        "entry-text",
+        "TEXT",
+        &[DialogType::Entry],
+        "Set default text",
+    ),
+    option(
+        "hide-text",
+        "",
+        &[DialogType::Entry, DialogType::Password],
+        "Hide the entered text (same as --password)",
+    ),
+    option(
+        "entry-restrict",
+        "",
+        &[DialogType::Entry],
+        "Only accept one of the suggested values",
+    ),
+    option(
+        "entry-ignore-case",
+        "",
+        &[DialogType::Entry],
+        "Match the suggested values ignoring case",
+    ),
+    option(
+        "confirm",
+        "",
+        &[DialogType::Password],
+        "Ask for the password twice, as when choosing a new one",
+    ),
+    option(
+        "show-strength",
+        "",
+        &[DialogType::Password],
+        "Show how strong the password is",
+    ),
+    option(
+        "max-length",
+        "N",
+        &[DialogType::Entry, DialogType::Password],
+        "Accept at most N characters",
+    ),
+    option(
+        "entry-pattern",
+        "PATTERN",
+        &[DialogType::Entry],
+        "Only accept text matching a shell glob, such as [0-9]*",
+    ),
+    option(
+        "entry-int",
+        "",
+        &[DialogType::Entry],
+        "Only accept a whole number",
+    ),
+    option(
+        "entry-float",
+        "",
+        &[DialogType::Entry],
+        "Only accept a number",
+    ),
+    option(
+        "username",
+        "",
+        &[DialogType::Password],
+        "Add a username field (outputs username|password)",
+    ),
+    // Progress
+    option(
+        "percentage",
+        "N",
+        &[DialogType::Progress],
+        "Initial progress percentage (0-100)",
+    ),
+    option(
+        "pulsate",
+        "",
+        &[DialogType::Progress],
+        "Enable pulsating/indeterminate mode",
+    ),
+    option(
+        "auto-close",
+        "",
+        &[DialogType::Progress],
+        "Close dialog when progress reaches 100%",
+    ),
+    option(
+        "auto-kill",
+        "",
+        &[DialogType::Progress],
+        "Send SIGHUP to the parent process on Cancel",
+    ),
+    option(
+        "no-cancel",
+        "",
+        &[DialogType::Progress],
+        "Hide Cancel button",
+    ),
+    option(
+        "time-remaining",
+        "",
+        &[DialogType::Progress],
+        "Show estimated time remaining",
+    ),
+    option(
+        "stall-timeout",
+        "SECS",
+        &[DialogType::Progress],
+        "Show \"(waiting…)\" after SECS without updates\n(default: 10, 0 disables)",
+    ),
+    option(
+        "multi",
+        "N",
+        &[DialogType::Progress],
+        "Stack N labeled bars over a total; input lines\nstart with the bar number, as in 2:45",
+    ),
+    // File selection
+    option(
+        "directory",
+        "",
+        &[DialogType::FileSelection],
+        "Select directories only",
+    ),
+    option(
+        "save",
+        "",
+        &[DialogType::FileSelection],
+        "Save mode (allows entering new filename)",
+    ),
+    option(
+        "confirm-overwrite",
+        "",
+        &[DialogType::FileSelection],
+        "Ask before replacing an existing file (save mode)",
+    ),
+    option(
+        "multiple",
+        "",
+        &[DialogType::FileSelection, DialogType::List],
+        "Allow selecting more than one file or row",
+    ),
+    option(
+        "filename",
+        "TEXT",
+        &[DialogType::FileSelection, DialogType::TextInfo],
+        "Default file name, or the file to show in text info\n\
+         (otherwise it reads stdin)",
+    ),
+    option(
+        "file-filter",
+        "SPEC",
+        &[DialogType::FileSelection],
+        "Add file filter (e.g., \"*.rs\" or \"Video | *.mkv *.mp4\")",
+    ),
+    option(
+        "compact",
+        "",
+        &[DialogType::FileSelection],
+        "Hide the places sidebar",
+    ),
+    option(
+        "portal",
+        "",
+        &[DialogType::FileSelection],
+        "Use the desktop portal's file chooser (default inside Flatpak)",
+    ),
+    option(
+        "null",
+        "",
+        &[DialogType::FileSelection],
+        "Terminate each selected path with a NUL byte",
+    ),
+    option(
+        "json",
+        "",
+        &[DialogType::FileSelection],
+        "Print selected paths as a JSON array",
+    ),
+    option(
+        "field-labels",
+        "WHERE",
+        &[
+            DialogType::Password,
+            DialogType::FileSelection,
+            DialogType::Forms,
+        ],
+        "Put field labels in a column at the side (default)\nor above",
+    ),
+    // List
+    option(
+        "column",
+        "TEXT",
+        &[DialogType::List],
+        "Add a column header (can be repeated)",
+    ),
+    option(
+        "checklist",
+        "",
+        &[DialogType::List],
+        "Enable multi-select with checkboxes",
+    ),
+    option(
+        "radiolist",
+        "",
+        &[DialogType::List],
+        "Enable single-select with radio buttons",
+    ),
+    option(
+        "imagelist",
+        "",
+        &[DialogType::List],
+        "Show the first column as images, from PNG paths",
+    ),
+    option(
+        "hide-column",
+        "N",
+        &[DialogType::List],
+        "Hide column N (1-based, can be repeated)",
+    ),
+    option(
+        "print-column",
+        "N",
+        &[DialogType::List],
+        "Print column N of the selected rows (1-based), or ALL",
+    ),
+    option(
+        "hide-header",
+        "",
+        &[DialogType::List],
+        "Hide the column headers",
+    ),
+    option(
+        "editable",
+        "",
+        &[DialogType::List, DialogType::TextInfo],
+        "Allow editing list cells (by double-clicking) or the\ntext of text info (OK prints it)",
+    ),
+    option(
+        "stdin-null",
+        "",
+        &[DialogType::List],
+        "Read list values from stdin ended by NUL bytes\ninstead of newlines, so they can hold newlines",
+    ),
+    option(
+        "mid-search",
+        "",
+        &[DialogType::List],
+        "Match typed searches anywhere in a cell, not only at its start",
+    ),
+    // Calendar
+    option("year", "N", &[DialogType::Calendar], "Initial year"),
+    option(
+        "month",
+        "N",
+        &[DialogType::Calendar],
+        "Initial month (1-12)",
+    ),
+    option("day", "N", &[DialogType::Calendar], "Initial day (1-31)"),
+    option(
+        "min-date",
+        "YYYY-MM-DD",
+        &[DialogType::Calendar],
+        "Gray out the days before this date",
+    ),
+    option(
+        "max-date",
+        "YYYY-MM-DD",
+        &[DialogType::Calendar],
+        "Gray out the days after this date",
+    ),
+    option(
+        "range",
+        "",
+        &[DialogType::Calendar],
+        "Pick a start and an end date, printed as START..END",
+    ),
+    option(
+        "week-numbers",
+        "",
+        &[DialogType::Calendar],
+        "Show ISO 8601 week numbers",
+    ),
+    option(
+        "week-start",
+        "DAY",
+        &[DialogType::Calendar],
+        "Start the week on monday or sunday (default: from the locale)",
+    ),
+    option(
+        "date-format",
+        "FORMAT",
+        &[DialogType::Calendar],
+        "Print the date in strftime style (default: %Y-%m-%d)",
+    ),
+    // Text info
+    option(
+        "checkbox",
+        "TEXT",
+        &[
+            DialogType::TextInfo,
+            DialogType::Info,
+            DialogType::Warning,
+            DialogType::Error,
+            DialogType::Question,
+        ],
+        "Add checkbox with label (for agreements). Message\n\
+         dialogs print whether it was checked",
+    ),
+    option(
+        "html",
+        "",
+        &[DialogType::TextInfo],
+        "Show the text as simple HTML (headings, lists, links)",
+    ),
+    option(
+        "url-support",
+        "",
+        &[DialogType::TextInfo],
+        "Underline URLs and open them when clicked (the default)",
+    ),
+    option(
+        "no-url-support",
+        "",
+        &[DialogType::TextInfo],
+        "Show URLs as plain text",
+    ),
+    option(
+        "auto-scroll",
+        "",
+        &[DialogType::TextInfo],
+        "Show text from stdin as it arrives and keep the\n\
+         end in view, like tail -f",
+    ),
+    option(
+        "max-lines",
+        "N",
+        &[DialogType::TextInfo],
+        "Keep only the last N lines of text",
+    ),
+    option(
+        "font",
+        "FONT",
+        &[DialogType::TextInfo],
+        "Show the text in FONT, a family and optional size\n\
+         in points, such as \"JetBrains Mono 12\"",
+    ),
+    // Scale
+    option(
+        "value",
+        "N",
+        &[DialogType::Scale],
+        "Initial value (default: 0)",
+    ),
+    option(
+        "min-value",
+        "N",
+        &[DialogType::Scale],
+        "Minimum value (default: 0)",
+    ),
+    option(
+        "max-value",
+        "N",
+        &[DialogType::Scale],
+        "Maximum value (default: 100)",
+    ),
+    option(
+        "step",
+        "N",
+        &[DialogType::Scale],
+        "Step increment (default: 1)",
+    ),
+    option(
+        "hide-value",
+        "",
+        &[DialogType::Scale],
+        "Hide the numeric value display",
+    ),
+    option(
+        "print-partial",
+        "",
+        &[DialogType::Scale],
+        "Print each value as the slider moves",
+    ),
+    option(
+        "marks",
+        "",
+        &[DialogType::Scale],
+        "Draw a tick at each step, with the minimum and maximum",
+    ),
+    option(
+        "vertical",
+        "",
+        &[DialogType::Scale],
+        "Run the slider up the window, larger values at the top",
+    ),
+    // Forms
+    option(
+        "add-entry",
+        "LABEL",
+        &[DialogType::Forms],
+        "Add a text entry field (can be repeated)",
+    ),
+    option(
+        "add-password",
+        "LABEL",
+        &[DialogType::Forms],
+        "Add a password field (can be repeated)",
+    ),
+    option(
+        "add-calendar",
+        "LABEL",
+        &[DialogType::Forms],
+        "Add a date field picked from a calendar (can be repeated)",
+    ),
+    option(
+        "add-combo",
+        "LABEL",
+        &[DialogType::Forms],
+        "Add a drop-down list field (can be repeated)",
+    ),
+    option(
+        "combo-values",
+        "LIST",
+        &[DialogType::Forms],
+        "Set the |-separated items of the last --add-combo",
+    ),
+    option(
+        "forms-date-format",
+        "FORMAT",
+        &[DialogType::Forms],
+        "Set the date field format, strftime style",
+    ),
+    // Notification
+    option(
+        "listen",
+        "",
+        &[DialogType::Notification],
+        "Keep updating it from stdin commands until EOF\n\
+         (message:TEXT, tooltip:TEXT, icon:NAME, visible:false)",
+    ),
+];
+
+const MESSAGE_DIALOGS: &[DialogType] = &[
+    DialogType::Info,
+    DialogType::Warning,
+    DialogType::Error,
+    DialogType::Question,
+];
+
+const MARKUP_DIALOGS: &[DialogType] = &[
+    DialogType::Info,
+    DialogType::Warning,
+    DialogType::Error,
+    DialogType::Question,
+    DialogType::Entry,
+    DialogType::List,
+];
+
+const ICON_DIALOGS: &[DialogType] = &[
+    DialogType::Info,
+    DialogType::Warning,
+    DialogType::Error,
+    DialogType::Question,
+    DialogType::Notification,
+];
+
+const DEFAULT_CANCEL_DIALOGS: &[DialogType] = &[
+    DialogType::Info,
+    DialogType::Warning,
+    DialogType::Error,
+    DialogType::Question,
+    DialogType::Entry,
+    DialogType::Password,
+    DialogType::Calendar,
+];
+
+/// Report flags that have no effect on the chosen dialog type. In strict mode
+/// the first such flag is an error; otherwise each one is warned about once.
+fn check_flags(
+    used_flags: &[String],
+    dialog_type: DialogType,
+    strict: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for flag in used_flags {
+        let Some(option) = OPTIONS.iter().find(|option| option.name == flag) else {
+            continue;
+        };
+        if !option.dialogs.is_empty() && !option.dialogs.contains(&dialog_type) {
+            let msg = format!(
+                "--{flag} is only valid with {}, not {}",
+                or_list(option.dialogs),
+                dialog_type.flag()
+            );
+            if strict {
+                return Err(msg.into());
+            }
+            eprintln!("zenity-rs: warning: {msg}, ignoring");
+        }
+    }
+    Ok(())
+}
+
+/// Parse a --width, --height or --max-lines value, which must be a positive
+/// integer.
+fn parse_dimension(flag: &str, value: &str) -> Result<u32, String> {
+    match value.parse() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => {
+            Err(format!(
+                "invalid value {value:?} for {flag}: expected a positive integer"
+            ))
+        }
+    }
+}
+
+/// Parse a --min-date or --max-date value, a date written YYYY-MM-DD.
+fn parse_date(flag: &str, value: &str) -> Result<(u32, u32, u32), String> {
+    let mut parts = value.splitn(3, '-').map(|part| part.parse::<u32>().ok());
+    match (
+        parts.next().flatten(),
+        parts.next().flatten(),
+        parts.next().flatten(),
+    ) {
+        (Some(year), Some(month), Some(day))
+            if year > 0
+                && (1..=12).contains(&month)
+                && (1..=days_in_month(year, month)).contains(&day) =>
+        {
+            Ok((year, month, day))
+        }
+        _ => {
+            Err(format!(
+                "invalid value {value:?} for {flag}: expected a date as YYYY-MM-DD"
+            ))
+        }
+    }
+}
+
+/// Set a width or height, warning when it replaces one given by a different
+/// flag (--geometry vs --width/--height); the last one wins.
+fn set_dimension(
+    slot: &mut Option<u32>,
+    source: &mut &'static str,
+    value: u32,
+    flag: &'static str,
+    what: &str,
+) {
+    if slot.is_some() && *source != flag {
+        eprintln!("zenity-rs: warning: {flag} overrides the {what} from {source}");
+    }
+    *slot = Some(value);
+    *source = flag;
+}
+
+/// Take the advisory lock for a --single-instance key. Returns `None` if
+/// another process holds it and `wait` is false.
+///
+/// The lock belongs to the open file, so the kernel releases it however the
+/// process exits, including panics and fatal signals.
+fn acquire_instance_lock(
+    key: &str,
+    wait: bool,
+) -> Result<Option<File>, Box<dyn std::error::Error>> {
+    if key.is_empty() || key.contains('/') || key.contains('\0') {
+        return Err(format!("invalid --single-instance key {key:?}").into());
+    }
+    let path = instance_path(key, "lock");
+    let file = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .map_err(|e| format!("cannot open {}: {e}", path.display()))?;
+
+    let op = if wait {
+        libc::LOCK_EX
+    } else {
+        libc::LOCK_EX | libc::LOCK_NB
+    };
+    loop {
+        if unsafe { libc::flock(file.as_raw_fd(), op) } == 0 {
+            return Ok(Some(file));
+        }
+        let err = std::io::Error::last_os_error();
+        match err.raw_os_error() {
+            Some(libc::EINTR) => continue,
+            Some(libc::EWOULDBLOCK) => return Ok(None),
+            _ => return Err(err.into()),
+        }
+    }
+}
+
+/// Where the lock and activation socket for a --single-instance key live.
+fn instance_path(key: &str, extension: &str) -> PathBuf {
+    let dir = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+    dir.join(format!("zenity-rs-{key}.{extension}"))
+}
+
+/// The activation socket of a held --single-instance key, removed on drop.
+struct ActivationSocket(PathBuf);
+
+impl Drop for ActivationSocket {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Brings the dialog forward whenever a later process with the same
+/// --single-instance key starts. Each connection to the socket carries that
+/// process's activation token, if it had one.
+fn listen_for_activation(key: &str) -> Option<ActivationSocket> {
+    let path = instance_path(key, "sock");
+    // Left over from an earlier holder of the lock, which is ours now
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).ok()?;
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let mut token = String::new();
+            let _ = stream.take(4096).read_to_string(&mut token);
+            let token = token.trim();
+            request_activation((!token.is_empty()).then_some(token));
+        }
+    });
+    Some(ActivationSocket(path))
+}
+
+/// Asks the dialog holding a --single-instance key to come forward, passing
+/// on the activation token we were launched with.
+fn activate_instance(key: &str) {
+    let token = std::env::var("XDG_ACTIVATION_TOKEN")
+        .or_else(|_| std::env::var("DESKTOP_STARTUP_ID"))
+        .unwrap_or_default();
+    if let Ok(mut stream) = UnixStream::connect(instance_path(key, "sock")) {
+        let _ = stream.write_all(token.as_bytes());
+    }
+}
+
+/// Clamp a flag value into `min..=max`. Out-of-range values are an error in
+/// strict mode and a warning otherwise.
+fn clamp_flag<T: PartialOrd + Copy + std::fmt::Display>(
+    value: T,
+    min: T,
+    max: T,
+    flag: &str,
+    dialog_type: DialogType,
+    strict: bool,
+) -> Result<T, Box<dyn std::error::Error>> {
+    let clamped = if value < min {
+        min
+    } else if value > max {
+        max
+    } else {
+        return Ok(value);
+    };
+    let msg = format!(
+        "--{flag}={value} is out of range for {} ({min}..={max})",
+        dialog_type.flag()
+    );
+    if strict {
+        return Err(msg.into());
+    }
+    eprintln!("zenity-rs: warning: {msg}, using {clamped}");
+    Ok(clamped)
+}
+
 fn main() -> ExitCode {
     match run() {
         Ok(code) => ExitCode::from(code as u8),
@@ -147,14 +1068,28 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
     // When it was not, message-style dialogs fall back to reading stdin.
     let mut text_explicit = false;
     let mut entry_text = String::new();
+    let mut entry_values: Vec<String> = Vec::new();
+    let mut entry_restrict = false;
+    let mut entry_ignore_case = false;
+    let mut entry_pattern: Option<String> = None;
+    let mut entry_int = false;
+    let mut max_length: Option<usize> = None;
+    let mut show_strength = false;
+    let mut confirm = false;
+    let mut entry_float = false;
+    let mut username = false;
     let mut timeout: Option<u32> = None;
     let mut width: Option<u32> = None;
     let mut height: Option<u32> = None;
+    let mut width_flag = "--width";
+    let mut height_flag = "--height";
+    let mut position: Option<(Offset, Offset)> = None;
     let mut no_wrap = false;
 
     // Shared options (for list, forms, file-selector)
     let mut separator = String::from("|");
     let mut multiple_mode = false;
+    let mut field_labels = LabelPlacement::Side;
 
     // Progress options
     let mut percentage: u32 = 0;
@@ -163,27 +1098,51 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
     let mut auto_kill = false;
     let mut no_cancel = false;
     let mut time_remaining = false;
+    let mut stall_timeout: Option<u32> = None;
+    let mut multi: usize = 1;
 
     // File selection options
     let mut directory_mode = false;
     let mut save_mode = false;
+    let mut confirm_overwrite = false;
     let mut filename = String::new();
     let mut file_filters: Vec<zenity_rs::FileFilter> = Vec::new();
+    let mut null_output = false;
+    let mut json_output = false;
+    let mut compact = false;
+    let mut portal = std::env::var_os("FLATPAK_ID").is_some();
 
     // List options
     let mut columns: Vec<String> = Vec::new();
     let mut list_values: Vec<String> = Vec::new();
     let mut checklist = false;
     let mut radiolist = false;
+    let mut imagelist = false;
     let mut hidden_columns: Vec<usize> = Vec::new();
+    let mut print_column = PrintColumn::Default;
+    let mut hide_header = false;
+    let mut editable = false;
+    let mut mid_search = false;
+    let mut stdin_null = false;
 
     // Calendar options
     let mut cal_year: Option<u32> = None;
     let mut cal_month: Option<u32> = None;
     let mut cal_day: Option<u32> = None;
+    let mut min_date: Option<(u32, u32, u32)> = None;
+    let mut max_date: Option<(u32, u32, u32)> = None;
+    let mut week_numbers = false;
+    let mut date_range = false;
+    let mut week_start: Option<WeekStart> = None;
+    let mut date_format: Option<String> = None;
 
     // Text info options
     let mut checkbox_text = String::new();
+    let mut html = false;
+    let mut url_support = true;
+    let mut auto_scroll = false;
+    let mut max_lines: Option<usize> = None;
+    let mut font = String::new();
 
     // Scale options
     let mut scale_value: i32 = 0;
@@ -191,16 +1150,25 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
     let mut scale_max: i32 = 100;
     let mut scale_step: i32 = 1;
     let mut hide_value = false;
+    let mut print_partial = false;
+    let mut scale_marks = false;
+    let mut scale_vertical = false;
+
+    // Notification options
+    let mut listen = false;
 
     // Forms options
-    let mut form_entries: Vec<String> = Vec::new();
-    let mut form_passwords: Vec<String> = Vec::new();
+    let mut form_fields: Vec<FormField> = Vec::new();
+    let mut forms_date_format: Option<String> = None;
 
     // Message dialog options
     let mut icon_name: Option<String> = None;
+    let mut icon_no_recolor = false;
     let mut no_markup = false;
     let mut ellipsize = false;
     let mut switch_mode = false;
+    let mut default_cancel = false;
+    let mut sound = false;
     let mut extra_buttons: Vec<String> = Vec::new();
     let mut ok_label = String::new();
     let mut cancel_label = String::new();
@@ -208,12 +1176,50 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
     // Dialog type
     let mut dialog_type: Option<DialogType> = None;
 
+    // Strictness and the long flags seen, checked once the dialog type is known
+    let mut strict = false;
+    let mut single_instance: Option<String> = None;
+    let mut single_instance_wait = false;
+    let mut attach: Option<String> = None;
+    let mut app_id: Option<String> = None;
+    let mut modal = false;
+    let mut remember_zoom = true;
+    let mut theme: Option<Theme> = None;
+    let mut used_flags: Vec<String> = Vec::new();
+
     while let Some(arg) = parser.next()? {
+        if let Long(name) = &arg
+            && !used_flags.iter().any(|f| f == name)
+        {
+            used_flags.push(name.to_string());
+        }
         match arg {
             Long("help") | Short('h') => {
                 print_help();
                 return Ok(0);
             }
+            Long("help-all") => {
+                print_general_help();
+                for dialogs in dialog_groups() {
+                    println!();
+                    print_dialog_help(&dialogs);
+                }
+                return Ok(0);
+            }
+            Long("help-general") => {
+                print_general_help();
+                return Ok(0);
+            }
+            Long(name) if name.starts_with("help-") => {
+                let Some(dialog) = DialogType::ALL
+                    .into_iter()
+                    .find(|dialog| dialog.flag()[2..] == name[5..])
+                else {
+                    return Err(format!("unknown help section --{name}, see --help").into());
+                };
+                print_dialog_help(&[dialog]);
+                return Ok(0);
+            }
             Long("version") => {
                 println!("{VERSION}");
                 return Ok(0);
@@ -233,6 +1239,7 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
             Long("text-info") => dialog_type = Some(DialogType::TextInfo),
             Long("scale") => dialog_type = Some(DialogType::Scale),
             Long("forms") => dialog_type = Some(DialogType::Forms),
+            Long("notification") => dialog_type = Some(DialogType::Notification),
 
             // Common options
             Long("title") => title = parser.value()?.string()?,
@@ -241,24 +1248,60 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
                 text_explicit = true;
             }
             Long("entry-text") => entry_text = parser.value()?.string()?,
+            Long("entry-restrict") => entry_restrict = true,
+            Long("entry-ignore-case") => entry_ignore_case = true,
+            Long("entry-pattern") => entry_pattern = Some(parser.value()?.string()?),
+            Long("entry-int") => entry_int = true,
+            Long("max-length") => max_length = Some(parser.value()?.string()?.parse()?),
+            Long("entry-float") => entry_float = true,
             Long("hide-text") => {
                 // If --hide-text is specified with --entry, treat as password mode
                 if dialog_type == Some(DialogType::Entry) {
                     dialog_type = Some(DialogType::Password);
                 }
             }
+            Long("username") => username = true,
+            Long("show-strength") => show_strength = true,
+            Long("confirm") => confirm = true,
             Long("timeout") => timeout = Some(parser.value()?.string()?.parse()?),
-            Long("width") => width = Some(parser.value()?.string()?.parse()?),
-            Long("height") => height = Some(parser.value()?.string()?.parse()?),
+            Long("width") => {
+                let w = parse_dimension("--width", &parser.value()?.string()?)?;
+                set_dimension(&mut width, &mut width_flag, w, "--width", "width");
+            }
+            Long("height") => {
+                let h = parse_dimension("--height", &parser.value()?.string()?)?;
+                set_dimension(&mut height, &mut height_flag, h, "--height", "height");
+            }
+            Long("geometry") => {
+                let geometry: Geometry = parser.value()?.string()?.parse()?;
+                if let Some(w) = geometry.width {
+                    set_dimension(&mut width, &mut width_flag, w, "--geometry", "width");
+                }
+                if let Some(h) = geometry.height {
+                    set_dimension(&mut height, &mut height_flag, h, "--geometry", "height");
+                }
+                if geometry.position.is_some() {
+                    position = geometry.position;
+                }
+            }
             Long("no-wrap") => no_wrap = true,
             Long("no-markup") => no_markup = true,
             Long("ellipsize") => ellipsize = true,
             Long("icon-name") | Long("icon") => icon_name = Some(parser.value()?.string()?),
+            Long("icon-no-recolor") => icon_no_recolor = true,
             Long("switch") => switch_mode = true,
+            Long("default-cancel") => default_cancel = true,
+            Long("sound") => sound = true,
             Long("extra-button") => extra_buttons.push(parser.value()?.string()?),
             Long("ok-label") => ok_label = parser.value()?.string()?,
             Long("cancel-label") => cancel_label = parser.value()?.string()?,
             Long("separator") => separator = parser.value()?.string()?,
+            Long("field-labels") => {
+                let value = parser.value()?.string()?;
+                field_labels = LabelPlacement::from_name(&value).ok_or_else(|| {
+                    format!("invalid value {value:?} for --field-labels: expected side or above")
+                })?;
+            }
 
             // Progress options
             Long("percentage") => percentage = parser.value()?.string()?.parse()?,
@@ -267,6 +1310,8 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
             Long("auto-kill") => auto_kill = true,
             Long("no-cancel") => no_cancel = true,
             Long("time-remaining") => time_remaining = true,
+            Long("stall-timeout") => stall_timeout = Some(parser.value()?.string()?.parse()?),
+            Long("multi") => multi = parser.value()?.string()?.parse()?,
 
             // File selection options
             Long("directory") => directory_mode = true,
@@ -275,30 +1320,26 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
                 multiple_mode = true;
             }
             Long("filename") => filename = parser.value()?.string()?,
-            Long("confirm-overwrite") => {
-                // Deprecated option, accepted for compatibility only
-            }
+            Long("confirm-overwrite") => confirm_overwrite = true,
+            Long("null") => null_output = true,
+            Long("json") => json_output = true,
+            Long("compact") => compact = true,
+            Long("portal") => portal = true,
             Long("file-filter") => {
                 let filter_spec = parser.value()?.string()?;
-                // Parse "Name | Pattern1 Pattern2 Pattern3" format
-                if let Some((name, patterns_str)) = filter_spec.split_once('|') {
-                    let name = name.trim().to_string();
-                    // Split patterns by whitespace and filter empty strings
-                    let patterns: Vec<String> = patterns_str
-                        .split_whitespace()
-                        .filter(|s| !s.is_empty())
-                        .map(|s| s.to_string())
-                        .collect();
+                // Parse "Name | Pattern1 Pattern2 Pattern3" format; without a
+                // name, the patterns are shown instead
+                let (name, patterns_str) =
+                    filter_spec.split_once('|').unwrap_or(("", &filter_spec));
+                let patterns: Vec<String> = patterns_str
+                    .split_whitespace()
+                    .map(str::to_string)
+                    .collect();
+                if !patterns.is_empty() {
                     file_filters.push(zenity_rs::FileFilter {
-                        name,
+                        name: name.trim().to_string(),
                         patterns,
                     });
-                } else {
-                    // Just pattern provided, use it as both name and single pattern
-                    file_filters.push(zenity_rs::FileFilter {
-                        name: filter_spec.clone(),
-                        patterns: vec![filter_spec],
-                    });
                 }
             }
 
@@ -306,15 +1347,60 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
             Long("column") => columns.push(parser.value()?.string()?),
             Long("checklist") => checklist = true,
             Long("radiolist") => radiolist = true,
+            Long("imagelist") => imagelist = true,
             Long("hide-column") => hidden_columns.push(parser.value()?.string()?.parse()?),
+            Long("hide-header") => hide_header = true,
+            Long("editable") => editable = true,
+            Long("mid-search") => mid_search = true,
+            Long("stdin-null") => stdin_null = true,
+            Long("print-column") => {
+                let value = parser.value()?.string()?;
+                print_column = PrintColumn::from_name(&value).ok_or_else(|| {
+                    format!("invalid value {value:?} for --print-column: expected a column number or ALL")
+                })?;
+            }
 
             // Calendar options
-            Long("year") => cal_year = Some(parser.value()?.string()?.parse()?),
+            Long("year") => {
+                let value = parser.value()?.string()?;
+                let year = value
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|&year| year > 0)
+                    .ok_or_else(|| {
+                        format!("invalid value {value:?} for --year: expected a year from 1")
+                    })?;
+                cal_year = Some(year);
+            }
             Long("month") => cal_month = Some(parser.value()?.string()?.parse()?),
             Long("day") => cal_day = Some(parser.value()?.string()?.parse()?),
+            Long("min-date") => {
+                min_date = Some(parse_date("--min-date", &parser.value()?.string()?)?);
+            }
+            Long("max-date") => {
+                max_date = Some(parse_date("--max-date", &parser.value()?.string()?)?);
+            }
+            Long("week-numbers") => week_numbers = true,
+            Long("range") => date_range = true,
+            Long("week-start") => {
+                let value = parser.value()?.string()?;
+                week_start = Some(WeekStart::from_name(&value).ok_or_else(|| {
+                    format!("invalid value {value:?} for --week-start: expected monday or sunday")
+                })?);
+            }
+            Long("date-format") => date_format = Some(parser.value()?.string()?),
 
             // Text info options
             Long("checkbox") => checkbox_text = parser.value()?.string()?,
+            Long("html") => html = true,
+            Long("url-support") => url_support = true,
+            Long("no-url-support") => url_support = false,
+            Long("auto-scroll") => auto_scroll = true,
+            Long("font") => font = parser.value()?.string()?,
+            Long("max-lines") => {
+                let n = parse_dimension("--max-lines", &parser.value()?.string()?)?;
+                max_lines = Some(n as usize);
+            }
 
             // Scale options
             Long("value") => scale_value = parser.value()?.string()?.parse()?,
@@ -322,18 +1408,66 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
             Long("max-value") => scale_max = parser.value()?.string()?.parse()?,
             Long("step") => scale_step = parser.value()?.string()?.parse()?,
             Long("hide-value") => hide_value = true,
+            Long("print-partial") => print_partial = true,
+            Long("marks") => scale_marks = true,
+            Long("vertical") => scale_vertical = true,
 
             // Forms options
-            Long("add-entry") => form_entries.push(parser.value()?.string()?),
-            Long("add-password") => form_passwords.push(parser.value()?.string()?),
-
-            // Ignored options (for compatibility with zenity)
-            Long("modal") => { /* Ignored */ }
+            Long("add-entry") => form_fields.push(FormField::Entry(parser.value()?.string()?)),
+            Long("add-password") => {
+                form_fields.push(FormField::Password(parser.value()?.string()?))
+            }
+            Long("add-calendar") => {
+                form_fields.push(FormField::Calendar(parser.value()?.string()?))
+            }
+            Long("add-combo") => {
+                form_fields.push(FormField::Combo(parser.value()?.string()?, Vec::new()))
+            }
+            Long("combo-values") => {
+                let value = parser.value()?.string()?;
+                // Fills in the last --add-combo
+                match form_fields.iter_mut().rev().find_map(|field| {
+                    match field {
+                        FormField::Combo(_, values) => Some(values),
+                        _ => None,
+                    }
+                }) {
+                    Some(values) => *values = value.split('|').map(str::to_string).collect(),
+                    None => eprintln!("zenity-rs: warning: --combo-values without --add-combo"),
+                }
+            }
+            Long("forms-date-format") => forms_date_format = Some(parser.value()?.string()?),
+
+            // Notification options
+            Long("listen") => listen = true,
+
+            Long("strict") => strict = true,
+            Long("single-instance") => single_instance = Some(parser.value()?.string()?),
+            Long("single-instance-wait") => single_instance_wait = true,
+            Long("attach") => attach = Some(parser.value()?.string()?),
+            Long("modal") => modal = true,
+            Long("class") | Long("name") => {
+                let value = parser.value()?.string()?;
+                if value.is_empty() {
+                    return Err("--class needs a non-empty ID".into());
+                }
+                app_id = Some(value);
+            }
+            Long("no-remember-zoom") => remember_zoom = false,
+            Long("theme") => {
+                let value = parser.value()?.string()?;
+                theme = Some(Theme::from_name(&value).ok_or_else(|| {
+                    format!("invalid value {value:?} for --theme: expected light, dark or auto")
+                })?);
+            }
 
             Value(val) => {
                 // Positional arguments - for list dialog these are row values
+                // and for an entry the values it suggests
                 if dialog_type == Some(DialogType::List) {
                     list_values.push(val.string()?);
+                } else if dialog_type == Some(DialogType::Entry) {
+                    entry_values.push(val.string()?);
                 } else if text.is_empty() {
                     text = val.string()?;
                     text_explicit = true;
@@ -353,18 +1487,112 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
         }
     };
 
+    check_flags(&used_flags, dialog_type, strict)?;
+
+    // --theme wins over the environment
+    let theme =
+        match theme {
+            Some(theme) => theme,
+            None => match std::env::var("RASK_THEME") {
+                Ok(value) if !value.is_empty() => {
+                    Theme::from_name(&value).ok_or_else(|| {
+                        format!(
+                            "invalid value {value:?} for RASK_THEME: expected light, dark or auto"
+                        )
+                    })?
+                }
+                _ => Theme::Auto,
+            },
+        };
+
+    if switch_mode && MESSAGE_DIALOGS.contains(&dialog_type) && extra_buttons.is_empty() {
+        return Err("--switch requires at least one --extra-button".into());
+    }
+    if entry_int && entry_float {
+        return Err("--entry-int and --entry-float can't be used together".into());
+    }
+
+    // Clamp out-of-range values the dialogs would otherwise clamp silently
+    match dialog_type {
+        DialogType::Progress => {
+            percentage = clamp_flag(percentage, 0, 100, "percentage", dialog_type, strict)?;
+        }
+        DialogType::Calendar => {
+            if let Some(m) = cal_month {
+                cal_month = Some(clamp_flag(m, 1, 12, "month", dialog_type, strict)?);
+            }
+            if let Some(d) = cal_day {
+                cal_day = Some(clamp_flag(d, 1, 31, "day", dialog_type, strict)?);
+            }
+        }
+        DialogType::Scale => {
+            scale_step = clamp_flag(scale_step, 1, i32::MAX, "step", dialog_type, strict)?;
+            if scale_min > scale_max {
+                let msg =
+                    format!("--min-value={scale_min} is greater than --max-value={scale_max}");
+                if strict {
+                    return Err(msg.into());
+                }
+                eprintln!("zenity-rs: warning: {msg}, swapping them");
+                std::mem::swap(&mut scale_min, &mut scale_max);
+            }
+            scale_value = clamp_flag(
+                scale_value,
+                scale_min,
+                scale_max,
+                "value",
+                dialog_type,
+                strict,
+            )?;
+        }
+        _ => {}
+    }
+
+    // Held until the dialog has been answered
+    let _instance_lock = match &single_instance {
+        Some(key) => {
+            let lock = match acquire_instance_lock(key, false)? {
+                Some(lock) => lock,
+                None => {
+                    activate_instance(key);
+                    match acquire_instance_lock(key, single_instance_wait)? {
+                        Some(lock) => lock,
+                        None => return Ok(EXIT_ALREADY_RUNNING),
+                    }
+                }
+            };
+            // The socket is dropped first, while the key is still ours
+            Some((listen_for_activation(key), lock))
+        }
+        None if single_instance_wait => {
+            return Err("--single-instance-wait requires --single-instance".into());
+        }
+        None => None,
+    };
+
+    if attach.is_some() || modal {
+        attach_to(attach.as_deref(), modal);
+    }
+    if let Some(app_id) = &app_id {
+        set_app_id(app_id);
+    }
+    if remember_zoom {
+        zenity_rs::remember_zoom();
+    }
+
     // When --text is not given and stdin is piped (e.g. a heredoc or another
     // command's output), read the dialog text from stdin. This lets scripts
     // write `zenity-rs --warning <<EOF ... EOF` instead of
     // `zenity-rs --warning --text="$(cat <<EOF ... EOF)"`.
     //
-    // Progress, list, and text-info already consume stdin for their own data,
-    // so they are excluded.
+    // Progress, list, text-info and notification --listen already consume
+    // stdin for their own data, so they are excluded.
     if !text_explicit
         && !matches!(
             dialog_type,
             DialogType::Progress | DialogType::List | DialogType::TextInfo
         )
+        && !(dialog_type == DialogType::Notification && listen)
         && !std::io::stdin().is_terminal()
     {
         text = read_stdin_text();
@@ -374,6 +1602,7 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
     match dialog_type {
         DialogType::Info => {
             let builder = message()
+                .colors(theme.colors())
                 .title(if title.is_empty() {
                     "Information"
                 } else {
@@ -381,6 +1610,7 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
                 })
                 .text(&text)
                 .icon(get_icon(&icon_name, Icon::Info))
+                .recolor_icon(!icon_no_recolor)
                 .buttons(get_button_preset(
                     &ok_label,
                     &cancel_label,
@@ -393,20 +1623,26 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
                 timeout,
                 width,
                 height,
+                position,
                 no_wrap,
                 no_markup,
                 ellipsize,
                 switch_mode,
+                default_cancel,
+                sound,
                 &extra_buttons,
+                &checkbox_text,
             );
-            let result = builder.show()?;
-            Ok(handle_message_result(result, &extra_buttons, None))
+            let outcome = builder.show_detailed()?;
+            Ok(handle_message_result(outcome, None, switch_mode))
         }
         DialogType::Warning => {
             let builder = message()
+                .colors(theme.colors())
                 .title(if title.is_empty() { "Warning" } else { &title })
                 .text(&text)
                 .icon(get_icon(&icon_name, Icon::Warning))
+                .recolor_icon(!icon_no_recolor)
                 .buttons(get_button_preset(
                     &ok_label,
                     &cancel_label,
@@ -419,20 +1655,26 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
                 timeout,
                 width,
                 height,
+                position,
                 no_wrap,
                 no_markup,
                 ellipsize,
                 switch_mode,
+                default_cancel,
+                sound,
                 &extra_buttons,
+                &checkbox_text,
             );
-            let result = builder.show()?;
-            Ok(handle_message_result(result, &extra_buttons, None))
+            let outcome = builder.show_detailed()?;
+            Ok(handle_message_result(outcome, None, switch_mode))
         }
         DialogType::Error => {
             let builder = message()
+                .colors(theme.colors())
                 .title(if title.is_empty() { "Error" } else { &title })
                 .text(&text)
                 .icon(get_icon(&icon_name, Icon::Error))
+                .recolor_icon(!icon_no_recolor)
                 .buttons(get_button_preset(
                     &ok_label,
                     &cancel_label,
@@ -445,20 +1687,26 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
                 timeout,
                 width,
                 height,
+                position,
                 no_wrap,
                 no_markup,
                 ellipsize,
                 switch_mode,
+                default_cancel,
+                sound,
                 &extra_buttons,
+                &checkbox_text,
             );
-            let result = builder.show()?;
-            Ok(handle_message_result(result, &extra_buttons, None))
+            let outcome = builder.show_detailed()?;
+            Ok(handle_message_result(outcome, None, switch_mode))
         }
         DialogType::Question => {
             let builder = message()
+                .colors(theme.colors())
                 .title(if title.is_empty() { "Question" } else { &title })
                 .text(&text)
                 .icon(get_icon(&icon_name, Icon::Question))
+                .recolor_icon(!icon_no_recolor)
                 .buttons(get_button_preset(
                     &ok_label,
                     &cancel_label,
@@ -471,48 +1719,99 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
                 timeout,
                 width,
                 height,
+                position,
                 no_wrap,
                 no_markup,
                 ellipsize,
                 switch_mode,
+                default_cancel,
+                sound,
                 &extra_buttons,
+                &checkbox_text,
             );
-            let result = builder.show()?;
-            Ok(handle_message_result(
-                result,
-                &extra_buttons,
-                Some(1 + extra_buttons.len()),
-            ))
+            let outcome = builder.show_detailed()?;
+            Ok(handle_message_result(outcome, Some(1), switch_mode))
         }
         DialogType::Entry => {
             let mut builder = entry()
+                .colors(theme.colors())
                 .title(if title.is_empty() { "Entry" } else { &title })
                 .text(&text)
-                .entry_text(&entry_text);
+                .no_markup(no_markup)
+                .entry_text(&entry_text)
+                .restrict_to_values(entry_restrict)
+                .ignore_case(entry_ignore_case)
+                .default_cancel(default_cancel);
+            for value in &entry_values {
+                builder = builder.value(value);
+            }
+            if let Some(n) = max_length {
+                builder = builder.max_length(n);
+            }
+            if let Some(pattern) = &entry_pattern {
+                builder = builder.pattern(pattern);
+            }
+            if entry_int {
+                builder = builder.validator(|text| {
+                    text.parse::<i64>()
+                        .map(drop)
+                        .map_err(|_| "Enter a whole number".to_string())
+                });
+            }
+            if entry_float {
+                builder = builder.validator(|text| {
+                    match text.parse::<f64>() {
+                        Ok(number) if number.is_finite() => Ok(()),
+                        _ => Err("Enter a number".to_string()),
+                    }
+                });
+            }
             if let Some(w) = width {
                 builder = builder.width(w);
             }
             if let Some(h) = height {
                 builder = builder.height(h);
             }
+            if let Some(t) = timeout {
+                builder = builder.timeout(t);
+            }
+            if let Some((x, y)) = position {
+                builder = builder.position(x, y);
+            }
             let result = builder.show()?;
             handle_entry_result(result)
         }
         DialogType::Password => {
             let mut builder = password()
+                .colors(theme.colors())
                 .title(if title.is_empty() { "Password" } else { &title })
-                .text(&text);
+                .text(&text)
+                .username(username)
+                .field_labels(field_labels)
+                .show_strength(show_strength)
+                .confirm(confirm)
+                .default_cancel(default_cancel);
+            if let Some(n) = max_length {
+                builder = builder.max_length(n);
+            }
             if let Some(w) = width {
                 builder = builder.width(w);
             }
             if let Some(h) = height {
                 builder = builder.height(h);
             }
+            if let Some(t) = timeout {
+                builder = builder.timeout(t);
+            }
+            if let Some((x, y)) = position {
+                builder = builder.position(x, y);
+            }
             let result = builder.show()?;
             handle_entry_result(result)
         }
         DialogType::Progress => {
             let mut builder = progress()
+                .colors(theme.colors())
                 .title(if title.is_empty() { "Progress" } else { &title })
                 .text(&text)
                 .percentage(percentage)
@@ -520,26 +1819,40 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
                 .auto_close(auto_close)
                 .auto_kill(auto_kill)
                 .no_cancel(no_cancel)
-                .time_remaining(time_remaining);
+                .time_remaining(time_remaining)
+                .multi(multi);
+            if let Some(seconds) = stall_timeout {
+                builder = builder.stall_timeout(seconds);
+            }
             if let Some(w) = width {
                 builder = builder.width(w);
             }
             if let Some(h) = height {
                 builder = builder.height(h);
             }
+            if let Some(t) = timeout {
+                builder = builder.timeout(t);
+            }
+            if let Some((x, y)) = position {
+                builder = builder.position(x, y);
+            }
             let result = builder.show()?;
             handle_progress_result(result)
         }
         DialogType::FileSelection => {
-            let mut builder = file_select();
+            let mut builder = file_select().colors(theme.colors());
             if !title.is_empty() {
                 builder = builder.title(&title);
             }
             builder = builder
                 .directory(directory_mode)
                 .save(save_mode)
+                .confirm_overwrite(confirm_overwrite)
                 .multiple(multiple_mode)
-                .separator(&separator);
+                .separator(&separator)
+                .field_labels(field_labels)
+                .compact(compact)
+                .portal(portal);
             if !filename.is_empty() {
                 builder = builder.filename(&filename);
             }
@@ -552,11 +1865,24 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
             if let Some(h) = height {
                 builder = builder.height(h);
             }
+            if let Some(t) = timeout {
+                builder = builder.timeout(t);
+            }
+            if let Some((x, y)) = position {
+                builder = builder.position(x, y);
+            }
+            let format = if null_output {
+                PathFormat::Null
+            } else if json_output {
+                PathFormat::Json
+            } else {
+                PathFormat::Separated(&separator)
+            };
             let result = builder.show()?;
-            handle_file_select_result(result, &separator)
+            handle_file_select_result(result, format)
         }
         DialogType::List => {
-            let mut builder = list();
+            let mut builder = list().colors(theme.colors()).no_markup(no_markup);
             if !title.is_empty() {
                 builder = builder.title(&title);
             }
@@ -576,38 +1902,62 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
             for col in &hidden_columns {
                 builder = builder.hide_column(*col);
             }
+            builder = builder
+                .print_column(print_column)
+                .hide_header(hide_header)
+                .imagelist(imagelist)
+                .editable(editable)
+                .mid_search(mid_search);
 
             // Determine column count for rows
             let num_columns = columns.len().max(1);
 
+            // Read additional rows from stdin if data is being piped
+            // Zenity format: each line is one column value, multiple lines form one row
+            let stdin = std::io::stdin();
+            if !stdin.is_terminal() {
+                list_values.extend(read_list_values(stdin.lock(), stdin_null)?);
+            } else if list_values.is_empty() {
+                // Reading a terminal would wait for typed rows
+                return Err("--list got no values: give them after the options, \
+                     or pipe them in on stdin, one per line"
+                    .into());
+            }
+
+            // Values fill the table row by row, so they must make up whole rows
+            if !list_values.len().is_multiple_of(num_columns) {
+                return Err(format!(
+                    "--list got {} values for {} columns; the number of values must be a multiple of the column count",
+                    list_values.len(),
+                    num_columns
+                )
+                .into());
+            }
+
             // Build rows from list_values based on column count
             for chunk in list_values.chunks(num_columns) {
                 builder = builder.row(chunk.to_vec());
             }
 
-            // Read additional rows from stdin if data is being piped
-            // Zenity format: each line is one column value, multiple lines form one row
-            if !std::io::stdin().is_terminal() {
-                use std::io::{self, BufRead};
-                let stdin = io::stdin();
-                let lines: Vec<String> = stdin.lock().lines().map_while(Result::ok).collect();
-                // Group lines by num_columns to form rows
-                for chunk in lines.chunks(num_columns) {
-                    builder = builder.row(chunk.to_vec());
-                }
-            }
-
             if let Some(w) = width {
                 builder = builder.width(w);
             }
             if let Some(h) = height {
                 builder = builder.height(h);
             }
+            if let Some(t) = timeout {
+                builder = builder.timeout(t);
+            }
+            if let Some((x, y)) = position {
+                builder = builder.position(x, y);
+            }
             let result = builder.show()?;
             handle_list_result(result, &separator)
         }
         DialogType::Calendar => {
-            let mut builder = calendar();
+            let mut builder = calendar()
+                .default_cancel(default_cancel)
+                .colors(theme.colors());
             if !title.is_empty() {
                 builder = builder.title(&title);
             }
@@ -623,17 +1973,36 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
             if let Some(d) = cal_day {
                 builder = builder.day(d);
             }
+            if let Some((y, m, d)) = min_date {
+                builder = builder.min_date(y, m, d);
+            }
+            if let Some((y, m, d)) = max_date {
+                builder = builder.max_date(y, m, d);
+            }
+            if let Some(week_start) = week_start {
+                builder = builder.week_start(week_start);
+            }
+            builder = builder.week_numbers(week_numbers).range(date_range);
+            if let Some(format) = &date_format {
+                builder = builder.date_format(format);
+            }
             if let Some(w) = width {
                 builder = builder.width(w);
             }
             if let Some(h) = height {
                 builder = builder.height(h);
             }
+            if let Some(t) = timeout {
+                builder = builder.timeout(t);
+            }
+            if let Some((x, y)) = position {
+                builder = builder.position(x, y);
+            }
             let result = builder.show()?;
             handle_calendar_result(result)
         }
         DialogType::TextInfo => {
-            let mut builder = text_info();
+            let mut builder = text_info().colors(theme.colors());
             if !title.is_empty() {
                 builder = builder.title(&title);
             }
@@ -644,17 +2013,34 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
             if has_checkbox {
                 builder = builder.checkbox(&checkbox_text);
             }
+            builder = builder
+                .html(html)
+                .url_support(url_support)
+                .editable(editable)
+                .auto_scroll(auto_scroll);
+            if let Some(n) = max_lines {
+                builder = builder.max_lines(n);
+            }
+            if !font.is_empty() {
+                builder = builder.font(&font);
+            }
             if let Some(w) = width {
                 builder = builder.width(w);
             }
             if let Some(h) = height {
                 builder = builder.height(h);
             }
+            if let Some(t) = timeout {
+                builder = builder.timeout(t);
+            }
+            if let Some((x, y)) = position {
+                builder = builder.position(x, y);
+            }
             let result = builder.show()?;
             handle_text_info_result(result, has_checkbox)
         }
         DialogType::Scale => {
-            let mut builder = scale();
+            let mut builder = scale().colors(theme.colors());
             if !title.is_empty() {
                 builder = builder.title(&title);
             }
@@ -666,18 +2052,31 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
                 .min_value(scale_min)
                 .max_value(scale_max)
                 .step(scale_step)
-                .hide_value(hide_value);
+                .hide_value(hide_value)
+                .print_partial(print_partial)
+                .marks(scale_marks)
+                .orientation(if scale_vertical {
+                    Orientation::Vertical
+                } else {
+                    Orientation::Horizontal
+                });
             if let Some(w) = width {
                 builder = builder.width(w);
             }
             if let Some(h) = height {
                 builder = builder.height(h);
             }
+            if let Some(t) = timeout {
+                builder = builder.timeout(t);
+            }
+            if let Some((x, y)) = position {
+                builder = builder.position(x, y);
+            }
             let result = builder.show()?;
             handle_scale_result(result)
         }
         DialogType::Forms => {
-            let mut builder = forms();
+            let mut builder = forms().colors(theme.colors());
             if !title.is_empty() {
                 builder = builder.title(&title);
             }
@@ -685,22 +2084,48 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
                 builder = builder.text(&text);
             }
             // Add fields in the order they were specified
-            for label in &form_entries {
-                builder = builder.add_entry(label);
+            for field in &form_fields {
+                builder = match field {
+                    FormField::Entry(label) => builder.add_entry(label),
+                    FormField::Password(label) => builder.add_password(label),
+                    FormField::Calendar(label) => builder.add_calendar(label),
+                    FormField::Combo(label, values) => builder.add_combo(label, values),
+                };
             }
-            for label in &form_passwords {
-                builder = builder.add_password(label);
+            if let Some(format) = &forms_date_format {
+                builder = builder.date_format(format);
             }
-            builder = builder.separator(&separator);
+            builder = builder.separator(&separator).field_labels(field_labels);
             if let Some(w) = width {
                 builder = builder.width(w);
             }
             if let Some(h) = height {
                 builder = builder.height(h);
             }
+            if let Some(t) = timeout {
+                builder = builder.timeout(t);
+            }
+            if let Some((x, y)) = position {
+                builder = builder.position(x, y);
+            }
             let result = builder.show()?;
             handle_forms_result(result, &separator)
         }
+        DialogType::Notification => {
+            let mut builder = notification().text(&text);
+            if let Some(icon) = &icon_name {
+                builder = builder.icon(icon);
+            }
+            if let Some(t) = timeout {
+                builder = builder.timeout(t);
+            }
+            if listen {
+                builder.listen(std::io::stdin().lock())?;
+            } else {
+                builder.show()?;
+            }
+            Ok(0)
+        }
     }
 }
 
@@ -708,54 +2133,27 @@ fn handle_list_result(
     result: ListResult,
     separator: &str,
 ) -> Result<i32, Box<dyn std::error::Error>> {
-    match result {
-        ListResult::Selected(items) => {
-            println!("{}", items.join(separator));
-            Ok(0)
-        }
-        ListResult::Cancelled => Ok(1),
-        ListResult::Closed => Ok(1),
+    if let Some(line) = result.joined(separator) {
+        println!("{line}");
     }
+    Ok(result.exit_code())
 }
 
 fn handle_calendar_result(result: CalendarResult) -> Result<i32, Box<dyn std::error::Error>> {
-    match result {
-        CalendarResult::Selected {
-            year,
-            month,
-            day,
-        } => {
-            println!("{:04}-{:02}-{:02}", year, month, day);
-            Ok(0)
-        }
-        CalendarResult::Cancelled => Ok(1),
-        CalendarResult::Closed => Ok(1),
+    if let Some(date) = result.to_string() {
+        println!("{date}");
     }
+    Ok(result.exit_code())
 }
 
 fn handle_file_select_result(
     result: FileSelectResult,
-    separator: &str,
+    format: PathFormat<'_>,
 ) -> Result<i32, Box<dyn std::error::Error>> {
-    match result {
-        FileSelectResult::Selected(path) => {
-            println!("{}", path.display());
-            Ok(0)
-        }
-        FileSelectResult::SelectedMultiple(paths) => {
-            println!(
-                "{}",
-                paths
-                    .iter()
-                    .map(|p| p.display().to_string())
-                    .collect::<Vec<_>>()
-                    .join(separator)
-            );
-            Ok(0)
-        }
-        FileSelectResult::Cancelled => Ok(1),
-        FileSelectResult::Closed => Ok(1),
-    }
+    // Written as raw bytes: paths need not be UTF-8 and NUL output isn't line-based
+    let mut stdout = std::io::stdout().lock();
+    result.write_paths(&mut stdout, format)?;
+    Ok(result.exit_code())
 }
 
 fn handle_progress_result(result: ProgressResult) -> Result<i32, Box<dyn std::error::Error>> {
@@ -768,8 +2166,16 @@ fn handle_entry_result(result: EntryResult) -> Result<i32, Box<dyn std::error::E
             println!("{text}");
             Ok(0)
         }
+        EntryResult::Credentials {
+            username,
+            password,
+        } => {
+            println!("{username}|{password}");
+            Ok(0)
+        }
         EntryResult::Cancelled => Ok(1),
         EntryResult::Closed => Ok(1),
+        EntryResult::Timeout => Ok(5),
     }
 }
 
@@ -780,7 +2186,14 @@ fn handle_text_info_result(
     match result {
         TextInfoResult::Ok {
             checkbox_checked,
+            text,
         } => {
+            // Exactly what was in the editor, without an added newline
+            if let Some(text) = text {
+                let mut stdout = std::io::stdout().lock();
+                stdout.write_all(text.as_bytes())?;
+                stdout.flush()?;
+            }
             // If checkbox was specified but not checked, return 1
             // Otherwise return 0
             if has_checkbox && !checkbox_checked {
@@ -791,6 +2204,7 @@ fn handle_text_info_result(
         }
         TextInfoResult::Cancelled => Ok(1),
         TextInfoResult::Closed => Ok(1),
+        TextInfoResult::Timeout => Ok(5),
     }
 }
 
@@ -802,6 +2216,7 @@ fn handle_scale_result(result: ScaleResult) -> Result<i32, Box<dyn std::error::E
         }
         ScaleResult::Cancelled => Ok(1),
         ScaleResult::Closed => Ok(1),
+        ScaleResult::Timeout => Ok(5),
     }
 }
 
@@ -809,14 +2224,10 @@ fn handle_forms_result(
     result: FormsResult,
     separator: &str,
 ) -> Result<i32, Box<dyn std::error::Error>> {
-    match result {
-        FormsResult::Values(values) => {
-            println!("{}", values.join(separator));
-            Ok(0)
-        }
-        FormsResult::Cancelled => Ok(1),
-        FormsResult::Closed => Ok(1),
+    if let Some(line) = result.joined(separator) {
+        println!("{line}");
     }
+    Ok(result.exit_code())
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -834,108 +2245,179 @@ enum DialogType {
     TextInfo,
     Scale,
     Forms,
+    Notification,
+}
+
+impl DialogType {
+    const ALL: [DialogType; 14] = [
+        DialogType::Info,
+        DialogType::Warning,
+        DialogType::Error,
+        DialogType::Question,
+        DialogType::Entry,
+        DialogType::Password,
+        DialogType::Progress,
+        DialogType::FileSelection,
+        DialogType::List,
+        DialogType::Calendar,
+        DialogType::TextInfo,
+        DialogType::Scale,
+        DialogType::Forms,
+        DialogType::Notification,
+    ];
+
+    /// The command-line flag that selects this dialog type.
+    fn flag(self) -> &'static str {
+        match self {
+            DialogType::Info => "--info",
+            DialogType::Warning => "--warning",
+            DialogType::Error => "--error",
+            DialogType::Question => "--question",
+            DialogType::Entry => "--entry",
+            DialogType::Password => "--password",
+            DialogType::Progress => "--progress",
+            DialogType::FileSelection => "--file-selection",
+            DialogType::List => "--list",
+            DialogType::Calendar => "--calendar",
+            DialogType::TextInfo => "--text-info",
+            DialogType::Scale => "--scale",
+            DialogType::Forms => "--forms",
+            DialogType::Notification => "--notification",
+        }
+    }
+
+    /// What the dialog does, for help; further lines give details.
+    fn description(self) -> &'static str {
+        match self {
+            DialogType::Info => "Display an information dialog",
+            DialogType::Warning => "Display a warning dialog",
+            DialogType::Error => "Display an error dialog",
+            DialogType::Question => "Display a question dialog (Yes/No)",
+            DialogType::Entry => {
+                "Display a text entry dialog\n\
+                 [VALUES...] are suggested below the entry (Tab completes)"
+            }
+            DialogType::Password => "Display a password entry dialog (same as --entry --hide-text)",
+            DialogType::Progress => {
+                "Display a progress dialog (reads percentage from stdin)\n\
+                 (lines: N sets percentage, #TEXT sets text,\n\
+                 pulsate starts pulsating; EOF enables OK)"
+            }
+            DialogType::FileSelection => "Display a file selection dialog",
+            DialogType::List => {
+                "Display a list selection dialog\n\
+                 [VALUES...] fill the rows (number must match column count)"
+            }
+            DialogType::Calendar => "Display a calendar date picker",
+            DialogType::TextInfo => "Display scrollable text from file or stdin",
+            DialogType::Scale => "Display a slider to select a numeric value",
+            DialogType::Forms => "Display a form with multiple input fields",
+            DialogType::Notification => "Send a desktop notification (first line is the summary)",
+        }
+    }
+
+    /// The options that apply to this dialog type only, in help order.
+    fn options(self) -> impl Iterator<Item = &'static CliOption> {
+        OPTIONS
+            .iter()
+            .filter(move |option| option.dialogs.contains(&self))
+    }
+}
+
+/// The dialog types' flags as "--a, --b or --c".
+fn or_list(dialogs: &[DialogType]) -> String {
+    let flags: Vec<&str> = dialogs.iter().map(|dialog| dialog.flag()).collect();
+    match flags.split_last() {
+        Some((last, [])) => last.to_string(),
+        Some((last, rest)) => format!("{} or {last}", rest.join(", ")),
+        None => String::new(),
+    }
+}
+
+/// Column the help text of each option starts at.
+const HELP_COLUMN: usize = 26;
+
+/// One help entry: `name` indented by `indent`, then `help` from
+/// [`HELP_COLUMN`], on a line of its own if `name` reaches it.
+fn help_entry(indent: usize, name: &str, help: &str) -> String {
+    let mut entry = format!("{:indent$}{name}", "");
+    for (i, line) in help.lines().enumerate() {
+        if i > 0 || entry.len() >= HELP_COLUMN {
+            entry.push('\n');
+            entry.push_str(&" ".repeat(HELP_COLUMN));
+        } else {
+            entry.push_str(&" ".repeat(HELP_COLUMN - entry.len()));
+        }
+        entry.push_str(line);
+    }
+    entry
+}
+
+fn option_help(option: &CliOption) -> String {
+    let name = match option.value {
+        "" => format!("--{}", option.name),
+        value => format!("--{}={value}", option.name),
+    };
+    help_entry(4, &name, option.help)
+}
+
+/// Dialog types in help order, those with the same options grouped together.
+fn dialog_groups() -> Vec<Vec<DialogType>> {
+    let mut groups: Vec<Vec<DialogType>> = Vec::new();
+    for dialog in DialogType::ALL {
+        let names = |dialog: DialogType| dialog.options().map(|option| option.name);
+        match groups.last_mut() {
+            Some(group) if names(group[0]).eq(names(dialog)) => group.push(dialog),
+            _ => groups.push(vec![dialog]),
+        }
+    }
+    groups
+}
+
+fn print_general_help() {
+    println!(
+        "zenity-rs {VERSION} - Display simple GUI dialogs from the command line\n\n\
+         USAGE:\n    zenity-rs --<dialog-type> [OPTIONS] [VALUES...]\n\n\
+         GENERAL OPTIONS:"
+    );
+    for option in OPTIONS.iter().filter(|option| option.dialogs.is_empty()) {
+        println!("{}", option_help(option));
+    }
+}
+
+/// Print dialog types that share their options, then those options.
+fn print_dialog_help(dialogs: &[DialogType]) {
+    for dialog in dialogs {
+        println!("{}", help_entry(2, dialog.flag(), dialog.description()));
+    }
+    for option in dialogs[0].options() {
+        println!("{}", option_help(option));
+    }
 }
 
 fn print_help() {
+    print_general_help();
+    println!("\nDIALOG TYPES:");
+    for dialog in DialogType::ALL {
+        let summary = dialog.description().lines().next().unwrap_or_default();
+        println!("{}", help_entry(4, dialog.flag(), summary));
+    }
     println!(
-        r#"zenity-rs {VERSION} - Display simple GUI dialogs from the command line
-
-USAGE:
-    zenity-rs --<dialog-type> [OPTIONS] [VALUES...]
-
-  COMMON OPTIONS:
-    --title=TEXT          Set the dialog title
-    --text=TEXT           Set the dialog text/prompt
-                          (if omitted, read from stdin when piped)
-    --width=N             Set the dialog width (minimum when --no-wrap is used)
-    --height=N            Set the dialog height
-    --no-wrap             Do not wrap text (width becomes minimum, content can expand)
-    --icon=ICON           Set the icon name (e.g., dialog-information, dialog-warning)
-    --ok-label=TEXT       Set the label of the OK button
-    --cancel-label=TEXT   Set the label of the Cancel button
-    --extra-button=TEXT   Add an extra button (outputs label text, exit code 1+)
-    --switch              Suppress OK/Cancel buttons, only show extra buttons
-    --no-markup           Do not enable pango markup (for compatibility)
-    --ellipsize           Enable ellipsizing in dialog text (for compatibility)
-    -h, --help            Print this help message
-    --version             Print version information
-
-  DIALOG TYPES AND OPTIONS:
-
-  Message Dialogs:
-    --info                Display an information dialog
-    --warning             Display a warning dialog
-    --error               Display an error dialog
-    --question            Display a question dialog (Yes/No)
-      --timeout=N         Auto-close after N seconds (exit code 5)
-      --no-wrap           Do not wrap text (width becomes minimum, content can expand)
-      --icon=ICON         Set the icon name (also accepts --icon-name for compatibility)
-      --switch            Only show extra buttons (suppress OK/Cancel)
-      --extra-button=TEXT Add extra buttons
-      --no-markup         Do not enable pango markup (for compatibility)
-      --ellipsize         Enable ellipsizing in dialog text (for compatibility)
-
-  --entry                 Display a text entry dialog
-    --entry-text=TEXT     Set default text
-    --hide-text           Hide entered text (password mode)
-
-  --password              Display a password entry dialog (same as --entry --hide-text)
-
-  --progress              Display a progress dialog (reads percentage from stdin)
-    --percentage=N        Initial progress percentage (0-100)
-    --pulsate             Enable pulsating/indeterminate mode
-    --auto-close          Close dialog when progress reaches 100%
-    --auto-kill           Kill parent process if Cancel button is pressed
-    --no-cancel           Hide Cancel button
-    --time-remaining      Show estimated time remaining
-
-  --file-selection      Display a file selection dialog
-    --directory       Select directories only
-    --save            Save mode (allows entering new filename)
-    --multiple        Allow multiple file selection
-    --separator=TEXT  Output separator for multiple files (default: space)
-    --filename=TEXT   Default filename/path
-    --file-filter=SPEC Add file filter (e.g., "*.rs" or "Video | *.mkv *.mp4")
-    --confirm-overwrite Deprecated, accepted for compatibility
-
-  --list                Display a list selection dialog
-    --column=TEXT     Add a column header (can be repeated)
-    --checklist       Enable multi-select with checkboxes
-    --radiolist       Enable single-select with radio buttons
-    --multiple        Enable multi-select without checkboxes
-    --hide-column=N   Hide column N (1-based, can be repeated)
-    [VALUES...]       Row values (number must match column count)
-
-  --calendar              Display a calendar date picker
-    --year=N              Initial year
-    --month=N             Initial month (1-12)
-    --day=N               Initial day (1-31)
-
-  --text-info             Display scrollable text from file or stdin
-    --filename=TEXT       Read text from file (otherwise reads stdin)
-    --checkbox=TEXT       Add checkbox with label (for agreements)
-
-  --scale                 Display a slider to select a numeric value
-    --value=N             Initial value (default: 0)
-    --min-value=N         Minimum value (default: 0)
-    --max-value=N         Maximum value (default: 100)
-    --step=N              Step increment (default: 1)
-    --hide-value          Hide the numeric value display
-
-  --forms                 Display a form with multiple input fields
-    --add-entry=LABEL     Add a text entry field (can be repeated)
-    --add-password=LABEL  Add a password field (can be repeated)
-    --separator=CHAR      Output separator (default: |)
-
- EXAMPLES:
+        r#"
+    See --help-DIALOG (e.g. --help-list) for the options of a dialog type,
+    or --help-all for all of them.
+
+EXAMPLES:
     zenity-rs --info --text="Operation completed"
     zenity-rs --warning <<EOF
     Multi-line warning text
     read straight from stdin
     EOF
     zenity-rs --question --text="Continue?" --timeout=10
+    zenity-rs --notification --text="Backup finished"
     zenity-rs --entry --text="Enter name:" --entry-text="John"
     zenity-rs --password --text="Enter password:"
+    zenity-rs --password --username --title="Login"
     echo "50" | zenity-rs --progress --text="Working..." --auto-close
     zenity-rs --file-selection --save --filename="output.txt"
     zenity-rs --file-selection --multiple --file-filter="*.rs" --file-filter="*.txt"
@@ -951,7 +2433,7 @@ EXIT CODES:
     0   OK/Yes clicked, or value selected
     1   Cancel/No clicked, dialog closed (ESC or window close), or checkbox unchecked
     5   Timeout reached
-    100 Error occurred
+    100 Error occurred (including --strict violations)
 "#
     );
 }
@@ -0,0 +1,150 @@
+//! File selection through the xdg-desktop-portal `FileChooser`, the only way
+//! to reach the user's files from inside a sandbox such as Flatpak.
+
+use std::{
+    ffi::OsStr,
+    os::unix::ffi::OsStrExt,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    dbus::{Body, Connection, Variant, percent_decode},
+    error::Error,
+    ui::file_select::{FileFilter, FileSelectResult},
+};
+
+const SERVICE: &str = "org.freedesktop.portal.Desktop";
+const PATH: &str = "/org/freedesktop/portal/desktop";
+const FILE_CHOOSER: &str = "org.freedesktop.portal.FileChooser";
+const REQUEST: &str = "org.freedesktop.portal.Request";
+
+/// How long the portal may take to start and answer the request itself,
+/// before the user has seen anything.
+const CALL_TIMEOUT: Duration = Duration::from_secs(25);
+
+// Filter pattern kinds
+const GLOB: u32 = 0;
+
+/// What to ask the portal for.
+pub(crate) struct Request<'a> {
+    pub title: &'a str,
+    pub save: bool,
+    pub multiple: bool,
+    pub directory: bool,
+    pub filters: &'a [FileFilter],
+    pub folder: PathBuf,
+    /// The file name a save starts with.
+    pub name: Option<String>,
+}
+
+/// Asks the portal for files. Returns `None` when no portal answers, for the
+/// built-in dialog to be shown instead.
+pub(crate) fn choose(
+    request: &Request,
+    deadline: Option<Instant>,
+) -> Result<Option<FileSelectResult>, Error> {
+    let Ok(mut connection) = Connection::session() else {
+        return Ok(None);
+    };
+    let Ok(handle) = send(&mut connection, request) else {
+        return Ok(None);
+    };
+
+    // The user may take as long as they like, up to the timeout
+    connection.set_timeout(deadline.map(|deadline| {
+        deadline
+            .saturating_duration_since(Instant::now())
+            .max(Duration::from_millis(1))
+    }))?;
+    let response = match connection.wait_signal(&handle, "Response") {
+        Ok(response) => response,
+        Err(_) if deadline.is_some_and(|deadline| Instant::now() >= deadline) => {
+            connection.set_timeout(Some(CALL_TIMEOUT))?;
+            connection.call(SERVICE, &handle, REQUEST, "Close", &Body::new())?;
+            return Ok(Some(FileSelectResult::Timeout));
+        }
+        Err(err) => return Err(err),
+    };
+
+    let mut reader = response.reader();
+    // 0 is success, 1 cancelled and 2 any other ending
+    if reader.u32()? != 0 {
+        return Ok(Some(FileSelectResult::Cancelled));
+    }
+    let uris = reader
+        .string_array_dict()?
+        .into_iter()
+        .find_map(|(key, values)| (key == "uris").then_some(values))
+        .unwrap_or_default();
+    let mut paths: Vec<PathBuf> = uris.iter().filter_map(|uri| file_uri_path(uri)).collect();
+    Ok(Some(match paths.len() {
+        0 => FileSelectResult::Cancelled,
+        1 if !request.multiple => FileSelectResult::Selected(paths.remove(0)),
+        _ => FileSelectResult::SelectedMultiple(paths),
+    }))
+}
+
+/// Sends the `OpenFile` or `SaveFile` call, returning the object path of the
+/// request whose `Response` signal carries the answer.
+fn send(connection: &mut Connection, request: &Request) -> Result<String, Error> {
+    connection.set_timeout(Some(CALL_TIMEOUT))?;
+    // Subscribe before calling, so a quick response isn't missed
+    let rule = format!("type='signal',interface='{REQUEST}',member='Response'");
+    connection.call(
+        "org.freedesktop.DBus",
+        "/org/freedesktop/DBus",
+        "org.freedesktop.DBus",
+        "AddMatch",
+        &Body::new().string(&rule),
+    )?;
+
+    let token = format!("zenity_rs_{}", std::process::id());
+    let mut folder = request.folder.as_os_str().as_bytes().to_vec();
+    folder.push(0);
+    let labels: Vec<String> = request.filters.iter().map(FileFilter::label).collect();
+    let filters: Vec<(&str, Vec<(u32, &str)>)> = request
+        .filters
+        .iter()
+        .zip(&labels)
+        .map(|(filter, label)| {
+            let patterns = filter.patterns.iter().map(|p| (GLOB, p.as_str())).collect();
+            (label.as_str(), patterns)
+        })
+        .collect();
+
+    let mut options = vec![
+        ("handle_token", Variant::String(&token)),
+        ("modal", Variant::Bool(true)),
+        ("current_folder", Variant::Bytes(&folder)),
+    ];
+    if !filters.is_empty() {
+        options.push(("filters", Variant::Filters(&filters)));
+    }
+    let method = if request.save {
+        if let Some(name) = &request.name {
+            options.push(("current_name", Variant::String(name)));
+        }
+        "SaveFile"
+    } else {
+        options.push(("multiple", Variant::Bool(request.multiple)));
+        options.push(("directory", Variant::Bool(request.directory)));
+        "OpenFile"
+    };
+
+    let body = Body::new().string("").string(request.title).dict(&options);
+    let reply = connection.call(SERVICE, PATH, FILE_CHOOSER, method, &body)?;
+    reply.reader().string()
+}
+
+/// The local path a `file://` URI names, decoding `%XX` escapes. Other
+/// schemes, and files on other hosts, have none.
+pub fn file_uri_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    let path = match rest.find('/')? {
+        0 => rest,
+        host_end if &rest[..host_end] == "localhost" => &rest[host_end..],
+        _ => return None,
+    };
+    Some(PathBuf::from(OsStr::from_bytes(&percent_decode(path))))
+}
@@ -0,0 +1,115 @@
+//! A fixed-size cache for rendered content that's cheaper to keep than to redraw.
+
+use std::collections::VecDeque;
+
+/// Keeps the last `capacity` values inserted, dropping the oldest once full.
+///
+/// Meant for a window of rendered rows that slides as a view scrolls: rows
+/// that stay in sight are hits, and the ones scrolled away age out.
+pub(crate) struct RingBufferCache<K, V> {
+    entries: VecDeque<(K, V)>,
+    capacity: usize,
+    hits: u64,
+    misses: u64,
+}
+
+impl<K: PartialEq, V> RingBufferCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Returns the value for `key`, making it with `make` if it isn't cached.
+    pub fn get_or_insert_with(&mut self, key: K, make: impl FnOnce() -> V) -> &V {
+        let index = match self.entries.iter().position(|(k, _)| *k == key) {
+            Some(index) => {
+                self.hits += 1;
+                index
+            }
+            None => {
+                self.misses += 1;
+                if self.entries.len() == self.capacity {
+                    self.entries.pop_front();
+                }
+                self.entries.push_back((key, make()));
+                self.entries.len() - 1
+            }
+        };
+        &self.entries[index].1
+    }
+
+    /// Drops the value for `key`, if cached, so the next lookup makes it again.
+    pub fn remove(&mut self, key: &K) {
+        self.entries.retain(|(k, _)| k != key);
+    }
+
+    /// Drops every value.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// The share of lookups so far that found their value cached.
+    #[cfg(any(test, feature = "mock-backend"))]
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{Canvas, Font, rgb};
+
+    /// Draws `visible` rows from each offset the way the list does, returning
+    /// the cache afterwards.
+    fn scroll_through(
+        rows: &[String],
+        visible: usize,
+        step: usize,
+    ) -> RingBufferCache<usize, Canvas> {
+        let font = Font::load(1.0);
+        let mut cache = RingBufferCache::new(visible * 4);
+        for offset in (0..rows.len() - visible).step_by(step) {
+            for (ri, row) in rows.iter().enumerate().skip(offset).take(visible) {
+                cache.get_or_insert_with(ri, || font.render(row).with_color(rgb(0, 0, 0)).finish());
+            }
+            assert!(cache.entries.len() <= visible * 4);
+        }
+        cache
+    }
+
+    #[test]
+    fn sequential_scrolling_mostly_hits() {
+        let rows: Vec<String> = (0..3_000).map(|i| format!("Row {i}")).collect();
+        // One row at a time, as the arrow keys scroll
+        assert!(scroll_through(&rows, 16, 1).hit_rate() > 0.9);
+        // Two at a time, as the wheel does
+        assert!(scroll_through(&rows, 16, 2).hit_rate() > 0.85);
+    }
+
+    #[test]
+    fn the_oldest_values_go_first() {
+        let mut cache = RingBufferCache::new(2);
+        cache.get_or_insert_with(1, || "one");
+        cache.get_or_insert_with(2, || "two");
+        cache.get_or_insert_with(3, || "three");
+        assert_eq!(cache.entries.len(), 2);
+        assert_eq!(*cache.get_or_insert_with(2, || "again"), "two");
+        assert_eq!(*cache.get_or_insert_with(1, || "again"), "again");
+
+        // Removed values are made again
+        cache.remove(&1);
+        assert_eq!(*cache.get_or_insert_with(1, || "edited"), "edited");
+        cache.clear();
+        assert!(cache.entries.is_empty());
+    }
+}
@@ -1,6 +1,14 @@
+mod cache;
+mod sanitize;
 mod text;
 
-pub(crate) use text::Font;
+#[cfg(feature = "list")]
+pub(crate) use cache::RingBufferCache;
+#[cfg(any(feature = "message", feature = "textinfo"))]
+pub(crate) use sanitize::sanitize_text;
+#[cfg(feature = "textinfo")]
+pub(crate) use text::parse_font_spec;
+pub use text::{Align, EllipsizeMode, Font, RunStyle, TextLayout, TextRenderer, TextRun};
 use tiny_skia::{Color, Paint, PathBuilder, Pixmap, PixmapRef, Rect, Transform};
 
 /// A canvas backed by a tiny-skia Pixmap.
@@ -24,6 +32,69 @@ impl Canvas {
         self.pixmap.height()
     }
 
+    /// Decodes a PNG image.
+    pub fn from_png(data: &[u8]) -> Option<Self> {
+        Pixmap::decode_png(data).ok().map(|pixmap| {
+            Self {
+                pixmap,
+            }
+        })
+    }
+
+    /// Returns a copy resized to `width`x`height`.
+    pub fn scaled(&self, width: u32, height: u32) -> Self {
+        Self {
+            pixmap: text::scale_pixmap(&self.pixmap, width, height),
+        }
+    }
+
+    /// The (unpremultiplied) color at a pixel, or `None` outside the canvas.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<Rgba> {
+        let c = self.pixmap.pixel(x, y)?.demultiply();
+        Some(Rgba::new(c.red(), c.green(), c.blue(), c.alpha()))
+    }
+
+    /// Whether the visible pixels are (nearly) all shades of gray, as in
+    /// symbolic icons. Fully transparent images don't count.
+    pub fn is_monochrome(&self) -> bool {
+        // Faint edge pixels carry too little color to judge
+        const MIN_ALPHA: u8 = 32;
+        const MAX_CHROMA: u8 = 24;
+
+        let mut visible = 0usize;
+        let mut colorful = 0usize;
+        for c in self.pixmap.pixels() {
+            if c.alpha() < MIN_ALPHA {
+                continue;
+            }
+            let c = c.demultiply();
+            let max = c.red().max(c.green()).max(c.blue());
+            let min = c.red().min(c.green()).min(c.blue());
+            visible += 1;
+            if max - min > MAX_CHROMA {
+                colorful += 1;
+            }
+        }
+        // Tolerate a few stray pixels from antialiasing or compression
+        visible > 0 && colorful * 50 <= visible
+    }
+
+    /// Paints every pixel `color`, keeping its alpha, the way GTK recolors
+    /// symbolic icons.
+    pub fn tint(&mut self, color: Rgba) {
+        for px in self.pixmap.pixels_mut() {
+            let alpha = px.alpha();
+            let premultiply = |v: u8| ((v as u16 * alpha as u16 + 127) / 255) as u8;
+            *px = tiny_skia::PremultipliedColorU8::from_rgba(
+                premultiply(color.r),
+                premultiply(color.g),
+                premultiply(color.b),
+                alpha,
+            )
+            .expect("premultiplied components never exceed alpha");
+        }
+    }
+
     /// Fills the entire canvas with a color.
     pub fn fill(&mut self, color: Rgba) {
         self.pixmap.fill(color.into());
@@ -42,6 +113,49 @@ impl Canvas {
             .fill_rect(rect, &paint, Transform::identity(), None);
     }
 
+    /// Fills a circle with a color.
+    pub fn fill_circle(&mut self, cx: f32, cy: f32, r: f32, color: Rgba) {
+        let Some(path) = PathBuilder::from_circle(cx, cy, r) else {
+            return;
+        };
+        let mut paint = Paint::default();
+        paint.set_color(color.into());
+        paint.anti_alias = true;
+        self.pixmap.fill_path(
+            &path,
+            &paint,
+            tiny_skia::FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+    }
+
+    /// Fills a closed polygon with a color.
+    pub fn fill_polygon(&mut self, points: &[(f32, f32)], color: Rgba) {
+        let mut pb = PathBuilder::new();
+        for (i, &(x, y)) in points.iter().enumerate() {
+            if i == 0 {
+                pb.move_to(x, y);
+            } else {
+                pb.line_to(x, y);
+            }
+        }
+        pb.close();
+        let Some(path) = pb.finish() else {
+            return;
+        };
+        let mut paint = Paint::default();
+        paint.set_color(color.into());
+        paint.anti_alias = true;
+        self.pixmap.fill_path(
+            &path,
+            &paint,
+            tiny_skia::FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+    }
+
     /// Fills a rounded rectangle with a color.
     pub fn fill_rounded_rect(&mut self, x: f32, y: f32, w: f32, h: f32, radius: f32, color: Rgba) {
         let path = rounded_rect_path(x, y, w, h, radius);
@@ -86,6 +200,20 @@ impl Canvas {
         self.draw_pixmap(other.pixmap.as_ref(), x, y);
     }
 
+    /// Draws another canvas slanted to the right by `slant` pixels per pixel
+    /// of height, keeping its bottom edge at `x`. Used for synthetic italics.
+    pub fn draw_canvas_slanted(&mut self, other: &Canvas, x: i32, y: i32, slant: f32) {
+        let height = other.height() as f32;
+        self.pixmap.draw_pixmap(
+            0,
+            0,
+            other.pixmap.as_ref(),
+            &tiny_skia::PixmapPaint::default(),
+            Transform::from_row(1.0, 0.0, -slant, 1.0, x as f32 + slant * height, y as f32),
+            None,
+        );
+    }
+
     /// Draws a pixmap onto this canvas at the given position.
     pub fn draw_pixmap(&mut self, src: PixmapRef, x: i32, y: i32) {
         self.pixmap.draw_pixmap(
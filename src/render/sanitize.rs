@@ -0,0 +1,200 @@
+//! Cleanup of text read from files or stdin before it is rendered.
+
+/// Tab stops are every this many columns.
+const TAB_WIDTH: usize = 8;
+
+const ESC: char = '\u{1b}';
+const BEL: char = '\u{07}';
+/// The single-character (C1) form of `ESC [`.
+const CSI: char = '\u{9b}';
+/// The single-character (C1) form of `ESC ]`.
+const OSC: char = '\u{9d}';
+/// The single-character (C1) string terminator.
+const ST: char = '\u{9c}';
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Text,
+    /// After ESC.
+    Escape,
+    /// Inside a control sequence; holds the byte offset of its start.
+    Csi(usize),
+    /// Inside an operating system command (or other string sequence).
+    Osc,
+    /// After ESC inside an OSC, which may start the ST terminator.
+    OscEscape,
+    /// After CR, which is dropped if a LF follows.
+    CarriageReturn,
+}
+
+/// Makes text safe to render as plain lines.
+///
+/// - Tabs are expanded to the next 8-column stop.
+/// - CRLF and lone CR become LF.
+/// - ANSI escape sequences are removed. With `keep_sgr`, SGR (color and
+///   style) sequences are kept verbatim and don't count towards tab columns.
+/// - Any other C0 or C1 control character is dropped.
+pub(crate) fn sanitize_text(text: &str, keep_sgr: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut column = 0;
+    let mut state = State::Text;
+
+    for (i, c) in text.char_indices() {
+        if state == State::CarriageReturn {
+            out.push('\n');
+            column = 0;
+            state = State::Text;
+            if c == '\n' {
+                continue;
+            }
+        }
+
+        match state {
+            State::Text | State::CarriageReturn => {
+                match c {
+                    '\n' => {
+                        out.push('\n');
+                        column = 0;
+                    }
+                    '\r' => state = State::CarriageReturn,
+                    '\t' => {
+                        let spaces = TAB_WIDTH - column % TAB_WIDTH;
+                        out.extend(std::iter::repeat_n(' ', spaces));
+                        column += spaces;
+                    }
+                    ESC => state = State::Escape,
+                    CSI => state = State::Csi(i),
+                    OSC | '\u{90}' | '\u{98}' | '\u{9e}' | '\u{9f}' => state = State::Osc,
+                    c if c.is_control() => {}
+                    c => {
+                        out.push(c);
+                        column += 1;
+                    }
+                }
+            }
+            State::Escape => {
+                state = match c {
+                    '[' => State::Csi(i - 1),
+                    // OSC, DCS, SOS, PM and APC all run until a terminator
+                    ']' | 'P' | 'X' | '^' | '_' => State::Osc,
+                    // Intermediate bytes of a longer escape (e.g. charset selection)
+                    ' '..='/' => State::Escape,
+                    _ => State::Text,
+                };
+            }
+            State::Csi(start) => {
+                match c {
+                    // Parameter and intermediate bytes
+                    ' '..='?' => {}
+                    // Final byte
+                    '@'..='~' => {
+                        if keep_sgr && c == 'm' {
+                            out.push_str(&text[start..=i]);
+                        }
+                        state = State::Text;
+                    }
+                    // Malformed sequence; drop what we have
+                    _ => state = State::Text,
+                }
+            }
+            State::Osc => {
+                match c {
+                    BEL | ST => state = State::Text,
+                    ESC => state = State::OscEscape,
+                    _ => {}
+                }
+            }
+            State::OscEscape => {
+                state = if c == '\\' { State::Text } else { State::Osc };
+            }
+        }
+    }
+
+    if state == State::CarriageReturn {
+        out.push('\n');
+    }
+    out
+}
+
+/// Checks that log-style input is cleaned up before rendering.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::Font;
+
+    /// Captured from `ls --color=always -l` (with a CRLF line ending added, as
+    /// seen when logs pass through Windows tools).
+    const LS_COLOR: &str = "total 12\r\n\
+    drwxr-xr-x 2 me me 4096 Jan  1 12:00 \u{1b}[0m\u{1b}[01;34massets\u{1b}[0m\n\
+    -rw-r--r-- 1 me me  512 Jan  1 12:00 Cargo.toml\n\
+    -rwxr-xr-x 1 me me 1024 Jan  1 12:00 \u{1b}[01;32mbuild.sh\u{1b}[0m\n\
+    lrwxrwxrwx 1 me me    7 Jan  1 12:00 \u{1b}[01;36mlink\u{1b}[0m -> \u{1b}[01;34msrc\u{1b}[0m\n";
+
+    const LS_PLAIN: &str = "total 12\n\
+    drwxr-xr-x 2 me me 4096 Jan  1 12:00 assets\n\
+    -rw-r--r-- 1 me me  512 Jan  1 12:00 Cargo.toml\n\
+    -rwxr-xr-x 1 me me 1024 Jan  1 12:00 build.sh\n\
+    lrwxrwxrwx 1 me me    7 Jan  1 12:00 link -> src\n";
+
+    #[test]
+    fn strips_ls_color_output() {
+        let clean = sanitize_text(LS_COLOR, false);
+        assert_eq!(clean, LS_PLAIN);
+        assert!(!clean.chars().any(|c| c.is_control() && c != '\n'));
+    }
+
+    #[test]
+    fn ls_color_measures_like_plain_text() {
+        let font = Font::load(1.0);
+        let clean = sanitize_text(LS_COLOR, false);
+        let measured = font.render(&clean).measure();
+        assert_eq!(measured, font.render(LS_PLAIN).measure());
+        // Sanitizing is idempotent, so re-running it can't shift the layout
+        let twice = sanitize_text(&clean, false);
+        assert_eq!(font.render(&twice).measure(), measured);
+    }
+
+    #[test]
+    fn keeps_sgr_when_asked() {
+        let kept = sanitize_text("\u{1b}[1mbold\u{1b}[0m\u{1b}[2K", true);
+        assert_eq!(kept, "\u{1b}[1mbold\u{1b}[0m");
+    }
+
+    #[test]
+    fn expands_tabs_to_stops() {
+        assert_eq!(sanitize_text("a\tb", false), "a       b");
+        assert_eq!(sanitize_text("12345678\tx", false), "12345678        x");
+        assert_eq!(sanitize_text("ab\ncd\te", false), "ab\ncd      e");
+        // Escape sequences take no columns
+        assert_eq!(
+            sanitize_text("\u{1b}[31mab\u{1b}[0m\tc", true),
+            "\u{1b}[31mab\u{1b}[0m      c"
+        );
+    }
+
+    #[test]
+    fn translates_carriage_returns() {
+        assert_eq!(sanitize_text("a\r\nb\rc\r", false), "a\nb\nc\n");
+        assert_eq!(sanitize_text("a\r\r\nb", false), "a\n\nb");
+    }
+
+    #[test]
+    fn drops_other_controls() {
+        assert_eq!(sanitize_text("a\u{0}b\u{7}c\u{7f}d\u{85}e", false), "abcde");
+        // OSC hyperlinks (as emitted by `ls --hyperlink`) are removed entirely
+        assert_eq!(
+            sanitize_text("\u{1b}]8;;file:///tmp\u{1b}\\tmp\u{1b}]8;;\u{7}", false),
+            "tmp"
+        );
+        // C1 CSI is handled like ESC [
+        assert_eq!(sanitize_text("\u{9b}1mx", false), "x");
+        // Charset selection escapes
+        assert_eq!(sanitize_text("\u{1b}(Bx", false), "x");
+    }
+
+    #[test]
+    fn leaves_plain_text_alone() {
+        let text = "Résumé — 日本語 🎉\nsecond line";
+        assert_eq!(sanitize_text(text, false), text);
+    }
+}
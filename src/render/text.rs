@@ -1,6 +1,8 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
+    ops::Range,
     path::{Path, PathBuf},
+    rc::Rc,
     sync::{Mutex, OnceLock},
 };
 
@@ -8,7 +10,7 @@ use ab_glyph::{
     Font as _, FontArc, Glyph, GlyphId, GlyphImageFormat, OutlinedGlyph, PxScaleFont, Rect,
     ScaleFont, point,
 };
-use tiny_skia::Pixmap;
+use tiny_skia::{Paint, Pixmap};
 
 use super::{Canvas, Rgba, rgb};
 
@@ -18,6 +20,49 @@ pub struct Font {
     primary: PxScaleFont<FontArc>,
     emoji: Option<PxScaleFont<FontArc>>,
     px_scale: ab_glyph::PxScale,
+    /// The file the primary face came from, whose bold and italic siblings
+    /// styled text uses.
+    path: Option<PathBuf>,
+    /// The bold, italic and bold italic faces, looked for the first time
+    /// styled text needs them.
+    styled: OnceLock<[Option<FontArc>; 3]>,
+}
+
+/// How a [`TextRun`] is drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunStyle {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    /// The run's own color, instead of the renderer's.
+    pub color: Option<Rgba>,
+}
+
+/// A byte range of the text drawn in a style of its own; see
+/// [`TextRenderer::with_runs`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextRun {
+    pub range: Range<usize>,
+    pub style: RunStyle,
+}
+
+/// Which part of a line too long for the max width an ellipsis replaces;
+/// see [`TextRenderer::with_ellipsize`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EllipsizeMode {
+    #[default]
+    End,
+    Middle,
+    Start,
+}
+
+/// How lines sit within the max width; see [`TextRenderer::with_align`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
 }
 
 const BASE_FONT_SIZE: f32 = 15.0;
@@ -44,7 +89,7 @@ fn discover_system_fonts() -> Vec<SystemFontEntry> {
         PathBuf::from("/usr/local/share/fonts"),
     ];
 
-    if let Some(home) = dirs::home_dir() {
+    if let Some(home) = crate::xdg::home_dir() {
         font_dirs.push(home.join(".fonts"));
         font_dirs.push(home.join(".local/share/fonts"));
         // NixOS user profile
@@ -136,7 +181,8 @@ fn parse_fontconfig_file(path: &Path, dirs: &mut Vec<PathBuf>) {
             let dir = after[..end].trim();
             // Expand ~ prefix
             let expanded = if let Some(stripped) = dir.strip_prefix('~') {
-                dirs::home_dir().map(|h| h.join(stripped.strip_prefix('/').unwrap_or(stripped)))
+                crate::xdg::home_dir()
+                    .map(|h| h.join(stripped.strip_prefix('/').unwrap_or(stripped)))
             } else {
                 Some(PathBuf::from(dir))
             };
@@ -212,16 +258,7 @@ fn font_priority(path: &Path) -> u8 {
     }
 
     // Deprioritize bold/italic/mono variants
-    let is_variant = name.contains("bold")
-        || name.contains("italic")
-        || name.contains("oblique")
-        || name.contains("mono")
-        || name.contains("condensed")
-        || name.contains("light")
-        || name.contains("thin")
-        || name.contains("black")
-        || name.contains("semibold")
-        || name.contains("extrabold");
+    let is_variant = name.contains("mono") || is_styled(&name);
 
     // Prefer common sans-serif fonts
     let base = if name == "notosans" || name == "notosans-regular" {
@@ -245,6 +282,126 @@ fn font_priority(path: &Path) -> u8 {
     if is_variant { base + 50 } else { base }
 }
 
+/// Whether a lowercased font file name is for a bold, italic or otherwise
+/// styled face rather than the regular one.
+fn is_styled(name: &str) -> bool {
+    [
+        "bold",
+        "italic",
+        "oblique",
+        "condensed",
+        "light",
+        "thin",
+        "black",
+        "semibold",
+        "extrabold",
+    ]
+    .iter()
+    .any(|style| name.contains(style))
+}
+
+/// A font file name with case and everything but letters and digits
+/// dropped, for comparing names written differently.
+fn squash_name(name: &str) -> String {
+    name.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+/// Finds the bold, italic and bold italic faces of the font in `path`, as
+/// files next to it in the system font list named like it with the style
+/// in place of "Regular".
+fn load_styled_faces(path: Option<&Path>) -> [Option<FontArc>; 3] {
+    let Some(stem) = path
+        .and_then(Path::file_stem)
+        .and_then(|stem| stem.to_str())
+    else {
+        return [None, None, None];
+    };
+    let name = squash_name(stem);
+    let family = ["regular", "book", "roman"]
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix))
+        .unwrap_or(&name);
+    let fonts = SYSTEM_FONTS.get_or_init(discover_system_fonts);
+    let find = |styles: &[&str]| {
+        fonts
+            .iter()
+            .find(|entry| {
+                entry
+                    .path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .map(squash_name)
+                    .is_some_and(|other| {
+                        other
+                            .strip_prefix(family)
+                            .is_some_and(|style| styles.contains(&style))
+                    })
+            })
+            .and_then(|entry| std::fs::read(&entry.path).ok())
+            .and_then(|data| FontArc::try_from_vec(data).ok())
+    };
+    [
+        find(&["bold"]),
+        find(&["italic", "oblique"]),
+        find(&["bolditalic", "boldoblique"]),
+    ]
+}
+
+/// Picks the file for font `family` from `paths`, matching file names: the
+/// family with spaces, dashes and case ignored must start the name, and the
+/// regular face is preferred over bold, italic and the rest. Without one, any
+/// font with "mono" in its name will do, for fixed-width text.
+pub(crate) fn find_font_file<'a>(family: &str, paths: &'a [PathBuf]) -> Option<&'a Path> {
+    let family = squash_name(family);
+    let names: Vec<(&Path, String)> = paths
+        .iter()
+        .filter_map(|path| Some((path.as_path(), squash_name(path.file_stem()?.to_str()?))))
+        .collect();
+
+    // Regular, then unstyled, then anything, and shorter names first among
+    // those, so "DejaVu Sans" is DejaVuSans.ttf rather than DejaVuSansMono.ttf
+    let rank = |rest: &str| {
+        let class = if matches!(rest, "" | "regular" | "book" | "roman") {
+            0
+        } else if is_styled(rest) {
+            2
+        } else {
+            1
+        };
+        (class, rest.len())
+    };
+    let named = names
+        .iter()
+        .filter(|_| !family.is_empty())
+        .filter_map(|(path, name)| Some((*path, rank(name.strip_prefix(family.as_str())?))))
+        .min_by_key(|&(_, rank)| rank)
+        .map(|(path, _)| path);
+    named.or_else(|| {
+        names
+            .iter()
+            .filter(|(_, name)| name.contains("mono"))
+            .min_by_key(|(_, name)| (is_styled(name), name.len()))
+            .map(|&(path, _)| path)
+    })
+}
+
+/// Splits a font description such as "JetBrains Mono 12" into the family and
+/// the size in points, if it ends with one.
+pub(crate) fn parse_font_spec(spec: &str) -> (&str, Option<f32>) {
+    let spec = spec.trim();
+    if let Some((family, size)) = spec.rsplit_once(char::is_whitespace)
+        && let Ok(size) = size.parse::<f32>()
+        && size > 0.0
+        && size.is_finite()
+    {
+        return (family.trim_end(), Some(size));
+    }
+    (spec, None)
+}
+
 fn ensure_fallback_cache() {
     let fonts = SYSTEM_FONTS.get_or_init(discover_system_fonts);
     FALLBACK_CACHE.get_or_init(|| {
@@ -308,17 +465,257 @@ fn find_fallback_for_char(c: char) -> Option<FontArc> {
 struct PlacedGlyph {
     glyph: Glyph,
     fallback: Option<FontArc>,
+    /// The color of the run the glyph is in, if it has its own.
+    color: Option<Rgba>,
+    /// Whether to thicken it into a bold the font has no face for.
+    embolden: bool,
+    /// Byte offset of the source character in the text.
+    byte: usize,
+    /// Bytes of text the glyph stands for: its character, or everything an
+    /// ellipsis hides.
+    len: usize,
+    advance: f32,
+}
+
+/// A source line (between newlines) as placed by [`TextRenderer::layout`].
+struct HardLine {
+    range: Range<usize>,
+    /// Baseline of the first visual line.
+    y: f32,
+    glyphs: Range<usize>,
+}
+
+struct Layout {
+    glyphs: Vec<PlacedGlyph>,
+    /// Advance width of trailing whitespace not represented by glyphs.
+    trailing_space: f32,
+    lines: Vec<HardLine>,
+    /// The width lines were aligned in, if they were.
+    align_width: Option<f32>,
+    /// How far alignment moved each visual line, in order, and where it
+    /// puts lines with nothing drawn.
+    row_shifts: Vec<f32>,
+    blank_shift: f32,
+}
+
+impl Layout {
+    /// Bounds of the rendered `glyphs`, widened to span the whole width the
+    /// lines were aligned in.
+    fn bounds(&self, glyphs: &[RenderedGlyph]) -> Rect {
+        let mut bounds = glyph_bounds(glyphs);
+        if let Some(width) = self.align_width {
+            bounds.min.x = bounds.min.x.min(0.0);
+            bounds.max.x = bounds.max.x.max(width);
+        }
+        bounds
+    }
+}
+
+/// Character positions of laid-out text, for mapping pointer coordinates to
+/// byte offsets and back. Coordinates are relative to the canvas returned by
+/// [`TextRenderer::finish`].
+#[derive(Debug, Clone, Default)]
+pub struct TextLayout {
+    lines: Vec<LayoutLine>,
+    line_height: f32,
+}
+
+/// One visual line of a [`TextLayout`].
+#[derive(Debug, Clone)]
+struct LayoutLine {
+    top: f32,
+    range: Range<usize>,
+    /// Character boundaries as (byte offset, x), sorted by offset.
+    stops: Vec<(usize, f32)>,
+}
+
+impl LayoutLine {
+    fn x_of(&self, offset: usize) -> f32 {
+        self.stops
+            .iter()
+            .take_while(|&&(byte, _)| byte <= offset)
+            .last()
+            .map_or(0.0, |&(_, x)| x)
+    }
+}
+
+impl TextLayout {
+    pub fn line_height(&self) -> f32 {
+        self.line_height
+    }
+
+    /// Returns the byte offset of the character boundary closest to (x, y).
+    /// Points above or below the text snap to the first or last line.
+    pub fn offset_at(&self, x: f32, y: f32) -> usize {
+        let Some(line) = self
+            .lines
+            .iter()
+            .find(|line| y < line.top + self.line_height)
+            .or(self.lines.last())
+        else {
+            return 0;
+        };
+        line.stops
+            .iter()
+            .min_by(|a, b| (a.1 - x).abs().total_cmp(&(b.1 - x).abs()))
+            .map_or(line.range.start, |&(byte, _)| byte)
+    }
+
+    /// Returns the (x, top) of the boundary at `offset`. An offset at a soft
+    /// wrap belongs to the start of the following line.
+    pub fn position_of(&self, offset: usize) -> (f32, f32) {
+        let line = self
+            .lines
+            .iter()
+            .find(|line| offset < line.range.end)
+            .or(self.lines.last());
+        line.map_or((0.0, 0.0), |line| (line.x_of(offset), line.top))
+    }
+
+    /// Returns rectangles (x, y, w, h) covering the text in `range`, one per
+    /// visual line it touches.
+    pub fn range_rects(&self, range: Range<usize>) -> Vec<(f32, f32, f32, f32)> {
+        if range.is_empty() {
+            return Vec::new();
+        }
+        let mut rects = Vec::new();
+        for line in &self.lines {
+            if range.start > line.range.end || range.end <= line.range.start {
+                continue;
+            }
+            let x0 = line.x_of(range.start.max(line.range.start));
+            let mut x1 = line.x_of(range.end.min(line.range.end));
+            // Show that the selection carries on past the end of the line
+            if range.end > line.range.end {
+                x1 += self.line_height / 4.0;
+            }
+            if x1 > x0 {
+                rects.push((x0, line.top, x1 - x0, self.line_height));
+            }
+        }
+        rects
+    }
+}
+
+/// A glyph rasterized once, however many times the text uses it at the same
+/// offset within a pixel.
+struct GlyphCoverage {
+    /// The bitmap's pixel bounds, relative to the whole pixel the glyph is
+    /// placed in.
+    bounds: Rect,
+    width: usize,
+    /// Coverage from 0 to 255, row by row.
+    data: Vec<u8>,
+}
+
+impl GlyphCoverage {
+    fn new(glyph: &OutlinedGlyph) -> Self {
+        let bounds = glyph.px_bounds();
+        let width = bounds.width() as usize;
+        let height = bounds.height() as usize;
+        let mut data = vec![0; width * height];
+        glyph.draw(|x, y, c| {
+            let (x, y) = (x as usize, y as usize);
+            if x < width && y < height {
+                data[y * width + x] = ((c * 255.0).round() as u32).min(255) as u8;
+            }
+        });
+        Self {
+            bounds,
+            width,
+            data,
+        }
+    }
+
+    /// Blends the coverage in `color` over `pixmap`, its top-left at (x, y).
+    fn blend(&self, pixmap: &mut Pixmap, x: i32, y: i32, color: Rgba) {
+        let (width, height) = (pixmap.width() as i32, pixmap.height() as i32);
+        // The columns that land inside the pixmap
+        let first = (-x).max(0) as usize;
+        let last = (width - x).clamp(0, self.width as i32) as usize;
+        if first >= last {
+            return;
+        }
+        let color_lanes = spread(&[color.r, color.g, color.b, 255]);
+        let data = pixmap.data_mut();
+        for (row, line) in self.data.chunks_exact(self.width).enumerate() {
+            let py = y + row as i32;
+            if py < 0 || py >= height {
+                continue;
+            }
+            let start = ((py * width + x) as usize + first) * 4;
+            let dst = &mut data[start..start + (last - first) * 4];
+            for (pixel, &c) in dst.chunks_exact_mut(4).zip(&line[first..last]) {
+                let a = match (c, color.a) {
+                    (0, _) => continue,
+                    (255, 255) => {
+                        pixel.copy_from_slice(&[color.r, color.g, color.b, 255]);
+                        continue;
+                    }
+                    (c, 255) => c as u64,
+                    (c, alpha) => div255(c as u32 * alpha as u32) as u64,
+                };
+                // Premultiplied source-over, every channel at once
+                let out = div255_lanes(color_lanes * a)
+                    + div255_lanes(spread(&[pixel[0], pixel[1], pixel[2], pixel[3]]) * (255 - a));
+                pixel.copy_from_slice(&pack(out).to_le_bytes());
+            }
+        }
+    }
+}
+
+/// `x / 255` for the product of two bytes, without a division, which builds
+/// optimized for size keep.
+fn div255(x: u32) -> u32 {
+    (x + 1 + (x >> 8)) >> 8
+}
+
+/// [`div255`] of each 16-bit lane.
+fn div255_lanes(x: u64) -> u64 {
+    const LOW_BYTES: u64 = 0x00ff_00ff_00ff_00ff;
+    ((x + 0x0001_0001_0001_0001 + ((x >> 8) & LOW_BYTES)) >> 8) & LOW_BYTES
+}
+
+/// The four bytes [`spread`] put in lanes, back in a pixel.
+fn pack(lanes: u64) -> u32 {
+    let x = (lanes | lanes >> 8) & 0x0000_ffff_0000_ffff;
+    (x | x >> 16) as u32
 }
 
 enum RenderedGlyph {
-    Outlined(OutlinedGlyph),
-    Raster { pixmap: Pixmap, x: f32, y: f32 },
+    Outlined {
+        coverage: Rc<GlyphCoverage>,
+        /// The whole pixel the glyph is placed in.
+        position: ab_glyph::Point,
+        color: Option<Rgba>,
+        /// How far right a second strike goes to make a synthetic bold, or
+        /// 0 for none.
+        embolden: f32,
+    },
+    Raster {
+        pixmap: Pixmap,
+        x: f32,
+        y: f32,
+    },
 }
 
 impl RenderedGlyph {
     fn bounds(&self) -> Rect {
         match self {
-            Self::Outlined(g) => g.px_bounds(),
+            Self::Outlined {
+                coverage,
+                position,
+                embolden,
+                ..
+            } => {
+                Rect {
+                    min: coverage.bounds.min + *position,
+                    max: point(
+                        coverage.bounds.max.x + position.x + embolden,
+                        coverage.bounds.max.y + position.y,
+                    ),
+                }
+            }
             Self::Raster {
                 pixmap,
                 x,
@@ -336,30 +733,85 @@ impl RenderedGlyph {
 impl Font {
     /// Loads the font with the given scale factor for crisp rendering.
     pub fn load(scale: f32) -> Self {
-        let px_scale = ab_glyph::PxScale::from(BASE_FONT_SIZE * scale);
-        let text_font = Self::load_text_font();
-        let emoji_font = Self::load_emoji_font();
-        Self {
-            primary: text_font.into_scaled(px_scale),
-            emoji: emoji_font.map(|f| f.into_scaled(px_scale)),
-            px_scale,
-        }
+        let (text_font, path) = Self::load_text_font();
+        Self::with_face(text_font, path, BASE_FONT_SIZE * scale)
     }
 
     /// Loads the font with a specific size in pixels (already scaled).
     pub fn load_with_size(size: f32) -> Self {
+        let (text_font, path) = Self::load_text_font();
+        Self::with_face(text_font, path, size)
+    }
+
+    /// Loads font `family` from the system fonts (see [`find_font_file`]),
+    /// `size` pixels high before scaling, or the usual size. Falls back to
+    /// the default font when nothing matches.
+    pub fn load_named(family: &str, size: Option<f32>, scale: f32) -> Self {
+        let px_scale = ab_glyph::PxScale::from(size.unwrap_or(BASE_FONT_SIZE) * scale);
+        let system_fonts = SYSTEM_FONTS.get_or_init(discover_system_fonts);
+        let paths: Vec<PathBuf> = system_fonts
+            .iter()
+            .filter(|entry| entry.priority < 255)
+            .map(|entry| entry.path.clone())
+            .collect();
+        let (text_font, path) = find_font_file(family, &paths)
+            .and_then(|path| {
+                let data = std::fs::read(path).ok()?;
+                Some((FontArc::try_from_vec(data).ok()?, Some(path.to_path_buf())))
+            })
+            .unwrap_or_else(Self::load_text_font);
+        Self::with_face(text_font, path, px_scale.y)
+    }
+
+    /// The font with `face` as its primary face, loaded from `path`, at
+    /// `size` pixels.
+    fn with_face(face: FontArc, path: Option<PathBuf>, size: f32) -> Self {
         let px_scale = ab_glyph::PxScale::from(size);
-        let text_font = Self::load_text_font();
         let emoji_font = Self::load_emoji_font();
         Self {
-            primary: text_font.into_scaled(px_scale),
+            primary: face.into_scaled(px_scale),
             emoji: emoji_font.map(|f| f.into_scaled(px_scale)),
             px_scale,
+            path,
+            styled: OnceLock::new(),
+        }
+    }
+
+    /// The face for bold and/or italic text, or `None` for the primary one,
+    /// and whether it still has to be made bold by drawing it thicker.
+    fn styled_face(&self, bold: bool, italic: bool) -> (Option<&FontArc>, bool) {
+        if !bold && !italic {
+            return (None, false);
+        }
+        let [bold_face, italic_face, bold_italic_face] = self
+            .styled
+            .get_or_init(|| load_styled_faces(self.path.as_deref()));
+        match (bold, italic) {
+            (true, true) => {
+                match (bold_italic_face, italic_face, bold_face) {
+                    (Some(face), _, _) => (Some(face), false),
+                    (None, Some(face), _) => (Some(face), true),
+                    (None, None, Some(face)) => (Some(face), false),
+                    (None, None, None) => (None, true),
+                }
+            }
+            (true, false) => (bold_face.as_ref(), bold_face.is_none()),
+            _ => (italic_face.as_ref(), false),
         }
     }
 
-    /// Loads the best available text font (not emoji).
-    fn load_text_font() -> FontArc {
+    /// How far the second strike of a synthetic bold is offset.
+    fn embolden_offset(&self) -> f32 {
+        (self.size() / 24.0).round().max(1.0)
+    }
+
+    /// The font size in pixels (already scaled).
+    pub fn size(&self) -> f32 {
+        self.px_scale.y
+    }
+
+    /// Loads the best available text font (not emoji), and where from.
+    fn load_text_font() -> (FontArc, Option<PathBuf>) {
         let system_fonts = SYSTEM_FONTS.get_or_init(discover_system_fonts);
 
         for entry in system_fonts {
@@ -377,12 +829,12 @@ impl Font {
 
             if let Ok(data) = std::fs::read(&entry.path) {
                 if let Ok(font) = FontArc::try_from_vec(data) {
-                    return font;
+                    return (font, Some(entry.path.clone()));
                 }
             }
         }
 
-        FontArc::try_from_slice(FALLBACK_FONT).unwrap()
+        (FontArc::try_from_slice(FALLBACK_FONT).unwrap(), None)
     }
 
     /// Loads an emoji font if available.
@@ -419,6 +871,10 @@ impl Font {
             text,
             color: rgb(255, 255, 255),
             max_width: f32::MAX,
+            ellipsize: None,
+            align: None,
+            underline: None,
+            runs: &[],
         }
     }
 }
@@ -428,9 +884,18 @@ pub struct TextRenderer<'a> {
     text: &'a str,
     color: Rgba,
     max_width: f32,
+    ellipsize: Option<EllipsizeMode>,
+    align: Option<Align>,
+    underline: Option<Range<usize>>,
+    runs: &'a [TextRun],
 }
 
 impl<'a> TextRenderer<'a> {
+    /// The text being rendered.
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
     pub fn with_color(self, color: Rgba) -> Self {
         Self {
             color,
@@ -445,105 +910,164 @@ impl<'a> TextRenderer<'a> {
         }
     }
 
+    /// Instead of wrapping at the max width, cut lines that overflow it short,
+    /// showing "…" at the end, middle or start in place of what's hidden.
+    /// Characters combined with the ones before them are hidden together.
+    pub fn with_ellipsize(self, mode: EllipsizeMode) -> Self {
+        Self {
+            ellipsize: Some(mode),
+            ..self
+        }
+    }
+
+    /// Place each line at the left, center or right of the max width (or of
+    /// the widest line without one). The rendered text then always spans
+    /// that whole width, so it can be positioned like a box.
+    pub fn with_align(self, align: Align) -> Self {
+        Self {
+            align: Some(align),
+            ..self
+        }
+    }
+
+    /// Underline the characters in this byte range, e.g. a mnemonic.
+    pub fn with_underline(self, range: Range<usize>) -> Self {
+        Self {
+            underline: Some(range),
+            ..self
+        }
+    }
+
+    /// Draw these byte ranges bold, italic, underlined or in colors of their
+    /// own. Without a bold face among the system fonts, bold is drawn
+    /// thicker instead.
+    pub fn with_runs(self, runs: &'a [TextRun]) -> Self {
+        Self {
+            runs,
+            ..self
+        }
+    }
+
+    /// The style of the run holding the character at `byte`.
+    fn style_at(&self, byte: usize) -> RunStyle {
+        self.runs
+            .iter()
+            .find(|run| run.range.contains(&byte))
+            .map_or_else(RunStyle::default, |run| run.style)
+    }
+
     /// Renders the text and returns a Canvas containing it.
     pub fn finish(self) -> Canvas {
-        let (placed, trailing_space) = self.layout();
-        let glyphs = self.resolve_glyphs(placed);
+        let layout = self.layout();
+        let glyphs = self.resolve_glyphs(&layout.glyphs);
 
         if glyphs.is_empty() {
             // Text is only whitespace - size canvas from trailing space advance
-            let w = (trailing_space.ceil() as u32 + 2).max(1);
+            let space = layout.align_width.unwrap_or(layout.trailing_space);
+            let w = (space.ceil() as u32 + 2).max(1);
             let h = (self.font.primary.height().ceil() as u32 + 2).max(1);
             return Canvas::new(w, h);
         }
 
-        let bounds = glyphs
+        let bounds = layout.bounds(&glyphs);
+        let Layout {
+            glyphs: placed,
+            trailing_space,
+            ..
+        } = layout;
+
+        // Offset to account for bounds.min (which can be negative for some glyphs)
+        let base_x = -bounds.min.x.floor() as i32 + 1;
+        let base_y = -bounds.min.y.floor() as i32 + 1;
+
+        // Underlines as (x, y, width, color), a little below the baseline:
+        // the mnemonic's and underlined runs', a piece per line they're on
+        let thickness = (self.font.size() / 14.0).round().max(1.0);
+        let underlined = self
+            .underline
             .iter()
-            .map(|g| g.bounds())
-            .reduce(|mut sum, next| {
-                sum.min.x = f32::min(sum.min.x, next.min.x);
-                sum.min.y = f32::min(sum.min.y, next.min.y);
-                sum.max.x = f32::max(sum.max.x, next.max.x);
-                sum.max.y = f32::max(sum.max.y, next.max.y);
-                sum
-            })
-            .unwrap_or_default();
+            .map(|range| (range.clone(), self.color))
+            .chain(
+                self.runs
+                    .iter()
+                    .filter(|run| run.style.underline)
+                    .map(|run| (run.range.clone(), run.style.color.unwrap_or(self.color))),
+            );
+        let mut underlines = Vec::new();
+        for (range, color) in underlined {
+            let run: Vec<&PlacedGlyph> =
+                placed.iter().filter(|g| range.contains(&g.byte)).collect();
+            for line in
+                run.chunk_by(|a, b| a.glyph.position.y.round() == b.glyph.position.y.round())
+            {
+                let (first, last) = (line[0], line[line.len() - 1]);
+                underlines.push((
+                    base_x as f32 + first.glyph.position.x,
+                    base_y as f32 + first.glyph.position.y.round() + thickness,
+                    last.glyph.position.x + last.advance - first.glyph.position.x,
+                    color,
+                ));
+            }
+        }
 
         // Add trailing space width for whitespace after the last visible glyph
         let width = (bounds.width().ceil() as u32 + trailing_space.ceil() as u32 + 2).max(1);
-        let height = (bounds.height().ceil() as u32 + 2).max(1);
+        let height = underlines
+            .iter()
+            .map(|&(_, y, _, _)| (y + thickness).ceil() as u32 + 1)
+            .fold(bounds.height().ceil() as u32 + 2, u32::max);
 
         let mut pixmap = Pixmap::new(width, height).unwrap();
 
-        // Offset to account for bounds.min (which can be negative for some glyphs)
-        let base_x = -bounds.min.x.floor() as i32 + 1;
-        let base_y = -bounds.min.y.floor() as i32 + 1;
+        // Each glyph is blended over the ones before it, in its own color
+        for g in &glyphs {
+            let RenderedGlyph::Outlined {
+                coverage,
+                position,
+                color,
+                embolden,
+            } = g
+            else {
+                continue;
+            };
+            let color = color.unwrap_or(self.color);
+            let gy = (position.y + coverage.bounds.min.y) as i32 + base_y;
+            // A synthetic bold is the glyph struck twice, side by side
+            let strikes = if *embolden > 0.0 { 2 } else { 1 };
+            for strike in 0..strikes {
+                let gx = (position.x + coverage.bounds.min.x) as i32
+                    + base_x
+                    + strike * *embolden as i32;
+                coverage.blend(&mut pixmap, gx, gy, color);
+            }
+        }
 
+        for (x, y, w, color) in underlines {
+            if let Some(rect) = tiny_skia::Rect::from_xywh(x, y, w, thickness) {
+                let mut paint = Paint::default();
+                paint.set_color(color.into());
+                pixmap.fill_rect(rect, &paint, tiny_skia::Transform::identity(), None);
+            }
+        }
+
+        // Color bitmap glyphs (emoji) are composited on top
         for g in &glyphs {
-            match g {
-                RenderedGlyph::Outlined(og) => {
-                    let glyph_bounds = og.px_bounds();
-                    let gx = glyph_bounds.min.x.floor() as i32 + base_x;
-                    let gy = glyph_bounds.min.y.floor() as i32 + base_y;
-
-                    let pixels = pixmap.pixels_mut();
-                    og.draw(|x, y, c| {
-                        let px = gx + x as i32;
-                        let py = gy + y as i32;
-
-                        if px >= 0 && py >= 0 && (px as u32) < width && (py as u32) < height {
-                            let idx = (py as u32 * width + px as u32) as usize;
-                            if let Some(pix) = pixels.get_mut(idx) {
-                                // Premultiplied alpha blending
-                                let a = (c * 255.0).round() as u8;
-                                if a > 0 {
-                                    let r = (self.color.r as u32 * a as u32 / 255) as u8;
-                                    let g = (self.color.g as u32 * a as u32 / 255) as u8;
-                                    let b = (self.color.b as u32 * a as u32 / 255) as u8;
-
-                                    let existing = *pix;
-                                    if existing.alpha() == 0 {
-                                        *pix =
-                                            tiny_skia::PremultipliedColorU8::from_rgba(r, g, b, a)
-                                                .unwrap();
-                                    } else {
-                                        let ea = existing.alpha() as u32;
-                                        let er = existing.red() as u32;
-                                        let eg = existing.green() as u32;
-                                        let eb = existing.blue() as u32;
-
-                                        let inv_a = 255 - a as u32;
-                                        let out_a = (a as u32 + ea * inv_a / 255).min(255) as u8;
-                                        let out_r = (r as u32 + er * inv_a / 255).min(255) as u8;
-                                        let out_g = (g as u32 + eg * inv_a / 255).min(255) as u8;
-                                        let out_b = (b as u32 + eb * inv_a / 255).min(255) as u8;
-
-                                        *pix = tiny_skia::PremultipliedColorU8::from_rgba(
-                                            out_r, out_g, out_b, out_a,
-                                        )
-                                        .unwrap();
-                                    }
-                                }
-                            }
-                        }
-                    });
-                }
-                RenderedGlyph::Raster {
-                    pixmap: src,
-                    x,
-                    y,
-                } => {
-                    let dx = x.round() as i32 + base_x;
-                    let dy = y.round() as i32 + base_y;
-                    pixmap.draw_pixmap(
-                        dx,
-                        dy,
-                        src.as_ref(),
-                        &tiny_skia::PixmapPaint::default(),
-                        tiny_skia::Transform::identity(),
-                        None,
-                    );
-                }
+            if let RenderedGlyph::Raster {
+                pixmap: src,
+                x,
+                y,
+            } = g
+            {
+                let dx = x.round() as i32 + base_x;
+                let dy = y.round() as i32 + base_y;
+                pixmap.draw_pixmap(
+                    dx,
+                    dy,
+                    src.as_ref(),
+                    &tiny_skia::PixmapPaint::default(),
+                    tiny_skia::Transform::identity(),
+                    None,
+                );
             }
         }
 
@@ -554,41 +1078,125 @@ impl<'a> TextRenderer<'a> {
 
     /// Computes the size of the rendered text without actually rendering it.
     pub fn measure(&self) -> (f32, f32) {
-        let (placed, trailing_space) = self.layout();
-        let glyphs = self.resolve_glyphs(placed);
+        let layout = self.layout();
+        let bounds = layout.bounds(&self.resolve_glyphs(&layout.glyphs));
+        (bounds.width() + layout.trailing_space, bounds.height())
+    }
 
-        let bounds = glyphs
-            .iter()
-            .map(|g| g.bounds())
-            .reduce(|mut sum, next| {
-                sum.min.x = f32::min(sum.min.x, next.min.x);
-                sum.min.y = f32::min(sum.min.y, next.min.y);
-                sum.max.x = f32::max(sum.max.x, next.max.x);
-                sum.max.y = f32::max(sum.max.y, next.max.y);
-                sum
-            })
-            .unwrap_or_default();
+    /// Computes character positions for hit-testing the canvas that
+    /// [`finish`](Self::finish) would produce.
+    pub fn text_layout(&self) -> TextLayout {
+        let layout = self.layout();
+        let bounds = layout.bounds(&self.resolve_glyphs(&layout.glyphs));
+        // Same offset as finish() applies when drawing
+        let origin_x = -bounds.min.x.floor() + 1.0;
+        let origin_y = -bounds.min.y.floor() + 1.0;
+        let ascent = self.font.primary.ascent();
+
+        let mut lines = Vec::new();
+        let mut row_shifts = layout.row_shifts.iter();
+        for hard in &layout.lines {
+            let glyphs = &layout.glyphs[hard.glyphs.clone()];
+            // Wrapping moves glyphs down, so each visual line is a run of equal
+            // y (rounded, as wrapped glyphs keep the unrounded value)
+            let rows: Vec<&[PlacedGlyph]> = glyphs
+                .chunk_by(|a, b| a.glyph.position.y.round() == b.glyph.position.y.round())
+                .collect();
+            if rows.is_empty() {
+                lines.push(LayoutLine {
+                    top: origin_y + hard.y - ascent,
+                    range: hard.range.clone(),
+                    stops: vec![
+                        (hard.range.start, origin_x + layout.blank_shift),
+                        (hard.range.end, origin_x + layout.blank_shift),
+                    ],
+                });
+                continue;
+            }
+            for (i, row) in rows.iter().enumerate() {
+                let shift = row_shifts.next().copied().unwrap_or(0.0);
+                let start = if i == 0 {
+                    hard.range.start
+                } else {
+                    row[0].byte
+                };
+                let end = rows.get(i + 1).map_or(hard.range.end, |next| next[0].byte);
+                let mut stops = vec![(start, origin_x + shift)];
+                let mut push = |byte: usize, x: f32| {
+                    if stops.last().is_none_or(|&(last, _)| byte > last) {
+                        stops.push((byte, x));
+                    }
+                };
+                for g in row.iter() {
+                    let x = origin_x + g.glyph.position.x;
+                    push(g.byte, x);
+                    push(g.byte + g.len, x + g.advance);
+                }
+                let last_x = stops.last().map_or(origin_x, |&(_, x)| x);
+                if stops.last().is_some_and(|&(last, _)| end > last) {
+                    stops.push((end, last_x));
+                }
+                lines.push(LayoutLine {
+                    top: origin_y + row[0].glyph.position.y - ascent,
+                    range: start..end,
+                    stops,
+                });
+            }
+        }
 
-        (bounds.width() + trailing_space, bounds.height())
+        TextLayout {
+            lines,
+            line_height: self.font.primary.height() + self.font.primary.line_gap(),
+        }
     }
 
     /// Converts placed glyphs into rendered form (outlined vectors or raster bitmaps).
-    fn resolve_glyphs(&self, placed: Vec<PlacedGlyph>) -> Vec<RenderedGlyph> {
+    fn resolve_glyphs(&self, placed: &[PlacedGlyph]) -> Vec<RenderedGlyph> {
         let ppem = self.font.px_scale.y as u16;
+        // Glyphs are outlined within the first pixel and moved to each place
+        // they're used, so ones at the same offset within a pixel share their
+        // coverage. Fallback faces can't be told apart cheaply, so only the
+        // primary face's glyphs are shared.
+        let mut primary: HashMap<(GlyphId, u32, u32), Option<Rc<GlyphCoverage>>> = HashMap::new();
 
         placed
-            .into_iter()
+            .iter()
             .filter_map(|pg| {
                 // Try vector outline first (normal text glyphs)
-                let outlined = if let Some(ref fb) = pg.fallback {
+                let position = pg.glyph.position;
+                let pixel = point(position.x.floor(), position.y.floor());
+                let offset = position - pixel;
+                let at_origin = Glyph {
+                    position: offset,
+                    ..pg.glyph.clone()
+                };
+                let coverage = if let Some(ref fb) = pg.fallback {
                     fb.as_scaled(self.font.px_scale)
-                        .outline_glyph(pg.glyph.clone())
+                        .outline_glyph(at_origin)
+                        .map(|og| Rc::new(GlyphCoverage::new(&og)))
                 } else {
-                    self.font.primary.outline_glyph(pg.glyph.clone())
+                    primary
+                        .entry((pg.glyph.id, offset.x.to_bits(), offset.y.to_bits()))
+                        .or_insert_with(|| {
+                            self.font
+                                .primary
+                                .outline_glyph(at_origin)
+                                .map(|og| Rc::new(GlyphCoverage::new(&og)))
+                        })
+                        .clone()
                 };
 
-                if let Some(og) = outlined {
-                    return Some(RenderedGlyph::Outlined(og));
+                if let Some(coverage) = coverage {
+                    return Some(RenderedGlyph::Outlined {
+                        coverage,
+                        position: pixel,
+                        color: pg.color,
+                        embolden: if pg.embolden {
+                            self.font.embolden_offset()
+                        } else {
+                            0.0
+                        },
+                    });
                 }
 
                 // Try raster image (colored emoji / bitmap glyphs)
@@ -620,11 +1228,10 @@ impl<'a> TextRenderer<'a> {
     }
 
     /// Performs text layout with soft wrapping and per-glyph font fallback.
-    /// Returns (glyphs, trailing_space_width) where trailing_space_width is the
-    /// advance width of any trailing whitespace not represented by glyphs.
-    fn layout(&self) -> (Vec<PlacedGlyph>, f32) {
+    fn layout(&self) -> Layout {
         let mut glyphs: Vec<PlacedGlyph> = Vec::new();
         let mut trailing_space: f32 = 0.0;
+        let mut lines = Vec::new();
 
         let mut y: f32 = 0.0;
         for line in self.text.lines() {
@@ -632,10 +1239,22 @@ impl<'a> TextRenderer<'a> {
             let mut last_softbreak: Option<usize> = None;
             let mut last_primary_glyph: Option<GlyphId> = None;
             let mut line_start: usize = glyphs.len();
-
-            for c in line.chars() {
+            // `lines()` yields subslices, so the pointer difference is the offset
+            let line_offset = line.as_ptr() as usize - self.text.as_ptr() as usize;
+            let first_glyph = glyphs.len();
+            let line_y = y;
+
+            for (i, c) in line.char_indices() {
+                let style = self.style_at(line_offset + i);
+                let (face, embolden) = self.font.styled_face(style.bold, style.italic);
+                let styled_glyph_id = face
+                    .map(|face| (face.glyph_id(c), face))
+                    .filter(|(id, _)| id.0 != 0);
                 let primary_glyph_id = self.font.primary.font.glyph_id(c);
-                let (glyph_id, fallback) = if primary_glyph_id.0 != 0 {
+                let (glyph_id, fallback) = if let Some((id, face)) = styled_glyph_id {
+                    // The bold or italic face has it
+                    (id, Some(face.clone()))
+                } else if primary_glyph_id.0 != 0 {
                     // Primary text font has it
                     (primary_glyph_id, None)
                 } else if let Some(ref emoji_font) = self.font.emoji {
@@ -676,12 +1295,15 @@ impl<'a> TextRenderer<'a> {
                 };
 
                 // Advance using the correct font
-                let advance = if let Some(ref fb) = fallback {
+                let mut advance = if let Some(ref fb) = fallback {
                     let scaled: PxScaleFont<&FontArc> = fb.as_scaled(self.font.px_scale);
                     scaled.h_advance(glyph_id)
                 } else {
                     self.font.primary.h_advance(glyph_id)
                 };
+                if embolden {
+                    advance += self.font.embolden_offset();
+                }
 
                 // Track last primary glyph for kerning
                 if fallback.is_none() {
@@ -700,9 +1322,14 @@ impl<'a> TextRenderer<'a> {
                     glyphs.push(PlacedGlyph {
                         glyph,
                         fallback,
+                        color: style.color,
+                        embolden,
+                        byte: line_offset + i,
+                        len: c.len_utf8(),
+                        advance,
                     });
 
-                    if x > self.max_width {
+                    if x > self.max_width && self.ellipsize.is_none() {
                         if let Some(i) = last_softbreak {
                             y += self.font.primary.height() + self.font.primary.line_gap();
                             let x_diff = glyphs.get(i).map(|g| g.glyph.position.x).unwrap_or(0.0);
@@ -725,15 +1352,233 @@ impl<'a> TextRenderer<'a> {
                     }
                 }
             }
+            if let Some(mode) = self.ellipsize
+                && x > self.max_width
+            {
+                let range = line_offset..line_offset + line.len();
+                self.ellipsize_line(&mut glyphs, first_glyph, range, y, mode);
+                trailing_space = 0.0;
+            }
+            lines.push(HardLine {
+                range: line_offset..line_offset + line.len(),
+                y: line_y,
+                glyphs: first_glyph..glyphs.len(),
+            });
             y += self.font.primary.height() + self.font.primary.line_gap();
         }
 
-        (glyphs, trailing_space)
+        let mut row_shifts = Vec::new();
+        let mut blank_shift = 0.0;
+        let align_width = self.align.map(|align| {
+            // Wrapping moves glyphs down, so each visual line is a run of
+            // equal y
+            let mut rows: Vec<&mut [PlacedGlyph]> = glyphs
+                .chunk_by_mut(|a, b| a.glyph.position.y.round() == b.glyph.position.y.round())
+                .collect();
+            let row_width =
+                |row: &[PlacedGlyph]| row.last().map_or(0.0, |g| g.glyph.position.x + g.advance);
+            let width = if self.max_width < f32::MAX {
+                self.max_width
+            } else {
+                rows.iter().map(|row| row_width(row)).fold(0.0, f32::max)
+            };
+            let share = match align {
+                Align::Left => 0.0,
+                Align::Center => 0.5,
+                Align::Right => 1.0,
+            };
+            for row in &mut rows {
+                let shift = ((width - row_width(row)) * share).max(0.0).round();
+                for g in row.iter_mut() {
+                    g.glyph.position.x += shift;
+                }
+                row_shifts.push(shift);
+            }
+            blank_shift = (width * share).round();
+            width
+        });
+
+        Layout {
+            glyphs,
+            // The aligned width includes any trailing whitespace
+            trailing_space: if align_width.is_some() {
+                0.0
+            } else {
+                trailing_space
+            },
+            lines,
+            align_width,
+            row_shifts,
+            blank_shift,
+        }
     }
 }
 
-/// Area-averaging downscale for raster emoji bitmaps.
-fn scale_pixmap(src: &Pixmap, target_w: u32, target_h: u32) -> Pixmap {
+impl TextRenderer<'_> {
+    /// Replaces part of the line starting at `glyphs[first]`, which covers
+    /// `line` of the text, with an ellipsis, hiding as many glyphs as it
+    /// takes to fit the max width.
+    fn ellipsize_line(
+        &self,
+        glyphs: &mut Vec<PlacedGlyph>,
+        first: usize,
+        line: Range<usize>,
+        y: f32,
+        mode: EllipsizeMode,
+    ) {
+        let primary = &self.font.primary;
+        let ellipsis = primary.font.glyph_id(ELLIPSIS);
+        // Fonts without the character get three periods
+        let (id, count) = if ellipsis.0 != 0 {
+            (ellipsis, 1)
+        } else {
+            (primary.font.glyph_id('.'), 3)
+        };
+        let advance = primary.h_advance(id);
+        let width = advance * count as f32;
+
+        let end = glyphs.len();
+        let right = |g: &PlacedGlyph| g.glyph.position.x + g.advance;
+        let line_width = glyphs[first..].last().map_or(0.0, right);
+        if line_width <= self.max_width {
+            // Only trailing whitespace overflows
+            return;
+        }
+        let room = self.max_width - width;
+        // The glyphs kept before the ellipsis fit in `head`, and the ones
+        // after it in what's left
+        let head = match mode {
+            EllipsizeMode::End => room,
+            EllipsizeMode::Middle => room / 2.0,
+            EllipsizeMode::Start => 0.0,
+        };
+        let mut cut = match mode {
+            EllipsizeMode::Start => first,
+            _ => {
+                first
+                    + glyphs[first..]
+                        .iter()
+                        .take_while(|g| right(g) <= head)
+                        .count()
+            }
+        };
+        while cut > first && !starts_cluster(self.text, glyphs[cut].byte) {
+            cut -= 1;
+        }
+        let head_width = glyphs[first..cut].last().map_or(0.0, right);
+        let tail = room - head_width;
+        let mut resume = match mode {
+            EllipsizeMode::End => end,
+            _ => {
+                end - glyphs[cut..]
+                    .iter()
+                    .rev()
+                    .take_while(|g| line_width - g.glyph.position.x <= tail)
+                    .count()
+            }
+        };
+        while resume < end && !starts_cluster(self.text, glyphs[resume].byte) {
+            resume += 1;
+        }
+
+        let hidden_from = if cut == first {
+            line.start
+        } else {
+            glyphs[cut].byte
+        };
+        let hidden_to = glyphs.get(resume).map_or(line.end, |g| g.byte);
+        let mut x = head_width;
+        let tail: Vec<PlacedGlyph> = glyphs.drain(cut..).skip(resume - cut).collect();
+        for i in 0..count {
+            glyphs.push(PlacedGlyph {
+                glyph: Glyph {
+                    id,
+                    scale: self.font.px_scale,
+                    position: point(x.round(), y.round()),
+                },
+                fallback: None,
+                color: None,
+                embolden: false,
+                // The (first) ellipsis glyph stands for all hidden text, so
+                // selecting it selects that
+                byte: hidden_from,
+                len: if i == 0 { hidden_to - hidden_from } else { 0 },
+                advance,
+            });
+            x += advance;
+        }
+        let shift = x - tail.first().map_or(x, |g| g.glyph.position.x);
+        glyphs.extend(tail.into_iter().map(|mut g| {
+            g.glyph.position.x = (g.glyph.position.x + shift).round();
+            g
+        }));
+    }
+}
+
+/// Whether a grapheme cluster starts at `byte`, i.e. the character there
+/// isn't drawn as part of the one before it: a combining mark, variation
+/// selector, emoji modifier or tag, something joined on with a zero-width
+/// joiner, or the second of a pair of regional indicators (a flag).
+fn starts_cluster(text: &str, byte: usize) -> bool {
+    let (Some(c), Some(prev)) = (
+        text[byte..].chars().next(),
+        text[..byte].chars().next_back(),
+    ) else {
+        return true;
+    };
+    let extends = matches!(c,
+        '\u{300}'..='\u{36f}'
+        | '\u{483}'..='\u{489}'
+        | '\u{591}'..='\u{5bd}'
+        | '\u{610}'..='\u{61a}'
+        | '\u{64b}'..='\u{65f}'
+        | '\u{900}'..='\u{903}'
+        | '\u{93a}'..='\u{94f}'
+        | '\u{e31}'
+        | '\u{e34}'..='\u{e3a}'
+        | '\u{e47}'..='\u{e4e}'
+        | '\u{1160}'..='\u{11ff}'
+        | '\u{1ab0}'..='\u{1aff}'
+        | '\u{1dc0}'..='\u{1dff}'
+        | ZWJ
+        | '\u{20d0}'..='\u{20ff}'
+        | '\u{fe00}'..='\u{fe0f}'
+        | '\u{fe20}'..='\u{fe2f}'
+        | '\u{1f3fb}'..='\u{1f3ff}'
+        | '\u{e0020}'..='\u{e007f}'
+        | '\u{e0100}'..='\u{e01ef}');
+    if extends || prev == ZWJ {
+        return false;
+    }
+    // Regional indicators pair up from the first in a row
+    let is_regional = |c: char| ('\u{1f1e6}'..='\u{1f1ff}').contains(&c);
+    !is_regional(c)
+        || text[..byte]
+            .chars()
+            .rev()
+            .take_while(|&c| is_regional(c))
+            .count()
+            % 2
+            == 0
+}
+
+/// Union of the pixel bounds of all glyphs.
+fn glyph_bounds(glyphs: &[RenderedGlyph]) -> Rect {
+    glyphs
+        .iter()
+        .map(|g| g.bounds())
+        .reduce(|mut sum, next| {
+            sum.min.x = f32::min(sum.min.x, next.min.x);
+            sum.min.y = f32::min(sum.min.y, next.min.y);
+            sum.max.x = f32::max(sum.max.x, next.max.x);
+            sum.max.y = f32::max(sum.max.y, next.max.y);
+            sum
+        })
+        .unwrap_or_default()
+}
+
+/// Area-averaging downscale for raster emoji bitmaps and icons.
+pub(crate) fn scale_pixmap(src: &Pixmap, target_w: u32, target_h: u32) -> Pixmap {
     if src.width() == target_w && src.height() == target_h {
         return src.clone();
     }
@@ -741,43 +1586,60 @@ fn scale_pixmap(src: &Pixmap, target_w: u32, target_h: u32) -> Pixmap {
     let mut dst = Pixmap::new(target_w, target_h).unwrap();
     let scale_x = src.width() as f32 / target_w as f32;
     let scale_y = src.height() as f32 / target_h as f32;
-    let src_pixels = src.pixels();
-    let dst_pixels = dst.pixels_mut();
-    let src_w = src.width();
+    let row_bytes = src.width() as usize * 4;
 
-    for dy in 0..target_h {
-        for dx in 0..target_w {
+    // Source columns of each destination column, the same in every row
+    let col_spans: Vec<Range<usize>> = (0..target_w)
+        .map(|dx| {
             let sx0 = (dx as f32 * scale_x) as u32;
-            let sy0 = (dy as f32 * scale_y) as u32;
             let sx1 = (((dx + 1) as f32 * scale_x).ceil() as u32).min(src.width());
-            let sy1 = (((dy + 1) as f32 * scale_y).ceil() as u32).min(src.height());
-
-            let mut r_sum: u32 = 0;
-            let mut g_sum: u32 = 0;
-            let mut b_sum: u32 = 0;
-            let mut a_sum: u32 = 0;
-            let mut count: u32 = 0;
-
-            for sy in sy0..sy1 {
-                for sx in sx0..sx1 {
-                    let p = src_pixels[(sy * src_w + sx) as usize];
-                    r_sum += p.red() as u32;
-                    g_sum += p.green() as u32;
-                    b_sum += p.blue() as u32;
-                    a_sum += p.alpha() as u32;
-                    count += 1;
+            sx0 as usize..sx1 as usize
+        })
+        .collect();
+    let mut columns = ColumnSums::new(src.width() as usize);
+    // Running channel sums across the columns
+    let mut prefix = vec![[0u32; 4]; src.width() as usize + 1];
+    // Channel sums of each destination pixel in the current row
+    let mut sums = vec![[0u32; 4]; target_w as usize];
+    // What to multiply each one's sums by to average them, for so many rows
+    let mut reciprocals = vec![0; target_w as usize];
+    let mut reciprocal_rows = 0;
+
+    let dst_data = dst.data_mut();
+    for (dy, dst_row) in dst_data.chunks_exact_mut(target_w as usize * 4).enumerate() {
+        let sy0 = (dy as f32 * scale_y) as usize;
+        let sy1 = (((dy + 1) as f32 * scale_y).ceil() as usize).min(src.height() as usize);
+        if sy1 <= sy0 {
+            continue;
+        }
+
+        // Sum down the columns, then across each destination pixel's span
+        sums.fill([0; 4]);
+        let rows = &src.data()[sy0 * row_bytes..sy1 * row_bytes];
+        for rows in rows.chunks(row_bytes * MAX_LANE_PIXELS) {
+            columns.clear();
+            for row in rows.chunks_exact(row_bytes) {
+                columns.add_row(row);
+            }
+            columns.prefix_sums(&mut prefix);
+            for (sum, span) in sums.iter_mut().zip(&col_spans) {
+                let (start, end) = (prefix[span.start], prefix[span.end]);
+                for channel in 0..4 {
+                    sum[channel] += end[channel] - start[channel];
                 }
             }
+        }
 
-            if count > 0 {
-                dst_pixels[(dy * target_w + dx) as usize] =
-                    tiny_skia::PremultipliedColorU8::from_rgba(
-                        (r_sum / count) as u8,
-                        (g_sum / count) as u8,
-                        (b_sum / count) as u8,
-                        (a_sum / count) as u8,
-                    )
-                    .unwrap();
+        let rows = (sy1 - sy0) as u32;
+        if rows != reciprocal_rows {
+            for (reciprocal, span) in reciprocals.iter_mut().zip(&col_spans) {
+                *reciprocal = reciprocal_of(span.len() as u32 * rows);
+            }
+            reciprocal_rows = rows;
+        }
+        for ((out, sum), &reciprocal) in dst_row.chunks_exact_mut(4).zip(&sums).zip(&reciprocals) {
+            for (out, &sum) in out.iter_mut().zip(sum) {
+                *out = ((sum as u128 * reciprocal) >> 64) as u8;
             }
         }
     }
@@ -785,4 +1647,392 @@ fn scale_pixmap(src: &Pixmap, target_w: u32, target_h: u32) -> Pixmap {
     dst
 }
 
+/// `1 / count` in 64-bit fixed point, rounded up so that `sum * reciprocal >> 64`
+/// is exactly `sum / count` for any `u32` sum, or 0 for no pixels at all.
+fn reciprocal_of(count: u32) -> u128 {
+    match count {
+        0 => 0,
+        count => (u64::MAX / count as u64) as u128 + 1,
+    }
+}
+
+/// The channel sums down each column of up to [`MAX_LANE_PIXELS`] rows of
+/// pixels, in 16-bit lanes.
+struct ColumnSums {
+    /// Two pixels' channels per vector.
+    #[cfg(feature = "simd")]
+    lanes: Vec<wide::u16x8>,
+    /// A pixel's channels each, as [`spread`] lays them out.
+    #[cfg(not(feature = "simd"))]
+    lanes: Vec<u64>,
+}
+
+impl ColumnSums {
+    fn new(width: usize) -> Self {
+        Self {
+            #[cfg(feature = "simd")]
+            lanes: vec![wide::u16x8::ZERO; width.div_ceil(4) * 2],
+            #[cfg(not(feature = "simd"))]
+            lanes: vec![0; width],
+        }
+    }
+
+    fn clear(&mut self) {
+        self.lanes.fill(Default::default());
+    }
+
+    #[cfg(feature = "simd")]
+    fn add_row(&mut self, row: &[u8]) {
+        use wide::{u8x16, u16x8};
+        let add = |lanes: &mut [u16x8], bytes: [u8; 16]| {
+            let bytes = u8x16::new(bytes);
+            lanes[0] += u16x8::from_u8x16_low(bytes);
+            lanes[1] += u16x8::from_u8x16_high(bytes);
+        };
+        // Four pixels at a time, the last ones padded out with zeros
+        let pixels = row.chunks_exact(16);
+        let rest = pixels.remainder();
+        let mut lanes = self.lanes.chunks_exact_mut(2);
+        for (lanes, pixels) in lanes.by_ref().zip(pixels) {
+            add(lanes, pixels.try_into().unwrap());
+        }
+        if let Some(lanes) = lanes.next() {
+            let mut bytes = [0; 16];
+            bytes[..rest.len()].copy_from_slice(rest);
+            add(lanes, bytes);
+        }
+    }
+
+    #[cfg(not(feature = "simd"))]
+    fn add_row(&mut self, row: &[u8]) {
+        for (lanes, pixel) in self.lanes.iter_mut().zip(row.chunks_exact(4)) {
+            *lanes += spread(&[pixel[0], pixel[1], pixel[2], pixel[3]]);
+        }
+    }
+
+    /// Fills `prefix[x]` with the channel sums of every column before `x`.
+    #[cfg(feature = "simd")]
+    fn prefix_sums(&self, prefix: &mut [[u32; 4]]) {
+        use wide::{u32x4, u32x8};
+        let mut total = u32x4::ZERO;
+        for (lanes, prefix) in self.lanes.iter().zip(prefix[1..].chunks_mut(2)) {
+            let lanes = u32x8::from(*lanes).to_array();
+            for (pixel, prefix) in lanes.chunks_exact(4).zip(prefix) {
+                total += u32x4::new([pixel[0], pixel[1], pixel[2], pixel[3]]);
+                *prefix = total.to_array();
+            }
+        }
+    }
+
+    /// Fills `prefix[x]` with the channel sums of every column before `x`.
+    #[cfg(not(feature = "simd"))]
+    fn prefix_sums(&self, prefix: &mut [[u32; 4]]) {
+        let mut total = [0; 4];
+        for (&lanes, prefix) in self.lanes.iter().zip(&mut prefix[1..]) {
+            for (channel, total) in total.iter_mut().enumerate() {
+                *total += (lanes >> (16 * channel)) as u32 & 0xffff;
+            }
+            *prefix = total;
+        }
+    }
+}
+
+/// How many pixels [`spread`] ones can be summed before a lane overflows.
+const MAX_LANE_PIXELS: usize = u16::MAX as usize / 255;
+
+/// A pixel's four bytes, each in its own 16-bit lane, so channels can be
+/// added and multiplied all at once.
+fn spread(pixel: &[u8; 4]) -> u64 {
+    let x = u32::from_le_bytes(*pixel) as u64;
+    let x = (x | x << 16) & 0x0000_ffff_0000_ffff;
+    (x | x << 8) & 0x00ff_00ff_00ff_00ff
+}
+
 const ZWSP: char = '\u{200b}';
+const ZWJ: char = '\u{200d}';
+const ELLIPSIS: char = '\u{2026}';
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `scale_pixmap` as it was before the row-wise box filter: every
+    /// destination pixel sums its whole source box.
+    fn scale_pixmap_per_pixel(src: &Pixmap, target_w: u32, target_h: u32) -> Pixmap {
+        let mut dst = Pixmap::new(target_w, target_h).unwrap();
+        let scale_x = src.width() as f32 / target_w as f32;
+        let scale_y = src.height() as f32 / target_h as f32;
+        let src_pixels = src.pixels();
+        let dst_pixels = dst.pixels_mut();
+        let src_w = src.width();
+
+        for dy in 0..target_h {
+            for dx in 0..target_w {
+                let sx0 = (dx as f32 * scale_x) as u32;
+                let sy0 = (dy as f32 * scale_y) as u32;
+                let sx1 = (((dx + 1) as f32 * scale_x).ceil() as u32).min(src.width());
+                let sy1 = (((dy + 1) as f32 * scale_y).ceil() as u32).min(src.height());
+
+                let mut sums = [0u32; 4];
+                let mut count: u32 = 0;
+                for sy in sy0..sy1 {
+                    for sx in sx0..sx1 {
+                        let p = src_pixels[(sy * src_w + sx) as usize];
+                        sums[0] += p.red() as u32;
+                        sums[1] += p.green() as u32;
+                        sums[2] += p.blue() as u32;
+                        sums[3] += p.alpha() as u32;
+                        count += 1;
+                    }
+                }
+                if count > 0 {
+                    let [r, g, b, a] = sums.map(|sum| (sum / count) as u8);
+                    dst_pixels[(dy * target_w + dx) as usize] =
+                        tiny_skia::PremultipliedColorU8::from_rgba(r, g, b, a).unwrap();
+                }
+            }
+        }
+        dst
+    }
+
+    /// `TextRenderer::finish` as it was before cached coverage, for plain
+    /// text in one color: each glyph blended into the pixmap pixel by pixel.
+    fn finish_per_pixel(renderer: &TextRenderer) -> Pixmap {
+        let layout = renderer.layout();
+        let outlined: Vec<OutlinedGlyph> = layout
+            .glyphs
+            .iter()
+            .filter_map(|pg| {
+                match &pg.fallback {
+                    Some(fb) => {
+                        fb.as_scaled(renderer.font.px_scale)
+                            .outline_glyph(pg.glyph.clone())
+                    }
+                    None => renderer.font.primary.outline_glyph(pg.glyph.clone()),
+                }
+            })
+            .collect();
+        let bounds = outlined
+            .iter()
+            .map(OutlinedGlyph::px_bounds)
+            .reduce(|sum, next| {
+                Rect {
+                    min: point(sum.min.x.min(next.min.x), sum.min.y.min(next.min.y)),
+                    max: point(sum.max.x.max(next.max.x), sum.max.y.max(next.max.y)),
+                }
+            })
+            .unwrap();
+        let base_x = -bounds.min.x.floor() as i32 + 1;
+        let base_y = -bounds.min.y.floor() as i32 + 1;
+        let width = (bounds.width().ceil() as u32 + layout.trailing_space.ceil() as u32 + 2).max(1);
+        let height = bounds.height().ceil() as u32 + 2;
+        let color = renderer.color;
+
+        let mut pixmap = Pixmap::new(width, height).unwrap();
+        for og in &outlined {
+            let glyph_bounds = og.px_bounds();
+            let gx = glyph_bounds.min.x.floor() as i32 + base_x;
+            let gy = glyph_bounds.min.y.floor() as i32 + base_y;
+            let pixels = pixmap.pixels_mut();
+            og.draw(|x, y, c| {
+                let px = gx + x as i32;
+                let py = gy + y as i32;
+                if px < 0 || py < 0 || px as u32 >= width || py as u32 >= height {
+                    return;
+                }
+                let pix = &mut pixels[(py as u32 * width + px as u32) as usize];
+                let a = (c * 255.0).round() as u8;
+                if a == 0 {
+                    return;
+                }
+                let r = (color.r as u32 * a as u32 / 255) as u8;
+                let g = (color.g as u32 * a as u32 / 255) as u8;
+                let b = (color.b as u32 * a as u32 / 255) as u8;
+                let inv_a = 255 - a as u32;
+                let blend =
+                    |src: u8, dst: u8| (src as u32 + dst as u32 * inv_a / 255).min(255) as u8;
+                *pix = tiny_skia::PremultipliedColorU8::from_rgba(
+                    blend(r, pix.red()),
+                    blend(g, pix.green()),
+                    blend(b, pix.blue()),
+                    blend(a, pix.alpha()),
+                )
+                .unwrap();
+            });
+        }
+        pixmap
+    }
+
+    /// Asserts every channel of every pixel is within one of the other's.
+    fn assert_within_one(new: &[u8], old: &[u8], what: &str) {
+        assert_eq!(new.len(), old.len(), "{what}");
+        for (i, (&n, &o)) in new.iter().zip(old).enumerate() {
+            assert!(n.abs_diff(o) <= 1, "{what}: byte {i} is {n}, was {o}");
+        }
+    }
+
+    /// Deterministic noise with valid premultiplied pixels, so every source
+    /// byte counts.
+    fn noise(width: u32, height: u32) -> Pixmap {
+        let mut pixmap = Pixmap::new(width, height).unwrap();
+        let mut seed = 0x2545_f491_u32;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed
+        };
+        for pix in pixmap.pixels_mut() {
+            let a = next() as u8;
+            let channel = |n: u32| (n % (a as u32 + 1)) as u8;
+            *pix = tiny_skia::PremultipliedColorU8::from_rgba(
+                channel(next()),
+                channel(next()),
+                channel(next()),
+                a,
+            )
+            .unwrap();
+        }
+        pixmap
+    }
+
+    #[test]
+    fn box_filter_matches_the_per_pixel_downscale() {
+        let src = noise(512, 512);
+        for (w, h) in [
+            (20, 20),
+            (64, 64),
+            (37, 53),
+            (300, 200),
+            (511, 1),
+            (700, 600),
+        ] {
+            assert_within_one(
+                scale_pixmap(&src, w, h).data(),
+                scale_pixmap_per_pixel(&src, w, h).data(),
+                &format!("512x512 to {w}x{h}"),
+            );
+        }
+        let src = noise(136, 128);
+        assert_within_one(
+            scale_pixmap(&src, 17, 16).data(),
+            scale_pixmap_per_pixel(&src, 17, 16).data(),
+            "136x128 to 17x16",
+        );
+    }
+
+    #[test]
+    fn reciprocals_divide_exactly() {
+        let counts = (1..=1024).chain([65_535, 262_144, 1 << 20, u32::MAX]);
+        for count in counts {
+            let reciprocal = reciprocal_of(count);
+            let quotients = [0, 1, 127, 254, 255, u32::MAX / count];
+            for sum in quotients.iter().filter_map(|q| q.checked_mul(count)) {
+                let quotient = sum / count;
+                let divided = |sum: u32| ((sum as u128 * reciprocal) >> 64) as u32;
+                assert_eq!(divided(sum), quotient, "{sum} / {count}");
+                if sum > 0 {
+                    assert_eq!(divided(sum - 1), (sum - 1) / count, "{} / {count}", sum - 1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cached_coverage_matches_per_pixel_blending() {
+        let text = "The quick brown fox jumps over the lazy dog. \
+                    Wavy AVA fff ffi \u{e9}\u{301} \u{fb01} 0123456789 ([{<@#%&*>}])";
+        for scale in [1.0, 1.5, 2.0] {
+            let font = Font::load(scale);
+            for color in [rgb(0, 0, 0), rgb(255, 255, 255), rgb(30, 120, 220)] {
+                for max_width in [None, Some(180.0)] {
+                    let mut renderer = font.render(text).with_color(color);
+                    if let Some(max_width) = max_width {
+                        renderer = renderer.with_max_width(max_width);
+                    }
+                    let old = finish_per_pixel(&renderer);
+                    let new = renderer.finish().pixmap;
+                    let what = format!("scale {scale}, {color:?}, max width {max_width:?}");
+                    assert_eq!(
+                        (new.width(), new.height()),
+                        (old.width(), old.height()),
+                        "{what}"
+                    );
+                    assert_within_one(new.data(), old.data(), &what);
+                }
+            }
+        }
+    }
+
+    fn paths(names: &[&str]) -> Vec<PathBuf> {
+        names
+            .iter()
+            .map(|name| Path::new("/usr/share/fonts").join(name))
+            .collect()
+    }
+
+    /// The file name `find_font_file` picks for `family`.
+    fn pick<'a>(family: &str, paths: &'a [PathBuf]) -> Option<&'a str> {
+        find_font_file(family, paths).map(|path| path.file_name().unwrap().to_str().unwrap())
+    }
+
+    #[test]
+    fn the_size_is_the_last_word_if_it_is_a_number() {
+        assert_eq!(
+            parse_font_spec("JetBrains Mono 12"),
+            ("JetBrains Mono", Some(12.0))
+        );
+        assert_eq!(
+            parse_font_spec(" Monospace  9.5 "),
+            ("Monospace", Some(9.5))
+        );
+        assert_eq!(
+            parse_font_spec("DejaVu Sans Mono"),
+            ("DejaVu Sans Mono", None)
+        );
+        assert_eq!(parse_font_spec("Iosevka 0"), ("Iosevka 0", None));
+        assert_eq!(parse_font_spec("12"), ("12", None));
+    }
+
+    #[test]
+    fn families_match_file_names_preferring_the_regular_face() {
+        let fonts = paths(&[
+            "JetBrainsMono-Bold.ttf",
+            "JetBrainsMono-Italic.ttf",
+            "JetBrainsMono-Regular.ttf",
+            "DejaVuSansMono.ttf",
+            "DejaVuSans.ttf",
+            "DejaVuSans-Bold.ttf",
+            "NotoSans-Regular.ttf",
+        ]);
+        assert_eq!(
+            pick("JetBrains Mono", &fonts),
+            Some("JetBrainsMono-Regular.ttf")
+        );
+        assert_eq!(
+            pick("jetbrains-mono", &fonts),
+            Some("JetBrainsMono-Regular.ttf")
+        );
+        assert_eq!(pick("DejaVu Sans", &fonts), Some("DejaVuSans.ttf"));
+        assert_eq!(pick("DejaVu Sans Mono", &fonts), Some("DejaVuSansMono.ttf"));
+        assert_eq!(pick("Noto Sans", &fonts), Some("NotoSans-Regular.ttf"));
+    }
+
+    #[test]
+    fn a_missing_family_falls_back_to_a_monospace_font() {
+        let fonts = paths(&[
+            "NotoSans-Regular.ttf",
+            "LiberationMono-Bold.ttf",
+            "LiberationMono-Regular.ttf",
+        ]);
+        assert_eq!(
+            pick("Fira Code", &fonts),
+            Some("LiberationMono-Regular.ttf")
+        );
+        assert_eq!(
+            pick("Monospace", &fonts),
+            Some("LiberationMono-Regular.ttf")
+        );
+        assert_eq!(pick("Fira Code", &paths(&["NotoSans-Regular.ttf"])), None);
+    }
+}
@@ -1,13 +1,27 @@
 //! Calendar date picker dialog implementation.
 
+use std::{
+    borrow::Borrow,
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
 use crate::{
-    backend::{MouseButton, Window, WindowEvent, create_window},
+    backend::{Modifiers, MouseButton, Window, WindowEvent, create_window},
     error::Error,
     render::{Canvas, Font, Rgba, rgb},
     ui::{
         BASE_BUTTON_HEIGHT, BASE_BUTTON_SPACING, BASE_CORNER_RADIUS, Colors, KEY_DOWN, KEY_ESCAPE,
-        KEY_LEFT, KEY_RETURN, KEY_RIGHT, KEY_UP,
+        KEY_LEFT, KEY_PAGE_DOWN, KEY_PAGE_UP, KEY_RETURN, KEY_RIGHT, KEY_UP,
+        date::{
+            WEEKDAY_ABBREVIATIONS, WeekStart, days_in_month, first_day_of_month, format_date,
+            iso_week, month_name, today,
+        },
+        focus::{FocusAction, FocusManager},
+        geometry::Offset,
+        wait_for_event_until,
         widgets::{Widget, button::Button},
+        zoom,
     },
 };
 
@@ -15,51 +29,141 @@ const BASE_PADDING: u32 = 16;
 const BASE_CELL_SIZE: u32 = 36;
 const BASE_HEADER_HEIGHT: u32 = 40;
 const BASE_DAY_HEADER_HEIGHT: u32 = 28;
-const BASE_DROPDOWN_ITEM_HEIGHT: u32 = 24;
+/// Weekdays are numbered from Sunday.
+const SUNDAY: u32 = 0;
+/// The week number column left of the grid.
+const BASE_WEEK_NUMBER_WIDTH: u32 = 28;
+/// The quick picker's year spinner row, and its arrows' width.
+const BASE_PICKER_SPINNER_HEIGHT: u32 = 40;
+const BASE_PICKER_ARROW_WIDTH: u32 = 40;
 const BASE_TEXT_HEIGHT: u32 = 24;
 const BASE_FOOTER_HEIGHT: u32 = 50;
 const BASE_TEXT_GAP: u32 = 8;
 
+/// The date format used unless [`CalendarBuilder::date_format`] sets one.
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
 /// Calendar dialog result.
 #[derive(Debug, Clone)]
 pub enum CalendarResult {
-    /// User selected a date.
-    Selected { year: u32, month: u32, day: u32 },
+    /// User selected a date. `formatted` is the date in the builder's
+    /// [date format](CalendarBuilder::date_format).
+    Selected {
+        year: u32,
+        month: u32,
+        day: u32,
+        formatted: String,
+    },
+    /// User picked dates in [range](CalendarBuilder::range) mode, from
+    /// `start` to `end` as (year, month, day). `formatted` is `START..END`,
+    /// each in the date format.
+    SelectedRange {
+        start: (u32, u32, u32),
+        end: (u32, u32, u32),
+        formatted: String,
+    },
     /// User cancelled.
     Cancelled,
     /// Dialog was closed.
     Closed,
+    /// The timeout passed.
+    Timeout,
 }
 
 impl CalendarResult {
+    /// The exit code the command-line tool uses for this result: 0 when
+    /// a date is picked, 1 when cancelled or closed, 5 on timeout.
     pub fn exit_code(&self) -> i32 {
         match self {
             CalendarResult::Selected {
                 ..
+            }
+            | CalendarResult::SelectedRange {
+                ..
             } => 0,
             CalendarResult::Cancelled => 1,
             CalendarResult::Closed => 1,
+            CalendarResult::Timeout => 5,
         }
     }
 
-    /// Returns the date as a string in YYYY-MM-DD format.
+    /// Returns the date in the builder's date format (YYYY-MM-DD by default).
     pub fn to_string(&self) -> Option<String> {
         match self {
             CalendarResult::Selected {
-                year,
-                month,
-                day,
-            } => Some(format!("{:04}-{:02}-{:02}", year, month, day)),
+                formatted, ..
+            }
+            | CalendarResult::SelectedRange {
+                formatted, ..
+            } => Some(formatted.clone()),
             _ => None,
         }
     }
 }
 
-#[derive(Clone, Copy, PartialEq)]
-enum DropdownState {
-    None,
-    Month,
-    Year,
+/// A date as (year, month, day), which orders the way dates do.
+type Date = (u32, u32, u32);
+
+/// The dates that may be picked.
+#[derive(Clone, Copy, Default)]
+struct DateRange {
+    min: Option<Date>,
+    max: Option<Date>,
+}
+
+impl DateRange {
+    fn contains(&self, date: Date) -> bool {
+        self.min.is_none_or(|min| date >= min) && self.max.is_none_or(|max| date <= max)
+    }
+
+    /// The nearest date that may be picked. A minimum after the maximum wins.
+    fn clamp(&self, date: Date) -> Date {
+        let date = self.max.map_or(date, |max| date.min(max));
+        self.min.map_or(date, |min| date.max(min))
+    }
+
+    /// Whether any day of the month may be picked.
+    fn has_month(&self, year: u32, month: u32) -> bool {
+        self.min
+            .is_none_or(|min| (year, month, days_in_month(year, month)) >= min)
+            && self.max.is_none_or(|max| (year, month, 1) <= max)
+    }
+}
+
+/// The ends of the dates picked in [range](CalendarBuilder::range) mode.
+#[derive(Clone, Copy, Default)]
+struct RangePick {
+    start: Option<Date>,
+    end: Option<Date>,
+}
+
+impl RangePick {
+    /// Picks a date: the end of the range begun, or else the start of a
+    /// new one.
+    fn pick(&mut self, date: Date) {
+        *self = match (self.start, self.end) {
+            (Some(start), None) => {
+                RangePick {
+                    start: Some(start.min(date)),
+                    end: Some(start.max(date)),
+                }
+            }
+            _ => {
+                RangePick {
+                    start: Some(date),
+                    end: None,
+                }
+            }
+        };
+    }
+
+    /// The dates to highlight, in order. Until its end is picked the range
+    /// runs to the cursor, so it follows it across months.
+    fn span(&self, range_selection: bool, cursor: Date) -> Option<(Date, Date)> {
+        let start = self.start.filter(|_| range_selection)?;
+        let end = self.end.unwrap_or(cursor);
+        Some((start.min(end), start.max(end)))
+    }
 }
 
 /// Calendar dialog builder.
@@ -69,9 +173,17 @@ pub struct CalendarBuilder {
     year: Option<u32>,
     month: Option<u32>,
     day: Option<u32>,
+    range: DateRange,
+    week_start: Option<WeekStart>,
+    week_numbers: bool,
+    range_selection: bool,
+    default_cancel: bool,
+    date_format: String,
     width: Option<u32>,
     height: Option<u32>,
-    colors: Option<&'static Colors>,
+    timeout: Option<u32>,
+    position: Option<(Offset, Offset)>,
+    colors: Option<Colors>,
 }
 
 impl CalendarBuilder {
@@ -82,8 +194,16 @@ impl CalendarBuilder {
             year: None,
             month: None,
             day: None,
+            range: DateRange::default(),
+            week_start: None,
+            week_numbers: false,
+            range_selection: false,
+            default_cancel: false,
+            date_format: DEFAULT_DATE_FORMAT.to_string(),
             width: None,
             height: None,
+            timeout: None,
+            position: None,
             colors: None,
         }
     }
@@ -98,9 +218,9 @@ impl CalendarBuilder {
         self
     }
 
-    /// Set initial year.
+    /// Set initial year (1 or later).
     pub fn year(mut self, year: u32) -> Self {
-        self.year = Some(year);
+        self.year = Some(year.max(1));
         self
     }
 
@@ -116,8 +236,54 @@ impl CalendarBuilder {
         self
     }
 
-    pub fn colors(mut self, colors: &'static Colors) -> Self {
-        self.colors = Some(colors);
+    /// Set the earliest date that may be picked. Days before it are grayed out.
+    pub fn min_date(mut self, year: u32, month: u32, day: u32) -> Self {
+        self.range.min = Some((year, month.clamp(1, 12), day.clamp(1, 31)));
+        self
+    }
+
+    /// Set the latest date that may be picked. Days after it are grayed out.
+    pub fn max_date(mut self, year: u32, month: u32, day: u32) -> Self {
+        self.range.max = Some((year, month.clamp(1, 12), day.clamp(1, 31)));
+        self
+    }
+
+    /// Set the weekday the rows start on. By default it's the one customary
+    /// in the locale (`LC_TIME`), or Sunday when that doesn't say.
+    pub fn week_start(mut self, week_start: WeekStart) -> Self {
+        self.week_start = Some(week_start);
+        self
+    }
+
+    /// Show ISO 8601 week numbers in a column left of the days.
+    pub fn week_numbers(mut self, week_numbers: bool) -> Self {
+        self.week_numbers = week_numbers;
+        self
+    }
+
+    /// Pick a range of dates rather than one: the first click or Enter sets
+    /// the start and the second the end. The result is
+    /// [`CalendarResult::SelectedRange`].
+    pub fn range(mut self, range: bool) -> Self {
+        self.range_selection = range;
+        self
+    }
+
+    /// Make Enter cancel the dialog instead of picking the selected day.
+    pub fn default_cancel(mut self, default_cancel: bool) -> Self {
+        self.default_cancel = default_cancel;
+        self
+    }
+
+    /// Set how the selected date is written, in `strftime` style. See
+    /// [`format_date`] for the supported conversions.
+    pub fn date_format(mut self, format: &str) -> Self {
+        self.date_format = format.to_string();
+        self
+    }
+
+    pub fn colors(mut self, colors: impl Borrow<Colors>) -> Self {
+        self.colors = Some(*colors.borrow());
         self
     }
 
@@ -131,11 +297,65 @@ impl CalendarBuilder {
         self
     }
 
-    pub fn show(self) -> Result<CalendarResult, Error> {
-        let colors = self.colors.unwrap_or_else(|| crate::ui::detect_theme());
+    /// Set timeout in seconds. Dialog will auto-close after this time.
+    pub fn timeout(mut self, seconds: u32) -> Self {
+        self.timeout = Some(seconds);
+        self
+    }
+
+    /// Request a window position. Ignored where the platform doesn't allow it.
+    pub fn position(mut self, x: Offset, y: Offset) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    pub fn show(mut self) -> Result<CalendarResult, Error> {
+        let deadline = self
+            .timeout
+            .map(|secs| Instant::now() + Duration::from_secs(secs as u64));
+        loop {
+            if let Some(result) = self.run(deadline)? {
+                return Ok(result);
+            }
+        }
+    }
+
+    fn selected(&self, year: u32, month: u32, day: u32) -> CalendarResult {
+        CalendarResult::Selected {
+            year,
+            month,
+            day,
+            formatted: format_date(&self.date_format, year, month, day),
+        }
+    }
+
+    /// The result of accepting with the cursor on `cursor`: that day, or in
+    /// range mode the range picked so far.
+    fn accept(&self, cursor: Date, picked: RangePick) -> CalendarResult {
+        if !self.range_selection {
+            return self.selected(cursor.0, cursor.1, cursor.2);
+        }
+        let (start, end) = picked.span(true, cursor).unwrap_or((cursor, cursor));
+        let format = |(year, month, day)| format_date(&self.date_format, year, month, day);
+        CalendarResult::SelectedRange {
+            start,
+            end,
+            formatted: format!("{}..{}", format(start), format(end)),
+        }
+    }
+
+    /// Shows the dialog until it's answered, or returns `None` to be run
+    /// again after a [zoom](crate::ui::zoom) change.
+    fn run(&mut self, deadline: Option<Instant>) -> Result<Option<CalendarResult>, Error> {
+        let colors = &self.colors.unwrap_or_else(|| *crate::ui::detect_theme());
 
         // Calculate logical dimensions at scale 1.0
-        let logical_grid_width = BASE_CELL_SIZE * 7;
+        let week_column = if self.week_numbers {
+            BASE_WEEK_NUMBER_WIDTH
+        } else {
+            0
+        };
+        let logical_grid_width = week_column + BASE_CELL_SIZE * 7;
         let logical_text_height = if self.text.is_empty() {
             0
         } else {
@@ -149,9 +369,9 @@ impl CalendarBuilder {
             + BASE_CELL_SIZE * 6
             + BASE_FOOTER_HEIGHT;
 
-        // Use custom dimensions if provided, otherwise use calculated defaults
-        let logical_width = self.width.unwrap_or(calc_width);
-        let logical_height = self.height.unwrap_or(calc_height);
+        // Custom dimensions can only make room around the grid, not shrink it
+        let logical_width = self.width.map_or(calc_width, |w| w.max(calc_width));
+        let logical_height = self.height.map_or(calc_height, |h| h.max(calc_height));
 
         // Create window with LOGICAL dimensions
         let mut window = create_window(logical_width as u16, logical_height as u16)?;
@@ -173,30 +393,45 @@ impl CalendarBuilder {
         let header_height = (BASE_HEADER_HEIGHT as f32 * scale) as u32;
         let day_header_height = (BASE_DAY_HEADER_HEIGHT as f32 * scale) as u32;
 
-        // Calculate physical dimensions
-        let grid_width = cell_size * 7;
+        // Calculate physical dimensions. The week numbers, if any, are part
+        // of the calendar, left of the days
+        let week_column = (week_column as f32 * scale) as u32;
+        let grid_width = week_column + cell_size * 7;
+        let week_start = self
+            .week_start
+            .or_else(WeekStart::from_locale)
+            .unwrap_or_default();
         let text_height = if self.text.is_empty() {
             0
         } else {
             (BASE_TEXT_HEIGHT as f32 * scale) as u32
         };
-        let width = grid_width + padding * 2;
-        let height = padding * 2
-            + text_height
-            + header_height
-            + day_header_height
-            + cell_size * 6
-            + (BASE_FOOTER_HEIGHT as f32 * scale) as u32;
+        let width = ((logical_width as f32 * scale) as u32).max(grid_width + padding * 2);
+        let height = ((logical_height as f32 * scale) as u32).max(
+            padding * 2
+                + text_height
+                + header_height
+                + day_header_height
+                + cell_size * 6
+                + (BASE_FOOTER_HEIGHT as f32 * scale) as u32,
+        );
 
         // Get current date as default
-        let now = current_date();
+        let now = today();
         let mut year = self.year.unwrap_or(now.0);
         let mut month = self.month.unwrap_or(now.1);
-        let mut selected_day = self.day.unwrap_or(now.2);
+        // Today's day may not exist in a month chosen with --month (e.g. the 31st)
+        let mut selected_day = self
+            .day
+            .unwrap_or(now.2)
+            .clamp(1, days_in_month(year, month));
+        let range = self.range;
+        (year, month, selected_day) = range.clamp((year, month, selected_day));
 
         // Create buttons at physical scale
-        let mut ok_button = Button::new("OK", &font, scale);
-        let mut cancel_button = Button::new("Cancel", &font, scale);
+        let mut ok_button = Button::new("_OK", &font, scale);
+        let mut cancel_button = Button::new("_Cancel", &font, scale);
+        cancel_button.set_default(self.default_cancel);
 
         // Layout in physical coordinates
         let mut y = padding as i32;
@@ -205,8 +440,10 @@ impl CalendarBuilder {
             y += text_height as i32 + (BASE_TEXT_GAP as f32 * scale) as i32;
         }
 
-        let calendar_x = padding as i32;
+        // Extra width from --width centers the grid
+        let calendar_x = (width - grid_width) as i32 / 2;
         let calendar_y = y;
+        let days_x = calendar_x + week_column as i32;
 
         let button_y = (height - padding - (BASE_BUTTON_HEIGHT as f32 * scale) as u32) as i32;
         let mut bx = width as i32 - padding as i32;
@@ -220,9 +457,10 @@ impl CalendarBuilder {
         let mut mouse_x = 0i32;
         let mut mouse_y = 0i32;
         let mut hovered_day: Option<u32> = None;
-        let mut dropdown = DropdownState::None;
-        let mut dropdown_hover: Option<usize> = None;
-        let mut year_scroll_offset: i32 = 0;
+        // Open while clicking the month and year in the header picks them
+        let mut picker: Option<QuickPicker> = None;
+        // The range being picked, in range mode
+        let mut picked = RangePick::default();
 
         // Initial draw
         draw_calendar(
@@ -238,25 +476,55 @@ impl CalendarBuilder {
             month,
             selected_day,
             hovered_day,
-            dropdown,
-            dropdown_hover,
-            year_scroll_offset,
+            picked.span(self.range_selection, (year, month, selected_day)),
+            week_start,
+            self.week_numbers,
+            range,
+            picker,
             &ok_button,
             &cancel_button,
             scale,
         );
         window.set_contents(&canvas)?;
+        if let Some((x, y)) = self.position {
+            window.set_position(x, y)?;
+        }
         window.show()?;
 
         let grid_y = calendar_y + header_height as i32 + day_header_height as i32;
 
         let mut window_dragging = false;
+        // Tab moves the focus from the grid to OK and Cancel and back
+        let mut focus = FocusManager::new(2);
+        // Events the batch loop read, left for the main loop to handle
+        let mut held: VecDeque<WindowEvent> = VecDeque::new();
         loop {
-            let event = window.wait_for_event()?;
-            let mut needs_redraw = false;
+            let event = match held.pop_front() {
+                Some(event) => event,
+                None => {
+                    match wait_for_event_until(&mut window, deadline)? {
+                        Some(event) => event,
+                        None => return Ok(Some(CalendarResult::Timeout)),
+                    }
+                }
+            };
+            let focus_moved = picker.is_none()
+                && match focus.process_event(&event) {
+                    Some(FocusAction::Activate(0)) => {
+                        return Ok(Some(self.accept((year, month, selected_day), picked)));
+                    }
+                    Some(FocusAction::Activate(_)) => return Ok(Some(CalendarResult::Cancelled)),
+                    Some(FocusAction::Moved) => {
+                        ok_button.set_focused(focus.is_focused(0));
+                        cancel_button.set_focused(focus.is_focused(1));
+                        true
+                    }
+                    None => false,
+                };
+            let mut needs_redraw = focus_moved;
 
             match &event {
-                WindowEvent::CloseRequested => return Ok(CalendarResult::Closed),
+                WindowEvent::CloseRequested => return Ok(Some(CalendarResult::Closed)),
                 WindowEvent::RedrawRequested => needs_redraw = true,
                 WindowEvent::CursorMove(pos) => {
                     if window_dragging {
@@ -267,13 +535,13 @@ impl CalendarBuilder {
                     mouse_x = pos.x as i32;
                     mouse_y = pos.y as i32;
 
-                    // Handle dropdown hover
-                    if dropdown != DropdownState::None {
-                        let old_hover = dropdown_hover;
-                        dropdown_hover = get_dropdown_hover(
-                            dropdown, mouse_x, mouse_y, calendar_x, calendar_y, scale,
-                        );
-                        if old_hover != dropdown_hover {
+                    // The pointer highlights months in the quick picker
+                    if let Some(picker) = &mut picker {
+                        if let Some(PickerHit::Month(m)) =
+                            picker_hit(mouse_x, mouse_y, calendar_x, calendar_y, grid_width, scale)
+                            && m != picker.month
+                        {
+                            picker.month = m;
                             needs_redraw = true;
                         }
                     } else {
@@ -281,20 +549,23 @@ impl CalendarBuilder {
                         let old_hovered = hovered_day;
                         hovered_day = None;
 
-                        if mouse_x >= calendar_x
-                            && mouse_x < calendar_x + grid_width as i32
+                        if mouse_x >= days_x
+                            && mouse_x < days_x + (cell_size * 7) as i32
                             && mouse_y >= grid_y
                             && mouse_y < grid_y + (cell_size * 6) as i32
                         {
-                            let col = (mouse_x - calendar_x) / cell_size as i32;
+                            let col = (mouse_x - days_x) / cell_size as i32;
                             let row = (mouse_y - grid_y) / cell_size as i32;
                             let cell_idx = row * 7 + col;
 
-                            let first_day = first_day_of_month(year, month);
+                            let first_day = week_start.column(first_day_of_month(year, month));
                             let days_in = days_in_month(year, month);
 
                             let day = cell_idx - first_day as i32 + 1;
-                            if day >= 1 && day <= days_in as i32 {
+                            if day >= 1
+                                && day <= days_in as i32
+                                && range.contains((year, month, day as u32))
+                            {
                                 hovered_day = Some(day as u32);
                             }
                         }
@@ -308,24 +579,24 @@ impl CalendarBuilder {
                     window_dragging = true;
                     let header_y = calendar_y;
 
-                    // Handle dropdown selection
-                    if dropdown != DropdownState::None {
-                        if let Some(idx) = dropdown_hover {
-                            match dropdown {
-                                DropdownState::Month => {
-                                    month = idx as u32 + 1;
-                                    selected_day = selected_day.min(days_in_month(year, month));
-                                }
-                                DropdownState::Year => {
-                                    let base_year = year as i32 - 5 + year_scroll_offset;
-                                    year = (base_year + idx as i32).max(1) as u32;
-                                    selected_day = selected_day.min(days_in_month(year, month));
-                                }
-                                DropdownState::None => {}
+                    // The quick picker takes every click until it's closed
+                    if let Some(open) = &mut picker {
+                        match picker_hit(
+                            mouse_x, mouse_y, calendar_x, calendar_y, grid_width, scale,
+                        ) {
+                            Some(PickerHit::PrevYear) => {
+                                open.year = open.year.saturating_sub(1).max(1)
+                            }
+                            Some(PickerHit::NextYear) => open.year += 1,
+                            Some(PickerHit::Month(m)) if range.has_month(open.year, m) => {
+                                (year, month) = (open.year, m);
+                                selected_day = selected_day.min(days_in_month(year, month));
+                                picker = None;
                             }
+                            // Clicking the header again closes it
+                            None if mouse_y < header_y + header_height as i32 => picker = None,
+                            _ => {}
                         }
-                        dropdown = DropdownState::None;
-                        dropdown_hover = None;
                         needs_redraw = true;
                     }
                     // Check header clicks
@@ -338,7 +609,6 @@ impl CalendarBuilder {
 
                         let prev_arrow_end = calendar_x + 28;
                         let month_x = calendar_x + 35;
-                        let month_end = month_x + month_text_width;
                         let year_x = month_x + month_text_width + 8;
                         let year_end = year_x + year_text_width;
                         let today_x = calendar_x + grid_width as i32 - 70;
@@ -346,133 +616,95 @@ impl CalendarBuilder {
 
                         // Check in order from left to right
                         if mouse_x < prev_arrow_end {
-                            // Previous month
-                            if month == 1 {
-                                month = 12;
-                                year -= 1;
-                            } else {
-                                month -= 1;
+                            // Previous month, unless none of it may be picked
+                            let (prev_year, prev_month) = add_months(year, month, -1);
+                            if range.has_month(prev_year, prev_month) {
+                                (year, month) = (prev_year, prev_month);
+                                selected_day = selected_day.min(days_in_month(year, month));
+                                needs_redraw = true;
                             }
-                            selected_day = selected_day.min(days_in_month(year, month));
-                            needs_redraw = true;
-                        } else if mouse_x >= month_x && mouse_x < month_end + 5 {
-                            // Month click
-                            dropdown = DropdownState::Month;
-                            dropdown_hover = Some((month - 1) as usize);
-                            needs_redraw = true;
-                        } else if mouse_x >= year_x && mouse_x < year_end + 5 {
-                            // Year click
-                            dropdown = DropdownState::Year;
-                            dropdown_hover = Some(5); // Current year is at index 5
-                            year_scroll_offset = 0;
+                        } else if mouse_x >= month_x && mouse_x < year_end + 5 {
+                            // Month and year click opens the quick picker
+                            picker = Some(QuickPicker {
+                                year,
+                                month,
+                            });
+                            hovered_day = None;
                             needs_redraw = true;
                         } else if mouse_x >= today_x && mouse_x < next_arrow_start {
                             // Today click
-                            let today = current_date();
+                            let today = today();
                             year = today.0;
                             month = today.1;
                             selected_day = today.2;
                             needs_redraw = true;
                         } else if mouse_x >= next_arrow_start {
-                            // Next month
-                            if month == 12 {
-                                month = 1;
-                                year += 1;
-                            } else {
-                                month += 1;
+                            // Next month, unless none of it may be picked
+                            let (next_year, next_month) = add_months(year, month, 1);
+                            if range.has_month(next_year, next_month) {
+                                (year, month) = (next_year, next_month);
+                                selected_day = selected_day.min(days_in_month(year, month));
+                                needs_redraw = true;
                             }
-                            selected_day = selected_day.min(days_in_month(year, month));
-                            needs_redraw = true;
                         }
                     }
                     // Check day click
                     else if let Some(day) = hovered_day {
                         selected_day = day;
+                        if self.range_selection {
+                            picked.pick((year, month, day));
+                        }
                         needs_redraw = true;
                     }
                 }
                 WindowEvent::ButtonRelease(MouseButton::Left, _) => {
                     window_dragging = false;
                 }
-                WindowEvent::Scroll(dir) => {
-                    if dropdown == DropdownState::Year {
+                WindowEvent::Scroll(dir, _) => {
+                    if let Some(open) = &mut picker {
                         match dir {
                             crate::backend::ScrollDirection::Up => {
-                                year_scroll_offset -= 1;
+                                open.year = open.year.saturating_sub(1).max(1);
                                 needs_redraw = true;
                             }
                             crate::backend::ScrollDirection::Down => {
-                                year_scroll_offset += 1;
+                                open.year += 1;
                                 needs_redraw = true;
                             }
                             _ => {}
                         }
                     }
                 }
-                WindowEvent::KeyPress(key_event) => {
-                    // Handle dropdown keyboard navigation
-                    if dropdown != DropdownState::None {
-                        let max_items = match dropdown {
-                            DropdownState::Month => 12,
-                            DropdownState::Year => 11,
-                            DropdownState::None => 0,
-                        };
-
+                WindowEvent::KeyPress(key_event) if !focus_moved => {
+                    if zoom::handle_key(key_event) {
+                        // Rebuilt at the new size on the same date
+                        (self.year, self.month, self.day) =
+                            (Some(year), Some(month), Some(selected_day));
+                        return Ok(None);
+                    }
+                    if let Some(open) = &mut picker {
                         match key_event.keysym {
-                            KEY_ESCAPE => {
-                                dropdown = DropdownState::None;
-                                dropdown_hover = None;
-                                needs_redraw = true;
-                            }
-                            KEY_UP => {
-                                let current = dropdown_hover.unwrap_or(0);
-                                if current > 0 {
-                                    dropdown_hover = Some(current - 1);
-                                } else if dropdown == DropdownState::Year {
-                                    // Scroll up for year dropdown
-                                    year_scroll_offset -= 1;
-                                }
-                                needs_redraw = true;
-                            }
-                            KEY_DOWN => {
-                                let current = dropdown_hover.unwrap_or(0);
-                                if current + 1 < max_items {
-                                    dropdown_hover = Some(current + 1);
-                                } else if dropdown == DropdownState::Year {
-                                    // Scroll down for year dropdown
-                                    year_scroll_offset += 1;
-                                }
-                                needs_redraw = true;
-                            }
-                            KEY_RETURN => {
-                                if let Some(idx) = dropdown_hover {
-                                    match dropdown {
-                                        DropdownState::Month => {
-                                            month = idx as u32 + 1;
-                                            selected_day =
-                                                selected_day.min(days_in_month(year, month));
-                                        }
-                                        DropdownState::Year => {
-                                            let base_year = year as i32 - 5 + year_scroll_offset;
-                                            year = (base_year + idx as i32).max(1) as u32;
-                                            selected_day =
-                                                selected_day.min(days_in_month(year, month));
-                                        }
-                                        DropdownState::None => {}
-                                    }
-                                }
-                                dropdown = DropdownState::None;
-                                dropdown_hover = None;
-                                needs_redraw = true;
+                            KEY_ESCAPE => picker = None,
+                            KEY_LEFT => open.month = open.month.saturating_sub(1).max(1),
+                            KEY_RIGHT => open.month = (open.month + 1).min(12),
+                            KEY_UP if open.month > 3 => open.month -= 3,
+                            KEY_DOWN if open.month <= 9 => open.month += 3,
+                            KEY_PAGE_UP => open.year = open.year.saturating_sub(1).max(1),
+                            KEY_PAGE_DOWN => open.year += 1,
+                            KEY_RETURN if range.has_month(open.year, open.month) => {
+                                (year, month) = (open.year, open.month);
+                                selected_day = selected_day.min(days_in_month(year, month));
+                                picker = None;
                             }
                             _ => {}
                         }
+                        needs_redraw = true;
                     } else {
                         match key_event.keysym {
                             KEY_LEFT => {
                                 if selected_day > 1 {
                                     selected_day -= 1;
-                                } else {
+                                } else if (year, month) > (1, 1) {
                                     if month == 1 {
                                         month = 12;
                                         year -= 1;
@@ -500,7 +732,7 @@ impl CalendarBuilder {
                             KEY_UP => {
                                 if selected_day > 7 {
                                     selected_day -= 7;
-                                } else {
+                                } else if (year, month) > (1, 1) {
                                     if month == 1 {
                                         month = 12;
                                         year -= 1;
@@ -528,15 +760,42 @@ impl CalendarBuilder {
                                 }
                                 needs_redraw = true;
                             }
+                            KEY_PAGE_UP | KEY_PAGE_DOWN => {
+                                // A month at a time, or a year with Ctrl
+                                let step = if key_event.modifiers.contains(Modifiers::CTRL) {
+                                    12
+                                } else {
+                                    1
+                                };
+                                let step = if key_event.keysym == KEY_PAGE_UP {
+                                    -step
+                                } else {
+                                    step
+                                };
+                                (year, month) = add_months(year, month, step);
+                                selected_day = selected_day.min(days_in_month(year, month));
+                                needs_redraw = true;
+                            }
+                            KEY_RETURN if self.default_cancel => {
+                                return Ok(Some(CalendarResult::Cancelled));
+                            }
+                            // The first Enter of a range sets its start, the
+                            // second its end and accepts it
+                            KEY_RETURN if self.range_selection && picked.end.is_none() => {
+                                let first = picked.start.is_none();
+                                picked.pick((year, month, selected_day));
+                                if !first {
+                                    return Ok(Some(
+                                        self.accept((year, month, selected_day), picked),
+                                    ));
+                                }
+                                needs_redraw = true;
+                            }
                             KEY_RETURN => {
-                                return Ok(CalendarResult::Selected {
-                                    year,
-                                    month,
-                                    day: selected_day,
-                                });
+                                return Ok(Some(self.accept((year, month, selected_day), picked)));
                             }
                             KEY_ESCAPE => {
-                                return Ok(CalendarResult::Cancelled);
+                                return Ok(Some(CalendarResult::Cancelled));
                             }
                             _ => {}
                         }
@@ -544,32 +803,36 @@ impl CalendarBuilder {
                 }
                 _ => {}
             }
+            // However the selection moved, it stays on a date that may be
+            // picked, so OK and Enter can only ever accept one
+            (year, month, selected_day) = range.clamp((year, month, selected_day));
 
             needs_redraw |= ok_button.process_event(&event);
             needs_redraw |= cancel_button.process_event(&event);
+            ok_button.process_mnemonic(&event, true);
+            cancel_button.process_mnemonic(&event, true);
 
             if ok_button.was_clicked() {
-                return Ok(CalendarResult::Selected {
-                    year,
-                    month,
-                    day: selected_day,
-                });
+                return Ok(Some(self.accept((year, month, selected_day), picked)));
             }
             if cancel_button.was_clicked() {
-                return Ok(CalendarResult::Cancelled);
+                return Ok(Some(CalendarResult::Cancelled));
             }
 
-            while let Some(ev) = window.poll_for_event()? {
-                if let WindowEvent::CloseRequested = ev {
-                    return Ok(CalendarResult::Closed);
-                }
-                if let WindowEvent::CursorMove(pos) = ev {
-                    mouse_x = pos.x as i32;
-                    mouse_y = pos.y as i32;
+            // Batch pending pointer motion. Anything else is left for the main
+            // loop, after the last move before it so clicks hit the right day
+            let mut last_move = None;
+            while held.is_empty()
+                && let Some(ev) = window.poll_for_event()?
+            {
+                if let WindowEvent::CursorMove(_) = ev {
+                    last_move = Some(ev);
+                } else {
+                    held.extend(last_move.take());
+                    held.push_back(ev);
                 }
-                needs_redraw |= ok_button.process_event(&ev);
-                needs_redraw |= cancel_button.process_event(&ev);
             }
+            held.extend(last_move);
 
             if needs_redraw {
                 draw_calendar(
@@ -585,9 +848,11 @@ impl CalendarBuilder {
                     month,
                     selected_day,
                     hovered_day,
-                    dropdown,
-                    dropdown_hover,
-                    year_scroll_offset,
+                    picked.span(self.range_selection, (year, month, selected_day)),
+                    week_start,
+                    self.week_numbers,
+                    range,
+                    picker,
                     &ok_button,
                     &cancel_button,
                     scale,
@@ -612,9 +877,11 @@ fn draw_calendar(
     month: u32,
     selected_day: u32,
     hovered_day: Option<u32>,
-    dropdown: DropdownState,
-    dropdown_hover: Option<usize>,
-    year_scroll_offset: i32,
+    span: Option<(Date, Date)>,
+    week_start: WeekStart,
+    week_numbers: bool,
+    range: DateRange,
+    picker: Option<QuickPicker>,
     ok_button: &Button,
     cancel_button: &Button,
     scale: f32,
@@ -674,11 +941,18 @@ fn draw_calendar(
         header_bg,
     );
 
-    // Navigation arrows
-    let nav_color = colors.text;
+    // Navigation arrows, grayed out when they'd leave the allowed dates
+    let nav_color = |delta| {
+        let (year, month) = add_months(year, month, delta);
+        if range.has_month(year, month) {
+            colors.text
+        } else {
+            colors.input_placeholder
+        }
+    };
 
     // Previous arrow
-    let prev_arrow = font.render("<").with_color(nav_color).finish();
+    let prev_arrow = font.render("<").with_color(nav_color(-1)).finish();
     canvas.draw_canvas(
         &prev_arrow,
         calendar_x + (10.0 * scale) as i32,
@@ -686,7 +960,7 @@ fn draw_calendar(
     );
 
     // Next arrow
-    let next_arrow = font.render(">").with_color(nav_color).finish();
+    let next_arrow = font.render(">").with_color(nav_color(1)).finish();
     canvas.draw_canvas(
         &next_arrow,
         calendar_x + grid_width as i32 - (18.0 * scale) as i32,
@@ -714,11 +988,17 @@ fn draw_calendar(
         - (8.0 * scale) as i32;
     canvas.draw_canvas(&today_text, today_x, header_y + (12.0 * scale) as i32);
 
-    // Day headers
+    // Day headers, from the first day of the week
+    let week_column = if week_numbers {
+        (BASE_WEEK_NUMBER_WIDTH as f32 * scale) as u32
+    } else {
+        0
+    };
+    let days_x = calendar_x + week_column as i32;
     let day_header_y = header_y + header_height as i32;
-    let days = ["Su", "Mo", "Tu", "We", "Th", "Fr", "Sa"];
-    for (i, day) in days.iter().enumerate() {
-        let dx = calendar_x + (i as u32 * cell_size) as i32;
+    for i in 0..7 {
+        let day = WEEKDAY_ABBREVIATIONS[((i + week_start.weekday()) % 7) as usize];
+        let dx = days_x + (i * cell_size) as i32;
         let dt = font.render(day).with_color(rgb(140, 140, 140)).finish();
         let dtx = dx + (cell_size as i32 - dt.width() as i32) / 2;
         canvas.draw_canvas(&dt, dtx, day_header_y + (6.0 * scale) as i32);
@@ -726,20 +1006,53 @@ fn draw_calendar(
 
     // Calendar grid
     let grid_y = day_header_y + day_header_height as i32;
-    let first_day = first_day_of_month(year, month);
+    let first_day = week_start.column(first_day_of_month(year, month));
     let days_in_month = days_in_month(year, month);
-    let today = current_date();
+    let today = today();
+
+    // Week numbers
+    if week_numbers {
+        let rows = (first_day + days_in_month).div_ceil(7);
+        for row in 0..rows {
+            let week = row_week(year, month, first_day, row, week_start);
+            let wt = font
+                .render(&week.to_string())
+                .with_color(rgb(140, 140, 140))
+                .finish();
+            canvas.draw_canvas(
+                &wt,
+                calendar_x + (week_column as i32 - wt.width() as i32) / 2,
+                grid_y + (row * cell_size) as i32 + (cell_size as i32 - wt.height() as i32) / 2,
+            );
+        }
+    }
 
     for day in 1..=days_in_month {
         let cell_idx = (first_day + day - 1) as i32;
         let row = cell_idx / 7;
         let col = cell_idx % 7;
 
-        let cx = calendar_x + col * cell_size as i32;
+        let cx = days_x + col * cell_size as i32;
         let cy = grid_y + row * cell_size as i32;
 
-        let is_selected = day == selected_day;
-        let is_hovered = hovered_day == Some(day);
+        // A range's ends are drawn selected and the days between banded,
+        // with the cursor marked like a hovered day
+        let date = (year, month, day);
+        let (is_selected, in_band) = match span {
+            Some((start, end)) => (date == start || date == end, date > start && date < end),
+            None => (day == selected_day, false),
+        };
+        let is_hovered =
+            hovered_day == Some(day) || span.is_some() && day == selected_day && !is_selected;
+        if in_band {
+            canvas.fill_rect(
+                cx as f32,
+                (cy + (2.0 * scale) as i32) as f32,
+                cell_size as f32,
+                (cell_size - (4.0 * scale) as u32) as f32,
+                colors.selection,
+            );
+        }
         let is_today = year == today.0 && month == today.1 && day == today.2;
 
         // Cell background
@@ -780,8 +1093,10 @@ fn draw_calendar(
         let day_str = day.to_string();
         let text_color = if is_selected {
             rgb(255, 255, 255)
-        } else if col == 0 {
-            rgb(200, 100, 100) // Sunday in red-ish
+        } else if !range.contains((year, month, day)) {
+            colors.input_placeholder
+        } else if (col as u32 + week_start.weekday()) % 7 == SUNDAY {
+            rgb(200, 100, 100) // Sunday in red-ish, wherever it falls
         } else {
             colors.text
         };
@@ -791,6 +1106,21 @@ fn draw_calendar(
         canvas.draw_canvas(&dt, dtx, dty);
     }
 
+    if let Some(picker) = picker {
+        draw_quick_picker(
+            canvas,
+            colors,
+            font,
+            calendar_x,
+            calendar_y,
+            grid_width,
+            picker,
+            (year, month),
+            range,
+            scale,
+        );
+    }
+
     // Border
     canvas.stroke_rounded_rect(
         calendar_x as f32,
@@ -802,267 +1132,204 @@ fn draw_calendar(
         1.0,
     );
 
-    // Buttons (draw before dropdowns so dropdowns appear on top)
+    // Buttons
     ok_button.draw_to(canvas, colors, font);
     cancel_button.draw_to(canvas, colors, font);
+}
 
-    // Draw dropdowns on top of everything
-    if dropdown == DropdownState::Month {
-        draw_month_dropdown(
-            canvas,
-            colors,
-            font,
-            calendar_x,
-            calendar_y,
-            month,
-            dropdown_hover,
-            scale,
-        );
-    } else if dropdown == DropdownState::Year {
-        draw_year_dropdown(
-            canvas,
-            colors,
-            font,
-            calendar_x,
-            calendar_y,
-            year,
-            year_scroll_offset,
-            dropdown_hover,
-            scale,
-        );
-    }
+/// The month and year quick picker, drawn over the day grid.
+#[derive(Clone, Copy)]
+struct QuickPicker {
+    /// The year its spinner shows.
+    year: u32,
+    /// The highlighted month (1-12).
+    month: u32,
 }
 
-#[allow(clippy::too_many_arguments)]
-fn draw_month_dropdown(
-    canvas: &mut Canvas,
-    colors: &Colors,
-    font: &Font,
+/// What a point in the quick picker lands on.
+#[derive(Clone, Copy, PartialEq)]
+enum PickerHit {
+    PrevYear,
+    NextYear,
+    Month(u32),
+}
+
+/// The quick picker's area: the day headers and grid below the header.
+fn picker_rect(
     calendar_x: i32,
     calendar_y: i32,
-    current_month: u32,
-    hover: Option<usize>,
+    grid_width: u32,
     scale: f32,
-) {
+) -> (i32, i32, u32, u32) {
     let header_height = (BASE_HEADER_HEIGHT as f32 * scale) as u32;
-    let dropdown_item_height = (BASE_DROPDOWN_ITEM_HEIGHT as f32 * scale) as u32;
-
-    let dropdown_x = calendar_x + (30.0 * scale) as i32;
-    let dropdown_y = calendar_y + header_height as i32;
-    let dropdown_w = (100.0 * scale) as u32;
-    let dropdown_h = 6 * dropdown_item_height; // Show 6 items at a time
-
-    // Background with shadow effect
-    canvas.fill_rounded_rect(
-        (dropdown_x + (3.0 * scale) as i32) as f32,
-        (dropdown_y + (3.0 * scale) as i32) as f32,
-        dropdown_w as f32,
-        (dropdown_h * 2) as f32,
-        6.0 * scale,
-        rgb(0, 0, 0),
-    );
-    canvas.fill_rounded_rect(
-        dropdown_x as f32,
-        dropdown_y as f32,
-        dropdown_w as f32,
-        (dropdown_h * 2) as f32,
-        6.0 * scale,
-        colors.window_bg,
-    );
-
-    // Items
-    for i in 0..12usize {
-        let item_y = dropdown_y + (i as u32 * dropdown_item_height) as i32;
-        let is_current = i + 1 == current_month as usize;
-        let is_hovered = hover == Some(i);
-
-        // Hover background - subtle gray
-        if is_hovered {
-            canvas.fill_rounded_rect(
-                (dropdown_x + (4.0 * scale) as i32) as f32,
-                (item_y + (2.0 * scale) as i32) as f32,
-                (dropdown_w - (8.0 * scale) as u32) as f32,
-                (dropdown_item_height - (4.0 * scale) as u32) as f32,
-                4.0 * scale,
-                rgb(70, 130, 180), // Steel blue for hover
-            );
-        }
-
-        // Current month gets a checkmark
-        let name = month_name(i as u32 + 1);
-        let display_name = if is_current {
-            format!("{} *", name)
-        } else {
-            name.to_string()
-        };
+    let day_header_height = (BASE_DAY_HEADER_HEIGHT as f32 * scale) as u32;
+    let cell_size = (BASE_CELL_SIZE as f32 * scale) as u32;
+    (
+        calendar_x,
+        calendar_y + header_height as i32,
+        grid_width,
+        day_header_height + cell_size * 6,
+    )
+}
 
-        let text_color = if is_hovered {
-            rgb(255, 255, 255)
-        } else if is_current {
-            rgb(70, 180, 130) // Teal for current
+fn picker_hit(
+    x: i32,
+    y: i32,
+    calendar_x: i32,
+    calendar_y: i32,
+    grid_width: u32,
+    scale: f32,
+) -> Option<PickerHit> {
+    let (px, py, pw, ph) = picker_rect(calendar_x, calendar_y, grid_width, scale);
+    let spinner_h = (BASE_PICKER_SPINNER_HEIGHT as f32 * scale) as i32;
+    let arrow_w = (BASE_PICKER_ARROW_WIDTH as f32 * scale) as i32;
+    if x < px || x >= px + pw as i32 || y < py || y >= py + ph as i32 {
+        return None;
+    }
+    if y < py + spinner_h {
+        return if x < px + arrow_w {
+            Some(PickerHit::PrevYear)
+        } else if x >= px + pw as i32 - arrow_w {
+            Some(PickerHit::NextYear)
         } else {
-            colors.text
+            None
         };
-        let tc = font.render(&display_name).with_color(text_color).finish();
-        canvas.draw_canvas(
-            &tc,
-            dropdown_x + (10.0 * scale) as i32,
-            item_y + (4.0 * scale) as i32,
-        );
     }
-
-    // Border
-    canvas.stroke_rounded_rect(
-        dropdown_x as f32,
-        dropdown_y as f32,
-        dropdown_w as f32,
-        (dropdown_h * 2) as f32,
-        6.0 * scale,
-        colors.input_border,
-        1.0,
-    );
+    let col = ((x - px) * 3 / pw as i32).min(2);
+    let row = ((y - py - spinner_h) * 4 / (ph as i32 - spinner_h)).min(3);
+    Some(PickerHit::Month((row * 3 + col) as u32 + 1))
 }
 
 #[allow(clippy::too_many_arguments)]
-fn draw_year_dropdown(
+fn draw_quick_picker(
     canvas: &mut Canvas,
     colors: &Colors,
     font: &Font,
     calendar_x: i32,
     calendar_y: i32,
-    current_year: u32,
-    scroll_offset: i32,
-    hover: Option<usize>,
+    grid_width: u32,
+    picker: QuickPicker,
+    (year, month): (u32, u32),
+    range: DateRange,
     scale: f32,
 ) {
-    let header_height = (BASE_HEADER_HEIGHT as f32 * scale) as u32;
-    let dropdown_item_height = (BASE_DROPDOWN_ITEM_HEIGHT as f32 * scale) as u32;
-
-    let dropdown_x = calendar_x + (100.0 * scale) as i32;
-    let dropdown_y = calendar_y + header_height as i32;
-    let dropdown_w = (70.0 * scale) as u32;
-    let visible_years = 11usize;
-    let dropdown_h = visible_years as u32 * dropdown_item_height;
+    let (px, py, pw, ph) = picker_rect(calendar_x, calendar_y, grid_width, scale);
+    let spinner_h = (BASE_PICKER_SPINNER_HEIGHT as f32 * scale) as i32;
+    let arrow_w = (BASE_PICKER_ARROW_WIDTH as f32 * scale) as i32;
 
-    // Background with shadow
+    // Rounded at the bottom like the calendar, square under the header
     canvas.fill_rounded_rect(
-        (dropdown_x + (3.0 * scale) as i32) as f32,
-        (dropdown_y + (3.0 * scale) as i32) as f32,
-        dropdown_w as f32,
-        dropdown_h as f32,
-        6.0 * scale,
-        rgb(0, 0, 0),
+        px as f32,
+        py as f32,
+        pw as f32,
+        ph as f32,
+        8.0 * scale,
+        colors.input_bg,
     );
-    canvas.fill_rounded_rect(
-        dropdown_x as f32,
-        dropdown_y as f32,
-        dropdown_w as f32,
-        dropdown_h as f32,
-        6.0 * scale,
-        colors.window_bg,
+    canvas.fill_rect(
+        px as f32,
+        py as f32,
+        pw as f32,
+        8.0 * scale,
+        colors.input_bg,
     );
 
-    // Years centered around current year
-    let base_year = current_year as i32 - 5 + scroll_offset;
-
-    for i in 0..visible_years {
-        let yr = base_year + i as i32;
-        if yr < 1 {
-            continue;
-        }
-
-        let item_y = dropdown_y + (i as u32 * dropdown_item_height) as i32;
-        let is_current = yr == current_year as i32;
-        let is_hovered = hover == Some(i);
+    // Year spinner
+    let text_y = |tc: &Canvas| py + (spinner_h - tc.height() as i32) / 2;
+    let prev = font.render("<").with_color(colors.text).finish();
+    canvas.draw_canvas(
+        &prev,
+        px + (arrow_w - prev.width() as i32) / 2,
+        text_y(&prev),
+    );
+    let next = font.render(">").with_color(colors.text).finish();
+    canvas.draw_canvas(
+        &next,
+        px + pw as i32 - (arrow_w + next.width() as i32) / 2,
+        text_y(&next),
+    );
+    let year_text = font
+        .render(&picker.year.to_string())
+        .with_color(colors.text)
+        .finish();
+    canvas.draw_canvas(
+        &year_text,
+        px + (pw as i32 - year_text.width() as i32) / 2,
+        text_y(&year_text),
+    );
+    canvas.fill_rect(
+        (px + (8.0 * scale) as i32) as f32,
+        (py + spinner_h) as f32,
+        pw as f32 - 16.0 * scale,
+        1.0,
+        colors.input_border,
+    );
 
-        // Hover background
-        if is_hovered {
+    // Months, three to a row
+    let cell_w = pw as i32 / 3;
+    let cell_h = (ph as i32 - spinner_h) / 4;
+    for m in 1..=12u32 {
+        let i = m as i32 - 1;
+        let cx = px + (i % 3) * cell_w;
+        let cy = py + spinner_h + (i / 3) * cell_h;
+        let is_highlighted = m == picker.month && range.has_month(picker.year, m);
+        let is_current = picker.year == year && m == month;
+        if is_highlighted {
             canvas.fill_rounded_rect(
-                (dropdown_x + (4.0 * scale) as i32) as f32,
-                (item_y + (2.0 * scale) as i32) as f32,
-                (dropdown_w - (8.0 * scale) as u32) as f32,
-                (dropdown_item_height - (4.0 * scale) as u32) as f32,
+                (cx + (4.0 * scale) as i32) as f32,
+                (cy + (4.0 * scale) as i32) as f32,
+                (cell_w - (8.0 * scale) as i32) as f32,
+                (cell_h - (8.0 * scale) as i32) as f32,
                 4.0 * scale,
-                rgb(70, 130, 180), // Steel blue for hover
+                rgb(70, 130, 180), // Steel blue for the highlight
             );
         }
-
-        let yr_str = if is_current {
-            format!("* {} *", yr)
-        } else {
-            yr.to_string()
-        };
-
-        let text_color = if is_hovered {
+        let text_color = if !range.has_month(picker.year, m) {
+            colors.input_placeholder
+        } else if is_highlighted {
             rgb(255, 255, 255)
         } else if is_current {
-            rgb(70, 180, 130) // Teal for current
+            rgb(70, 180, 130) // Teal for the month shown
         } else {
             colors.text
         };
-        let tc = font.render(&yr_str).with_color(text_color).finish();
-        let tx = dropdown_x + (dropdown_w as i32 - tc.width() as i32) / 2;
-        canvas.draw_canvas(&tc, tx, item_y + (4.0 * scale) as i32);
+        let tc = font
+            .render(&month_name(m)[..3])
+            .with_color(text_color)
+            .finish();
+        canvas.draw_canvas(
+            &tc,
+            cx + (cell_w - tc.width() as i32) / 2,
+            cy + (cell_h - tc.height() as i32) / 2,
+        );
     }
-
-    // Border
-    canvas.stroke_rounded_rect(
-        dropdown_x as f32,
-        dropdown_y as f32,
-        dropdown_w as f32,
-        dropdown_h as f32,
-        6.0 * scale,
-        colors.input_border,
-        1.0,
-    );
 }
 
-fn get_dropdown_hover(
-    dropdown: DropdownState,
-    mouse_x: i32,
-    mouse_y: i32,
-    calendar_x: i32,
-    calendar_y: i32,
-    scale: f32,
-) -> Option<usize> {
-    let header_height = (BASE_HEADER_HEIGHT as f32 * scale) as u32;
-    let dropdown_item_height = (BASE_DROPDOWN_ITEM_HEIGHT as f32 * scale) as u32;
-
-    let dropdown_y = calendar_y + header_height as i32;
-
-    match dropdown {
-        DropdownState::Month => {
-            let dropdown_x = calendar_x + (30.0 * scale) as i32;
-            let dropdown_w = (100.0 * scale) as i32;
-            let dropdown_h = (12 * dropdown_item_height) as i32;
+/// The ISO week of a grid row whose first day is in column `first_day`:
+/// that of its Monday, which every day but a leading Sunday shares.
+fn row_week(year: u32, month: u32, first_day: u32, row: u32, week_start: WeekStart) -> u32 {
+    let days = days_in_month(year, month) as i32;
+    let day = (row * 7 + week_start.column(1)) as i32 - first_day as i32 + 1;
+    let (year, month, day) = if day < 1 {
+        let (year, month) = add_months(year, month, -1);
+        (
+            year,
+            month,
+            (days_in_month(year, month) as i32 + day) as u32,
+        )
+    } else if day > days {
+        let (year, month) = add_months(year, month, 1);
+        (year, month, (day - days) as u32)
+    } else {
+        (year, month, day as u32)
+    };
+    iso_week(year, month, day).1
+}
 
-            if mouse_x >= dropdown_x
-                && mouse_x < dropdown_x + dropdown_w
-                && mouse_y >= dropdown_y
-                && mouse_y < dropdown_y + dropdown_h
-            {
-                let idx = (mouse_y - dropdown_y) / dropdown_item_height as i32;
-                return Some(idx as usize);
-            }
-        }
-        DropdownState::Year => {
-            let dropdown_x = calendar_x + (100.0 * scale) as i32;
-            let dropdown_w = (70.0 * scale) as i32;
-            let visible_years = 11u32;
-            let dropdown_h = (visible_years * dropdown_item_height) as i32;
-
-            if mouse_x >= dropdown_x
-                && mouse_x < dropdown_x + dropdown_w
-                && mouse_y >= dropdown_y
-                && mouse_y < dropdown_y + dropdown_h
-            {
-                let idx = (mouse_y - dropdown_y) / dropdown_item_height as i32;
-                return Some(idx as usize);
-            }
-        }
-        DropdownState::None => {}
-    }
-    None
+/// Moves `delta` months from `month` of `year`, not before year 1.
+fn add_months(year: u32, month: u32, delta: i32) -> (u32, u32) {
+    let index = (year as i32 * 12 + month as i32 - 1 + delta).max(12);
+    (index.div_euclid(12) as u32, index.rem_euclid(12) as u32 + 1)
 }
 
 impl Default for CalendarBuilder {
@@ -1078,81 +1345,3 @@ fn darken(color: Rgba, amount: f32) -> Rgba {
         (color.b as f32 * (1.0 - amount)) as u8,
     )
 }
-
-/// Get current local date as (year, month, day).
-fn current_date() -> (u32, u32, u32) {
-    unsafe {
-        let now = libc::time(std::ptr::null_mut());
-        let mut tm: libc::tm = std::mem::zeroed();
-        libc::localtime_r(&raw const now, &raw mut tm);
-        (
-            (tm.tm_year + 1900) as u32,
-            (tm.tm_mon + 1) as u32,
-            tm.tm_mday as u32,
-        )
-    }
-}
-
-fn is_leap_year(year: u32) -> bool {
-    (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
-}
-
-fn days_in_month(year: u32, month: u32) -> u32 {
-    match month {
-        1 => 31,
-        2 => {
-            if is_leap_year(year) {
-                29
-            } else {
-                28
-            }
-        }
-        3 => 31,
-        4 => 30,
-        5 => 31,
-        6 => 30,
-        7 => 31,
-        8 => 31,
-        9 => 30,
-        10 => 31,
-        11 => 30,
-        12 => 31,
-        _ => 30,
-    }
-}
-
-/// Get the day of week (0=Sunday) for the first day of the month.
-fn first_day_of_month(year: u32, month: u32) -> u32 {
-    // Zeller's congruence (adjusted for Sunday=0)
-    let mut y = year as i32;
-    let mut m = month as i32;
-
-    if m < 3 {
-        m += 12;
-        y -= 1;
-    }
-
-    let k = y % 100;
-    let j = y / 100;
-
-    let h = (1 + (13 * (m + 1)) / 5 + k + k / 4 + j / 4 - 2 * j) % 7;
-    ((h + 6) % 7) as u32 // Convert to Sunday=0
-}
-
-fn month_name(month: u32) -> &'static str {
-    match month {
-        1 => "January",
-        2 => "February",
-        3 => "March",
-        4 => "April",
-        5 => "May",
-        6 => "June",
-        7 => "July",
-        8 => "August",
-        9 => "September",
-        10 => "October",
-        11 => "November",
-        12 => "December",
-        _ => "Unknown",
-    }
-}
@@ -0,0 +1,540 @@
+//! Gregorian calendar arithmetic shared by the date pickers.
+//!
+//! Weekdays are numbered from Sunday = 0 throughout.
+
+/// Two-letter weekday abbreviations, indexed by weekday (Sunday = 0).
+pub(crate) const WEEKDAY_ABBREVIATIONS: [&str; 7] = ["Su", "Mo", "Tu", "We", "Th", "Fr", "Sa"];
+
+/// Territories whose calendars customarily start the week on Sunday.
+const SUNDAY_FIRST_TERRITORIES: &[&str] = &[
+    "AG", "AS", "BD", "BR", "BS", "BT", "BW", "BZ", "CA", "CO", "DM", "DO", "ET", "GT", "GU", "HK",
+    "HN", "ID", "IL", "IN", "JM", "JP", "KE", "KH", "KR", "LA", "MH", "MM", "MO", "MT", "MX", "MZ",
+    "NI", "NP", "PA", "PE", "PH", "PK", "PR", "PT", "PY", "SA", "SG", "SV", "TH", "TT", "TW", "UM",
+    "US", "VE", "VI", "WS", "YE", "ZA", "ZW",
+];
+
+/// The weekday the calendar's rows start on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WeekStart {
+    #[default]
+    Sunday,
+    Monday,
+}
+
+impl WeekStart {
+    /// Parses a `--week-start` value: `sunday` or `monday`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "sunday" | "sun" => Some(WeekStart::Sunday),
+            "monday" | "mon" => Some(WeekStart::Monday),
+            _ => None,
+        }
+    }
+
+    /// The week start customary in the territory of a locale name such as
+    /// `en_GB.UTF-8`. `None` for locales without one, like `C`.
+    pub(crate) fn from_locale_name(locale: &str) -> Option<Self> {
+        let (_, rest) = locale.split_once('_')?;
+        let territory = rest.split(['.', '@']).next()?;
+        if territory.len() != 2 {
+            return None;
+        }
+        Some(if SUNDAY_FIRST_TERRITORIES.contains(&territory) {
+            WeekStart::Sunday
+        } else {
+            WeekStart::Monday
+        })
+    }
+
+    /// The week start of the locale dates are shown in, from `LC_ALL`,
+    /// `LC_TIME` or `LANG`.
+    pub(crate) fn from_locale() -> Option<Self> {
+        ["LC_ALL", "LC_TIME", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|locale| Self::from_locale_name(&locale))
+    }
+
+    /// The weekday (Sunday = 0) of the first column.
+    pub(crate) fn weekday(self) -> u32 {
+        match self {
+            WeekStart::Sunday => 0,
+            WeekStart::Monday => 1,
+        }
+    }
+
+    /// The column (0-6) `weekday` (Sunday = 0) falls in.
+    pub(crate) fn column(self, weekday: u32) -> u32 {
+        (weekday + 7 - self.weekday()) % 7
+    }
+}
+
+/// Returns the current local date as (year, month, day).
+pub(crate) fn today() -> (u32, u32, u32) {
+    local_date(unsafe { libc::time(std::ptr::null_mut()) } as i64)
+}
+
+unsafe extern "C" {
+    /// Loads the local time zone. POSIX, but missing from the `libc` crate.
+    fn tzset();
+}
+
+/// Held by tests that set `TZ` or read dates in the local zone, which the C
+/// library takes from it.
+#[cfg(test)]
+pub(crate) static TZ_ENV: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Returns the local date of a UNIX timestamp as (year, month, day), in the
+/// zone `TZ` names or else `/etc/localtime`, and UTC when neither is found.
+pub(crate) fn local_date(timestamp: i64) -> (u32, u32, u32) {
+    let timestamp = timestamp as libc::time_t;
+    unsafe {
+        // localtime_r() needn't load the zone itself, and some C libraries
+        // don't, leaving it in UTC
+        tzset();
+        let mut tm: libc::tm = std::mem::zeroed();
+        libc::localtime_r(&raw const timestamp, &raw mut tm);
+        (
+            (tm.tm_year + 1900) as u32,
+            (tm.tm_mon + 1) as u32,
+            tm.tm_mday as u32,
+        )
+    }
+}
+
+pub(crate) fn is_leap_year(year: u32) -> bool {
+    (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
+}
+
+/// Returns the number of days in `month` (1-12).
+pub fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => 30,
+    }
+}
+
+/// Returns the weekday (Sunday = 0) of the given date.
+pub(crate) fn day_of_week(year: u32, month: u32, day: u32) -> u32 {
+    // Zeller's congruence, with January and February counted as months 13
+    // and 14 of the previous year
+    let (y, m) = if month < 3 {
+        (year as i64 - 1, month as i64 + 12)
+    } else {
+        (year as i64, month as i64)
+    };
+    let k = y.rem_euclid(100);
+    let j = y.div_euclid(100);
+    // 0 = Saturday
+    let h = (day as i64 + 13 * (m + 1) / 5 + k + k / 4 + j / 4 + 5 * j).rem_euclid(7);
+    ((h + 6) % 7) as u32
+}
+
+/// Returns the weekday (Sunday = 0) of the first day of the month.
+pub(crate) fn first_day_of_month(year: u32, month: u32) -> u32 {
+    day_of_week(year, month, 1)
+}
+
+pub(crate) fn month_name(month: u32) -> &'static str {
+    match month {
+        1 => "January",
+        2 => "February",
+        3 => "March",
+        4 => "April",
+        5 => "May",
+        6 => "June",
+        7 => "July",
+        8 => "August",
+        9 => "September",
+        10 => "October",
+        11 => "November",
+        12 => "December",
+        _ => "Unknown",
+    }
+}
+
+/// Returns the English name of `weekday` (Sunday = 0).
+pub(crate) fn weekday_name(weekday: u32) -> &'static str {
+    match weekday {
+        0 => "Sunday",
+        1 => "Monday",
+        2 => "Tuesday",
+        3 => "Wednesday",
+        4 => "Thursday",
+        5 => "Friday",
+        6 => "Saturday",
+        _ => "Unknown",
+    }
+}
+
+/// Returns the day of the year, from 1 for January 1st.
+pub(crate) fn day_of_year(year: u32, month: u32, day: u32) -> u32 {
+    (1..month).map(|m| days_in_month(year, m)).sum::<u32>() + day
+}
+
+/// Returns the number of ISO 8601 weeks in `year`: 53 when it starts on a
+/// Thursday, or on a Wednesday in a leap year, and 52 otherwise.
+pub(crate) fn iso_weeks_in_year(year: u32) -> u32 {
+    match first_day_of_month(year, 1) {
+        4 => 53,
+        3 if is_leap_year(year) => 53,
+        _ => 52,
+    }
+}
+
+/// Returns the ISO 8601 week of a date as (week-numbering year, week).
+/// Weeks start on Monday, and week 1 is the one with the year's first
+/// Thursday, so the first days of January can be in the previous year's
+/// last week and the last days of December in the next year's first.
+pub(crate) fn iso_week(year: u32, month: u32, day: u32) -> (u32, u32) {
+    // Monday = 1 to Sunday = 7
+    let weekday = (day_of_week(year, month, day) + 6) % 7 + 1;
+    let week = (day_of_year(year, month, day) as i32 - weekday as i32 + 10) / 7;
+    if week < 1 {
+        (year - 1, iso_weeks_in_year(year - 1))
+    } else if week as u32 > iso_weeks_in_year(year) {
+        (year + 1, 1)
+    } else {
+        (year, week as u32)
+    }
+}
+
+/// Formats a date the way `strftime` would with `format`, supporting
+/// `%Y %y %m %d %e %b %B %a %A %j` and `%%`. Other conversions are copied
+/// through unchanged.
+pub(crate) fn format_date(format: &str, year: u32, month: u32, day: u32) -> String {
+    let mut out = String::with_capacity(format.len() + 8);
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => out.push_str(&year.to_string()),
+            Some('y') => out.push_str(&format!("{:02}", year % 100)),
+            Some('m') => out.push_str(&format!("{month:02}")),
+            Some('d') => out.push_str(&format!("{day:02}")),
+            Some('e') => out.push_str(&format!("{day:2}")),
+            Some('b') => out.push_str(&month_name(month)[..3]),
+            Some('B') => out.push_str(month_name(month)),
+            Some('a') => out.push_str(&weekday_name(day_of_week(year, month, day))[..3]),
+            Some('A') => out.push_str(weekday_name(day_of_week(year, month, day))),
+            Some('j') => out.push_str(&format!("{:03}", day_of_year(year, month, day))),
+            Some('%') => out.push('%'),
+            Some(other) => {
+                out.push('%');
+                out.push(other);
+            }
+            None => out.push('%'),
+        }
+    }
+    out
+}
+
+/// Checks the calendar date math against known anchors and a day-counting
+/// reference implementation.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUNDAY: u32 = 0;
+    const MONDAY: u32 = 1;
+    const TUESDAY: u32 = 2;
+    const WEDNESDAY: u32 = 3;
+    const THURSDAY: u32 = 4;
+    const FRIDAY: u32 = 5;
+    const SATURDAY: u32 = 6;
+
+    /// Leap year rule spelled out by hand rather than with the shared helper.
+    fn reference_is_leap(year: u32) -> bool {
+        if year.is_multiple_of(400) {
+            true
+        } else if year.is_multiple_of(100) {
+            false
+        } else {
+            year.is_multiple_of(4)
+        }
+    }
+
+    /// Days in a month, from the usual table.
+    fn reference_days_in_month(year: u32, month: u32) -> u32 {
+        const DAYS: [u32; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+        if month == 2 && reference_is_leap(year) {
+            29
+        } else {
+            DAYS[month as usize - 1]
+        }
+    }
+
+    /// Walks every day from 1900-01-01 (a Monday) to 2100-12-31 in order,
+    /// yielding (year, month, day, weekday).
+    fn reference_days() -> impl Iterator<Item = (u32, u32, u32, u32)> {
+        let mut weekday = MONDAY;
+        (1900..=2100)
+            .flat_map(move |year| {
+                (1..=12).flat_map(move |month| {
+                    (1..=reference_days_in_month(year, month)).map(move |day| (year, month, day))
+                })
+            })
+            .map(move |(year, month, day)| {
+                let current = weekday;
+                weekday = (weekday + 1) % 7;
+                (year, month, day, current)
+            })
+    }
+
+    #[test]
+    fn known_weekdays() {
+        assert_eq!(day_of_week(1900, 1, 1), MONDAY);
+        assert_eq!(day_of_week(1970, 1, 1), THURSDAY);
+        assert_eq!(day_of_week(2000, 1, 1), SATURDAY);
+        assert_eq!(day_of_week(2000, 2, 29), TUESDAY);
+        assert_eq!(day_of_week(2000, 3, 1), WEDNESDAY);
+        assert_eq!(day_of_week(2024, 2, 29), THURSDAY);
+        assert_eq!(day_of_week(2024, 3, 1), FRIDAY);
+        assert_eq!(day_of_week(2024, 12, 25), WEDNESDAY);
+        assert_eq!(day_of_week(2100, 12, 31), FRIDAY);
+    }
+
+    #[test]
+    fn january_and_february_use_previous_year_adjustment() {
+        // Month 1 and 2 are shifted into the previous year; a slip across a
+        // century boundary shows up here first
+        assert_eq!(first_day_of_month(2000, 1), SATURDAY);
+        assert_eq!(first_day_of_month(2000, 2), TUESDAY);
+        assert_eq!(first_day_of_month(1900, 2), THURSDAY);
+        assert_eq!(first_day_of_month(2100, 1), FRIDAY);
+        assert_eq!(first_day_of_month(2100, 2), MONDAY);
+        assert_eq!(first_day_of_month(2023, 1), SUNDAY);
+    }
+
+    #[test]
+    fn leap_years() {
+        assert!(is_leap_year(2000));
+        assert!(is_leap_year(2024));
+        assert!(!is_leap_year(1900));
+        assert!(!is_leap_year(2100));
+        assert!(!is_leap_year(2023));
+
+        assert_eq!(days_in_month(2000, 2), 29);
+        assert_eq!(days_in_month(1900, 2), 28);
+        assert_eq!(days_in_month(2024, 2), 29);
+        assert_eq!(days_in_month(2023, 2), 28);
+    }
+
+    #[test]
+    fn day_names_row_starts_on_sunday() {
+        assert_eq!(WEEKDAY_ABBREVIATIONS[SUNDAY as usize], "Su");
+        assert_eq!(WEEKDAY_ABBREVIATIONS[SATURDAY as usize], "Sa");
+        // 1970-01-01 sits under the "Th" column
+        assert_eq!(
+            WEEKDAY_ABBREVIATIONS[first_day_of_month(1970, 1) as usize],
+            "Th"
+        );
+    }
+
+    #[test]
+    fn matches_day_counting_reference() {
+        for (year, month, day, weekday) in reference_days() {
+            assert_eq!(
+                day_of_week(year, month, day),
+                weekday,
+                "weekday of {year:04}-{month:02}-{day:02}"
+            );
+            if day == 1 {
+                assert_eq!(first_day_of_month(year, month), weekday);
+                assert_eq!(
+                    days_in_month(year, month),
+                    reference_days_in_month(year, month),
+                    "length of {year:04}-{month:02}"
+                );
+            }
+        }
+        for year in 1900..=2100 {
+            assert_eq!(is_leap_year(year), reference_is_leap(year), "{year}");
+        }
+    }
+
+    #[test]
+    fn formats_strftime_conversions() {
+        assert_eq!(format_date("%Y-%m-%d", 2025, 3, 14), "2025-03-14");
+        assert_eq!(
+            format_date("%A %d %B %Y", 2025, 3, 14),
+            "Friday 14 March 2025"
+        );
+        assert_eq!(format_date("%a %e %b %y", 2024, 1, 7), "Sun  7 Jan 24");
+        assert_eq!(format_date("day %j", 2024, 12, 31), "day 366");
+        assert_eq!(format_date("day %j", 2025, 2, 1), "day 032");
+        assert_eq!(format_date("100%%", 2025, 1, 1), "100%");
+    }
+
+    #[test]
+    fn unknown_conversions_pass_through() {
+        assert_eq!(format_date("%Q %d", 2025, 3, 14), "%Q 14");
+        assert_eq!(format_date("%d%", 2025, 3, 14), "14%");
+        assert_eq!(format_date("plain", 2025, 3, 14), "plain");
+    }
+
+    #[test]
+    fn day_of_year_counts_from_january_first() {
+        assert_eq!(day_of_year(2025, 1, 1), 1);
+        assert_eq!(day_of_year(2024, 3, 1), 61);
+        assert_eq!(day_of_year(2025, 3, 1), 60);
+    }
+
+    #[test]
+    fn iso_weeks_across_new_year() {
+        // 2008-12-29 is a Monday, so the week holding 1 January 2009 starts there
+        assert_eq!(iso_week(2008, 12, 28), (2008, 52));
+        assert_eq!(iso_week(2008, 12, 29), (2009, 1));
+        assert_eq!(iso_week(2008, 12, 31), (2009, 1));
+        assert_eq!(iso_week(2009, 1, 1), (2009, 1));
+        // 2009 starts on a Thursday and has 53 weeks, the last running into 2010
+        assert_eq!(iso_week(2009, 12, 31), (2009, 53));
+        assert_eq!(iso_week(2010, 1, 1), (2009, 53));
+        assert_eq!(iso_week(2010, 1, 3), (2009, 53));
+        assert_eq!(iso_week(2010, 1, 4), (2010, 1));
+        // 2020 is a leap year starting on a Wednesday
+        assert_eq!(iso_week(2020, 12, 31), (2020, 53));
+        assert_eq!(iso_week(2021, 1, 3), (2020, 53));
+        assert_eq!(iso_week(2021, 1, 4), (2021, 1));
+        assert_eq!(iso_week(2024, 12, 29), (2024, 52));
+        assert_eq!(iso_week(2024, 12, 30), (2025, 1));
+        // Year 1, the first the calendar shows, starts on a Monday
+        assert_eq!(iso_week(1, 1, 1), (1, 1));
+        assert_eq!(iso_week(2025, 1, 1), (2025, 1));
+        assert_eq!(iso_week(2026, 12, 31), (2026, 53));
+        assert_eq!(iso_week(2027, 1, 3), (2026, 53));
+        // Sunday 2 January 2022 is still in the last week of 2021
+        assert_eq!(iso_week(2021, 12, 29), (2021, 52));
+        assert_eq!(iso_week(2022, 1, 2), (2021, 52));
+        assert_eq!(iso_week(2022, 1, 3), (2022, 1));
+    }
+
+    #[test]
+    fn long_iso_years() {
+        for year in [2004, 2009, 2015, 2020, 2026] {
+            assert_eq!(iso_weeks_in_year(year), 53, "{year}");
+        }
+        for year in [2008, 2019, 2021, 2024, 2025] {
+            assert_eq!(iso_weeks_in_year(year), 52, "{year}");
+        }
+    }
+
+    #[test]
+    fn iso_weeks_match_their_thursdays() {
+        // A week belongs to the year its Thursday is in, numbered by how many
+        // Thursdays that year has had
+        let days: Vec<_> = reference_days().collect();
+        for (i, &(year, month, day, weekday)) in days.iter().enumerate() {
+            let from_monday = (weekday + 6) % 7;
+            let Some(&(thursday_year, thursday_month, thursday_day, _)) = (i + 3)
+                .checked_sub(from_monday as usize)
+                .and_then(|i| days.get(i))
+            else {
+                continue;
+            };
+            let week = (day_of_year(thursday_year, thursday_month, thursday_day) - 1) / 7 + 1;
+            assert_eq!(
+                iso_week(year, month, day),
+                (thursday_year, week),
+                "{year:04}-{month:02}-{day:02}"
+            );
+        }
+    }
+
+    #[test]
+    fn week_start_follows_the_locale_territory() {
+        assert_eq!(
+            WeekStart::from_locale_name("en_US.UTF-8"),
+            Some(WeekStart::Sunday)
+        );
+        assert_eq!(
+            WeekStart::from_locale_name("ja_JP"),
+            Some(WeekStart::Sunday)
+        );
+        assert_eq!(
+            WeekStart::from_locale_name("en_GB.UTF-8"),
+            Some(WeekStart::Monday)
+        );
+        assert_eq!(
+            WeekStart::from_locale_name("de_DE@euro"),
+            Some(WeekStart::Monday)
+        );
+        assert_eq!(WeekStart::from_locale_name("C"), None);
+        assert_eq!(WeekStart::from_locale_name("C.UTF-8"), None);
+        assert_eq!(WeekStart::from_locale_name("POSIX"), None);
+
+        assert_eq!(WeekStart::from_name("Monday"), Some(WeekStart::Monday));
+        assert_eq!(WeekStart::from_name("sunday"), Some(WeekStart::Sunday));
+        assert_eq!(WeekStart::from_name("friday"), None);
+    }
+
+    #[test]
+    fn week_start_shifts_the_columns() {
+        assert_eq!(WeekStart::Sunday.column(SUNDAY), 0);
+        assert_eq!(WeekStart::Sunday.column(SATURDAY), 6);
+        assert_eq!(WeekStart::Monday.column(MONDAY), 0);
+        assert_eq!(WeekStart::Monday.column(SUNDAY), 6);
+        // 1 January 2024 is a Monday
+        assert_eq!(WeekStart::Monday.column(first_day_of_month(2024, 1)), 0);
+        assert_eq!(WeekStart::Sunday.column(first_day_of_month(2024, 1)), 1);
+    }
+
+    /// Dates of UNIX timestamps in the local time zone, which the calendar
+    /// highlights as today.
+    ///
+    /// The zone comes from the process-wide `TZ`, so tests that read it hold
+    /// [`TZ_ENV`](super::super::TZ_ENV).
+    mod local {
+        use super::super::{TZ_ENV, local_date};
+
+        /// 2024-03-10 04:30 UTC: still the 9th in New York.
+        const MARCH_10_0430: i64 = 1_710_045_000;
+        /// 2024-03-11 04:30 UTC: just past midnight in New York on summer time.
+        const MARCH_11_0430: i64 = 1_710_131_400;
+        /// 2024-03-31 22:30 UTC: after central Europe's summer time starts.
+        const MARCH_31_2230: i64 = 1_711_924_200;
+        /// 2024-11-04 04:30 UTC: back on standard time in New York.
+        const NOVEMBER_4_0430: i64 = 1_730_694_600;
+        /// 2024-12-31 20:00 UTC.
+        const DECEMBER_31_2000: i64 = 1_735_675_200;
+
+        fn in_zone(tz: &str, timestamp: i64) -> (u32, u32, u32) {
+            let _zone = TZ_ENV.lock().unwrap_or_else(|e| e.into_inner());
+            // SAFETY: tests reading the zone hold the lock too
+            unsafe { std::env::set_var("TZ", tz) };
+            local_date(timestamp)
+        }
+
+        #[test]
+        fn dates_follow_the_local_zone() {
+            // Fixed offsets either side of UTC
+            assert_eq!(in_zone("UTC0", DECEMBER_31_2000), (2024, 12, 31));
+            assert_eq!(in_zone("JST-9", DECEMBER_31_2000), (2025, 1, 1));
+            assert_eq!(in_zone("<+0530>-5:30", DECEMBER_31_2000), (2025, 1, 1));
+            assert_eq!(in_zone("HST10", MARCH_11_0430), (2024, 3, 10));
+
+            // Across daylight saving changes, which move midnight by an hour
+            let new_york = "EST5EDT,M3.2.0,M11.1.0";
+            assert_eq!(in_zone(new_york, MARCH_10_0430), (2024, 3, 9));
+            assert_eq!(in_zone(new_york, MARCH_11_0430), (2024, 3, 11));
+            assert_eq!(in_zone(new_york, NOVEMBER_4_0430), (2024, 11, 3));
+            let berlin = "CET-1CEST,M3.5.0,M10.5.0/3";
+            assert_eq!(in_zone(berlin, MARCH_31_2230), (2024, 4, 1));
+            assert_eq!(in_zone(berlin, DECEMBER_31_2000), (2024, 12, 31));
+
+            // An unknown zone falls back to UTC
+            assert_eq!(
+                in_zone(":Nowhere/Unknown", DECEMBER_31_2000),
+                (2024, 12, 31)
+            );
+            assert_eq!(in_zone(":Nowhere/Unknown", MARCH_10_0430), (2024, 3, 10));
+        }
+    }
+}
@@ -1,35 +1,204 @@
 //! Entry dialog implementation for text input.
 
+use std::{
+    borrow::Borrow,
+    time::{Duration, Instant},
+};
+
 use crate::{
-    backend::{CursorShape, Window, WindowEvent, create_window},
+    backend::{CursorShape, KeyEvent, Modifiers, Window, WindowEvent, create_window},
     error::Error,
-    render::{Canvas, Font},
+    render::{Canvas, Font, Rgba, rgb},
     ui::{
         BASE_BUTTON_HEIGHT, BASE_BUTTON_SPACING, BASE_CORNER_RADIUS, Colors, KEY_ESCAPE,
+        KEY_ISO_LEFT_TAB, KEY_TAB,
+        geometry::Offset,
+        glob::glob_match,
+        layout::{LabelColumn, LabelPlacement},
+        markup::{self, Markup},
+        sync_clipboard, wait_for_event_until,
         widgets::{Widget, button::Button, text_input::TextInput},
+        zoom,
     },
 };
 
 const BASE_PADDING: u32 = 20;
 const BASE_INPUT_WIDTH: u32 = 300;
+/// Height of a row in the list of suggested values.
+const BASE_VALUE_ROW_HEIGHT: u32 = 24;
+/// Suggested values shown at once.
+const MAX_SHOWN_VALUES: usize = 5;
+/// Height of the line under the entry saying why its text isn't accepted.
+const BASE_ERROR_HEIGHT: u32 = 20;
+/// Height of the password strength bar, with the space above it.
+const BASE_STRENGTH_HEIGHT: u32 = 10;
+/// Segments of the password strength bar.
+const STRENGTH_SEGMENTS: usize = 4;
+
+/// Checks the entry's text, returning why it isn't accepted.
+type Validator = Box<dyn Fn(&str) -> Result<(), String> + Send>;
 
 /// Entry dialog result.
 #[derive(Debug, Clone)]
 pub enum EntryResult {
     /// User entered text and clicked OK.
     Text(String),
+    /// User entered a username and password and clicked OK.
+    Credentials { username: String, password: String },
     /// User cancelled the dialog.
     Cancelled,
     /// Dialog was closed.
     Closed,
+    /// The timeout passed.
+    Timeout,
 }
 
 impl EntryResult {
+    /// The exit code the command-line tool uses for this result: 0 when
+    /// text is entered, 1 when cancelled or closed, 5 on timeout.
     pub fn exit_code(&self) -> i32 {
         match self {
             EntryResult::Text(_) => 0,
+            EntryResult::Credentials {
+                ..
+            } => 0,
             EntryResult::Cancelled => 1,
             EntryResult::Closed => 1,
+            EntryResult::Timeout => 5,
+        }
+    }
+}
+
+/// The values suggested by an entry and how typed text matches them.
+#[derive(Debug, Clone, Default)]
+pub struct EntryValues {
+    values: Vec<String>,
+    ignore_case: bool,
+}
+
+impl EntryValues {
+    /// Matches `values` exactly, or ignoring case.
+    pub fn new(values: Vec<String>, ignore_case: bool) -> Self {
+        Self {
+            values,
+            ignore_case,
+        }
+    }
+
+    pub fn values(&self) -> &[String] {
+        &self.values
+    }
+
+    /// The values starting with `text`, with their indices, in order.
+    pub fn matching<'a>(&'a self, text: &str) -> impl Iterator<Item = (usize, &'a str)> {
+        let text = self.fold(text);
+        self.values
+            .iter()
+            .enumerate()
+            .filter(move |(_, value)| self.fold(value).starts_with(text.as_str()))
+            .map(|(i, value)| (i, value.as_str()))
+    }
+
+    /// The value `text` is, as it was given.
+    pub fn exact(&self, text: &str) -> Option<&str> {
+        let text = self.fold(text);
+        self.values
+            .iter()
+            .find(|value| self.fold(value) == text)
+            .map(String::as_str)
+    }
+
+    /// What Tab appends to `text`: the rest of the only value starting with
+    /// it. `None` if no value or several do, or `text` is already complete.
+    pub fn completion(&self, text: &str) -> Option<String> {
+        let mut matching = self.matching(text);
+        let (_, value) = matching.next()?;
+        if matching.next().is_some() {
+            return None;
+        }
+        let suffix: String = value.chars().skip(text.chars().count()).collect();
+        (!suffix.is_empty()).then_some(suffix)
+    }
+
+    fn fold(&self, text: &str) -> String {
+        if self.ignore_case {
+            text.to_lowercase()
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// The suggested values under the entry, filtered by what's typed.
+struct ValueList {
+    x: i32,
+    y: i32,
+    width: u32,
+    row_height: u32,
+    labels: Vec<Canvas>,
+    /// Indices of the values shown, in order.
+    shown: Vec<usize>,
+    hovered: Option<usize>,
+}
+
+impl ValueList {
+    fn height(&self) -> u32 {
+        self.row_height * MAX_SHOWN_VALUES.min(self.labels.len()) as u32
+    }
+
+    /// Shows the values matching `text`.
+    fn filter(&mut self, values: &EntryValues, text: &str) {
+        self.shown = values
+            .matching(text)
+            .map(|(i, _)| i)
+            .take(MAX_SHOWN_VALUES)
+            .collect();
+        self.hovered = None;
+    }
+
+    /// The index of the value shown at (`x`, `y`).
+    fn value_at(&self, x: i32, y: i32) -> Option<usize> {
+        if x < self.x || x >= self.x + self.width as i32 || y < self.y {
+            return None;
+        }
+        let row = ((y - self.y) / self.row_height as i32) as usize;
+        self.shown.get(row).copied()
+    }
+
+    fn draw(&self, canvas: &mut Canvas, colors: &Colors, scale: f32) {
+        let radius = 5.0 * scale;
+        canvas.fill_rounded_rect(
+            self.x as f32,
+            self.y as f32,
+            self.width as f32,
+            self.height() as f32,
+            radius,
+            colors.input_bg,
+        );
+        canvas.stroke_rounded_rect(
+            self.x as f32,
+            self.y as f32,
+            self.width as f32,
+            self.height() as f32,
+            radius,
+            colors.input_border,
+            1.0,
+        );
+        let padding = (8.0 * scale) as i32;
+        for (row, &i) in self.shown.iter().enumerate() {
+            let y = self.y + (row as u32 * self.row_height) as i32;
+            if self.hovered == Some(i) {
+                canvas.fill_rect(
+                    (self.x + 1) as f32,
+                    y as f32,
+                    (self.width - 2) as f32,
+                    self.row_height as f32,
+                    colors.selection,
+                );
+            }
+            let label = &self.labels[i];
+            let label_y = y + (self.row_height as i32 - label.height() as i32) / 2;
+            canvas.draw_canvas(label, self.x + padding, label_y);
         }
     }
 }
@@ -38,11 +207,28 @@ impl EntryResult {
 pub struct EntryBuilder {
     title: String,
     text: String,
+    no_markup: bool,
     entry_text: String,
     hide_text: bool,
+    username: bool,
+    confirm: bool,
+    default_cancel: bool,
+    field_labels: LabelPlacement,
+    values: Vec<String>,
+    restrict_to_values: bool,
+    ignore_case: bool,
+    validators: Vec<Validator>,
+    max_length: Option<usize>,
+    allowed_chars: Option<String>,
+    denied_chars: String,
+    show_strength: bool,
     width: Option<u32>,
     height: Option<u32>,
-    colors: Option<&'static Colors>,
+    timeout: Option<u32>,
+    position: Option<(Offset, Offset)>,
+    colors: Option<Colors>,
+    /// Field contents and focus carried over a zoom rebuild.
+    restored: Option<(Vec<String>, usize)>,
 }
 
 impl EntryBuilder {
@@ -50,11 +236,27 @@ impl EntryBuilder {
         Self {
             title: String::new(),
             text: String::new(),
+            no_markup: false,
             entry_text: String::new(),
             hide_text: false,
+            username: false,
+            confirm: false,
+            default_cancel: false,
+            field_labels: LabelPlacement::Side,
+            values: Vec::new(),
+            restrict_to_values: false,
+            ignore_case: false,
+            validators: Vec::new(),
+            max_length: None,
+            allowed_chars: None,
+            denied_chars: String::new(),
+            show_strength: false,
             width: None,
             height: None,
+            timeout: None,
+            position: None,
             colors: None,
+            restored: None,
         }
     }
 
@@ -68,6 +270,12 @@ impl EntryBuilder {
         self
     }
 
+    /// Show the prompt text literally instead of as Pango [markup](markup).
+    pub fn no_markup(mut self, no_markup: bool) -> Self {
+        self.no_markup = no_markup;
+        self
+    }
+
     pub fn entry_text(mut self, entry_text: &str) -> Self {
         self.entry_text = entry_text.to_string();
         self
@@ -78,8 +286,116 @@ impl EntryBuilder {
         self
     }
 
-    pub fn colors(mut self, colors: &'static Colors) -> Self {
-        self.colors = Some(colors);
+    /// Add an unmasked username field above the (masked) password field.
+    /// The dialog then returns [`EntryResult::Credentials`].
+    pub fn username(mut self, username: bool) -> Self {
+        self.username = username;
+        if username {
+            self.hide_text = true;
+        }
+        self
+    }
+
+    /// Add a masked field below the password to type it again, for choosing
+    /// a new one. OK stays disabled until both match, and the password is
+    /// returned once.
+    pub fn confirm(mut self, confirm: bool) -> Self {
+        self.confirm = confirm;
+        if confirm {
+            self.hide_text = true;
+        }
+        self
+    }
+
+    /// Make Enter cancel the dialog instead of accepting the text.
+    pub fn default_cancel(mut self, default_cancel: bool) -> Self {
+        self.default_cancel = default_cancel;
+        self
+    }
+
+    /// Set where the username and password labels go (default: beside the
+    /// inputs).
+    pub fn field_labels(mut self, placement: LabelPlacement) -> Self {
+        self.field_labels = placement;
+        self
+    }
+
+    /// Suggest a value, listed under the entry and completed with Tab.
+    pub fn value(mut self, value: &str) -> Self {
+        self.values.push(value.to_string());
+        self
+    }
+
+    /// Only accept one of the suggested values: OK and Enter do nothing
+    /// until the text is one of them.
+    pub fn restrict_to_values(mut self, restrict: bool) -> Self {
+        self.restrict_to_values = restrict;
+        self
+    }
+
+    /// Match the text against the suggested values ignoring case.
+    pub fn ignore_case(mut self, ignore_case: bool) -> Self {
+        self.ignore_case = ignore_case;
+        self
+    }
+
+    /// Only accept text matching a shell glob (`*`, `?` and `[a-z]`) as a
+    /// whole.
+    pub fn pattern(self, pattern: &str) -> Self {
+        let pattern = pattern.to_string();
+        self.validator(move |text| {
+            if glob_match(&pattern, text) {
+                Ok(())
+            } else {
+                Err(format!("Must match {pattern}"))
+            }
+        })
+    }
+
+    /// Only accept text `validator` returns `Ok` for. Otherwise OK and Enter
+    /// do nothing, and its error is shown under the entry. It runs after
+    /// every edit, so should be quick. With several, the first error shows.
+    ///
+    /// With [`username`](Self::username), the password is checked.
+    pub fn validator(
+        mut self,
+        validator: impl Fn(&str) -> Result<(), String> + Send + 'static,
+    ) -> Self {
+        self.validators.push(Box::new(validator));
+        self
+    }
+
+    /// Ignore typing beyond `max_length` characters, and cut pastes short
+    /// there. The count is shown in the entry.
+    ///
+    /// With [`username`](Self::username), this limits the password.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Only let these characters be typed or pasted, such as
+    /// `"0123456789abcdef"` for hex digits.
+    pub fn allow_chars(mut self, chars: &str) -> Self {
+        self.allowed_chars = Some(chars.to_string());
+        self
+    }
+
+    /// Never let these characters be typed or pasted.
+    pub fn deny_chars(mut self, chars: &str) -> Self {
+        self.denied_chars.push_str(chars);
+        self
+    }
+
+    /// Show how strong the password is in a bar under the entry, judged by
+    /// its length and the kinds of characters in it.
+    pub fn show_strength(mut self, show_strength: bool) -> Self {
+        self.show_strength = show_strength;
+        self
+    }
+
+    pub fn colors(mut self, colors: impl Borrow<Colors>) -> Self {
+        self.colors = Some(*colors.borrow());
         self
     }
 
@@ -93,27 +409,126 @@ impl EntryBuilder {
         self
     }
 
-    pub fn show(self) -> Result<EntryResult, Error> {
-        let colors = self.colors.unwrap_or_else(|| crate::ui::detect_theme());
+    /// Set timeout in seconds. Dialog will auto-close after this time.
+    pub fn timeout(mut self, seconds: u32) -> Self {
+        self.timeout = Some(seconds);
+        self
+    }
+
+    /// Request a window position. Ignored where the platform doesn't allow it.
+    pub fn position(mut self, x: Offset, y: Offset) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    pub fn show(mut self) -> Result<EntryResult, Error> {
+        if self.restrict_to_values && self.values.is_empty() {
+            return Err(Error::InvalidConfig(
+                "an entry restricted to its values needs at least one value".to_string(),
+            ));
+        }
+        let deadline = self
+            .timeout
+            .map(|secs| Instant::now() + Duration::from_secs(secs as u64));
+        loop {
+            if let Some(result) = self.run(deadline)? {
+                return Ok(result);
+            }
+        }
+    }
+
+    /// Labels of the fields from top to bottom, or none for a single field.
+    fn row_labels(&self) -> Vec<&'static str> {
+        let mut labels = Vec::new();
+        if self.username {
+            labels.extend(["Username", "Password"]);
+        }
+        if self.confirm {
+            if labels.is_empty() {
+                labels.push("Password");
+            }
+            labels.push("Confirm");
+        }
+        labels
+    }
+
+    /// Shows the dialog until it's answered, or returns `None` to be run
+    /// again after a [zoom](crate::ui::zoom) change.
+    fn run(&mut self, deadline: Option<Instant>) -> Result<Option<EntryResult>, Error> {
+        let colors = &self.colors.unwrap_or_else(|| *crate::ui::detect_theme());
+        let prompt = if self.no_markup {
+            Markup::plain(&self.text)
+        } else {
+            markup::parse(&self.text)
+        };
+        let row_labels = self.row_labels();
+        // Suggested values go with the single text field
+        let values = EntryValues::new(
+            if !row_labels.is_empty() {
+                Vec::new()
+            } else {
+                self.values.clone()
+            },
+            self.ignore_case,
+        );
+        let shown_values = MAX_SHOWN_VALUES.min(values.values().len()) as u32;
 
         // First pass: calculate LOGICAL dimensions using scale 1.0
         let temp_font = Font::load(1.0);
-        let temp_ok = Button::new("OK", &temp_font, 1.0);
-        let temp_cancel = Button::new("Cancel", &temp_font, 1.0);
+        let temp_ok = Button::new("_OK", &temp_font, 1.0);
+        let temp_cancel = Button::new("_Cancel", &temp_font, 1.0);
+        let temp_input = TextInput::new(BASE_INPUT_WIDTH);
+
+        let logical_buttons_width = temp_ok.width() + temp_cancel.width() + BASE_BUTTON_SPACING;
+        let logical_content_width = BASE_INPUT_WIDTH.max(logical_buttons_width);
+        let calc_width = logical_content_width + BASE_PADDING * 2;
+        // A narrower --width is clamped so the buttons don't overlap
+        let logical_width = self.width.map_or(calc_width, |w| {
+            w.max(logical_buttons_width + BASE_PADDING * 2)
+        });
+
+        // The prompt wraps to whatever width the dialog ends up with
         let temp_prompt_height = if !self.text.is_empty() {
             temp_font
-                .render(&self.text)
-                .with_max_width(BASE_INPUT_WIDTH as f32)
+                .render(&prompt.text)
+                .with_runs(&prompt.runs)
+                .with_max_width((logical_width - BASE_PADDING * 2) as f32)
                 .finish()
                 .height()
         } else {
             0
         };
-        let temp_input = TextInput::new(BASE_INPUT_WIDTH);
-
-        let logical_buttons_width = temp_ok.width() + temp_cancel.width() + BASE_BUTTON_SPACING;
-        let logical_content_width = BASE_INPUT_WIDTH.max(logical_buttons_width);
-        let calc_width = logical_content_width + BASE_PADDING * 2;
+        // With several fields, each input is a row with a label
+        let inputs_height = if !row_labels.is_empty() {
+            let column = LabelColumn::new(
+                &temp_font,
+                &row_labels,
+                logical_width - BASE_PADDING * 2,
+                self.field_labels,
+                1.0,
+            );
+            row_labels
+                .iter()
+                .map(|label| {
+                    let label = column.render_label(&temp_font, label, colors.text);
+                    column.place(&label, 0, 0, temp_input.height()).height + BASE_BUTTON_SPACING
+                })
+                .sum()
+        } else if shown_values > 0 {
+            // The values are listed just under the input
+            temp_input.height() + 2 + BASE_VALUE_ROW_HEIGHT * shown_values + BASE_BUTTON_SPACING
+        } else {
+            temp_input.height() + BASE_BUTTON_SPACING
+        };
+        let validating = !self.validators.is_empty();
+        let show_strength = self.show_strength;
+        let inputs_height = inputs_height
+            + if validating { BASE_ERROR_HEIGHT } else { 0 }
+            + if show_strength {
+                BASE_STRENGTH_HEIGHT
+            } else {
+                0
+            };
         let calc_height = BASE_PADDING * 3
             + temp_prompt_height
             + (if temp_prompt_height > 0 {
@@ -121,8 +536,7 @@ impl EntryBuilder {
             } else {
                 0
             })
-            + temp_input.height()
-            + BASE_BUTTON_SPACING
+            + inputs_height
             + BASE_BUTTON_HEIGHT;
 
         drop(temp_font);
@@ -130,9 +544,8 @@ impl EntryBuilder {
         drop(temp_cancel);
         drop(temp_input);
 
-        // Use custom dimensions if provided, otherwise use calculated defaults
-        let logical_width = self.width.unwrap_or(calc_width) as u16;
-        let logical_height = self.height.unwrap_or(calc_height) as u16;
+        let logical_width = logical_width as u16;
+        let logical_height = self.height.map_or(calc_height, |h| h.max(calc_height)) as u16;
 
         // Create window with LOGICAL dimensions
         let mut window = create_window(logical_width, logical_height)?;
@@ -155,24 +568,67 @@ impl EntryBuilder {
         // Scale dimensions for physical rendering
         let padding = (BASE_PADDING as f32 * scale) as u32;
         let button_spacing = (BASE_BUTTON_SPACING as f32 * scale) as u32;
+        let error_height = if validating {
+            (BASE_ERROR_HEIGHT as f32 * scale) as i32
+        } else {
+            0
+        };
+        let strength_height = if show_strength {
+            (BASE_STRENGTH_HEIGHT as f32 * scale) as i32
+        } else {
+            0
+        };
 
-        // Input should fill available width
-        let input_width = physical_width - (padding * 2);
+        // Inputs fill the available width, less the label column if any
+        let column = (!row_labels.is_empty()).then(|| {
+            LabelColumn::new(
+                &font,
+                &row_labels,
+                physical_width - padding * 2,
+                self.field_labels,
+                scale,
+            )
+        });
+        let input_width = column.map_or(physical_width - padding * 2, |c| c.input_width());
 
         // Create buttons at physical scale
-        let mut ok_button = Button::new("OK", &font, scale);
-        let mut cancel_button = Button::new("Cancel", &font, scale);
+        let mut ok_button = Button::new("_OK", &font, scale);
+        let mut cancel_button = Button::new("_Cancel", &font, scale);
+        cancel_button.set_default(self.default_cancel);
 
-        // Create text input at physical scale
-        let mut input = TextInput::new(input_width)
-            .with_password(self.hide_text)
-            .with_default_text(&self.entry_text);
-        input.set_focus(true);
+        // Create text inputs at physical scale. With a username field, the
+        // masked input is the second one, and the confirmation follows it.
+        let mut inputs = Vec::with_capacity(3);
+        if self.username {
+            inputs.push(TextInput::new(input_width));
+        }
+        let main_input = inputs.len();
+        let text_input = || {
+            TextInput::new(input_width)
+                .with_password(self.hide_text)
+                .with_reveal_button(true)
+                .with_max_length(self.max_length)
+                .with_char_filter(self.allowed_chars.as_deref(), &self.denied_chars)
+        };
+        inputs.push(text_input().with_default_text(&self.entry_text));
+        let confirm_input = self.confirm.then(|| {
+            inputs.push(text_input());
+            main_input + 1
+        });
+        let mut focused_index = 0usize;
+        if let Some((texts, focused)) = self.restored.take() {
+            for (input, text) in inputs.iter_mut().zip(&texts) {
+                input.set_text(text);
+            }
+            focused_index = focused.min(inputs.len() - 1);
+        }
+        inputs[focused_index].set_focus(true);
 
         // Render prompt text at physical scale (wrapped to fit)
         let prompt_canvas = if !self.text.is_empty() {
             Some(
-                font.render(&self.text)
+                font.render(&prompt.text)
+                    .with_runs(&prompt.runs)
                     .with_color(colors.text)
                     .with_max_width((physical_width - padding * 2) as f32)
                     .finish(),
@@ -189,9 +645,61 @@ impl EntryBuilder {
             y += prompt_height as i32 + (BASE_BUTTON_SPACING as f32 * scale) as i32;
         }
 
-        // Input position
-        input.set_position(padding as i32, y);
-        y += input.height() as i32 + (BASE_BUTTON_SPACING as f32 * scale) as i32;
+        // Input positions, with their labels when there are several. The
+        // main input is the one validated, with room for the strength bar
+        // under it; the error goes under the last one.
+        let mut labels = Vec::new();
+        let last_input = inputs.len() - 1;
+        for (i, input) in inputs.iter_mut().enumerate() {
+            let row_height = match &column {
+                Some(column) => {
+                    let label = column.render_label(&font, row_labels[i], colors.text);
+                    let row = column.place(&label, padding as i32, y, input.height());
+                    input.set_position(row.input_x, row.input_y);
+                    labels.push((label, row.label_x, row.label_y));
+                    row.height
+                }
+                None => {
+                    input.set_position(padding as i32, y);
+                    input.height()
+                }
+            };
+            if i == main_input {
+                y += strength_height;
+            }
+            if i == last_input {
+                y += error_height;
+            }
+            y += row_height as i32 + (BASE_BUTTON_SPACING as f32 * scale) as i32;
+        }
+        let error_x = inputs[last_input].x();
+        let strength_y = inputs[main_input].y() + inputs[main_input].height() as i32;
+        let error_y = inputs[last_input].y()
+            + inputs[last_input].height() as i32
+            + if last_input == main_input {
+                strength_height
+            } else {
+                0
+            };
+        let mut value_list = (shown_values > 0).then(|| {
+            let input = &inputs[0];
+            ValueList {
+                x: input.x(),
+                y: error_y + error_height + (2.0 * scale) as i32,
+                width: input.width(),
+                row_height: (BASE_VALUE_ROW_HEIGHT as f32 * scale) as u32,
+                labels: values
+                    .values()
+                    .iter()
+                    .map(|value| font.render(value).with_color(colors.text).finish())
+                    .collect(),
+                shown: Vec::new(),
+                hovered: None,
+            }
+        });
+        if let Some(list) = &value_list {
+            y = list.y + list.height() as i32 + button_spacing as i32;
+        }
 
         // Button positions (right-aligned)
         let mut button_x = physical_width as i32 - padding as i32;
@@ -208,7 +716,10 @@ impl EntryBuilder {
                     colors: &Colors,
                     font: &Font,
                     prompt_canvas: &Option<Canvas>,
-                    input: &TextInput,
+                    labels: &[(Canvas, i32, i32)],
+                    inputs: &[TextInput],
+                    error: &Option<Canvas>,
+                    value_list: &Option<ValueList>,
                     ok_button: &Button,
                     cancel_button: &Button,
                     padding: u32,
@@ -232,21 +743,105 @@ impl EntryBuilder {
                 canvas.draw_canvas(prompt, padding as i32, prompt_y);
             }
 
-            // Draw input
-            input.draw_to(canvas, colors, font);
+            // Draw labels and inputs
+            for (label, x, y) in labels {
+                canvas.draw_canvas(label, *x, *y);
+            }
+            for input in inputs {
+                input.draw_to(canvas, colors, font);
+            }
+            if show_strength {
+                let input = &inputs[main_input];
+                draw_strength(
+                    canvas,
+                    colors,
+                    input.x(),
+                    strength_y + strength_height / 2,
+                    input.width(),
+                    scale,
+                    password_strength(input.text()),
+                );
+            }
+            if let Some(error) = error {
+                let y = error_y + (error_height - error.height() as i32) / 2;
+                canvas.draw_canvas(error, error_x, y);
+            }
+            if let Some(list) = value_list {
+                list.draw(canvas, colors, scale);
+            }
 
             // Draw buttons
             ok_button.draw_to(canvas, colors, font);
             cancel_button.draw_to(canvas, colors, font);
         };
 
+        // Values, and so restricting to them, only go with a single field
+        let restrict = self.restrict_to_values && row_labels.is_empty();
+        let validators = &self.validators;
+        let validate = |text: &str| validators.iter().try_for_each(|validator| validator(text));
+        // The confirmation is `Some(false)` while it doesn't match, or is
+        // still empty
+        let confirmed = |inputs: &[TextInput]| {
+            confirm_input.map(|i| {
+                !inputs[i].text().is_empty() && inputs[i].text() == inputs[main_input].text()
+            })
+        };
+        // Filters the listed values, offers completion and checks the text
+        // against them, the validators and the confirmation after it changes
+        let mut last_texts = Vec::new();
+        let mut confirm_touched = false;
+        let mut error = None;
+        let mut sync_values = |inputs: &mut [TextInput],
+                               value_list: &mut Option<ValueList>,
+                               ok_button: &mut Button,
+                               error: &mut Option<Canvas>| {
+            if last_texts
+                .iter()
+                .map(String::as_str)
+                .eq(inputs.iter().map(TextInput::text))
+            {
+                return;
+            }
+            last_texts = inputs
+                .iter()
+                .map(|input| input.text().to_string())
+                .collect();
+            let confirmed = confirmed(inputs);
+            // Only complain about the confirmation once it's been typed in
+            confirm_touched |= confirm_input.is_some_and(|i| !inputs[i].text().is_empty());
+            let mismatched = confirm_touched && confirmed == Some(false);
+            let input = &mut inputs[main_input];
+            if let Some(list) = value_list {
+                list.filter(&values, input.text());
+                input.set_completion(values.completion(input.text()));
+            }
+            let invalid = validate(input.text()).err();
+            let valid = invalid.is_none() && (!restrict || values.exact(input.text()).is_some());
+            input.set_invalid(!valid);
+            if let Some(i) = confirm_input {
+                inputs[i].set_invalid(mismatched);
+            }
+            let message =
+                invalid.or_else(|| mismatched.then(|| "Passwords do not match".to_string()));
+            *error = message.as_ref().map(|message| {
+                font.render(message)
+                    .with_color(colors.input_border_invalid)
+                    .finish()
+            });
+            ok_button.set_enabled(valid && confirmed != Some(false));
+        };
+        sync_values(&mut inputs, &mut value_list, &mut ok_button, &mut error);
+
         // Initial draw
         draw(
             &mut canvas,
             colors,
             &font,
             &prompt_canvas,
-            &input,
+            &labels,
+            &inputs,
+            &error,
+            &value_list,
             &ok_button,
             &cancel_button,
             padding,
@@ -254,16 +849,54 @@ impl EntryBuilder {
             scale,
         );
         window.set_contents(&canvas)?;
+        if let Some((x, y)) = self.position {
+            window.set_position(x, y)?;
+        }
         window.show()?;
 
+        let username = self.username;
+        // A restricted entry gives the value as it was listed, whatever its
+        // case was typed in; `None` while the text isn't one of them or
+        // isn't valid
+        let make_result = |inputs: &[TextInput]| {
+            if validate(inputs[main_input].text()).is_err() || confirmed(inputs) == Some(false) {
+                None
+            } else if username {
+                Some(EntryResult::Credentials {
+                    username: inputs[0].text().to_string(),
+                    password: inputs[1].text().to_string(),
+                })
+            } else if restrict {
+                values
+                    .exact(inputs[main_input].text())
+                    .map(|value| EntryResult::Text(value.to_string()))
+            } else {
+                Some(EntryResult::Text(inputs[main_input].text().to_string()))
+            }
+        };
+        // What Enter in the last field gives
+        let default_cancel = self.default_cancel;
+        let enter_result = |inputs: &[TextInput]| {
+            if default_cancel {
+                Some(EntryResult::Cancelled)
+            } else {
+                make_result(inputs)
+            }
+        };
+
         // Event loop
         let mut window_dragging = false;
+        let mut cursor_x = 0i32;
+        let mut cursor_y = 0i32;
         loop {
-            let event = window.wait_for_event()?;
+            let mut needs_redraw = false;
+            let Some(event) = wait_for_event_until(&mut window, deadline)? else {
+                return Ok(Some(EntryResult::Timeout));
+            };
 
             match &event {
                 WindowEvent::CloseRequested => {
-                    return Ok(EntryResult::Closed);
+                    return Ok(Some(EntryResult::Closed));
                 }
                 WindowEvent::RedrawRequested => {
                     draw(
@@ -271,7 +904,10 @@ impl EntryBuilder {
                         colors,
                         &font,
                         &prompt_canvas,
-                        &input,
+                        &labels,
+                        &inputs,
+                        &error,
+                        &value_list,
                         &ok_button,
                         &cancel_button,
                         padding,
@@ -286,33 +922,78 @@ impl EntryBuilder {
                         window_dragging = false;
                     }
 
-                    let cursor_x = pos.x as i32;
-                    let cursor_y = pos.y as i32;
-
-                    // Check if cursor is over the input field
-                    let ix = input.x();
-                    let iy = input.y();
-                    let iw = input.width();
-                    let ih = input.height();
+                    cursor_x = pos.x as i32;
+                    cursor_y = pos.y as i32;
 
-                    let over_input = cursor_x >= ix
-                        && cursor_x < ix + iw as i32
-                        && cursor_y >= iy
-                        && cursor_y < iy + ih as i32;
+                    // Check if cursor is over an input field
+                    let over_input = inputs.iter().any(|input| {
+                        cursor_x >= input.x()
+                            && cursor_x < input.x() + input.width() as i32
+                            && cursor_y >= input.y()
+                            && cursor_y < input.y() + input.height() as i32
+                    });
 
                     let _ = window.set_cursor(if over_input {
                         CursorShape::Text
                     } else {
                         CursorShape::Default
                     });
+
+                    // The focused input sees this below; the others need it
+                    // for their eye buttons' hover
+                    for (i, input) in inputs.iter_mut().enumerate() {
+                        if i != focused_index {
+                            needs_redraw |= input.process_event(&event);
+                        }
+                    }
+
+                    if let Some(list) = &mut value_list {
+                        let hovered = list.value_at(cursor_x, cursor_y);
+                        if hovered != list.hovered {
+                            list.hovered = hovered;
+                            needs_redraw = true;
+                        }
+                    }
                 }
                 WindowEvent::KeyPress(key_event) => {
-                    if key_event.keysym == KEY_ESCAPE {
-                        return Ok(EntryResult::Closed);
+                    if zoom::handle_key(key_event) {
+                        let texts = inputs.iter().map(|i| i.text().to_string()).collect();
+                        self.restored = Some((texts, focused_index));
+                        return Ok(None);
+                    }
+                    match key_event.keysym {
+                        KEY_ESCAPE => return Ok(Some(EntryResult::Closed)),
+                        KEY_TAB | KEY_ISO_LEFT_TAB if inputs.len() > 1 => {
+                            cycle_focus(&mut inputs, &mut focused_index, key_event);
+                            needs_redraw = true;
+                        }
+                        _ => {}
                     }
                 }
                 WindowEvent::ButtonPress(crate::backend::MouseButton::Left, _) => {
-                    window_dragging = true;
+                    // Clicking a listed value puts it in the entry
+                    if let Some(i) = value_list
+                        .as_ref()
+                        .and_then(|list| list.value_at(cursor_x, cursor_y))
+                    {
+                        inputs[0].set_text(&values.values()[i]);
+                        needs_redraw = true;
+                    } else {
+                        window_dragging = true;
+                    }
+                    // Focus the clicked input field
+                    if let Some(i) = inputs.iter().position(|input| {
+                        cursor_x >= input.x()
+                            && cursor_x < input.x() + input.width() as i32
+                            && cursor_y >= input.y()
+                            && cursor_y < input.y() + input.height() as i32
+                    }) && i != focused_index
+                    {
+                        inputs[focused_index].set_focus(false);
+                        focused_index = i;
+                        inputs[focused_index].set_focus(true);
+                        needs_redraw = true;
+                    }
                 }
                 WindowEvent::ButtonRelease(crate::backend::MouseButton::Left, _) => {
                     window_dragging = false;
@@ -321,11 +1002,22 @@ impl EntryBuilder {
             }
 
             // Process input events
-            let mut needs_redraw = input.process_event(&event);
+            if inputs[focused_index].process_event(&event) {
+                needs_redraw = true;
+            }
+            needs_redraw |= sync_clipboard(&mut window, &mut inputs[focused_index])?;
 
-            // Check for Enter key submission
-            if input.was_submitted() {
-                return Ok(EntryResult::Text(input.text().to_string()));
+            // Check for Enter key submission. Enter in the username field
+            // moves on to the password field instead.
+            if inputs[focused_index].was_submitted() {
+                if focused_index + 1 < inputs.len() {
+                    inputs[focused_index].set_focus(false);
+                    focused_index += 1;
+                    inputs[focused_index].set_focus(true);
+                    needs_redraw = true;
+                } else if let Some(result) = enter_result(&inputs) {
+                    return Ok(Some(result));
+                }
             }
 
             // Process button events
@@ -335,26 +1027,49 @@ impl EntryBuilder {
             if cancel_button.process_event(&event) {
                 needs_redraw = true;
             }
+            // Letters go to the input, so only Alt presses the buttons
+            ok_button.process_mnemonic(&event, false);
+            cancel_button.process_mnemonic(&event, false);
 
-            if ok_button.was_clicked() {
-                return Ok(EntryResult::Text(input.text().to_string()));
+            if ok_button.was_clicked()
+                && let Some(result) = make_result(&inputs)
+            {
+                return Ok(Some(result));
             }
             if cancel_button.was_clicked() {
-                return Ok(EntryResult::Cancelled);
+                return Ok(Some(EntryResult::Cancelled));
             }
 
             // Batch process pending events
             while let Some(event) = window.poll_for_event()? {
                 match &event {
                     WindowEvent::CloseRequested => {
-                        return Ok(EntryResult::Closed);
+                        return Ok(Some(EntryResult::Closed));
+                    }
+                    WindowEvent::KeyPress(key_event) if key_event.keysym == KEY_ESCAPE => {
+                        return Ok(Some(EntryResult::Closed));
+                    }
+                    WindowEvent::KeyPress(key_event)
+                        if matches!(key_event.keysym, KEY_TAB | KEY_ISO_LEFT_TAB)
+                            && inputs.len() > 1 =>
+                    {
+                        cycle_focus(&mut inputs, &mut focused_index, key_event);
+                        needs_redraw = true;
                     }
                     _ => {
-                        if input.process_event(&event) {
+                        if inputs[focused_index].process_event(&event) {
                             needs_redraw = true;
                         }
-                        if input.was_submitted() {
-                            return Ok(EntryResult::Text(input.text().to_string()));
+                        needs_redraw |= sync_clipboard(&mut window, &mut inputs[focused_index])?;
+                        if inputs[focused_index].was_submitted() {
+                            if focused_index + 1 < inputs.len() {
+                                inputs[focused_index].set_focus(false);
+                                focused_index += 1;
+                                inputs[focused_index].set_focus(true);
+                                needs_redraw = true;
+                            } else if let Some(result) = enter_result(&inputs) {
+                                return Ok(Some(result));
+                            }
                         }
                         if ok_button.process_event(&event) {
                             needs_redraw = true;
@@ -362,23 +1077,31 @@ impl EntryBuilder {
                         if cancel_button.process_event(&event) {
                             needs_redraw = true;
                         }
-                        if ok_button.was_clicked() {
-                            return Ok(EntryResult::Text(input.text().to_string()));
+                        ok_button.process_mnemonic(&event, false);
+                        cancel_button.process_mnemonic(&event, false);
+                        if ok_button.was_clicked()
+                            && let Some(result) = make_result(&inputs)
+                        {
+                            return Ok(Some(result));
                         }
                         if cancel_button.was_clicked() {
-                            return Ok(EntryResult::Cancelled);
+                            return Ok(Some(EntryResult::Cancelled));
                         }
                     }
                 }
             }
 
             if needs_redraw {
+                sync_values(&mut inputs, &mut value_list, &mut ok_button, &mut error);
                 draw(
                     &mut canvas,
                     colors,
                     &font,
                     &prompt_canvas,
-                    &input,
+                    &labels,
+                    &inputs,
+                    &error,
+                    &value_list,
                     &ok_button,
                     &cancel_button,
                     padding,
@@ -391,6 +1114,76 @@ impl EntryBuilder {
     }
 }
 
+/// Moves the focus to the next input for Tab, or the previous one for
+/// Shift+Tab, wrapping around.
+fn cycle_focus(inputs: &mut [TextInput], focused: &mut usize, key: &KeyEvent) {
+    let backward = key.keysym == KEY_ISO_LEFT_TAB || key.modifiers.contains(Modifiers::SHIFT);
+    inputs[*focused].set_focus(false);
+    *focused = if backward {
+        (*focused + inputs.len() - 1) % inputs.len()
+    } else {
+        (*focused + 1) % inputs.len()
+    };
+    inputs[*focused].set_focus(true);
+}
+
+/// How strong a password looks, from 0 (empty) to [`STRENGTH_SEGMENTS`]:
+/// a point for every four characters and for each kind of character
+/// (lowercase, uppercase, digits, others) past the first, halved.
+fn password_strength(password: &str) -> usize {
+    if password.is_empty() {
+        return 0;
+    }
+    let length_points = (password.chars().count() / 4).min(4);
+    let kinds = [
+        password.chars().any(char::is_lowercase),
+        password.chars().any(char::is_uppercase),
+        password.chars().any(|c| c.is_ascii_digit()),
+        password.chars().any(|c| !c.is_alphanumeric()),
+    ];
+    let kind_points = kinds.iter().filter(|&&kind| kind).count().saturating_sub(1);
+    (length_points + kind_points)
+        .div_ceil(2)
+        .clamp(1, STRENGTH_SEGMENTS)
+}
+
+/// Draws the strength bar centered on `y`, its lit segments going from red
+/// for a weak password to green for a strong one.
+fn draw_strength(
+    canvas: &mut Canvas,
+    colors: &Colors,
+    x: i32,
+    y: i32,
+    width: u32,
+    scale: f32,
+    strength: usize,
+) {
+    const STRENGTH_COLORS: [Rgba; STRENGTH_SEGMENTS] = [
+        rgb(210, 60, 60),
+        rgb(230, 150, 50),
+        rgb(200, 190, 50),
+        rgb(70, 170, 80),
+    ];
+    let gap = 4.0 * scale;
+    let height = 4.0 * scale;
+    let segment = (width as f32 - gap * (STRENGTH_SEGMENTS - 1) as f32) / STRENGTH_SEGMENTS as f32;
+    for i in 0..STRENGTH_SEGMENTS {
+        let color = if i < strength {
+            STRENGTH_COLORS[strength - 1]
+        } else {
+            colors.input_border
+        };
+        canvas.fill_rounded_rect(
+            x as f32 + i as f32 * (segment + gap),
+            y as f32 - height / 2.0,
+            segment,
+            height,
+            height / 2.0,
+            color,
+        );
+    }
+}
+
 impl Default for EntryBuilder {
     fn default() -> Self {
         Self::new()
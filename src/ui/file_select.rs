@@ -1,26 +1,40 @@
 //! File selection dialog implementation with enhanced UI.
 
 use std::{
-    collections::HashSet,
-    fs::{self, Metadata},
+    borrow::Borrow,
+    cell::OnceCell,
+    collections::{HashSet, VecDeque},
+    fs,
+    io::{self, Write},
+    os::unix::ffi::OsStrExt,
     path::{Path, PathBuf},
-    time::SystemTime,
+    time::{Duration, Instant, SystemTime},
 };
 
 use crate::{
-    backend::{MouseButton, Window, WindowEvent, create_window},
+    backend::{Modifiers, MouseButton, Window, WindowEvent, create_window},
     error::Error,
-    render::{Canvas, Font, Rgba, rgb},
+    render::{Canvas, EllipsizeMode, Font, Rgba, rgb},
     ui::{
-        BASE_BUTTON_HEIGHT, BASE_BUTTON_SPACING, BASE_CORNER_RADIUS, Colors, KEY_BACKSPACE,
-        KEY_DOWN, KEY_ESCAPE, KEY_RETURN, KEY_UP,
-        widgets::{Widget, button::Button, text_input::TextInput},
+        BASE_BUTTON_HEIGHT, BASE_BUTTON_SPACING, BASE_CORNER_RADIUS, Colors, DialogResult,
+        KEY_BACKSPACE, KEY_DOWN, KEY_END, KEY_ESCAPE, KEY_HOME, KEY_L, KEY_PAGE_DOWN, KEY_PAGE_UP,
+        KEY_RETURN, KEY_UP,
+        geometry::Offset,
+        glob::glob_match,
+        layout::{LabelColumn, LabelPlacement},
+        wait_for_event_until,
+        widgets::{
+            MULTI_CLICK_TIME, Widget, button::Button, combo_box::ComboBox, text_input::TextInput,
+        },
+        zoom,
     },
 };
 
 // Layout constants (logical, at scale 1.0)
 const BASE_WINDOW_WIDTH: u32 = 700;
 const BASE_WINDOW_HEIGHT: u32 = 500;
+const BASE_MIN_WINDOW_WIDTH: u32 = 520;
+const BASE_MIN_WINDOW_HEIGHT: u32 = 360;
 const BASE_PADDING: u32 = 12;
 const BASE_SIDEBAR_WIDTH: u32 = 160;
 const BASE_TOOLBAR_HEIGHT: u32 = 36;
@@ -34,10 +48,12 @@ const BASE_SECTION_HEADER_HEIGHT: u32 = 22;
 const BASE_NAME_COL_WIDTH: u32 = 280;
 const BASE_SIZE_COL_WIDTH: u32 = 80;
 const BASE_COLUMN_HEADER_HEIGHT: u32 = 28;
-const BASE_FILENAME_ROW_HEIGHT: u32 = 58;
+/// Space between the filename row and the file list.
+const BASE_FILENAME_ROW_GAP: u32 = 6;
 const BASE_FOOTER_HEIGHT: u32 = 44;
 const BASE_CONTENT_GAP: u32 = 12;
-const BASE_FILENAME_LABEL_HEIGHT: u32 = 20;
+/// A pause this long starts a new typeahead search.
+const TYPEAHEAD_RESET: Duration = Duration::from_secs(1);
 
 /// File selection dialog result.
 #[derive(Debug, Clone)]
@@ -46,14 +62,235 @@ pub enum FileSelectResult {
     SelectedMultiple(Vec<PathBuf>),
     Cancelled,
     Closed,
+    /// The timeout passed.
+    Timeout,
 }
 
 impl FileSelectResult {
+    /// The exit code the command-line tool uses for this result: 0 when
+    /// something is selected, 1 when cancelled or closed, 5 on timeout.
     pub fn exit_code(&self) -> i32 {
         match self {
             FileSelectResult::Selected(_) | FileSelectResult::SelectedMultiple(_) => 0,
             FileSelectResult::Cancelled => 1,
             FileSelectResult::Closed => 1,
+            FileSelectResult::Timeout => 5,
+        }
+    }
+
+    /// Returns the selected paths (empty if nothing was selected).
+    pub fn paths(&self) -> Vec<&Path> {
+        match self {
+            FileSelectResult::Selected(path) => vec![path.as_path()],
+            FileSelectResult::SelectedMultiple(paths) => {
+                paths.iter().map(PathBuf::as_path).collect()
+            }
+            FileSelectResult::Cancelled | FileSelectResult::Closed | FileSelectResult::Timeout => {
+                Vec::new()
+            }
+        }
+    }
+
+    /// Writes the selected paths to `out` in the given format.
+    ///
+    /// Nothing is written if nothing was selected.
+    pub fn write_paths(&self, out: &mut impl Write, format: PathFormat<'_>) -> io::Result<()> {
+        let paths = self.paths();
+        if paths.is_empty() {
+            return Ok(());
+        }
+        match format {
+            PathFormat::Separated(separator) => {
+                for (i, path) in paths.iter().enumerate() {
+                    if i > 0 {
+                        out.write_all(separator.as_bytes())?;
+                    }
+                    out.write_all(path.as_os_str().as_bytes())?;
+                }
+                out.write_all(b"\n")
+            }
+            PathFormat::Null => {
+                for path in paths {
+                    out.write_all(path.as_os_str().as_bytes())?;
+                    out.write_all(b"\0")?;
+                }
+                Ok(())
+            }
+            PathFormat::Json => {
+                let items: Vec<String> = paths
+                    .iter()
+                    .map(|p| json_string(&p.to_string_lossy()))
+                    .collect();
+                writeln!(out, "[{}]", items.join(","))
+            }
+        }
+    }
+}
+
+/// Output format for [`FileSelectResult::write_paths`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathFormat<'a> {
+    /// Paths joined by a separator, followed by a newline.
+    Separated(&'a str),
+    /// Each path followed by a NUL byte, as for `xargs -0`.
+    Null,
+    /// A JSON array of strings on a single line.
+    Json,
+}
+
+/// What the file selection dialog is picking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileSelectMode {
+    /// Existing files (`--file-selection`).
+    Open,
+    /// A file name to save to (`--save`).
+    Save,
+    /// Directories (`--directory`).
+    Directory,
+}
+
+/// Column the file list is sorted by. Folders always come first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FileSort {
+    #[default]
+    Name,
+    Size,
+    Modified,
+}
+
+/// How the user acted on the file list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Activation {
+    /// A single click on an entry.
+    Click,
+    /// A double click on an entry.
+    DoubleClick,
+    /// Enter in the file list or the file name field.
+    Enter,
+    /// The Open/Save button.
+    Ok,
+}
+
+/// What an [`Activation`] applies to: the clicked entry for clicks, the
+/// selection otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ActivationTarget {
+    Nothing,
+    File,
+    Directory,
+    /// More than one selected entry (`--multiple`).
+    Several,
+}
+
+/// What the dialog does in response to an [`Activation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ActivationOutcome {
+    /// Nothing happens.
+    Ignore,
+    /// The entry becomes (or, with `--multiple`, toggles) the selection.
+    Select,
+    /// The dialog navigates into the directory.
+    EnterDirectory,
+    /// The target is returned as the result.
+    Accept,
+    /// The name typed in the file name field is returned (save mode).
+    AcceptTyped,
+    /// The directory being shown is returned (directory mode).
+    AcceptCurrentDirectory,
+}
+
+/// Decides what an activation does.
+///
+/// A click selects. Double-clicking a directory enters it and double-clicking
+/// a file accepts it. Enter and OK accept a typed name in save mode, enter a
+/// selected directory (OK accepts it instead in directory mode) and accept
+/// a selected file; with nothing selected, directory mode accepts the
+/// directory being shown.
+pub(crate) fn activation_outcome(
+    mode: FileSelectMode,
+    action: Activation,
+    target: ActivationTarget,
+    has_typed_name: bool,
+) -> ActivationOutcome {
+    use ActivationOutcome::*;
+
+    match action {
+        Activation::Click => {
+            match target {
+                ActivationTarget::Nothing => Ignore,
+                _ => Select,
+            }
+        }
+        Activation::DoubleClick => {
+            match target {
+                ActivationTarget::Directory => EnterDirectory,
+                // Only directories are listed in directory mode
+                ActivationTarget::File if mode != FileSelectMode::Directory => Accept,
+                _ => Ignore,
+            }
+        }
+        Activation::Enter | Activation::Ok => {
+            if mode == FileSelectMode::Save && has_typed_name {
+                return AcceptTyped;
+            }
+            match target {
+                ActivationTarget::Nothing if mode == FileSelectMode::Directory => {
+                    AcceptCurrentDirectory
+                }
+                ActivationTarget::Nothing => Ignore,
+                ActivationTarget::Directory
+                    if mode == FileSelectMode::Directory && action == Activation::Ok =>
+                {
+                    Accept
+                }
+                ActivationTarget::Directory => EnterDirectory,
+                ActivationTarget::File if mode == FileSelectMode::Directory => Ignore,
+                ActivationTarget::File | ActivationTarget::Several => Accept,
+            }
+        }
+    }
+}
+
+/// Applies a click on entry `clicked` to the `selected` entries in
+/// `--multiple` mode, where `order` is the entries as listed.
+///
+/// A plain click selects just the entry and Ctrl+click toggles it.
+/// Shift+click selects the entries from `anchor` to the clicked one, adding
+/// them to the selection with Ctrl held too. Returns the anchor for the next
+/// Shift+click.
+pub(crate) fn click_selection(
+    selected: &mut HashSet<usize>,
+    order: &[usize],
+    anchor: Option<usize>,
+    clicked: usize,
+    modifiers: Modifiers,
+) -> usize {
+    let ctrl = modifiers.contains(Modifiers::CTRL);
+    let range = modifiers
+        .contains(Modifiers::SHIFT)
+        .then(|| {
+            let position = |entry| order.iter().position(|&e| e == entry);
+            Some((position(anchor?)?, position(clicked)?))
+        })
+        .flatten();
+    match range {
+        Some((from, to)) => {
+            if !ctrl {
+                selected.clear();
+            }
+            selected.extend(&order[from.min(to)..=from.max(to)]);
+            anchor.unwrap_or(clicked)
+        }
+        None if ctrl => {
+            if !selected.remove(&clicked) {
+                selected.insert(clicked);
+            }
+            clicked
+        }
+        None => {
+            selected.clear();
+            selected.insert(clicked);
+            clicked
         }
     }
 }
@@ -75,6 +312,7 @@ enum QuickAccessIcon {
     Pictures,
     Music,
     Videos,
+    Root,
 }
 
 /// Represents a mounted drive
@@ -101,19 +339,49 @@ pub struct FileFilter {
     pub patterns: Vec<String>,
 }
 
+impl FileFilter {
+    /// The name shown for the filter: its name, or its patterns if unnamed.
+    pub fn label(&self) -> String {
+        if self.name.is_empty() {
+            self.patterns.join(" ")
+        } else {
+            self.name.clone()
+        }
+    }
+
+    /// Whether a file `name` matches any of the patterns, ignoring case.
+    pub fn matches(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.patterns
+            .iter()
+            .any(|pattern| glob_match(&pattern.to_lowercase(), &name))
+    }
+}
+
 /// File selection dialog builder.
 pub struct FileSelectBuilder {
     title: String,
     directory: bool,
     save: bool,
+    confirm_overwrite: bool,
     filename: String,
+    field_labels: LabelPlacement,
     start_path: Option<PathBuf>,
     width: Option<u32>,
     height: Option<u32>,
-    colors: Option<&'static Colors>,
+    timeout: Option<u32>,
+    position: Option<(Offset, Offset)>,
+    colors: Option<Colors>,
     filters: Vec<FileFilter>,
     multiple: bool,
     separator: String,
+    compact: bool,
+    sort: (FileSort, bool),
+    #[cfg(feature = "portal")]
+    portal: bool,
+    /// The folder, typed filename, hidden-file toggle and filter carried over
+    /// a zoom rebuild.
+    restored: Option<(PathBuf, Option<String>, bool, usize)>,
 }
 
 impl FileSelectBuilder {
@@ -122,14 +390,23 @@ impl FileSelectBuilder {
             title: String::new(),
             directory: false,
             save: false,
+            confirm_overwrite: false,
             filename: String::new(),
+            field_labels: LabelPlacement::Side,
             start_path: None,
             width: None,
             height: None,
+            timeout: None,
+            position: None,
             colors: None,
             filters: Vec::new(),
             multiple: false,
             separator: String::from(" "),
+            compact: false,
+            sort: (FileSort::Name, false),
+            #[cfg(feature = "portal")]
+            portal: false,
+            restored: None,
         }
     }
 
@@ -148,18 +425,31 @@ impl FileSelectBuilder {
         self
     }
 
+    /// In save mode, ask before returning a file that already exists.
+    pub fn confirm_overwrite(mut self, confirm: bool) -> Self {
+        self.confirm_overwrite = confirm;
+        self
+    }
+
     pub fn filename(mut self, filename: &str) -> Self {
         self.filename = filename.to_string();
         self
     }
 
+    /// Set where the save dialog's filename label goes (default: beside the
+    /// input).
+    pub fn field_labels(mut self, placement: LabelPlacement) -> Self {
+        self.field_labels = placement;
+        self
+    }
+
     pub fn start_path(mut self, path: &Path) -> Self {
         self.start_path = Some(path.to_path_buf());
         self
     }
 
-    pub fn colors(mut self, colors: &'static Colors) -> Self {
-        self.colors = Some(colors);
+    pub fn colors(mut self, colors: impl Borrow<Colors>) -> Self {
+        self.colors = Some(*colors.borrow());
         self
     }
 
@@ -173,6 +463,18 @@ impl FileSelectBuilder {
         self
     }
 
+    /// Set timeout in seconds. Dialog will auto-close after this time.
+    pub fn timeout(mut self, seconds: u32) -> Self {
+        self.timeout = Some(seconds);
+        self
+    }
+
+    /// Request a window position. Ignored where the platform doesn't allow it.
+    pub fn position(mut self, x: Offset, y: Offset) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
     pub fn add_filter(mut self, filter: FileFilter) -> Self {
         self.filters.push(filter);
         self
@@ -188,27 +490,122 @@ impl FileSelectBuilder {
         self
     }
 
-    pub fn show(self) -> Result<FileSelectResult, Error> {
-        let colors = self.colors.unwrap_or_else(|| crate::ui::detect_theme());
+    /// Sort the files by a column, descending if `descending` (default: by
+    /// name, ascending). Clicking a column header sorts by it too.
+    pub fn sort_by(mut self, column: FileSort, descending: bool) -> Self {
+        self.sort = (column, descending);
+        self
+    }
+
+    /// Leave out the places sidebar, giving the file list the whole width.
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    /// Ask the desktop portal's file chooser instead, as sandboxed apps
+    /// must. The built-in dialog is shown if no portal answers.
+    #[cfg(feature = "portal")]
+    pub fn portal(mut self, portal: bool) -> Self {
+        self.portal = portal;
+        self
+    }
+
+    pub fn show(mut self) -> Result<FileSelectResult, Error> {
+        let deadline = self
+            .timeout
+            .map(|secs| Instant::now() + Duration::from_secs(secs as u64));
+        #[cfg(feature = "portal")]
+        if self.portal {
+            let (folder, name) = self.starting_point();
+            let request = crate::portal::Request {
+                title: self.window_title(),
+                save: self.save,
+                multiple: self.multiple,
+                directory: self.directory,
+                filters: &self.filters,
+                folder: std::path::absolute(&folder).unwrap_or(folder),
+                name,
+            };
+            if let Some(result) = crate::portal::choose(&request, deadline)? {
+                return Ok(result);
+            }
+        }
+        loop {
+            if let Some(result) = self.run(deadline)? {
+                return Ok(result);
+            }
+        }
+    }
+
+    fn window_title(&self) -> &str {
+        if !self.title.is_empty() {
+            &self.title
+        } else if self.directory {
+            "Select Directory"
+        } else if self.save {
+            "Save File"
+        } else {
+            "Open File"
+        }
+    }
+
+    /// The directory to start in, and the name of a file to preselect in it,
+    /// from [`start_path`](Self::start_path) or [`filename`](Self::filename).
+    /// A directory opens in place; a file path opens its parent and yields
+    /// the file name for preselection (zenity semantics). A path whose
+    /// directory doesn't exist falls back to the home directory.
+    fn starting_point(&self) -> (PathBuf, Option<String>) {
+        let home = || dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+        match &self.start_path {
+            Some(p) if p.is_dir() => (p.clone(), None),
+            Some(_) => (home(), None),
+            None if self.filename.is_empty() => (home(), None),
+            None => {
+                let path = Path::new(&self.filename);
+                if path.is_dir() {
+                    (path.to_path_buf(), None)
+                } else if self.filename.ends_with('/') {
+                    (home(), None)
+                } else {
+                    match path.parent() {
+                        Some(p) if p.as_os_str().is_empty() => {
+                            (
+                                std::env::current_dir().unwrap_or_else(|_| home()),
+                                path.file_name().map(|n| n.to_string_lossy().to_string()),
+                            )
+                        }
+                        Some(p) if p.is_dir() => {
+                            (
+                                p.to_path_buf(),
+                                path.file_name().map(|n| n.to_string_lossy().to_string()),
+                            )
+                        }
+                        _ => (home(), None),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Shows the dialog until it's answered, or returns `None` to be run
+    /// again after a [zoom](crate::ui::zoom) change.
+    fn run(&mut self, deadline: Option<Instant>) -> Result<Option<FileSelectResult>, Error> {
+        let colors = &self.colors.unwrap_or_else(|| *crate::ui::detect_theme());
 
         // Use custom dimensions if provided, otherwise use defaults
-        let logical_width = self.width.unwrap_or(BASE_WINDOW_WIDTH);
-        let logical_height = self.height.unwrap_or(BASE_WINDOW_HEIGHT);
+        let logical_width = self
+            .width
+            .unwrap_or(BASE_WINDOW_WIDTH)
+            .max(BASE_MIN_WINDOW_WIDTH);
+        let logical_height = self
+            .height
+            .unwrap_or(BASE_WINDOW_HEIGHT)
+            .max(BASE_MIN_WINDOW_HEIGHT);
 
         // Create window with LOGICAL dimensions first
         let mut window = create_window(logical_width as u16, logical_height as u16)?;
-        let title = if self.title.is_empty() {
-            if self.directory {
-                "Select Directory"
-            } else if self.save {
-                "Save File"
-            } else {
-                "Open File"
-            }
-        } else {
-            &self.title
-        };
-        window.set_title(title)?;
+        window.set_title(self.window_title())?;
 
         // Get the actual scale factor from the window (compositor scale)
         let scale = window.scale_factor();
@@ -220,7 +617,11 @@ impl FileSelectBuilder {
         let window_width = (logical_width as f32 * scale) as u32;
         let window_height = (logical_height as f32 * scale) as u32;
         let padding = (BASE_PADDING as f32 * scale) as u32;
-        let sidebar_width = (BASE_SIDEBAR_WIDTH as f32 * scale) as u32;
+        let sidebar_width = if self.compact {
+            0
+        } else {
+            (BASE_SIDEBAR_WIDTH as f32 * scale) as u32
+        };
         let toolbar_height = (BASE_TOOLBAR_HEIGHT as f32 * scale) as u32;
         let path_bar_height = (BASE_PATH_BAR_HEIGHT as f32 * scale) as u32;
         let search_width = (BASE_SEARCH_WIDTH as f32 * scale) as u32;
@@ -228,15 +629,19 @@ impl FileSelectBuilder {
         let name_col_width = (BASE_NAME_COL_WIDTH as f32 * scale) as u32;
         let size_col_width = (BASE_SIZE_COL_WIDTH as f32 * scale) as u32;
 
-        // Build quick access locations
-        let quick_access = build_quick_access();
-
-        // Load mounted drives
-        let mounted_drives = get_mounted_drives();
+        // Build quick access locations and load mounted drives, unless
+        // there's no sidebar to show them
+        let (quick_access, mounted_drives) = if self.compact {
+            (Vec::new(), Vec::new())
+        } else {
+            (build_quick_access(), get_mounted_drives())
+        };
 
         // Create UI elements at physical scale
         let mut ok_button = Button::new(if self.save { "Save" } else { "Open" }, &font, scale);
         let mut cancel_button = Button::new("Cancel", &font, scale);
+        let mut new_folder_button =
+            (self.save && !self.directory).then(|| Button::new("New Folder", &font, scale));
 
         // Search input
         let mut search_input = TextInput::new(search_width).with_placeholder("Search...");
@@ -249,40 +654,24 @@ impl FileSelectBuilder {
         let mut history_index: usize = 0;
 
         // Current state
-        // Resolve the initial directory (and optional preselected file name) from
-        // --filename / start_path. A directory opens in place; a file path opens
-        // its parent and yields the file name for preselection (zenity semantics).
-        let (mut current_dir, preselected_name) = match &self.start_path {
-            Some(p) => (p.clone(), None),
-            None if self.filename.is_empty() => {
-                (dirs::home_dir().unwrap_or_else(|| PathBuf::from("/")), None)
-            }
-            None => {
-                let path = Path::new(&self.filename);
-                if path.is_dir() {
-                    (path.to_path_buf(), None)
-                } else {
-                    let name = path.file_name().map(|n| n.to_string_lossy().to_string());
-                    let dir = path
-                        .parent()
-                        .filter(|p| !p.as_os_str().is_empty() && p.is_dir())
-                        .map(|p| p.to_path_buf())
-                        .unwrap_or_else(|| {
-                            std::env::current_dir().unwrap_or_else(|_| {
-                                dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"))
-                            })
-                        });
-                    (dir, name)
-                }
-            }
-        };
+        let (initial_dir, preselected_name) = self.starting_point();
+        // Selected paths are printed as-is, so keep them absolute
+        let mut current_dir = std::path::absolute(&initial_dir).unwrap_or(initial_dir);
+        let restored = self.restored.take();
+        if let Some((dir, ..)) = &restored {
+            current_dir = dir.clone();
+        }
         history.push(current_dir.clone());
 
         let mut all_entries: Vec<DirEntry> = Vec::new();
         let mut filtered_entries: Vec<usize> = Vec::new(); // Indices into all_entries
         let mut selected_indices: HashSet<usize> = HashSet::new();
         let mut scroll_offset: usize = 0;
-        let mut show_hidden = false;
+        let mut show_hidden = restored.as_ref().is_some_and(|(_, _, hidden, _)| *hidden);
+        // Only the filter picked in the filter box applies, the first at first
+        let filter_index = restored.as_ref().map_or(0, |(.., filter)| *filter);
+        let mut active_filter: &[FileFilter] =
+            self.filters.get(filter_index..=filter_index).unwrap_or(&[]);
         let mut search_text = String::new();
         let mut hovered_quick_access: Option<usize> = None;
         let mut hovered_entry: Option<usize> = None;
@@ -297,42 +686,81 @@ impl FileSelectBuilder {
         let mut search_popup_index: usize = 0;
 
         let mut window_dragging = false;
+        // Events the batch loop read, left for the main loop to handle
+        let mut held: VecDeque<WindowEvent> = VecDeque::new();
 
         // Scrollbar thumb dragging state
         let mut thumb_drag = false;
         let mut thumb_drag_offset: Option<i32> = None;
         let mut scrollbar_hovered = false;
 
+        // Entry clicked last, for double-click detection
+        let mut last_click: Option<(Instant, usize)> = None;
+        // Modifiers held for the last click, and where a Shift+click range
+        // starts (`--multiple`)
+        let mut click_modifiers = Modifiers::empty();
+        let mut selection_anchor: Option<usize> = None;
+
+        // Typed characters jump to the first name starting with them
+        let mut typeahead = String::new();
+        let mut typeahead_at: Option<Instant> = None;
+        // The path typed after Ctrl+L, shown in place of the breadcrumbs
+        let mut location_input: Option<TextInput> = None;
+        // The name typed for a new folder (save mode), over the top of the
+        // file list, and why creating it failed
+        let mut new_folder_input: Option<TextInput> = None;
+        let mut new_folder_error: Option<String> = None;
+        let new_folder_row_h = TextInput::new(0).height() + (8.0 * scale) as u32;
+
+        let mode = if self.directory {
+            FileSelectMode::Directory
+        } else if save_mode {
+            FileSelectMode::Save
+        } else {
+            FileSelectMode::Open
+        };
+
         // Load initial directory
-        load_directory(&current_dir, &mut all_entries, self.directory, show_hidden);
+        let mut sort = self.sort;
+        load_directory(
+            &current_dir,
+            &mut all_entries,
+            self.directory,
+            show_hidden,
+            sort,
+        );
         update_filtered(
             &all_entries,
             &search_text,
             &mut filtered_entries,
-            &self.filters,
+            active_filter,
         );
 
         // Calculate layout in physical coordinates
+        let content_gap = (BASE_CONTENT_GAP as f32 * scale) as u32;
+        let sidebar_gap = if self.compact { 0 } else { content_gap };
+        let main_w = window_width - padding * 2 - sidebar_width - sidebar_gap;
+
+        // The filename row (save mode only) sits above the file list
+        let filename_column = LabelColumn::new(&font, &["Name"], main_w, self.field_labels, scale);
+        let filename_label = filename_column.render_label(&font, "Name", colors.text);
+        let filename_input_h = TextInput::new(0).height();
         let filename_row_height = if save_mode {
-            (BASE_FILENAME_ROW_HEIGHT as f32 * scale) as u32
+            filename_column
+                .place(&filename_label, 0, 0, filename_input_h)
+                .height
+                + (BASE_FILENAME_ROW_GAP as f32 * scale) as u32
         } else {
             0
         };
-        let content_gap = (BASE_CONTENT_GAP as f32 * scale) as u32;
         let footer_height = (BASE_FOOTER_HEIGHT as f32 * scale) as u32;
         let sidebar_x = padding as i32;
         let sidebar_y = (padding + toolbar_height + content_gap) as i32;
-        let sidebar_h = window_height
-            - padding * 2
-            - toolbar_height
-            - content_gap
-            - footer_height
-            - filename_row_height;
-
-        let main_x = (padding + sidebar_width + content_gap) as i32;
-        let main_y = sidebar_y;
-        let main_w = window_width - padding * 2 - sidebar_width - content_gap;
-        let main_h = sidebar_h;
+        let sidebar_h = window_height - padding * 2 - toolbar_height - content_gap - footer_height;
+
+        let main_x = (padding + sidebar_width + sidebar_gap) as i32;
+        let main_y = sidebar_y + filename_row_height as i32;
+        let main_h = sidebar_h - filename_row_height;
 
         let header_offset = (BASE_COLUMN_HEADER_HEIGHT as f32 * scale) as u32;
         let list_y = main_y + path_bar_height as i32 + header_offset as i32;
@@ -367,6 +795,22 @@ impl FileSelectBuilder {
         let section_header_height = (BASE_SECTION_HEADER_HEIGHT as f32 * scale) as u32;
         let item_height_scaled = item_height;
         let gap_between_sections = content_gap;
+        // The sidebar scrolls on its own once the places and drives overflow it
+        let sidebar_content_h = {
+            let drives_h = if mounted_drives.is_empty() {
+                0
+            } else {
+                gap_between_sections
+                    + section_header_height
+                    + mounted_drives.len() as u32 * item_height_scaled
+            };
+            (16.0 * scale) as u32
+                + section_header_height
+                + quick_access.len() as u32 * item_height_scaled
+                + drives_h
+        };
+        let sidebar_max_scroll = sidebar_content_h.saturating_sub(sidebar_h) as i32;
+        let mut sidebar_scroll = 0i32;
 
         // Position buttons
         let button_y =
@@ -376,17 +820,42 @@ impl FileSelectBuilder {
         cancel_button.set_position(bx, button_y);
         bx -= (BASE_BUTTON_SPACING as f32 * scale) as i32 + ok_button.width() as i32;
         ok_button.set_position(bx, button_y);
+        if let Some(button) = &mut new_folder_button {
+            bx -= (BASE_BUTTON_SPACING as f32 * scale) as i32 + button.width() as i32;
+            button.set_position(bx, button_y);
+        }
 
-        // Position filename area (label above, full-width input below, save mode only)
-        let filename_y = button_y - filename_row_height as i32;
-        let filename_label_h = (BASE_FILENAME_LABEL_HEIGHT as f32 * scale) as i32;
+        // Filter box left of the buttons, if there are filters
+        let mut filter_combo = (!self.filters.is_empty()).then(|| {
+            let labels = self.filters.iter().map(FileFilter::label).collect();
+            let mut combo = ComboBox::new(labels, &font, scale);
+            let max_width = (bx - main_x) as u32 / 2;
+            combo.set_width(combo.width().min(max_width));
+            combo.set_position(
+                bx - (BASE_BUTTON_SPACING as f32 * scale) as i32 - combo.width() as i32,
+                button_y,
+            );
+            combo.set_popup_above(true);
+            combo.set_selected(filter_index);
+            combo
+        });
+
+        // Position filename row (save mode only)
+        let filename_row =
+            filename_column.place(&filename_label, main_x, sidebar_y, filename_input_h);
+        // Completions drop down over the file list
+        let completion_popup_y = filename_row.input_y + filename_input_h as i32;
         let mut filename_input = if save_mode {
-            let mut input = TextInput::new(main_w).with_placeholder("Enter filename...");
+            let mut input =
+                TextInput::new(filename_column.input_width()).with_placeholder("Enter filename...");
             if let Some(name) = &preselected_name {
                 input = input.with_default_text(name);
             }
+            if let Some((_, Some(name), ..)) = &restored {
+                input.set_text(name);
+            }
             input.set_focus(true);
-            input.set_position(main_x, filename_y + filename_label_h);
+            input.set_position(filename_row.input_x, filename_row.input_y);
             Some(input)
         } else {
             None
@@ -410,6 +879,7 @@ impl FileSelectBuilder {
             show_hidden: bool,
             hovered_qa: Option<usize>,
             hovered_drive: Option<usize>,
+            sidebar_scroll: i32,
             history_index: usize,
             history_len: usize,
             search: String,
@@ -417,8 +887,10 @@ impl FileSelectBuilder {
             filename: Option<(String, bool)>,
             qa_len: usize,
             drives_len: usize,
+            sort: (FileSort, bool),
         }
         let mut chrome_canvas = Canvas::new(window_width, window_height);
+        let compact = self.compact;
         let mut chrome_sig: Option<ChromeSig> = None;
 
         let mut mouse_x = 0i32;
@@ -433,9 +905,11 @@ impl FileSelectBuilder {
                            mounted_drives: &[MountPoint],
                            hovered_quick_access: Option<usize>,
                            hovered_drive: Option<usize>,
+                           sidebar_scroll: i32,
                            history: &[PathBuf],
                            history_index: usize,
                            show_hidden: bool,
+                           sort: (FileSort, bool),
                            search_input: &TextInput,
                            scale: f32| {
             let width = canvas.width() as f32;
@@ -528,149 +1002,174 @@ impl FileSelectBuilder {
             search_input.draw_to(canvas, colors, font);
 
             // Sidebar
-            let sidebar_bg = darken(colors.window_bg, 0.02);
-            canvas.fill_rounded_rect(
-                sidebar_x as f32,
-                sidebar_y as f32,
-                sidebar_width as f32,
-                sidebar_h as f32,
-                6.0 * scale,
-                sidebar_bg,
-            );
-
-            // ===== PLACES SECTION =====
-            draw_section_header(
-                canvas,
-                sidebar_x,
-                sidebar_y + (8.0 * scale) as i32,
-                "PLACES",
-                colors,
-                font,
-                scale,
-            );
-
-            let places_items_start_y =
-                sidebar_y + (8.0 * scale) as i32 + section_header_height as i32;
-            for (i, qa) in quick_access.iter().enumerate() {
-                let y = places_items_start_y + (i as i32 * item_height_scaled as i32);
-                let is_hovered = hovered_quick_access == Some(i);
-                let is_current = qa.path == current_dir;
-
-                if is_current {
-                    canvas.fill_rounded_rect(
-                        (sidebar_x + (4.0 * scale) as i32) as f32,
-                        y as f32,
-                        (sidebar_width - (8.0 * scale) as u32) as f32,
-                        28.0 * scale,
-                        4.0 * scale,
-                        colors.input_border_focused,
-                    );
-                } else if is_hovered {
-                    canvas.fill_rounded_rect(
-                        (sidebar_x + (4.0 * scale) as i32) as f32,
-                        y as f32,
-                        (sidebar_width - (8.0 * scale) as u32) as f32,
-                        28.0 * scale,
-                        4.0 * scale,
-                        darken(colors.window_bg, 0.05),
-                    );
-                }
-
-                draw_quick_access_icon(
-                    canvas,
-                    sidebar_x + (12.0 * scale) as i32,
-                    y + (4.0 * scale) as i32,
-                    qa.icon,
-                    colors,
-                    scale,
-                );
-
-                let text_color = if is_current {
-                    rgb(255, 255, 255)
-                } else {
-                    colors.text
-                };
-                let name_canvas = font.render(qa.name).with_color(text_color).finish();
-                canvas.draw_canvas(
-                    &name_canvas,
-                    sidebar_x + (36.0 * scale) as i32,
-                    y + (6.0 * scale) as i32,
+            if !compact {
+                let sidebar_bg = darken(colors.window_bg, 0.02);
+                canvas.fill_rounded_rect(
+                    sidebar_x as f32,
+                    sidebar_y as f32,
+                    sidebar_width as f32,
+                    sidebar_h as f32,
+                    6.0 * scale,
+                    sidebar_bg,
                 );
-            }
 
-            // ===== DRIVES SECTION =====
-            if !mounted_drives.is_empty() {
-                let drives_section_y = places_items_start_y
-                    + (quick_access.len() as i32 * item_height_scaled as i32)
-                    + gap_between_sections as i32;
-
-                draw_section_header(
-                    canvas,
-                    sidebar_x,
-                    drives_section_y,
-                    "DRIVES",
-                    colors,
-                    font,
-                    scale,
-                );
+                // Places and drives scroll together inside the sidebar
+                let mut sidebar = Canvas::new(sidebar_width, sidebar_h);
+                {
+                    let canvas = &mut sidebar;
+                    let (sidebar_x, sidebar_y) = (0, -sidebar_scroll);
+                    // ===== PLACES SECTION =====
+                    draw_section_header(
+                        canvas,
+                        sidebar_x,
+                        sidebar_y + (8.0 * scale) as i32,
+                        "PLACES",
+                        colors,
+                        font,
+                        scale,
+                    );
 
-                let drives_items_start_y = drives_section_y + section_header_height as i32;
-                for (i, drive) in mounted_drives.iter().enumerate() {
-                    let y = drives_items_start_y + (i as i32 * item_height_scaled as i32);
-                    let is_hovered = hovered_drive == Some(i);
-                    let is_current = drive.mount_point == current_dir;
+                    let places_items_start_y =
+                        sidebar_y + (8.0 * scale) as i32 + section_header_height as i32;
+                    for (i, qa) in quick_access.iter().enumerate() {
+                        let y = places_items_start_y + (i as i32 * item_height_scaled as i32);
+                        let is_hovered = hovered_quick_access == Some(i);
+                        let is_current = qa.path == current_dir;
+
+                        if is_current {
+                            canvas.fill_rounded_rect(
+                                (sidebar_x + (4.0 * scale) as i32) as f32,
+                                y as f32,
+                                (sidebar_width - (8.0 * scale) as u32) as f32,
+                                28.0 * scale,
+                                4.0 * scale,
+                                colors.input_border_focused,
+                            );
+                        } else if is_hovered {
+                            canvas.fill_rounded_rect(
+                                (sidebar_x + (4.0 * scale) as i32) as f32,
+                                y as f32,
+                                (sidebar_width - (8.0 * scale) as u32) as f32,
+                                28.0 * scale,
+                                4.0 * scale,
+                                darken(colors.window_bg, 0.05),
+                            );
+                        }
 
-                    if is_current {
-                        canvas.fill_rounded_rect(
-                            (sidebar_x + (4.0 * scale) as i32) as f32,
-                            y as f32,
-                            (sidebar_width - (8.0 * scale) as u32) as f32,
-                            28.0 * scale,
-                            4.0 * scale,
-                            colors.input_border_focused,
+                        draw_quick_access_icon(
+                            canvas,
+                            sidebar_x + (12.0 * scale) as i32,
+                            y + (4.0 * scale) as i32,
+                            qa.icon,
+                            colors,
+                            scale,
                         );
-                    } else if is_hovered {
-                        canvas.fill_rounded_rect(
-                            (sidebar_x + (4.0 * scale) as i32) as f32,
-                            y as f32,
-                            (sidebar_width - (8.0 * scale) as u32) as f32,
-                            28.0 * scale,
-                            4.0 * scale,
-                            darken(colors.window_bg, 0.05),
+
+                        let text_color = if is_current {
+                            rgb(255, 255, 255)
+                        } else {
+                            colors.text
+                        };
+                        let name_canvas = font.render(qa.name).with_color(text_color).finish();
+                        canvas.draw_canvas(
+                            &name_canvas,
+                            sidebar_x + (36.0 * scale) as i32,
+                            y + (6.0 * scale) as i32,
                         );
                     }
 
-                    let icon = get_mount_icon(&drive.device);
-                    draw_mount_icon(
-                        canvas,
-                        sidebar_x + (12.0 * scale) as i32,
-                        y + (6.0 * scale) as i32,
-                        icon,
-                        colors,
-                        scale,
-                    );
+                    // ===== DRIVES SECTION =====
+                    if !mounted_drives.is_empty() {
+                        let drives_section_y = places_items_start_y
+                            + (quick_access.len() as i32 * item_height_scaled as i32)
+                            + gap_between_sections as i32;
+
+                        draw_section_header(
+                            canvas,
+                            sidebar_x,
+                            drives_section_y,
+                            "DRIVES",
+                            colors,
+                            font,
+                            scale,
+                        );
 
-                    let display_name = drive.label.as_deref().unwrap_or_else(|| {
-                        drive
-                            .mount_point
-                            .file_name()
-                            .and_then(|n| n.to_str())
-                            .unwrap_or(&drive.device)
-                    });
-                    let truncated_name = truncate_name(display_name, 18);
+                        let drives_items_start_y = drives_section_y + section_header_height as i32;
+                        for (i, drive) in mounted_drives.iter().enumerate() {
+                            let y = drives_items_start_y + (i as i32 * item_height_scaled as i32);
+                            let is_hovered = hovered_drive == Some(i);
+                            let is_current = drive.mount_point == current_dir;
+
+                            if is_current {
+                                canvas.fill_rounded_rect(
+                                    (sidebar_x + (4.0 * scale) as i32) as f32,
+                                    y as f32,
+                                    (sidebar_width - (8.0 * scale) as u32) as f32,
+                                    28.0 * scale,
+                                    4.0 * scale,
+                                    colors.input_border_focused,
+                                );
+                            } else if is_hovered {
+                                canvas.fill_rounded_rect(
+                                    (sidebar_x + (4.0 * scale) as i32) as f32,
+                                    y as f32,
+                                    (sidebar_width - (8.0 * scale) as u32) as f32,
+                                    28.0 * scale,
+                                    4.0 * scale,
+                                    darken(colors.window_bg, 0.05),
+                                );
+                            }
 
-                    let text_color = if is_current {
-                        rgb(255, 255, 255)
-                    } else {
-                        colors.text
-                    };
-                    let name_canvas = font.render(&truncated_name).with_color(text_color).finish();
-                    canvas.draw_canvas(
-                        &name_canvas,
-                        sidebar_x + (36.0 * scale) as i32,
-                        y + (6.0 * scale) as i32,
+                            let icon = get_mount_icon(&drive.device);
+                            draw_mount_icon(
+                                canvas,
+                                sidebar_x + (12.0 * scale) as i32,
+                                y + (6.0 * scale) as i32,
+                                icon,
+                                colors,
+                                scale,
+                            );
+
+                            let display_name = drive.label.as_deref().unwrap_or_else(|| {
+                                drive
+                                    .mount_point
+                                    .file_name()
+                                    .and_then(|n| n.to_str())
+                                    .unwrap_or(&drive.device)
+                            });
+                            let truncated_name = truncate_name(display_name, 18);
+
+                            let text_color = if is_current {
+                                rgb(255, 255, 255)
+                            } else {
+                                colors.text
+                            };
+                            let name_canvas =
+                                font.render(&truncated_name).with_color(text_color).finish();
+                            canvas.draw_canvas(
+                                &name_canvas,
+                                sidebar_x + (36.0 * scale) as i32,
+                                y + (6.0 * scale) as i32,
+                            );
+                        }
+                    }
+                }
+                if sidebar_max_scroll > 0 {
+                    let track_h = sidebar_h as f32 - 12.0 * scale;
+                    let thumb_h =
+                        (track_h * sidebar_h as f32 / sidebar_content_h as f32).max(20.0 * scale);
+                    let thumb_y =
+                        (track_h - thumb_h) * sidebar_scroll as f32 / sidebar_max_scroll as f32;
+                    sidebar.fill_rounded_rect(
+                        sidebar_width as f32 - 6.0 * scale,
+                        6.0 * scale + thumb_y,
+                        3.0 * scale,
+                        thumb_h,
+                        1.5 * scale,
+                        darken(colors.window_bg, 0.2),
                     );
                 }
+                canvas.draw_canvas(&sidebar, sidebar_x, sidebar_y);
             }
 
             // Main area background
@@ -706,24 +1205,34 @@ impl FileSelectBuilder {
             );
 
             let header_text = rgb(150, 150, 150);
-            let name_header = font.render("Name").with_color(header_text).finish();
-            canvas.draw_canvas(
-                &name_header,
-                main_x + (32.0 * scale) as i32,
-                header_y + (5.0 * scale) as i32,
-            );
-            let size_header = font.render("Size").with_color(header_text).finish();
-            canvas.draw_canvas(
-                &size_header,
-                main_x + name_col_width as i32 + (8.0 * scale) as i32,
-                header_y + (5.0 * scale) as i32,
-            );
-            let date_header = font.render("Modified").with_color(header_text).finish();
-            canvas.draw_canvas(
-                &date_header,
-                main_x + name_col_width as i32 + size_col_width as i32 + (16.0 * scale) as i32,
-                header_y + (5.0 * scale) as i32,
-            );
+            let headers = [
+                (FileSort::Name, "Name", 32.0 * scale),
+                (FileSort::Size, "Size", name_col_width as f32 + 8.0 * scale),
+                (
+                    FileSort::Modified,
+                    "Modified",
+                    (name_col_width + size_col_width) as f32 + 16.0 * scale,
+                ),
+            ];
+            for (column, label, x) in headers {
+                let label_canvas = font.render(label).with_color(header_text).finish();
+                let x = main_x + x as i32;
+                canvas.draw_canvas(&label_canvas, x, header_y + (5.0 * scale) as i32);
+                // A small triangle after the sorted column's name
+                if sort.0 == column {
+                    let x = (x + label_canvas.width() as i32) as f32 + 6.0 * scale;
+                    let mid = header_y as f32 + 13.0 * scale;
+                    let (tip, base) = if sort.1 {
+                        (mid + 3.0 * scale, mid - 3.0 * scale)
+                    } else {
+                        (mid - 3.0 * scale, mid + 3.0 * scale)
+                    };
+                    canvas.fill_polygon(
+                        &[(x, base), (x + 8.0 * scale, base), (x + 4.0 * scale, tip)],
+                        header_text,
+                    );
+                }
+            }
 
             // Separator line
             canvas.fill_rect(
@@ -737,185 +1246,249 @@ impl FileSelectBuilder {
 
         // Dynamic layer: the scrollable file list + scrollbar + inputs + buttons.
         // Redrawn every frame on top of the cached chrome.
-        let draw_dynamic = |canvas: &mut Canvas,
-                            colors: &Colors,
-                            font: &Font,
-                            all_entries: &[DirEntry],
-                            filtered_entries: &[usize],
-                            selected_indices: &HashSet<usize>,
-                            scroll_offset: usize,
-                            hovered_entry: Option<usize>,
-                            scale: f32,
-                            scrollbar_hovered: bool,
-                            ok_button: &Button,
-                            cancel_button: &Button,
-                            filename_input: Option<&TextInput>| {
-            // File list
-            let list_x = main_x;
-            for (vi, &ei) in filtered_entries
-                .iter()
-                .skip(scroll_offset)
-                .take(visible_items)
-                .enumerate()
-            {
-                let entry = &all_entries[ei];
-                let y = list_y + (vi as u32 * item_height) as i32;
-                let is_selected = selected_indices.contains(&ei);
-                let is_hovered = hovered_entry == Some(ei);
-
-                // Alternating background
-                let row_bg = if vi % 2 == 1 {
-                    darken(colors.input_bg, 0.02)
-                } else {
-                    colors.input_bg
-                };
+        let draw_dynamic =
+            |canvas: &mut Canvas,
+             colors: &Colors,
+             font: &Font,
+             all_entries: &[DirEntry],
+             filtered_entries: &[usize],
+             selected_indices: &HashSet<usize>,
+             scroll_offset: usize,
+             hovered_entry: Option<usize>,
+             scale: f32,
+             scrollbar_hovered: bool,
+             ok_button: &Button,
+             cancel_button: &Button,
+             filename_input: Option<&TextInput>,
+             filter_combo: Option<&ComboBox>,
+             location_input: Option<&TextInput>,
+             new_folder_button: Option<&Button>,
+             new_folder: Option<(&TextInput, Option<&str>)>| {
+                // File list
+                let list_x = main_x;
+                let now = SystemTime::now();
+                for (vi, &ei) in filtered_entries
+                    .iter()
+                    .skip(scroll_offset)
+                    .take(visible_items)
+                    .enumerate()
+                {
+                    let entry = &all_entries[ei];
+                    let y = list_y + (vi as u32 * item_height) as i32;
+                    let is_selected = selected_indices.contains(&ei);
+                    let is_hovered = hovered_entry == Some(ei);
+
+                    // Alternating background
+                    let row_bg = if vi % 2 == 1 {
+                        darken(colors.input_bg, 0.02)
+                    } else {
+                        colors.input_bg
+                    };
 
-                // Selection/hover highlight
-                if is_selected {
-                    canvas.fill_rect(
-                        (list_x + 2) as f32,
-                        y as f32,
-                        (main_w - 4) as f32,
-                        item_height as f32,
-                        colors.input_border_focused,
-                    );
-                } else if is_hovered {
-                    canvas.fill_rect(
-                        (list_x + 2) as f32,
-                        y as f32,
-                        (main_w - 4) as f32,
-                        item_height as f32,
-                        darken(colors.input_bg, 0.06),
-                    );
-                } else {
-                    canvas.fill_rect(
-                        list_x as f32,
-                        y as f32,
-                        main_w as f32,
-                        item_height as f32,
-                        row_bg,
-                    );
-                }
+                    // Selection/hover highlight
+                    if is_selected {
+                        canvas.fill_rect(
+                            (list_x + 2) as f32,
+                            y as f32,
+                            (main_w - 4) as f32,
+                            item_height as f32,
+                            colors.input_border_focused,
+                        );
+                    } else if is_hovered {
+                        canvas.fill_rect(
+                            (list_x + 2) as f32,
+                            y as f32,
+                            (main_w - 4) as f32,
+                            item_height as f32,
+                            darken(colors.input_bg, 0.06),
+                        );
+                    } else {
+                        canvas.fill_rect(
+                            list_x as f32,
+                            y as f32,
+                            main_w as f32,
+                            item_height as f32,
+                            row_bg,
+                        );
+                    }
 
-                // Icon
-                let icon_x = list_x + (8.0 * scale) as i32;
-                let icon_y = y + (4.0 * scale) as i32;
-                if entry.is_dir {
-                    draw_folder_icon(canvas, icon_x, icon_y, colors, scale);
-                } else {
-                    draw_file_icon(canvas, icon_x, icon_y, &entry.name, colors, scale);
-                }
+                    // Icon
+                    let icon_x = list_x + (8.0 * scale) as i32;
+                    let icon_y = y + (4.0 * scale) as i32;
+                    if entry.is_dir {
+                        draw_folder_icon(canvas, icon_x, icon_y, colors, scale);
+                    } else {
+                        draw_file_icon(canvas, icon_x, icon_y, &entry.name, colors, scale);
+                    }
 
-                // Name
-                let text_color = if is_selected {
-                    rgb(255, 255, 255)
-                } else {
-                    colors.text
-                };
-                let display_name = truncate_name(&entry.name, 35);
-                let name_canvas = font.render(&display_name).with_color(text_color).finish();
-                canvas.draw_canvas(
-                    &name_canvas,
-                    list_x + (32.0 * scale) as i32,
-                    y + (6.0 * scale) as i32,
-                );
+                    // Name
+                    let text_color = if is_selected {
+                        rgb(255, 255, 255)
+                    } else {
+                        colors.text
+                    };
+                    let display_name = truncate_name(&entry.name, 35);
+                    let name_canvas = font.render(&display_name).with_color(text_color).finish();
+                    canvas.draw_canvas(
+                        &name_canvas,
+                        list_x + (32.0 * scale) as i32,
+                        y + (6.0 * scale) as i32,
+                    );
+
+                    // Size (for files)
+                    if !entry.is_dir {
+                        let size_str = format_size(entry.stat().0);
+                        let size_color = if is_selected {
+                            rgb(220, 220, 220)
+                        } else {
+                            rgb(140, 140, 140)
+                        };
+                        let size_canvas = font.render(&size_str).with_color(size_color).finish();
+                        canvas.draw_canvas(
+                            &size_canvas,
+                            list_x + name_col_width as i32 + (8.0 * scale) as i32,
+                            y + (6.0 * scale) as i32,
+                        );
+                    }
 
-                // Size (for files)
-                if !entry.is_dir {
-                    let size_str = format_size(entry.size);
-                    let size_color = if is_selected {
+                    // Date
+                    let date_str = entry
+                        .stat()
+                        .1
+                        .map_or_else(|| "-".to_string(), |time| format_modified(time, now));
+                    let date_color = if is_selected {
                         rgb(220, 220, 220)
                     } else {
                         rgb(140, 140, 140)
                     };
-                    let size_canvas = font.render(&size_str).with_color(size_color).finish();
+                    let date_canvas = font.render(&date_str).with_color(date_color).finish();
                     canvas.draw_canvas(
-                        &size_canvas,
-                        list_x + name_col_width as i32 + (8.0 * scale) as i32,
+                        &date_canvas,
+                        list_x
+                            + name_col_width as i32
+                            + size_col_width as i32
+                            + (16.0 * scale) as i32,
                         y + (6.0 * scale) as i32,
                     );
                 }
 
-                // Date
-                let date_str = format_date(entry.modified);
-                let date_color = if is_selected {
-                    rgb(220, 220, 220)
-                } else {
-                    rgb(140, 140, 140)
-                };
-                let date_canvas = font.render(&date_str).with_color(date_color).finish();
-                canvas.draw_canvas(
-                    &date_canvas,
-                    list_x + name_col_width as i32 + size_col_width as i32 + (16.0 * scale) as i32,
-                    y + (6.0 * scale) as i32,
-                );
-            }
-
-            // Scrollbar
-            if filtered_entries.len() > visible_items {
-                let scrollbar_width = if scrollbar_hovered {
-                    12.0 * scale
-                } else {
-                    8.0 * scale
-                };
-                let scrollbar_x = main_x + main_w as i32 - scrollbar_width as i32;
-                let scrollbar_h = list_h as f32;
-                let thumb_h = (visible_items as f32 / filtered_entries.len() as f32 * scrollbar_h)
-                    .max(20.0 * scale);
-                let thumb_y = scroll_offset as f32 / filtered_entries.len() as f32 * scrollbar_h;
-
-                // Track
-                canvas.fill_rounded_rect(
-                    scrollbar_x as f32,
-                    list_y as f32,
-                    scrollbar_width - 2.0 * scale,
-                    scrollbar_h,
-                    3.0 * scale,
-                    darken(colors.input_bg, 0.05),
-                );
-                // Thumb
-                canvas.fill_rounded_rect(
-                    scrollbar_x as f32,
-                    list_y as f32 + thumb_y,
-                    scrollbar_width - 2.0 * scale,
-                    thumb_h,
-                    3.0 * scale,
-                    if scrollbar_hovered {
-                        colors.input_border_focused
+                // Scrollbar
+                if filtered_entries.len() > visible_items {
+                    let scrollbar_width = if scrollbar_hovered {
+                        12.0 * scale
                     } else {
-                        colors.input_border
-                    },
+                        8.0 * scale
+                    };
+                    let scrollbar_x = main_x + main_w as i32 - scrollbar_width as i32;
+                    let scrollbar_h = list_h as f32;
+                    let thumb_h = (visible_items as f32 / filtered_entries.len() as f32
+                        * scrollbar_h)
+                        .max(20.0 * scale);
+                    let thumb_y =
+                        scroll_offset as f32 / filtered_entries.len() as f32 * scrollbar_h;
+
+                    // Track
+                    canvas.fill_rounded_rect(
+                        scrollbar_x as f32,
+                        list_y as f32,
+                        scrollbar_width - 2.0 * scale,
+                        scrollbar_h,
+                        3.0 * scale,
+                        darken(colors.input_bg, 0.05),
+                    );
+                    // Thumb
+                    canvas.fill_rounded_rect(
+                        scrollbar_x as f32,
+                        list_y as f32 + thumb_y,
+                        scrollbar_width - 2.0 * scale,
+                        thumb_h,
+                        3.0 * scale,
+                        if scrollbar_hovered {
+                            colors.input_border_focused
+                        } else {
+                            colors.input_border
+                        },
+                    );
+                }
+
+                // Border
+                canvas.stroke_rounded_rect(
+                    main_x as f32,
+                    main_y as f32,
+                    main_w as f32,
+                    main_h as f32,
+                    6.0 * scale,
+                    colors.input_border,
+                    1.0,
                 );
-            }
 
-            // Border
-            canvas.stroke_rounded_rect(
-                main_x as f32,
-                main_y as f32,
-                main_w as f32,
-                main_h as f32,
-                6.0 * scale,
-                colors.input_border,
-                1.0,
-            );
+                // New folder row, covering the top of the list
+                if let Some((input, error)) = new_folder {
+                    canvas.fill_rect(
+                        list_x as f32,
+                        list_y as f32,
+                        main_w as f32 - 12.0 * scale,
+                        new_folder_row_h as f32,
+                        colors.input_bg,
+                    );
+                    let icon_y = list_y + (new_folder_row_h as i32 - (20.0 * scale) as i32) / 2;
+                    draw_folder_icon(canvas, list_x + (8.0 * scale) as i32, icon_y, colors, scale);
+                    input.draw_to(canvas, colors, font);
+                    if let Some(error) = error {
+                        let error_x = input.x() + input.width() as i32 + (8.0 * scale) as i32;
+                        let max_w = list_x + main_w as i32 - error_x - (16.0 * scale) as i32;
+                        let error_canvas = font
+                            .render(error)
+                            .with_color(colors.input_border_invalid)
+                            .with_max_width(max_w as f32)
+                            .with_ellipsize(EllipsizeMode::End)
+                            .finish();
+                        let error_y =
+                            list_y + (new_folder_row_h as i32 - error_canvas.height() as i32) / 2;
+                        canvas.draw_canvas(&error_canvas, error_x, error_y);
+                    }
+                    canvas.fill_rect(
+                        list_x as f32,
+                        (list_y + new_folder_row_h as i32) as f32,
+                        main_w as f32 - 12.0 * scale,
+                        1.0,
+                        colors.input_border,
+                    );
+                }
 
-            // Filename input (save mode): label above, input below
-            if let Some(fi) = filename_input {
-                let label = title;
-                let label_canvas = font.render(label).with_color(colors.text).finish();
-                canvas.draw_canvas(&label_canvas, main_x, filename_y + (2.0 * scale) as i32);
-                fi.draw_to(canvas, colors, font);
-            }
+                // Location bar, covering the breadcrumbs
+                if let Some(input) = location_input {
+                    canvas.fill_rect(
+                        main_x as f32,
+                        main_y as f32,
+                        main_w as f32,
+                        path_bar_height as f32,
+                        colors.window_bg,
+                    );
+                    input.draw_to(canvas, colors, font);
+                }
 
-            // Buttons
-            ok_button.draw_to(canvas, colors, font);
-            cancel_button.draw_to(canvas, colors, font);
+                // Filename row (save mode)
+                if let Some(fi) = filename_input {
+                    canvas.draw_canvas(&filename_label, filename_row.label_x, filename_row.label_y);
+                    fi.draw_to(canvas, colors, font);
+                }
 
-            // Status bar
-            let status = format!("{} items", filtered_entries.len());
-            let status_canvas = font.render(&status).with_color(rgb(120, 120, 120)).finish();
-            canvas.draw_canvas(&status_canvas, main_x, button_y + (8.0 * scale) as i32);
-        };
+                // Buttons
+                ok_button.draw_to(canvas, colors, font);
+                cancel_button.draw_to(canvas, colors, font);
+                if let Some(button) = new_folder_button {
+                    button.draw_to(canvas, colors, font);
+                }
+                if let Some(combo) = filter_combo {
+                    combo.draw_to(canvas, colors, font);
+                }
+
+                // Status bar
+                let status = format!("{} items", filtered_entries.len());
+                let status_canvas = font.render(&status).with_color(rgb(120, 120, 120)).finish();
+                canvas.draw_canvas(&status_canvas, main_x, button_y + (8.0 * scale) as i32);
+            };
 
         // Initial draw
         let sig = ChromeSig {
@@ -923,6 +1496,7 @@ impl FileSelectBuilder {
             show_hidden,
             hovered_qa: hovered_quick_access,
             hovered_drive,
+            sidebar_scroll,
             history_index,
             history_len: history.len(),
             search: search_input.text().to_owned(),
@@ -932,6 +1506,7 @@ impl FileSelectBuilder {
                 .map(|f| (f.text().to_owned(), f.has_focus())),
             qa_len: quick_access.len(),
             drives_len: mounted_drives.len(),
+            sort,
         };
         if chrome_sig.as_ref() != Some(&sig) {
             draw_chrome(
@@ -943,9 +1518,11 @@ impl FileSelectBuilder {
                 &mounted_drives,
                 hovered_quick_access,
                 hovered_drive,
+                sidebar_scroll,
                 &history,
                 history_index,
                 show_hidden,
+                sort,
                 &search_input,
                 scale,
             );
@@ -966,10 +1543,17 @@ impl FileSelectBuilder {
             &ok_button,
             &cancel_button,
             filename_input.as_ref(),
+            filter_combo.as_ref(),
+            location_input.as_ref(),
+            new_folder_button.as_ref(),
+            new_folder_input
+                .as_ref()
+                .map(|input| (input, new_folder_error.as_deref())),
         );
+        if let Some(combo) = &filter_combo {
+            combo.draw_popup(&mut canvas, colors, &font);
+        }
         if save_mode && !completion_matches.is_empty() {
-            let visible = completion_matches.len().min(MAX_POPUP_ITEMS);
-            let popup_h = (visible as i32) * POPUP_ITEM_HEIGHT + 2;
             draw_completion_popup(
                 &mut canvas,
                 &font,
@@ -977,7 +1561,7 @@ impl FileSelectBuilder {
                 &completion_matches,
                 completion_popup_index,
                 main_x,
-                filename_y + filename_label_h - popup_h,
+                completion_popup_y,
                 main_w,
             );
         }
@@ -994,16 +1578,36 @@ impl FileSelectBuilder {
             );
         }
         window.set_contents(&canvas)?;
+        if let Some((x, y)) = self.position {
+            window.set_position(x, y)?;
+        }
         window.show()?;
 
         // Event loop
         loop {
-            let event = window.wait_for_event()?;
+            let event = match held.pop_front() {
+                Some(event) => event,
+                None => {
+                    match wait_for_event_until(&mut window, deadline)? {
+                        Some(event) => event,
+                        None => return Ok(Some(FileSelectResult::Timeout)),
+                    }
+                }
+            };
             let mut needs_redraw = false;
+            // Set by clicks, Enter and OK; the clicked entry, if any, is the target
+            let mut activation: Option<(Activation, Option<usize>)> = None;
+            let filter_open = filter_combo.as_ref().is_some_and(ComboBox::is_open);
 
             match &event {
-                WindowEvent::CloseRequested => return Ok(FileSelectResult::Closed),
+                WindowEvent::CloseRequested => return Ok(Some(FileSelectResult::Closed)),
                 WindowEvent::RedrawRequested => needs_redraw = true,
+                // The open filter list takes the input until it closes
+                _ if filter_open => {
+                    if let Some(combo) = &mut filter_combo {
+                        needs_redraw |= combo.process_event(&event);
+                    }
+                }
                 WindowEvent::CursorEnter(pos) | WindowEvent::CursorMove(pos) => {
                     if window_dragging {
                         let _ = window.start_drag();
@@ -1063,9 +1667,11 @@ impl FileSelectBuilder {
                         if mouse_x >= sidebar_x
                             && mouse_x < sidebar_x + sidebar_width as i32
                             && mouse_y >= sidebar_y
+                            && mouse_y < sidebar_y + sidebar_h as i32
                         {
-                            let places_items_start_y =
-                                sidebar_y + (8.0 * scale) as i32 + section_header_height as i32;
+                            let places_items_start_y = sidebar_y - sidebar_scroll
+                                + (8.0 * scale) as i32
+                                + section_header_height as i32;
                             let rel_y = mouse_y - places_items_start_y;
                             if rel_y >= 0 {
                                 let idx = (rel_y as f32 / item_height_scaled as f32) as usize;
@@ -1105,9 +1711,14 @@ impl FileSelectBuilder {
                             && mouse_y < list_y + list_h as i32
                             && !filtered_entries.is_empty();
 
+                        // The new folder row covers the top of the list
+                        let rows_y = match new_folder_input {
+                            Some(_) => list_y + new_folder_row_h as i32,
+                            None => list_y,
+                        };
                         if mouse_x >= main_x
                             && mouse_x < scrollbar_x
-                            && mouse_y >= list_y
+                            && mouse_y >= rows_y
                             && mouse_y < list_y + list_h as i32
                         {
                             let rel_y = (mouse_y - list_y) as usize;
@@ -1125,7 +1736,8 @@ impl FileSelectBuilder {
                         }
                     }
                 }
-                WindowEvent::ButtonPress(MouseButton::Left, _) => {
+                WindowEvent::ButtonPress(MouseButton::Left, modifiers) => {
+                    click_modifiers = *modifiers;
                     window_dragging = true;
                     let mut clicking_scrollbar = false;
 
@@ -1195,12 +1807,13 @@ impl FileSelectBuilder {
                                     &mut all_entries,
                                     self.directory,
                                     show_hidden,
+                                    sort,
                                 );
                                 update_filtered(
                                     &all_entries,
                                     &search_text,
                                     &mut filtered_entries,
-                                    &self.filters,
+                                    active_filter,
                                 );
                                 selected_indices.clear();
                                 scroll_offset = 0;
@@ -1219,12 +1832,13 @@ impl FileSelectBuilder {
                                     &mut all_entries,
                                     self.directory,
                                     show_hidden,
+                                    sort,
                                 );
                                 update_filtered(
                                     &all_entries,
                                     &search_text,
                                     &mut filtered_entries,
-                                    &self.filters,
+                                    active_filter,
                                 );
                                 selected_indices.clear();
                                 scroll_offset = 0;
@@ -1244,11 +1858,12 @@ impl FileSelectBuilder {
                                     &mut all_entries,
                                     self.directory,
                                     show_hidden,
+                                    sort,
                                     &search_text,
                                     &mut filtered_entries,
                                     &mut selected_indices,
                                     &mut scroll_offset,
-                                    &self.filters,
+                                    active_filter,
                                 );
                                 needs_redraw = true;
                             }
@@ -1266,11 +1881,12 @@ impl FileSelectBuilder {
                                     &mut all_entries,
                                     self.directory,
                                     show_hidden,
+                                    sort,
                                     &search_text,
                                     &mut filtered_entries,
                                     &mut selected_indices,
                                     &mut scroll_offset,
-                                    &self.filters,
+                                    active_filter,
                                 );
                                 needs_redraw = true;
                             }
@@ -1285,12 +1901,13 @@ impl FileSelectBuilder {
                                 &mut all_entries,
                                 self.directory,
                                 show_hidden,
+                                sort,
                             );
                             update_filtered(
                                 &all_entries,
                                 &search_text,
                                 &mut filtered_entries,
-                                &self.filters,
+                                active_filter,
                             );
                             selected_indices.clear();
                             scroll_offset = 0;
@@ -1298,12 +1915,28 @@ impl FileSelectBuilder {
                         }
                     }
 
-                    // Breadcrumb (path bar) click
-                    if mouse_y >= main_y
+                    // A click anywhere else closes the location bar
+                    let in_path_bar = mouse_y >= main_y
                         && mouse_y < main_y + path_bar_height as i32
                         && mouse_x >= main_x
+                        && mouse_x < main_x + main_w as i32;
+                    if location_input.is_some() && !in_path_bar {
+                        location_input = None;
+                        needs_redraw = true;
+                    }
+                    // And the new folder row
+                    let in_new_folder_row = mouse_x >= main_x
                         && mouse_x < main_x + main_w as i32
-                    {
+                        && mouse_y >= list_y
+                        && mouse_y < list_y + new_folder_row_h as i32;
+                    if new_folder_input.is_some() && !in_new_folder_row {
+                        new_folder_input = None;
+                        new_folder_error = None;
+                        needs_redraw = true;
+                    }
+
+                    // Breadcrumb (path bar) click
+                    if in_path_bar && location_input.is_none() {
                         let crumbs = breadcrumb_layout(
                             &current_dir,
                             main_x + (8.0 * scale) as i32,
@@ -1321,11 +1954,12 @@ impl FileSelectBuilder {
                                     &mut all_entries,
                                     self.directory,
                                     show_hidden,
+                                    sort,
                                     &search_text,
                                     &mut filtered_entries,
                                     &mut selected_indices,
                                     &mut scroll_offset,
-                                    &self.filters,
+                                    active_filter,
                                 );
                                 needs_redraw = true;
                                 break;
@@ -1333,6 +1967,49 @@ impl FileSelectBuilder {
                         }
                     }
 
+                    // Clicking a column header sorts by it, or flips the order
+                    let header_y = main_y + path_bar_height as i32;
+                    if mouse_x >= main_x
+                        && mouse_x < main_x + main_w as i32
+                        && mouse_y >= header_y
+                        && mouse_y < header_y + (26.0 * scale) as i32
+                    {
+                        let column = if mouse_x < main_x + name_col_width as i32 {
+                            FileSort::Name
+                        } else if mouse_x
+                            < main_x
+                                + (name_col_width + size_col_width) as i32
+                                + (12.0 * scale) as i32
+                        {
+                            FileSort::Size
+                        } else {
+                            FileSort::Modified
+                        };
+                        sort = (column, sort.0 == column && !sort.1);
+                        // Entries move, so keep the selection by path
+                        let selected: HashSet<PathBuf> = selected_indices
+                            .iter()
+                            .map(|&i| all_entries[i].path.clone())
+                            .collect();
+                        sort_entries(&mut all_entries, sort);
+                        selected_indices = all_entries
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, entry)| selected.contains(&entry.path))
+                            .map(|(i, _)| i)
+                            .collect();
+                        update_filtered(
+                            &all_entries,
+                            &search_text,
+                            &mut filtered_entries,
+                            active_filter,
+                        );
+                        hovered_entry = None;
+                        selection_anchor = None;
+                        last_click = None;
+                        needs_redraw = true;
+                    }
+
                     // Quick access click
                     if !clicking_scrollbar {
                         if let Some(idx) = hovered_quick_access {
@@ -1345,11 +2022,12 @@ impl FileSelectBuilder {
                                 &mut all_entries,
                                 self.directory,
                                 show_hidden,
+                                sort,
                                 &search_text,
                                 &mut filtered_entries,
                                 &mut selected_indices,
                                 &mut scroll_offset,
-                                &self.filters,
+                                active_filter,
                             );
                             needs_redraw = true;
                         }
@@ -1365,76 +2043,29 @@ impl FileSelectBuilder {
                                 &mut all_entries,
                                 self.directory,
                                 show_hidden,
+                                sort,
                                 &search_text,
                                 &mut filtered_entries,
                                 &mut selected_indices,
                                 &mut scroll_offset,
-                                &self.filters,
+                                active_filter,
                             );
                             needs_redraw = true;
                         }
 
                         // File list click
                         if let Some(ei) = hovered_entry {
-                            if self.multiple {
-                                // Toggle selection in multiple mode
-                                if selected_indices.contains(&ei) {
-                                    selected_indices.remove(&ei);
-                                } else {
-                                    selected_indices.insert(ei);
-                                }
+                            let now = Instant::now();
+                            let double = last_click.is_some_and(|(at, last)| {
+                                last == ei && now.duration_since(at) < MULTI_CLICK_TIME
+                            });
+                            last_click = (!double).then_some((now, ei));
+                            let action = if double {
+                                Activation::DoubleClick
                             } else {
-                                // Single click - activate if already selected (double click behavior)
-                                if selected_indices.contains(&ei) {
-                                    let entry = &all_entries[ei];
-                                    if entry.is_dir {
-                                        navigate_to(
-                                            entry.path.clone(),
-                                            &mut current_dir,
-                                            &mut history,
-                                            &mut history_index,
-                                        );
-                                        load_directory(
-                                            &current_dir,
-                                            &mut all_entries,
-                                            self.directory,
-                                            show_hidden,
-                                        );
-                                        update_filtered(
-                                            &all_entries,
-                                            &search_text,
-                                            &mut filtered_entries,
-                                            &self.filters,
-                                        );
-                                        selected_indices.clear();
-                                        scroll_offset = 0;
-                                    } else if save_mode {
-                                        // In save mode, double-click on file populates filename
-                                        if let Some(ref mut fi) = filename_input {
-                                            fi.set_text(&entry.name);
-                                            completion_matches.clear();
-                                            completion_popup_index = 0;
-                                        }
-                                    } else if !self.directory {
-                                        return Ok(FileSelectResult::Selected(entry.path.clone()));
-                                    }
-                                } else {
-                                    selected_indices.clear();
-                                    selected_indices.insert(ei);
-                                    // In save mode, single click on file populates filename input
-                                    if save_mode {
-                                        let entry = &all_entries[ei];
-                                        if !entry.is_dir {
-                                            if let Some(ref mut fi) = filename_input {
-                                                fi.set_text(&entry.name);
-                                                completion_matches.clear();
-                                                completion_popup_index = 0;
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                            needs_redraw = true;
+                                Activation::Click
+                            };
+                            activation = Some((action, Some(ei)));
                         }
                     }
 
@@ -1475,6 +2106,13 @@ impl FileSelectBuilder {
                         }
                         search_input.set_focus(in_search);
                     }
+                    // Clicks in the location bar or new folder row leave it focused
+                    if location_input.is_some() || new_folder_input.is_some() {
+                        search_input.set_focus(false);
+                        if let Some(ref mut fi) = filename_input {
+                            fi.set_focus(false);
+                        }
+                    }
                     if search_input.has_focus() != search_focused_before
                         || filename_input.as_ref().is_some_and(|f| f.has_focus())
                             != filename_focused_before
@@ -1487,7 +2125,28 @@ impl FileSelectBuilder {
                     thumb_drag = false;
                     thumb_drag_offset = None;
                 }
-                WindowEvent::Scroll(direction) => {
+                WindowEvent::Scroll(direction, _)
+                    if mouse_x >= sidebar_x
+                        && mouse_x < sidebar_x + sidebar_width as i32
+                        && mouse_y >= sidebar_y
+                        && mouse_y < sidebar_y + sidebar_h as i32 =>
+                {
+                    let step = 3 * item_height_scaled as i32;
+                    let scroll = match direction {
+                        crate::backend::ScrollDirection::Up => sidebar_scroll - step,
+                        crate::backend::ScrollDirection::Down => sidebar_scroll + step,
+                        _ => sidebar_scroll,
+                    }
+                    .clamp(0, sidebar_max_scroll);
+                    if scroll != sidebar_scroll {
+                        sidebar_scroll = scroll;
+                        // The places moved under the pointer
+                        hovered_quick_access = None;
+                        hovered_drive = None;
+                        needs_redraw = true;
+                    }
+                }
+                WindowEvent::Scroll(direction, _) => {
                     match direction {
                         crate::backend::ScrollDirection::Up => {
                             if scroll_offset > 0 {
@@ -1506,11 +2165,47 @@ impl FileSelectBuilder {
                     }
                 }
                 WindowEvent::KeyPress(key_event) => {
+                    if zoom::handle_key(key_event) {
+                        let name = filename_input.as_ref().map(|fi| fi.text().to_string());
+                        let filter = filter_combo.as_ref().map_or(0, ComboBox::selected);
+                        self.restored = Some((current_dir, name, show_hidden, filter));
+                        self.sort = sort;
+                        return Ok(None);
+                    }
                     let filename_has_focus =
                         filename_input.as_ref().map_or(false, |fi| fi.has_focus());
 
+                    // Ctrl+L opens a bar to type a path into, over the breadcrumbs
+                    if key_event.keysym == KEY_L && key_event.modifiers.contains(Modifiers::CTRL) {
+                        let input_h = TextInput::new(0).height();
+                        let mut input = TextInput::new(main_w - (8.0 * scale) as u32);
+                        let mut text = current_dir.to_string_lossy().into_owned();
+                        if !text.ends_with('/') {
+                            text.push('/');
+                        }
+                        input.set_text(&text);
+                        input.set_focus(true);
+                        input.set_position(
+                            main_x + (4.0 * scale) as i32,
+                            main_y + (path_bar_height as i32 - input_h as i32) / 2,
+                        );
+                        search_input.set_focus(false);
+                        if let Some(ref mut fi) = filename_input {
+                            fi.set_focus(false);
+                        }
+                        location_input = Some(input);
+                        needs_redraw = true;
+                    }
+
                     if key_event.keysym == KEY_ESCAPE {
-                        if search_input.has_focus() {
+                        if location_input.take().is_some() || new_folder_input.take().is_some() {
+                            new_folder_error = None;
+                            // In save mode, return focus to filename input
+                            if let Some(ref mut fi) = filename_input {
+                                fi.set_focus(true);
+                            }
+                            needs_redraw = true;
+                        } else if search_input.has_focus() {
                             if !search_matches.is_empty() {
                                 // Close search popup first
                                 search_matches.clear();
@@ -1539,131 +2234,65 @@ impl FileSelectBuilder {
                             }
                             needs_redraw = true;
                         } else {
-                            return Ok(FileSelectResult::Cancelled);
+                            return Ok(Some(FileSelectResult::Cancelled));
                         }
                     }
-                    if !search_input.has_focus() && !filename_has_focus {
+                    if !search_input.has_focus()
+                        && !filename_has_focus
+                        && location_input.is_none()
+                        && new_folder_input.is_none()
+                    {
+                        let alt = key_event.modifiers.contains(Modifiers::ALT);
                         match key_event.keysym {
-                            KEY_UP => {
-                                if !filtered_entries.is_empty() {
-                                    let new_index =
-                                        if let Some(&sel) = selected_indices.iter().next() {
-                                            if let Some(pos) =
-                                                filtered_entries.iter().position(|&e| e == sel)
-                                            {
-                                                if pos > 0 {
-                                                    Some(filtered_entries[pos - 1])
-                                                } else {
-                                                    Some(sel)
-                                                }
-                                            } else {
-                                                Some(filtered_entries[0])
-                                            }
-                                        } else {
-                                            Some(filtered_entries[0])
-                                        };
-
-                                    if let Some(idx) = new_index {
-                                        if self.multiple {
-                                            if selected_indices.contains(&idx) {
-                                                selected_indices.remove(&idx);
-                                            } else {
-                                                selected_indices.insert(idx);
-                                            }
-                                        } else {
-                                            selected_indices.clear();
-                                            selected_indices.insert(idx);
-                                        }
-
-                                        if let Some(pos) =
-                                            filtered_entries.iter().position(|&e| e == idx)
-                                        {
-                                            if pos < scroll_offset {
-                                                scroll_offset = pos;
-                                            }
-                                        }
-                                        needs_redraw = true;
+                            KEY_UP | KEY_DOWN | KEY_HOME | KEY_END | KEY_PAGE_UP
+                            | KEY_PAGE_DOWN
+                                if !alt && !filtered_entries.is_empty() =>
+                            {
+                                let last = filtered_entries.len() - 1;
+                                let page = visible_items.max(1);
+                                // The cursor is the entry last clicked or moved to
+                                let cursor = selection_anchor
+                                    .filter(|a| selected_indices.contains(a))
+                                    .and_then(|a| filtered_entries.iter().position(|&e| e == a))
+                                    .or_else(|| {
+                                        filtered_entries
+                                            .iter()
+                                            .position(|e| selected_indices.contains(e))
+                                    });
+                                let target = match (key_event.keysym, cursor) {
+                                    (KEY_END, _) => last,
+                                    (KEY_HOME, _) | (_, None) => 0,
+                                    (KEY_UP, Some(pos)) => pos.saturating_sub(1),
+                                    (KEY_DOWN, Some(pos)) => (pos + 1).min(last),
+                                    (KEY_PAGE_UP, Some(pos)) => pos.saturating_sub(page),
+                                    (_, Some(pos)) => (pos + page).min(last),
+                                };
+                                // Page keys scroll by a viewport as well
+                                let max_scroll =
+                                    filtered_entries.len().saturating_sub(visible_items);
+                                match key_event.keysym {
+                                    KEY_PAGE_UP => {
+                                        scroll_offset = scroll_offset.saturating_sub(page)
                                     }
-                                }
-                            }
-                            KEY_DOWN => {
-                                if !filtered_entries.is_empty() {
-                                    let new_index =
-                                        if let Some(&sel) = selected_indices.iter().next() {
-                                            if let Some(pos) =
-                                                filtered_entries.iter().position(|&e| e == sel)
-                                            {
-                                                if pos + 1 < filtered_entries.len() {
-                                                    Some(filtered_entries[pos + 1])
-                                                } else {
-                                                    Some(sel)
-                                                }
-                                            } else {
-                                                Some(filtered_entries[0])
-                                            }
-                                        } else {
-                                            Some(filtered_entries[0])
-                                        };
-
-                                    if let Some(idx) = new_index {
-                                        if self.multiple {
-                                            if selected_indices.contains(&idx) {
-                                                selected_indices.remove(&idx);
-                                            } else {
-                                                selected_indices.insert(idx);
-                                            }
-                                        } else {
-                                            selected_indices.clear();
-                                            selected_indices.insert(idx);
-                                        }
-
-                                        if let Some(pos) =
-                                            filtered_entries.iter().position(|&e| e == idx)
-                                        {
-                                            if pos + 1 >= scroll_offset + visible_items {
-                                                scroll_offset = pos + 1 - visible_items + 1;
-                                            }
-                                        }
-                                        needs_redraw = true;
+                                    KEY_PAGE_DOWN => {
+                                        scroll_offset = (scroll_offset + page).min(max_scroll)
                                     }
+                                    _ => {}
                                 }
+                                select_listed(
+                                    target,
+                                    &filtered_entries,
+                                    &mut selected_indices,
+                                    &mut selection_anchor,
+                                    &mut scroll_offset,
+                                    visible_items,
+                                );
+                                needs_redraw = true;
                             }
                             KEY_RETURN => {
-                                if self.multiple && !selected_indices.is_empty() {
-                                    let selected_files: Vec<PathBuf> = selected_indices
-                                        .iter()
-                                        .filter(|&ei| !all_entries[*ei].is_dir)
-                                        .map(|&ei| all_entries[ei].path.clone())
-                                        .collect();
-                                    if !selected_files.is_empty() {
-                                        return Ok(FileSelectResult::SelectedMultiple(
-                                            selected_files,
-                                        ));
-                                    }
-                                } else if let Some(&sel) = selected_indices.iter().next() {
-                                    let entry = &all_entries[sel];
-                                    if entry.is_dir {
-                                        navigate_to_directory(
-                                            entry.path.clone(),
-                                            &mut current_dir,
-                                            &mut history,
-                                            &mut history_index,
-                                            &mut all_entries,
-                                            self.directory,
-                                            show_hidden,
-                                            &search_text,
-                                            &mut filtered_entries,
-                                            &mut selected_indices,
-                                            &mut scroll_offset,
-                                            &self.filters,
-                                        );
-                                        needs_redraw = true;
-                                    } else if !self.directory {
-                                        return Ok(FileSelectResult::Selected(entry.path.clone()));
-                                    }
-                                }
+                                activation = Some((Activation::Enter, None));
                             }
-                            KEY_BACKSPACE => {
+                            KEY_BACKSPACE | KEY_UP if key_event.keysym == KEY_BACKSPACE || alt => {
                                 if let Some(parent) = current_dir.parent() {
                                     navigate_to_directory(
                                         parent.to_path_buf(),
@@ -1673,11 +2302,12 @@ impl FileSelectBuilder {
                                         &mut all_entries,
                                         self.directory,
                                         show_hidden,
+                                        sort,
                                         &search_text,
                                         &mut filtered_entries,
                                         &mut selected_indices,
                                         &mut scroll_offset,
-                                        &self.filters,
+                                        active_filter,
                                     );
                                     needs_redraw = true;
                                 }
@@ -1686,9 +2316,145 @@ impl FileSelectBuilder {
                         }
                     }
                 }
+                WindowEvent::TextInput(c)
+                    if !search_input.has_focus()
+                        && !filename_input.as_ref().is_some_and(|fi| fi.has_focus())
+                        && location_input.is_none()
+                        && new_folder_input.is_none()
+                        && !c.is_control() =>
+                {
+                    let now = Instant::now();
+                    if typeahead_at.is_none_or(|at| now.duration_since(at) > TYPEAHEAD_RESET) {
+                        typeahead.clear();
+                    }
+                    typeahead_at = Some(now);
+                    typeahead.extend(c.to_lowercase());
+                    let found = filtered_entries.iter().position(|&idx| {
+                        let name = &all_entries[idx].name;
+                        name != ".." && name.to_lowercase().starts_with(&typeahead)
+                    });
+                    if let Some(pos) = found {
+                        select_listed(
+                            pos,
+                            &filtered_entries,
+                            &mut selected_indices,
+                            &mut selection_anchor,
+                            &mut scroll_offset,
+                            visible_items,
+                        );
+                        needs_redraw = true;
+                    }
+                }
                 _ => {}
             }
 
+            // Process the location bar: Tab completes, Enter goes to the path
+            if let Some(ref mut input) = location_input {
+                let text_before = input.text().to_string();
+                if input.process_event(&event) {
+                    needs_redraw = true;
+                }
+                let tab = input.was_tab_pressed();
+                if tab || input.text() != text_before {
+                    input.set_invalid(false);
+                    input.set_completion(complete_location(input.text()));
+                    needs_redraw = true;
+                }
+                if input.was_submitted() {
+                    let target = current_dir.join(input.text());
+                    let (dir, file) = if target.is_dir() {
+                        (Some(target), None)
+                    } else if target.is_file() {
+                        (target.parent().map(Path::to_path_buf), target.file_name())
+                    } else {
+                        (None, None)
+                    };
+                    match dir {
+                        Some(dir) => {
+                            let file = file.map(|name| name.to_string_lossy().into_owned());
+                            navigate_to_directory(
+                                dir,
+                                &mut current_dir,
+                                &mut history,
+                                &mut history_index,
+                                &mut all_entries,
+                                self.directory,
+                                show_hidden,
+                                sort,
+                                &search_text,
+                                &mut filtered_entries,
+                                &mut selected_indices,
+                                &mut scroll_offset,
+                                active_filter,
+                            );
+                            if let Some(pos) = file.and_then(|name| {
+                                filtered_entries
+                                    .iter()
+                                    .position(|&idx| all_entries[idx].name == name)
+                            }) {
+                                select_listed(
+                                    pos,
+                                    &filtered_entries,
+                                    &mut selected_indices,
+                                    &mut selection_anchor,
+                                    &mut scroll_offset,
+                                    visible_items,
+                                );
+                            }
+                            location_input = None;
+                            if let Some(ref mut fi) = filename_input {
+                                fi.set_focus(true);
+                            }
+                        }
+                        None => input.set_invalid(true),
+                    }
+                    needs_redraw = true;
+                }
+            }
+
+            // Process the new folder row: Enter creates the folder and opens it
+            if let Some(ref mut input) = new_folder_input {
+                let text_before = input.text().to_string();
+                if input.process_event(&event) {
+                    needs_redraw = true;
+                }
+                if input.text() != text_before {
+                    input.set_invalid(false);
+                    new_folder_error = None;
+                }
+                if input.was_submitted() {
+                    match create_folder(&current_dir, input.text().trim()) {
+                        Ok(Some(path)) => {
+                            navigate_to_directory(
+                                path,
+                                &mut current_dir,
+                                &mut history,
+                                &mut history_index,
+                                &mut all_entries,
+                                self.directory,
+                                show_hidden,
+                                sort,
+                                &search_text,
+                                &mut filtered_entries,
+                                &mut selected_indices,
+                                &mut scroll_offset,
+                                active_filter,
+                            );
+                            new_folder_input = None;
+                            if let Some(ref mut fi) = filename_input {
+                                fi.set_focus(true);
+                            }
+                        }
+                        Ok(None) => {}
+                        Err(message) => {
+                            input.set_invalid(true);
+                            new_folder_error = Some(message);
+                        }
+                    }
+                    needs_redraw = true;
+                }
+            }
+
             // Process search input (with completion popup)
             {
                 let mut search_popup_handled = false;
@@ -1762,7 +2528,7 @@ impl FileSelectBuilder {
                                         &all_entries,
                                         &search_text,
                                         &mut filtered_entries,
-                                        &self.filters,
+                                        active_filter,
                                     );
                                     selected_indices.clear();
                                     scroll_offset = 0;
@@ -1786,7 +2552,7 @@ impl FileSelectBuilder {
                             &all_entries,
                             &search_text,
                             &mut filtered_entries,
-                            &self.filters,
+                            active_filter,
                         );
                         selected_indices.clear();
                         scroll_offset = 0;
@@ -1848,7 +2614,7 @@ impl FileSelectBuilder {
                                 &all_entries,
                                 &search_text,
                                 &mut filtered_entries,
-                                &self.filters,
+                                active_filter,
                             );
                             selected_indices.clear();
                             scroll_offset = 0;
@@ -1867,7 +2633,7 @@ impl FileSelectBuilder {
                                 &all_entries,
                                 &search_text,
                                 &mut filtered_entries,
-                                &self.filters,
+                                active_filter,
                             );
                             selected_indices.clear();
                             scroll_offset = 0;
@@ -1922,7 +2688,7 @@ impl FileSelectBuilder {
                         let popup_w = main_w as i32;
                         let visible = completion_matches.len().min(MAX_POPUP_ITEMS) as i32;
                         let popup_h = visible * POPUP_ITEM_HEIGHT + 2;
-                        let popup_y = filename_y + filename_label_h - popup_h;
+                        let popup_y = completion_popup_y;
                         if mouse_x >= popup_x
                             && mouse_x < popup_x + popup_w
                             && mouse_y >= popup_y
@@ -1989,121 +2755,212 @@ impl FileSelectBuilder {
                         }
                         needs_redraw = true;
                     }
-                    if fi.was_submitted() {
-                        // If popup is open, accept the highlighted item instead of submitting
-                        if !completion_matches.is_empty() {
-                            fi.set_text(&completion_matches[completion_popup_index]);
+                    if fi.was_submitted() {
+                        // If popup is open, accept the highlighted item instead of submitting
+                        if !completion_matches.is_empty() {
+                            fi.set_text(&completion_matches[completion_popup_index]);
+                            completion_matches.clear();
+                            completion_popup_index = 0;
+                            needs_redraw = true;
+                        } else {
+                            activation = Some((Activation::Enter, None));
+                        }
+                    }
+                }
+            }
+
+            // Process buttons
+            if !filter_open && let Some(combo) = &mut filter_combo {
+                needs_redraw |= combo.process_event(&event);
+            }
+            if let Some(combo) = &mut filter_combo
+                && combo.was_changed()
+            {
+                active_filter = &self.filters[combo.selected()..=combo.selected()];
+                update_filtered(
+                    &all_entries,
+                    &search_text,
+                    &mut filtered_entries,
+                    active_filter,
+                );
+                // Files the new filter hides can't stay selected
+                selected_indices.retain(|i| filtered_entries.contains(i));
+                scroll_offset = 0;
+                needs_redraw = true;
+            }
+            needs_redraw |= ok_button.process_event(&event);
+            needs_redraw |= cancel_button.process_event(&event);
+            if let Some(button) = &mut new_folder_button {
+                needs_redraw |= button.process_event(&event);
+                if button.was_clicked() {
+                    let mut input = TextInput::new(name_col_width - (32.0 * scale) as u32)
+                        .with_placeholder("Folder name");
+                    input.set_position(
+                        main_x + (32.0 * scale) as i32,
+                        list_y + (4.0 * scale) as i32,
+                    );
+                    input.set_focus(true);
+                    new_folder_input = Some(input);
+                    new_folder_error = None;
+                    location_input = None;
+                    search_input.set_focus(false);
+                    if let Some(ref mut fi) = filename_input {
+                        fi.set_focus(false);
+                    }
+                    completion_matches.clear();
+                    completion_popup_index = 0;
+                    needs_redraw = true;
+                }
+            }
+
+            if ok_button.was_clicked() {
+                activation = Some((Activation::Ok, None));
+            }
+
+            if let Some((action, clicked)) = activation {
+                let targets: Vec<usize> = match clicked {
+                    Some(ei) => vec![ei],
+                    None => selected_indices.iter().copied().collect(),
+                };
+                let target = match targets.as_slice() {
+                    [] => ActivationTarget::Nothing,
+                    [ei] if all_entries[*ei].is_dir => ActivationTarget::Directory,
+                    [_] => ActivationTarget::File,
+                    _ => ActivationTarget::Several,
+                };
+                let typed_name = filename_input
+                    .as_ref()
+                    .map(|fi| fi.text().trim().to_string())
+                    .filter(|name| !name.is_empty());
+
+                match activation_outcome(mode, action, target, typed_name.is_some()) {
+                    ActivationOutcome::Ignore => {}
+                    ActivationOutcome::Select => {
+                        let ei = targets[0];
+                        if self.multiple {
+                            // An anchor left behind in another folder isn't selected
+                            let anchor = selection_anchor.filter(|a| selected_indices.contains(a));
+                            selection_anchor = Some(click_selection(
+                                &mut selected_indices,
+                                &filtered_entries,
+                                anchor,
+                                ei,
+                                click_modifiers,
+                            ));
+                        } else {
+                            selected_indices.clear();
+                            selected_indices.insert(ei);
+                        }
+                        // In save mode, clicking a file fills in its name
+                        let entry = &all_entries[ei];
+                        if save_mode
+                            && !entry.is_dir
+                            && let Some(fi) = filename_input.as_mut()
+                        {
+                            fi.set_text(&entry.name);
+                            completion_matches.clear();
+                            completion_popup_index = 0;
+                        }
+                        needs_redraw = true;
+                    }
+                    ActivationOutcome::EnterDirectory => {
+                        navigate_to_directory(
+                            all_entries[targets[0]].path.clone(),
+                            &mut current_dir,
+                            &mut history,
+                            &mut history_index,
+                            &mut all_entries,
+                            self.directory,
+                            show_hidden,
+                            sort,
+                            &search_text,
+                            &mut filtered_entries,
+                            &mut selected_indices,
+                            &mut scroll_offset,
+                            active_filter,
+                        );
+                        last_click = None;
+                        needs_redraw = true;
+                    }
+                    ActivationOutcome::Accept => {
+                        // Directory mode returns directories, the others files
+                        let mut paths: Vec<PathBuf> = targets
+                            .iter()
+                            .filter(|&&ei| all_entries[ei].is_dir == self.directory)
+                            .map(|&ei| all_entries[ei].path.clone())
+                            .collect();
+                        if self.multiple && !paths.is_empty() {
+                            return Ok(Some(FileSelectResult::SelectedMultiple(paths)));
+                        } else if let Some(path) = paths.pop() {
+                            return Ok(Some(FileSelectResult::Selected(path)));
+                        }
+                    }
+                    ActivationOutcome::AcceptTyped => {
+                        let Some(name) = typed_name else { continue };
+                        let path = current_dir.join(name);
+                        if path.is_dir() {
+                            // A typed folder name opens the folder
+                            navigate_to_directory(
+                                path,
+                                &mut current_dir,
+                                &mut history,
+                                &mut history_index,
+                                &mut all_entries,
+                                self.directory,
+                                show_hidden,
+                                sort,
+                                &search_text,
+                                &mut filtered_entries,
+                                &mut selected_indices,
+                                &mut scroll_offset,
+                                active_filter,
+                            );
+                            if let Some(ref mut fi) = filename_input {
+                                fi.set_text("");
+                            }
                             completion_matches.clear();
                             completion_popup_index = 0;
+                            last_click = None;
                             needs_redraw = true;
-                        } else {
-                            let name = fi.text().trim().to_string();
-                            if !name.is_empty() {
-                                return Ok(FileSelectResult::Selected(current_dir.join(&name)));
-                            }
-                        }
-                    }
-                }
-            }
-
-            // Process buttons
-            needs_redraw |= ok_button.process_event(&event);
-            needs_redraw |= cancel_button.process_event(&event);
-
-            if ok_button.was_clicked() {
-                // In save mode, use filename input text
-                if save_mode {
-                    if let Some(ref fi) = filename_input {
-                        let name = fi.text().trim().to_string();
-                        if !name.is_empty() {
-                            return Ok(FileSelectResult::Selected(current_dir.join(&name)));
+                        } else if !self.confirm_overwrite
+                            || !path.exists()
+                            || confirm_replace(&path, colors)?
+                        {
+                            return Ok(Some(FileSelectResult::Selected(path)));
                         }
                     }
-                } else if self.multiple && !selected_indices.is_empty() {
-                    let selected_files: Vec<PathBuf> = selected_indices
-                        .iter()
-                        .filter(|&ei| !all_entries[*ei].is_dir)
-                        .map(|&ei| all_entries[ei].path.clone())
-                        .collect();
-                    if !selected_files.is_empty() {
-                        return Ok(FileSelectResult::SelectedMultiple(selected_files));
+                    ActivationOutcome::AcceptCurrentDirectory => {
+                        return Ok(Some(FileSelectResult::Selected(current_dir.clone())));
                     }
-                } else if let Some(&sel) = selected_indices.iter().next() {
-                    let entry = &all_entries[sel];
-                    return Ok(FileSelectResult::Selected(entry.path.clone()));
-                } else if self.directory {
-                    return Ok(FileSelectResult::Selected(current_dir.clone()));
                 }
             }
 
             if cancel_button.was_clicked() {
-                return Ok(FileSelectResult::Cancelled);
+                return Ok(Some(FileSelectResult::Cancelled));
             }
 
-            // Batch pending events
-            while let Some(ev) = window.poll_for_event()? {
+            // Batch pending pointer motion. Anything else is left for the main
+            // loop, after the last move before it so clicks hit the right entry
+            let mut last_move = None;
+            while held.is_empty()
+                && let Some(ev) = window.poll_for_event()?
+            {
                 match &ev {
-                    WindowEvent::CloseRequested => {
-                        return Ok(FileSelectResult::Closed);
-                    }
                     WindowEvent::CursorEnter(pos) | WindowEvent::CursorMove(pos) => {
                         mouse_x = pos.x as i32;
                         mouse_y = pos.y as i32;
-                    }
-                    WindowEvent::ButtonPress(button, _modifiers)
-                        if *button == MouseButton::Left =>
-                    {
-                        if !filtered_entries.is_empty() {
-                            let scrollbar_x = main_x + main_w as i32 - (8.0 * scale) as i32;
-                            let scrollbar_y = list_y;
-
-                            if mouse_x >= main_x
-                                && mouse_x < main_x + main_w as i32
-                                && mouse_y >= list_y
-                                && mouse_y < list_y + list_h as i32
-                            {
-                                let visible_items = (list_h / item_height) as usize;
-                                let total_items = filtered_entries.len();
-
-                                if visible_items < total_items {
-                                    let scrollbar_h_f32 = list_h as f32 - 8.0 * scale;
-                                    let thumb_h_f32 = (visible_items as f32 / total_items as f32
-                                        * scrollbar_h_f32)
-                                        .max(20.0 * scale);
-                                    let thumb_h = thumb_h_f32 as i32;
-
-                                    let max_scroll = total_items - visible_items;
-                                    let max_thumb_y = scrollbar_h_f32 as i32 - thumb_h;
-                                    let thumb_y = if max_thumb_y > 0 {
-                                        ((scroll_offset as f32 / max_scroll as f32)
-                                            * max_thumb_y as f32)
-                                            as i32
-                                    } else {
-                                        0
-                                    };
-
-                                    let rel_y = mouse_y - scrollbar_y;
-                                    if mouse_x >= scrollbar_x
-                                        && mouse_x < scrollbar_x + (6.0 * scale) as i32
-                                        && rel_y >= thumb_y
-                                        && rel_y < thumb_y + thumb_h
-                                    {
-                                        thumb_drag = true;
-                                        thumb_drag_offset = Some(mouse_y - (scrollbar_y + thumb_y));
-                                    }
-                                }
-                            }
+                        needs_redraw |= ok_button.process_event(&ev);
+                        needs_redraw |= cancel_button.process_event(&ev);
+                        if let Some(button) = &mut new_folder_button {
+                            needs_redraw |= button.process_event(&ev);
                         }
+                        last_move = Some(ev);
                     }
-                    WindowEvent::ButtonRelease(_, _) => {
-                        thumb_drag = false;
-                        thumb_drag_offset = None;
+                    _ => {
+                        held.extend(last_move.take());
+                        held.push_back(ev);
                     }
-                    _ => {}
                 }
-
-                needs_redraw |= ok_button.process_event(&ev);
-                needs_redraw |= cancel_button.process_event(&ev);
             }
 
             if needs_redraw {
@@ -2112,6 +2969,7 @@ impl FileSelectBuilder {
                     show_hidden,
                     hovered_qa: hovered_quick_access,
                     hovered_drive,
+                    sidebar_scroll,
                     history_index,
                     history_len: history.len(),
                     search: search_input.text().to_owned(),
@@ -2121,6 +2979,7 @@ impl FileSelectBuilder {
                         .map(|f| (f.text().to_owned(), f.has_focus())),
                     qa_len: quick_access.len(),
                     drives_len: mounted_drives.len(),
+                    sort,
                 };
                 if chrome_sig.as_ref() != Some(&sig) {
                     draw_chrome(
@@ -2132,9 +2991,11 @@ impl FileSelectBuilder {
                         &mounted_drives,
                         hovered_quick_access,
                         hovered_drive,
+                        sidebar_scroll,
                         &history,
                         history_index,
                         show_hidden,
+                        sort,
                         &search_input,
                         scale,
                     );
@@ -2155,10 +3016,17 @@ impl FileSelectBuilder {
                     &ok_button,
                     &cancel_button,
                     filename_input.as_ref(),
+                    filter_combo.as_ref(),
+                    location_input.as_ref(),
+                    new_folder_button.as_ref(),
+                    new_folder_input
+                        .as_ref()
+                        .map(|input| (input, new_folder_error.as_deref())),
                 );
+                if let Some(combo) = &filter_combo {
+                    combo.draw_popup(&mut canvas, colors, &font);
+                }
                 if save_mode && !completion_matches.is_empty() {
-                    let visible = completion_matches.len().min(MAX_POPUP_ITEMS);
-                    let popup_h = (visible as i32) * POPUP_ITEM_HEIGHT + 2;
                     draw_completion_popup(
                         &mut canvas,
                         &font,
@@ -2166,7 +3034,7 @@ impl FileSelectBuilder {
                         &completion_matches,
                         completion_popup_index,
                         main_x,
-                        filename_y + filename_label_h - popup_h,
+                        completion_popup_y,
                         main_w,
                     );
                 }
@@ -2200,8 +3068,20 @@ struct DirEntry {
     name: String,
     path: PathBuf,
     is_dir: bool,
-    size: u64,
-    modified: Option<SystemTime>,
+    /// Size and modification time, read the first time they're needed so
+    /// huge folders open without a stat per entry.
+    stat: OnceCell<(u64, Option<SystemTime>)>,
+}
+
+impl DirEntry {
+    fn stat(&self) -> (u64, Option<SystemTime>) {
+        *self.stat.get_or_init(|| {
+            match self.path.metadata() {
+                Ok(metadata) => (metadata.len(), metadata.modified().ok()),
+                Err(_) => (0, None),
+            }
+        })
+    }
 }
 
 fn build_quick_access() -> Vec<QuickAccess> {
@@ -2257,54 +3137,125 @@ fn build_quick_access() -> Vec<QuickAccess> {
         });
     }
 
-    items
-}
+    // user-dirs.dirs disables a folder by pointing it at home
+    let home = items.first().map(|qa| qa.path.clone());
+    items.retain(|qa| {
+        matches!(qa.icon, QuickAccessIcon::Home)
+            || (Some(&qa.path) != home.as_ref() && qa.path.is_dir())
+    });
 
-fn get_mounted_drives() -> Vec<MountPoint> {
-    let mut drives = Vec::new();
-
-    // Parse /run/mount/utab for user-mounted drives (much cleaner than /proc/mounts)
-    if let Ok(content) = std::fs::read_to_string("/run/mount/utab") {
-        for line in content.lines() {
-            let mut device: Option<String> = None;
-            let mut mount_point: Option<PathBuf> = None;
-
-            // Parse KEY=VALUE pairs
-            for pair in line.split_whitespace() {
-                let mut kv = pair.split('=');
-                if let Some(key) = kv.next() {
-                    let value = kv.next();
-                    match key {
-                        "SRC" => {
-                            device = value.map(|v| v.to_string());
-                        }
-                        "TARGET" => {
-                            mount_point = value.map(PathBuf::from);
-                        }
-                        _ => {}
-                    }
-                }
-            }
+    items.push(QuickAccess {
+        name: "File System",
+        path: PathBuf::from("/"),
+        icon: QuickAccessIcon::Root,
+    });
 
-            // We have both source and target, create a mount point entry
-            if let (Some(dev), Some(mp)) = (device, mount_point) {
-                // Skip root filesystem
-                if mp.as_os_str() == "/" {
-                    continue;
-                }
+    items
+}
 
-                let label = get_volume_label(&dev);
+/// Filesystem types that only hold kernel or runtime state.
+const PSEUDO_FILESYSTEMS: &[&str] = &[
+    "autofs",
+    "binfmt_misc",
+    "bpf",
+    "cgroup",
+    "cgroup2",
+    "configfs",
+    "debugfs",
+    "devpts",
+    "devtmpfs",
+    "efivarfs",
+    "fusectl",
+    "hugetlbfs",
+    "mqueue",
+    "nsfs",
+    "overlay",
+    "proc",
+    "pstore",
+    "ramfs",
+    "rpc_pipefs",
+    "securityfs",
+    "selinuxfs",
+    "squashfs",
+    "sysfs",
+    "tmpfs",
+    "tracefs",
+];
+
+/// Mount points under these belong to the system rather than the user.
+const SYSTEM_MOUNT_DIRS: &[&str] = &[
+    "/boot", "/dev", "/efi", "/nix", "/opt", "/proc", "/run", "/snap", "/srv", "/sys", "/tmp",
+    "/usr", "/var",
+];
+
+/// The mounts in a `/proc/mounts` table worth listing as places, as device
+/// and mount point. Pseudo filesystems, system directories and the root,
+/// which has its own place, are left out; removable media under
+/// `/run/media` are kept. A later mount over the same point replaces the
+/// earlier one.
+pub(crate) fn parse_mounts(table: &str) -> Vec<(String, PathBuf)> {
+    let mut mounts: Vec<(String, PathBuf)> = Vec::new();
+    for line in table.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(device), Some(mount_point), Some(fs_type)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let mount_point = PathBuf::from(unescape_mount_field(mount_point));
+        let system = SYSTEM_MOUNT_DIRS
+            .iter()
+            .any(|dir| mount_point.starts_with(dir))
+            && !mount_point.starts_with("/run/media");
+        if PSEUDO_FILESYSTEMS.contains(&fs_type) || system || mount_point == Path::new("/") {
+            continue;
+        }
+        mounts.retain(|(_, existing)| *existing != mount_point);
+        mounts.push((unescape_mount_field(device), mount_point));
+    }
+    mounts
+}
 
-                drives.push(MountPoint {
-                    device: dev,
-                    mount_point: mp,
-                    label,
-                });
+/// Decodes the octal escapes `/proc/mounts` uses for spaces, tabs,
+/// newlines and backslashes, as in `My\040Disk`.
+fn unescape_mount_field(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let octal = bytes
+            .get(i + 1..i + 4)
+            .filter(|digits| bytes[i] == b'\\' && digits.iter().all(|d| (b'0'..=b'7').contains(d)));
+        match octal.and_then(|digits| u8::from_str_radix(std::str::from_utf8(digits).ok()?, 8).ok())
+        {
+            Some(byte) => {
+                out.push(byte);
+                i += 4;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
             }
         }
     }
+    String::from_utf8_lossy(&out).into_owned()
+}
 
-    drives
+fn get_mounted_drives() -> Vec<MountPoint> {
+    let table = std::fs::read_to_string("/proc/mounts").unwrap_or_default();
+    parse_mounts(&table)
+        .into_iter()
+        .map(|(device, mount_point)| {
+            MountPoint {
+                label: device
+                    .starts_with("/dev/")
+                    .then(|| get_volume_label(&device))
+                    .flatten(),
+                device,
+                mount_point,
+            }
+        })
+        .collect()
 }
 
 fn get_volume_label(device: &str) -> Option<String> {
@@ -2360,7 +3311,13 @@ fn get_mount_icon(device: &str) -> MountIcon {
     MountIcon::Generic
 }
 
-fn load_directory(path: &Path, entries: &mut Vec<DirEntry>, dirs_only: bool, show_hidden: bool) {
+fn load_directory(
+    path: &Path,
+    entries: &mut Vec<DirEntry>,
+    dirs_only: bool,
+    show_hidden: bool,
+    sort: (FileSort, bool),
+) {
     entries.clear();
 
     if let Some(parent) = path.parent() {
@@ -2368,14 +3325,10 @@ fn load_directory(path: &Path, entries: &mut Vec<DirEntry>, dirs_only: bool, sho
             name: "..".to_string(),
             path: parent.to_path_buf(),
             is_dir: true,
-            size: 0,
-            modified: None,
+            stat: OnceCell::from((0, None)),
         });
     }
 
-    let mut dirs: Vec<DirEntry> = Vec::new();
-    let mut files: Vec<DirEntry> = Vec::new();
-
     if let Ok(read_dir) = fs::read_dir(path) {
         for entry in read_dir.flatten() {
             let name = entry.file_name().to_string_lossy().to_string();
@@ -2384,37 +3337,59 @@ fn load_directory(path: &Path, entries: &mut Vec<DirEntry>, dirs_only: bool, sho
                 continue;
             }
 
-            let metadata = entry.path().metadata().ok();
-            let is_dir = metadata.as_ref().map(|m| m.is_dir()).unwrap_or(false);
+            // The directory listing says what most entries are; only links
+            // need a stat to find out where they point
+            let stat = OnceCell::new();
+            let is_dir = match entry.file_type() {
+                Ok(file_type) if !file_type.is_symlink() => file_type.is_dir(),
+                _ => {
+                    match entry.path().metadata() {
+                        Ok(metadata) => {
+                            let _ = stat.set((metadata.len(), metadata.modified().ok()));
+                            metadata.is_dir()
+                        }
+                        Err(_) => false,
+                    }
+                }
+            };
 
             if dirs_only && !is_dir {
                 continue;
             }
 
-            let size = metadata.as_ref().map(Metadata::len).unwrap_or(0);
-            let modified = metadata.as_ref().and_then(|m| m.modified().ok());
-
-            let de = DirEntry {
+            entries.push(DirEntry {
                 name,
                 path: entry.path(),
                 is_dir,
-                size,
-                modified,
-            };
-
-            if is_dir {
-                dirs.push(de);
-            } else {
-                files.push(de);
-            }
+                stat,
+            });
         }
     }
 
-    dirs.sort_by_key(|a| a.name.to_lowercase());
-    files.sort_by_key(|a| a.name.to_lowercase());
+    sort_entries(entries, sort);
+}
 
-    entries.extend(dirs);
-    entries.extend(files);
+/// Orders the entries after `..` with folders first, each group by the
+/// column and then by name ignoring case. Sorting by size or date reads
+/// every entry's metadata.
+fn sort_entries(entries: &mut [DirEntry], (column, descending): (FileSort, bool)) {
+    let start = usize::from(entries.first().is_some_and(|entry| entry.name == ".."));
+    let entries = &mut entries[start..];
+    entries.sort_by_cached_key(|entry| {
+        let (size, modified) = match column {
+            FileSort::Name => (0, None),
+            // Folder sizes say nothing about their contents
+            FileSort::Size if entry.is_dir => (0, None),
+            FileSort::Size => (entry.stat().0, None),
+            FileSort::Modified => (0, entry.stat().1),
+        };
+        (!entry.is_dir, size, modified, entry.name.to_lowercase())
+    });
+    if descending {
+        let dirs = entries.partition_point(|entry| entry.is_dir);
+        entries[..dirs].reverse();
+        entries[dirs..].reverse();
+    }
 }
 
 fn update_filtered(
@@ -2431,7 +3406,8 @@ fn update_filtered(
                 filtered.push(i);
             }
         } else {
-            let matches_filter = filters.is_empty() || matches_any_filter(&entry.name, filters);
+            let matches_filter =
+                filters.is_empty() || filters.iter().any(|filter| filter.matches(&entry.name));
             if matches_filter && matches_search {
                 filtered.push(i);
             }
@@ -2439,37 +3415,6 @@ fn update_filtered(
     }
 }
 
-fn matches_any_filter(name: &str, filters: &[FileFilter]) -> bool {
-    let name_lower = name.to_lowercase();
-    for filter in filters {
-        for pattern in &filter.patterns {
-            if matches_pattern(&name_lower, pattern) {
-                return true;
-            }
-        }
-    }
-    false
-}
-
-fn matches_pattern(name: &str, pattern: &str) -> bool {
-    let pattern_lower = pattern.to_lowercase();
-    if pattern_lower == "*" {
-        return true;
-    }
-
-    if pattern_lower.starts_with("*") && pattern_lower.ends_with("*") {
-        let inner = &pattern_lower[1..pattern_lower.len() - 1];
-        name.contains(inner)
-    } else if let Some(suffix) = pattern_lower.strip_prefix("*") {
-        name.ends_with(suffix)
-    } else if pattern_lower.ends_with("*") {
-        let prefix = &pattern_lower[..pattern_lower.len() - 1];
-        name.starts_with(prefix)
-    } else {
-        name == pattern_lower
-    }
-}
-
 fn navigate_to(
     dest: PathBuf,
     current: &mut PathBuf,
@@ -2492,6 +3437,7 @@ fn navigate_to_directory(
     all_entries: &mut Vec<DirEntry>,
     directory_mode: bool,
     show_hidden: bool,
+    sort: (FileSort, bool),
     search_text: &str,
     filtered_entries: &mut Vec<usize>,
     selected_indices: &mut HashSet<usize>,
@@ -2500,13 +3446,87 @@ fn navigate_to_directory(
 ) {
     if dest.exists() {
         navigate_to(dest, current_dir, history, history_index);
-        load_directory(current_dir, all_entries, directory_mode, show_hidden);
+        load_directory(current_dir, all_entries, directory_mode, show_hidden, sort);
         update_filtered(all_entries, search_text, filtered_entries, filters);
         selected_indices.clear();
         *scroll_offset = 0;
     }
 }
 
+/// Creates the folder `name` in `dir`, returning its path, or nothing if no
+/// name was given. Errors are worded to show beside the name.
+fn create_folder(dir: &Path, name: &str) -> Result<Option<PathBuf>, String> {
+    if name.is_empty() {
+        return Ok(None);
+    }
+    if name.contains('/') {
+        return Err("Folder names can't contain \"/\"".to_string());
+    }
+    let path = dir.join(name);
+    match fs::create_dir(&path) {
+        Ok(()) => Ok(Some(path)),
+        Err(err) => {
+            Err(match err.kind() {
+                io::ErrorKind::AlreadyExists => format!("\"{name}\" already exists"),
+                io::ErrorKind::PermissionDenied => "Permission denied".to_string(),
+                _ => err.to_string(),
+            })
+        }
+    }
+}
+
+/// Selects only the listed entry at `pos`, scrolling it into view.
+fn select_listed(
+    pos: usize,
+    filtered_entries: &[usize],
+    selected_indices: &mut HashSet<usize>,
+    selection_anchor: &mut Option<usize>,
+    scroll_offset: &mut usize,
+    visible_items: usize,
+) {
+    let idx = filtered_entries[pos];
+    selected_indices.clear();
+    selected_indices.insert(idx);
+    *selection_anchor = Some(idx);
+    if pos < *scroll_offset {
+        *scroll_offset = pos;
+    } else if pos >= *scroll_offset + visible_items {
+        *scroll_offset = pos + 1 - visible_items.max(1);
+    }
+}
+
+/// Completes the last component of a typed path, returning the text to
+/// append: as much as all names starting with it share, and a `/` after
+/// a single directory. Hidden names only complete once a `.` is typed.
+pub(crate) fn complete_location(text: &str) -> Option<String> {
+    let (dir, prefix) = match text.rfind('/') {
+        Some(slash) => (&text[..=slash], &text[slash + 1..]),
+        None => ("./", text),
+    };
+    let mut names = std::fs::read_dir(dir).ok()?.filter_map(|entry| {
+        let entry = entry.ok()?;
+        let name = entry.file_name().into_string().ok()?;
+        (name.starts_with(prefix) && (prefix.starts_with('.') || !name.starts_with('.')))
+            .then_some((name, entry))
+    });
+    let (first, entry) = names.next()?;
+    let mut common = first.len();
+    let mut single = true;
+    for (name, _) in names {
+        single = false;
+        common = first[..common]
+            .char_indices()
+            .zip(name.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(common.min(name.len()), |((i, _), _)| i);
+    }
+    let mut suffix = first[prefix.len()..common].to_string();
+    if single && std::fs::metadata(entry.path()).is_ok_and(|m| m.is_dir()) {
+        suffix.push('/');
+    }
+    (!suffix.is_empty()).then_some(suffix)
+}
+
 /// Returns all file entry names matching `prefix` (case-insensitive), up to `max` items.
 fn find_all_completions(
     entries: &[DirEntry],
@@ -2539,31 +3559,13 @@ fn find_all_completions(
 const POPUP_ITEM_HEIGHT: i32 = 26;
 const MAX_POPUP_ITEMS: usize = 8;
 
-/// Truncates `text` to fit within `max_width` pixels, appending an ellipsis
-/// ("…") when it had to be cut. Width is color-independent, so measurement uses
-/// a placeholder color.
-fn truncate_to_width(text: &str, max_width: i32, font: &Font) -> String {
-    let measure = |s: &str| font.render(s).with_color(rgb(0, 0, 0)).finish().width() as i32;
-    if measure(text) <= max_width {
-        return text.to_string();
-    }
-    let ellipsis = "…";
-    let ell_w = measure(ellipsis);
-    if max_width <= ell_w {
-        return ellipsis.to_string();
-    }
-    let mut out = String::new();
-    let mut w = 0i32;
-    for c in text.chars() {
-        let cw = measure(&c.to_string());
-        if w + cw > max_width - ell_w {
-            break;
-        }
-        out.push(c);
-        w += cw;
-    }
-    out.push('…');
-    out
+/// Asks whether to replace `path`, which already exists.
+fn confirm_replace(path: &Path, colors: &Colors) -> Result<bool, Error> {
+    let result = crate::question(&format!("Replace existing file?\n\n{}", path.display()))
+        .title("Confirm Save")
+        .colors(colors)
+        .show()?;
+    Ok(result == DialogResult::Button(0))
 }
 
 fn draw_completion_popup(
@@ -2621,8 +3623,13 @@ fn draw_completion_popup(
         } else {
             colors.text
         };
-        let display = truncate_to_width(name, width as i32 - 12, font);
-        let label = font.render(&display).with_color(text_color).finish();
+        // Completions share their start, so keep the end in view
+        let label = font
+            .render(name)
+            .with_color(text_color)
+            .with_max_width(width as f32 - 12.0)
+            .with_ellipsize(EllipsizeMode::Middle)
+            .finish();
         let text_y = item_y + (POPUP_ITEM_HEIGHT - label.height() as i32) / 2;
         canvas.draw_canvas(&label, x + 6, text_y);
     }
@@ -2636,6 +3643,25 @@ fn darken(color: Rgba, amount: f32) -> Rgba {
     )
 }
 
+/// Quotes a string as a JSON string literal.
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
 fn truncate_name(name: &str, max_len: usize) -> String {
     if name.chars().count() > max_len {
         format!("{}...", name.chars().take(max_len - 3).collect::<String>())
@@ -2656,34 +3682,26 @@ fn format_size(bytes: u64) -> String {
     }
 }
 
-fn format_date(time: Option<SystemTime>) -> String {
-    match time {
-        Some(t) => {
-            let duration = t.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
-            let secs = duration.as_secs();
-            // Simple date format (just show relative or basic)
-            let now = SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs();
-            let diff = now.saturating_sub(secs);
-
-            if diff < 60 {
-                "Just now".to_string()
-            } else if diff < 3600 {
-                format!("{} min ago", diff / 60)
-            } else if diff < 86400 {
-                format!("{} hrs ago", diff / 3600)
-            } else if diff < 86400 * 7 {
-                format!("{} days ago", diff / 86400)
-            } else {
-                // Convert to date-ish
-                let days_since_epoch = secs / 86400;
-                let years = 1970 + days_since_epoch / 365;
-                format!("{}", years)
-            }
-        }
-        None => "-".to_string(),
+/// Words a modification time relative to `now`, as in "2 days ago", or
+/// gives its local date as YYYY-MM-DD once it's a week old.
+pub(crate) fn format_modified(time: SystemTime, now: SystemTime) -> String {
+    let ago = now.duration_since(time).unwrap_or_default().as_secs();
+    let plural = |n: u64, unit: &str| format!("{n} {unit}{} ago", if n == 1 { "" } else { "s" });
+    if ago < 60 {
+        "Just now".to_string()
+    } else if ago < 3600 {
+        plural(ago / 60, "minute")
+    } else if ago < 86400 {
+        plural(ago / 3600, "hour")
+    } else if ago < 86400 * 7 {
+        plural(ago / 86400, "day")
+    } else {
+        let secs = match time.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(after) => after.as_secs() as i64,
+            Err(before) => -(before.duration().as_secs() as i64),
+        };
+        let (year, month, day) = crate::ui::date::local_date(secs);
+        format!("{year:04}-{month:02}-{day:02}")
     }
 }
 
@@ -2918,33 +3936,16 @@ fn draw_breadcrumbs(
         // Check if this component would overflow
         let remaining_width = available_width - (cx - x);
         if tc.width() as i32 > remaining_width && is_last {
-            // Truncate the last component to fit
-            let chars: Vec<char> = display.chars().collect();
-            let ellipsis = font.render("...").with_color(text_color).finish();
-            let ellipsis_w = ellipsis.width() as i32;
-            let max_text_w = remaining_width - ellipsis_w;
-
-            if max_text_w > 0 {
-                let mut truncated = String::new();
-                let mut current_w = 0i32;
-
-                for c in chars {
-                    let c_canvas = font
-                        .render(c.to_string().as_str())
-                        .with_color(text_color)
-                        .finish();
-                    if current_w + c_canvas.width() as i32 > max_text_w {
-                        truncated.push('…');
-                        break;
-                    }
-                    truncated.push(c);
-                    current_w += c_canvas.width() as i32;
-                }
-
-                let truncated_tc = font.render(&truncated).with_color(text_color).finish();
-                canvas.draw_canvas(&truncated_tc, cx, y);
-                cx += truncated_tc.width() as i32;
-            }
+            // Cut the last component short in the middle, keeping its
+            // extension
+            let truncated_tc = font
+                .render(display)
+                .with_color(text_color)
+                .with_max_width(remaining_width as f32)
+                .with_ellipsize(EllipsizeMode::Middle)
+                .finish();
+            canvas.draw_canvas(&truncated_tc, cx, y);
+            cx += truncated_tc.width() as i32;
         } else {
             canvas.draw_canvas(&tc, cx, y);
             cx += tc.width() as i32;
@@ -3034,6 +4035,7 @@ fn draw_quick_access_icon(
         QuickAccessIcon::Pictures => rgb(180, 120, 180),
         QuickAccessIcon::Music => rgb(220, 120, 120),
         QuickAccessIcon::Videos => rgb(180, 100, 200),
+        QuickAccessIcon::Root => rgb(150, 150, 160),
     };
 
     canvas.fill_rounded_rect(
@@ -3112,3 +4114,398 @@ fn draw_mount_icon(
 
     let _ = colors;
 }
+
+#[cfg(test)]
+mod tests {
+    /// Checks what clicks, Enter and OK do in the file selection dialog for
+    /// every mode and kind of selection.
+    mod activation {
+        use super::super::{
+            Activation::{self, *},
+            ActivationOutcome::{self, *},
+            ActivationTarget::{self, *},
+            FileSelectMode::{self, *},
+            activation_outcome,
+        };
+
+        const MODES: [FileSelectMode; 3] = [Open, Save, FileSelectMode::Directory];
+        const TARGETS: [ActivationTarget; 4] =
+            [Nothing, File, ActivationTarget::Directory, Several];
+
+        fn outcome(
+            mode: FileSelectMode,
+            action: Activation,
+            target: ActivationTarget,
+        ) -> ActivationOutcome {
+            activation_outcome(mode, action, target, false)
+        }
+
+        #[test]
+        fn click_selects_any_entry() {
+            for mode in MODES {
+                for target in TARGETS {
+                    let expected = if target == Nothing { Ignore } else { Select };
+                    assert_eq!(
+                        outcome(mode, Click, target),
+                        expected,
+                        "{mode:?} {target:?}"
+                    );
+                    // A typed name doesn't change what a click does
+                    assert_eq!(
+                        activation_outcome(mode, Click, target, true),
+                        expected,
+                        "{mode:?} {target:?}"
+                    );
+                }
+            }
+        }
+
+        #[test]
+        fn double_click() {
+            for mode in MODES {
+                assert_eq!(outcome(mode, DoubleClick, Nothing), Ignore);
+                assert_eq!(
+                    outcome(mode, DoubleClick, ActivationTarget::Directory),
+                    EnterDirectory,
+                    "{mode:?}"
+                );
+                assert_eq!(outcome(mode, DoubleClick, Several), Ignore);
+            }
+            assert_eq!(outcome(Open, DoubleClick, File), Accept);
+            assert_eq!(outcome(Save, DoubleClick, File), Accept);
+            assert_eq!(
+                outcome(FileSelectMode::Directory, DoubleClick, File),
+                Ignore
+            );
+        }
+
+        #[test]
+        fn enter_and_ok_in_open_mode() {
+            for action in [Enter, Ok] {
+                assert_eq!(outcome(Open, action, Nothing), Ignore);
+                assert_eq!(outcome(Open, action, File), Accept);
+                assert_eq!(outcome(Open, action, Several), Accept);
+                // OK on a directory enters it rather than returning it
+                assert_eq!(
+                    outcome(Open, action, ActivationTarget::Directory),
+                    EnterDirectory
+                );
+            }
+        }
+
+        #[test]
+        fn enter_and_ok_in_save_mode() {
+            for action in [Enter, Ok] {
+                for target in TARGETS {
+                    assert_eq!(
+                        activation_outcome(Save, action, target, true),
+                        AcceptTyped,
+                        "{action:?} {target:?}"
+                    );
+                }
+                assert_eq!(outcome(Save, action, Nothing), Ignore);
+                assert_eq!(outcome(Save, action, File), Accept);
+                assert_eq!(
+                    outcome(Save, action, ActivationTarget::Directory),
+                    EnterDirectory
+                );
+            }
+        }
+
+        #[test]
+        fn enter_and_ok_in_directory_mode() {
+            let mode = FileSelectMode::Directory;
+            for action in [Enter, Ok] {
+                assert_eq!(outcome(mode, action, Nothing), AcceptCurrentDirectory);
+                assert_eq!(outcome(mode, action, File), Ignore);
+                assert_eq!(outcome(mode, action, Several), Accept);
+                // Only save mode has a file name field
+                assert_eq!(
+                    activation_outcome(mode, action, Nothing, true),
+                    AcceptCurrentDirectory
+                );
+            }
+            assert_eq!(
+                outcome(mode, Enter, ActivationTarget::Directory),
+                EnterDirectory
+            );
+            assert_eq!(outcome(mode, Ok, ActivationTarget::Directory), Accept);
+        }
+
+        #[test]
+        fn typed_name_only_matters_in_save_mode() {
+            for action in [Enter, Ok] {
+                for target in TARGETS {
+                    assert_eq!(
+                        activation_outcome(Open, action, target, true),
+                        outcome(Open, action, target),
+                        "{action:?} {target:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    /// Checks how clicks with Ctrl and Shift change a `--multiple` selection.
+    mod multiple {
+        use std::collections::HashSet;
+
+        use super::super::{Modifiers, click_selection};
+
+        /// Entries as listed; indices into the folder's entries, sorted by name.
+        const ORDER: [usize; 6] = [4, 0, 3, 1, 5, 2];
+
+        fn click(
+            selected: &mut HashSet<usize>,
+            anchor: Option<usize>,
+            clicked: usize,
+            modifiers: Modifiers,
+        ) -> usize {
+            click_selection(selected, &ORDER, anchor, clicked, modifiers)
+        }
+
+        fn set(entries: &[usize]) -> HashSet<usize> {
+            entries.iter().copied().collect()
+        }
+
+        #[test]
+        fn plain_click_selects_only_the_entry() {
+            let mut selected = set(&[4, 0]);
+            assert_eq!(click(&mut selected, Some(4), 3, Modifiers::empty()), 3);
+            assert_eq!(selected, set(&[3]));
+        }
+
+        #[test]
+        fn ctrl_click_toggles() {
+            let mut selected = set(&[4]);
+            let anchor = click(&mut selected, Some(4), 1, Modifiers::CTRL);
+            assert_eq!(anchor, 1);
+            assert_eq!(selected, set(&[4, 1]));
+            click(&mut selected, Some(anchor), 4, Modifiers::CTRL);
+            assert_eq!(selected, set(&[1]));
+        }
+
+        #[test]
+        fn shift_click_selects_the_listed_range() {
+            let mut selected = set(&[0]);
+            // Down the list and back up past the anchor, in list order not index order
+            assert_eq!(click(&mut selected, Some(0), 5, Modifiers::SHIFT), 0);
+            assert_eq!(selected, set(&[0, 3, 1, 5]));
+            assert_eq!(click(&mut selected, Some(0), 4, Modifiers::SHIFT), 0);
+            assert_eq!(selected, set(&[4, 0]));
+        }
+
+        #[test]
+        fn ctrl_shift_click_adds_the_range() {
+            let mut selected = set(&[2]);
+            click(
+                &mut selected,
+                Some(0),
+                1,
+                Modifiers::CTRL | Modifiers::SHIFT,
+            );
+            assert_eq!(selected, set(&[2, 0, 3, 1]));
+        }
+
+        #[test]
+        fn shift_click_without_an_anchor_selects_the_entry() {
+            let mut selected = HashSet::new();
+            assert_eq!(click(&mut selected, None, 3, Modifiers::SHIFT), 3);
+            assert_eq!(selected, set(&[3]));
+            // An anchor that isn't listed (filtered out) is ignored too
+            assert_eq!(click(&mut selected, Some(9), 1, Modifiers::SHIFT), 1);
+            assert_eq!(selected, set(&[1]));
+        }
+    }
+
+    /// Picking the mounted drives to list in the file dialog's sidebar.
+    mod places {
+        use std::path::PathBuf;
+
+        use super::super::parse_mounts;
+
+        fn mounts(table: &str) -> Vec<(String, String)> {
+            parse_mounts(table)
+                .into_iter()
+                .map(|(device, path)| (device, path.to_string_lossy().into_owned()))
+                .collect()
+        }
+
+        #[test]
+        fn pseudo_and_system_mounts_are_left_out() {
+            let table = "\
+        proc /proc proc rw,nosuid 0 0
+        sysfs /sys sysfs rw 0 0
+        /dev/nvme0n1p2 / ext4 rw,relatime 0 0
+        tmpfs /tmp tmpfs rw 0 0
+        /dev/nvme0n1p1 /boot/efi vfat rw 0 0
+        /dev/loop3 /snap/core/123 squashfs ro 0 0
+        portal /run/user/1000/doc fuse.portal rw 0 0
+        /dev/nvme0n1p3 /home ext4 rw 0 0
+        /dev/sdb1 /run/media/sam/USB vfat rw 0 0
+        /dev/sdc1 /mnt/backup ext4 rw 0 0
+        ";
+            assert_eq!(
+                mounts(table),
+                [
+                    ("/dev/nvme0n1p3".into(), "/home".into()),
+                    ("/dev/sdb1".into(), "/run/media/sam/USB".into()),
+                    ("/dev/sdc1".into(), "/mnt/backup".into()),
+                ]
+            );
+        }
+
+        #[test]
+        fn escaped_spaces_are_decoded() {
+            let table = "/dev/sdb1 /media/sam/My\\040Disk vfat rw 0 0\n";
+            assert_eq!(
+                parse_mounts(table),
+                [("/dev/sdb1".to_string(), PathBuf::from("/media/sam/My Disk"))]
+            );
+        }
+
+        #[test]
+        fn a_later_mount_over_the_same_point_wins() {
+            let table = "/dev/sdb1 /mnt/data ext4 rw 0 0\nserver:/share /mnt/data nfs4 rw 0 0\n";
+            assert_eq!(
+                mounts(table),
+                [("server:/share".into(), "/mnt/data".into())]
+            );
+        }
+
+        #[test]
+        fn short_lines_are_skipped() {
+            assert!(parse_mounts("garbage\n\n/dev/sda1 /mnt\n").is_empty());
+        }
+    }
+
+    /// How the Modified column words a time.
+    mod modified {
+        use std::time::{Duration, SystemTime};
+
+        use super::super::format_modified;
+
+        const MINUTE: u64 = 60;
+        const HOUR: u64 = 60 * MINUTE;
+        const DAY: u64 = 24 * HOUR;
+
+        fn ago(secs: u64) -> String {
+            let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+            format_modified(now - Duration::from_secs(secs), now)
+        }
+
+        #[test]
+        fn recent_times_are_relative() {
+            assert_eq!(ago(0), "Just now");
+            assert_eq!(ago(59), "Just now");
+            assert_eq!(ago(MINUTE), "1 minute ago");
+            assert_eq!(ago(45 * MINUTE), "45 minutes ago");
+            assert_eq!(ago(HOUR), "1 hour ago");
+            assert_eq!(ago(23 * HOUR + 59 * MINUTE), "23 hours ago");
+            assert_eq!(ago(DAY), "1 day ago");
+            assert_eq!(ago(2 * DAY + HOUR), "2 days ago");
+        }
+
+        #[test]
+        fn older_times_are_dates() {
+            let _zone = crate::ui::date::TZ_ENV
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            // Noon UTC, the same date in nearly every time zone
+            let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_710_504_000);
+            assert_eq!(
+                format_modified(time, time + Duration::from_secs(30 * DAY)),
+                "2024-03-15"
+            );
+        }
+
+        #[test]
+        fn future_times_are_just_now() {
+            let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+            assert_eq!(
+                format_modified(now + Duration::from_secs(HOUR), now),
+                "Just now"
+            );
+        }
+    }
+
+    /// Completing a path typed into the location bar.
+    mod location {
+        use std::path::PathBuf;
+
+        use super::super::complete_location;
+
+        /// A scratch directory removed on drop.
+        struct TempDir(PathBuf);
+
+        impl TempDir {
+            /// Three files, and a folder holding one more.
+            fn new(name: &str) -> Self {
+                let dir = std::env::temp_dir()
+                    .join(format!("zenity-rs-complete-{}-{name}", std::process::id()));
+                let _ = std::fs::remove_dir_all(&dir);
+                std::fs::create_dir_all(dir.join("docs")).unwrap();
+                for name in ["apple.txt", "banana.txt", "cherry.txt", "docs/notes.txt"] {
+                    std::fs::write(dir.join(name), "").unwrap();
+                }
+                Self(dir)
+            }
+
+            fn path(&self, name: &str) -> String {
+                format!("{}/{name}", self.0.display())
+            }
+        }
+
+        impl Drop for TempDir {
+            fn drop(&mut self) {
+                let _ = std::fs::remove_dir_all(&self.0);
+            }
+        }
+
+        #[test]
+        fn completion_extends_to_the_shared_prefix() {
+            let dir = TempDir::new("complete");
+            std::fs::write(dir.0.join("banana.md"), "").unwrap();
+            std::fs::write(dir.0.join(".hidden"), "").unwrap();
+            assert_eq!(
+                complete_location(&dir.path("ch")).as_deref(),
+                Some("erry.txt")
+            );
+            assert_eq!(complete_location(&dir.path("ba")).as_deref(), Some("nana."));
+            assert_eq!(complete_location(&dir.path("banana.")), None);
+            assert_eq!(
+                complete_location(&dir.path("docs/n")).as_deref(),
+                Some("otes.txt")
+            );
+        }
+
+        #[test]
+        fn a_single_folder_completes_with_a_slash() {
+            let dir = TempDir::new("folder");
+            assert_eq!(complete_location(&dir.path("d")).as_deref(), Some("ocs/"));
+            assert_eq!(complete_location(&dir.path("docs")).as_deref(), Some("/"));
+        }
+
+        #[test]
+        fn hidden_names_need_a_dot() {
+            let dir = TempDir::new("hidden");
+            std::fs::write(dir.0.join(".apricot"), "").unwrap();
+            assert_eq!(
+                complete_location(&dir.path("a")).as_deref(),
+                Some("pple.txt")
+            );
+            assert_eq!(
+                complete_location(&dir.path(".")).as_deref(),
+                Some("apricot")
+            );
+        }
+
+        #[test]
+        fn nothing_to_complete() {
+            let dir = TempDir::new("nothing");
+            assert_eq!(complete_location(&dir.path("x")), None);
+            assert_eq!(complete_location(&dir.path("")), None);
+            assert_eq!(complete_location("/nonexistent-zenity-rs/a"), None);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Keyboard focus over a dialog's buttons.
+//!
+//! Tab and Shift+Tab cycle through the buttons and the dialog's own content,
+//! which has the focus to begin with. While a button has it, the arrow keys
+//! move to the adjacent button and Enter or Space activate it; otherwise
+//! those keys are left to the dialog.
+
+use crate::{
+    backend::{KeyEvent, Modifiers, WindowEvent},
+    ui::{
+        KEY_DOWN, KEY_ISO_LEFT_TAB, KEY_KP_ENTER, KEY_LEFT, KEY_RETURN, KEY_RIGHT, KEY_TAB, KEY_UP,
+    },
+};
+
+/// What a focus key did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FocusAction {
+    /// The focus moved; focus rings need redrawing.
+    Moved,
+    /// The button with this index was activated.
+    Activate(usize),
+}
+
+/// Tracks which of `count` buttons, in focus order, has the keyboard focus.
+pub(crate) struct FocusManager {
+    count: usize,
+    focused: Option<usize>,
+}
+
+impl FocusManager {
+    pub fn new(count: usize) -> Self {
+        Self {
+            count,
+            focused: None,
+        }
+    }
+
+    /// The focused button, or `None` while the dialog's content has the focus.
+    pub fn focused(&self) -> Option<usize> {
+        self.focused
+    }
+
+    pub fn is_focused(&self, index: usize) -> bool {
+        self.focused == Some(index)
+    }
+
+    /// Handles focus keys. Returns `None` for events it leaves to the dialog.
+    pub fn process_event(&mut self, event: &WindowEvent) -> Option<FocusAction> {
+        match (event, self.focused) {
+            (WindowEvent::KeyPress(key), _) if is_backward_tab(key) => {
+                // Content, then the buttons from last to first
+                self.focused = match self.focused {
+                    None => self.count.checked_sub(1),
+                    Some(index) => index.checked_sub(1),
+                };
+                Some(FocusAction::Moved)
+            }
+            (WindowEvent::KeyPress(key), _) if key.keysym == KEY_TAB => {
+                self.focused = match self.focused {
+                    None => (self.count > 0).then_some(0),
+                    Some(index) => Some(index + 1).filter(|&next| next < self.count),
+                };
+                Some(FocusAction::Moved)
+            }
+            (WindowEvent::KeyPress(key), Some(index)) => {
+                match key.keysym {
+                    KEY_LEFT | KEY_UP => {
+                        self.focused = Some(index.saturating_sub(1));
+                        Some(FocusAction::Moved)
+                    }
+                    KEY_RIGHT | KEY_DOWN => {
+                        self.focused = Some((index + 1).min(self.count - 1));
+                        Some(FocusAction::Moved)
+                    }
+                    KEY_RETURN | KEY_KP_ENTER => Some(FocusAction::Activate(index)),
+                    _ => None,
+                }
+            }
+            (WindowEvent::TextInput(' '), Some(index)) => Some(FocusAction::Activate(index)),
+            _ => None,
+        }
+    }
+}
+
+fn is_backward_tab(key: &KeyEvent) -> bool {
+    key.keysym == KEY_ISO_LEFT_TAB
+        || (key.keysym == KEY_TAB && key.modifiers.contains(Modifiers::SHIFT))
+}
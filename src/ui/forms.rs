@@ -1,5 +1,10 @@
 //! Forms dialog implementation for multiple input fields.
 
+use std::{
+    borrow::Borrow,
+    time::{Duration, Instant},
+};
+
 use crate::{
     backend::{CursorShape, Window, WindowEvent, create_window},
     error::Error,
@@ -7,18 +12,27 @@ use crate::{
     ui::{
         BASE_BUTTON_HEIGHT, BASE_BUTTON_SPACING, BASE_CORNER_RADIUS, Colors, KEY_ESCAPE,
         KEY_ISO_LEFT_TAB, KEY_RETURN, KEY_TAB,
-        widgets::{Widget, button::Button, text_input::TextInput},
+        calendar::CalendarResult,
+        geometry::Offset,
+        layout::{BASE_LABEL_GUTTER, LabelColumn, LabelPlacement},
+        wait_for_event_until,
+        widgets::{
+            Widget, button::Button, combo_box::ComboBox, point_in_rect, text_input::TextInput,
+        },
+        zoom,
     },
 };
 
 const BASE_PADDING: u32 = 20;
 const BASE_FIELD_HEIGHT: u32 = 32;
 const BASE_FIELD_SPACING: u32 = 12;
+/// Label column width the default dialog width is sized for.
 const BASE_LABEL_WIDTH: u32 = 120;
 const BASE_INPUT_WIDTH: u32 = 250;
 const BASE_MIN_WIDTH: u32 = 420;
+/// Narrowest the inputs get when --width is smaller than the default.
+const BASE_MIN_INPUT_WIDTH: u32 = 100;
 const BASE_PROMPT_SPACING: u32 = 16;
-const BASE_LABEL_GAP: u32 = 10;
 
 /// Field type for forms.
 #[derive(Debug, Clone)]
@@ -27,6 +41,10 @@ pub enum FormField {
     Entry(String),
     /// Password field (hidden text).
     Password(String),
+    /// Date field, picked in a calendar.
+    Calendar(String),
+    /// Drop-down list field, with its items.
+    Combo(String, Vec<String>),
 }
 
 impl FormField {
@@ -34,6 +52,8 @@ impl FormField {
         match self {
             FormField::Entry(label) => label,
             FormField::Password(label) => label,
+            FormField::Calendar(label) => label,
+            FormField::Combo(label, _) => label,
         }
     }
 
@@ -51,14 +71,28 @@ pub enum FormsResult {
     Cancelled,
     /// Dialog was closed.
     Closed,
+    /// The timeout passed.
+    Timeout,
 }
 
 impl FormsResult {
+    /// The exit code the command-line tool uses for this result: 0 when
+    /// the form is accepted, 1 when cancelled or closed, 5 on timeout.
     pub fn exit_code(&self) -> i32 {
         match self {
             FormsResult::Values(_) => 0,
             FormsResult::Cancelled => 1,
             FormsResult::Closed => 1,
+            FormsResult::Timeout => 5,
+        }
+    }
+
+    /// The field values joined by `separator`, used verbatim, as printed on
+    /// stdout. `None` unless the form was accepted.
+    pub fn joined(&self, separator: &str) -> Option<String> {
+        match self {
+            FormsResult::Values(values) => Some(values.join(separator)),
+            _ => None,
         }
     }
 }
@@ -69,9 +103,15 @@ pub struct FormsBuilder {
     text: String,
     fields: Vec<FormField>,
     separator: String,
+    date_format: Option<String>,
+    field_labels: LabelPlacement,
     width: Option<u32>,
     height: Option<u32>,
-    colors: Option<&'static Colors>,
+    timeout: Option<u32>,
+    position: Option<(Offset, Offset)>,
+    colors: Option<Colors>,
+    /// Field contents and focus carried over a zoom rebuild.
+    restored: Option<(Vec<String>, usize)>,
 }
 
 impl FormsBuilder {
@@ -81,9 +121,14 @@ impl FormsBuilder {
             text: String::new(),
             fields: Vec::new(),
             separator: "|".to_string(),
+            date_format: None,
+            field_labels: LabelPlacement::Side,
             width: None,
             height: None,
+            timeout: None,
+            position: None,
             colors: None,
+            restored: None,
         }
     }
 
@@ -109,14 +154,41 @@ impl FormsBuilder {
         self
     }
 
+    /// Add a date field. Clicking it opens a calendar to pick the date.
+    pub fn add_calendar(mut self, label: &str) -> Self {
+        self.fields.push(FormField::Calendar(label.to_string()));
+        self
+    }
+
+    /// Add a drop-down list field showing `values`, the first selected.
+    pub fn add_combo(mut self, label: &str, values: &[impl AsRef<str>]) -> Self {
+        let values = values.iter().map(|v| v.as_ref().to_string()).collect();
+        self.fields
+            .push(FormField::Combo(label.to_string(), values));
+        self
+    }
+
+    /// Set how date fields are formatted, strftime style (default: the
+    /// calendar's, "%Y-%m-%d").
+    pub fn date_format(mut self, format: &str) -> Self {
+        self.date_format = Some(format.to_string());
+        self
+    }
+
     /// Set the output separator (default: "|").
     pub fn separator(mut self, sep: &str) -> Self {
         self.separator = sep.to_string();
         self
     }
 
-    pub fn colors(mut self, colors: &'static Colors) -> Self {
-        self.colors = Some(colors);
+    /// Set where field labels go (default: in a column beside the inputs).
+    pub fn field_labels(mut self, placement: LabelPlacement) -> Self {
+        self.field_labels = placement;
+        self
+    }
+
+    pub fn colors(mut self, colors: impl Borrow<Colors>) -> Self {
+        self.colors = Some(*colors.borrow());
         self
     }
 
@@ -130,34 +202,80 @@ impl FormsBuilder {
         self
     }
 
-    pub fn show(self) -> Result<FormsResult, Error> {
+    /// Set timeout in seconds. Dialog will auto-close after this time.
+    pub fn timeout(mut self, seconds: u32) -> Self {
+        self.timeout = Some(seconds);
+        self
+    }
+
+    /// Request a window position. Ignored where the platform doesn't allow it.
+    pub fn position(mut self, x: Offset, y: Offset) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    pub fn show(mut self) -> Result<FormsResult, Error> {
         if self.fields.is_empty() {
             return Ok(FormsResult::Values(Vec::new()));
         }
 
-        let colors = self.colors.unwrap_or_else(|| crate::ui::detect_theme());
+        let deadline = self
+            .timeout
+            .map(|secs| Instant::now() + Duration::from_secs(secs as u64));
+        loop {
+            if let Some(result) = self.run(deadline)? {
+                return Ok(result);
+            }
+        }
+    }
+
+    /// Shows the dialog until it's answered, or returns `None` to be run
+    /// again after a [zoom](crate::ui::zoom) change.
+    fn run(&mut self, deadline: Option<Instant>) -> Result<Option<FormsResult>, Error> {
+        let colors = &self.colors.unwrap_or_else(|| *crate::ui::detect_theme());
 
         // First pass: calculate LOGICAL dimensions using scale 1.0
         let temp_font = Font::load(1.0);
         let temp_ok = Button::new("OK", &temp_font, 1.0);
         let temp_cancel = Button::new("Cancel", &temp_font, 1.0);
+
+        let logical_buttons_width = temp_ok.width() + temp_cancel.width() + BASE_BUTTON_SPACING;
+        let logical_content_width =
+            (BASE_LABEL_WIDTH + BASE_INPUT_WIDTH + BASE_LABEL_GUTTER).max(logical_buttons_width);
+        let calc_width = (logical_content_width + BASE_PADDING * 2).max(BASE_MIN_WIDTH);
+        // The inputs stretch or shrink with --width, down to a minimum
+        let min_width = (BASE_LABEL_WIDTH + BASE_LABEL_GUTTER + BASE_MIN_INPUT_WIDTH)
+            .max(logical_buttons_width)
+            + BASE_PADDING * 2;
+        let logical_width = self.width.map_or(calc_width, |w| w.max(min_width));
+        let labels: Vec<&str> = self.fields.iter().map(FormField::label).collect();
+        let temp_column = LabelColumn::new(
+            &temp_font,
+            &labels,
+            logical_width - BASE_PADDING * 2,
+            self.field_labels,
+            1.0,
+        );
+        let logical_input_width = temp_column.input_width();
+
         let temp_prompt_height = if !self.text.is_empty() {
             temp_font
                 .render(&self.text)
-                .with_max_width(BASE_INPUT_WIDTH as f32)
+                .with_max_width(logical_input_width as f32)
                 .finish()
                 .height()
         } else {
             0
         };
 
-        let logical_buttons_width = temp_ok.width() + temp_cancel.width() + BASE_BUTTON_SPACING;
-        let logical_content_width =
-            (BASE_LABEL_WIDTH + BASE_INPUT_WIDTH + BASE_LABEL_GAP).max(logical_buttons_width);
-        let calc_width = (logical_content_width + BASE_PADDING * 2).max(BASE_MIN_WIDTH);
-
         // Height: padding + text + fields + buttons + padding
-        let fields_height = self.fields.len() as u32 * (BASE_FIELD_HEIGHT + BASE_FIELD_SPACING);
+        let fields_height: u32 = labels
+            .iter()
+            .map(|label| {
+                let label = temp_column.render_label(&temp_font, label, colors.text);
+                temp_column.place(&label, 0, 0, BASE_FIELD_HEIGHT).height + BASE_FIELD_SPACING
+            })
+            .sum();
         let calc_height = BASE_PADDING * 2
             + temp_prompt_height
             + (if temp_prompt_height > 0 {
@@ -173,9 +291,8 @@ impl FormsBuilder {
         drop(temp_ok);
         drop(temp_cancel);
 
-        // Use custom dimensions if provided, otherwise use calculated defaults
-        let logical_width = self.width.unwrap_or(calc_width) as u16;
-        let logical_height = self.height.unwrap_or(calc_height) as u16;
+        let logical_width = logical_width as u16;
+        let logical_height = self.height.map_or(calc_height, |h| h.max(calc_height)) as u16;
 
         // Create window with LOGICAL dimensions
         let mut window = create_window(logical_width, logical_height)?;
@@ -195,13 +312,24 @@ impl FormsBuilder {
         let padding = (BASE_PADDING as f32 * scale) as u32;
         let field_height = (BASE_FIELD_HEIGHT as f32 * scale) as u32;
         let field_spacing = (BASE_FIELD_SPACING as f32 * scale) as u32;
-        let label_width = (BASE_LABEL_WIDTH as f32 * scale) as u32;
-        let input_width = (BASE_INPUT_WIDTH as f32 * scale) as u32;
 
         // Calculate physical dimensions
         let physical_width = (logical_width as f32 * scale) as u32;
         let physical_height = (logical_height as f32 * scale) as u32;
 
+        let column = LabelColumn::new(
+            &font,
+            &labels,
+            physical_width - padding * 2,
+            self.field_labels,
+            scale,
+        );
+        let input_width = column.input_width();
+        let label_canvases: Vec<Canvas> = labels
+            .iter()
+            .map(|label| column.render_label(&font, label, colors.text))
+            .collect();
+
         // Create buttons at physical scale
         let mut ok_button = Button::new("OK", &font, scale);
         let mut cancel_button = Button::new("Cancel", &font, scale);
@@ -219,18 +347,40 @@ impl FormsBuilder {
         };
         let prompt_height = prompt_canvas.as_ref().map(|c| c.height()).unwrap_or(0);
 
-        // Create text inputs for each field
-        let mut inputs: Vec<TextInput> = self
+        // Create an input for each field
+        let mut controls: Vec<Control> = self
             .fields
             .iter()
-            .map(|field| TextInput::new(input_width).with_password(field.is_password()))
+            .map(|field| {
+                match field {
+                    FormField::Entry(_) => Control::Text(TextInput::new(input_width)),
+                    FormField::Password(_) => {
+                        Control::Text(TextInput::new(input_width).with_password(true))
+                    }
+                    FormField::Calendar(_) => {
+                        let input = TextInput::new(input_width)
+                            .with_read_only(true)
+                            .with_placeholder("Click to pick a date");
+                        Control::Date(input, None)
+                    }
+                    FormField::Combo(_, values) => {
+                        let mut combo = ComboBox::new(values.clone(), &font, scale);
+                        combo.set_width(input_width);
+                        Control::Combo(combo)
+                    }
+                }
+            })
             .collect();
 
-        // Set first input as focused
-        if !inputs.is_empty() {
-            inputs[0].set_focus(true);
-        }
+        // Focus the first input, or the one focused before a rebuild
         let mut focused_index = 0usize;
+        if let Some((texts, focused)) = self.restored.take() {
+            for (control, text) in controls.iter_mut().zip(&texts) {
+                control.set_value(text);
+            }
+            focused_index = focused.min(controls.len() - 1);
+        }
+        controls[focused_index].set_focus(true);
 
         // Layout calculation
         let mut y = padding as i32;
@@ -239,15 +389,18 @@ impl FormsBuilder {
             y += prompt_height as i32 + (BASE_PROMPT_SPACING as f32 * scale) as i32;
         }
 
-        // Position inputs
-        let label_x = padding as i32;
-        let input_x = padding as i32 + label_width as i32 + (BASE_LABEL_GAP as f32 * scale) as i32;
-        let mut field_positions: Vec<i32> = Vec::new();
-
-        for (i, input) in inputs.iter_mut().enumerate() {
-            let field_y = y + (i as u32 * (field_height + field_spacing)) as i32;
-            field_positions.push(field_y);
-            input.set_position(input_x, field_y);
+        // Position labels and inputs
+        let mut label_positions: Vec<(i32, i32)> = Vec::new();
+        for (label, control) in label_canvases.iter().zip(controls.iter_mut()) {
+            let row = column.place(label, padding as i32, y, field_height);
+            label_positions.push((row.label_x, row.label_y));
+            control.widget_mut().set_position(row.input_x, row.input_y);
+            // Lists that would run off the bottom open upwards
+            if let Control::Combo(combo) = control {
+                let bottom = row.input_y + (combo.height() + combo.popup_height()) as i32;
+                combo.set_popup_above(bottom > physical_height as i32);
+            }
+            y += (row.height + field_spacing) as i32;
         }
 
         // Button positions (right-aligned)
@@ -271,15 +424,13 @@ impl FormsBuilder {
                     colors: &Colors,
                     font: &Font,
                     prompt_canvas: &Option<Canvas>,
-                    fields: &[FormField],
-                    inputs: &[TextInput],
+                    label_canvases: &[Canvas],
+                    controls: &[Control],
                     ok_button: &Button,
                     cancel_button: &Button,
                     // Layout params
                     padding: u32,
-                    label_x: i32,
-                    field_positions: &[i32],
-                    field_height: u32,
+                    label_positions: &[(i32, i32)],
                     prompt_y: i32,
                     scale: f32| {
             let width = canvas.width() as f32;
@@ -301,25 +452,23 @@ impl FormsBuilder {
             }
 
             // Draw fields
-            for (i, (field, input)) in fields.iter().zip(inputs.iter()).enumerate() {
-                let field_y = field_positions[i];
-
-                // Draw label (vertically centered with input, wrapped if needed)
-                let label_canvas = font
-                    .render(field.label())
-                    .with_color(colors.text)
-                    .with_max_width(label_width as f32)
-                    .finish();
-                let label_y = field_y + (field_height as i32 - label_canvas.height() as i32) / 2;
-                canvas.draw_canvas(&label_canvas, label_x, label_y);
-
-                // Draw input
-                input.draw_to(canvas, colors, font);
+            for ((label, &(label_x, label_y)), control) in
+                label_canvases.iter().zip(label_positions).zip(controls)
+            {
+                canvas.draw_canvas(label, label_x, label_y);
+                control.draw_to(canvas, colors, font);
             }
 
             // Draw buttons
             ok_button.draw_to(canvas, colors, font);
             cancel_button.draw_to(canvas, colors, font);
+
+            // An open list covers whatever is below it
+            for control in controls {
+                if let Control::Combo(combo) = control {
+                    combo.draw_popup(canvas, colors, font);
+                }
+            }
         };
 
         // Initial draw
@@ -328,29 +477,38 @@ impl FormsBuilder {
             colors,
             &font,
             &prompt_canvas,
-            &self.fields,
-            &inputs,
+            &label_canvases,
+            &controls,
             &ok_button,
             &cancel_button,
             padding,
-            label_x,
-            &field_positions,
-            field_height,
+            &label_positions,
             prompt_y,
             scale,
         );
         window.set_contents(&canvas)?;
+        if let Some((x, y)) = self.position {
+            window.set_position(x, y)?;
+        }
         window.show()?;
 
         // Event loop
         let mut window_dragging = false;
+        let values = |controls: &[Control]| controls.iter().map(Control::value).collect();
         loop {
-            let event = window.wait_for_event()?;
+            let Some(event) = wait_for_event_until(&mut window, deadline)? else {
+                return Ok(Some(FormsResult::Timeout));
+            };
             let mut needs_redraw = false;
+            // Set by a click on a date field or Space in one
+            let mut pick_date = false;
+            let list_open = controls[focused_index].is_open();
 
             match &event {
-                WindowEvent::CloseRequested => return Ok(FormsResult::Closed),
+                WindowEvent::CloseRequested => return Ok(Some(FormsResult::Closed)),
                 WindowEvent::RedrawRequested => needs_redraw = true,
+                // An open list takes the input until it closes
+                _ if list_open => {}
                 WindowEvent::CursorMove(pos) => {
                     if window_dragging {
                         let _ = window.start_drag();
@@ -360,23 +518,11 @@ impl FormsBuilder {
                     cursor_x = pos.x as i32;
                     cursor_y = pos.y as i32;
 
-                    // Check if cursor is over any input field and update cursor shape
-                    let mut over_input = false;
-                    for input in inputs.iter() {
-                        let ix = input.x();
-                        let iy = input.y();
-                        let iw = input.width();
-                        let ih = input.height();
-
-                        if cursor_x >= ix
-                            && cursor_x < ix + iw as i32
-                            && cursor_y >= iy
-                            && cursor_y < iy + ih as i32
-                        {
-                            over_input = true;
-                            break;
-                        }
-                    }
+                    // Check if cursor is over any text field and update cursor shape
+                    let over_input = controls
+                        .iter()
+                        .filter(|control| matches!(control, Control::Text(_)))
+                        .any(|control| control.contains(cursor_x, cursor_y));
                     let _ = window.set_cursor(if over_input {
                         CursorShape::Text
                     } else {
@@ -385,61 +531,57 @@ impl FormsBuilder {
                 }
                 WindowEvent::ButtonPress(crate::backend::MouseButton::Left, _) => {
                     window_dragging = true;
-                    // Check if clicking on any input field
-                    for (i, input) in inputs.iter().enumerate() {
-                        let ix = input.x();
-                        let iy = input.y();
-                        let iw = input.width();
-                        let ih = input.height();
-
-                        if cursor_x >= ix
-                            && cursor_x < ix + iw as i32
-                            && cursor_y >= iy
-                            && cursor_y < iy + ih as i32
-                        {
-                            if i != focused_index {
-                                inputs[focused_index].set_focus(false);
-                                focused_index = i;
-                                inputs[focused_index].set_focus(true);
-                                needs_redraw = true;
-                            }
-                            break;
+                    // Check if clicking on any field
+                    if let Some(i) = controls
+                        .iter()
+                        .position(|control| control.contains(cursor_x, cursor_y))
+                    {
+                        if i != focused_index {
+                            controls[focused_index].set_focus(false);
+                            focused_index = i;
+                            controls[focused_index].set_focus(true);
+                            needs_redraw = true;
                         }
+                        pick_date = matches!(controls[i], Control::Date(..));
                     }
                 }
                 WindowEvent::ButtonRelease(crate::backend::MouseButton::Left, _) => {
                     window_dragging = false;
                 }
+                WindowEvent::TextInput(' ') => {
+                    pick_date = matches!(controls[focused_index], Control::Date(..));
+                }
                 WindowEvent::KeyPress(key_event) => {
+                    if zoom::handle_key(key_event) {
+                        let texts = controls.iter().map(Control::value).collect();
+                        self.restored = Some((texts, focused_index));
+                        return Ok(None);
+                    }
                     match key_event.keysym {
                         KEY_TAB => {
                             // Move to next field
-                            inputs[focused_index].set_focus(false);
-                            focused_index = (focused_index + 1) % inputs.len();
-                            inputs[focused_index].set_focus(true);
+                            controls[focused_index].set_focus(false);
+                            focused_index = (focused_index + 1) % controls.len();
+                            controls[focused_index].set_focus(true);
                             needs_redraw = true;
                         }
                         KEY_ISO_LEFT_TAB => {
                             // Move to previous field (Shift+Tab)
-                            inputs[focused_index].set_focus(false);
+                            controls[focused_index].set_focus(false);
                             focused_index = if focused_index == 0 {
-                                inputs.len() - 1
+                                controls.len() - 1
                             } else {
                                 focused_index - 1
                             };
-                            inputs[focused_index].set_focus(true);
+                            controls[focused_index].set_focus(true);
                             needs_redraw = true;
                         }
                         KEY_RETURN => {
                             // Submit form
-                            let values: Vec<String> = inputs
-                                .iter()
-                                .map(|input| input.text().to_string())
-                                .collect();
-                            return Ok(FormsResult::Values(values));
+                            return Ok(Some(FormsResult::Values(values(&controls))));
                         }
                         KEY_ESCAPE => {
-                            return Ok(FormsResult::Cancelled);
+                            return Ok(Some(FormsResult::Cancelled));
                         }
                         _ => {}
                     }
@@ -447,49 +589,61 @@ impl FormsBuilder {
                 _ => {}
             }
 
-            // Process input events for focused field
-            if inputs[focused_index].process_event(&event) {
-                needs_redraw = true;
+            // Pointer movement goes to every field, so lists know when
+            // they're hovered; everything else to the focused one
+            let pointer = matches!(
+                event,
+                WindowEvent::CursorMove(_) | WindowEvent::CursorEnter(_) | WindowEvent::CursorLeave
+            );
+            for (i, control) in controls.iter_mut().enumerate() {
+                if i == focused_index || (pointer && !list_open) {
+                    needs_redraw |= control.widget_mut().process_event(&event);
+                }
             }
 
             // Check for submission via input
-            if inputs[focused_index].was_submitted() {
-                let values: Vec<String> = inputs
-                    .iter()
-                    .map(|input| input.text().to_string())
-                    .collect();
-                return Ok(FormsResult::Values(values));
+            if controls[focused_index].was_submitted() {
+                return Ok(Some(FormsResult::Values(values(&controls))));
+            }
+
+            if pick_date && let Control::Date(input, date) = &mut controls[focused_index] {
+                let label = self.fields[focused_index].label();
+                if let CalendarResult::Selected {
+                    year,
+                    month,
+                    day,
+                    formatted,
+                } = self.pick_date(label, *date, colors)?
+                {
+                    input.set_text(&formatted);
+                    *date = Some((year, month, day));
+                }
+                needs_redraw = true;
             }
 
             // Process button events
-            needs_redraw |= ok_button.process_event(&event);
-            needs_redraw |= cancel_button.process_event(&event);
+            if !list_open {
+                needs_redraw |= ok_button.process_event(&event);
+                needs_redraw |= cancel_button.process_event(&event);
+            }
 
             if ok_button.was_clicked() {
-                let values: Vec<String> = inputs
-                    .iter()
-                    .map(|input| input.text().to_string())
-                    .collect();
-                return Ok(FormsResult::Values(values));
+                return Ok(Some(FormsResult::Values(values(&controls))));
             }
             if cancel_button.was_clicked() {
-                return Ok(FormsResult::Cancelled);
+                return Ok(Some(FormsResult::Cancelled));
             }
 
             // Batch process pending events
             while let Some(ev) = window.poll_for_event()? {
                 match &ev {
-                    WindowEvent::CloseRequested => return Ok(FormsResult::Closed),
+                    WindowEvent::CloseRequested => return Ok(Some(FormsResult::Closed)),
                     _ => {
-                        if inputs[focused_index].process_event(&ev) {
+                        if controls[focused_index].widget_mut().process_event(&ev) {
                             needs_redraw = true;
                         }
-                        if inputs[focused_index].was_submitted() {
-                            let values: Vec<String> = inputs
-                                .iter()
-                                .map(|input| input.text().to_string())
-                                .collect();
-                            return Ok(FormsResult::Values(values));
+                        if controls[focused_index].was_submitted() {
+                            return Ok(Some(FormsResult::Values(values(&controls))));
                         }
                         needs_redraw |= ok_button.process_event(&ev);
                         needs_redraw |= cancel_button.process_event(&ev);
@@ -503,14 +657,12 @@ impl FormsBuilder {
                     colors,
                     &font,
                     &prompt_canvas,
-                    &self.fields,
-                    &inputs,
+                    &label_canvases,
+                    &controls,
                     &ok_button,
                     &cancel_button,
                     padding,
-                    label_x,
-                    &field_positions,
-                    field_height,
+                    &label_positions,
                     prompt_y,
                     scale,
                 );
@@ -518,6 +670,106 @@ impl FormsBuilder {
             }
         }
     }
+
+    /// Opens a calendar for the date field `label`, on its `date` if it has
+    /// one.
+    fn pick_date(
+        &self,
+        label: &str,
+        date: Option<(u32, u32, u32)>,
+        colors: &Colors,
+    ) -> Result<CalendarResult, Error> {
+        let mut calendar = crate::calendar().title(label).colors(colors);
+        if let Some(format) = &self.date_format {
+            calendar = calendar.date_format(format);
+        }
+        if let Some((year, month, day)) = date {
+            calendar = calendar.year(year).month(month).day(day);
+        }
+        calendar.show()
+    }
+}
+
+/// The input for one field.
+enum Control {
+    Text(TextInput),
+    /// Read-only; a click or Space picks the date in a calendar. Holds the
+    /// date picked, if any.
+    Date(TextInput, Option<(u32, u32, u32)>),
+    Combo(ComboBox),
+}
+
+impl Control {
+    fn widget(&self) -> &dyn Widget {
+        match self {
+            Control::Text(input) | Control::Date(input, _) => input,
+            Control::Combo(combo) => combo,
+        }
+    }
+
+    fn widget_mut(&mut self) -> &mut dyn Widget {
+        match self {
+            Control::Text(input) | Control::Date(input, _) => input,
+            Control::Combo(combo) => combo,
+        }
+    }
+
+    fn contains(&self, x: i32, y: i32) -> bool {
+        let widget = self.widget();
+        point_in_rect(
+            x,
+            y,
+            widget.x(),
+            widget.y(),
+            widget.width(),
+            widget.height(),
+        )
+    }
+
+    /// The field's output.
+    fn value(&self) -> String {
+        match self {
+            Control::Text(input) | Control::Date(input, _) => input.text().to_string(),
+            Control::Combo(combo) => combo.selected_text().to_string(),
+        }
+    }
+
+    /// Restores an output of [`value`](Self::value).
+    fn set_value(&mut self, value: &str) {
+        match self {
+            Control::Text(input) | Control::Date(input, _) => input.set_text(value),
+            Control::Combo(combo) => {
+                if let Some(i) = combo.items().iter().position(|item| item == value) {
+                    combo.set_selected(i);
+                }
+            }
+        }
+    }
+
+    fn set_focus(&mut self, focused: bool) {
+        match self {
+            Control::Text(input) | Control::Date(input, _) => input.set_focus(focused),
+            Control::Combo(combo) => combo.set_focus(focused),
+        }
+    }
+
+    fn is_open(&self) -> bool {
+        matches!(self, Control::Combo(combo) if combo.is_open())
+    }
+
+    fn was_submitted(&mut self) -> bool {
+        match self {
+            Control::Text(input) | Control::Date(input, _) => input.was_submitted(),
+            Control::Combo(_) => false,
+        }
+    }
+
+    fn draw_to(&self, canvas: &mut Canvas, colors: &Colors, font: &Font) {
+        match self {
+            Control::Text(input) | Control::Date(input, _) => input.draw_to(canvas, colors, font),
+            Control::Combo(combo) => combo.draw_to(canvas, colors, font),
+        }
+    }
 }
 
 impl Default for FormsBuilder {
@@ -0,0 +1,150 @@
+//! X11-style window geometry strings (`WIDTHxHEIGHT+X+Y`).
+
+use std::{fmt, str::FromStr};
+
+/// A window offset along one axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Offset {
+    /// Pixels from the left (or top) edge of the output.
+    Start(u32),
+    /// Pixels from the right (or bottom) edge of the output.
+    End(u32),
+}
+
+impl Offset {
+    /// Resolves to a coordinate for a window `size` pixels long on an output
+    /// `output_size` pixels long.
+    pub fn resolve(self, output_size: u32, size: u32) -> i32 {
+        match self {
+            Offset::Start(n) => n as i32,
+            Offset::End(n) => output_size as i32 - size as i32 - n as i32,
+        }
+    }
+}
+
+/// Parsed `--geometry` value. Every part is optional, but offsets come in
+/// pairs as in X11: `400x300`, `+10+20`, `400x300-0+0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Geometry {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub position: Option<(Offset, Offset)>,
+}
+
+/// Error returned when a geometry string can't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseGeometryError {
+    input: String,
+    suggestion: Option<String>,
+}
+
+impl fmt::Display for ParseGeometryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid geometry {:?}: expected WIDTHxHEIGHT, +X+Y or WIDTHxHEIGHT+X+Y",
+            self.input
+        )?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " (did you mean {suggestion:?}?)")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseGeometryError {}
+
+impl FromStr for Geometry {
+    type Err = ParseGeometryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s).ok_or_else(|| {
+            ParseGeometryError {
+                input: s.to_string(),
+                suggestion: suggest(s),
+            }
+        })
+    }
+}
+
+fn parse(s: &str) -> Option<Geometry> {
+    // XParseGeometry accepts a leading '='
+    let s = s.strip_prefix('=').unwrap_or(s);
+    if s.is_empty() {
+        return None;
+    }
+
+    let (size, offsets) = match s.find(['+', '-']) {
+        Some(i) => s.split_at(i),
+        None => (s, ""),
+    };
+
+    let mut geometry = Geometry::default();
+    if !size.is_empty() {
+        let (w, h) = match size.split_once(['x', 'X']) {
+            Some((w, h)) => (w, Some(h)),
+            None => (size, None),
+        };
+        geometry.width = parse_dimension(w)?;
+        if let Some(h) = h {
+            geometry.height = Some(parse_dimension(h)??);
+        }
+    }
+
+    if !offsets.is_empty() {
+        let (x, rest) = parse_offset(offsets)?;
+        let (y, rest) = parse_offset(rest)?;
+        if !rest.is_empty() {
+            return None;
+        }
+        geometry.position = Some((x, y));
+    }
+    Some(geometry)
+}
+
+/// Parses a width or height. An empty string means "not given".
+fn parse_dimension(s: &str) -> Option<Option<u32>> {
+    if s.is_empty() {
+        return Some(None);
+    }
+    if !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok().filter(|&n| n > 0).map(Some)
+}
+
+/// Parses a signed offset like `+10` or `-0`, returning the rest of the string.
+fn parse_offset(s: &str) -> Option<(Offset, &str)> {
+    let mut chars = s.chars();
+    let sign = chars.next()?;
+    let rest = chars.as_str();
+    let digits = rest.find(['+', '-']).unwrap_or(rest.len());
+    let (n, rest) = rest.split_at(digits);
+    if n.is_empty() || !n.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let n = n.parse().ok()?;
+    let offset = match sign {
+        '+' => Offset::Start(n),
+        '-' => Offset::End(n),
+        _ => return None,
+    };
+    Some((offset, rest))
+}
+
+/// Guesses what a malformed geometry string was meant to be.
+fn suggest(s: &str) -> Option<String> {
+    let trimmed: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+    // Common stand-ins for the 'x' separator
+    let mut candidate = trimmed.replace(['*', '×', ',', ':'], "x");
+    // A lone offset gets a zero partner
+    if parse(&candidate).is_none() {
+        let signs = candidate.matches(['+', '-']).count();
+        if signs == 1 {
+            candidate.push_str("+0");
+        }
+    }
+    parse(&candidate)
+        .filter(|_| candidate != s)
+        .map(|_| candidate)
+}
@@ -0,0 +1,73 @@
+//! Shell-style wildcard patterns.
+
+/// Matches `name` against a shell glob: `*` matches any run of characters,
+/// `?` any one character and `[abc]` or `[a-z]` (`[!...]` negated) one of a
+/// set. A `[` without its `]` matches itself.
+pub(crate) fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Where to resume after the last `*` if the rest fails to match
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        let step = match pattern.get(p) {
+            Some('*') => {
+                star = Some((p + 1, n));
+                p += 1;
+                continue;
+            }
+            Some('?') => Some(1),
+            Some('[') => {
+                match class(&pattern[p..]) {
+                    Some((set, negated, len)) => {
+                        (class_contains(set, name[n]) != negated).then_some(len)
+                    }
+                    None => (name[n] == '[').then_some(1),
+                }
+            }
+            Some(&c) => (c == name[n]).then_some(1),
+            None => None,
+        };
+        match (step, star) {
+            (Some(len), _) => {
+                p += len;
+                n += 1;
+            }
+            (None, Some((star_p, star_n))) => {
+                p = star_p;
+                n = star_n + 1;
+                star = Some((star_p, star_n + 1));
+            }
+            (None, None) => return false,
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// The `[...]` set `pattern` starts with: its characters, whether it's
+/// negated and its length in the pattern. None if it isn't closed.
+fn class(pattern: &[char]) -> Option<(&[char], bool, usize)> {
+    let negated = matches!(pattern.get(1), Some('!' | '^'));
+    let start = if negated { 2 } else { 1 };
+    // A `]` first in the set is part of it
+    let end = start + 1 + pattern.get(start + 1..)?.iter().position(|&c| c == ']')?;
+    Some((&pattern[start..end], negated, end + 1))
+}
+
+fn class_contains(set: &[char], c: char) -> bool {
+    let mut i = 0;
+    while i < set.len() {
+        if i + 2 < set.len() && set[i + 1] == '-' {
+            if (set[i]..=set[i + 2]).contains(&c) {
+                return true;
+            }
+            i += 3;
+        } else {
+            if set[i] == c {
+                return true;
+            }
+            i += 1;
+        }
+    }
+    false
+}
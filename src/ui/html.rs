@@ -0,0 +1,965 @@
+//! The small subset of HTML shown by `--text-info --html`.
+//!
+//! Release notes and license texts rarely need more than headings, lists and
+//! a few inline styles, so instead of an HTML engine this is a tolerant
+//! tokenizer feeding a flat list of styled blocks:
+//!
+//! - `p`, `div`, `h1`–`h3`, `ul`/`ol`/`li`, `pre` and `blockquote` start
+//!   blocks, which become spacing, indentation and heading sizes;
+//! - `b`/`strong`, `i`/`em`, `u`, `code`/`tt`/`kbd` and `a href` style runs;
+//! - `br` breaks the line, `script` and `style` are dropped, and every other
+//!   tag is unwrapped, keeping its text.
+//!
+//! Malformed input never fails: stray `<` are text, unclosed tags end with
+//! the document and unmatched closing tags are ignored.
+
+use std::process::{Command, Stdio};
+
+use bitflags::bitflags;
+
+use crate::{
+    render::{Canvas, Font},
+    ui::{Colors, markup::decode_entities},
+};
+
+bitflags! {
+    /// Inline styles of a run of text.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub(crate) struct Style: u8 {
+        const BOLD      = 0x01;
+        const ITALIC    = 0x02;
+        const UNDERLINE = 0x04;
+        const CODE      = 0x08;
+    }
+}
+
+/// What a block is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum BlockKind {
+    Paragraph,
+    /// `h1` to `h3`; deeper headings are shown as `h3`.
+    Heading(u8),
+    /// A list item with its marker (`•` or `1.`).
+    ListItem(String),
+    /// Text shown with its line breaks and spacing kept.
+    Preformatted,
+}
+
+/// A run of text in one style.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Run {
+    /// The text, with entities decoded; `\n` is a line break.
+    pub text: String,
+    pub style: Style,
+    /// Target of the enclosing link, if any.
+    pub link: Option<String>,
+}
+
+/// A paragraph-like block of runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Block {
+    pub kind: BlockKind,
+    /// How many lists the block is nested in.
+    pub list_depth: u32,
+    /// How many block quotes the block is nested in.
+    pub quote_depth: u32,
+    pub runs: Vec<Run>,
+}
+
+/// A parsed document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Document {
+    pub blocks: Vec<Block>,
+}
+
+/// Parses `html`. Never fails; see the [module docs](self) for what is kept.
+pub(crate) fn parse(html: &str) -> Document {
+    let mut parser = Parser::default();
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            parser.text(rest);
+            break;
+        };
+        parser.text(&rest[..lt]);
+        rest = &rest[lt..];
+        rest = match markup_len(rest) {
+            Some(len) => {
+                let inner = &rest[1..len];
+                parser.markup(inner.strip_suffix('>').unwrap_or(inner));
+                &rest[len..]
+            }
+            None => {
+                parser.text("<");
+                &rest[1..]
+            }
+        };
+        // Skip the contents of script, style and title elements
+        if let Some(name) = parser.skipping.take() {
+            let close = format!("</{name}");
+            rest = match find_ascii_ci(rest, &close) {
+                Some(end) => &rest[end..],
+                None => "",
+            };
+        }
+    }
+    parser.finish()
+}
+
+/// Length of the tag, comment or declaration at the start of `s` (which
+/// starts with `<`), or `None` if the `<` is just text.
+fn markup_len(s: &str) -> Option<usize> {
+    let next = s[1..].chars().next()?;
+    if let Some(comment) = s.strip_prefix("<!--") {
+        // An unterminated comment hides the rest, as in browsers
+        return Some(comment.find("-->").map_or(s.len(), |end| end + 7));
+    }
+    if !(next.is_ascii_alphabetic() || matches!(next, '/' | '!' | '?')) {
+        return None;
+    }
+    // A `>` inside a quoted attribute value doesn't end the tag
+    let mut quote = None;
+    for (i, c) in s.char_indices().skip(1) {
+        match (quote, c) {
+            (None, '>') => return Some(i + 1),
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '<') => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn find_ascii_ci(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+#[derive(Default)]
+struct Parser {
+    blocks: Vec<Block>,
+    current: Option<Block>,
+    /// A space is due before the next word.
+    pending_space: bool,
+    bold: u32,
+    italic: u32,
+    underline: u32,
+    code: u32,
+    links: Vec<Option<String>>,
+    /// Open lists, with the next number for ordered ones.
+    lists: Vec<Option<u32>>,
+    quote_depth: u32,
+    pre_depth: u32,
+    /// Element whose contents are being skipped.
+    skipping: Option<String>,
+}
+
+impl Parser {
+    fn style(&self) -> Style {
+        let mut style = Style::empty();
+        style.set(Style::BOLD, self.bold > 0);
+        style.set(Style::ITALIC, self.italic > 0);
+        style.set(Style::UNDERLINE, self.underline > 0);
+        style.set(Style::CODE, self.code > 0);
+        style
+    }
+
+    fn link(&self) -> Option<String> {
+        self.links.iter().rev().flatten().next().cloned()
+    }
+
+    fn markup(&mut self, tag: &str) {
+        let (closing, tag) = match tag.strip_prefix('/') {
+            Some(tag) => (true, tag),
+            None => (false, tag),
+        };
+        let name_len = tag
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(tag.len());
+        let name = tag[..name_len].to_ascii_lowercase();
+        let attrs = &tag[name_len..];
+        // Comments, declarations and processing instructions
+        if name.is_empty() {
+            return;
+        }
+        if closing {
+            self.close(&name);
+        } else {
+            self.open(&name, attrs);
+            // `<br/>`, `<p/>`...
+            if attrs.trim_end().ends_with('/') {
+                self.close(&name);
+            }
+        }
+    }
+
+    fn open(&mut self, name: &str, attrs: &str) {
+        match name {
+            "p" | "div" | "section" | "article" | "header" | "footer" | "hr" | "table" | "tr"
+            | "dl" | "dt" | "dd" => self.end_block(),
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.end_block();
+                let level = (name.as_bytes()[1] - b'0').min(3);
+                self.start_block(BlockKind::Heading(level));
+            }
+            "ul" | "ol" => {
+                self.end_block();
+                let start = attr(attrs, "start").and_then(|start| start.parse().ok());
+                self.lists
+                    .push((name == "ol").then_some(start.unwrap_or(1)));
+            }
+            "li" => {
+                self.end_block();
+                let marker = match self.lists.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        format!("{}.", *n - 1)
+                    }
+                    _ => "•".to_string(),
+                };
+                self.start_block(BlockKind::ListItem(marker));
+            }
+            "pre" => {
+                self.end_block();
+                self.pre_depth += 1;
+                self.start_block(BlockKind::Preformatted);
+            }
+            "blockquote" => {
+                self.end_block();
+                self.quote_depth += 1;
+            }
+            "br" => {
+                self.pending_space = false;
+                self.push('\n');
+            }
+            "b" | "strong" => self.bold += 1,
+            "i" | "em" | "cite" | "var" => self.italic += 1,
+            "u" | "ins" => self.underline += 1,
+            "code" | "tt" | "kbd" | "samp" => self.code += 1,
+            "a" => self.links.push(attr(attrs, "href")),
+            "script" | "style" | "title" => self.skipping = Some(name.to_string()),
+            _ => {}
+        }
+    }
+
+    fn close(&mut self, name: &str) {
+        match name {
+            "p" | "div" | "section" | "article" | "header" | "footer" | "table" | "tr" | "dl"
+            | "dt" | "dd" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => self.end_block(),
+            // Closing tags for lists, items, quotes and preformatted text
+            // that were never opened are ignored
+            "li" => {
+                if let Some(BlockKind::ListItem(_)) = self.current.as_ref().map(|block| &block.kind)
+                {
+                    self.end_block();
+                }
+            }
+            "ul" | "ol" => {
+                if self.lists.pop().is_some() {
+                    self.end_block();
+                }
+            }
+            "pre" if self.pre_depth > 0 => {
+                self.end_block();
+                self.pre_depth -= 1;
+            }
+            "blockquote" if self.quote_depth > 0 => {
+                self.end_block();
+                self.quote_depth -= 1;
+            }
+            "b" | "strong" => self.bold = self.bold.saturating_sub(1),
+            "i" | "em" | "cite" | "var" => self.italic = self.italic.saturating_sub(1),
+            "u" | "ins" => self.underline = self.underline.saturating_sub(1),
+            "code" | "tt" | "kbd" | "samp" => self.code = self.code.saturating_sub(1),
+            "a" => {
+                self.links.pop();
+            }
+            "script" | "style" | "title" => self.skipping = None,
+            _ => {}
+        }
+    }
+
+    fn start_block(&mut self, kind: BlockKind) {
+        self.current = Some(Block {
+            kind,
+            list_depth: self.lists.len() as u32,
+            quote_depth: self.quote_depth,
+            runs: Vec::new(),
+        });
+        self.pending_space = false;
+    }
+
+    fn end_block(&mut self) {
+        if let Some(mut block) = self.current.take() {
+            // Drop trailing line breaks
+            while let Some(run) = block.runs.last_mut() {
+                let trimmed = run.text.trim_end_matches('\n').len();
+                run.text.truncate(trimmed);
+                if !run.text.is_empty() {
+                    break;
+                }
+                block.runs.pop();
+            }
+            if !block.runs.is_empty() {
+                self.blocks.push(block);
+            }
+        }
+        self.pending_space = false;
+    }
+
+    fn text(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let text = decode_entities(text);
+        if self.pre_depth > 0 {
+            // The line break right after `<pre>` isn't part of the text
+            let at_start = self
+                .current
+                .as_ref()
+                .is_some_and(|block| block.runs.is_empty());
+            let text = if at_start {
+                text.strip_prefix('\n').unwrap_or(&text)
+            } else {
+                &text
+            };
+            for c in text.chars() {
+                self.push(if c == '\t' { ' ' } else { c });
+            }
+            return;
+        }
+        for c in text.chars() {
+            if c.is_whitespace() && c != '\u{a0}' {
+                let has_text = self
+                    .current
+                    .as_ref()
+                    .and_then(|block| block.runs.last())
+                    .is_some_and(|run| !run.text.ends_with('\n'));
+                self.pending_space |= has_text;
+            } else {
+                // Between differently styled runs the space is unstyled, so
+                // styles and links neither start nor end with one
+                if std::mem::take(&mut self.pending_space) {
+                    let (style, link) = (self.style(), self.link());
+                    if let Some(block) = self.current.as_mut() {
+                        match block.runs.last_mut() {
+                            Some(run) if run.style == style && run.link == link => {
+                                run.text.push(' ')
+                            }
+                            _ => {
+                                block.runs.push(Run {
+                                    text: " ".to_string(),
+                                    style: Style::empty(),
+                                    link: None,
+                                })
+                            }
+                        }
+                    }
+                }
+                self.push(if c == '\u{a0}' { ' ' } else { c });
+            }
+        }
+    }
+
+    /// Appends a character to the current block, starting a paragraph if
+    /// there is none.
+    fn push(&mut self, c: char) {
+        if self.current.is_none() {
+            if c == '\n' {
+                return;
+            }
+            self.start_block(BlockKind::Paragraph);
+        }
+        let style = self.style();
+        let link = self.link();
+        let Some(block) = self.current.as_mut() else {
+            return;
+        };
+        match block.runs.last_mut() {
+            Some(run) if run.style == style && run.link == link => run.text.push(c),
+            _ => {
+                block.runs.push(Run {
+                    text: c.to_string(),
+                    style,
+                    link,
+                })
+            }
+        }
+    }
+
+    fn finish(mut self) -> Document {
+        self.end_block();
+        Document {
+            blocks: self.blocks,
+        }
+    }
+}
+
+/// The decoded value of attribute `name` in a tag's attribute text.
+fn attr(attrs: &str, name: &str) -> Option<String> {
+    let mut rest = attrs;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+        if rest.is_empty() {
+            return None;
+        }
+        let key_len = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '/')
+            .unwrap_or(rest.len());
+        let key = &rest[..key_len];
+        rest = rest[key_len..].trim_start();
+        let value = match rest.strip_prefix('=') {
+            Some(after) => {
+                let after = after.trim_start();
+                let (value, remaining) = match after.chars().next() {
+                    Some(q @ ('"' | '\'')) => {
+                        let end = after[1..].find(q).map_or(after.len(), |end| end + 1);
+                        (&after[1..end], after.get(end + 1..).unwrap_or(""))
+                    }
+                    _ => {
+                        let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                        (&after[..end], &after[end..])
+                    }
+                };
+                rest = remaining;
+                value
+            }
+            None => "",
+        };
+        if key.eq_ignore_ascii_case(name) {
+            return Some(decode_entities(value));
+        }
+    }
+}
+
+/// Indentation per list level (logical).
+const BASE_LIST_INDENT: f32 = 28.0;
+/// Indentation per quote level (logical).
+const BASE_QUOTE_INDENT: f32 = 16.0;
+/// Horizontal shift per pixel of height for synthetic italics.
+const ITALIC_SLANT: f32 = 0.2;
+/// Heading sizes relative to the body text, for `h1` to `h3`.
+const HEADING_SCALES: [f32; 3] = [1.6, 1.3, 1.15];
+
+/// The body font and the heading fonts.
+pub(crate) struct Fonts<'a> {
+    body: &'a Font,
+    headings: [Font; 3],
+}
+
+impl<'a> Fonts<'a> {
+    pub(crate) fn new(body: &'a Font, body_size: f32) -> Self {
+        Self {
+            body,
+            headings: HEADING_SCALES.map(|factor| Font::load_with_size(body_size * factor)),
+        }
+    }
+
+    fn get(&self, heading: Option<u8>) -> &Font {
+        match heading {
+            Some(level) => &self.headings[(level.clamp(1, 3) - 1) as usize],
+            None => self.body,
+        }
+    }
+}
+
+/// A word placed on a line.
+pub(crate) struct Word {
+    text: String,
+    style: Style,
+    link: Option<String>,
+    x: f32,
+    width: f32,
+}
+
+/// One laid-out line, taking one or more rows of the text area.
+pub(crate) struct Line {
+    words: Vec<Word>,
+    heading: Option<u8>,
+    /// List marker and its x.
+    marker: Option<(String, f32)>,
+    quote_depth: u32,
+    pub(crate) rows: usize,
+}
+
+impl Line {
+    fn blank() -> Self {
+        Self {
+            words: Vec::new(),
+            heading: None,
+            marker: None,
+            quote_depth: 0,
+            rows: 1,
+        }
+    }
+
+    /// The link under `x`, if any.
+    pub(crate) fn link_at(&self, x: f32) -> Option<&str> {
+        self.words
+            .iter()
+            .find(|word| x >= word.x && x < word.x + word.width)
+            .and_then(|word| word.link.as_deref())
+    }
+
+    /// Renders the line onto an opaque text-area background, `row_height`
+    /// pixels per row and `width` wide.
+    pub(crate) fn render(
+        &self,
+        fonts: &Fonts,
+        colors: &Colors,
+        width: u32,
+        row_height: u32,
+        scale: f32,
+    ) -> Canvas {
+        let height = row_height * self.rows as u32;
+        let mut canvas = Canvas::new(width.max(1), height);
+        canvas.fill(colors.input_bg);
+        let font = fonts.get(self.heading);
+        let text_height = font.render("Ag").text_layout().line_height();
+        // Headings sit at the bottom of their rows, closer to what follows
+        let top = if self.heading.is_some() {
+            height as f32 - text_height
+        } else {
+            (height as f32 - text_height) / 2.0
+        };
+        let px = scale.max(1.0);
+
+        for level in 0..self.quote_depth {
+            let x = level as f32 * BASE_QUOTE_INDENT * scale + 2.0 * scale;
+            canvas.fill_rect(x, 0.0, 3.0 * scale, height as f32, colors.input_border);
+        }
+        if let Some((marker, x)) = &self.marker {
+            draw_text(&mut canvas, font, marker, colors.text, *x, top);
+        }
+        for word in &self.words {
+            let color = if word.link.is_some() {
+                colors.input_border_focused
+            } else {
+                colors.text
+            };
+            if word.style.contains(Style::CODE) {
+                canvas.fill_rounded_rect(
+                    word.x - 2.0 * scale,
+                    top,
+                    word.width + 4.0 * scale,
+                    text_height,
+                    3.0 * scale,
+                    colors.input_border,
+                );
+            }
+            let text = font.render(&word.text).with_color(color);
+            let layout = text.text_layout();
+            let rendered = text.finish();
+            let (_, line_top) = layout.position_of(0);
+            let y = (top - line_top).round() as i32;
+            // Synthetic bold strikes again a pixel to the right, and synthetic
+            // italic slants the text
+            let bold = word.style.contains(Style::BOLD) || self.heading.is_some();
+            let strikes = if bold { 2 } else { 1 };
+            for strike in 0..strikes {
+                let x = (word.x + strike as f32 * px).round() as i32;
+                if word.style.contains(Style::ITALIC) {
+                    canvas.draw_canvas_slanted(&rendered, x, y, ITALIC_SLANT);
+                } else {
+                    canvas.draw_canvas(&rendered, x, y);
+                }
+            }
+            if word.style.contains(Style::UNDERLINE) || word.link.is_some() {
+                let baseline = top + font.render("A").text_layout().line_height() * 0.8;
+                canvas.fill_rect(word.x, baseline + px, word.width, px, color);
+            }
+        }
+        canvas
+    }
+}
+
+fn draw_text(
+    canvas: &mut Canvas,
+    font: &Font,
+    text: &str,
+    color: crate::render::Rgba,
+    x: f32,
+    top: f32,
+) {
+    let text = font.render(text).with_color(color);
+    let (_, line_top) = text.text_layout().position_of(0);
+    canvas.draw_canvas(
+        &text.finish(),
+        x.round() as i32,
+        (top - line_top).round() as i32,
+    );
+}
+
+/// Lays out `doc` in lines `width` pixels wide, each taking a whole number
+/// of `row_height` rows. Blocks are separated by a blank row, except for
+/// consecutive list items.
+pub(crate) fn layout(
+    doc: &Document,
+    fonts: &Fonts,
+    width: f32,
+    row_height: u32,
+    scale: f32,
+) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut previous: Option<&Block> = None;
+    for block in &doc.blocks {
+        let tight = matches!(
+            (previous.map(|block| &block.kind), &block.kind),
+            (Some(BlockKind::ListItem(_)), BlockKind::ListItem(_))
+        );
+        if previous.is_some() && !tight {
+            lines.push(Line::blank());
+        }
+        previous = Some(block);
+
+        let heading = match block.kind {
+            BlockKind::Heading(level) => Some(level),
+            _ => None,
+        };
+        let font = fonts.get(heading);
+        let text_height = font.render("Ag").text_layout().line_height();
+        let rows = ((text_height / row_height as f32).ceil() as usize).max(1);
+        let rows = if heading.is_some() { rows.max(2) } else { rows };
+        let space = font.render("a a").measure().0 - font.render("aa").measure().0;
+        let indent = block.quote_depth as f32 * BASE_QUOTE_INDENT * scale
+            + block.list_depth as f32 * BASE_LIST_INDENT * scale;
+        let preformatted = block.kind == BlockKind::Preformatted;
+
+        let mut line = Line {
+            words: Vec::new(),
+            heading,
+            marker: match &block.kind {
+                BlockKind::ListItem(marker) => {
+                    let marker_width = font.render(marker).measure().0;
+                    let x = indent - marker_width - 6.0 * scale;
+                    Some((marker.clone(), x.max(0.0)))
+                }
+                _ => None,
+            },
+            quote_depth: block.quote_depth,
+            rows,
+        };
+        let mut x = indent;
+        let new_line = |line: &mut Line, lines: &mut Vec<Line>| {
+            let next = Line {
+                words: Vec::new(),
+                heading,
+                marker: None,
+                quote_depth: block.quote_depth,
+                rows,
+            };
+            lines.push(std::mem::replace(line, next));
+        };
+
+        // Spaces seen since the last word, which may be in an earlier run
+        let mut spaces = 0;
+        for run in &block.runs {
+            for (i, segment) in run.text.split('\n').enumerate() {
+                if i > 0 {
+                    new_line(&mut line, &mut lines);
+                    x = indent;
+                    spaces = 0;
+                }
+                for (j, piece) in segment.split(' ').enumerate() {
+                    if j > 0 {
+                        spaces += 1;
+                    }
+                    if piece.is_empty() {
+                        continue;
+                    }
+                    let word_width = font.render(piece).measure().0;
+                    let gap = if preformatted || x > indent {
+                        spaces as f32 * space
+                    } else {
+                        0.0
+                    };
+                    if x > indent && x + gap + word_width > width {
+                        new_line(&mut line, &mut lines);
+                        x = indent;
+                    } else {
+                        x += gap;
+                    }
+                    line.words.push(Word {
+                        text: piece.to_string(),
+                        style: run.style,
+                        link: run.link.clone(),
+                        x,
+                        width: word_width,
+                    });
+                    x += word_width;
+                    spaces = 0;
+                }
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Opens `link` with the desktop's handler, without waiting for it.
+pub(crate) fn open_link(link: &str) {
+    let child = Command::new("xdg-open")
+        .arg(link)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    match child {
+        // Reap it once it exits
+        Ok(mut child) => {
+            std::thread::spawn(move || child.wait());
+        }
+        Err(e) => eprintln!("zenity-rs: warning: can't open {link:?}: {e}"),
+    }
+}
+
+/// The HTML subset shown by `--text-info --html`: a sample release-notes
+/// document, entities, and malformed input.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl Document {
+        /// The document as Markdown-like plain text: `#` headings, list markers,
+        /// `>` quotes, fenced preformatted text and `**bold**`, `*italic*`,
+        /// `_underline_`, `` `code` `` and `[text](link)` runs. One line per
+        /// block.
+        fn to_outline(&self) -> String {
+            let mut out = String::new();
+            for block in &self.blocks {
+                let quote = "> ".repeat(block.quote_depth as usize);
+                let text: String = block.runs.iter().map(outline_run).collect();
+                out.push_str(&quote);
+                match &block.kind {
+                    BlockKind::Paragraph => {}
+                    BlockKind::Heading(level) => {
+                        out.push_str(&"#".repeat(*level as usize));
+                        out.push(' ');
+                    }
+                    BlockKind::ListItem(marker) => {
+                        out.push_str(&"  ".repeat(block.list_depth.saturating_sub(1) as usize));
+                        out.push_str(marker);
+                        out.push(' ');
+                    }
+                    BlockKind::Preformatted => {
+                        let plain: String =
+                            block.runs.iter().map(|run| run.text.as_str()).collect();
+                        out.push_str("```\n");
+                        out.push_str(&plain);
+                        out.push_str("\n```\n");
+                        continue;
+                    }
+                }
+                out.push_str(&text.replace('\n', "\\\n"));
+                out.push('\n');
+            }
+            out
+        }
+    }
+
+    fn outline_run(run: &Run) -> String {
+        let mut text = run.text.clone();
+        for (style, mark) in [
+            (Style::CODE, "`"),
+            (Style::UNDERLINE, "_"),
+            (Style::ITALIC, "*"),
+            (Style::BOLD, "**"),
+        ] {
+            if run.style.contains(style) {
+                text = format!("{mark}{text}{mark}");
+            }
+        }
+        match &run.link {
+            Some(link) => format!("[{text}]({link})"),
+            None => text,
+        }
+    }
+
+    const RELEASE_NOTES: &str = r#"<!DOCTYPE html>
+<html><head><title>Release notes</title>
+<style>body { font-family: sans-serif; }</style></head>
+<body>
+<h1>Version 2.4</h1>
+<p>This release brings <b>faster startup</b> and a few <i>small</i> fixes.
+See the <a href="https://example.org/changelog?from=2.3&amp;to=2.4">full changelog</a>.</p>
+<h2>New</h2>
+<ul>
+  <li>Dark theme &mdash; follows the desktop</li>
+  <li>Run <code>app --check</code> to verify
+    <ul><li>nested item</li></ul>
+  </li>
+</ul>
+<h3>Upgrading</h3>
+<ol start="3">
+  <li>Back up &lt;config&gt;</li>
+  <li>Install</li>
+</ol>
+<pre>
+$ app --version
+app 2.4
+</pre>
+<blockquote><p>Thanks to <u>everyone</u> who tested!</p></blockquote>
+<script>alert("never shown")</script>
+</body></html>
+"#;
+
+    const OUTLINE: &str = "\
+# Version 2.4
+This release brings **faster startup** and a few *small* fixes. See the [full changelog](https://example.org/changelog?from=2.3&to=2.4).
+## New
+• Dark theme — follows the desktop
+• Run `app --check` to verify
+  • nested item
+### Upgrading
+3. Back up <config>
+4. Install
+```
+$ app --version
+app 2.4
+```
+> Thanks to _everyone_ who tested!
+";
+
+    #[test]
+    fn release_notes_snapshot() {
+        let doc = parse(RELEASE_NOTES);
+        assert_eq!(doc.to_outline(), OUTLINE);
+    }
+
+    #[test]
+    fn block_structure() {
+        let doc = parse(RELEASE_NOTES);
+        let kinds: Vec<&BlockKind> = doc.blocks.iter().map(|block| &block.kind).collect();
+        assert_eq!(kinds[0], &BlockKind::Heading(1));
+        assert_eq!(kinds[1], &BlockKind::Paragraph);
+        assert_eq!(kinds[3], &BlockKind::ListItem("•".into()));
+        let nested = &doc.blocks[5];
+        assert_eq!(
+            (nested.list_depth, nested.runs[0].text.as_str()),
+            (2, "nested item")
+        );
+        let link = doc.blocks[1]
+            .runs
+            .iter()
+            .find(|run| run.link.is_some())
+            .unwrap();
+        assert_eq!(link.text, "full changelog");
+        let bold = doc.blocks[1]
+            .runs
+            .iter()
+            .find(|run| run.style == Style::BOLD)
+            .unwrap();
+        assert_eq!(bold.text, "faster startup");
+        assert_eq!(doc.blocks.last().unwrap().quote_depth, 1);
+    }
+
+    #[test]
+    fn line_breaks_and_whitespace() {
+        let doc = parse("<p>one\n   two<br>three<br/>  four</p>");
+        assert_eq!(doc.to_outline(), "one two\\\nthree\\\nfour\n");
+        // Text outside any block still shows
+        assert_eq!(parse("just   text").to_outline(), "just text\n");
+    }
+
+    #[test]
+    fn entities() {
+        assert_eq!(
+            decode_entities("&lt;a&gt; &amp; &quot;b&quot; &#233; &#x2014; &nbsp;"),
+            "<a> & \"b\" é — \u{a0}"
+        );
+        // Unknown, unterminated and invalid references are kept as written
+        assert_eq!(
+            decode_entities("&bogus; & &amp &#xZZ; &#0; AT&T"),
+            "&bogus; & &amp &#xZZ; &#0; AT&T"
+        );
+    }
+
+    #[test]
+    fn malformed_markup_degrades_to_text() {
+        let cases = [
+            ("1 < 2 and 3 > 2", "1 < 2 and 3 > 2\n"),
+            ("<b>unclosed bold", "**unclosed bold**\n"),
+            ("stray </i></ul></blockquote> closers", "stray closers\n"),
+            ("<p>cut off <a href=\"x", "cut off <a href=\"x\n"),
+            ("<!-- never closed", ""),
+            ("a <unknown attr=1>b</unknown> c", "a b c\n"),
+            ("<li>item without list", "• item without list\n"),
+            ("<a href='it&apos;s'>q</a>", "[q](it's)\n"),
+            ("<p title=\"a > b\">quoted</p>", "quoted\n"),
+        ];
+        for (html, outline) in cases {
+            assert_eq!(parse(html).to_outline(), outline, "{html}");
+        }
+    }
+
+    #[test]
+    fn fuzzed_input_never_panics() {
+        const PIECES: &[&str] = &[
+            "<",
+            ">",
+            "</",
+            "/>",
+            "<p>",
+            "</p>",
+            "<b>",
+            "</b>",
+            "<i>",
+            "<ul>",
+            "</ul>",
+            "<li>",
+            "<ol start=x>",
+            "<pre>",
+            "</pre>",
+            "<blockquote>",
+            "<a href=\"",
+            "\"",
+            "'",
+            "&",
+            "&amp;",
+            "&#",
+            "&#x",
+            ";",
+            "<!--",
+            "-->",
+            "<script>",
+            "</script",
+            "<h1>",
+            "<h7>",
+            "text",
+            " ",
+            "\n",
+            "é",
+            "漢字",
+            "🙂",
+            "<br>",
+            "=",
+            "<!DOCTYPE",
+            "<?",
+            "<code>",
+            "\u{a0}",
+        ];
+        // A fixed linear congruential generator keeps the cases reproducible
+        let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..2000 {
+            let mut html = String::new();
+            let len = (seed >> 59) as usize + 1;
+            for _ in 0..len * 2 {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                html.push_str(PIECES[(seed >> 33) as usize % PIECES.len()]);
+            }
+            let doc = parse(&html);
+            let _ = doc.to_outline();
+            // Words outside of markup survive
+            if !html.contains('<') && !html.contains('&') {
+                for word in html.split_whitespace() {
+                    assert!(doc.to_outline().contains(word), "{html:?}");
+                }
+            }
+        }
+    }
+}
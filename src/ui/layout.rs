@@ -0,0 +1,291 @@
+//! Labelled input rows, shared by the forms, entry and file save dialogs.
+//!
+//! Labels sit in a right-aligned column to the left of their inputs, as wide
+//! as the widest label but no more than 40% of the row; longer labels wrap.
+//! With [`LabelPlacement::Above`] each label is stacked over its input
+//! instead, which suits narrow dialogs.
+
+use crate::render::{Canvas, Font, Rgba};
+
+/// Gap between the label column and the inputs (logical).
+pub const BASE_LABEL_GUTTER: u32 = 10;
+/// Gap between a stacked label and its input (logical).
+const BASE_LABEL_ABOVE_GAP: u32 = 4;
+/// Largest share of the row the label column may take.
+const MAX_LABEL_SHARE: f32 = 0.4;
+
+/// Where field labels go relative to their inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LabelPlacement {
+    /// In a right-aligned column to the left of the inputs.
+    #[default]
+    Side,
+    /// Stacked above each input.
+    Above,
+}
+
+impl LabelPlacement {
+    /// Parses a `--field-labels` value: `side` (or `left`) or `above`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "side" | "left" => Some(LabelPlacement::Side),
+            "above" | "top" => Some(LabelPlacement::Above),
+            _ => None,
+        }
+    }
+}
+
+/// Where one row's label and input go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RowLayout {
+    pub label_x: i32,
+    pub label_y: i32,
+    pub input_x: i32,
+    pub input_y: i32,
+    /// Height of the whole row.
+    pub height: u32,
+}
+
+/// The label column for a set of rows, in the font's (physical) pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LabelColumn {
+    placement: LabelPlacement,
+    row_width: u32,
+    /// Width labels wrap at.
+    label_width: u32,
+    gutter: u32,
+    above_gap: u32,
+}
+
+impl LabelColumn {
+    /// Sizes the column for `labels` in rows `row_width` pixels wide.
+    pub fn new(
+        font: &Font,
+        labels: &[&str],
+        row_width: u32,
+        placement: LabelPlacement,
+        scale: f32,
+    ) -> Self {
+        let widest = labels
+            .iter()
+            .map(|label| font.render(label).measure().0.ceil() as u32 + 2)
+            .max()
+            .unwrap_or(0);
+        let label_width = match placement {
+            LabelPlacement::Side => widest.min((row_width as f32 * MAX_LABEL_SHARE) as u32),
+            LabelPlacement::Above => row_width,
+        };
+        Self {
+            placement,
+            row_width,
+            label_width,
+            gutter: (BASE_LABEL_GUTTER as f32 * scale) as u32,
+            above_gap: (BASE_LABEL_ABOVE_GAP as f32 * scale) as u32,
+        }
+    }
+
+    /// Offset of the inputs from the start of the row.
+    pub fn input_offset(&self) -> u32 {
+        match self.placement {
+            LabelPlacement::Side => self.label_width + self.gutter,
+            LabelPlacement::Above => 0,
+        }
+    }
+
+    /// Width left for the inputs.
+    pub fn input_width(&self) -> u32 {
+        self.row_width.saturating_sub(self.input_offset())
+    }
+
+    /// Renders a label wrapped to the column.
+    pub fn render_label(&self, font: &Font, label: &str, color: Rgba) -> Canvas {
+        font.render(label)
+            .with_color(color)
+            .with_max_width(self.label_width.saturating_sub(2) as f32)
+            .finish()
+    }
+
+    /// Lays out a row starting at (`x`, `y`) holding `label` (as rendered by
+    /// [`render_label`](Self::render_label)) and an input `input_height` tall.
+    pub fn place(&self, label: &Canvas, x: i32, y: i32, input_height: u32) -> RowLayout {
+        match self.placement {
+            LabelPlacement::Side => {
+                // Right-aligned in the column, the shorter of the two
+                // centered on the taller
+                let height = input_height.max(label.height());
+                let label_x = x + (self.label_width as i32 - label.width() as i32).max(0);
+                RowLayout {
+                    label_x,
+                    label_y: y + (height - label.height()) as i32 / 2,
+                    input_x: x + self.input_offset() as i32,
+                    input_y: y + (height - input_height) as i32 / 2,
+                    height,
+                }
+            }
+            LabelPlacement::Above => {
+                RowLayout {
+                    label_x: x,
+                    label_y: y,
+                    input_x: x,
+                    input_y: y + (label.height() + self.above_gap) as i32,
+                    height: label.height() + self.above_gap + input_height,
+                }
+            }
+        }
+    }
+}
+
+/// Layout of labelled input rows (`--field-labels`) with short, long and
+/// CJK labels, beside and above the inputs.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{render::rgb, ui::zoom::MAX_ZOOM};
+
+    const ROW_WIDTH: u32 = 400;
+    const INPUT_HEIGHT: u32 = 32;
+    const SHORT: [&str; 2] = ["Name", "E-mail"];
+    const LONG: &str = "Name of the person to contact in an emergency";
+    const CJK: &str = "緊急時に連絡する人の名前とメールアドレスと電話番号";
+
+    fn column(font: &Font, labels: &[&str], placement: LabelPlacement) -> LabelColumn {
+        LabelColumn::new(font, labels, ROW_WIDTH, placement, 1.0)
+    }
+
+    #[test]
+    fn short_labels_are_right_aligned_in_a_shared_column() {
+        let font = Font::load(1.0);
+        let column = column(&font, &SHORT, LabelPlacement::Side);
+        let widest = font.render("E-mail").measure().0.ceil() as u32 + 2;
+        assert_eq!(column.label_width, widest);
+        assert_eq!(column.input_width(), ROW_WIDTH - column.input_offset());
+
+        let mut y = 0;
+        for label in SHORT {
+            let canvas = column.render_label(&font, label, rgb(0, 0, 0));
+            let row = column.place(&canvas, 20, y, INPUT_HEIGHT);
+            assert_eq!(
+                row.label_x + canvas.width() as i32,
+                20 + column.label_width as i32,
+                "{label}"
+            );
+            assert_eq!(row.input_x, 20 + column.input_offset() as i32);
+            assert_eq!(row.height, INPUT_HEIGHT);
+            // Centered on the input
+            assert!(row.label_y > y && row.label_y + (canvas.height() as i32) < y + 32);
+            y += row.height as i32;
+        }
+    }
+
+    #[test]
+    fn long_labels_wrap_within_the_column_cap() {
+        let font = Font::load(1.0);
+        for label in [LONG, CJK] {
+            let column = column(&font, &[label, "Name"], LabelPlacement::Side);
+            assert_eq!(column.label_width, ROW_WIDTH * 2 / 5, "{label}");
+            assert!(column.input_width() >= ROW_WIDTH * 3 / 5 - 20, "{label}");
+
+            let one_line = font.render("Name").finish().height();
+            let canvas = column.render_label(&font, label, rgb(0, 0, 0));
+            assert!(canvas.width() <= column.label_width + 1, "{label}");
+            assert!(canvas.height() > one_line * 3 / 2, "{label} didn't wrap");
+
+            let row = column.place(&canvas, 0, 0, INPUT_HEIGHT);
+            assert_eq!(row.height, canvas.height().max(INPUT_HEIGHT));
+            assert_eq!(row.input_x, column.input_offset() as i32);
+        }
+    }
+
+    #[test]
+    fn labels_above_stack_over_full_width_inputs() {
+        let font = Font::load(1.0);
+        for labels in [&SHORT[..], &[LONG], &[CJK]] {
+            let column = column(&font, labels, LabelPlacement::Above);
+            assert_eq!(column.input_offset(), 0);
+            assert_eq!(column.input_width(), ROW_WIDTH);
+
+            for label in labels {
+                let canvas = column.render_label(&font, label, rgb(0, 0, 0));
+                let row = column.place(&canvas, 20, 100, INPUT_HEIGHT);
+                assert_eq!((row.label_x, row.label_y), (20, 100));
+                assert_eq!(row.input_x, 20);
+                assert!(row.input_y >= 100 + canvas.height() as i32, "{label}");
+                assert_eq!(row.height, (row.input_y - 100) as u32 + INPUT_HEIGHT);
+            }
+        }
+    }
+
+    #[test]
+    fn placement_names() {
+        assert_eq!(
+            LabelPlacement::from_name("side"),
+            Some(LabelPlacement::Side)
+        );
+        assert_eq!(
+            LabelPlacement::from_name("left"),
+            Some(LabelPlacement::Side)
+        );
+        assert_eq!(
+            LabelPlacement::from_name("above"),
+            Some(LabelPlacement::Above)
+        );
+        assert_eq!(LabelPlacement::from_name("below"), None);
+        assert_eq!(LabelPlacement::default(), LabelPlacement::Side);
+    }
+
+    #[test]
+    fn a_200_character_label_stays_in_its_column() {
+        let font = Font::load(1.0);
+        let words = "word ".repeat(40);
+        // Nowhere to break but between characters
+        let solid = "x".repeat(200);
+        for label in [words.trim_end(), &solid] {
+            let column = column(&font, &[label, "Name"], LabelPlacement::Side);
+            assert_eq!(column.label_width, ROW_WIDTH * 2 / 5);
+            let canvas = column.render_label(&font, label, rgb(0, 0, 0));
+            assert!(canvas.width() <= column.label_width + 1);
+            assert!(canvas.height() > INPUT_HEIGHT * 3);
+
+            // The rows after it start below its last line, and inputs keep
+            // their column whatever the label's height
+            let long = column.place(&canvas, 20, 0, INPUT_HEIGHT);
+            assert_eq!(long.label_y, 0);
+            assert_eq!(long.height, canvas.height());
+            let short = column.render_label(&font, "Name", rgb(0, 0, 0));
+            let next = column.place(&short, 20, long.height as i32, INPUT_HEIGHT);
+            assert!(next.label_y >= long.label_y + canvas.height() as i32);
+            assert_eq!(next.input_x, long.input_x);
+            assert_eq!(next.height, INPUT_HEIGHT);
+        }
+    }
+
+    #[test]
+    fn labelled_rows_keep_their_shape_at_max_zoom() {
+        let labels = ["Name", "E-mail address"];
+        let normal_font = Font::load(1.0);
+        let zoomed_font = Font::load(MAX_ZOOM);
+        let normal = LabelColumn::new(&normal_font, &labels, 400, LabelPlacement::Side, 1.0);
+        let zoomed = LabelColumn::new(&zoomed_font, &labels, 800, LabelPlacement::Side, MAX_ZOOM);
+
+        // Everything grows with the zoom, within a few pixels of rounding
+        let doubled = |small: u32, big: u32| big.abs_diff(small * 2) <= 4;
+        assert!(doubled(normal.label_width, zoomed.label_width));
+        assert!(doubled(normal.input_offset(), zoomed.input_offset()));
+        assert!(doubled(normal.input_width(), zoomed.input_width()));
+
+        // Labels still fit their column and sit beside their inputs
+        let mut y = 0;
+        for label in labels {
+            let canvas = zoomed.render_label(&zoomed_font, label, rgb(0, 0, 0));
+            assert!(canvas.width() <= zoomed.label_width + 1, "{label}");
+            let row = zoomed.place(&canvas, 0, y, 64);
+            assert!(
+                row.label_x + canvas.width() as i32 <= row.input_x,
+                "{label}"
+            );
+            assert!(row.input_x as u32 + zoomed.input_width() <= 800);
+            assert!(row.height >= 64);
+            y += row.height as i32;
+        }
+    }
+}
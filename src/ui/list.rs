@@ -1,18 +1,34 @@
 //! List selection dialog implementation.
 
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
 use crate::{
-    backend::{MouseButton, Window, WindowEvent, create_window},
+    backend::{CursorShape, MouseButton, Window, WindowEvent, create_window},
     error::Error,
-    render::{Canvas, Font, rgb},
+    render::{Canvas, EllipsizeMode, Font, RingBufferCache, rgb},
     ui::{
         BASE_BUTTON_HEIGHT, BASE_BUTTON_SPACING, BASE_CORNER_RADIUS, Colors, KEY_DOWN, KEY_ESCAPE,
-        KEY_LEFT, KEY_LSHIFT, KEY_RETURN, KEY_RIGHT, KEY_RSHIFT, KEY_SPACE, KEY_UP,
-        widgets::{Widget, button::Button},
+        KEY_LEFT, KEY_LSHIFT, KEY_PAGE_DOWN, KEY_PAGE_UP, KEY_RETURN, KEY_RIGHT, KEY_RSHIFT,
+        KEY_SPACE, KEY_UP,
+        geometry::Offset,
+        markup::{self, Markup},
+        wait_for_event_until,
+        widgets::{MULTI_CLICK_TIME, Widget, button::Button, point_in_rect, text_input::TextInput},
+        zoom,
     },
 };
 
 const BASE_PADDING: u32 = 16;
 const BASE_ROW_HEIGHT: u32 = 28;
+/// Widest a column starts out; longer cells are ellipsized.
+const BASE_MAX_COLUMN_WIDTH: u32 = 300;
+/// Narrowest a column can be dragged.
+const BASE_MIN_COLUMN_WIDTH: u32 = 40;
 const BASE_CHECKBOX_SIZE: u32 = 16;
 const BASE_MIN_WIDTH: u32 = 350;
 const BASE_MAX_WIDTH: u32 = 600;
@@ -22,20 +38,81 @@ const BASE_MAX_HEIGHT: u32 = 450;
 /// List dialog result.
 #[derive(Debug, Clone)]
 pub enum ListResult {
-    /// User selected item(s). Contains the values from the first column.
+    /// User selected item(s). Contains the printed values of the selected
+    /// rows in order: one per row, or every column of each with
+    /// [`PrintColumn::All`].
     Selected(Vec<String>),
     /// User cancelled.
     Cancelled,
     /// Dialog was closed.
     Closed,
+    /// The timeout passed.
+    Timeout,
 }
 
 impl ListResult {
+    /// The exit code the command-line tool uses for this result: 0 when
+    /// rows are selected, 1 when cancelled or closed, 5 on timeout.
     pub fn exit_code(&self) -> i32 {
         match self {
             ListResult::Selected(_) => 0,
             ListResult::Cancelled => 1,
             ListResult::Closed => 1,
+            ListResult::Timeout => 5,
+        }
+    }
+
+    /// The selected values joined by `separator`, used verbatim, as printed
+    /// on stdout. `None` unless something was selected.
+    pub fn joined(&self, separator: &str) -> Option<String> {
+        match self {
+            ListResult::Selected(items) => Some(items.join(separator)),
+            _ => None,
+        }
+    }
+}
+
+/// Which column of a selected row is printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrintColumn {
+    /// The first column, or the first after the toggles of a checklist or
+    /// radiolist.
+    #[default]
+    Default,
+    /// Column N, 1-based and counting the toggle column like zenity.
+    Column(usize),
+    /// Every column, hidden ones included.
+    All,
+}
+
+impl PrintColumn {
+    /// Parses a `--print-column` value: a column number or `ALL`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("all") {
+            return Some(PrintColumn::All);
+        }
+        match name.parse() {
+            Ok(0) | Err(_) => None,
+            Ok(n) => Some(PrintColumn::Column(n)),
+        }
+    }
+
+    /// The values printed for `row`, as given minus any TRUE/FALSE toggle
+    /// column in `mode`.
+    pub fn values(self, row: &[String], mode: ListMode) -> Vec<String> {
+        let toggles = matches!(mode, ListMode::Checklist | ListMode::Radiolist);
+        match self {
+            PrintColumn::All => row.to_vec(),
+            PrintColumn::Default => row.first().cloned().into_iter().collect(),
+            // Only checked rows are printed, so their toggle is always TRUE
+            PrintColumn::Column(1) if toggles => vec!["TRUE".to_string()],
+            PrintColumn::Column(n) => {
+                n.checked_sub(if toggles { 2 } else { 1 })
+                    .and_then(|index| row.get(index))
+                    .cloned()
+                    .into_iter()
+                    .collect()
+            }
         }
     }
 }
@@ -53,6 +130,66 @@ pub enum ListMode {
     Multiple,
 }
 
+impl ListMode {
+    /// Activates the toggle of `row` in `checked`: a radio button becomes the
+    /// only one checked, anything else flips.
+    pub fn toggle(self, checked: &mut [bool], row: usize) {
+        if row >= checked.len() {
+            return;
+        }
+        match self {
+            ListMode::Radiolist => {
+                for (i, c) in checked.iter_mut().enumerate() {
+                    *c = i == row;
+                }
+            }
+            _ => checked[row] = !checked[row],
+        }
+    }
+
+    /// Whether a click `x` pixels into a row (scrolling included) lands on
+    /// its toggle rather than its text.
+    pub fn hits_toggle(self, x: i32, toggle_width: u32) -> bool {
+        matches!(self, ListMode::Checklist | ListMode::Radiolist)
+            && (0..toggle_width as i32).contains(&x)
+    }
+
+    /// The visible column under a click `x` pixels into a row (scrolling
+    /// included), given the column widths and the gap between columns.
+    pub fn column_at(
+        self,
+        x: i32,
+        toggle_width: u32,
+        col_widths: &[u32],
+        column_gap: u32,
+    ) -> Option<usize> {
+        (0..col_widths.len()).find(|&col| {
+            let start = self.column_start(col, toggle_width, col_widths, column_gap) as i32;
+            (start..start + col_widths[col] as i32).contains(&x)
+        })
+    }
+
+    /// Where column `col` starts, `x` pixels into a row.
+    fn column_start(
+        self,
+        col: usize,
+        toggle_width: u32,
+        col_widths: &[u32],
+        column_gap: u32,
+    ) -> u32 {
+        let toggle_gap = match self {
+            ListMode::Checklist | ListMode::Radiolist => column_gap,
+            ListMode::Single | ListMode::Multiple => 0,
+        };
+        toggle_width
+            + toggle_gap
+            + col_widths[..col]
+                .iter()
+                .map(|w| w + column_gap)
+                .sum::<u32>()
+    }
+}
+
 /// List dialog builder.
 pub struct ListBuilder {
     title: String,
@@ -61,9 +198,22 @@ pub struct ListBuilder {
     rows: Vec<Vec<String>>,
     mode: ListMode,
     hidden_columns: Vec<usize>,
+    print_column: PrintColumn,
+    hide_header: bool,
+    imagelist: bool,
+    editable: bool,
+    mid_search: bool,
+    no_markup: bool,
+    /// The column rows are sorted by (0-based, counting every column) and
+    /// whether it's descending.
+    sort: Option<(usize, bool)>,
     width: Option<u32>,
     height: Option<u32>,
-    colors: Option<&'static Colors>,
+    timeout: Option<u32>,
+    position: Option<(Offset, Offset)>,
+    colors: Option<Colors>,
+    /// Checked rows and the single selection carried over a zoom rebuild.
+    restored: Option<(Vec<bool>, Option<usize>)>,
 }
 
 impl ListBuilder {
@@ -75,9 +225,19 @@ impl ListBuilder {
             rows: Vec::new(),
             mode: ListMode::Single,
             hidden_columns: Vec::new(),
+            print_column: PrintColumn::Default,
+            hide_header: false,
+            imagelist: false,
+            editable: false,
+            mid_search: false,
+            no_markup: false,
+            sort: None,
             width: None,
             height: None,
+            timeout: None,
+            position: None,
             colors: None,
+            restored: None,
         }
     }
 
@@ -91,6 +251,14 @@ impl ListBuilder {
         self
     }
 
+    /// Show the prompt and cells literally instead of as Pango
+    /// [markup](markup). Either way the values printed, searched and sorted
+    /// by are the cells as given.
+    pub fn no_markup(mut self, no_markup: bool) -> Self {
+        self.no_markup = no_markup;
+        self
+    }
+
     /// Add a column header.
     pub fn column(mut self, name: &str) -> Self {
         self.columns.push(name.to_string());
@@ -127,8 +295,8 @@ impl ListBuilder {
         self
     }
 
-    pub fn colors(mut self, colors: &'static Colors) -> Self {
-        self.colors = Some(colors);
+    pub fn colors(mut self, colors: impl Borrow<Colors>) -> Self {
+        self.colors = Some(*colors.borrow());
         self
     }
 
@@ -142,6 +310,18 @@ impl ListBuilder {
         self
     }
 
+    /// Set timeout in seconds. Dialog will auto-close after this time.
+    pub fn timeout(mut self, seconds: u32) -> Self {
+        self.timeout = Some(seconds);
+        self
+    }
+
+    /// Request a window position. Ignored where the platform doesn't allow it.
+    pub fn position(mut self, x: Offset, y: Offset) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
     /// Hide a column by index (1-based, like zenity).
     /// Hidden columns are not displayed but their values are still included in output.
     pub fn hide_column(mut self, col: usize) -> Self {
@@ -151,11 +331,63 @@ impl ListBuilder {
         self
     }
 
-    pub fn show(self) -> Result<ListResult, Error> {
-        let colors = self.colors.unwrap_or_else(|| crate::ui::detect_theme());
+    /// Choose which column of the selected rows is printed.
+    pub fn print_column(mut self, column: PrintColumn) -> Self {
+        self.print_column = column;
+        self
+    }
+
+    /// Leave out the row of column headers.
+    pub fn hide_header(mut self, hide: bool) -> Self {
+        self.hide_header = hide;
+        self
+    }
+
+    /// Show the first column (after any toggles) as small images, its values
+    /// being paths to PNG files. It's never printed.
+    pub fn imagelist(mut self, imagelist: bool) -> Self {
+        self.imagelist = imagelist;
+        self
+    }
+
+    /// Let the user edit a cell by double-clicking it, rather than accept the
+    /// row. Edited values are the ones printed.
+    pub fn editable(mut self, editable: bool) -> Self {
+        self.editable = editable;
+        self
+    }
+
+    /// Let typeahead searches match anywhere in a cell, not only at its start.
+    pub fn mid_search(mut self, mid_search: bool) -> Self {
+        self.mid_search = mid_search;
+        self
+    }
+
+    /// Sort the rows by a column (1-based, like zenity), descending if
+    /// `descending`. Clicking a column header sorts by it too.
+    pub fn sort_by_column(mut self, col: usize, descending: bool) -> Self {
+        self.sort = col.checked_sub(1).map(|col| (col, descending));
+        self
+    }
+
+    pub fn show(mut self) -> Result<ListResult, Error> {
+        let deadline = self
+            .timeout
+            .map(|secs| Instant::now() + Duration::from_secs(secs as u64));
+        loop {
+            if let Some(result) = self.run(deadline)? {
+                return Ok(result);
+            }
+        }
+    }
+
+    /// Shows the dialog until it's answered, or returns `None` to be run
+    /// again after a [zoom](crate::ui::zoom) change.
+    fn run(&mut self, deadline: Option<Instant>) -> Result<Option<ListResult>, Error> {
+        let colors = &self.colors.unwrap_or_else(|| *crate::ui::detect_theme());
 
         // Process rows - for checklist/radiolist, first column is TRUE/FALSE
-        let (rows, mut selected): (Vec<Vec<String>>, Vec<bool>) = match self.mode {
+        let (mut rows, mut selected): (Vec<Vec<String>>, Vec<bool>) = match self.mode {
             ListMode::Checklist | ListMode::Radiolist => {
                 let mut processed_rows = Vec::new();
                 let mut selections = Vec::new();
@@ -167,6 +399,12 @@ impl ListBuilder {
                         processed_rows.push(row[1..].to_vec());
                     }
                 }
+                // A radiolist starts with at most one row checked
+                if self.mode == ListMode::Radiolist
+                    && let Some(first) = selections.iter().position(|&s| s)
+                {
+                    ListMode::Radiolist.toggle(&mut selections, first);
+                }
                 (processed_rows, selections)
             }
             ListMode::Single | ListMode::Multiple => {
@@ -208,11 +446,26 @@ impl ListBuilder {
             ListMode::Single | ListMode::Multiple => self.hidden_columns.clone(),
         };
 
+        // The sort column, in the same terms
+        let toggle_columns = match self.mode {
+            ListMode::Checklist | ListMode::Radiolist => 1,
+            ListMode::Single | ListMode::Multiple => 0,
+        };
+        let mut sort = self
+            .sort
+            .and_then(|(col, descending)| Some((col.checked_sub(toggle_columns)?, descending)));
+
         // Determine which columns are visible (not hidden)
         let visible_col_indices: Vec<usize> = (0..all_columns.len())
             .filter(|i| !adjusted_hidden.contains(i))
             .collect();
 
+        // With --imagelist the first data column shows images, not text
+        let image_col = visible_col_indices
+            .iter()
+            .position(|&i| i == 0)
+            .filter(|_| self.imagelist);
+
         // Get visible columns only
         let columns: Vec<&str> = visible_col_indices
             .iter()
@@ -220,15 +473,23 @@ impl ListBuilder {
             .collect();
 
         // Create display rows with only visible columns (original rows kept for result)
-        let display_rows: Vec<Vec<String>> = rows
-            .iter()
-            .map(|row| {
-                visible_col_indices
-                    .iter()
-                    .filter_map(|&i| row.get(i).cloned())
-                    .collect()
-            })
-            .collect();
+        let visible_cells = |row: &[String]| -> Vec<String> {
+            visible_col_indices
+                .iter()
+                .enumerate()
+                .filter_map(|(vi, &i)| {
+                    let cell = row.get(i)?;
+                    Some(if image_col == Some(vi) {
+                        String::new()
+                    } else {
+                        cell.clone()
+                    })
+                })
+                .collect()
+        };
+        let mut display_rows: Vec<Vec<String>> =
+            rows.iter().map(|row| visible_cells(row)).collect();
+        let show_header = !self.hide_header && !columns.is_empty();
 
         let num_cols = columns.len().max(1);
         let num_rows = rows.len();
@@ -238,22 +499,37 @@ impl ListBuilder {
 
         // First pass: calculate LOGICAL dimensions using scale 1.0
         let temp_font = Font::load(1.0);
+        let no_markup = self.no_markup;
+        let styled = |text: &str| {
+            if no_markup {
+                Markup::plain(text)
+            } else {
+                markup::parse(text)
+            }
+        };
 
         // Calculate logical column widths (only for visible columns)
         let mut logical_col_widths: Vec<u32> = vec![100; num_cols];
+        if let Some(ci) = image_col {
+            logical_col_widths[ci] = BASE_ROW_HEIGHT;
+        }
         for (i, col) in columns.iter().enumerate() {
             let (w, _) = temp_font.render(col).measure();
             logical_col_widths[i] = logical_col_widths[i].max(w as u32 + 20);
         }
         for row in &rows {
             for (vi, &orig_i) in visible_col_indices.iter().enumerate() {
-                if let Some(cell) = row.get(orig_i) {
-                    let (w, _) = temp_font.render(cell).measure();
+                if image_col != Some(vi)
+                    && let Some(cell) = row.get(orig_i)
+                {
+                    let cell = styled(cell);
+                    let (w, _) = temp_font.render(&cell.text).with_runs(&cell.runs).measure();
                     logical_col_widths[vi] = logical_col_widths[vi].max(w as u32 + 20);
                 }
             }
         }
         drop(temp_font);
+        let logical_col_widths = fit_column_widths(&logical_col_widths, BASE_MAX_COLUMN_WIDTH, 0);
 
         // Calculate logical total width (including gaps between columns)
         let logical_checkbox_col = if self.mode != ListMode::Single {
@@ -271,24 +547,28 @@ impl ListBuilder {
         // Calculate logical height
         let logical_title_height = if self.title.is_empty() { 0 } else { 32 };
         let logical_text_height = if self.text.is_empty() { 0 } else { 24 };
-        let logical_header_height = if columns.is_empty() {
-            0
-        } else {
-            BASE_ROW_HEIGHT
-        };
+        let logical_header_height = if !show_header { 0 } else { BASE_ROW_HEIGHT };
         let logical_list_height =
             (num_rows as u32 * BASE_ROW_HEIGHT).clamp(BASE_ROW_HEIGHT * 3, BASE_MAX_HEIGHT - 100);
-        let calc_height = (BASE_PADDING * 2
+        let logical_chrome_height = BASE_PADDING * 2
             + logical_title_height
             + logical_text_height
             + logical_header_height
-            + logical_list_height
-            + 50)
-            .clamp(BASE_MIN_HEIGHT, BASE_MAX_HEIGHT);
-
-        // Use custom dimensions if provided, otherwise use calculated defaults
-        let logical_width = self.width.unwrap_or(calc_width);
-        let logical_height = self.height.unwrap_or(calc_height);
+            + 50;
+        let calc_height =
+            (logical_chrome_height + logical_list_height).clamp(BASE_MIN_HEIGHT, BASE_MAX_HEIGHT);
+
+        // Custom dimensions resize the list, which scrolls rather than shrinking
+        // below three rows or squeezing the buttons together
+        let logical_width = self.width.map_or(calc_width, |w| w.max(BASE_MIN_WIDTH));
+        let logical_height = self.height.map_or(calc_height, |h| {
+            h.max(logical_chrome_height + BASE_ROW_HEIGHT * 3)
+        });
+        let logical_list_height = if self.height.is_some() {
+            logical_height - logical_chrome_height
+        } else {
+            logical_list_height
+        };
 
         // Create window with LOGICAL dimensions
         let mut window = create_window(logical_width as u16, logical_height as u16)?;
@@ -319,6 +599,9 @@ impl ListBuilder {
 
         // Recalculate column widths at physical scale
         let mut col_widths: Vec<u32> = vec![(100.0 * scale) as u32; num_cols];
+        if let Some(ci) = image_col {
+            col_widths[ci] = row_height;
+        }
         for (i, col) in columns.iter().enumerate() {
             let (w, _) = font.render(col).measure();
             col_widths[i] = col_widths[i].max(w as u32 + (20.0 * scale) as u32);
@@ -326,7 +609,8 @@ impl ListBuilder {
         for row in &display_rows {
             for (i, cell) in row.iter().enumerate() {
                 if i < num_cols {
-                    let (w, _) = font.render(cell).measure();
+                    let cell = styled(cell);
+                    let (w, _) = font.render(&cell.text).with_runs(&cell.runs).measure();
                     col_widths[i] = col_widths[i].max(w as u32 + (20.0 * scale) as u32);
                 }
             }
@@ -362,10 +646,31 @@ impl ListBuilder {
         } else {
             0
         };
-        let total_content_width = checkbox_col
-            + checkbox_gap
-            + col_widths.iter().sum::<u32>()
-            + (num_gaps as u32 * column_gap);
+        // Columns are as wide as their widest cell up to a cap, and the last
+        // one takes up any room left; dragging a boundary resizes them
+        let fixed_width = checkbox_col + checkbox_gap + num_gaps as u32 * column_gap;
+        let available_width = (physical_width - padding * 2).saturating_sub(fixed_width);
+        let mut base_col_widths = fit_column_widths(
+            &col_widths,
+            (BASE_MAX_COLUMN_WIDTH as f32 * scale) as u32,
+            0,
+        );
+        col_widths = fit_column_widths(&base_col_widths, u32::MAX, available_width);
+        let mut total_content_width = fixed_width + col_widths.iter().sum::<u32>();
+        let mode = self.mode;
+        let resize_grab = (4.0 * scale) as i32;
+        // The boundary after a column, `x` pixels into the header (scrolling
+        // included), within grabbing distance
+        let boundary_at = |x: i32, col_widths: &[u32]| {
+            (0..col_widths.len().saturating_sub(1)).find(|&col| {
+                let edge = mode.column_start(col, checkbox_col, col_widths, column_gap)
+                    + col_widths[col]
+                    + column_gap / 2;
+                (x - edge as i32).abs() <= resize_grab
+            })
+        };
+        // The column being resized, where the drag started and its width then
+        let mut resizing: Option<(usize, i32, u32)> = None;
 
         // Create buttons at physical scale
         let mut ok_button = Button::new("OK", &font, scale);
@@ -417,6 +722,10 @@ impl ListBuilder {
         let mut hovered_row: Option<usize> = None;
         let mut single_selected: Option<usize> = None;
         let mut h_scroll_mode = false;
+        if let Some((checked, single)) = self.restored.take() {
+            selected = checked;
+            single_selected = single;
+        }
 
         // Track last cursor position for drag scrolling
         let mut last_cursor_pos: Option<(i32, i32)> = None;
@@ -431,6 +740,33 @@ impl ListBuilder {
         let mut v_scrollbar_hovered = false;
         let mut h_scrollbar_hovered = false;
 
+        // The cell being edited: its row, visible column and editor
+        let mut editing: Option<(usize, usize, TextInput)> = None;
+        let mut last_click: Option<(Instant, (usize, usize))> = None;
+        // Double-clicking a row accepts it unless that edits a cell: the row
+        // the button went down on, and when and where it last came up
+        let mut row_press: Option<usize> = None;
+        let mut last_release: Option<(Instant, usize)> = None;
+
+        // Typing opens a search box that filters the rows; `shown` holds the
+        // rows it matches, and scrolling counts positions in it
+        let mut search: Option<TextInput> = None;
+        let mut shown: Vec<usize> = (0..rows.len()).collect();
+        if let Some((col, descending)) = sort {
+            sort_rows(&mut shown, &rows, col, descending);
+        }
+        // The visible column showing the sort order
+        let sort_indicator = |sort: Option<(usize, bool)>| {
+            sort.and_then(|(col, descending)| {
+                let ci = visible_col_indices.iter().position(|&c| c == col)?;
+                Some((ci, descending))
+            })
+        };
+        let search_w = (list_w / 2).min((240.0 * scale) as u32);
+        // Keys and clicks the batch loop read, left for the main loop to
+        // handle, after the last move before them so clicks hit the right row
+        let mut held: VecDeque<WindowEvent> = VecDeque::new();
+
         // Create sub-canvas for the list area to enable clipping
         let mut list_canvas = Canvas::new(list_w, list_h);
 
@@ -442,27 +778,46 @@ impl ListBuilder {
         let checkbox_header_canvas: Option<Canvas> = checkbox_column_header
             .as_ref()
             .map(|h| font.render(h).with_color(header_text_color).finish());
-        let column_header_canvases: Vec<Canvas> = columns
-            .iter()
-            .map(|c| font.render(c).with_color(header_text_color).finish())
-            .collect();
-        // Pre-render every cell in both color variants; the scroll loop only blits.
-        let cell_normal: Vec<Vec<Canvas>> = display_rows
-            .iter()
-            .map(|row| {
-                row.iter()
-                    .map(|cell| font.render(cell).with_color(normal_text_color).finish())
-                    .collect::<Vec<_>>()
-            })
-            .collect();
-        let cell_selected: Vec<Vec<Canvas>> = display_rows
-            .iter()
-            .map(|row| {
-                row.iter()
-                    .map(|cell| font.render(cell).with_color(selected_text_color).finish())
-                    .collect::<Vec<_>>()
-            })
-            .collect();
+        // Text too wide for its column is cut short with an ellipsis, leaving
+        // headers room for the sort indicator
+        let render_headers = |col_widths: &[u32]| -> Vec<Canvas> {
+            columns
+                .iter()
+                .zip(col_widths)
+                .map(|(c, &w)| {
+                    font.render(c)
+                        .with_color(header_text_color)
+                        .with_max_width(w as f32 - 24.0 * scale)
+                        .with_ellipsize(EllipsizeMode::End)
+                        .finish()
+                })
+                .collect()
+        };
+        let mut column_header_canvases = render_headers(&col_widths);
+        // Cells are rendered as their rows scroll into view, keyed by row and
+        // color variant, and kept while they stay near it; long lists never
+        // hold more than a few screens of them.
+        let render_cells = |row: &[String], color, col_widths: &[u32]| -> Vec<Canvas> {
+            row.iter()
+                .zip(col_widths)
+                .map(|(cell, &w)| {
+                    let cell = styled(cell);
+                    font.render(&cell.text)
+                        .with_runs(&cell.runs)
+                        .with_color(color)
+                        .with_max_width(w as f32 - 16.0 * scale)
+                        .with_ellipsize(EllipsizeMode::End)
+                        .finish()
+                })
+                .collect()
+        };
+        let mut cell_cache: RingBufferCache<(usize, bool), Vec<Canvas>> =
+            RingBufferCache::new(visible_rows * 4);
+        // Images likewise, by path, so rows sharing one decode it once;
+        // `None` for files that couldn't be read
+        let image_size = row_height.saturating_sub((8.0 * scale) as u32).max(1);
+        let mut image_cache: RingBufferCache<String, Option<Canvas>> =
+            RingBufferCache::new(visible_rows * 4);
 
         // ---- Chrome layer: dialog bg + title + prompt, rendered once and blitted ----
         let radius = BASE_CORNER_RADIUS * scale;
@@ -477,7 +832,13 @@ impl ListBuilder {
             None
         };
         let prompt_rendered: Option<Canvas> = if !self.text.is_empty() {
-            Some(font.render(&self.text).with_color(colors.text).finish())
+            let prompt = styled(&self.text);
+            Some(
+                font.render(&prompt.text)
+                    .with_runs(&prompt.runs)
+                    .with_color(colors.text)
+                    .finish(),
+            )
         } else {
             None
         };
@@ -504,9 +865,11 @@ impl ListBuilder {
                          columns: &[&str],
                          checkbox_header_canvas: &Option<Canvas>,
                          column_header_canvases: &[Canvas],
+                         shown: &[usize],
                          rows: &[Vec<String>],
-                         cell_normal: &[Vec<Canvas>],
-                         cell_selected: &[Vec<Canvas>],
+                         display_rows: &[Vec<String>],
+                         cell_cache: &mut RingBufferCache<(usize, bool), Vec<Canvas>>,
+                         image_cache: &mut RingBufferCache<String, Option<Canvas>>,
                          col_widths: &[u32],
                          selected: &[bool],
                          single_selected: Option<usize>,
@@ -523,7 +886,8 @@ impl ListBuilder {
                          visible_rows: usize,
                          scale: f32,
                          v_scrollbar_hovered: bool,
-                         h_scrollbar_hovered: bool| {
+                         h_scrollbar_hovered: bool,
+                         sorted: Option<(usize, bool)>| {
             // Clear list canvas
             list_canvas.fill(colors.input_bg);
 
@@ -531,7 +895,7 @@ impl ListBuilder {
 
             // Draw header if columns exist
             let mut data_y_local = 0i32;
-            if !columns.is_empty() || checkbox_header_canvas.is_some() {
+            if show_header {
                 let header_bg = darken(colors.input_bg, 0.05);
                 list_canvas.fill_rect(0.0, 0.0, list_w as f32, row_height as f32, header_bg);
 
@@ -552,7 +916,22 @@ impl ListBuilder {
                 }
                 for (i, tc) in column_header_canvases.iter().enumerate() {
                     list_canvas.draw_canvas(tc, cx + (8.0 * scale) as i32, (6.0 * scale) as i32);
-                    cx += col_widths.get(i).copied().unwrap_or((100.0 * scale) as u32) as i32;
+                    let col_w = col_widths.get(i).copied().unwrap_or((100.0 * scale) as u32);
+                    // A small triangle at the right of the sorted column
+                    if let Some((_, descending)) = sorted.filter(|&(ci, _)| ci == i) {
+                        let x = (cx + col_w as i32) as f32 - 14.0 * scale;
+                        let mid = row_height as f32 / 2.0;
+                        let (tip, base) = if descending {
+                            (mid + 3.0 * scale, mid - 3.0 * scale)
+                        } else {
+                            (mid - 3.0 * scale, mid + 3.0 * scale)
+                        };
+                        list_canvas.fill_polygon(
+                            &[(x, base), (x + 8.0 * scale, base), (x + 4.0 * scale, tip)],
+                            header_text_color,
+                        );
+                    }
+                    cx += col_w as i32;
                     // Add gap between columns
                     if i < columns.len().saturating_sub(1) {
                         cx += column_gap;
@@ -571,23 +950,24 @@ impl ListBuilder {
             }
 
             // Draw rows
-            let data_visible = if columns.is_empty() {
+            let data_visible = if !show_header {
                 visible_rows
             } else {
                 visible_rows.saturating_sub(1)
             };
-            for (vi, ri) in
-                (scroll_offset..rows.len().min(scroll_offset + data_visible)).enumerate()
-            {
+            let first = scroll_offset.min(shown.len());
+            for (vi, &ri) in shown[first..].iter().take(data_visible).enumerate() {
                 let ry = data_y_local + (vi as u32 * row_height) as i32;
 
                 // Background
                 let is_hovered = hovered_row == Some(ri);
+                // Checked rows show it in their toggle; the highlight is the
+                // keyboard cursor
                 let is_selected = match mode {
-                    ListMode::Single => single_selected == Some(ri),
-                    ListMode::Multiple | ListMode::Checklist | ListMode::Radiolist => {
-                        selected.get(ri).copied().unwrap_or(false)
+                    ListMode::Single | ListMode::Checklist | ListMode::Radiolist => {
+                        single_selected == Some(ri)
                     }
+                    ListMode::Multiple => selected.get(ri).copied().unwrap_or(false),
                 };
 
                 let bg = if is_selected {
@@ -631,12 +1011,15 @@ impl ListBuilder {
                     }
                 }
 
-                // Cell values (pre-rendered; pick color variant by selection state)
-                let row_cells = if is_selected {
-                    &cell_selected[ri]
+                // Cell values, in the color variant for the selection state
+                let color = if is_selected {
+                    selected_text_color
                 } else {
-                    &cell_normal[ri]
+                    normal_text_color
                 };
+                let row_cells = cell_cache.get_or_insert_with((ri, is_selected), || {
+                    render_cells(&display_rows[ri], color, col_widths)
+                });
                 let mut cx = checkbox_col as i32 - h_scroll_offset as i32;
                 let column_gap = (16.0 * scale) as i32;
                 // Add gap after checkbox column if there are data columns
@@ -645,11 +1028,41 @@ impl ListBuilder {
                 }
                 for (ci, tc) in row_cells.iter().enumerate() {
                     if ci < col_widths.len() {
-                        list_canvas.draw_canvas(
-                            tc,
-                            cx + (8.0 * scale) as i32,
-                            ry + (6.0 * scale) as i32,
-                        );
+                        if image_col == Some(ci) {
+                            let path = rows[ri].first().map_or("", String::as_str);
+                            if !path.is_empty() {
+                                let image = image_cache
+                                    .get_or_insert_with(path.to_string(), || {
+                                        load_row_image(path, image_size)
+                                    });
+                                let x = cx + (8.0 * scale) as i32;
+                                match image {
+                                    Some(image) => {
+                                        list_canvas.draw_canvas(
+                                            image,
+                                            x,
+                                            ry + (row_height as i32 - image.height() as i32) / 2,
+                                        )
+                                    }
+                                    None => {
+                                        draw_image_placeholder(
+                                            list_canvas,
+                                            x,
+                                            ry + (row_height - image_size) as i32 / 2,
+                                            image_size,
+                                            colors,
+                                            scale,
+                                        )
+                                    }
+                                }
+                            }
+                        } else {
+                            list_canvas.draw_canvas(
+                                tc,
+                                cx + (8.0 * scale) as i32,
+                                ry + (6.0 * scale) as i32,
+                            );
+                        }
                         cx += col_widths[ci] as i32;
                         // Add gap between columns
                         if ci < row_cells.len().saturating_sub(1) {
@@ -658,22 +1071,24 @@ impl ListBuilder {
                     }
                 }
             }
+            #[cfg(feature = "mock-backend")]
+            crate::backend::mock::record_list_cache_hit_rate(cell_cache.hit_rate());
 
             // Vertical Scrollbar
-            if rows.len() > data_visible {
+            if shown.len() > data_visible {
                 let sb_x = list_w as i32 - (8.0 * scale) as i32;
                 let sb_h = list_h as f32
-                    - if columns.is_empty() {
+                    - if !show_header {
                         0.0
                     } else {
                         row_height as f32 + 1.0
                     };
                 let sb_y = data_y_local as f32;
                 let thumb_h =
-                    ((data_visible as f32 / rows.len() as f32 * sb_h).max(20.0 * scale)).min(sb_h);
+                    ((data_visible as f32 / shown.len() as f32 * sb_h).max(20.0 * scale)).min(sb_h);
                 let max_thumb_y = sb_h - thumb_h;
-                let thumb_y = if rows.len() > data_visible {
-                    scroll_offset as f32 / (rows.len() - data_visible) as f32 * max_thumb_y
+                let thumb_y = if shown.len() > data_visible {
+                    scroll_offset as f32 / (shown.len() - data_visible) as f32 * max_thumb_y
                 } else {
                     0.0
                 };
@@ -770,9 +1185,11 @@ impl ListBuilder {
             &columns,
             &checkbox_header_canvas,
             &column_header_canvases,
+            &shown,
+            &rows,
             &display_rows,
-            &cell_normal,
-            &cell_selected,
+            &mut cell_cache,
+            &mut image_cache,
             &col_widths,
             &selected,
             single_selected,
@@ -790,6 +1207,7 @@ impl ListBuilder {
             scale,
             v_scrollbar_hovered,
             h_scrollbar_hovered,
+            sort_indicator(sort),
         );
         canvas.blit_region(
             &list_canvas,
@@ -803,32 +1221,130 @@ impl ListBuilder {
         ok_button.draw_to(&mut canvas, colors, &font);
         cancel_button.draw_to(&mut canvas, colors, &font);
         window.set_contents(&canvas)?;
+        if let Some((x, y)) = self.position {
+            window.set_position(x, y)?;
+        }
         window.show()?;
 
         // Dirty-region tracking flags. `full_redraw` persists across iterations
         // (set by RedrawRequested); the list/button flags are reset each iteration.
         let mut full_redraw = false;
 
-        let header_height_px = if columns.is_empty() {
-            0
-        } else {
-            row_height + 1
-        };
+        let header_height_px = if !show_header { 0 } else { row_height + 1 };
         let data_y = list_y + header_height_px as i32;
-        let data_visible = if columns.is_empty() {
+        let data_visible = if !show_header {
             visible_rows
         } else {
             visible_rows.saturating_sub(1)
         };
 
+        // The column boundary under the cursor in the header, if any
+        let header_boundary = |(mx, my): (i32, i32), h_scroll_offset: u32, col_widths: &[u32]| {
+            if !show_header
+                || !(list_y..list_y + row_height as i32).contains(&my)
+                || !(list_x..list_x + list_w as i32).contains(&mx)
+            {
+                return None;
+            }
+            boundary_at(mx - list_x + h_scroll_offset as i32, col_widths)
+        };
+
         loop {
-            let event = window.wait_for_event()?;
+            let event = match held.pop_front() {
+                Some(event) => event,
+                None => {
+                    match wait_for_event_until(&mut window, deadline)? {
+                        Some(event) => event,
+                        None => return Ok(Some(ListResult::Timeout)),
+                    }
+                }
+            };
             let mut needs_redraw = false;
             let mut buttons_dirty = false;
+            let mut finished_edit: Option<(usize, usize, TextInput)> = None;
+            let mut view_changed = false;
 
             match &event {
-                WindowEvent::CloseRequested => return Ok(ListResult::Closed),
+                WindowEvent::CloseRequested => return Ok(Some(ListResult::Closed)),
                 WindowEvent::RedrawRequested => full_redraw = true,
+                // Clicks in the cell editor stay there
+                WindowEvent::ButtonPress(MouseButton::Left, _)
+                    if editing.as_ref().zip(last_cursor_pos).is_some_and(
+                        |((ri, _, input), (mx, my))| {
+                            shown.iter().position(|r| r == ri).is_some_and(|pos| {
+                                (scroll_offset..scroll_offset + data_visible).contains(&pos)
+                            }) && point_in_rect(
+                                mx,
+                                my,
+                                input.x(),
+                                input.y(),
+                                input.width(),
+                                input.height(),
+                            )
+                        },
+                    ) => {}
+                // While a cell is edited, typing goes to its editor
+                WindowEvent::KeyPress(_) | WindowEvent::TextInput(_)
+                    if editing.is_some()
+                        && !matches!(&event, WindowEvent::KeyPress(key)
+                            if zoom::ZoomKey::from_key(key).is_some()) =>
+                {
+                    if let Some((_, _, input)) = &mut editing
+                        && let Some(keep) = edit_event(input, &event)
+                    {
+                        finished_edit = editing.take().filter(|_| keep);
+                    }
+                    full_redraw = true;
+                }
+                // Printable keys open the search box, which then takes the
+                // keys that edit text
+                WindowEvent::TextInput(c)
+                    if search.is_some() || !(c.is_whitespace() || c.is_control()) =>
+                {
+                    let input = search.get_or_insert_with(|| {
+                        let mut input = TextInput::new(search_w);
+                        input.set_focus(true);
+                        input
+                    });
+                    input.process_event(&event);
+                    view_changed = true;
+                }
+                WindowEvent::KeyPress(key)
+                    if search.is_some()
+                        && !matches!(
+                            key.keysym,
+                            KEY_UP
+                                | KEY_DOWN
+                                | KEY_PAGE_UP
+                                | KEY_PAGE_DOWN
+                                | KEY_RETURN
+                                | KEY_ESCAPE
+                                | KEY_LSHIFT
+                                | KEY_RSHIFT
+                        )
+                        && zoom::ZoomKey::from_key(key).is_none() =>
+                {
+                    if let Some(input) = &mut search {
+                        input.process_event(&event);
+                    }
+                    view_changed = true;
+                }
+                WindowEvent::CursorMove(pos) if resizing.is_some() => {
+                    let mx = pos.x as i32;
+                    last_cursor_pos = Some((mx, pos.y as i32));
+                    if let Some((col, start_x, start_w)) = resizing {
+                        let min = (BASE_MIN_COLUMN_WIDTH as f32 * scale) as i32;
+                        base_col_widths[col] = (start_w as i32 + mx - start_x).max(min) as u32;
+                        col_widths = fit_column_widths(&base_col_widths, u32::MAX, available_width);
+                        total_content_width = fixed_width + col_widths.iter().sum::<u32>();
+                        h_scroll_offset =
+                            h_scroll_offset.min(total_content_width.saturating_sub(list_w));
+                        column_header_canvases = render_headers(&col_widths);
+                        // Cells are ellipsized to the old widths
+                        cell_cache.clear();
+                        needs_redraw = true;
+                    }
+                }
                 WindowEvent::CursorMove(pos) => {
                     if window_dragging {
                         let _ = window.start_drag();
@@ -846,20 +1362,20 @@ impl ListBuilder {
                         let list_mx = mx - list_x;
                         let list_my = my - list_y;
 
-                        if v_thumb_drag && rows.len() > data_visible {
+                        if v_thumb_drag && shown.len() > data_visible {
                             let sb_h_f32 = list_h as f32
-                                - if columns.is_empty() {
+                                - if !show_header {
                                     0.0
                                 } else {
                                     row_height as f32 + 1.0
                                 };
                             let sb_h = sb_h_f32 as i32;
-                            let sb_y = if columns.is_empty() {
+                            let sb_y = if !show_header {
                                 0
                             } else {
                                 (row_height + 1) as i32
                             };
-                            let thumb_h_f32 = ((data_visible as f32 / rows.len() as f32
+                            let thumb_h_f32 = ((data_visible as f32 / shown.len() as f32
                                 * sb_h_f32)
                                 .max(20.0 * scale))
                             .min(sb_h_f32);
@@ -876,9 +1392,9 @@ impl ListBuilder {
                             } else {
                                 0.0
                             };
-                            scroll_offset = ((scroll_ratio * (rows.len() - data_visible) as f32)
+                            scroll_offset = ((scroll_ratio * (shown.len() - data_visible) as f32)
                                 as usize)
-                                .clamp(0, rows.len().saturating_sub(data_visible));
+                                .clamp(0, shown.len().saturating_sub(data_visible));
                             needs_redraw = true;
                         }
 
@@ -924,7 +1440,7 @@ impl ListBuilder {
                             8.0 * scale
                         };
 
-                        v_scrollbar_hovered = rows.len() > data_visible
+                        v_scrollbar_hovered = shown.len() > data_visible
                             && mx >= list_x + v_scrollbar_x
                             && mx < list_x + list_w as i32
                             && my >= list_y
@@ -938,9 +1454,9 @@ impl ListBuilder {
 
                         // Check row hover (only if not over scrollbar)
                         let effective_v_scrollbar_width =
-                            if v_scrollbar_hovered && rows.len() > data_visible {
+                            if v_scrollbar_hovered && shown.len() > data_visible {
                                 12.0 * scale
-                            } else if rows.len() > data_visible {
+                            } else if shown.len() > data_visible {
                                 8.0 * scale
                             } else {
                                 0.0
@@ -952,19 +1468,37 @@ impl ListBuilder {
                             && my < list_y + list_h as i32
                         {
                             let rel_y = (my - data_y) as usize;
-                            let ri = scroll_offset + rel_y / row_height as usize;
-                            if ri < rows.len() {
-                                hovered_row = Some(ri);
-                            }
+                            hovered_row = shown
+                                .get(scroll_offset + rel_y / row_height as usize)
+                                .copied();
                         }
 
                         if old_hovered != hovered_row {
                             needs_redraw = true;
                         }
+
+                        let _ = window.set_cursor(
+                            if header_boundary((mx, my), h_scroll_offset, &col_widths).is_some() {
+                                CursorShape::ColResize
+                            } else {
+                                CursorShape::Default
+                            },
+                        );
                     }
                 }
                 WindowEvent::ButtonPress(MouseButton::Left, mods) => {
                     window_dragging = true;
+                    // A click anywhere else keeps the edit
+                    finished_edit = editing.take();
+                    row_press = None;
+                    // Grabbing a boundary between headers resizes the column
+                    // before it
+                    if let Some((mx, my)) = last_cursor_pos
+                        && let Some(col) = header_boundary((mx, my), h_scroll_offset, &col_widths)
+                    {
+                        window_dragging = false;
+                        resizing = Some((col, mx, base_col_widths[col]));
+                    }
                     let mut clicking_scrollbar = false;
 
                     // Check if clicking anywhere in scrollbar area (thumb OR track)
@@ -979,7 +1513,7 @@ impl ListBuilder {
                             && list_my < list_h as i32
                         {
                             // Vertical scrollbar area
-                            if rows.len() > data_visible {
+                            if shown.len() > data_visible {
                                 let v_scrollbar_width = if v_scrollbar_hovered {
                                     12.0 * scale
                                 } else {
@@ -992,24 +1526,24 @@ impl ListBuilder {
                                     clicking_scrollbar = true;
 
                                     let sb_h_f32 = list_h as f32
-                                        - if columns.is_empty() {
+                                        - if !show_header {
                                             0.0
                                         } else {
                                             row_height as f32 + 1.0
                                         };
-                                    let sb_y = if columns.is_empty() {
+                                    let sb_y = if !show_header {
                                         0
                                     } else {
                                         (row_height + 1) as i32
                                     };
-                                    let thumb_h_f32 = ((data_visible as f32 / rows.len() as f32
+                                    let thumb_h_f32 = ((data_visible as f32 / shown.len() as f32
                                         * sb_h_f32)
                                         .max(20.0 * scale))
                                     .min(sb_h_f32);
                                     let thumb_h = thumb_h_f32 as i32;
                                     let max_thumb_y = (sb_h_f32 - thumb_h_f32) as i32;
-                                    let thumb_y = if rows.len() > data_visible {
-                                        (scroll_offset as f32 / (rows.len() - data_visible) as f32
+                                    let thumb_y = if shown.len() > data_visible {
+                                        (scroll_offset as f32 / (shown.len() - data_visible) as f32
                                             * max_thumb_y as f32)
                                             as i32
                                     } else {
@@ -1068,14 +1602,35 @@ impl ListBuilder {
                         }
                     }
 
+                    // Clicking a header sorts by its column, then reverses
+                    if !clicking_scrollbar
+                        && resizing.is_none()
+                        && show_header
+                        && let Some((mx, my)) = last_cursor_pos
+                        && (list_y..list_y + row_height as i32).contains(&my)
+                        && (list_x..list_x + list_w as i32).contains(&mx)
+                        && let Some(ci) = self.mode.column_at(
+                            mx - list_x + h_scroll_offset as i32,
+                            checkbox_col,
+                            &col_widths,
+                            column_gap,
+                        )
+                    {
+                        let col = visible_col_indices[ci];
+                        sort = Some((col, sort == Some((col, false))));
+                        view_changed = true;
+                    }
+
                     // Only process row selection if not clicking on scrollbar
                     if !clicking_scrollbar {
                         if let Some(ri) = hovered_row {
                             match self.mode {
                                 ListMode::Single => {
                                     single_selected = Some(ri);
+                                    row_press = Some(ri);
                                 }
                                 ListMode::Multiple => {
+                                    row_press = Some(ri);
                                     // Only toggle selection if Ctrl is held, otherwise select only this item
                                     if mods.contains(crate::backend::Modifiers::CTRL) {
                                         if let Some(sel) = selected.get_mut(ri) {
@@ -1090,34 +1645,92 @@ impl ListBuilder {
                                         }
                                     }
                                 }
-                                ListMode::Checklist => {
-                                    if let Some(sel) = selected.get_mut(ri) {
-                                        *sel = !*sel;
+                                ListMode::Checklist | ListMode::Radiolist => {
+                                    // The toggle cell flips the box; the rest of
+                                    // the row only moves the cursor
+                                    single_selected = Some(ri);
+                                    if last_cursor_pos.is_some_and(|(mx, _)| {
+                                        self.mode.hits_toggle(
+                                            mx - list_x + h_scroll_offset as i32,
+                                            checkbox_col,
+                                        )
+                                    }) {
+                                        self.mode.toggle(&mut selected, ri);
+                                    } else {
+                                        row_press = Some(ri);
                                     }
                                 }
-                                ListMode::Radiolist => {
-                                    // Only one can be selected
-                                    for s in selected.iter_mut() {
-                                        *s = false;
-                                    }
-                                    if let Some(sel) = selected.get_mut(ri) {
-                                        *sel = true;
-                                    }
+                            }
+                            if self.editable {
+                                let cell = last_cursor_pos
+                                    .and_then(|(mx, _)| {
+                                        self.mode.column_at(
+                                            mx - list_x + h_scroll_offset as i32,
+                                            checkbox_col,
+                                            &col_widths,
+                                            column_gap,
+                                        )
+                                    })
+                                    .filter(|&ci| image_col != Some(ci))
+                                    .map(|ci| (ri, ci));
+                                let now = Instant::now();
+                                let double = cell.filter(|&cell| {
+                                    last_click.is_some_and(|(at, last)| {
+                                        last == cell && now.duration_since(at) < MULTI_CLICK_TIME
+                                    })
+                                });
+                                last_click = match double {
+                                    Some(_) => None,
+                                    None => cell.map(|cell| (now, cell)),
+                                };
+                                if let Some((ri, ci)) = double {
+                                    let text = display_rows[ri].get(ci).map_or("", String::as_str);
+                                    let mut input =
+                                        TextInput::new(col_widths[ci]).with_default_text(text);
+                                    input.set_focus(true);
+                                    editing = Some((ri, ci, input));
+                                    full_redraw = true;
                                 }
                             }
                             needs_redraw = true;
                         }
                     }
                 }
-                WindowEvent::ButtonRelease(_, _) => {
+                WindowEvent::ButtonRelease(button, _) => {
                     window_dragging = false;
+                    resizing = None;
                     // End scrollbar thumb dragging
                     v_thumb_drag = false;
                     h_thumb_drag = false;
                     v_thumb_drag_offset = None;
                     h_thumb_drag_offset = None;
+
+                    // Editable lists edit the cell on a double-click instead
+                    let released = row_press
+                        .take()
+                        .filter(|&ri| *button == MouseButton::Left && hovered_row == Some(ri));
+                    if let Some(ri) = released
+                        && !self.editable
+                    {
+                        let now = Instant::now();
+                        if last_release.is_some_and(|(at, last)| {
+                            last == ri && now.duration_since(at) < MULTI_CLICK_TIME
+                        }) {
+                            return Ok(Some(get_result(
+                                &rows,
+                                &selected,
+                                single_selected,
+                                self.mode,
+                                self.print_column,
+                                self.imagelist,
+                            )));
+                        }
+                        last_release = Some((now, ri));
+                    } else {
+                        last_release = None;
+                    }
                 }
-                WindowEvent::Scroll(direction) => {
+                WindowEvent::Scroll(direction, _) => {
                     if h_scroll_mode {
                         // Shift + wheel: horizontal scroll
                         match direction {
@@ -1146,9 +1759,9 @@ impl ListBuilder {
                                 }
                             }
                             crate::backend::ScrollDirection::Down => {
-                                if scroll_offset + data_visible < rows.len() {
+                                if scroll_offset + data_visible < shown.len() {
                                     scroll_offset = (scroll_offset + 2)
-                                        .min(rows.len().saturating_sub(data_visible));
+                                        .min(shown.len().saturating_sub(data_visible));
                                     needs_redraw = true;
                                 }
                             }
@@ -1169,6 +1782,29 @@ impl ListBuilder {
                     }
                 }
                 WindowEvent::KeyPress(key_event) => {
+                    if zoom::handle_key(key_event) {
+                        if self.editable {
+                            // Keep the edits; toggle modes put the TRUE/FALSE column back
+                            self.rows = match self.mode {
+                                ListMode::Checklist | ListMode::Radiolist => {
+                                    rows.into_iter()
+                                        .zip(&selected)
+                                        .map(|(row, &checked)| {
+                                            let checked = if checked { "TRUE" } else { "FALSE" };
+                                            std::iter::once(checked.to_string())
+                                                .chain(row)
+                                                .collect()
+                                        })
+                                        .collect()
+                                }
+                                ListMode::Single | ListMode::Multiple => rows,
+                            };
+                        }
+                        self.restored = Some((selected, single_selected));
+                        self.sort =
+                            sort.map(|(col, descending)| (col + toggle_columns, descending));
+                        return Ok(None);
+                    }
                     // Handle shift for scroll mode
                     if key_event.keysym == KEY_LSHIFT || key_event.keysym == KEY_RSHIFT {
                         h_scroll_mode = true;
@@ -1178,64 +1814,37 @@ impl ListBuilder {
                     }
 
                     match key_event.keysym {
-                        KEY_UP => {
-                            if self.mode == ListMode::Single {
-                                if let Some(sel) = single_selected {
-                                    if sel > 0 {
-                                        single_selected = Some(sel - 1);
-                                        if sel - 1 < scroll_offset {
-                                            scroll_offset = sel - 1;
-                                        }
-                                        needs_redraw = true;
-                                    }
-                                } else if !rows.is_empty() {
-                                    single_selected = Some(0);
-                                    needs_redraw = true;
-                                }
-                            } else if self.mode == ListMode::Multiple {
-                                let last_selected = selected.iter().position(|&s| s);
-                                if let Some(last) = last_selected {
-                                    if last > 0 {
-                                        single_selected = Some(last - 1);
-                                        if last - 1 < scroll_offset {
-                                            scroll_offset = last - 1;
-                                        }
-                                        needs_redraw = true;
-                                    }
-                                } else if !rows.is_empty() {
-                                    single_selected = Some(0);
-                                    needs_redraw = true;
-                                }
-                            }
-                        }
-                        KEY_DOWN => {
-                            if self.mode == ListMode::Single {
-                                if let Some(sel) = single_selected {
-                                    if sel + 1 < rows.len() {
-                                        single_selected = Some(sel + 1);
-                                        if sel + 1 >= scroll_offset + data_visible {
-                                            scroll_offset = sel + 2 - data_visible;
-                                        }
-                                        needs_redraw = true;
-                                    }
-                                } else if !rows.is_empty() {
-                                    single_selected = Some(0);
-                                    needs_redraw = true;
+                        KEY_UP | KEY_DOWN | KEY_PAGE_UP | KEY_PAGE_DOWN => {
+                            // The cursor steps through the rows the search shows,
+                            // a row or a page at a time
+                            let back = matches!(key_event.keysym, KEY_UP | KEY_PAGE_UP);
+                            let step = match key_event.keysym {
+                                KEY_PAGE_UP | KEY_PAGE_DOWN => data_visible.max(1),
+                                _ => 1,
+                            };
+                            let current = if self.mode == ListMode::Multiple {
+                                selected.iter().position(|&s| s)
+                            } else {
+                                single_selected
+                            };
+                            let next = match current
+                                .and_then(|ri| shown.iter().position(|&r| r == ri))
+                            {
+                                Some(pos) if back => (pos > 0).then(|| pos.saturating_sub(step)),
+                                Some(pos) => {
+                                    (pos + 1 < shown.len())
+                                        .then(|| (pos + step).min(shown.len() - 1))
                                 }
-                            } else if self.mode == ListMode::Multiple {
-                                let last_selected = selected.iter().position(|&s| s);
-                                if let Some(last) = last_selected {
-                                    if last + 1 < rows.len() {
-                                        single_selected = Some(last + 1);
-                                        if last + 1 >= scroll_offset + data_visible {
-                                            scroll_offset = last + 2 - data_visible;
-                                        }
-                                        needs_redraw = true;
-                                    }
-                                } else if !rows.is_empty() {
-                                    single_selected = Some(0);
-                                    needs_redraw = true;
+                                None => (!shown.is_empty()).then_some(0),
+                            };
+                            if let Some(next) = next {
+                                single_selected = Some(shown[next]);
+                                if next < scroll_offset {
+                                    scroll_offset = next;
+                                } else if next >= scroll_offset + data_visible {
+                                    scroll_offset = next + 1 - data_visible;
                                 }
+                                needs_redraw = true;
                             }
                         }
                         KEY_LEFT => {
@@ -1252,21 +1861,42 @@ impl ListBuilder {
                             }
                         }
                         KEY_SPACE => {
-                            if self.mode == ListMode::Checklist || self.mode == ListMode::Multiple {
-                                if let Some(ri) = hovered_row.or(single_selected) {
-                                    if let Some(sel) = selected.get_mut(ri) {
+                            match self.mode {
+                                ListMode::Multiple => {
+                                    if let Some(ri) = hovered_row.or(single_selected)
+                                        && let Some(sel) = selected.get_mut(ri)
+                                    {
                                         *sel = !*sel;
                                         needs_redraw = true;
                                     }
                                 }
+                                ListMode::Checklist | ListMode::Radiolist => {
+                                    // Toggles the cursor row
+                                    if let Some(ri) = single_selected.or(hovered_row) {
+                                        self.mode.toggle(&mut selected, ri);
+                                        needs_redraw = true;
+                                    }
+                                }
+                                ListMode::Single => {}
                             }
                         }
                         KEY_RETURN => {
                             // Return selected
-                            return Ok(get_result(&rows, &selected, single_selected, self.mode));
+                            return Ok(Some(get_result(
+                                &rows,
+                                &selected,
+                                single_selected,
+                                self.mode,
+                                self.print_column,
+                                self.imagelist,
+                            )));
                         }
                         KEY_ESCAPE => {
-                            return Ok(ListResult::Cancelled);
+                            // The first Escape only clears the search
+                            if search.take().is_none() {
+                                return Ok(Some(ListResult::Cancelled));
+                            }
+                            view_changed = true;
                         }
                         _ => {}
                     }
@@ -1284,91 +1914,51 @@ impl ListBuilder {
             buttons_dirty |= cancel_button.process_event(&event);
 
             if ok_button.was_clicked() {
-                return Ok(get_result(&rows, &selected, single_selected, self.mode));
+                return Ok(Some(get_result(
+                    &rows,
+                    &selected,
+                    single_selected,
+                    self.mode,
+                    self.print_column,
+                    self.imagelist,
+                )));
             }
             if cancel_button.was_clicked() {
-                return Ok(ListResult::Cancelled);
+                return Ok(Some(ListResult::Cancelled));
             }
 
-            while let Some(ev) = window.poll_for_event()? {
+            let mut last_move = None;
+            while held.is_empty()
+                && let Some(ev) = window.poll_for_event()?
+            {
+                if matches!(ev, WindowEvent::ButtonPress(..))
+                    || row_press.is_some() && matches!(ev, WindowEvent::ButtonRelease(..))
+                    || resizing.is_some() && matches!(ev, WindowEvent::CursorMove(_))
+                    || editing.is_none()
+                        && matches!(ev, WindowEvent::KeyPress(_) | WindowEvent::TextInput(_))
+                {
+                    held.extend(last_move);
+                    held.push_back(ev);
+                    break;
+                }
                 match &ev {
                     WindowEvent::CloseRequested => {
-                        return Ok(ListResult::Closed);
+                        return Ok(Some(ListResult::Closed));
                     }
                     WindowEvent::CursorEnter(pos) | WindowEvent::CursorMove(pos) => {
                         last_cursor_pos = Some((pos.x as i32, pos.y as i32));
+                        last_move = Some(ev.clone());
                     }
-                    WindowEvent::ButtonPress(button, _modifiers)
-                        if *button == MouseButton::Left =>
-                    {
-                        if let Some((list_mx, list_my)) = last_cursor_pos {
-                            // Check vertical scrollbar thumb
-                            if rows.len() > data_visible {
-                                let sb_x = list_w as i32 - (8.0 * scale) as i32;
-                                let sb_h_f32 = list_h as f32
-                                    - if columns.is_empty() {
-                                        0.0
-                                    } else {
-                                        row_height as f32 + 1.0
-                                    };
-                                let thumb_h_f32 = ((data_visible as f32 / rows.len() as f32
-                                    * sb_h_f32)
-                                    .max(20.0 * scale))
-                                .min(sb_h_f32);
-                                let thumb_h = thumb_h_f32 as i32;
-                                let max_thumb_y = (sb_h_f32 - thumb_h_f32) as i32;
-                                let thumb_y = if rows.len() > data_visible {
-                                    (scroll_offset as f32 / (rows.len() - data_visible) as f32
-                                        * max_thumb_y as f32)
-                                        as i32
-                                } else {
-                                    0
-                                };
-
-                                if list_mx >= sb_x
-                                    && list_mx < sb_x + (8.0 * scale) as i32
-                                    && list_my >= thumb_y
-                                    && list_my < thumb_y + thumb_h
-                                {
-                                    v_thumb_drag = true;
-                                    v_thumb_drag_offset = Some(list_my - thumb_y);
-                                }
-                            }
-
-                            // Check horizontal scrollbar thumb
-                            if total_content_width > list_w {
-                                let sb_h = (6.0 * scale) as i32;
-                                let sb_y = list_h as i32 - sb_h;
-                                let sb_w_f32 = list_w as f32;
-                                let sb_w = list_w as i32;
-                                let max_scroll_u32 = total_content_width.saturating_sub(list_w);
-                                let max_scroll = (max_scroll_u32 as i32).max(1);
-                                let thumb_w_f32 = ((list_w as f32 / total_content_width as f32
-                                    * sb_w_f32)
-                                    .max(20.0 * scale))
-                                .min(sb_w_f32);
-                                let thumb_w = thumb_w_f32 as i32;
-                                let max_thumb_x = sb_w - thumb_w;
-                                let thumb_x = if max_scroll > 0 {
-                                    (h_scroll_offset as f32 / max_scroll as f32
-                                        * max_thumb_x as f32)
-                                        as i32
-                                } else {
-                                    0
-                                };
-
-                                if list_my >= sb_y
-                                    && list_my < sb_y + sb_h
-                                    && list_mx >= thumb_x
-                                    && list_mx < thumb_x + thumb_w
-                                {
-                                    h_thumb_drag = true;
-                                    h_thumb_drag_offset = Some(list_mx - thumb_x);
-                                }
-                            }
+                    WindowEvent::KeyPress(_) | WindowEvent::TextInput(_) if editing.is_some() => {
+                        if let Some((_, _, input)) = &mut editing
+                            && let Some(keep) = edit_event(input, &ev)
+                        {
+                            finished_edit = editing.take().filter(|_| keep);
                         }
+                        full_redraw = true;
                     }
                     WindowEvent::ButtonRelease(_, _) => {
+                        resizing = None;
                         v_thumb_drag = false;
                         h_thumb_drag = false;
                         v_thumb_drag_offset = None;
@@ -1381,6 +1971,46 @@ impl ListBuilder {
                 buttons_dirty |= cancel_button.process_event(&ev);
             }
 
+            if let Some((ri, ci, input)) = finished_edit {
+                let col = visible_col_indices[ci];
+                let row = &mut rows[ri];
+                if row.len() <= col {
+                    row.resize(col + 1, String::new());
+                }
+                row[col] = input.text().to_string();
+                display_rows[ri] = visible_cells(row);
+                cell_cache.remove(&(ri, false));
+                cell_cache.remove(&(ri, true));
+                full_redraw = true;
+            }
+            if view_changed {
+                let query = search
+                    .as_ref()
+                    .map_or(String::new(), |input| input.text().to_lowercase());
+                shown = (0..display_rows.len())
+                    .filter(|&ri| row_matches(&display_rows[ri], &query, self.mid_search))
+                    .collect();
+                if let Some((col, descending)) = sort {
+                    sort_rows(&mut shown, &rows, col, descending);
+                }
+                hovered_row = None;
+                // The cursor jumps to the first match when the search hides it
+                if self.mode != ListMode::Multiple
+                    && !query.is_empty()
+                    && let Some(&first) = shown.first()
+                    && single_selected.is_none_or(|ri| !shown.contains(&ri))
+                {
+                    single_selected = Some(first);
+                }
+                scroll_offset = single_selected
+                    .and_then(|ri| shown.iter().position(|&r| r == ri))
+                    .map_or(0, |pos| pos.saturating_sub(data_visible.saturating_sub(1)));
+                full_redraw = true;
+            }
+            // The editor and search box sit over the list, so they're redrawn
+            // with everything
+            full_redraw |= needs_redraw && (editing.is_some() || search.is_some());
+
             if full_redraw || needs_redraw || buttons_dirty {
                 if full_redraw {
                     // Chrome + list + buttons, then a single full upload.
@@ -1393,9 +2023,11 @@ impl ListBuilder {
                         &columns,
                         &checkbox_header_canvas,
                         &column_header_canvases,
+                        &shown,
+                        &rows,
                         &display_rows,
-                        &cell_normal,
-                        &cell_selected,
+                        &mut cell_cache,
+                        &mut image_cache,
                         &col_widths,
                         &selected,
                         single_selected,
@@ -1413,6 +2045,7 @@ impl ListBuilder {
                         scale,
                         v_scrollbar_hovered,
                         h_scrollbar_hovered,
+                        sort_indicator(sort),
                     );
                     canvas.blit_region(
                         &list_canvas,
@@ -1423,6 +2056,29 @@ impl ListBuilder {
                         list_x as u32,
                         list_y as u32,
                     );
+                    if let Some((ri, ci, input)) = &mut editing
+                        && let Some(pos) = shown.iter().position(|r| r == ri)
+                        && (scroll_offset..scroll_offset + data_visible).contains(&pos)
+                    {
+                        let cell_x =
+                            self.mode
+                                .column_start(*ci, checkbox_col, &col_widths, column_gap)
+                                as i32;
+                        let row_y = ((pos - scroll_offset) as u32 * row_height) as i32;
+                        input.set_position(
+                            list_x + cell_x - h_scroll_offset as i32,
+                            data_y + row_y + (row_height as i32 - input.height() as i32) / 2,
+                        );
+                        input.draw_to(&mut canvas, colors, &font);
+                    }
+                    if let Some(input) = &mut search {
+                        let margin = (12.0 * scale) as i32;
+                        input.set_position(
+                            list_x + (list_w - search_w) as i32 - margin,
+                            list_y + list_h as i32 - input.height() as i32 - margin,
+                        );
+                        input.draw_to(&mut canvas, colors, &font);
+                    }
                     ok_button.draw_to(&mut canvas, colors, &font);
                     cancel_button.draw_to(&mut canvas, colors, &font);
                     window.set_contents(&canvas)?;
@@ -1437,9 +2093,11 @@ impl ListBuilder {
                             &columns,
                             &checkbox_header_canvas,
                             &column_header_canvases,
+                            &shown,
+                            &rows,
                             &display_rows,
-                            &cell_normal,
-                            &cell_selected,
+                            &mut cell_cache,
+                            &mut image_cache,
                             &col_widths,
                             &selected,
                             single_selected,
@@ -1457,6 +2115,7 @@ impl ListBuilder {
                             scale,
                             v_scrollbar_hovered,
                             h_scrollbar_hovered,
+                            sort_indicator(sort),
                         );
                         canvas.blit_region(
                             &list_canvas,
@@ -1522,27 +2181,38 @@ fn get_result(
     selected: &[bool],
     single_selected: Option<usize>,
     mode: ListMode,
+    print_column: PrintColumn,
+    images: bool,
 ) -> ListResult {
+    // The image column is never printed, and the columns after it count as
+    // if it weren't there
+    let image_column = if matches!(mode, ListMode::Checklist | ListMode::Radiolist) {
+        2
+    } else {
+        1
+    };
+    let print_column = match print_column {
+        PrintColumn::Column(n) if images && n >= image_column => {
+            (n > image_column).then(|| PrintColumn::Column(n - 1))
+        }
+        column => Some(column),
+    };
+    let values = |row: &[String]| {
+        let row = &row[usize::from(images).min(row.len())..];
+        print_column.map_or(Vec::new(), |column| column.values(row, mode))
+    };
     let mut result = Vec::new();
 
     match mode {
         ListMode::Single => {
-            if let Some(idx) = single_selected {
-                if let Some(row) = rows.get(idx) {
-                    if let Some(val) = row.first() {
-                        result.push(val.clone());
-                    }
-                }
+            if let Some(row) = single_selected.and_then(|idx| rows.get(idx)) {
+                result.extend(values(row));
             }
         }
         ListMode::Multiple | ListMode::Checklist | ListMode::Radiolist => {
             for (i, &sel) in selected.iter().enumerate() {
-                if sel {
-                    if let Some(row) = rows.get(i) {
-                        if let Some(val) = row.first() {
-                            result.push(val.clone());
-                        }
-                    }
+                if sel && let Some(row) = rows.get(i) {
+                    result.extend(values(row));
                 }
             }
         }
@@ -1555,6 +2225,111 @@ fn get_result(
     }
 }
 
+/// Sizes list columns: each as wide as its widest cell (`natural`) but no
+/// wider than `cap`, and the last one stretched over whatever is left of
+/// `available`. Columns wider in total than `available` scroll instead.
+pub(crate) fn fit_column_widths(natural: &[u32], cap: u32, available: u32) -> Vec<u32> {
+    let mut widths: Vec<u32> = natural.iter().map(|&w| w.min(cap)).collect();
+    let total = widths.iter().fold(0u32, |sum, &w| sum.saturating_add(w));
+    if let Some(last) = widths.last_mut() {
+        *last += available.saturating_sub(total);
+    }
+    widths
+}
+
+/// Loads the PNG at `path` for an image column, shrunk to fit `size` pixels
+/// square.
+fn load_row_image(path: &str, size: u32) -> Option<Canvas> {
+    let image = Canvas::from_png(&std::fs::read(path).ok()?)?;
+    let fit = (size as f32 / image.width().max(image.height()) as f32).min(1.0);
+    let width = ((image.width() as f32 * fit).round() as u32).max(1);
+    let height = ((image.height() as f32 * fit).round() as u32).max(1);
+    Some(image.scaled(width, height))
+}
+
+/// Draws the outline of a picture, in place of an image that couldn't be
+/// loaded.
+fn draw_image_placeholder(
+    canvas: &mut Canvas,
+    x: i32,
+    y: i32,
+    size: u32,
+    colors: &Colors,
+    scale: f32,
+) {
+    let (x, y, size) = (x as f32, y as f32, size as f32);
+    canvas.stroke_rounded_rect(
+        x + 1.0,
+        y + 1.0,
+        size - 2.0,
+        size - 2.0,
+        2.0 * scale,
+        colors.input_border,
+        1.0 * scale,
+    );
+    // A hill and a sun
+    let base = y + size * 0.75;
+    canvas.fill_polygon(
+        &[
+            (x + size * 0.2, base),
+            (x + size * 0.45, y + size * 0.4),
+            (x + size * 0.7, base),
+        ],
+        colors.input_border,
+    );
+    canvas.fill_circle(
+        x + size * 0.7,
+        y + size * 0.33,
+        size * 0.1,
+        colors.input_border,
+    );
+}
+
+/// Feeds `event` to a cell editor. Returns `Some(true)` once Enter keeps the
+/// edit, or `Some(false)` once Escape drops it.
+fn edit_event(input: &mut TextInput, event: &WindowEvent) -> Option<bool> {
+    if let WindowEvent::KeyPress(key) = event
+        && key.keysym == KEY_ESCAPE
+    {
+        return Some(false);
+    }
+    input.process_event(event);
+    input.was_submitted().then_some(true)
+}
+
+/// Orders `shown` rows by their cell in `col`: as numbers when every row's
+/// cell is one, otherwise as text ignoring case. Ties keep their order.
+fn sort_rows(shown: &mut [usize], rows: &[Vec<String>], col: usize, descending: bool) {
+    let cell = |ri: usize| rows[ri].get(col).map_or("", String::as_str);
+    let numbers: Option<Vec<f64>> = (0..rows.len())
+        .map(|ri| cell(ri).trim().parse().ok())
+        .collect();
+    let compare = |&a: &usize, &b: &usize| -> Ordering {
+        match &numbers {
+            Some(numbers) => numbers[a].total_cmp(&numbers[b]),
+            None => cell(a).to_lowercase().cmp(&cell(b).to_lowercase()),
+        }
+    };
+    if descending {
+        shown.sort_by(|a, b| compare(b, a));
+    } else {
+        shown.sort_by(compare);
+    }
+}
+
+/// Whether a row's visible `cells` match a lowercase search `query`: one
+/// starts with it, or with `mid_search` contains it.
+fn row_matches(cells: &[String], query: &str, mid_search: bool) -> bool {
+    cells.iter().any(|cell| {
+        let cell = cell.to_lowercase();
+        if mid_search {
+            cell.contains(query)
+        } else {
+            cell.starts_with(query)
+        }
+    })
+}
+
 fn darken(color: crate::render::Rgba, amount: f32) -> crate::render::Rgba {
     rgb(
         (color.r as f32 * (1.0 - amount)) as u8,
@@ -1650,3 +2425,45 @@ fn draw_radio(
         );
     }
 }
+
+/// Sizing list columns to their cells and the room there is.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_last_column_takes_the_room_left() {
+        assert_eq!(fit_column_widths(&[100, 120], 300, 500), [100, 400]);
+        assert_eq!(fit_column_widths(&[50], 300, 400), [400]);
+        // Exactly full
+        assert_eq!(fit_column_widths(&[200, 300], 300, 500), [200, 300]);
+    }
+
+    #[test]
+    fn wide_cells_are_capped() {
+        // One enormous cell doesn't crowd out the others
+        assert_eq!(
+            fit_column_widths(&[100, 50_000, 80], 300, 600),
+            [100, 300, 200]
+        );
+        assert_eq!(fit_column_widths(&[u32::MAX], 300, 0), [300]);
+    }
+
+    #[test]
+    fn columns_too_wide_together_are_left_to_scroll() {
+        assert_eq!(
+            fit_column_widths(&[300, 300, 300], 300, 500),
+            [300, 300, 300]
+        );
+        assert_eq!(fit_column_widths(&[100, 100], 300, 0), [100, 100]);
+    }
+
+    #[test]
+    fn uncapped_widths_do_not_overflow() {
+        assert_eq!(
+            fit_column_widths(&[u32::MAX, u32::MAX, 10], u32::MAX, 500),
+            [u32::MAX, u32::MAX, 10]
+        );
+        assert!(fit_column_widths(&[], 300, 500).is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+//! The `--notification --listen` stdin protocol.
+//!
+//! Each line is `command: value`, as in zenity. Commands are matched
+//! case-insensitively and values are trimmed. Message and tooltip values
+//! understand C-style escapes, so scripts can write `message: a\nb` to get
+//! two lines.
+
+/// A parsed `--listen` line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Command {
+    /// Show a notification. The first line is its summary, the rest the body.
+    Message(String),
+    /// Show a notification with only a summary.
+    Tooltip(String),
+    /// Set the icon for this and later notifications.
+    Icon(String),
+    /// Hide (`false`) or show again (anything else) the notification.
+    Visible(bool),
+    /// A well-formed line with a command we don't know; holds its name.
+    Unknown(String),
+    /// A line without a `:`.
+    Malformed,
+}
+
+/// Parses one line of input. Blank lines give `None`.
+pub(crate) fn parse_line(line: &str) -> Option<Command> {
+    if line.trim().is_empty() {
+        return None;
+    }
+    let Some((name, value)) = line.split_once(':') else {
+        return Some(Command::Malformed);
+    };
+    let name = name.trim().to_ascii_lowercase();
+    let value = value.trim();
+    Some(match name.as_str() {
+        "message" => Command::Message(unescape(value)),
+        "tooltip" => Command::Tooltip(unescape(value)),
+        "icon" => Command::Icon(value.to_string()),
+        "visible" => Command::Visible(!value.eq_ignore_ascii_case("false")),
+        _ => Command::Unknown(name),
+    })
+}
+
+/// Expands escapes the way GLib's `g_strcompress` does: `\n`, `\t`, `\r`,
+/// `\b`, `\f`, `\v`, octal `\NNN`, and a backslash before any other
+/// character stands for that character. A trailing backslash is dropped.
+pub(crate) fn unescape(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'\\' {
+            out.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+        i += 1;
+        let Some(&c) = bytes.get(i) else {
+            break;
+        };
+        i += 1;
+        match c {
+            b'n' => out.push(b'\n'),
+            b't' => out.push(b'\t'),
+            b'r' => out.push(b'\r'),
+            b'b' => out.push(0x08),
+            b'f' => out.push(0x0c),
+            b'v' => out.push(0x0b),
+            b'0'..=b'7' => {
+                let mut n = u32::from(c - b'0');
+                for _ in 0..2 {
+                    match bytes.get(i) {
+                        Some(&d @ b'0'..=b'7') => {
+                            n = n * 8 + u32::from(d - b'0');
+                            i += 1;
+                        }
+                        _ => break,
+                    }
+                }
+                out.push(n as u8);
+            }
+            _ => out.push(c),
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Parsing of the `--notification --listen` stdin protocol.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_known_commands() {
+        assert_eq!(
+            parse_line("message: Done"),
+            Some(Command::Message("Done".into()))
+        );
+        assert_eq!(
+            parse_line("tooltip:Still working"),
+            Some(Command::Tooltip("Still working".into()))
+        );
+        assert_eq!(
+            parse_line("icon: dialog-warning"),
+            Some(Command::Icon("dialog-warning".into()))
+        );
+    }
+
+    #[test]
+    fn command_names_are_case_insensitive_and_trimmed() {
+        assert_eq!(
+            parse_line("  MESSAGE  :  hi  "),
+            Some(Command::Message("hi".into()))
+        );
+        assert_eq!(parse_line("Visible: FALSE"), Some(Command::Visible(false)));
+    }
+
+    #[test]
+    fn only_false_hides() {
+        assert_eq!(parse_line("visible: false"), Some(Command::Visible(false)));
+        assert_eq!(parse_line("visible: true"), Some(Command::Visible(true)));
+        assert_eq!(parse_line("visible: 0"), Some(Command::Visible(true)));
+        assert_eq!(parse_line("visible:"), Some(Command::Visible(true)));
+    }
+
+    #[test]
+    fn value_keeps_later_colons() {
+        assert_eq!(
+            parse_line("message: at 12:30"),
+            Some(Command::Message("at 12:30".into()))
+        );
+    }
+
+    #[test]
+    fn message_and_tooltip_are_unescaped() {
+        assert_eq!(
+            parse_line(r"message: Title\nBody"),
+            Some(Command::Message("Title\nBody".into()))
+        );
+        assert_eq!(
+            parse_line(r"tooltip: a\tb"),
+            Some(Command::Tooltip("a\tb".into()))
+        );
+        // Icon names are taken literally
+        assert_eq!(
+            parse_line(r"icon: C:\new"),
+            Some(Command::Icon(r"C:\new".into()))
+        );
+    }
+
+    #[test]
+    fn unknown_and_malformed_lines() {
+        assert_eq!(
+            parse_line("Menu: Quit"),
+            Some(Command::Unknown("menu".into()))
+        );
+        assert_eq!(parse_line("just some text"), Some(Command::Malformed));
+        assert_eq!(parse_line(""), None);
+        assert_eq!(parse_line("   "), None);
+    }
+
+    #[test]
+    fn unescape_quirks() {
+        assert_eq!(unescape(r"a\\nb"), r"a\nb");
+        assert_eq!(unescape(r"\101\102"), "AB");
+        assert_eq!(unescape(r"\0"), "\0");
+        assert_eq!(unescape(r"\q"), "q");
+        assert_eq!(unescape(r#"say \"hi\""#), r#"say "hi""#);
+        assert_eq!(unescape(r"trailing\"), "trailing");
+        assert_eq!(unescape("héllo\\nwörld"), "héllo\nwörld");
+    }
+}
@@ -0,0 +1,530 @@
+//! The Pango markup that dialog text may use, as in zenity:
+//! `<b>bold</b>`, `<i>italic</i>`, `<u>underlined</u>` and
+//! `<span foreground="#ff0000">` (with `weight`, `style` and `underline`
+//! too), plus entities such as `&amp;`.
+//!
+//! Pango's other tags (`big`, `small`, `tt`, `s`, `sub`, `sup`) are taken
+//! out and their text kept as it is. Markup never fails: a tag that isn't
+//! Pango's, or that isn't closed in order, is shown as written.
+
+use crate::render::{Rgba, RunStyle, TextRun, rgb};
+
+/// Text with its markup taken out, and the styles the markup gave it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Markup {
+    pub text: String,
+    /// Styled byte ranges of `text`, in order and not overlapping. Text
+    /// outside them is plain.
+    pub runs: Vec<TextRun>,
+}
+
+impl Markup {
+    /// `text` as it is, with nothing styled.
+    pub fn plain(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            runs: Vec::new(),
+        }
+    }
+}
+
+/// A style change made by a tag, applied over the styles around it.
+#[derive(Debug, Clone, Copy, Default)]
+struct Change {
+    bold: Option<bool>,
+    italic: Option<bool>,
+    underline: Option<bool>,
+    color: Option<Rgba>,
+}
+
+impl Change {
+    fn apply(&self, style: &mut RunStyle) {
+        style.bold = self.bold.unwrap_or(style.bold);
+        style.italic = self.italic.unwrap_or(style.italic);
+        style.underline = self.underline.unwrap_or(style.underline);
+        style.color = self.color.or(style.color);
+    }
+}
+
+enum Token<'a> {
+    Text(&'a str),
+    /// An opening tag, as written, with its name and what it changes.
+    Open(&'a str, &'a str, Change),
+    /// A closing tag, as written, with its name.
+    Close(&'a str, &'a str),
+}
+
+/// Parses Pango `markup`. Never fails; see the [module docs](self).
+pub(crate) fn parse(markup: &str) -> Markup {
+    let tokens = tokenize(markup);
+
+    // Pair each closing tag with the opening tag it closes; tags left
+    // unpaired are text
+    let mut paired = vec![false; tokens.len()];
+    let mut open: Vec<usize> = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Open(..) => open.push(i),
+            Token::Close(_, name) => {
+                if let Some(&top) = open.last()
+                    && let Token::Open(_, open_name, _) = tokens[top]
+                    && open_name.eq_ignore_ascii_case(name)
+                {
+                    open.pop();
+                    paired[top] = true;
+                    paired[i] = true;
+                }
+            }
+            Token::Text(_) => {}
+        }
+    }
+
+    let mut result = Markup::default();
+    let mut changes: Vec<Change> = Vec::new();
+    for (token, paired) in tokens.iter().zip(paired) {
+        let text = match (token, paired) {
+            (Token::Open(_, _, change), true) => {
+                changes.push(*change);
+                continue;
+            }
+            (Token::Close(..), true) => {
+                changes.pop();
+                continue;
+            }
+            (Token::Text(text), _) => decode_entities(text),
+            (Token::Open(raw, ..) | Token::Close(raw, _), false) => raw.to_string(),
+        };
+        if text.is_empty() {
+            continue;
+        }
+        let mut style = RunStyle::default();
+        for change in &changes {
+            change.apply(&mut style);
+        }
+        let start = result.text.len();
+        result.text.push_str(&text);
+        let end = result.text.len();
+        if style == RunStyle::default() {
+            continue;
+        }
+        match result.runs.last_mut() {
+            Some(run) if run.range.end == start && run.style == style => run.range.end = end,
+            _ => {
+                result.runs.push(TextRun {
+                    range: start..end,
+                    style,
+                })
+            }
+        }
+    }
+    result
+}
+
+/// Splits markup into text and tags. Tags that aren't Pango's, or are
+/// written wrong, stay in the text.
+fn tokenize(markup: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut text_start = 0;
+    let mut i = 0;
+    while let Some(lt) = markup[i..].find('<') {
+        let start = i + lt;
+        let Some(len) = tag_len(&markup[start..]) else {
+            i = start + 1;
+            continue;
+        };
+        let raw = &markup[start..start + len];
+        let Some(tag) = parse_tag(raw) else {
+            i = start + 1;
+            continue;
+        };
+        if text_start < start {
+            tokens.push(Token::Text(&markup[text_start..start]));
+        }
+        tokens.push(tag);
+        i = start + len;
+        text_start = i;
+    }
+    if text_start < markup.len() {
+        tokens.push(Token::Text(&markup[text_start..]));
+    }
+    tokens
+}
+
+/// Length of the tag at the start of `s` (which starts with `<`), up to
+/// the `>` that isn't inside a quoted attribute value.
+fn tag_len(s: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in s.char_indices().skip(1) {
+        match (quote, c) {
+            (None, '>') => return Some(i + 1),
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '<') => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The tag written as `raw`, if it's one of Pango's and well formed.
+fn parse_tag(raw: &str) -> Option<Token<'_>> {
+    let inner = &raw[1..raw.len() - 1];
+    if let Some(name) = inner.strip_prefix('/') {
+        let name = name.trim_end();
+        return known_tag(name).then_some(Token::Close(raw, name));
+    }
+    let name_len = inner
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(inner.len());
+    let (name, attrs) = inner.split_at(name_len);
+    if !known_tag(name) || !(attrs.is_empty() || attrs.starts_with(char::is_whitespace)) {
+        return None;
+    }
+    let mut change = Change::default();
+    match name.to_ascii_lowercase().as_str() {
+        "b" => change.bold = Some(true),
+        "i" => change.italic = Some(true),
+        "u" => change.underline = Some(true),
+        "span" => {
+            for (key, value) in attributes(attrs)? {
+                span_attribute(&mut change, key, &value);
+            }
+        }
+        // Sizes, fonts and the like aren't shown
+        _ => {}
+    }
+    // Only spans take attributes
+    if !name.eq_ignore_ascii_case("span") && !attrs.trim().is_empty() {
+        return None;
+    }
+    Some(Token::Open(raw, name, change))
+}
+
+fn known_tag(name: &str) -> bool {
+    [
+        "b", "i", "u", "s", "tt", "big", "small", "sub", "sup", "span",
+    ]
+    .iter()
+    .any(|tag| tag.eq_ignore_ascii_case(name))
+}
+
+/// The `key="value"` pairs of a tag, or `None` if they're written wrong.
+fn attributes(attrs: &str) -> Option<Vec<(&str, String)>> {
+    let mut result = Vec::new();
+    let mut rest = attrs.trim_start();
+    while !rest.is_empty() {
+        let (key, after) = rest.split_once('=')?;
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return None;
+        }
+        let after = after.trim_start();
+        let quote = after.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+        let end = after[1..].find(quote)? + 1;
+        result.push((key, decode_entities(&after[1..end])));
+        rest = after[end + 1..].trim_start();
+    }
+    Some(result)
+}
+
+/// Applies one of a span's attributes. Ones that aren't shown, and values
+/// that make no sense, are ignored.
+fn span_attribute(change: &mut Change, key: &str, value: &str) {
+    let value = value.trim().to_ascii_lowercase();
+    match key.to_ascii_lowercase().as_str() {
+        "foreground" | "fgcolor" | "color" => {
+            if let Some(color) = parse_color(&value) {
+                change.color = Some(color);
+            }
+        }
+        "weight" | "font_weight" => {
+            change.bold = match value.as_str() {
+                "bold" | "semibold" | "ultrabold" | "heavy" | "ultraheavy" => Some(true),
+                "normal" | "book" | "medium" | "light" | "ultralight" | "thin" => Some(false),
+                number => number.parse::<u32>().ok().map(|weight| weight >= 600),
+            }
+        }
+        "style" | "font_style" => {
+            change.italic = match value.as_str() {
+                "italic" | "oblique" => Some(true),
+                "normal" => Some(false),
+                _ => None,
+            }
+        }
+        "underline" => {
+            change.underline = match value.as_str() {
+                "single" | "double" | "low" | "error" | "true" => Some(true),
+                "none" | "false" => Some(false),
+                _ => None,
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A color written `#rgb`, `#rrggbb` or `#rrrrggggbbbb`, or one of the
+/// common color names.
+pub(crate) fn parse_color(value: &str) -> Option<Rgba> {
+    if let Some(hex) = value.strip_prefix('#') {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let digits = match hex.len() {
+            3 => 1,
+            6 => 2,
+            12 => 4,
+            _ => return None,
+        };
+        let channel = |i: usize| {
+            let part = &hex[i * digits..(i + 1) * digits];
+            let value = u32::from_str_radix(part, 16).ok()?;
+            let max = (1u32 << (digits * 4)) - 1;
+            Some((value * 255 / max) as u8)
+        };
+        return Some(rgb(channel(0)?, channel(1)?, channel(2)?));
+    }
+    Some(match value.to_ascii_lowercase().as_str() {
+        "black" => rgb(0, 0, 0),
+        "white" => rgb(255, 255, 255),
+        "red" => rgb(255, 0, 0),
+        "green" => rgb(0, 128, 0),
+        "blue" => rgb(0, 0, 255),
+        "yellow" => rgb(255, 255, 0),
+        "cyan" => rgb(0, 255, 255),
+        "magenta" => rgb(255, 0, 255),
+        "gray" | "grey" => rgb(128, 128, 128),
+        "orange" => rgb(255, 165, 0),
+        "purple" => rgb(128, 0, 128),
+        "brown" => rgb(165, 42, 42),
+        "pink" => rgb(255, 192, 203),
+        _ => return None,
+    })
+}
+
+/// Decodes character references (`&amp;`, `&#233;`, `&#x2014;`...). Unknown
+/// or malformed ones are kept as written.
+pub(crate) fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let end = rest[1..]
+            .char_indices()
+            .take(32)
+            .find(|&(_, c)| !c.is_ascii_alphanumeric() && c != '#')
+            .map(|(i, _)| i + 1);
+        let decoded = end
+            .filter(|&end| rest[end..].starts_with(';'))
+            .and_then(|end| entity(&rest[1..end]).map(|c| (c, end + 1)));
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn entity(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code).filter(|&c| c != '\0');
+    }
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "mdash" => '—',
+        "ndash" => '–',
+        "hellip" => '…',
+        "bull" => '•',
+        "middot" => '·',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "larr" => '←',
+        "rarr" => '→',
+        "times" => '×',
+        "deg" => '°',
+        "euro" => '€',
+        _ => return None,
+    })
+}
+
+/// The Pango markup subset dialog text may use: styled runs, entities, and
+/// markup that's malformed or isn't Pango's staying as written.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::Font;
+
+    const BOLD: RunStyle = RunStyle {
+        bold: true,
+        italic: false,
+        underline: false,
+        color: None,
+    };
+
+    fn run(range: std::ops::Range<usize>, style: RunStyle) -> TextRun {
+        TextRun {
+            range,
+            style,
+        }
+    }
+
+    /// The text of each styled run.
+    fn styled_text(markup: &Markup) -> Vec<&str> {
+        markup
+            .runs
+            .iter()
+            .map(|run| &markup.text[run.range.clone()])
+            .collect()
+    }
+
+    #[test]
+    fn tags_become_styled_runs() {
+        let markup = parse("Delete <b>all</b> files in <i>~/tmp</i>?");
+        assert_eq!(markup.text, "Delete all files in ~/tmp?");
+        assert_eq!(styled_text(&markup), ["all", "~/tmp"]);
+        assert_eq!(markup.runs[0].style, BOLD);
+        assert!(markup.runs[1].style.italic && !markup.runs[1].style.bold);
+
+        let markup = parse("<u>under</u><s>struck</s><tt>mono</tt>");
+        assert_eq!(markup.text, "understruckmono");
+        assert_eq!(markup.runs.len(), 1);
+        assert!(markup.runs[0].style.underline);
+    }
+
+    #[test]
+    fn spans_set_color_weight_style_and_underline() {
+        let markup = parse(
+            r##"<span foreground="#ff0000">red</span> <span color='blue' weight="bold" style="italic" underline="single">all</span>"##,
+        );
+        assert_eq!(markup.text, "red all");
+        assert_eq!(markup.runs[0].style.color, Some(rgb(255, 0, 0)));
+        assert_eq!(
+            markup.runs[1].style,
+            RunStyle {
+                bold: true,
+                italic: true,
+                underline: true,
+                color: Some(rgb(0, 0, 255)),
+            }
+        );
+        // Attributes that aren't shown, or make no sense, are ignored
+        let markup = parse(r#"<span font="Sans 12" foreground="nonsense" weight="900">x</span>"#);
+        assert_eq!(markup.text, "x");
+        assert_eq!(markup.runs, [run(0..1, BOLD)]);
+    }
+
+    #[test]
+    fn nested_styles_combine_and_inner_ones_win() {
+        let markup = parse(r#"<b>a<i>b</i><span weight="normal">c</span></b>"#);
+        assert_eq!(markup.text, "abc");
+        assert_eq!(
+            markup.runs,
+            [
+                run(0..1, BOLD),
+                run(
+                    1..2,
+                    RunStyle {
+                        italic: true,
+                        ..BOLD
+                    }
+                ),
+            ]
+        );
+        // Neighbouring text in the same style is one run
+        assert_eq!(parse("<b>a</b><b>b</b>").runs, [run(0..2, BOLD)]);
+    }
+
+    #[test]
+    fn unbalanced_and_unknown_tags_are_shown_as_written() {
+        for text in [
+            "a < b",
+            "x<y",
+            "<b>never closed",
+            "stray </b>",
+            "<foo>not pango</foo>",
+            "<b class=\"x\">no attributes</b>",
+            "<span foreground=\"red>x</span>",
+            "<>",
+            "</>",
+            "<<b>>",
+        ] {
+            let markup = parse(text);
+            assert_eq!(markup.text, text);
+            assert!(markup.runs.is_empty(), "{text}");
+        }
+
+        // Crossed tags: </b> doesn't close the <i> opened after it, so only
+        // the <i> pairs
+        let markup = parse("<b>one <i>two</b> three</i>");
+        assert_eq!(markup.text, "<b>one two</b> three");
+        assert_eq!(styled_text(&markup), ["two</b> three"]);
+    }
+
+    #[test]
+    fn entities_are_decoded_outside_tags() {
+        let markup = parse("<b>Tom &amp; Jerry</b> &lt;b&gt; &copy; &#233; &bogus;");
+        assert_eq!(markup.text, "Tom & Jerry <b> © é &bogus;");
+        assert_eq!(styled_text(&markup), ["Tom & Jerry"]);
+    }
+
+    #[test]
+    fn plain_text_is_left_alone() {
+        let text = "<b>literal</b> &amp;";
+        let plain = Markup::plain(text);
+        assert_eq!(plain.text, text);
+        assert!(plain.runs.is_empty());
+        assert_eq!(parse("no markup here"), Markup::plain("no markup here"));
+    }
+
+    #[test]
+    fn colors_are_hex_or_names() {
+        assert_eq!(parse_color("#f00"), Some(rgb(255, 0, 0)));
+        assert_eq!(parse_color("#00ff80"), Some(rgb(0, 255, 128)));
+        assert_eq!(parse_color("#ffff00000000"), Some(rgb(255, 0, 0)));
+        assert_eq!(parse_color("Orange"), Some(rgb(255, 165, 0)));
+        for bad in ["#ff", "#gg0000", "ff0000", "#", "", "notacolor"] {
+            assert_eq!(parse_color(bad), None, "{bad}");
+        }
+    }
+
+    #[test]
+    fn styled_runs_render() {
+        let font = Font::load(1.0);
+        let markup = parse(
+            r##"plain <b>bold</b> <i>italic</i> <span foreground="#3070f0"><u>link</u></span>"##,
+        );
+        let plain = font.render(&markup.text).finish();
+        let styled = font
+            .render(&markup.text)
+            .with_runs(&markup.runs)
+            .with_max_width(80.0)
+            .finish();
+        assert!(plain.width() > 0 && styled.width() > 0);
+        // Runs past the end of the text are ignored
+        let stray = [run(3..50, BOLD)];
+        font.render("abc").with_runs(&stray).finish();
+    }
+}
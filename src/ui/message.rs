@@ -1,15 +1,23 @@
 //! Message dialog implementation (info, warning, error, question).
 
-use std::time::{Duration, Instant};
+use std::{
+    borrow::Borrow,
+    time::{Duration, Instant},
+};
 
 use crate::{
-    backend::{MouseButton, Window, WindowEvent, create_window},
+    backend::{CursorShape, Modifiers, MouseButton, Window, WindowEvent, create_window},
     error::Error,
-    render::{Canvas, Font, rgb},
+    render::{Align, Canvas, EllipsizeMode, Font, rgb, sanitize_text},
     ui::{
-        BASE_BUTTON_HEIGHT, BASE_BUTTON_SPACING, BASE_CORNER_RADIUS, ButtonPreset, Colors,
-        DialogResult, Icon, KEY_ESCAPE, KEY_RETURN,
-        widgets::{Widget, button::Button},
+        BASE_BUTTON_HEIGHT, BASE_BUTTON_SPACING, BASE_CORNER_RADIUS, ButtonOrder, ButtonPreset,
+        Colors, DialogOutcome, DialogResult, Icon, KEY_C, KEY_ESCAPE, KEY_RETURN,
+        focus::{FocusAction, FocusManager},
+        geometry::Offset,
+        markup::{self, Markup},
+        sound, wait_for_event_until,
+        widgets::{Widget, button::Button, checkbox::Checkbox, selectable_text::SelectableText},
+        zoom,
     },
 };
 
@@ -17,6 +25,8 @@ const BASE_ICON_SIZE: u32 = 48;
 const BASE_PADDING: u32 = 20;
 const BASE_MIN_WIDTH: u32 = 150;
 const BASE_MAX_TEXT_WIDTH: f32 = 150.0;
+/// How long the default button flashes when the dialog is activated again.
+const FLASH_DURATION: Duration = Duration::from_millis(300);
 
 /// Message dialog builder.
 pub struct MessageBuilder {
@@ -27,12 +37,20 @@ pub struct MessageBuilder {
     timeout: Option<u32>,
     width: Option<u32>,
     height: Option<u32>,
+    position: Option<(Offset, Offset)>,
     no_wrap: bool,
     no_markup: bool,
     ellipsize: bool,
+    text_align: Option<Align>,
     switch: bool,
     extra_buttons: Vec<String>,
-    colors: Option<&'static Colors>,
+    custom_content: Option<Box<dyn Widget + Send>>,
+    checkbox: Option<String>,
+    checkbox_checked: bool,
+    colors: Option<Colors>,
+    recolor_icon: bool,
+    default_cancel: bool,
+    sound: bool,
 }
 
 impl MessageBuilder {
@@ -45,12 +63,20 @@ impl MessageBuilder {
             timeout: None,
             width: None,
             height: None,
+            position: None,
             no_wrap: false,
             no_markup: false,
             ellipsize: false,
+            text_align: None,
             switch: false,
             extra_buttons: Vec::new(),
+            custom_content: None,
+            checkbox: None,
+            checkbox_checked: false,
             colors: None,
+            recolor_icon: true,
+            default_cancel: false,
+            sound: false,
         }
     }
 
@@ -66,7 +92,7 @@ impl MessageBuilder {
     }
 
     pub fn text(mut self, text: &str) -> Self {
-        self.text = text.to_string();
+        self.text = sanitize_text(text, false);
         self
     }
 
@@ -80,8 +106,22 @@ impl MessageBuilder {
         self
     }
 
-    pub fn colors(mut self, colors: &'static Colors) -> Self {
-        self.colors = Some(colors);
+    pub fn colors(mut self, colors: impl Borrow<Colors>) -> Self {
+        self.colors = Some(*colors.borrow());
+        self
+    }
+
+    /// Whether a monochrome custom icon is tinted to match the theme
+    /// (default: true).
+    pub fn recolor_icon(mut self, recolor: bool) -> Self {
+        self.recolor_icon = recolor;
+        self
+    }
+
+    /// Play the icon's event sound from the sound theme when the dialog
+    /// opens (default: false).
+    pub fn sound(mut self, sound: bool) -> Self {
+        self.sound = sound;
         self
     }
 
@@ -95,52 +135,148 @@ impl MessageBuilder {
         self
     }
 
+    /// Request a window position. Ignored where the platform doesn't allow it.
+    pub fn position(mut self, x: Offset, y: Offset) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    /// Don't wrap the text; the dialog grows to fit it and the width set
+    /// with [`width`](Self::width) becomes a minimum.
     pub fn no_wrap(mut self, no_wrap: bool) -> Self {
         self.no_wrap = no_wrap;
         self
     }
 
+    /// Show the text literally instead of as Pango [markup](markup).
     pub fn no_markup(mut self, no_markup: bool) -> Self {
         self.no_markup = no_markup;
         self
     }
 
+    /// Cut lines longer than the text width short with "…" instead of
+    /// wrapping them. Takes precedence over [`no_wrap`](Self::no_wrap).
     pub fn ellipsize(mut self, ellipsize: bool) -> Self {
         self.ellipsize = ellipsize;
         self
     }
 
+    /// Align each line of the text within the text width. By default the
+    /// text is left-aligned and centered as a block.
+    pub fn text_align(mut self, align: Align) -> Self {
+        self.text_align = Some(align);
+        self
+    }
+
     pub fn switch(mut self, switch: bool) -> Self {
         self.switch = switch;
         self
     }
 
+    /// Make the negative button (Cancel or No) the one Enter activates, for
+    /// destructive confirmations.
+    pub fn default_cancel(mut self, default_cancel: bool) -> Self {
+        self.default_cancel = default_cancel;
+        self
+    }
+
     pub fn extra_button(mut self, label: &str) -> Self {
         self.extra_buttons.push(label.to_string());
         self
     }
 
+    /// Insert an application-provided widget between the text and the buttons.
+    ///
+    /// The widget is moved into the dialog and receives every window event
+    /// while it is shown. Use [`show_with_content`](Self::show_with_content)
+    /// to get it back afterwards and downcast it to read its state.
+    pub fn custom_content(mut self, widget: Box<dyn Widget + Send>) -> Self {
+        self.custom_content = Some(widget);
+        self
+    }
+
+    /// Show a checkbox with this label between the text and the buttons,
+    /// e.g. "Do not ask again". Its state is reported by
+    /// [`show_detailed`](Self::show_detailed).
+    pub fn checkbox(mut self, label: &str) -> Self {
+        self.checkbox = Some(label.to_string());
+        self
+    }
+
     pub fn show(self) -> Result<DialogResult, Error> {
-        let colors = self.colors.unwrap_or_else(|| crate::ui::detect_theme());
+        self.show_with_content().map(|(result, _)| result)
+    }
 
-        // First pass: calculate LOGICAL dimensions using a temporary font at scale 1.0
-        let temp_font = Font::load(1.0);
-        let mut labels = self.buttons.labels();
+    /// Like [`show`](Self::show), but also tells which label the chosen
+    /// button had.
+    pub fn show_detailed(mut self) -> Result<DialogOutcome, Error> {
+        let mut content = self.custom_content.take();
+        self.show_outcome(content.as_deref_mut())
+    }
 
-        // Apply --switch mode: if switch is true, use only extra buttons
-        if self.switch {
-            labels = self.extra_buttons.clone();
-        } else {
-            // Append extra buttons to preset buttons
-            labels.extend(self.extra_buttons.clone());
+    /// Like [`show`](Self::show), but also returns the widget passed to
+    /// [`custom_content`](Self::custom_content), if any.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn widget() -> Box<dyn zenity_rs::Widget + Send> { unimplemented!() }
+    /// use std::any::Any;
+    ///
+    /// let (result, content) = zenity_rs::question("Apply profile?")
+    ///     .custom_content(widget())
+    ///     .show_with_content()
+    ///     .unwrap();
+    /// let content: Box<dyn Any> = content.unwrap();
+    /// ```
+    pub fn show_with_content(
+        mut self,
+    ) -> Result<(DialogResult, Option<Box<dyn Widget + Send>>), Error> {
+        let mut content = self.custom_content.take();
+        let outcome = self.show_outcome(content.as_deref_mut())?;
+        Ok((outcome.result, content))
+    }
+
+    fn show_outcome(
+        &mut self,
+        mut content: Option<&mut (dyn Widget + Send)>,
+    ) -> Result<DialogOutcome, Error> {
+        let deadline = self
+            .timeout
+            .map(|secs| Instant::now() + Duration::from_secs(secs as u64));
+        loop {
+            if let Some(mut outcome) = self.run(content.as_deref_mut(), deadline)? {
+                outcome.checked = self.checkbox.is_some().then_some(self.checkbox_checked);
+                return Ok(outcome);
+            }
         }
+    }
+
+    /// Shows the dialog until it's answered, or returns `None` to be run
+    /// again after a [zoom](crate::ui::zoom) change.
+    fn run(
+        &mut self,
+        mut content: Option<&mut (dyn Widget + Send)>,
+        deadline: Option<Instant>,
+    ) -> Result<Option<DialogOutcome>, Error> {
+        let colors = &self.colors.unwrap_or_else(|| *crate::ui::detect_theme());
+        let styled = if self.no_markup {
+            Markup::plain(&self.text)
+        } else {
+            markup::parse(&self.text)
+        };
 
-        // Reverse labels so that when we position them right-to-left,
-        // the last buttons (standard Yes/No) appear on the right
-        let num_labels = labels.len();
-        labels.reverse();
-        // Map reversed index back to original index for correct exit codes
-        let original_index: Vec<usize> = (0..num_labels).rev().collect();
+        // First pass: calculate LOGICAL dimensions using a temporary font at scale 1.0
+        let temp_font = Font::load(1.0);
+        // With --switch, only the extra buttons are shown
+        let preset = if self.switch {
+            Vec::new()
+        } else {
+            self.buttons.labels()
+        };
+        let preset_count = preset.len();
+        let order = ButtonOrder::new(preset, &self.extra_buttons);
+        let labels: Vec<&str> = order.laid_out().collect();
 
         // Calculate logical button widths and determine layout
         let temp_buttons: Vec<Button> = labels
@@ -172,12 +308,24 @@ impl MessageBuilder {
         // --width specifies text area width, not total window width
         let text_width = self.width.map(|w| w as f32).unwrap_or(BASE_MAX_TEXT_WIDTH);
 
-        // Calculate logical text size with/without wrapping
-        let temp_text = if self.no_wrap {
-            temp_font.render(&self.text).finish()
+        // Calculate logical text size with/without wrapping. Ellipsizing
+        // wins over --no-wrap: lines never wrap and are cut at the text width.
+        let temp_text = if self.ellipsize {
+            temp_font
+                .render(&styled.text)
+                .with_runs(&styled.runs)
+                .with_max_width(text_width)
+                .with_ellipsize(EllipsizeMode::End)
+                .finish()
+        } else if self.no_wrap {
+            temp_font
+                .render(&styled.text)
+                .with_runs(&styled.runs)
+                .finish()
         } else {
             temp_font
-                .render(&self.text)
+                .render(&styled.text)
+                .with_runs(&styled.runs)
                 .with_max_width(text_width)
                 .finish()
         };
@@ -185,16 +333,41 @@ impl MessageBuilder {
         // Use specified text_width for window sizing
         // When no_wrap is true, width is treated as minimum, content can expand beyond it
         let logical_content_width = logical_icon_width
-            + if self.no_wrap {
+            + if self.no_wrap && !self.ellipsize {
                 // Treat width as minimum: use max of content width and specified width
                 temp_text.width().max(text_width as u32)
             } else {
                 // Use specified width for wrapping
                 text_width as u32
             };
-        let logical_inner_width = logical_content_width.max(logical_buttons_width);
+        // Custom content goes on its own row below the text
+        let (logical_custom_width, logical_custom_height) = content
+            .as_deref()
+            .map(|w| {
+                let (w, h) = w.preferred_size();
+                (w, h + BASE_PADDING)
+            })
+            .unwrap_or((0, 0));
+        // So does the checkbox, lined up with the text
+        let (logical_checkbox_width, logical_checkbox_height) = self
+            .checkbox
+            .as_deref()
+            .map(|label| {
+                let checkbox = Checkbox::new(label, &temp_font, 1.0);
+                (
+                    logical_icon_width + checkbox.width(),
+                    checkbox.height() + BASE_PADDING,
+                )
+            })
+            .unwrap_or((0, 0));
+        let logical_inner_width = logical_content_width
+            .max(logical_buttons_width)
+            .max(logical_custom_width)
+            .max(logical_checkbox_width);
         let calc_width = (logical_inner_width + BASE_PADDING * 2).max(BASE_MIN_WIDTH);
-        let logical_text_height = temp_text.height().max(BASE_ICON_SIZE);
+        let logical_text_height = temp_text.height().max(BASE_ICON_SIZE)
+            + logical_custom_height
+            + logical_checkbox_height;
         let button_area_height = if use_vertical_layout {
             temp_buttons.len() as u32 * 32
                 + (temp_buttons.len().saturating_sub(1) as u32 * BASE_BUTTON_SPACING)
@@ -204,7 +377,7 @@ impl MessageBuilder {
         let calc_height = BASE_PADDING * 3 + logical_text_height + button_area_height;
 
         let logical_width = calc_width as u16;
-        let logical_height = self.height.unwrap_or(calc_height) as u16;
+        let logical_height = self.height.map_or(calc_height, |h| h.max(calc_height)) as u16;
 
         // Create window with LOGICAL dimensions - window will handle physical scaling
         let mut window = create_window(logical_width, logical_height)?;
@@ -228,19 +401,40 @@ impl MessageBuilder {
             .map(|l| Button::new(l, &font, scale))
             .collect();
 
+        // Enter activates the first button, or with --default-cancel the last
+        // preset one (Cancel or No), which is then marked
+        let default_index = if self.default_cancel && preset_count >= 2 {
+            preset_count - 1
+        } else {
+            0
+        };
+        let default_button = order.position(default_index);
+        if default_index > 0
+            && let Some(button) = default_button.and_then(|i| buttons.get_mut(i))
+        {
+            button.set_default(true);
+        }
+
         // Calculate physical dimensions
         let physical_width = (logical_width as f32 * scale) as u32;
         let physical_height = (logical_height as f32 * scale) as u32;
 
-        // Pre-render text to get actual height
-        let text_canvas = if self.no_wrap {
-            font.render(&self.text).with_color(colors.text).finish()
-        } else {
-            font.render(&self.text)
-                .with_color(colors.text)
+        // Lay out the (selectable) text once; its size drives the layout below
+        let mut renderer = font
+            .render(&styled.text)
+            .with_color(colors.text)
+            .with_runs(&styled.runs);
+        if self.ellipsize {
+            renderer = renderer
                 .with_max_width(max_text_width)
-                .finish()
-        };
+                .with_ellipsize(EllipsizeMode::End);
+        } else if !self.no_wrap {
+            renderer = renderer.with_max_width(max_text_width);
+        }
+        if let Some(align) = self.text_align {
+            renderer = renderer.with_align(align);
+        }
+        let mut text = SelectableText::from_renderer(renderer);
 
         // Position buttons
         let mut button_positions = Vec::with_capacity(buttons.len());
@@ -278,101 +472,222 @@ impl MessageBuilder {
             button.set_position(button_positions[idx].0, button_positions[idx].1);
         }
 
+        // Position custom content below the text/icon row
+        if let Some(widget) = content.as_deref_mut() {
+            widget.set_scale(scale);
+            let icon_size = (BASE_ICON_SIZE as f32 * scale) as u32;
+            let text_area_height = text.height().max(icon_size);
+            widget.set_position(padding as i32, (padding * 2 + text_area_height) as i32);
+        }
+
+        // The checkbox goes below that, under the text. It has the focus
+        // until Tab moves it to the buttons.
+        let mut checkbox = self.checkbox.as_deref().map(|label| {
+            let mut checkbox = Checkbox::new(label, &font, scale);
+            checkbox.set_checked(self.checkbox_checked);
+            checkbox.set_focus(true);
+            let icon_size = (BASE_ICON_SIZE as f32 * scale) as u32;
+            let x = if self.icon.is_some() {
+                padding * 2 + icon_size
+            } else {
+                padding
+            };
+            let content_height = content.as_deref().map_or(0, |w| w.height() + padding);
+            let y = padding * 2 + text.height().max(icon_size) + content_height;
+            checkbox.set_position(x as i32, y as i32);
+            checkbox
+        });
+
+        // Text sits right of the icon, centered horizontally within the text
+        // area (which aligned text fills) and vertically with the icon
+        {
+            let icon_size = (BASE_ICON_SIZE as f32 * scale) as u32;
+            let text_area_x = if self.icon.is_some() {
+                padding * 2 + icon_size
+            } else {
+                padding
+            } as i32;
+            let text_x =
+                text_area_x + ((max_text_width - text.width() as f32) / 2.0).max(0.0) as i32;
+            let text_y = padding as i32 + (icon_size as i32 - text.height() as i32) / 2;
+            text.set_position(text_x, text_y.max(padding as i32));
+        }
+
         // Create canvas at PHYSICAL dimensions
         let mut canvas = Canvas::new(physical_width, physical_height);
 
         // Clone icon for multiple uses
-        let icon = self.icon.clone();
+        let mut icon = self.icon.clone();
+        let icon_size = (BASE_ICON_SIZE as f32 * scale) as u32;
+        let icon_image = icon
+            .as_ref()
+            .and_then(|icon| icon.load_image(icon_size, colors, self.recolor_icon));
+        // An image file that can't be read shouldn't take the dialog down
+        if icon_image.is_none()
+            && let Some(Icon::Custom(path)) = icon.as_ref().filter(|icon| icon.is_path())
+        {
+            eprintln!("zenity-rs: warning: can't load icon {path:?}, using the info icon");
+            icon = Some(Icon::Info);
+        }
 
         // Initial draw
         draw_dialog(
             &mut canvas,
             colors,
             &font,
-            &self.text,
+            &text,
             icon.clone(),
+            icon_image.as_ref(),
             &buttons,
-            text_canvas.height(),
-            max_text_width,
-            self.no_wrap,
+            content.as_deref(),
+            checkbox.as_ref(),
             scale,
         );
         window.set_contents(&canvas)?;
+        if let Some((x, y)) = self.position {
+            window.set_position(x, y)?;
+        }
         window.show()?;
+        if self.sound
+            && let Some(name) = self.icon.as_ref().and_then(Icon::sound_name)
+        {
+            sound::play(name);
+        }
 
         // Event loop
         let mut dragging = false;
-        let deadline = self
-            .timeout
-            .map(|secs| Instant::now() + Duration::from_secs(secs as u64));
+
+        // Focus goes through the buttons top to bottom, or left to right
+        let mut focus = FocusManager::new(buttons.len());
+        let button_count = buttons.len();
+        let button_at = move |i: usize| {
+            if use_vertical_layout {
+                button_count - 1 - i
+            } else {
+                i
+            }
+        };
+
+        // The button Enter presses flashes when the dialog is activated again
+        let mut flash_until: Option<Instant> = None;
+        let flash = |buttons: &mut [Button]| {
+            if let Some(button) = default_button.and_then(|i| buttons.get_mut(i)) {
+                button.set_flashing(true);
+            }
+            Some(Instant::now() + FLASH_DURATION)
+        };
 
         loop {
-            // Check timeout
-            if let Some(deadline) = deadline {
-                if Instant::now() >= deadline {
-                    return Ok(DialogResult::Timeout);
+            let wake_at = match (deadline, flash_until) {
+                (Some(deadline), Some(flash_end)) => Some(deadline.min(flash_end)),
+                (deadline, flash_end) => deadline.or(flash_end),
+            };
+            let Some(event) = wait_for_event_until(&mut window, wake_at)? else {
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    return Ok(Some(DialogResult::Timeout.into()));
                 }
-            }
+                // The flash is over
+                flash_until = None;
+                for button in &mut buttons {
+                    button.set_flashing(false);
+                }
+                draw_dialog(
+                    &mut canvas,
+                    colors,
+                    &font,
+                    &text,
+                    icon.clone(),
+                    icon_image.as_ref(),
+                    &buttons,
+                    content.as_deref(),
+                    checkbox.as_ref(),
+                    scale,
+                );
+                window.set_contents(&canvas)?;
+                continue;
+            };
 
-            // Get event (use polling with sleep if timeout is set)
-            let event = if deadline.is_some() {
-                match window.poll_for_event()? {
-                    Some(e) => e,
-                    None => {
-                        std::thread::sleep(Duration::from_millis(50));
-                        continue;
-                    }
+            let focus_moved = match focus.process_event(&event) {
+                Some(FocusAction::Activate(i)) => return Ok(Some(order.outcome_at(button_at(i)))),
+                Some(FocusAction::Moved) => {
+                    show_focus(&focus, &mut buttons, checkbox.as_mut(), button_at);
+                    true
                 }
-            } else {
-                window.wait_for_event()?
+                None => false,
             };
 
             match &event {
                 WindowEvent::CloseRequested => {
-                    return Ok(DialogResult::Closed);
+                    return Ok(Some(DialogResult::Closed.into()));
                 }
                 WindowEvent::RedrawRequested => {
                     draw_dialog(
                         &mut canvas,
                         colors,
                         &font,
-                        &self.text,
+                        &text,
                         icon.clone(),
+                        icon_image.as_ref(),
                         &buttons,
-                        text_canvas.height(),
-                        max_text_width,
-                        self.no_wrap,
+                        content.as_deref(),
+                        checkbox.as_ref(),
                         scale,
                     );
                     window.set_contents(&canvas)?;
                 }
                 WindowEvent::KeyPress(key_event) => {
+                    if zoom::handle_key(key_event) {
+                        // Rebuilt at the new size; the sound was played already
+                        self.sound = false;
+                        return Ok(None);
+                    }
                     if key_event.keysym == KEY_ESCAPE {
-                        return Ok(DialogResult::Closed);
+                        return Ok(Some(DialogResult::Closed.into()));
                     }
                     if key_event.keysym == KEY_RETURN && !buttons.is_empty() {
-                        return Ok(DialogResult::Button(0));
+                        return Ok(Some(order.outcome(default_index)));
                     }
                 }
                 WindowEvent::ButtonPress(MouseButton::Left, _) => {
-                    dragging = true;
+                    // Presses on the text select it instead of moving the
+                    // window, and presses on the checkbox toggle it
+                    dragging =
+                        !text.is_hovered() && !checkbox.as_ref().is_some_and(Checkbox::is_hovered);
                 }
                 WindowEvent::ButtonRelease(MouseButton::Left, _) => {
                     if dragging {
                         dragging = false;
                     }
                 }
+                WindowEvent::Activated => flash_until = flash(&mut buttons),
                 _ => {}
             }
 
-            // Process events for buttons
-            let mut needs_redraw = false;
+            // Process events for the text, custom content and buttons
+            let mut needs_redraw = focus_moved
+                || text.process_event(&event)
+                || matches!(event, WindowEvent::Activated);
+            if is_copy(&event) {
+                window.write_clipboard(text.selected_text().unwrap_or(text.text()))?;
+            }
+            if let Some(widget) = content.as_deref_mut()
+                && widget.process_event(&event)
+            {
+                needs_redraw = true;
+            }
+            if let Some(checkbox) = checkbox.as_mut() {
+                needs_redraw |= checkbox.process_event(&event);
+                if checkbox.was_toggled() {
+                    self.checkbox_checked = checkbox.is_checked();
+                }
+            }
             for (i, button) in buttons.iter_mut().enumerate() {
                 if button.process_event(&event) {
                     needs_redraw = true;
                 }
+                button.process_mnemonic(&event, true);
                 if button.was_clicked() {
-                    return Ok(DialogResult::Button(original_index[i]));
+                    return Ok(Some(order.outcome_at(i)));
                 }
             }
 
@@ -386,34 +701,80 @@ impl MessageBuilder {
 
             // Batch process pending events
             while let Some(event) = window.poll_for_event()? {
+                match focus.process_event(&event) {
+                    Some(FocusAction::Activate(i)) => {
+                        return Ok(Some(order.outcome_at(button_at(i))));
+                    }
+                    Some(FocusAction::Moved) => {
+                        show_focus(&focus, &mut buttons, checkbox.as_mut(), button_at);
+                        needs_redraw = true;
+                    }
+                    None => {}
+                }
                 match &event {
                     WindowEvent::CloseRequested => {
-                        return Ok(DialogResult::Closed);
+                        return Ok(Some(DialogResult::Closed.into()));
+                    }
+                    WindowEvent::Activated => {
+                        flash_until = flash(&mut buttons);
+                        needs_redraw = true;
+                    }
+                    WindowEvent::KeyPress(key) if key.keysym == KEY_ESCAPE => {
+                        return Ok(Some(DialogResult::Closed.into()));
+                    }
+                    WindowEvent::KeyPress(key)
+                        if key.keysym == KEY_RETURN && !buttons.is_empty() =>
+                    {
+                        return Ok(Some(order.outcome(default_index)));
                     }
                     _ => {
+                        if text.process_event(&event) {
+                            needs_redraw = true;
+                        }
+                        if is_copy(&event) {
+                            window.write_clipboard(text.selected_text().unwrap_or(text.text()))?;
+                        }
+                        if let Some(widget) = content.as_deref_mut()
+                            && widget.process_event(&event)
+                        {
+                            needs_redraw = true;
+                        }
+                        if let Some(checkbox) = checkbox.as_mut() {
+                            needs_redraw |= checkbox.process_event(&event);
+                            if checkbox.was_toggled() {
+                                self.checkbox_checked = checkbox.is_checked();
+                            }
+                        }
                         for (i, button) in buttons.iter_mut().enumerate() {
                             if button.process_event(&event) {
                                 needs_redraw = true;
                             }
+                            button.process_mnemonic(&event, true);
                             if button.was_clicked() {
-                                return Ok(DialogResult::Button(original_index[i]));
+                                return Ok(Some(order.outcome_at(i)));
                             }
                         }
                     }
                 }
             }
 
+            window.set_cursor(if text.is_hovered() {
+                CursorShape::Text
+            } else {
+                CursorShape::Default
+            })?;
+
             if needs_redraw {
                 draw_dialog(
                     &mut canvas,
                     colors,
                     &font,
-                    &self.text,
+                    &text,
                     icon.clone(),
+                    icon_image.as_ref(),
                     &buttons,
-                    text_canvas.height(),
-                    max_text_width,
-                    self.no_wrap,
+                    content.as_deref(),
+                    checkbox.as_ref(),
                     scale,
                 );
                 window.set_contents(&canvas)?;
@@ -427,16 +788,15 @@ fn draw_dialog(
     canvas: &mut Canvas,
     colors: &Colors,
     font: &Font,
-    text: &str,
+    text: &SelectableText,
     icon: Option<Icon>,
+    icon_image: Option<&Canvas>,
     buttons: &[Button],
-    text_height: u32,
-    max_text_width: f32,
-    no_wrap: bool,
+    content: Option<&(dyn Widget + Send)>,
+    checkbox: Option<&Checkbox>,
     scale: f32,
 ) {
     // Scale dimensions
-    let icon_size = (BASE_ICON_SIZE as f32 * scale) as u32;
     let padding = (BASE_PADDING as f32 * scale) as u32;
     let width = canvas.width() as f32;
     let height = canvas.height() as f32;
@@ -452,30 +812,24 @@ fn draw_dialog(
         radius,
     );
 
-    let mut x = padding as i32;
-    let y = padding as i32;
-
     // Draw icon
-    if let Some(icon) = icon {
-        draw_icon(canvas, x, y, icon, scale);
-        x += (icon_size + padding) as i32;
+    if let Some(image) = icon_image {
+        canvas.draw_canvas(image, padding as i32, padding as i32);
+    } else if let Some(icon) = icon {
+        draw_icon(canvas, padding as i32, padding as i32, icon, scale);
     }
 
     // Draw text
-    let text_canvas = if no_wrap {
-        font.render(text).with_color(colors.text).finish()
-    } else {
-        font.render(text)
-            .with_color(colors.text)
-            .with_max_width(max_text_width)
-            .finish()
-    };
+    text.draw(canvas, colors);
+
+    // Draw custom content
+    if let Some(widget) = content {
+        widget.draw(canvas, colors);
+    }
 
-    // Center text horizontally within text area
-    let text_x = x + ((max_text_width - text_canvas.width() as f32) / 2.0).max(0.0) as i32;
-    // Center text vertically with icon
-    let text_y = y + (icon_size as i32 - text_height as i32) / 2;
-    canvas.draw_canvas(&text_canvas, text_x, text_y.max(y));
+    if let Some(checkbox) = checkbox {
+        checkbox.draw_to(canvas, colors, font);
+    }
 
     // Draw buttons
     for button in buttons {
@@ -483,6 +837,28 @@ fn draw_dialog(
     }
 }
 
+/// Rings the focused button, or focuses the checkbox while no button is.
+fn show_focus(
+    focus: &FocusManager,
+    buttons: &mut [Button],
+    checkbox: Option<&mut Checkbox>,
+    button_at: impl Fn(usize) -> usize,
+) {
+    let focused = focus.focused().map(button_at);
+    for (i, button) in buttons.iter_mut().enumerate() {
+        button.set_focused(focused == Some(i));
+    }
+    if let Some(checkbox) = checkbox {
+        checkbox.set_focus(focused.is_none());
+    }
+}
+
+/// True for the copy shortcut, Ctrl+C.
+fn is_copy(event: &WindowEvent) -> bool {
+    matches!(event, WindowEvent::KeyPress(key)
+        if key.keysym == KEY_C && key.modifiers.contains(Modifiers::CTRL))
+}
+
 fn draw_icon(canvas: &mut Canvas, x: i32, y: i32, icon: Icon, scale: f32) {
     let icon_size = (BASE_ICON_SIZE as f32 * scale) as u32;
     let inset = 4.0 * scale;
@@ -1,17 +1,54 @@
 //! UI components and dialog implementations.
 
+#[cfg(feature = "calendar")]
 pub(crate) mod calendar;
+pub mod date;
+#[cfg(feature = "entry")]
 pub(crate) mod entry;
+#[cfg(feature = "files")]
 pub(crate) mod file_select;
+#[cfg(any(feature = "message", feature = "calendar"))]
+pub(crate) mod focus;
+#[cfg(feature = "forms")]
 pub(crate) mod forms;
+pub(crate) mod geometry;
+#[cfg(any(feature = "files", feature = "entry"))]
+pub(crate) mod glob;
+#[cfg(feature = "textinfo")]
+pub(crate) mod html;
+pub(crate) mod layout;
+#[cfg(feature = "list")]
 pub(crate) mod list;
+#[cfg(feature = "notify")]
+pub(crate) mod listen;
+pub(crate) mod markup;
+#[cfg(feature = "message")]
 pub(crate) mod message;
+#[cfg(feature = "notify")]
+pub(crate) mod notification;
+#[cfg(feature = "progress")]
 pub(crate) mod progress;
+#[cfg(feature = "scale")]
 pub(crate) mod scale;
+pub(crate) mod sound;
+#[cfg(feature = "textinfo")]
 pub(crate) mod text_info;
-pub(crate) mod widgets;
+#[cfg(feature = "textinfo")]
+pub(crate) mod url;
+pub mod widgets;
+pub(crate) mod zoom;
 
-use crate::render::{Rgba, rgb};
+use std::{
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    backend::{Window, WindowEvent},
+    error::Error,
+    render::{Canvas, Rgba, rgb},
+    ui::widgets::{button::parse_mnemonic, text_input::TextInput},
+};
 
 // XKB keysym constants shared across dialog implementations
 pub(crate) const KEY_BACKSPACE: u32 = 0xff08;
@@ -32,13 +69,129 @@ pub(crate) const KEY_ISO_LEFT_TAB: u32 = 0xfe20;
 pub(crate) const KEY_LSHIFT: u32 = 0xffe1;
 pub(crate) const KEY_RSHIFT: u32 = 0xffe2;
 pub(crate) const KEY_SPACE: u32 = 0x20;
+pub(crate) const KEY_PLUS: u32 = 0x2b;
+pub(crate) const KEY_MINUS: u32 = 0x2d;
+pub(crate) const KEY_0: u32 = 0x30;
+pub(crate) const KEY_EQUAL: u32 = 0x3d;
+pub(crate) const KEY_KP_ADD: u32 = 0xffab;
+pub(crate) const KEY_KP_SUBTRACT: u32 = 0xffad;
+pub(crate) const KEY_KP_0: u32 = 0xffb0;
+pub(crate) const KEY_A: u32 = 0x61;
+pub(crate) const KEY_C: u32 = 0x63;
+pub(crate) const KEY_F: u32 = 0x66;
+pub(crate) const KEY_L: u32 = 0x6c;
+pub(crate) const KEY_V: u32 = 0x76;
+pub(crate) const KEY_X: u32 = 0x78;
+pub(crate) const KEY_Y: u32 = 0x79;
+pub(crate) const KEY_Z: u32 = 0x7a;
+/// Z with Shift held, as some keyboard layouts report Ctrl+Shift+Z.
+pub(crate) const KEY_CAPITAL_Z: u32 = 0x5a;
 
 // Shared layout constants (logical, at scale 1.0)
 pub(crate) const BASE_CORNER_RADIUS: f32 = 8.0;
 pub(crate) const BASE_BUTTON_HEIGHT: u32 = 32;
 pub(crate) const BASE_BUTTON_SPACING: u32 = 10;
 
+/// Waits for the next window event. With a deadline, polls instead of
+/// blocking and returns `None` once the deadline has passed.
+pub(crate) fn wait_for_event_until(
+    window: &mut impl Window,
+    deadline: Option<Instant>,
+) -> Result<Option<WindowEvent>, Error> {
+    let Some(deadline) = deadline else {
+        return window.wait_for_event().map(Some);
+    };
+    loop {
+        if Instant::now() >= deadline {
+            return Ok(None);
+        }
+        if let Some(event) = window.poll_for_event()? {
+            return Ok(Some(event));
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// How often [`read_stdin_lines`] checks whether it should stop.
+#[cfg(any(feature = "progress", feature = "textinfo"))]
+const STDIN_CHECK_MS: i32 = 100;
+
+/// Passes each line of stdin, newline and all, to `line` as it arrives.
+/// Returns true once stdin has closed and every line was taken, or false if
+/// `stop` was set or `line` returned false to stop early.
+#[cfg(any(feature = "progress", feature = "textinfo"))]
+pub(crate) fn read_stdin_lines(
+    stop: &std::sync::atomic::AtomicBool,
+    mut line: impl FnMut(&str) -> bool,
+) -> bool {
+    use std::sync::atomic::Ordering;
+
+    let mut pending = Vec::new();
+    let mut buf = [0u8; 4096];
+    while !stop.load(Ordering::Relaxed) {
+        // Wait for input with a timeout, so a stop is noticed
+        let mut fd = libc::pollfd {
+            fd: 0,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        if unsafe { libc::poll(&mut fd, 1, STDIN_CHECK_MS) } <= 0 {
+            continue;
+        }
+        let n = unsafe { libc::read(0, buf.as_mut_ptr().cast(), buf.len()) };
+        if n <= 0 {
+            if n < 0 && std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted {
+                continue;
+            }
+            break;
+        }
+        pending.extend_from_slice(&buf[..n as usize]);
+        while let Some(end) = pending.iter().position(|&b| b == b'\n') {
+            let text: Vec<u8> = pending.drain(..=end).collect();
+            if !line(&String::from_utf8_lossy(&text)) {
+                return false;
+            }
+        }
+    }
+    if stop.load(Ordering::Relaxed) {
+        return false;
+    }
+    // A last line without a newline still counts
+    pending.is_empty() || line(&String::from_utf8_lossy(&pending))
+}
+
+/// Carries out a text input's pending copy or paste. Returns true if a
+/// paste changed its text.
+pub(crate) fn sync_clipboard(
+    window: &mut impl Window,
+    input: &mut TextInput,
+) -> Result<bool, Error> {
+    if let Some(text) = input.take_copied() {
+        window.write_clipboard(&text)?;
+    }
+    if input.take_paste_request()
+        && let Some(text) = window.read_clipboard()?
+    {
+        input.paste(&text);
+        return Ok(true);
+    }
+    Ok(false)
+}
+
 /// Color theme for dialogs.
+///
+/// Builders take one by value or by reference, so a built-in theme can be
+/// adjusted at runtime:
+///
+/// ```no_run
+/// use zenity_rs::{Colors, THEME_LIGHT, rgb};
+///
+/// let brand = Colors {
+///     progress_fill: rgb(200, 60, 120),
+///     ..THEME_LIGHT
+/// };
+/// zenity_rs::progress().colors(brand).show().unwrap();
+/// ```
 #[derive(Debug, Clone, Copy)]
 pub struct Colors {
     pub window_bg: Rgba,
@@ -52,12 +205,16 @@ pub struct Colors {
     pub input_bg_focused: Rgba,
     pub input_border: Rgba,
     pub input_border_focused: Rgba,
+    /// Border of an input whose text isn't accepted.
+    pub input_border_invalid: Rgba,
     pub input_placeholder: Rgba,
     pub progress_bg: Rgba,
     pub progress_fill: Rgba,
     pub progress_border: Rgba,
     pub window_border: Rgba,
     pub window_shadow: Rgba,
+    /// Background of selected text.
+    pub selection: Rgba,
 }
 
 /// Light theme colors.
@@ -73,12 +230,14 @@ pub static THEME_LIGHT: Colors = Colors {
     input_bg_focused: rgb(255, 255, 255),
     input_border: rgb(200, 200, 200),
     input_border_focused: rgb(100, 150, 200),
+    input_border_invalid: rgb(210, 60, 60),
     input_placeholder: rgb(150, 150, 150),
     progress_bg: rgb(230, 230, 230),
     progress_fill: rgb(70, 140, 220),
     progress_border: rgb(200, 200, 200),
     window_border: rgb(180, 180, 180),
     window_shadow: Rgba::new(0, 0, 0, 50),
+    selection: rgb(179, 212, 252),
 };
 
 /// Dark theme colors.
@@ -94,17 +253,106 @@ pub static THEME_DARK: Colors = Colors {
     input_bg_focused: rgb(65, 65, 65),
     input_border: rgb(90, 90, 90),
     input_border_focused: rgb(100, 150, 200),
+    input_border_invalid: rgb(230, 90, 90),
     input_placeholder: rgb(120, 120, 120),
     progress_bg: rgb(60, 60, 60),
     progress_fill: rgb(70, 140, 220),
     progress_border: rgb(90, 90, 90),
     window_border: rgb(70, 70, 70),
     window_shadow: Rgba::new(0, 0, 0, 80),
+    selection: rgb(38, 79, 120),
 };
 
+/// Which colors dialogs use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Theme {
+    /// Whichever the system uses.
+    #[default]
+    Auto,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    /// Parses a `--theme` value: `light`, `dark` or `auto`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "auto" => Some(Theme::Auto),
+            "light" => Some(Theme::Light),
+            "dark" => Some(Theme::Dark),
+            _ => None,
+        }
+    }
+
+    /// The colors for this theme, detecting the system's for `Auto`.
+    pub fn colors(self) -> &'static Colors {
+        match self {
+            Theme::Auto => detect_theme(),
+            Theme::Light => &THEME_LIGHT,
+            Theme::Dark => &THEME_DARK,
+        }
+    }
+}
+
 /// Detect the current system theme.
 /// Returns dark theme if detection fails.
-pub fn detect_theme() -> &'static Colors {
+///
+/// Asks the desktop portal first, which follows GNOME, KDE and others; then
+/// looks at `GTK_THEME` and `gsettings`. The answer is kept for the rest of
+/// the process.
+pub(crate) fn detect_theme() -> &'static Colors {
+    static DETECTED: OnceLock<&'static Colors> = OnceLock::new();
+    DETECTED.get_or_init(|| {
+        let scheme = crate::dbus::Connection::session()
+            .ok()
+            .and_then(portal_color_scheme);
+        match scheme {
+            Some(COLOR_SCHEME_DARK) => &THEME_DARK,
+            Some(COLOR_SCHEME_LIGHT) => &THEME_LIGHT,
+            _ => detect_theme_fallback(),
+        }
+    })
+}
+
+/// `color-scheme` values of the portal's appearance settings; 0 means no
+/// preference.
+const COLOR_SCHEME_DARK: u32 = 1;
+const COLOR_SCHEME_LIGHT: u32 = 2;
+
+/// The `org.freedesktop.appearance` `color-scheme` setting, read from the
+/// XDG desktop portal over `connection`.
+fn portal_color_scheme(mut connection: crate::dbus::Connection) -> Option<u32> {
+    // Starting the portal can take a moment, but not a dialog's worth
+    connection.set_timeout(Some(Duration::from_secs(1))).ok()?;
+    let body = crate::dbus::Body::new()
+        .string("org.freedesktop.appearance")
+        .string("color-scheme");
+    let call = |connection: &mut crate::dbus::Connection, method| {
+        connection.call(
+            "org.freedesktop.portal.Desktop",
+            "/org/freedesktop/portal/desktop",
+            "org.freedesktop.portal.Settings",
+            method,
+            &body,
+        )
+    };
+    // ReadOne is newer; Read wraps the value in a second variant
+    let reply = call(&mut connection, "ReadOne")
+        .or_else(|_| call(&mut connection, "Read"))
+        .ok()?;
+    let mut reader = reply.reader();
+    let mut signature = reader.signature().ok()?;
+    while signature == "v" {
+        signature = reader.signature().ok()?;
+    }
+    if signature != "u" {
+        return None;
+    }
+    reader.u32().ok()
+}
+
+/// Detects the theme without the portal.
+fn detect_theme_fallback() -> &'static Colors {
     // Try to detect theme from environment
     if let Ok(theme) = std::env::var("GTK_THEME") {
         if theme.to_lowercase().contains("dark") {
@@ -152,6 +400,45 @@ impl Icon {
             other => Some(Icon::Custom(other.to_string())),
         }
     }
+
+    /// The freedesktop event sound that goes with this icon, for `--sound`.
+    pub fn sound_name(&self) -> Option<&'static str> {
+        match self {
+            Icon::Info => Some("dialog-information"),
+            Icon::Warning => Some("dialog-warning"),
+            Icon::Error => Some("dialog-error"),
+            Icon::Question => Some("dialog-question"),
+            Icon::Custom(_) => None,
+        }
+    }
+
+    /// Whether this is a custom icon naming an image file rather than an
+    /// icon theme name: it contains a `/`, ends in `.png` or names a file.
+    pub fn is_path(&self) -> bool {
+        let Icon::Custom(name) = self else {
+            return false;
+        };
+        name.contains('/')
+            || name.to_ascii_lowercase().ends_with(".png")
+            || std::path::Path::new(name).is_file()
+    }
+
+    /// Loads a custom icon naming a PNG file, scaled to `size` pixels square.
+    /// Returns `None` for built-in icons and names that aren't PNG files.
+    ///
+    /// Monochrome images (symbolic icons) are tinted with the theme's text
+    /// color so they stay visible on either theme, unless `recolor` is false.
+    pub fn load_image(&self, size: u32, colors: &Colors, recolor: bool) -> Option<Canvas> {
+        let Icon::Custom(path) = self else {
+            return None;
+        };
+        let data = std::fs::read(path).ok()?;
+        let mut image = Canvas::from_png(&data)?.scaled(size, size);
+        if recolor && image.is_monochrome() {
+            image.tint(colors.text);
+        }
+        Some(image)
+    }
 }
 
 /// Button presets for message dialogs.
@@ -167,38 +454,227 @@ pub enum ButtonPreset {
 }
 
 impl ButtonPreset {
+    /// The button labels, with GTK-style mnemonics: `_Yes` shows "Yes" and
+    /// is pressed with Y.
     pub fn labels(&self) -> Vec<String> {
         match self {
-            ButtonPreset::Ok => vec!["OK".to_string()],
-            ButtonPreset::OkCancel => vec!["OK".to_string(), "Cancel".to_string()],
-            ButtonPreset::YesNo => vec!["Yes".to_string(), "No".to_string()],
+            ButtonPreset::Ok => vec!["_OK".to_string()],
+            ButtonPreset::OkCancel => vec!["_OK".to_string(), "_Cancel".to_string()],
+            ButtonPreset::YesNo => vec!["_Yes".to_string(), "_No".to_string()],
             ButtonPreset::YesNoCancel => {
-                vec!["Yes".to_string(), "No".to_string(), "Cancel".to_string()]
+                vec!["_Yes".to_string(), "_No".to_string(), "_Cancel".to_string()]
             }
-            ButtonPreset::Close => vec!["Close".to_string()],
+            ButtonPreset::Close => vec!["_Close".to_string()],
             ButtonPreset::Empty => vec![],
             ButtonPreset::Custom(labels) => labels.clone(),
         }
     }
 }
 
+/// A message dialog's buttons: the preset's, then the extra ones, in the
+/// order they were added.
+///
+/// They're laid out right to left so the preset buttons end up at the right;
+/// converting between the two orders only happens here, so results always
+/// name the button that was actually clicked.
+#[derive(Debug, Clone)]
+pub struct ButtonOrder {
+    labels: Vec<String>,
+    preset_count: usize,
+}
+
+impl ButtonOrder {
+    pub fn new(preset: Vec<String>, extra: &[String]) -> Self {
+        let preset_count = preset.len();
+        let mut labels = preset;
+        labels.extend_from_slice(extra);
+        Self {
+            labels,
+            preset_count,
+        }
+    }
+
+    /// The labels from left to right, as laid out.
+    pub fn laid_out(&self) -> impl Iterator<Item = &str> {
+        self.labels.iter().rev().map(String::as_str)
+    }
+
+    /// Where the button added `index`th is laid out, counting from the left.
+    pub fn position(&self, index: usize) -> Option<usize> {
+        (index < self.labels.len()).then(|| self.labels.len() - 1 - index)
+    }
+
+    /// The outcome of choosing the button added `index`th.
+    pub fn outcome(&self, index: usize) -> DialogOutcome {
+        DialogOutcome {
+            result: DialogResult::from_index(index, self.preset_count),
+            label: self.labels.get(index).map(|label| parse_mnemonic(label).0),
+            checked: None,
+        }
+    }
+
+    /// The outcome of clicking the button at `position` from the left.
+    pub fn outcome_at(&self, position: usize) -> DialogOutcome {
+        self.outcome(self.labels.len().wrapping_sub(position + 1))
+    }
+}
+
+/// What a message dialog was answered with: the result, the label of the
+/// button chosen, if one was, and the checkbox state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DialogOutcome {
+    pub result: DialogResult,
+    /// As shown on the button, without mnemonic underscores; `None` if the
+    /// dialog was closed or timed out.
+    pub label: Option<String>,
+    /// The state of the [checkbox](crate::MessageBuilder::checkbox) when the
+    /// dialog closed; `None` if it had none.
+    pub checked: Option<bool>,
+}
+
+impl DialogOutcome {
+    /// Whether the button was one of the extra buttons rather than a preset.
+    pub fn is_extra(&self) -> bool {
+        matches!(self.result, DialogResult::Extra(_))
+    }
+
+    /// Same as the result's [`exit_code`](DialogResult::exit_code).
+    pub fn exit_code(&self) -> i32 {
+        self.result.exit_code()
+    }
+}
+
+impl From<DialogResult> for DialogOutcome {
+    /// An outcome without a button, such as [`DialogResult::Closed`].
+    fn from(result: DialogResult) -> Self {
+        Self {
+            result,
+            label: None,
+            checked: None,
+        }
+    }
+}
+
 /// Dialog result indicating which button was pressed.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DialogResult {
+    /// A preset button, by its index in [`ButtonPreset::labels`].
     Button(usize),
+    /// An extra button, by the order it was added in.
+    Extra(usize),
     Closed,
     Timeout,
 }
 
 impl DialogResult {
+    /// The result for the button at `index` when the dialog shows
+    /// `preset_count` preset buttons followed by its extra buttons.
+    pub fn from_index(index: usize, preset_count: usize) -> Self {
+        if index < preset_count {
+            DialogResult::Button(index)
+        } else {
+            DialogResult::Extra(index - preset_count)
+        }
+    }
+
+    /// The exit code the command-line tool uses for this result: the index
+    /// of a preset button (3 past the third), 1 for extra buttons or when
+    /// closed, 5 on timeout.
     pub fn exit_code(self) -> i32 {
         match self {
             DialogResult::Button(0) => 0,
             DialogResult::Button(1) => 1,
             DialogResult::Button(2) => 2,
             DialogResult::Button(_) => 3, // Additional buttons
+            DialogResult::Extra(_) => 1,
             DialogResult::Timeout => 5,
             DialogResult::Closed => 1,
         }
     }
 }
+
+/// Reading the portal's color scheme from a stand-in session bus that
+/// answers "prefer light".
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        os::{
+            linux::net::SocketAddrExt,
+            unix::net::{SocketAddr, UnixListener, UnixStream},
+        },
+    };
+
+    use super::*;
+
+    /// Reads one message and returns its serial and raw bytes.
+    fn read_message(stream: &mut UnixStream) -> (u32, Vec<u8>) {
+        let mut head = [0u8; 16];
+        stream.read_exact(&mut head).unwrap();
+        let u32_at = |i: usize| u32::from_le_bytes(head[i..i + 4].try_into().unwrap());
+        let (body_len, serial, fields_len) = (u32_at(4), u32_at(8), u32_at(12));
+        let rest = (16 + fields_len as usize).next_multiple_of(8) - 16 + body_len as usize;
+        let mut message = vec![0u8; rest];
+        stream.read_exact(&mut message).unwrap();
+        (serial, message)
+    }
+
+    /// A method return for `serial` with a body of signature `signature`.
+    fn reply(serial: u32, signature: &str, body: &[u8]) -> Vec<u8> {
+        let mut fields = vec![5, 1, b'u', 0];
+        fields.extend_from_slice(&serial.to_le_bytes());
+        if !signature.is_empty() {
+            fields.extend_from_slice(&[8, 1, b'g', 0, signature.len() as u8]);
+            fields.extend_from_slice(signature.as_bytes());
+            fields.push(0);
+        }
+        let mut message = vec![b'l', 2, 0, 1];
+        message.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        message.extend_from_slice(&1u32.to_le_bytes());
+        message.extend_from_slice(&(fields.len() as u32).to_le_bytes());
+        message.extend_from_slice(&fields);
+        message.resize(message.len().next_multiple_of(8), 0);
+        message.extend_from_slice(body);
+        message
+    }
+
+    /// Accepts one client and answers its calls until it hangs up.
+    fn serve(listener: UnixListener) {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut line = Vec::new();
+        let mut byte = [0u8];
+        while !line.ends_with(b"\r\n") {
+            stream.read_exact(&mut byte).unwrap();
+            line.push(byte[0]);
+        }
+        stream.write_all(b"OK 0123456789abcdef\r\n").unwrap();
+        let mut begin = [0u8; 7];
+        stream.read_exact(&mut begin).unwrap();
+        assert_eq!(&begin, b"BEGIN\r\n");
+
+        let (serial, _) = read_message(&mut stream);
+        stream.write_all(&reply(serial, "", &[])).unwrap();
+
+        let (serial, message) = read_message(&mut stream);
+        let text = String::from_utf8_lossy(&message);
+        assert!(text.contains("org.freedesktop.portal.Settings"), "{text}");
+        assert!(text.contains("color-scheme"), "{text}");
+        // A variant holding the u32 2, "prefer light"
+        stream
+            .write_all(&reply(serial, "v", &[1, b'u', 0, 0, 2, 0, 0, 0]))
+            .unwrap();
+    }
+
+    #[test]
+    fn portal_reports_prefer_light() {
+        // Abstract, so there is no socket file to clean up
+        let name = format!("zenity-rs-bus-{}", std::process::id());
+        let addr = SocketAddr::from_abstract_name(&name).unwrap();
+        let listener = UnixListener::bind_addr(&addr).unwrap();
+        let server = std::thread::spawn(move || serve(listener));
+
+        let connection = crate::dbus::Connection::open(&format!("unix:abstract={name}")).unwrap();
+        assert_eq!(portal_color_scheme(connection), Some(COLOR_SCHEME_LIGHT));
+        server.join().unwrap();
+    }
+}
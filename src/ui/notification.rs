@@ -0,0 +1,180 @@
+//! Desktop notifications through the freedesktop notification service.
+
+use std::{collections::HashSet, io::BufRead};
+
+use crate::{
+    dbus::{Body, Connection},
+    error::Error,
+    ui::listen::{Command, parse_line},
+};
+
+const SERVICE: &str = "org.freedesktop.Notifications";
+const PATH: &str = "/org/freedesktop/Notifications";
+const APP_NAME: &str = "zenity-rs";
+
+/// Notification builder.
+pub struct NotificationBuilder {
+    text: String,
+    icon: String,
+    timeout: Option<u32>,
+}
+
+impl NotificationBuilder {
+    pub fn new() -> Self {
+        Self {
+            text: String::new(),
+            icon: String::new(),
+            timeout: None,
+        }
+    }
+
+    /// Set the text. The first line is the summary, the rest the body.
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = text.to_string();
+        self
+    }
+
+    /// Set the icon name (or path).
+    pub fn icon(mut self, icon: &str) -> Self {
+        self.icon = icon.to_string();
+        self
+    }
+
+    /// Set how long the notification stays up, in seconds. By default the
+    /// notification server decides.
+    pub fn timeout(mut self, seconds: u32) -> Self {
+        self.timeout = Some(seconds);
+        self
+    }
+
+    /// Sends the notification and returns.
+    pub fn show(self) -> Result<(), Error> {
+        let mut notifier = Notifier::new(self)?;
+        notifier.show()
+    }
+
+    /// Shows the text (if any), then updates the notification from `--listen`
+    /// commands read from `input` until it reaches EOF.
+    ///
+    /// Fails if the notification service goes away in the meantime.
+    pub fn listen(self, input: impl BufRead) -> Result<(), Error> {
+        let mut notifier = Notifier::new(self)?;
+        if !notifier.summary.is_empty() {
+            notifier.show()?;
+        }
+
+        let mut warned = HashSet::new();
+        for line in input.lines() {
+            let Some(command) = parse_line(&line?) else {
+                continue;
+            };
+            match command {
+                Command::Message(text) => {
+                    (notifier.summary, notifier.body) = split_message(&text);
+                    notifier.show()?;
+                }
+                Command::Tooltip(text) => {
+                    notifier.summary = text;
+                    notifier.body.clear();
+                    notifier.show()?;
+                }
+                Command::Icon(icon) => {
+                    notifier.icon = icon;
+                    if notifier.visible {
+                        notifier.show()?;
+                    }
+                }
+                Command::Visible(true) => {
+                    if !notifier.visible && !notifier.summary.is_empty() {
+                        notifier.show()?;
+                    }
+                }
+                Command::Visible(false) => notifier.close()?,
+                Command::Unknown(name) => {
+                    if warned.insert(name.clone()) {
+                        eprintln!("zenity-rs: warning: unknown --listen command {name:?}");
+                    }
+                }
+                Command::Malformed => {
+                    if warned.insert(String::new()) {
+                        eprintln!(
+                            "zenity-rs: warning: ignoring --listen input without a \"command:\" prefix"
+                        );
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for NotificationBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One notification, replaced in place on every update.
+struct Notifier {
+    connection: Connection,
+    /// Server-assigned ID; 0 until the first notification is sent.
+    id: u32,
+    summary: String,
+    body: String,
+    icon: String,
+    expire_timeout: i32,
+    visible: bool,
+}
+
+impl Notifier {
+    fn new(builder: NotificationBuilder) -> Result<Self, Error> {
+        let (summary, body) = split_message(&builder.text);
+        Ok(Self {
+            connection: Connection::session()?,
+            id: 0,
+            summary,
+            body,
+            icon: builder.icon,
+            expire_timeout: builder
+                .timeout
+                .map_or(-1, |secs| secs.saturating_mul(1000) as i32),
+            visible: false,
+        })
+    }
+
+    fn show(&mut self) -> Result<(), Error> {
+        let body = Body::new()
+            .string(APP_NAME)
+            .u32(self.id)
+            .string(&self.icon)
+            .string(&self.summary)
+            .string(&self.body)
+            .string_array(&[])
+            .string_dict(&[])
+            .i32(self.expire_timeout);
+        let reply = self
+            .connection
+            .call(SERVICE, PATH, SERVICE, "Notify", &body)?;
+        self.id = reply.reader().u32()?;
+        self.visible = true;
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<(), Error> {
+        if self.visible {
+            let body = Body::new().u32(self.id);
+            self.connection
+                .call(SERVICE, PATH, SERVICE, "CloseNotification", &body)?;
+            self.visible = false;
+        }
+        Ok(())
+    }
+}
+
+/// Splits text into a summary (the first line) and body (the rest).
+fn split_message(text: &str) -> (String, String) {
+    match text.split_once('\n') {
+        Some((summary, body)) => (summary.to_string(), body.to_string()),
+        None => (text.to_string(), String::new()),
+    }
+}
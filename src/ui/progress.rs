@@ -1,22 +1,30 @@
 //! Progress dialog implementation.
 
 use std::{
-    io::{BufRead, BufReader},
-    sync::mpsc::{self, TryRecvError},
+    borrow::Borrow,
+    collections::VecDeque,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender, TryRecvError},
+    },
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 #[cfg(unix)]
-use libc::{SIGTERM, getppid, kill};
+use libc::{SIGHUP, getppid, kill};
 
 use crate::{
     backend::{Window, WindowEvent, create_window},
     error::Error,
     render::{Canvas, Font},
     ui::{
-        BASE_BUTTON_SPACING, BASE_CORNER_RADIUS, Colors,
+        BASE_BUTTON_SPACING, BASE_CORNER_RADIUS, Colors, KEY_ESCAPE, KEY_KP_ENTER, KEY_RETURN,
+        geometry::Offset,
+        read_stdin_lines,
         widgets::{Widget, button::Button, progress_bar::ProgressBar},
+        zoom,
     },
 };
 
@@ -24,6 +32,19 @@ const BASE_PADDING: u32 = 20;
 const BASE_BAR_WIDTH: u32 = 300;
 const BASE_TEXT_HEIGHT: u32 = 20;
 const BASE_BUTTON_HEIGHT: u32 = 32;
+/// Space between a stacked bar's label and the bar.
+const BASE_LABEL_GAP: u32 = 4;
+/// Space between stacked bars.
+const BASE_BAR_GAP: u32 = 12;
+/// Default quiet period before the dialog shows it is waiting.
+const DEFAULT_STALL_TIMEOUT: u32 = 10;
+/// How many recent updates the time remaining is averaged over.
+const ESTIMATE_WINDOW: usize = 10;
+/// Updates needed before there's an estimate to show.
+const MIN_ESTIMATE_SAMPLES: usize = 3;
+/// Shortest time between changes to the shown estimate, so it doesn't flicker.
+const ESTIMATE_REFRESH: Duration = Duration::from_secs(1);
+const ESTIMATING: &str = "Estimating time remaining…";
 
 /// Progress dialog result.
 #[derive(Debug, Clone)]
@@ -34,26 +55,264 @@ pub enum ProgressResult {
     Cancelled,
     /// Dialog was closed.
     Closed,
+    /// The timeout passed.
+    Timeout,
 }
 
 impl ProgressResult {
+    /// The exit code the command-line tool uses for this result: 0 when
+    /// the progress completes, 1 when cancelled or closed, 5 on timeout.
     pub fn exit_code(&self) -> i32 {
         match self {
             ProgressResult::Completed => 0,
             ProgressResult::Cancelled => 1,
             ProgressResult::Closed => 1,
+            ProgressResult::Timeout => 5,
+        }
+    }
+}
+
+/// Tracks whether progress updates have stopped arriving.
+///
+/// Times are passed in rather than read from the clock, so the dialog's
+/// event loop and tests drive it the same way.
+#[derive(Debug, Clone)]
+pub(crate) struct StallDetector {
+    timeout: Option<Duration>,
+    last_update: Option<Instant>,
+    stalled: bool,
+    finished: bool,
+}
+
+impl StallDetector {
+    /// A zero `timeout` disables stall detection.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout: (!timeout.is_zero()).then_some(timeout),
+            last_update: None,
+            stalled: false,
+            finished: false,
         }
     }
+
+    /// Records an update received at `now`. Returns true if it ended a stall.
+    pub fn update(&mut self, now: Instant) -> bool {
+        self.last_update = Some(now);
+        std::mem::take(&mut self.stalled)
+    }
+
+    /// Marks the work as finished, after which the detector never stalls.
+    /// Returns true if it ended a stall.
+    pub fn finish(&mut self) -> bool {
+        self.finished = true;
+        std::mem::take(&mut self.stalled)
+    }
+
+    /// Re-evaluates at `now`. Returns true if the stalled state changed.
+    pub fn check(&mut self, now: Instant) -> bool {
+        let stalled = !self.finished && self.deadline().is_some_and(|deadline| now >= deadline);
+        let changed = stalled != self.stalled;
+        self.stalled = stalled;
+        changed
+    }
+
+    pub fn is_stalled(&self) -> bool {
+        self.stalled
+    }
+
+    /// When the current quiet period turns into a stall. `None` before the
+    /// first update, since slow starts are normal.
+    pub fn deadline(&self) -> Option<Instant> {
+        Some(self.last_update? + self.timeout?)
+    }
+}
+
+/// Estimates the time left from the rate of the last few percentage
+/// updates, for the line under the progress bar.
+///
+/// Times are passed in like [`StallDetector`]'s. The shown line changes at
+/// most once a second.
+#[derive(Debug, Clone)]
+pub(crate) struct TimeEstimator {
+    samples: VecDeque<(Instant, u32)>,
+    text: String,
+    shown_at: Option<Instant>,
+}
+
+impl TimeEstimator {
+    pub fn new() -> Self {
+        Self {
+            samples: VecDeque::new(),
+            text: ESTIMATING.to_string(),
+            shown_at: None,
+        }
+    }
+
+    /// Records that `percentage` was reached at `now`. Returns true if the
+    /// shown line changed.
+    pub fn update(&mut self, now: Instant, percentage: u32) -> bool {
+        // Going backwards starts a new stretch of work
+        if self
+            .samples
+            .back()
+            .is_some_and(|&(_, last)| percentage < last)
+        {
+            self.samples.clear();
+        }
+        self.samples.push_back((now, percentage));
+        if self.samples.len() > ESTIMATE_WINDOW {
+            self.samples.pop_front();
+        }
+        if percentage >= 100 {
+            self.shown_at = Some(now);
+            return !std::mem::take(&mut self.text).is_empty();
+        }
+        self.refresh(now)
+    }
+
+    /// Brings the shown line up to date at `now`, unless it changed less
+    /// than a second ago. Returns true if it changed.
+    pub fn refresh(&mut self, now: Instant) -> bool {
+        if self
+            .shown_at
+            .is_some_and(|at| now.duration_since(at) < ESTIMATE_REFRESH)
+        {
+            return false;
+        }
+        let text = match self.remaining() {
+            Some(remaining) => format_time_remaining(remaining),
+            None if self.samples.back().is_some_and(|&(_, last)| last >= 100) => String::new(),
+            None => ESTIMATING.to_string(),
+        };
+        if text == self.text {
+            return false;
+        }
+        self.text = text;
+        self.shown_at = Some(now);
+        true
+    }
+
+    /// The time left at the average rate of the recent updates, or `None`
+    /// while there are too few of them to tell or the work is done.
+    pub fn remaining(&self) -> Option<Duration> {
+        if self.samples.len() < MIN_ESTIMATE_SAMPLES {
+            return None;
+        }
+        let (&(start, from), &(end, to)) = (self.samples.front()?, self.samples.back()?);
+        if to <= from || to >= 100 {
+            return None;
+        }
+        let per_percent = end.duration_since(start).as_secs_f64() / (to - from) as f64;
+        Some(Duration::from_secs_f64(per_percent * (100 - to) as f64))
+    }
+
+    /// The line to show: the estimate, "Estimating time remaining…" until
+    /// there is one, or nothing once the work is done.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Default for TimeEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Words a time remaining roughly, as in "About 2 minutes remaining".
+pub(crate) fn format_time_remaining(remaining: Duration) -> String {
+    let seconds = remaining.as_secs_f64();
+    let plural = |n: u64, unit: &str| {
+        format!(
+            "About {n} {unit}{} remaining",
+            if n == 1 { "" } else { "s" }
+        )
+    };
+    if seconds < 10.0 {
+        "A few seconds remaining".to_string()
+    } else if seconds < 55.0 {
+        // To the nearest five seconds
+        plural(((seconds / 5.0).round() * 5.0) as u64, "second")
+    } else if seconds < 59.5 * 60.0 {
+        plural((seconds / 60.0).round().max(1.0) as u64, "minute")
+    } else {
+        plural((seconds / 3600.0).round().max(1.0) as u64, "hour")
+    }
 }
 
-/// Message from stdin reader thread.
+/// Message from stdin reader thread, for the bar at the index.
 enum StdinMessage {
-    Progress(u32),
-    Text(String),
-    Pulsate,
+    Progress(usize, u32),
+    Text(usize, String),
+    Pulsate(usize),
     Done,
 }
 
+/// Splits the bar number off a line of input for a dialog with `bars`
+/// bars, as in `2:45` or `2:# Extracting`. Returns the zero-based bar and
+/// the rest of the line, or `None` if the number names no bar.
+///
+/// Lines without a number are for the first bar, and a single bar takes
+/// every line as it is.
+pub(crate) fn split_bar_index(line: &str, bars: usize) -> Option<(usize, &str)> {
+    let line = line.trim();
+    if bars > 1
+        && let Some((index, rest)) = line.split_once(':')
+        && let Ok(index) = index.trim().parse::<usize>()
+    {
+        return (1..=bars)
+            .contains(&index)
+            .then(|| (index - 1, rest.trim()));
+    }
+    Some((0, line))
+}
+
+/// Reads progress commands from stdin and sends them on until stdin closes,
+/// the dialog stops listening, or `stop` is set.
+fn pump_stdin(tx: &Sender<StdinMessage>, stop: &AtomicBool, bars: usize) {
+    if read_stdin_lines(stop, |line| send_line(tx, line, bars)) {
+        let _ = tx.send(StdinMessage::Done);
+    }
+}
+
+/// Sends the command on one line of stdin, if it is one. Returns false once
+/// the dialog has stopped listening.
+fn send_line(tx: &Sender<StdinMessage>, line: &str, bars: usize) -> bool {
+    let Some((bar, trimmed)) = split_bar_index(line, bars) else {
+        return true;
+    };
+    let message = if let Some(text) = trimmed.strip_prefix('#') {
+        // Status text update
+        StdinMessage::Text(bar, text.trim().to_string())
+    } else if trimmed.eq_ignore_ascii_case("pulsate") {
+        StdinMessage::Pulsate(bar)
+    } else if let Ok(num) = trimmed.parse::<u32>() {
+        StdinMessage::Progress(bar, num.min(100))
+    } else {
+        return true;
+    };
+    tx.send(message).is_ok()
+}
+
+fn bar_percentage(bar: &ProgressBar) -> u32 {
+    (bar.progress() * 100.0).round() as u32
+}
+
+/// The mean of the bars' percentages, rounded down so it only reaches 100
+/// once they all do.
+fn total_percentage(bars: &[ProgressBar]) -> u32 {
+    bars.iter().map(bar_percentage).sum::<u32>() / bars.len().max(1) as u32
+}
+
+/// Hangs up the parent process, usually the shell feeding the dialog, so an
+/// `--auto-kill` cancel stops the work too.
+fn hang_up_parent() {
+    #[cfg(unix)]
+    unsafe {
+        kill(getppid(), SIGHUP);
+    }
+}
+
 /// Progress dialog builder.
 pub struct ProgressBuilder {
     title: String,
@@ -64,9 +323,17 @@ pub struct ProgressBuilder {
     auto_kill: bool,
     no_cancel: bool,
     show_time_remaining: bool,
+    stall_timeout: u32,
     width: Option<u32>,
     height: Option<u32>,
-    colors: Option<&'static Colors>,
+    timeout: Option<u32>,
+    position: Option<(Offset, Offset)>,
+    colors: Option<Colors>,
+    multi: usize,
+    /// Each bar's percentage, pulsing and label, carried over a zoom rebuild.
+    bar_states: Vec<(u32, bool, String)>,
+    /// The time remaining estimate, carried over a zoom rebuild.
+    time_estimate: Option<TimeEstimator>,
 }
 
 impl ProgressBuilder {
@@ -80,9 +347,15 @@ impl ProgressBuilder {
             auto_kill: false,
             no_cancel: false,
             show_time_remaining: false,
+            stall_timeout: DEFAULT_STALL_TIMEOUT,
             width: None,
             height: None,
+            timeout: None,
+            position: None,
             colors: None,
+            multi: 1,
+            bar_states: Vec::new(),
+            time_estimate: None,
         }
     }
 
@@ -101,23 +374,27 @@ impl ProgressBuilder {
         self
     }
 
+    /// Start with a sliding indeterminate bar, until a percentage arrives.
     pub fn pulsate(mut self, pulsate: bool) -> Self {
         self.pulsate = pulsate;
         self
     }
 
+    /// Finish as soon as the progress reaches 100, without waiting for OK.
     pub fn auto_close(mut self, auto_close: bool) -> Self {
         self.auto_close = auto_close;
         self
     }
 
+    /// On Cancel (the button or Escape, not closing the window), send SIGHUP
+    /// to the process that started the dialog, like zenity.
     pub fn auto_kill(mut self, auto_kill: bool) -> Self {
         self.auto_kill = auto_kill;
         self
     }
 
-    pub fn colors(mut self, colors: &'static Colors) -> Self {
-        self.colors = Some(colors);
+    pub fn colors(mut self, colors: impl Borrow<Colors>) -> Self {
+        self.colors = Some(*colors.borrow());
         self
     }
 
@@ -131,39 +408,118 @@ impl ProgressBuilder {
         self
     }
 
+    /// Set timeout in seconds. Dialog will auto-close after this time.
+    pub fn timeout(mut self, seconds: u32) -> Self {
+        self.timeout = Some(seconds);
+        self
+    }
+
+    /// Request a window position. Ignored where the platform doesn't allow it.
+    pub fn position(mut self, x: Offset, y: Offset) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    /// Remove the Cancel button. Escape and closing the window do nothing.
     pub fn no_cancel(mut self, no_cancel: bool) -> Self {
         self.no_cancel = no_cancel;
         self
     }
 
+    /// Show an estimate of the time left under the bar, from the rate of the
+    /// recent percentage updates.
     pub fn time_remaining(mut self, show_time_remaining: bool) -> Self {
         self.show_time_remaining = show_time_remaining;
         self
     }
 
-    pub fn show(self) -> Result<ProgressResult, Error> {
-        let colors = self.colors.unwrap_or_else(|| crate::ui::detect_theme());
+    /// Show that the dialog is waiting once no update has arrived for this
+    /// many seconds (default 10). Zero disables it.
+    pub fn stall_timeout(mut self, seconds: u32) -> Self {
+        self.stall_timeout = seconds;
+        self
+    }
+
+    /// Stack this many labeled bars above a total bar showing their mean.
+    /// Input lines pick a bar by number, as in `2:45` or `2:# Extracting`,
+    /// and lines without one update the first bar. With
+    /// [`auto_close`](Self::auto_close), the dialog waits for every bar.
+    pub fn multi(mut self, bars: usize) -> Self {
+        self.multi = bars.max(1);
+        self
+    }
+
+    pub fn show(mut self) -> Result<ProgressResult, Error> {
+        // Start stdin reader thread
+        let (tx, rx) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let pump = thread::spawn({
+            let stop = stop.clone();
+            let bars = self.multi;
+            move || pump_stdin(&tx, &stop, bars)
+        });
+
+        let deadline = self
+            .timeout
+            .map(|secs| Instant::now() + Duration::from_secs(secs as u64));
+        let result = loop {
+            match self.run(&rx, deadline) {
+                Ok(None) => continue,
+                Ok(Some(result)) => break Ok(result),
+                Err(e) => break Err(e),
+            }
+        };
+
+        // However the dialog ended, even with the display gone, the reader
+        // stops rather than outliving it
+        stop.store(true, Ordering::Relaxed);
+        let _ = pump.join();
+        result
+    }
+
+    /// Shows the dialog until it's answered, or returns `None` to be run
+    /// again after a [zoom](crate::ui::zoom) change.
+    fn run(
+        &mut self,
+        rx: &Receiver<StdinMessage>,
+        deadline: Option<Instant>,
+    ) -> Result<Option<ProgressResult>, Error> {
+        let colors = &self.colors.unwrap_or_else(|| *crate::ui::detect_theme());
 
         // First pass: calculate LOGICAL dimensions using scale 1.0
         let temp_font = Font::load(1.0);
+        let temp_ok = Button::new("OK", &temp_font, 1.0);
         let temp_button = Button::new("Cancel", &temp_font, 1.0);
         let temp_bar = ProgressBar::new(BASE_BAR_WIDTH, 1.0);
 
         let calc_width = BASE_BAR_WIDTH + BASE_PADDING * 2;
+        let min_width =
+            temp_ok.width() + BASE_BUTTON_SPACING + temp_button.width() + BASE_PADDING * 2;
         let time_remaining_height = if self.show_time_remaining { 24 } else { 0 };
+        // Stacked bars add a total bar, and each has a label above it
+        let stacked = self.multi > 1;
+        let bar_count = if stacked { self.multi + 1 } else { 1 };
+        let bars_height = if stacked {
+            let rows = bar_count as u32;
+            rows * (BASE_TEXT_HEIGHT + BASE_LABEL_GAP + temp_bar.height())
+                + (rows - 1) * BASE_BAR_GAP
+        } else {
+            temp_bar.height()
+        };
         let calc_height = BASE_PADDING * 3
             + BASE_TEXT_HEIGHT
             + time_remaining_height
             + 10
-            + temp_bar.height()
+            + bars_height
             + 10
             + BASE_BUTTON_HEIGHT;
         drop(temp_font);
+        drop(temp_ok);
         drop(temp_button);
 
-        // Use custom dimensions if provided, otherwise use calculated defaults
-        let logical_width = self.width.unwrap_or(calc_width) as u16;
-        let logical_height = self.height.unwrap_or(calc_height) as u16;
+        // Use custom dimensions if provided, but never smaller than the content
+        let logical_width = self.width.map_or(calc_width, |w| w.max(min_width)) as u16;
+        let logical_height = self.height.map_or(calc_height, |h| h.max(calc_height)) as u16;
 
         // Create window with LOGICAL dimensions
         let mut window = create_window(logical_width, logical_height)?;
@@ -176,8 +532,26 @@ impl ProgressBuilder {
         // Get the actual scale factor from the window (compositor scale)
         let scale = window.scale_factor();
 
+        // Each bar's state, from the builder or the last run before a zoom
+        let bar_states = if self.bar_states.is_empty() {
+            (0..self.multi)
+                .map(|i| {
+                    match i {
+                        0 => (self.percentage, self.pulsate, String::new()),
+                        _ => (0, false, String::new()),
+                    }
+                })
+                .collect()
+        } else {
+            std::mem::take(&mut self.bar_states)
+        };
+        let finished = bar_states.iter().all(|&(p, _, _)| p >= 100);
+
         // Now create everything at PHYSICAL scale
         let font = Font::load(scale);
+        // OK stays disabled until the progress reaches 100% or stdin is closed
+        let mut ok_button = Button::new("OK", &font, scale);
+        ok_button.set_enabled(finished);
         let mut cancel_button = if self.no_cancel {
             None
         } else {
@@ -186,84 +560,89 @@ impl ProgressBuilder {
 
         // Scale dimensions for physical rendering
         let padding = (BASE_PADDING as f32 * scale) as u32;
-        let bar_width = (BASE_BAR_WIDTH as f32 * scale) as u32;
         let text_height = (BASE_TEXT_HEIGHT as f32 * scale) as u32;
 
         // Calculate physical dimensions
         let physical_width = (logical_width as f32 * scale) as u32;
         let physical_height = (logical_height as f32 * scale) as u32;
+        let bar_width = physical_width - padding * 2;
 
-        // Create progress bar at physical scale
-        let mut progress_bar = ProgressBar::new(bar_width, scale);
-        progress_bar.set_percentage(self.percentage);
-        if self.pulsate {
-            progress_bar.set_pulsating(true);
+        // Create the bars at physical scale, the total last when stacked
+        let bar_total = self.multi;
+        let mut bars: Vec<ProgressBar> = (0..bar_count)
+            .map(|_| ProgressBar::new(bar_width, scale))
+            .collect();
+        let mut labels = Vec::new();
+        for (bar, (percentage, pulsate, label)) in bars.iter_mut().zip(bar_states) {
+            bar.set_percentage(percentage);
+            if pulsate {
+                bar.set_pulsating(true);
+            }
+            labels.push(label);
+        }
+        if stacked {
+            let total = total_percentage(&bars[..bar_total]);
+            bars[bar_total].set_percentage(total);
+            labels.push("Total".to_string());
+        } else {
+            labels.clear();
         }
 
         // Current status text
         let mut status_text = self.text.clone();
 
         // Time remaining calculation
-        let start_time = std::time::Instant::now();
-        let mut time_remaining_text = String::new();
+        let mut stall = StallDetector::new(Duration::from_secs(self.stall_timeout as u64));
+        if finished {
+            stall.finish();
+        }
+        let mut estimate = self.time_estimate.take().unwrap_or_default();
+        if finished {
+            estimate.update(Instant::now(), 100);
+        }
 
         // Position elements in physical coordinates
         let text_y = padding as i32;
         let time_remaining_offset = if self.show_time_remaining { 24 } else { 0 };
-        let bar_y = text_y + text_height as i32 + 10 + time_remaining_offset;
-        progress_bar.set_position(padding as i32, bar_y);
+        let label_gap = (BASE_LABEL_GAP as f32 * scale) as i32;
+        let bar_gap = (BASE_BAR_GAP as f32 * scale) as i32;
+        let mut bar_y = text_y + text_height as i32 + 10 + time_remaining_offset;
+        let mut label_ys = Vec::new();
+        for (i, bar) in bars.iter_mut().enumerate() {
+            if i > 0 {
+                bar_y += bar.height() as i32 + bar_gap;
+            }
+            if stacked {
+                label_ys.push(bar_y);
+                bar_y += text_height as i32 + label_gap;
+            }
+            bar.set_position(padding as i32, bar_y);
+        }
 
         let button_y =
-            bar_y + progress_bar.height() as i32 + (BASE_BUTTON_SPACING as f32 * scale) as i32;
+            bar_y + bars[0].height() as i32 + (BASE_BUTTON_SPACING as f32 * scale) as i32;
+        let mut button_x = physical_width as i32 - padding as i32;
         if let Some(ref mut cancel_button) = cancel_button {
-            let button_x = physical_width as i32 - padding as i32 - cancel_button.width() as i32;
+            button_x -= cancel_button.width() as i32;
             cancel_button.set_position(button_x, button_y);
+            button_x -= (BASE_BUTTON_SPACING as f32 * scale) as i32;
         }
+        button_x -= ok_button.width() as i32;
+        ok_button.set_position(button_x, button_y);
 
         // Create canvas at PHYSICAL dimensions
         let mut canvas = Canvas::new(physical_width, physical_height);
 
-        // Start stdin reader thread
-        let (tx, rx) = mpsc::channel();
-        thread::spawn(move || {
-            let stdin = std::io::stdin();
-            let reader = BufReader::new(stdin.lock());
-
-            for line in reader.lines() {
-                let line = match line {
-                    Ok(l) => l,
-                    Err(_) => break,
-                };
-
-                let trimmed = line.trim();
-
-                if let Some(text) = trimmed.strip_prefix('#') {
-                    // Status text update
-                    let text = text.trim().to_string();
-                    if tx.send(StdinMessage::Text(text)).is_err() {
-                        break;
-                    }
-                } else if trimmed.eq_ignore_ascii_case("pulsate") {
-                    if tx.send(StdinMessage::Pulsate).is_err() {
-                        break;
-                    }
-                } else if let Ok(num) = trimmed.parse::<u32>() {
-                    if tx.send(StdinMessage::Progress(num.min(100))).is_err() {
-                        break;
-                    }
-                }
-            }
-
-            let _ = tx.send(StdinMessage::Done);
-        });
-
         // Draw function
         let draw = |canvas: &mut Canvas,
                     colors: &Colors,
                     font: &Font,
                     status_text: &str,
                     time_remaining_text: &str,
-                    progress_bar: &ProgressBar,
+                    bars: &[ProgressBar],
+                    labels: &[String],
+                    label_ys: &[i32],
+                    ok_button: &Button,
                     cancel_button: &Option<Button>,
                     padding: u32,
                     text_y: i32,
@@ -283,6 +662,17 @@ impl ProgressBuilder {
             );
 
             // Draw status text
+            let waiting;
+            let status_text = if bars[0].is_stalled() {
+                waiting = if status_text.is_empty() {
+                    "(waiting…)".to_string()
+                } else {
+                    format!("{status_text} (waiting…)")
+                };
+                &waiting
+            } else {
+                status_text
+            };
             if !status_text.is_empty() {
                 let text_canvas = font.render(status_text).with_color(colors.text).finish();
                 canvas.draw_canvas(&text_canvas, padding as i32, text_y);
@@ -302,38 +692,35 @@ impl ProgressBuilder {
                 canvas.draw_canvas(&text_canvas, padding as i32, time_remaining_y);
             }
 
-            // Draw progress bar
-            progress_bar.draw(canvas, colors);
+            // Draw the bars, with the labels of stacked ones
+            for (label, &y) in labels.iter().zip(label_ys) {
+                if !label.is_empty() {
+                    let text_canvas = font.render(label).with_color(colors.text).finish();
+                    canvas.draw_canvas(&text_canvas, padding as i32, y);
+                }
+            }
+            for bar in bars {
+                bar.draw(canvas, colors);
+            }
 
-            // Draw cancel button
+            // Draw buttons
+            ok_button.draw_to(canvas, colors, font);
             if let Some(button) = cancel_button {
                 button.draw_to(canvas, colors, font);
             }
         };
 
-        let format_time_remaining = |seconds: f64| -> String {
-            if seconds < 60.0 {
-                format!("{:.0}s remaining", seconds)
-            } else if seconds < 3600.0 {
-                let mins = (seconds / 60.0).floor();
-                let secs = seconds % 60.0;
-                format!("{:.0}m {:.0}s remaining", mins, secs)
-            } else {
-                let hours = (seconds / 3600.0).floor();
-                let mins = ((seconds % 3600.0) / 60.0).floor();
-                let secs = seconds % 60.0;
-                format!("{:.0}h {:.0}m {:.0}s remaining", hours, mins, secs)
-            }
-        };
-
         // Initial draw
         draw(
             &mut canvas,
             colors,
             &font,
             &status_text,
-            &time_remaining_text,
-            &progress_bar,
+            estimate.text(),
+            &bars,
+            &labels,
+            &label_ys,
+            &ok_button,
             &cancel_button,
             padding,
             text_y,
@@ -341,6 +728,9 @@ impl ProgressBuilder {
             scale,
         );
         window.set_contents(&canvas)?;
+        if let Some((x, y)) = self.position {
+            window.set_position(x, y)?;
+        }
         window.show()?;
 
         let auto_close = self.auto_close;
@@ -348,65 +738,98 @@ impl ProgressBuilder {
         // Event loop with timeout for animation
         let mut window_dragging = false;
         loop {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Ok(Some(ProgressResult::Timeout));
+            }
             let mut needs_redraw = false;
 
             // Check for stdin messages
             loop {
                 match rx.try_recv() {
-                    Ok(StdinMessage::Progress(p)) => {
-                        progress_bar.set_percentage(p);
-                        if self.show_time_remaining && !self.pulsate && p > 0 {
-                            let elapsed = start_time.elapsed().as_secs_f64();
-                            let progress_fraction = p as f64 / 100.0;
-                            let estimated_total = elapsed / progress_fraction;
-                            let remaining = (estimated_total - elapsed).max(0.0);
-                            time_remaining_text = format_time_remaining(remaining);
-                        }
+                    Ok(StdinMessage::Progress(i, p)) => {
+                        stall.update(Instant::now());
+                        bars[i].set_percentage(p);
+                        let p = if stacked {
+                            let total = total_percentage(&bars[..bar_total]);
+                            bars[bar_total].set_percentage(total);
+                            total
+                        } else {
+                            p
+                        };
+                        estimate.update(Instant::now(), p);
                         needs_redraw = true;
-                        if p >= 100 && auto_close {
-                            return Ok(ProgressResult::Completed);
+                        if p >= 100 {
+                            stall.finish();
+                            if auto_close {
+                                return Ok(Some(ProgressResult::Completed));
+                            }
+                            ok_button.set_enabled(true);
                         }
                     }
-                    Ok(StdinMessage::Text(t)) => {
-                        status_text = t;
+                    Ok(StdinMessage::Text(i, t)) => {
+                        stall.update(Instant::now());
+                        if stacked {
+                            labels[i] = t;
+                        } else {
+                            status_text = t;
+                        }
                         needs_redraw = true;
                     }
-                    Ok(StdinMessage::Pulsate) => {
-                        progress_bar.set_pulsating(true);
+                    Ok(StdinMessage::Pulsate(i)) => {
+                        stall.update(Instant::now());
+                        bars[i].set_pulsating(true);
                         needs_redraw = true;
                     }
                     Ok(StdinMessage::Done) => {
-                        needs_redraw = true;
+                        stall.finish();
+                        // EOF on stdin means the work is finished, like zenity
                         if auto_close {
-                            return Ok(ProgressResult::Completed);
+                            return Ok(Some(ProgressResult::Completed));
                         }
-                    }
-                    Err(TryRecvError::Empty) => break,
-                    Err(TryRecvError::Disconnected) => {
-                        needs_redraw = true;
-                        if auto_close {
-                            return Ok(ProgressResult::Completed);
+                        for bar in &mut bars {
+                            bar.set_percentage(100);
                         }
-                        break;
+                        estimate.update(Instant::now(), 100);
+                        ok_button.set_enabled(true);
+                        needs_redraw = true;
                     }
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => break,
                 }
             }
 
-            // Poll for window events (non-blocking if pulsating)
-            let event = if progress_bar.is_pulsating() {
+            // The quiet-period check rides the loop's timed wait below
+            stall.check(Instant::now());
+            if stall.is_stalled() != bars[0].is_stalled() {
+                for bar in &mut bars {
+                    bar.set_stalled(stall.is_stalled());
+                }
+                needs_redraw = true;
+            }
+            // An estimate held back to avoid flicker shows a second later
+            needs_redraw |= self.show_time_remaining && estimate.refresh(Instant::now());
+
+            // Poll for window events (non-blocking while animating)
+            let animating = bars.iter().any(ProgressBar::is_animating);
+            let event = if animating {
                 // Use short timeout for animation
                 match window.poll_for_event()? {
                     Some(e) => Some(e),
                     None => {
                         // Tick animation and redraw
-                        progress_bar.tick();
+                        for bar in &mut bars {
+                            bar.tick();
+                        }
                         draw(
                             &mut canvas,
                             colors,
                             &font,
                             &status_text,
-                            &time_remaining_text,
-                            &progress_bar,
+                            estimate.text(),
+                            &bars,
+                            &labels,
+                            &label_ys,
+                            &ok_button,
                             &cancel_button,
                             padding,
                             text_y,
@@ -425,8 +848,9 @@ impl ProgressBuilder {
 
             if let Some(event) = event {
                 match &event {
-                    WindowEvent::CloseRequested => {
-                        return Ok(ProgressResult::Closed);
+                    // Without Cancel the window can't be closed either
+                    WindowEvent::CloseRequested if !self.no_cancel => {
+                        return Ok(Some(ProgressResult::Closed));
                     }
                     WindowEvent::RedrawRequested => {
                         needs_redraw = true;
@@ -443,21 +867,59 @@ impl ProgressBuilder {
                     WindowEvent::ButtonRelease(crate::backend::MouseButton::Left, _) => {
                         window_dragging = false;
                     }
+                    WindowEvent::KeyPress(key_event) => {
+                        if zoom::handle_key(key_event) {
+                            // Rebuilt at the new size, still reading stdin
+                            self.bar_states = bars[..bar_total]
+                                .iter()
+                                .enumerate()
+                                .map(|(i, bar)| {
+                                    let percentage = if ok_button.is_enabled() {
+                                        100
+                                    } else {
+                                        bar_percentage(bar)
+                                    };
+                                    let label = labels.get(i).cloned().unwrap_or_default();
+                                    (percentage, bar.is_pulsating(), label)
+                                })
+                                .collect();
+                            self.text = status_text;
+                            self.time_estimate = Some(estimate);
+                            return Ok(None);
+                        }
+                        match key_event.keysym {
+                            KEY_RETURN | KEY_KP_ENTER if ok_button.is_enabled() => {
+                                return Ok(Some(ProgressResult::Completed));
+                            }
+                            KEY_ESCAPE if cancel_button.is_some() => {
+                                if self.auto_kill {
+                                    hang_up_parent();
+                                }
+                                return Ok(Some(ProgressResult::Cancelled));
+                            }
+                            _ => {}
+                        }
+                    }
                     _ => {}
                 }
 
                 // Process button events
+                if ok_button.process_event(&event) {
+                    needs_redraw = true;
+                }
+                if ok_button.was_clicked() {
+                    return Ok(Some(ProgressResult::Completed));
+                }
                 if let Some(ref mut cancel_button) = cancel_button {
-                    cancel_button.process_event(&event);
+                    if cancel_button.process_event(&event) {
+                        needs_redraw = true;
+                    }
 
                     if cancel_button.was_clicked() {
                         if self.auto_kill {
-                            #[cfg(unix)]
-                            unsafe {
-                                kill(getppid(), SIGTERM);
-                            }
+                            hang_up_parent();
                         }
-                        return Ok(ProgressResult::Cancelled);
+                        return Ok(Some(ProgressResult::Cancelled));
                     }
                 }
             }
@@ -469,8 +931,11 @@ impl ProgressBuilder {
                     colors,
                     &font,
                     &status_text,
-                    &time_remaining_text,
-                    &progress_bar,
+                    estimate.text(),
+                    &bars,
+                    &labels,
+                    &label_ys,
+                    &ok_button,
                     &cancel_button,
                     padding,
                     text_y,
@@ -481,7 +946,7 @@ impl ProgressBuilder {
             }
 
             // Short sleep to prevent CPU spinning when idle
-            if !needs_redraw && !progress_bar.is_pulsating() {
+            if !needs_redraw && !animating {
                 std::thread::sleep(Duration::from_millis(50));
             }
         }
@@ -493,3 +958,222 @@ impl Default for ProgressBuilder {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    /// Drives the progress dialog's stall detector with a scripted clock.
+    mod stall {
+        use std::time::{Duration, Instant};
+
+        use super::super::StallDetector;
+
+        const TIMEOUT: Duration = Duration::from_secs(10);
+
+        fn secs(start: Instant, n: u64) -> Instant {
+            start + Duration::from_secs(n)
+        }
+
+        #[test]
+        fn quiet_before_first_update() {
+            let t0 = Instant::now();
+            let mut stall = StallDetector::new(TIMEOUT);
+            // A slow start is not a stall, however long it takes
+            assert!(!stall.check(secs(t0, 60)));
+            assert!(!stall.is_stalled());
+            assert_eq!(stall.deadline(), None);
+        }
+
+        #[test]
+        fn update_stall_resume_complete() {
+            let t0 = Instant::now();
+            let mut stall = StallDetector::new(TIMEOUT);
+
+            assert!(!stall.update(secs(t0, 1)));
+            assert_eq!(stall.deadline(), Some(secs(t0, 11)));
+            assert!(!stall.check(secs(t0, 10)));
+            assert!(!stall.is_stalled());
+
+            // Quiet period elapses
+            assert!(stall.check(secs(t0, 11)));
+            assert!(stall.is_stalled());
+            // Still stalled, but nothing changed
+            assert!(!stall.check(secs(t0, 20)));
+            assert!(stall.is_stalled());
+
+            // The next update reverts instantly and restarts the quiet period
+            assert!(stall.update(secs(t0, 25)));
+            assert!(!stall.is_stalled());
+            assert!(!stall.check(secs(t0, 34)));
+            assert!(stall.check(secs(t0, 35)));
+
+            // Completion clears the stall and keeps it clear
+            assert!(stall.finish());
+            assert!(!stall.is_stalled());
+            assert!(!stall.check(secs(t0, 100)));
+            assert!(!stall.is_stalled());
+        }
+
+        #[test]
+        fn steady_updates_never_stall() {
+            let t0 = Instant::now();
+            let mut stall = StallDetector::new(TIMEOUT);
+            for n in (0..120).step_by(5) {
+                stall.update(secs(t0, n));
+                assert!(!stall.check(secs(t0, n + 9)));
+            }
+            assert!(!stall.is_stalled());
+        }
+
+        #[test]
+        fn finish_without_stall() {
+            let t0 = Instant::now();
+            let mut stall = StallDetector::new(TIMEOUT);
+            stall.update(t0);
+            assert!(!stall.finish());
+            assert!(!stall.check(secs(t0, 60)));
+        }
+
+        #[test]
+        fn zero_timeout_disables() {
+            let t0 = Instant::now();
+            let mut stall = StallDetector::new(Duration::ZERO);
+            stall.update(t0);
+            assert_eq!(stall.deadline(), None);
+            assert!(!stall.check(secs(t0, 3600)));
+            assert!(!stall.is_stalled());
+        }
+    }
+
+    /// The progress dialog's time remaining estimate, driven by a scripted clock.
+    mod time_remaining {
+        use std::time::{Duration, Instant};
+
+        use super::super::{TimeEstimator, format_time_remaining};
+
+        const ESTIMATING: &str = "Estimating time remaining…";
+
+        fn at(start: Instant, seconds: u64) -> Instant {
+            start + Duration::from_secs(seconds)
+        }
+
+        fn words(seconds: u64) -> String {
+            format_time_remaining(Duration::from_secs(seconds))
+        }
+
+        #[test]
+        fn formats_roughly() {
+            assert_eq!(words(0), "A few seconds remaining");
+            assert_eq!(words(9), "A few seconds remaining");
+            assert_eq!(words(10), "About 10 seconds remaining");
+            assert_eq!(words(23), "About 25 seconds remaining");
+            assert_eq!(words(58), "About 1 minute remaining");
+            assert_eq!(words(100), "About 2 minutes remaining");
+            assert_eq!(words(59 * 60), "About 59 minutes remaining");
+            assert_eq!(words(3590), "About 1 hour remaining");
+            assert_eq!(words(5 * 3600 + 10), "About 5 hours remaining");
+        }
+
+        #[test]
+        fn estimates_after_three_updates() {
+            let t0 = Instant::now();
+            let mut estimate = TimeEstimator::new();
+            assert_eq!(estimate.text(), ESTIMATING);
+
+            // 1% every 6 seconds
+            assert!(!estimate.update(t0, 10));
+            assert!(!estimate.update(at(t0, 6), 11));
+            assert_eq!(estimate.remaining(), None);
+            assert_eq!(estimate.text(), ESTIMATING);
+
+            assert!(estimate.update(at(t0, 12), 12));
+            assert_eq!(estimate.remaining(), Some(Duration::from_secs(88 * 6)));
+            assert_eq!(estimate.text(), "About 9 minutes remaining");
+        }
+
+        #[test]
+        fn averages_over_recent_updates() {
+            let t0 = Instant::now();
+            let mut estimate = TimeEstimator::new();
+            // Slow at first, then 10% a second for the last ten updates
+            estimate.update(t0, 0);
+            estimate.update(at(t0, 100), 1);
+            for i in 0..10 {
+                estimate.update(at(t0, 101 + i), 2 + i as u32 * 5);
+            }
+            // 45% in the last 9 seconds leaves 53% to go
+            let remaining = estimate.remaining().unwrap().as_secs_f64();
+            assert!((remaining - 10.6).abs() < 1e-6, "{remaining}");
+        }
+
+        #[test]
+        fn changes_at_most_once_a_second() {
+            let t0 = Instant::now();
+            let mut estimate = TimeEstimator::new();
+            for (i, percentage) in [0, 10, 20].into_iter().enumerate() {
+                estimate.update(at(t0, i as u64 * 10), percentage);
+            }
+            assert_eq!(estimate.text(), "About 1 minute remaining");
+
+            // Suddenly much faster, but too soon after the last change
+            let soon = at(t0, 20) + Duration::from_millis(500);
+            assert!(!estimate.update(soon, 90));
+            assert_eq!(estimate.text(), "About 1 minute remaining");
+            assert!(estimate.refresh(at(t0, 21)));
+            assert_eq!(estimate.text(), "A few seconds remaining");
+        }
+
+        #[test]
+        fn clears_when_done_and_restarts_when_going_back() {
+            let t0 = Instant::now();
+            let mut estimate = TimeEstimator::new();
+            for (i, percentage) in [0, 50, 90].into_iter().enumerate() {
+                estimate.update(at(t0, i as u64 * 10), percentage);
+            }
+            assert!(estimate.update(at(t0, 30), 100));
+            assert_eq!(estimate.text(), "");
+            assert_eq!(estimate.remaining(), None);
+
+            // A second pass starts estimating afresh
+            assert!(estimate.update(at(t0, 40), 0));
+            estimate.update(at(t0, 50), 10);
+            assert_eq!(estimate.text(), ESTIMATING);
+        }
+    }
+
+    /// Addressing one of several stacked progress bars by number.
+    mod multi {
+        use super::super::split_bar_index;
+
+        #[test]
+        fn numbered_lines_pick_a_bar() {
+            assert_eq!(split_bar_index("2:45\n", 3), Some((1, "45")));
+            assert_eq!(
+                split_bar_index("3: # Extracting", 3),
+                Some((2, "# Extracting"))
+            );
+            assert_eq!(split_bar_index(" 1:pulsate ", 3), Some((0, "pulsate")));
+        }
+
+        #[test]
+        fn lines_without_a_number_go_to_the_first_bar() {
+            assert_eq!(split_bar_index("45", 3), Some((0, "45")));
+            // The colon in a label doesn't make a bar number
+            assert_eq!(
+                split_bar_index("# Step 1: fetch", 3),
+                Some((0, "# Step 1: fetch"))
+            );
+            assert_eq!(split_bar_index("a:45", 3), Some((0, "a:45")));
+        }
+
+        #[test]
+        fn numbers_past_the_bars_are_dropped() {
+            assert_eq!(split_bar_index("0:45", 3), None);
+            assert_eq!(split_bar_index("4:45", 3), None);
+        }
+
+        #[test]
+        fn a_single_bar_takes_lines_as_they_are() {
+            assert_eq!(split_bar_index("2:45", 1), Some((0, "2:45")));
+        }
+    }
+}
@@ -1,13 +1,23 @@
 //! Scale dialog implementation for selecting a numeric value with a slider.
 
+use std::{
+    borrow::Borrow,
+    collections::VecDeque,
+    io::Write,
+    time::{Duration, Instant},
+};
+
 use crate::{
-    backend::{MouseButton, Window, WindowEvent, create_window},
+    backend::{Modifiers, MouseButton, ScrollDirection, Window, WindowEvent, create_window},
     error::Error,
-    render::{Canvas, Font},
+    render::{Align, Canvas, Font},
     ui::{
-        BASE_BUTTON_HEIGHT, BASE_BUTTON_SPACING, BASE_CORNER_RADIUS, Colors, KEY_END, KEY_ESCAPE,
-        KEY_HOME, KEY_LEFT, KEY_RETURN, KEY_RIGHT,
+        BASE_BUTTON_HEIGHT, BASE_BUTTON_SPACING, BASE_CORNER_RADIUS, Colors, KEY_DOWN, KEY_END,
+        KEY_ESCAPE, KEY_HOME, KEY_LEFT, KEY_RETURN, KEY_RIGHT, KEY_UP,
+        geometry::Offset,
+        wait_for_event_until,
         widgets::{Widget, button::Button},
+        zoom,
     },
 };
 
@@ -16,6 +26,26 @@ const BASE_SLIDER_HEIGHT: u32 = 8;
 const BASE_THUMB_SIZE: u32 = 20;
 const BASE_SLIDER_WIDTH: u32 = 300;
 const BASE_MIN_WIDTH: u32 = 350;
+/// Tick marks under the track, and the gap before them.
+const BASE_TICK_HEIGHT: u32 = 5;
+const BASE_TICK_GAP: u32 = 4;
+/// Room for the ticks and the min and max labels under them.
+const BASE_MARKS_HEIGHT: u32 = 26;
+/// Ticks closer together than this are thinned out.
+const BASE_MIN_TICK_SPACING: u32 = 4;
+/// How many steps Shift+wheel moves.
+const SHIFT_WHEEL_STEPS: i32 = 10;
+/// Gap between a vertical slider and the value beside it.
+const BASE_SIDE_GAP: u32 = 12;
+
+/// Which way the slider runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Orientation {
+    #[default]
+    Horizontal,
+    /// Larger values are higher up.
+    Vertical,
+}
 
 /// Scale dialog result.
 #[derive(Debug, Clone)]
@@ -26,14 +56,19 @@ pub enum ScaleResult {
     Cancelled,
     /// Dialog was closed.
     Closed,
+    /// The timeout passed.
+    Timeout,
 }
 
 impl ScaleResult {
+    /// The exit code the command-line tool uses for this result: 0 when
+    /// a value is picked, 1 when cancelled or closed, 5 on timeout.
     pub fn exit_code(&self) -> i32 {
         match self {
             ScaleResult::Value(_) => 0,
             ScaleResult::Cancelled => 1,
             ScaleResult::Closed => 1,
+            ScaleResult::Timeout => 5,
         }
     }
 }
@@ -47,9 +82,14 @@ pub struct ScaleBuilder {
     max_value: i32,
     step: i32,
     hide_value: bool,
+    print_partial: bool,
+    marks: bool,
+    orientation: Orientation,
     width: Option<u32>,
     height: Option<u32>,
-    colors: Option<&'static Colors>,
+    timeout: Option<u32>,
+    position: Option<(Offset, Offset)>,
+    colors: Option<Colors>,
 }
 
 impl ScaleBuilder {
@@ -62,8 +102,13 @@ impl ScaleBuilder {
             max_value: 100,
             step: 1,
             hide_value: false,
+            print_partial: false,
+            marks: false,
+            orientation: Orientation::Horizontal,
             width: None,
             height: None,
+            timeout: None,
+            position: None,
             colors: None,
         }
     }
@@ -102,14 +147,34 @@ impl ScaleBuilder {
         self
     }
 
+    /// Draw a tick at each step under the slider (right of it when
+    /// vertical), with the minimum and maximum labelled at the ends.
+    pub fn marks(mut self, marks: bool) -> Self {
+        self.marks = marks;
+        self
+    }
+
+    /// Run the slider across (the default) or up the window.
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
     /// Hide the value display.
     pub fn hide_value(mut self, hide: bool) -> Self {
         self.hide_value = hide;
         self
     }
 
-    pub fn colors(mut self, colors: &'static Colors) -> Self {
-        self.colors = Some(colors);
+    /// Print every value the slider moves to on stdout as it happens, one
+    /// per line.
+    pub fn print_partial(mut self, print_partial: bool) -> Self {
+        self.print_partial = print_partial;
+        self
+    }
+
+    pub fn colors(mut self, colors: impl Borrow<Colors>) -> Self {
+        self.colors = Some(*colors.borrow());
         self
     }
 
@@ -123,8 +188,38 @@ impl ScaleBuilder {
         self
     }
 
-    pub fn show(self) -> Result<ScaleResult, Error> {
-        let colors = self.colors.unwrap_or_else(|| crate::ui::detect_theme());
+    /// Set timeout in seconds. Dialog will auto-close after this time.
+    pub fn timeout(mut self, seconds: u32) -> Self {
+        self.timeout = Some(seconds);
+        self
+    }
+
+    /// Request a window position. Ignored where the platform doesn't allow it.
+    pub fn position(mut self, x: Offset, y: Offset) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    pub fn show(mut self) -> Result<ScaleResult, Error> {
+        let deadline = self
+            .timeout
+            .map(|secs| Instant::now() + Duration::from_secs(secs as u64));
+        loop {
+            if let Some(result) = self.run(deadline)? {
+                return Ok(result);
+            }
+        }
+    }
+
+    /// Shows the dialog until it's answered, or returns `None` to be run
+    /// again after a [zoom](crate::ui::zoom) change.
+    fn run(&mut self, deadline: Option<Instant>) -> Result<Option<ScaleResult>, Error> {
+        let colors = &self.colors.unwrap_or_else(|| *crate::ui::detect_theme());
+
+        // A reversed range would make every clamp below panic
+        if self.min_value > self.max_value {
+            std::mem::swap(&mut self.min_value, &mut self.max_value);
+        }
 
         // Clamp initial value to range
         let mut value = self.value.clamp(self.min_value, self.max_value);
@@ -140,25 +235,50 @@ impl ScaleBuilder {
         };
 
         let logical_buttons_width = temp_ok.width() + temp_cancel.width() + 10;
-        let logical_content_width = BASE_SLIDER_WIDTH.max(logical_buttons_width);
-        let calc_width = (logical_content_width + BASE_PADDING * 2).max(BASE_MIN_WIDTH);
-
-        // Height: padding + text + slider area + value display + buttons + padding
         let value_display_height = if self.hide_value { 0 } else { 24 };
-        let calc_height = BASE_PADDING * 2
-            + temp_prompt_height
-            + (if temp_prompt_height > 0 { 16 } else { 0 })
-            + BASE_THUMB_SIZE + 16  // Slider area with some margin
-            + value_display_height
-            + 32 + 16; // Buttons
+        let prompt_block = temp_prompt_height + if temp_prompt_height > 0 { 16 } else { 0 };
+        let buttons_block = 32 + 16;
+        let (calc_width, calc_height) = match self.orientation {
+            Orientation::Horizontal => {
+                // Padding + text + slider area + value display + buttons + padding
+                let logical_content_width = BASE_SLIDER_WIDTH.max(logical_buttons_width);
+                let marks_height = if self.marks { BASE_MARKS_HEIGHT } else { 0 };
+                (
+                    (logical_content_width + BASE_PADDING * 2).max(BASE_MIN_WIDTH),
+                    BASE_PADDING * 2
+                        + prompt_block
+                        + BASE_THUMB_SIZE + 16  // Slider area with some margin
+                        + marks_height
+                        + value_display_height
+                        + buttons_block,
+                )
+            }
+            Orientation::Vertical => {
+                // The value and the marks go either side of the track, so
+                // each side has room for the widest number
+                let widest = [self.min_value, self.max_value]
+                    .map(|n| temp_font.render(&n.to_string()).finish().width())
+                    .into_iter()
+                    .max()
+                    .unwrap_or(0);
+                let side = BASE_SIDE_GAP.max(BASE_TICK_GAP + BASE_TICK_HEIGHT) + 2 + widest;
+                let logical_content_width = (BASE_THUMB_SIZE + side * 2).max(logical_buttons_width);
+                (
+                    logical_content_width + BASE_PADDING * 2,
+                    BASE_PADDING * 2 + prompt_block + BASE_SLIDER_WIDTH + 16 + buttons_block,
+                )
+            }
+        };
 
         drop(temp_font);
         drop(temp_ok);
         drop(temp_cancel);
 
-        // Use custom dimensions if provided, otherwise use calculated defaults
-        let logical_width = self.width.unwrap_or(calc_width) as u16;
-        let logical_height = self.height.unwrap_or(calc_height) as u16;
+        // Use custom dimensions if provided, but never smaller than the content
+        let logical_width = self.width.map_or(calc_width, |w| {
+            w.max(logical_buttons_width + BASE_PADDING * 2)
+        }) as u16;
+        let logical_height = self.height.map_or(calc_height, |h| h.max(calc_height)) as u16;
 
         // Create window with LOGICAL dimensions
         let mut window = create_window(logical_width, logical_height)?;
@@ -178,7 +298,6 @@ impl ScaleBuilder {
         let padding = (BASE_PADDING as f32 * scale) as u32;
         let slider_height = (BASE_SLIDER_HEIGHT as f32 * scale) as u32;
         let thumb_size = (BASE_THUMB_SIZE as f32 * scale) as u32;
-        let slider_width = (BASE_SLIDER_WIDTH as f32 * scale) as u32;
 
         // Calculate physical dimensions
         let physical_width = (logical_width as f32 * scale) as u32;
@@ -203,12 +322,6 @@ impl ScaleBuilder {
             y += prompt_height as i32 + (16.0 * scale) as i32;
         }
 
-        // Slider position (centered horizontally)
-        let slider_x = (physical_width - slider_width) as i32 / 2;
-        let slider_y = y + (thumb_size as i32 - slider_height as i32) / 2;
-        let thumb_y = y;
-        y += thumb_size as i32 + (16.0 * scale) as i32;
-
         // Button positions (right-aligned)
         let button_y =
             physical_height as i32 - padding as i32 - (BASE_BUTTON_HEIGHT as f32 * scale) as i32;
@@ -218,6 +331,74 @@ impl ScaleBuilder {
         button_x -= (BASE_BUTTON_SPACING as f32 * scale) as i32 + ok_button.width() as i32;
         ok_button.set_position(button_x, button_y);
 
+        // The slider keeps its length unless --width (or --height when
+        // vertical) asks for a different one
+        let track = match self.orientation {
+            Orientation::Horizontal => {
+                let length = if self.width.is_some() {
+                    physical_width - padding * 2
+                } else {
+                    (BASE_SLIDER_WIDTH as f32 * scale) as u32
+                };
+                // Centered horizontally
+                let x = (physical_width - length) as i32 / 2;
+                let track_y = y;
+                y += thumb_size as i32 + (16.0 * scale) as i32;
+                if self.marks {
+                    y += (BASE_MARKS_HEIGHT as f32 * scale) as i32;
+                }
+                Track {
+                    vertical: false,
+                    x,
+                    y: track_y,
+                    length,
+                    thumb_size,
+                    thickness: slider_height,
+                }
+            }
+            Orientation::Vertical => {
+                let length = if self.height.is_some() {
+                    (button_y - y - (16.0 * scale) as i32).max(thumb_size as i32) as u32
+                } else {
+                    (BASE_SLIDER_WIDTH as f32 * scale) as u32
+                };
+                Track {
+                    vertical: true,
+                    x: (physical_width - thumb_size) as i32 / 2,
+                    y,
+                    length,
+                    thumb_size,
+                    thickness: slider_height,
+                }
+            }
+        };
+        let value_y = y;
+
+        let ticks = if self.marks {
+            scale_ticks(self.min_value, self.max_value, self.step, track.travel())
+        } else {
+            Vec::new()
+        };
+        let end_labels = self.marks.then(|| {
+            let label = |value: i32| {
+                font.render(&value.to_string())
+                    .with_color(colors.text)
+                    .finish()
+            };
+            (label(self.min_value), label(self.max_value))
+        });
+        // The value is right-aligned in room for the widest one, so its
+        // digits stay put as it changes
+        let value_width = [self.min_value, self.max_value]
+            .map(|n| {
+                font.render(&n.to_string())
+                    .with_align(Align::Right)
+                    .measure()
+                    .0
+            })
+            .into_iter()
+            .fold(0.0, f32::max);
+
         // State
         let mut dragging = false;
         let mut thumb_hovered = false;
@@ -227,35 +408,22 @@ impl ScaleBuilder {
         // Create canvas at PHYSICAL dimensions
         let mut canvas = Canvas::new(physical_width, physical_height);
 
-        // Helper to calculate thumb position from value
-        let value_to_thumb_x = |val: i32| -> i32 {
-            let range = (self.max_value - self.min_value) as f32;
-            let ratio = if range > 0.0 {
-                (val - self.min_value) as f32 / range
-            } else {
-                0.0
-            };
-            slider_x + (ratio * (slider_width - thumb_size) as f32) as i32
-        };
-
-        // Helper to calculate value from x position
-        let x_to_value = |x: i32| -> i32 {
-            let track_start = slider_x + thumb_size as i32 / 2;
-            let track_end = slider_x + slider_width as i32 - thumb_size as i32 / 2;
-            let track_width = track_end - track_start;
+        let (min_value, max_value, step) = (self.min_value, self.max_value, self.step);
+        // Where along the track a value puts the thumb, the way the ticks are
+        // placed so it lines up with them
+        let offset_of = |val: i32| tick_offset(val, min_value, max_value, track.travel());
 
-            let ratio = if track_width > 0 {
-                ((x - track_start) as f32 / track_width as f32).clamp(0.0, 1.0)
+        // Helper to calculate value from the pointer
+        let value_at = |x: i32, y: i32| -> i32 {
+            let travel = track.travel();
+            let ratio = if travel > 0 {
+                (track.offset_at(x, y) as f32 / travel as f32).clamp(0.0, 1.0)
             } else {
                 0.0
             };
-
-            let range = self.max_value - self.min_value;
-            let raw_value = self.min_value + (ratio * range as f32) as i32;
-
-            // Snap to step
-            let steps = (raw_value - self.min_value) / self.step;
-            (self.min_value + steps * self.step).clamp(self.min_value, self.max_value)
+            let range = max_value as i64 - min_value as i64;
+            let raw_value = min_value as i64 + (ratio as f64 * range as f64).round() as i64;
+            snap_to_step(raw_value as i32, min_value, max_value, step)
         };
 
         // Draw function
@@ -271,17 +439,13 @@ impl ScaleBuilder {
                     hide_value: bool,
                     // Layout params
                     padding: u32,
-                    slider_x: i32,
-                    slider_y: i32,
-                    slider_width: u32,
-                    slider_height: u32,
-                    thumb_y: i32,
-                    thumb_size: u32,
+                    track: &Track,
+                    ticks: &[(i32, u32)],
+                    end_labels: &Option<(Canvas, Canvas)>,
                     value_y: i32,
                     prompt_y: i32,
                     physical_width: u32,
-                    scale: f32,
-                    value_to_thumb_x: &dyn Fn(i32) -> i32| {
+                    scale: f32| {
             let width = canvas.width() as f32;
             let height = canvas.height() as f32;
             let radius = BASE_CORNER_RADIUS * scale;
@@ -301,40 +465,88 @@ impl ScaleBuilder {
             }
 
             // Draw slider track background
+            let (bar_x, bar_y, bar_width, bar_height) = track.bar();
+            let bar_radius = track.thickness as f32 / 2.0;
             canvas.fill_rounded_rect(
-                slider_x as f32,
-                slider_y as f32,
-                slider_width as f32,
-                slider_height as f32,
-                slider_height as f32 / 2.0,
+                bar_x,
+                bar_y,
+                bar_width,
+                bar_height,
+                bar_radius,
                 colors.progress_bg,
             );
 
-            // Draw filled portion of track
-            let thumb_x = value_to_thumb_x(value);
-            let fill_width = (thumb_x - slider_x + thumb_size as i32 / 2) as f32;
-            if fill_width > 0.0 {
-                canvas.fill_rounded_rect(
-                    slider_x as f32,
-                    slider_y as f32,
-                    fill_width.min(slider_width as f32),
-                    slider_height as f32,
-                    slider_height as f32 / 2.0,
-                    colors.progress_fill,
-                );
-            }
+            // Draw filled portion of track, from the minimum to the thumb's centre
+            let offset = offset_of(value);
+            let (fill_x, fill_y, fill_width, fill_height) = track.filled(offset);
+            canvas.fill_rounded_rect(
+                fill_x,
+                fill_y,
+                fill_width,
+                fill_height,
+                bar_radius,
+                colors.progress_fill,
+            );
 
             // Draw track border
             canvas.stroke_rounded_rect(
-                slider_x as f32,
-                slider_y as f32,
-                slider_width as f32,
-                slider_height as f32,
-                slider_height as f32 / 2.0,
+                bar_x,
+                bar_y,
+                bar_width,
+                bar_height,
+                bar_radius,
                 colors.progress_border,
                 1.0,
             );
 
+            // Draw tick marks, thinned out where they'd run together, and the
+            // min and max beside the ends
+            let gap = (BASE_TICK_GAP as f32 * scale) as i32;
+            let tick_length = BASE_TICK_HEIGHT as f32 * scale;
+            let mut last_tick: Option<u32> = None;
+            let min_spacing = (BASE_MIN_TICK_SPACING as f32 * scale) as u32;
+            for (i, &(_, offset)) in ticks.iter().enumerate() {
+                let is_end = i == 0 || i == ticks.len() - 1;
+                if !is_end && last_tick.is_some_and(|last| offset - last < min_spacing) {
+                    continue;
+                }
+                last_tick = Some(offset);
+                let (x, y) = track.mark(offset, gap);
+                let (tick_width, tick_height) = if track.vertical {
+                    (tick_length, scale.max(1.0))
+                } else {
+                    (scale.max(1.0), tick_length)
+                };
+                canvas.fill_rect(
+                    x as f32,
+                    y as f32,
+                    tick_width,
+                    tick_height,
+                    colors.progress_border,
+                );
+            }
+            if let (Some((min_label, max_label)), Some(&(_, last))) = (end_labels, ticks.last()) {
+                let gap = gap + ((BASE_TICK_HEIGHT + 2) as f32 * scale) as i32;
+                for (label, offset) in [(min_label, 0), (max_label, last)] {
+                    let (x, y) = track.mark(offset, gap);
+                    // Centred on the tick, but kept within the track's ends
+                    let (x, y) = if track.vertical {
+                        let y = (y - label.height() as i32 / 2).clamp(
+                            track.y,
+                            track.y + track.length as i32 - label.height() as i32,
+                        );
+                        (x, y)
+                    } else {
+                        let x = (x - label.width() as i32 / 2).clamp(
+                            track.x,
+                            track.x + track.length as i32 - label.width() as i32,
+                        );
+                        (x, y)
+                    };
+                    canvas.draw_canvas(label, x, y);
+                }
+            }
+
             // Draw thumb
             let thumb_color = if dragging {
                 colors.button_pressed
@@ -343,29 +555,45 @@ impl ScaleBuilder {
             } else {
                 colors.button
             };
+            let (thumb_x, thumb_y) = track.thumb(offset);
+            let thumb_size = track.thumb_size as f32;
             canvas.fill_rounded_rect(
                 thumb_x as f32,
                 thumb_y as f32,
-                thumb_size as f32,
-                thumb_size as f32,
-                thumb_size as f32 / 2.0,
+                thumb_size,
+                thumb_size,
+                thumb_size / 2.0,
                 thumb_color,
             );
             canvas.stroke_rounded_rect(
                 thumb_x as f32,
                 thumb_y as f32,
-                thumb_size as f32,
-                thumb_size as f32,
-                thumb_size as f32 / 2.0,
+                thumb_size,
+                thumb_size,
+                thumb_size / 2.0,
                 colors.button_outline,
                 1.0,
             );
 
-            // Draw value display
+            // Draw value display, under the slider or beside the thumb
             if !hide_value {
                 let value_text = value.to_string();
-                let value_canvas = font.render(&value_text).with_color(colors.text).finish();
-                let value_x = (physical_width - value_canvas.width()) as i32 / 2;
+                let value_canvas = font
+                    .render(&value_text)
+                    .with_color(colors.text)
+                    .with_max_width(value_width)
+                    .with_align(Align::Right)
+                    .finish();
+                let (value_x, value_y) = if track.vertical {
+                    (
+                        thumb_x
+                            - (BASE_SIDE_GAP as f32 * scale) as i32
+                            - value_canvas.width() as i32,
+                        thumb_y + (track.thumb_size as i32 - value_canvas.height() as i32) / 2,
+                    )
+                } else {
+                    ((physical_width - value_canvas.width()) as i32 / 2, value_y)
+                };
                 canvas.draw_canvas(&value_canvas, value_x, value_y);
             }
 
@@ -387,29 +615,39 @@ impl ScaleBuilder {
             &cancel_button,
             self.hide_value,
             padding,
-            slider_x,
-            slider_y,
-            slider_width,
-            slider_height,
-            thumb_y,
-            thumb_size,
-            y,
+            &track,
+            &ticks,
+            &end_labels,
+            value_y,
             prompt_y,
             physical_width,
             scale,
-            &value_to_thumb_x,
         );
         window.set_contents(&canvas)?;
+        if let Some((x, y)) = self.position {
+            window.set_position(x, y)?;
+        }
         window.show()?;
 
         // Event loop
         let mut window_dragging = false;
+        let mut printed_value = value;
+        // Events the batch loop read, left for the main loop to handle
+        let mut held: VecDeque<WindowEvent> = VecDeque::new();
         loop {
-            let event = window.wait_for_event()?;
+            let event = match held.pop_front() {
+                Some(event) => event,
+                None => {
+                    match wait_for_event_until(&mut window, deadline)? {
+                        Some(event) => event,
+                        None => return Ok(Some(ScaleResult::Timeout)),
+                    }
+                }
+            };
             let mut needs_redraw = false;
 
             match &event {
-                WindowEvent::CloseRequested => return Ok(ScaleResult::Closed),
+                WindowEvent::CloseRequested => return Ok(Some(ScaleResult::Closed)),
                 WindowEvent::RedrawRequested => needs_redraw = true,
                 WindowEvent::CursorMove(pos) => {
                     if window_dragging {
@@ -421,12 +659,8 @@ impl ScaleBuilder {
                     cursor_y = pos.y as i32;
 
                     // Check thumb hover
-                    let thumb_x = value_to_thumb_x(value);
                     let old_hovered = thumb_hovered;
-                    thumb_hovered = cursor_x >= thumb_x
-                        && cursor_x < thumb_x + thumb_size as i32
-                        && cursor_y >= thumb_y
-                        && cursor_y < thumb_y + thumb_size as i32;
+                    thumb_hovered = track.on_thumb(offset_of(value), cursor_x, cursor_y);
 
                     if old_hovered != thumb_hovered {
                         needs_redraw = true;
@@ -434,7 +668,7 @@ impl ScaleBuilder {
 
                     // Handle dragging
                     if dragging {
-                        let new_value = x_to_value(cursor_x);
+                        let new_value = value_at(cursor_x, cursor_y);
                         if new_value != value {
                             value = new_value;
                             needs_redraw = true;
@@ -443,26 +677,15 @@ impl ScaleBuilder {
                 }
                 WindowEvent::ButtonPress(MouseButton::Left, _) => {
                     window_dragging = true;
-                    let mx = cursor_x;
-                    let my = cursor_y;
 
                     // Check if clicking on thumb
-                    let thumb_x = value_to_thumb_x(value);
-                    if mx >= thumb_x
-                        && mx < thumb_x + thumb_size as i32
-                        && my >= thumb_y
-                        && my < thumb_y + thumb_size as i32
-                    {
+                    if track.on_thumb(offset_of(value), cursor_x, cursor_y) {
                         dragging = true;
                         needs_redraw = true;
                     }
                     // Check if clicking on track
-                    else if mx >= slider_x
-                        && mx < slider_x + slider_width as i32
-                        && my >= slider_y
-                        && my < slider_y + slider_height as i32 + thumb_size as i32
-                    {
-                        let new_value = x_to_value(mx);
+                    else if track.contains(cursor_x, cursor_y) {
+                        let new_value = value_at(cursor_x, cursor_y);
                         if new_value != value {
                             value = new_value;
                             needs_redraw = true;
@@ -477,39 +700,63 @@ impl ScaleBuilder {
                         needs_redraw = true;
                     }
                 }
+                WindowEvent::Scroll(direction, modifiers) if track.contains(cursor_x, cursor_y) => {
+                    // The wheel moves a step at a time over the slider, and
+                    // ten with Shift
+                    let steps = if modifiers.contains(Modifiers::SHIFT) {
+                        SHIFT_WHEEL_STEPS
+                    } else {
+                        1
+                    };
+                    let steps = match direction {
+                        ScrollDirection::Up | ScrollDirection::Right => steps,
+                        ScrollDirection::Down | ScrollDirection::Left => -steps,
+                    };
+                    let new_value = step_value(value, min_value, max_value, step, steps);
+                    if new_value != value {
+                        value = new_value;
+                        needs_redraw = true;
+                    }
+                }
                 WindowEvent::KeyPress(key_event) => {
+                    if zoom::handle_key(key_event) {
+                        // Rebuilt at the new size with the current value
+                        self.value = value;
+                        return Ok(None);
+                    }
                     match key_event.keysym {
-                        KEY_LEFT => {
-                            let new_value = (value - self.step).max(self.min_value);
+                        // Either pair of arrows, whichever way the slider runs
+                        KEY_LEFT | KEY_DOWN => {
+                            let new_value = step_value(value, min_value, max_value, step, -1);
                             if new_value != value {
                                 value = new_value;
                                 needs_redraw = true;
                             }
                         }
-                        KEY_RIGHT => {
-                            let new_value = (value + self.step).min(self.max_value);
+                        KEY_RIGHT | KEY_UP => {
+                            let new_value = step_value(value, min_value, max_value, step, 1);
                             if new_value != value {
                                 value = new_value;
                                 needs_redraw = true;
                             }
                         }
                         KEY_HOME => {
-                            if value != self.min_value {
-                                value = self.min_value;
+                            if value != min_value {
+                                value = min_value;
                                 needs_redraw = true;
                             }
                         }
                         KEY_END => {
-                            if value != self.max_value {
-                                value = self.max_value;
+                            if value != max_value {
+                                value = max_value;
                                 needs_redraw = true;
                             }
                         }
                         KEY_RETURN => {
-                            return Ok(ScaleResult::Value(value));
+                            return Ok(Some(ScaleResult::Value(value)));
                         }
                         KEY_ESCAPE => {
-                            return Ok(ScaleResult::Cancelled);
+                            return Ok(Some(ScaleResult::Cancelled));
                         }
                         _ => {}
                     }
@@ -521,33 +768,38 @@ impl ScaleBuilder {
             needs_redraw |= cancel_button.process_event(&event);
 
             if ok_button.was_clicked() {
-                return Ok(ScaleResult::Value(value));
+                return Ok(Some(ScaleResult::Value(value)));
             }
             if cancel_button.was_clicked() {
-                return Ok(ScaleResult::Cancelled);
+                return Ok(Some(ScaleResult::Cancelled));
             }
 
-            // Batch process pending events
-            while let Some(ev) = window.poll_for_event()? {
+            // Batch pending pointer motion, which only matters while dragging.
+            // Anything else is left for the main loop
+            while held.is_empty()
+                && let Some(ev) = window.poll_for_event()?
+            {
                 match &ev {
-                    WindowEvent::CloseRequested => return Ok(ScaleResult::Closed),
-                    WindowEvent::CursorMove(pos) if dragging => {
-                        let new_value = x_to_value(pos.x as i32);
-                        if new_value != value {
-                            value = new_value;
-                            needs_redraw = true;
-                        }
-                    }
-                    WindowEvent::ButtonRelease(MouseButton::Left, _) => {
+                    WindowEvent::CursorMove(pos) => {
+                        cursor_x = pos.x as i32;
+                        cursor_y = pos.y as i32;
                         if dragging {
-                            dragging = false;
-                            needs_redraw = true;
+                            let new_value = value_at(cursor_x, cursor_y);
+                            if new_value != value {
+                                value = new_value;
+                                needs_redraw = true;
+                            }
                         }
+                        needs_redraw |= ok_button.process_event(&ev);
+                        needs_redraw |= cancel_button.process_event(&ev);
                     }
-                    _ => {}
+                    _ => held.push_back(ev),
                 }
-                needs_redraw |= ok_button.process_event(&ev);
-                needs_redraw |= cancel_button.process_event(&ev);
+            }
+
+            if self.print_partial && value != printed_value {
+                write_partial(value);
+                printed_value = value;
             }
 
             if needs_redraw {
@@ -563,17 +815,13 @@ impl ScaleBuilder {
                     &cancel_button,
                     self.hide_value,
                     padding,
-                    slider_x,
-                    slider_y,
-                    slider_width,
-                    slider_height,
-                    thumb_y,
-                    thumb_size,
-                    y,
+                    &track,
+                    &ticks,
+                    &end_labels,
+                    value_y,
                     prompt_y,
                     physical_width,
                     scale,
-                    &value_to_thumb_x,
                 );
                 window.set_contents(&canvas)?;
             }
@@ -581,8 +829,226 @@ impl ScaleBuilder {
     }
 }
 
+/// Where the slider sits. Offsets along it count from the minimum end: the
+/// left, or the bottom when vertical.
+struct Track {
+    vertical: bool,
+    /// Top left of the band the thumb moves in.
+    x: i32,
+    y: i32,
+    /// Length along the slider, including half a thumb past each end value.
+    length: u32,
+    thumb_size: u32,
+    /// Thickness of the bar drawn under the thumb.
+    thickness: u32,
+}
+
+impl Track {
+    /// How far the thumb's centre can move.
+    fn travel(&self) -> u32 {
+        self.length.saturating_sub(self.thumb_size)
+    }
+
+    /// Turns a distance along the slider and one across it into a point.
+    fn point(&self, along: i32, across: i32) -> (i32, i32) {
+        if self.vertical {
+            (self.x + across, self.y + self.length as i32 - along)
+        } else {
+            (self.x + along, self.y + across)
+        }
+    }
+
+    /// Top left of the thumb at `offset`.
+    fn thumb(&self, offset: u32) -> (i32, i32) {
+        let (x, y) = self.point(offset as i32, 0);
+        if self.vertical {
+            (x, y - self.thumb_size as i32)
+        } else {
+            (x, y)
+        }
+    }
+
+    /// The offset the thumb's centre would have at a point, not clamped.
+    fn offset_at(&self, x: i32, y: i32) -> i32 {
+        let along = if self.vertical {
+            self.y + self.length as i32 - y
+        } else {
+            x - self.x
+        };
+        along - self.thumb_size as i32 / 2
+    }
+
+    fn on_thumb(&self, offset: u32, x: i32, y: i32) -> bool {
+        let (thumb_x, thumb_y) = self.thumb(offset);
+        let size = self.thumb_size as i32;
+        x >= thumb_x && x < thumb_x + size && y >= thumb_y && y < thumb_y + size
+    }
+
+    /// Whether a point is over the band the thumb moves in.
+    fn contains(&self, x: i32, y: i32) -> bool {
+        let (width, height) = self.size(self.length, self.thumb_size);
+        x >= self.x && x < self.x + width as i32 && y >= self.y && y < self.y + height as i32
+    }
+
+    /// Width and height of something `along` long and `across` thick.
+    fn size(&self, along: u32, across: u32) -> (u32, u32) {
+        if self.vertical {
+            (across, along)
+        } else {
+            (along, across)
+        }
+    }
+
+    /// The bar, centred in the thumb's band.
+    fn bar(&self) -> (f32, f32, f32, f32) {
+        self.span(self.length)
+    }
+
+    /// The part of the bar from the minimum end to the thumb's centre.
+    fn filled(&self, offset: u32) -> (f32, f32, f32, f32) {
+        self.span((offset + self.thumb_size / 2).min(self.length))
+    }
+
+    /// The bar's first `along` pixels from the minimum end.
+    fn span(&self, along: u32) -> (f32, f32, f32, f32) {
+        let inset = (self.thumb_size as i32 - self.thickness as i32) / 2;
+        let (x, y) = self.point(0, inset);
+        let (width, height) = self.size(along, self.thickness);
+        let y = if self.vertical { y - along as i32 } else { y };
+        (x as f32, y as f32, width as f32, height as f32)
+    }
+
+    /// Where a mark for `offset` starts, `gap` beyond the thumb's band:
+    /// below it, or right of it when vertical.
+    fn mark(&self, offset: u32, gap: i32) -> (i32, i32) {
+        self.point(
+            (offset + self.thumb_size / 2) as i32,
+            self.thumb_size as i32 + gap,
+        )
+    }
+}
+
+/// Where `value` falls along a track `travel` pixels long from `min` to
+/// `max`. Worked out afresh for each value, so no rounding error builds up.
+fn tick_offset(value: i32, min: i32, max: i32, travel: u32) -> u32 {
+    let range = max as i64 - min as i64;
+    if range <= 0 {
+        return 0;
+    }
+    ((value as i64 - min as i64).clamp(0, range) * travel as i64 / range) as u32
+}
+
+/// The values a slider from `min` to `max` stops at with `step`, each with
+/// its offset along a track `travel` pixels long: every `step` from `min`,
+/// then `max` even when it isn't a multiple.
+pub(crate) fn scale_ticks(min: i32, max: i32, step: i32, travel: u32) -> Vec<(i32, u32)> {
+    let step = step.max(1) as i64;
+    let mut ticks: Vec<(i32, u32)> = (0..)
+        .map(|i| min as i64 + i * step)
+        .take_while(|&value| value <= max as i64)
+        .map(|value| (value as i32, tick_offset(value as i32, min, max, travel)))
+        .collect();
+    if ticks.last().is_none_or(|&(value, _)| value != max) {
+        ticks.push((max, travel));
+    }
+    ticks
+}
+
+/// The stop of a `min` to `max` slider with `step` nearest to `value`:
+/// a multiple of `step` from `min`, or `max`. Halfway rounds up.
+pub(crate) fn snap_to_step(value: i32, min: i32, max: i32, step: i32) -> i32 {
+    let step = step.max(1) as i64;
+    let value = value.clamp(min, max) as i64;
+    let below = min as i64 + (value - min as i64) / step * step;
+    let above = (below + step).min(max as i64);
+    (if value - below < above - value {
+        below
+    } else {
+        above
+    }) as i32
+}
+
+/// The stop `steps` stops above `value` (below for negative `steps`),
+/// within `min` and `max`. A value between stops moves to the next one.
+pub(crate) fn step_value(value: i32, min: i32, max: i32, step: i32, steps: i32) -> i32 {
+    let step = step.max(1) as i64;
+    let offset = value.clamp(min, max) as i64 - min as i64;
+    let stop = if steps >= 0 {
+        offset / step + steps as i64
+    } else {
+        // Off a stop, the first one down is the one just below
+        let from = offset.div_euclid(step) + i64::from(offset % step != 0);
+        from + steps as i64
+    };
+    (min as i64 + stop * step).clamp(min as i64, max as i64) as i32
+}
+
+/// Writes a value the slider moved to and flushes it, so a script reading
+/// the pipe sees it right away.
+fn write_partial(value: i32) {
+    let mut stdout = std::io::stdout().lock();
+    // A reader that went away only misses the live updates
+    let _ = writeln!(stdout, "{value}").and_then(|()| stdout.flush());
+}
+
 impl Default for ScaleBuilder {
     fn default() -> Self {
         Self::new()
     }
 }
+
+/// Placing tick marks and snapping values to steps.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticks_end_at_the_maximum() {
+        assert_eq!(
+            scale_ticks(0, 7, 3, 70),
+            [(0, 0), (3, 30), (6, 60), (7, 70)]
+        );
+        assert_eq!(
+            scale_ticks(0, 9, 3, 90),
+            [(0, 0), (3, 30), (6, 60), (9, 90)]
+        );
+        assert_eq!(scale_ticks(5, 5, 1, 100), [(5, 0)]);
+    }
+
+    #[test]
+    fn ticks_do_not_drift_on_odd_ranges() {
+        // Each tick is placed from its own value, so the last full step lands
+        // where its value says rather than where adding 280 / 7 * 3 would
+        let ticks = scale_ticks(0, 7, 1, 280);
+        assert_eq!(ticks[3], (3, 120));
+        assert_eq!(ticks.last(), Some(&(7, 280)));
+        let ticks = scale_ticks(-1000, 999, 3, 281);
+        for &(value, offset) in &ticks {
+            assert_eq!(offset as i64, (value as i64 + 1000) * 281 / 1999);
+        }
+        assert_eq!(ticks.last(), Some(&(999, 281)));
+    }
+
+    #[test]
+    fn values_snap_to_the_nearest_step() {
+        assert_eq!(snap_to_step(34, 0, 100, 10), 30);
+        assert_eq!(snap_to_step(35, 0, 100, 10), 40);
+        // The maximum is a stop of its own
+        assert_eq!(snap_to_step(7, 0, 7, 3), 7);
+        assert_eq!(snap_to_step(6, 0, 7, 3), 6);
+        assert_eq!(snap_to_step(-3, 1, 10, 4), 1);
+        assert_eq!(snap_to_step(i32::MAX, i32::MIN, i32::MAX, 1000), i32::MAX);
+    }
+
+    #[test]
+    fn steps_move_between_stops() {
+        assert_eq!(step_value(3, 0, 7, 3, 1), 6);
+        assert_eq!(step_value(6, 0, 7, 3, 1), 7);
+        assert_eq!(step_value(7, 0, 7, 3, -1), 6);
+        // A value between stops goes to the next one either way
+        assert_eq!(step_value(4, 0, 100, 10, 1), 10);
+        assert_eq!(step_value(14, 0, 100, 10, -1), 10);
+        assert_eq!(step_value(50, 0, 100, 1, -100), 0);
+        assert_eq!(step_value(50, 0, 100, 1, 100), 100);
+    }
+}
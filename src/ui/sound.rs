@@ -0,0 +1,269 @@
+//! Event sounds from the freedesktop sound theme (`--sound`).
+//!
+//! Sounds are looked up as the sound theme spec describes: in the `sounds`
+//! directory of each XDG data directory, through the theme and the themes it
+//! inherits from, ending with `freedesktop`; a name that isn't found is
+//! shortened at its last `-` (`dialog-error` → `dialog`) and tried again.
+//! The file is played by whichever of `pw-play`, `paplay` or `aplay` is
+//! installed, or by `canberra-gtk-play` when no file is found.
+//!
+//! Everything happens on a background thread and failures are ignored, so a
+//! missing sound never delays or breaks the dialog.
+
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+/// The theme every other theme falls back to.
+pub(crate) const FALLBACK_THEME: &str = "freedesktop";
+/// Extensions tried for each sound, in order.
+const EXTENSIONS: &[&str] = &["oga", "ogg", "wav"];
+/// Players tried in order; `aplay` only handles WAV.
+const PLAYERS: &[(&str, &[&str])] = &[
+    ("pw-play", &["oga", "ogg", "wav"]),
+    ("paplay", &["oga", "ogg", "wav"]),
+    ("aplay", &["wav"]),
+];
+
+/// The `sounds` directories of the XDG data directories, most important
+/// first.
+pub(crate) fn sound_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(data) = crate::xdg::data_dir() {
+        dirs.push(data.join("sounds"));
+    }
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    for dir in data_dirs.split(':').filter(|dir| !dir.is_empty()) {
+        dirs.push(Path::new(dir).join("sounds"));
+    }
+    dirs
+}
+
+/// The sound theme to use: `gtk-sound-theme-name` from the GTK 3 settings,
+/// or [`FALLBACK_THEME`].
+pub(crate) fn theme_name() -> String {
+    crate::xdg::config_dir()
+        .and_then(|config| std::fs::read_to_string(config.join("gtk-3.0/settings.ini")).ok())
+        .and_then(|settings| ini_value(&settings, "gtk-sound-theme-name"))
+        .unwrap_or_else(|| FALLBACK_THEME.to_string())
+}
+
+/// Finds the file for sound `name` in `theme`, searching `dirs` (as from
+/// [`sound_dirs`]). Returns `None` if there is none, or if the theme
+/// disables the sound with a `.disabled` file.
+pub(crate) fn find_sound(name: &str, theme: &str, dirs: &[PathBuf]) -> Option<PathBuf> {
+    let themes = theme_chain(theme, dirs);
+    let mut name = name;
+    loop {
+        for theme in &themes {
+            for subdir in theme_subdirs(theme, dirs) {
+                for dir in dirs {
+                    let base = dir.join(theme).join(&subdir);
+                    if base.join(format!("{name}.disabled")).exists() {
+                        return None;
+                    }
+                    for ext in EXTENSIONS {
+                        let path = base.join(format!("{name}.{ext}"));
+                        if path.is_file() {
+                            return Some(path);
+                        }
+                    }
+                }
+            }
+        }
+        name = &name[..name.rfind('-')?];
+    }
+}
+
+/// Plays sound `name` in the background, if it can be found and played.
+pub(crate) fn play(name: &'static str) {
+    std::thread::spawn(move || {
+        match find_sound(name, &theme_name(), &sound_dirs()) {
+            Some(path) => play_file(&path),
+            None => {
+                let _ = run(Command::new("canberra-gtk-play").args(["-i", name]));
+            }
+        }
+    });
+}
+
+fn play_file(path: &Path) {
+    let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    for (player, formats) in PLAYERS {
+        if formats.contains(&ext) && run(Command::new(player).arg(path)) {
+            return;
+        }
+    }
+}
+
+/// Runs a player to completion; false if it couldn't be started or failed.
+fn run(command: &mut Command) -> bool {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// `theme` followed by the themes it inherits from, ending with
+/// [`FALLBACK_THEME`].
+fn theme_chain(theme: &str, dirs: &[PathBuf]) -> Vec<String> {
+    let mut chain = Vec::new();
+    let mut pending = vec![theme.to_string()];
+    while let Some(theme) = pending.pop() {
+        if chain.contains(&theme) {
+            continue;
+        }
+        if let Some(index) = theme_index(&theme, dirs)
+            && let Some(inherits) = ini_value(&index, "Inherits")
+        {
+            // Pushed in reverse so the first parent is searched first
+            for parent in inherits.rsplit(',').map(str::trim) {
+                if !parent.is_empty() {
+                    pending.push(parent.to_string());
+                }
+            }
+        }
+        chain.push(theme);
+    }
+    if !chain.iter().any(|theme| theme == FALLBACK_THEME) {
+        chain.push(FALLBACK_THEME.to_string());
+    }
+    chain
+}
+
+/// The subdirectories of `theme` holding sounds: its `Directories`, or
+/// `stereo` for themes without an index.
+fn theme_subdirs(theme: &str, dirs: &[PathBuf]) -> Vec<String> {
+    theme_index(theme, dirs)
+        .and_then(|index| ini_value(&index, "Directories"))
+        .map(|list| {
+            list.split(',')
+                .map(str::trim)
+                .filter(|dir| !dir.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .filter(|list| !list.is_empty())
+        .unwrap_or_else(|| vec!["stereo".to_string()])
+}
+
+fn theme_index(theme: &str, dirs: &[PathBuf]) -> Option<String> {
+    dirs.iter()
+        .find_map(|dir| std::fs::read_to_string(dir.join(theme).join("index.theme")).ok())
+}
+
+/// The value of the first `key=value` line for `key`.
+fn ini_value(text: &str, key: &str) -> Option<String> {
+    text.lines().find_map(|line| {
+        let (k, v) = line.split_once('=')?;
+        (k.trim() == key).then(|| v.trim().to_string())
+    })
+}
+
+/// Resolution against theme trees built in a temporary directory. Nothing
+/// is played.
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::ui::Icon;
+
+    /// A scratch sounds directory removed on drop.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir()
+                .join(format!("zenity-rs-sounds-{}-{name}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn touch(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, b"").unwrap();
+    }
+
+    #[test]
+    fn icons_map_to_dialog_sounds() {
+        assert_eq!(Icon::Error.sound_name(), Some("dialog-error"));
+        assert_eq!(Icon::Question.sound_name(), Some("dialog-question"));
+        assert_eq!(Icon::Info.sound_name(), Some("dialog-information"));
+        assert_eq!(Icon::Warning.sound_name(), Some("dialog-warning"));
+        assert_eq!(Icon::Custom("x.png".into()).sound_name(), None);
+    }
+
+    #[test]
+    fn falls_back_through_inherited_themes_to_freedesktop() {
+        let tree = TempDir::new("inherit");
+        let dir = &tree.0;
+        fs::create_dir_all(dir.join("ocean")).unwrap();
+        fs::write(
+            dir.join("ocean/index.theme"),
+            "[Sound Theme]\nName=Ocean\nInherits=deep\nDirectories=stereo\n",
+        )
+        .unwrap();
+        touch(&dir.join("deep/stereo/dialog-question.wav"));
+        touch(&dir.join("freedesktop/stereo/dialog-error.oga"));
+        touch(&dir.join("freedesktop/stereo/dialog-question.oga"));
+        let dirs = [dir.clone()];
+
+        assert_eq!(
+            find_sound("dialog-question", "ocean", &dirs),
+            Some(dir.join("deep/stereo/dialog-question.wav"))
+        );
+        assert_eq!(
+            find_sound("dialog-error", "ocean", &dirs),
+            Some(dir.join("freedesktop/stereo/dialog-error.oga"))
+        );
+        assert_eq!(find_sound("bell", "ocean", &dirs), None);
+    }
+
+    #[test]
+    fn earlier_data_dirs_win_and_names_are_shortened() {
+        let user = TempDir::new("user");
+        let system = TempDir::new("system");
+        touch(&system.0.join("freedesktop/stereo/dialog-error.oga"));
+        touch(&user.0.join("freedesktop/stereo/dialog-error.wav"));
+        touch(&system.0.join("freedesktop/stereo/dialog.oga"));
+        let dirs = [user.0.clone(), system.0.clone()];
+
+        assert_eq!(
+            find_sound("dialog-error", "freedesktop", &dirs),
+            Some(user.0.join("freedesktop/stereo/dialog-error.wav"))
+        );
+        assert_eq!(
+            find_sound("dialog-question", "freedesktop", &dirs),
+            Some(system.0.join("freedesktop/stereo/dialog.oga"))
+        );
+    }
+
+    #[test]
+    fn disabled_sounds_are_not_played() {
+        let tree = TempDir::new("disabled");
+        let dir = &tree.0;
+        fs::create_dir_all(dir.join("quiet")).unwrap();
+        fs::write(dir.join("quiet/index.theme"), "Directories=stereo\n").unwrap();
+        touch(&dir.join("quiet/stereo/dialog-error.disabled"));
+        touch(&dir.join("freedesktop/stereo/dialog-error.oga"));
+        assert_eq!(
+            find_sound("dialog-error", "quiet", std::slice::from_ref(dir)),
+            None
+        );
+    }
+}
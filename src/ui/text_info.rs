@@ -1,15 +1,34 @@
 //! Text info dialog implementation for displaying text from files or stdin.
 
-use std::io::Read;
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, VecDeque},
+    io::Read,
+    ops::Range,
+    rc::Rc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, TryRecvError},
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
 use crate::{
-    backend::{Window, WindowEvent, create_window},
+    backend::{CursorShape, Modifiers, Window, WindowEvent, create_window},
     error::Error,
-    render::{Canvas, Font, rgb},
+    render::{Canvas, Font, TextLayout, parse_font_spec, rgb, sanitize_text},
     ui::{
         BASE_BUTTON_HEIGHT, BASE_BUTTON_SPACING, BASE_CORNER_RADIUS, Colors, KEY_DOWN, KEY_END,
-        KEY_ESCAPE, KEY_HOME, KEY_PAGE_DOWN, KEY_PAGE_UP, KEY_RETURN, KEY_UP,
-        widgets::{Widget, button::Button},
+        KEY_ESCAPE, KEY_F, KEY_HOME, KEY_KP_ENTER, KEY_PAGE_DOWN, KEY_PAGE_UP, KEY_RETURN, KEY_UP,
+        geometry::Offset,
+        html, read_stdin_lines, url, wait_for_event_until,
+        widgets::{
+            Widget, button::Button, scrollbar::Scrollbar, text_editor::TextEditor,
+            text_input::TextInput,
+        },
+        zoom,
     },
 };
 
@@ -20,23 +39,36 @@ const BASE_MIN_WIDTH: u32 = 400;
 const BASE_MIN_HEIGHT: u32 = 300;
 const BASE_DEFAULT_WIDTH: u32 = 500;
 const BASE_DEFAULT_HEIGHT: u32 = 400;
+/// Room at the right of the search bar for the match count.
+const BASE_MATCH_COUNT_WIDTH: u32 = 110;
+/// How often the dialog checks for new lines while stdin is still open.
+const STREAM_CHECK: Duration = Duration::from_millis(50);
 
 /// Text info dialog result.
 #[derive(Debug, Clone)]
 pub enum TextInfoResult {
-    /// User clicked OK. Contains whether checkbox was checked (if present).
-    Ok { checkbox_checked: bool },
+    /// User clicked OK. Contains whether checkbox was checked (if present)
+    /// and, in an [editable](TextInfoBuilder::editable) dialog, the text.
+    Ok {
+        checkbox_checked: bool,
+        text: Option<String>,
+    },
     /// User cancelled the dialog.
     Cancelled,
     /// Dialog was closed.
     Closed,
+    /// The timeout passed.
+    Timeout,
 }
 
 impl TextInfoResult {
+    /// The exit code the command-line tool uses for this result: 0 when
+    /// accepted, 1 when cancelled, closed or accepted with the checkbox
+    /// unchecked, 5 on timeout.
     pub fn exit_code(&self) -> i32 {
         match self {
             TextInfoResult::Ok {
-                checkbox_checked,
+                checkbox_checked, ..
             } => {
                 if *checkbox_checked {
                     0
@@ -46,6 +78,7 @@ impl TextInfoResult {
             }
             TextInfoResult::Cancelled => 1,
             TextInfoResult::Closed => 1,
+            TextInfoResult::Timeout => 5,
         }
     }
 }
@@ -55,9 +88,22 @@ pub struct TextInfoBuilder {
     title: String,
     filename: Option<String>,
     checkbox_text: Option<String>,
+    html: bool,
+    url_support: bool,
+    editable: bool,
+    auto_scroll: bool,
+    max_lines: Option<usize>,
+    font: Option<String>,
     width: Option<u32>,
     height: Option<u32>,
-    colors: Option<&'static Colors>,
+    timeout: Option<u32>,
+    position: Option<(Offset, Offset)>,
+    colors: Option<Colors>,
+    /// How far down the text was scrolled (0.0–1.0) and whether the checkbox
+    /// was checked, carried over a zoom rebuild.
+    restored: Option<(f32, bool)>,
+    /// The text being edited, carried over a zoom rebuild.
+    editor: Option<TextEditor>,
 }
 
 impl TextInfoBuilder {
@@ -66,9 +112,19 @@ impl TextInfoBuilder {
             title: String::new(),
             filename: None,
             checkbox_text: None,
+            html: false,
+            url_support: true,
+            editable: false,
+            auto_scroll: false,
+            max_lines: None,
+            font: None,
             width: None,
             height: None,
+            timeout: None,
+            position: None,
             colors: None,
+            restored: None,
+            editor: None,
         }
     }
 
@@ -83,14 +139,59 @@ impl TextInfoBuilder {
         self
     }
 
-    /// Add a checkbox at the bottom (e.g., "I agree to the terms").
+    /// Add a checkbox at the bottom (e.g., "I agree to the terms"). OK stays
+    /// disabled until it's checked.
     pub fn checkbox(mut self, text: &str) -> Self {
         self.checkbox_text = Some(text.to_string());
         self
     }
 
-    pub fn colors(mut self, colors: &'static Colors) -> Self {
-        self.colors = Some(colors);
+    /// Show the text as HTML: headings, lists, bold, italic, code and
+    /// clickable links. Other markup is dropped; see [`html`].
+    pub fn html(mut self, html: bool) -> Self {
+        self.html = html;
+        self
+    }
+
+    /// Underline `http://` and `https://` URLs in plain text and open them
+    /// in the default browser when clicked (default: on).
+    pub fn url_support(mut self, url_support: bool) -> Self {
+        self.url_support = url_support;
+        self
+    }
+
+    /// Let the user edit the text. OK returns it in [`TextInfoResult::Ok`].
+    /// Overrides [`html`](Self::html).
+    pub fn editable(mut self, editable: bool) -> Self {
+        self.editable = editable;
+        self
+    }
+
+    /// Follow the end of the text, like `tail -f`. Plain text from stdin is
+    /// shown as it arrives, and the view stays at the bottom until scrolled
+    /// up, then follows again once scrolled back down.
+    pub fn auto_scroll(mut self, auto_scroll: bool) -> Self {
+        self.auto_scroll = auto_scroll;
+        self
+    }
+
+    /// Keep only the last `max_lines` lines of plain text, dropping the
+    /// oldest as new ones arrive. Ignored in an editable dialog.
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = Some(max_lines);
+        self
+    }
+
+    /// Show the text in a font of its own, given as a family and an optional
+    /// size in points, as in "JetBrains Mono 12". A family that isn't
+    /// installed gives way to any monospace font there is.
+    pub fn font(mut self, font: &str) -> Self {
+        self.font = Some(font.to_string());
+        self
+    }
+
+    pub fn colors(mut self, colors: impl Borrow<Colors>) -> Self {
+        self.colors = Some(*colors.borrow());
         self
     }
 
@@ -104,8 +205,58 @@ impl TextInfoBuilder {
         self
     }
 
-    pub fn show(self) -> Result<TextInfoResult, Error> {
-        let colors = self.colors.unwrap_or_else(|| crate::ui::detect_theme());
+    /// Set timeout in seconds. Dialog will auto-close after this time.
+    pub fn timeout(mut self, seconds: u32) -> Self {
+        self.timeout = Some(seconds);
+        self
+    }
+
+    /// Request a window position. Ignored where the platform doesn't allow it.
+    pub fn position(mut self, x: Offset, y: Offset) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    /// Whether the text is read from stdin while the dialog is up.
+    fn streaming(&self) -> bool {
+        self.auto_scroll && self.filename.is_none() && !self.html && !self.editable
+    }
+
+    pub fn show(mut self) -> Result<TextInfoResult, Error> {
+        let deadline = self
+            .timeout
+            .map(|secs| Instant::now() + Duration::from_secs(secs as u64));
+
+        if self.streaming() {
+            // Lines are read in the background and shown as they come
+            let (tx, rx) = mpsc::channel();
+            let stop = Arc::new(AtomicBool::new(false));
+            let pump = thread::spawn({
+                let stop = stop.clone();
+                move || {
+                    read_stdin_lines(&stop, |line| {
+                        let line = line.strip_suffix('\n').unwrap_or(line);
+                        let line = line.strip_suffix('\r').unwrap_or(line);
+                        tx.send(sanitize_text(line, false)).is_ok()
+                    })
+                }
+            });
+            let mut stream = Stream {
+                lines: rx,
+                done: false,
+                received: VecDeque::new(),
+            };
+            let result = loop {
+                match self.run("", Some(&mut stream), deadline) {
+                    Ok(None) => continue,
+                    Ok(Some(result)) => break Ok(result),
+                    Err(e) => break Err(e),
+                }
+            };
+            stop.store(true, Ordering::Relaxed);
+            let _ = pump.join();
+            return result;
+        }
 
         // Read content from file or stdin
         let content = if let Some(ref filename) = self.filename {
@@ -117,8 +268,31 @@ impl TextInfoBuilder {
                 .map_err(Error::Io)?;
             buf
         };
+        let content = sanitize_text(&content, false);
 
+        loop {
+            if let Some(result) = self.run(&content, None, deadline)? {
+                return Ok(result);
+            }
+        }
+    }
+
+    /// Shows the dialog until it's answered, or returns `None` to be run
+    /// again after a [zoom](crate::ui::zoom) change. With a `stream`, the
+    /// text is what it has received so far, and grows as it receives more.
+    fn run(
+        &mut self,
+        content: &str,
+        mut stream: Option<&mut Stream>,
+        deadline: Option<Instant>,
+    ) -> Result<Option<TextInfoResult>, Error> {
+        let colors = &self.colors.unwrap_or_else(|| *crate::ui::detect_theme());
         let has_checkbox = self.checkbox_text.is_some();
+        let mut editor = self.editable.then(|| {
+            self.editor
+                .take()
+                .unwrap_or_else(|| TextEditor::new(content))
+        });
 
         // Use provided dimensions or defaults
         let logical_width = self.width.unwrap_or(BASE_DEFAULT_WIDTH).max(BASE_MIN_WIDTH);
@@ -140,10 +314,18 @@ impl TextInfoBuilder {
 
         // Now create everything at PHYSICAL scale
         let font = Font::load(scale);
+        // The text itself may have a font of its own, with rows as tall as
+        // it needs
+        let named_font = self.font.as_deref().map(|spec| {
+            let (family, points) = parse_font_spec(spec);
+            Font::load_named(family, points.map(|points| points * 96.0 / 72.0), scale)
+        });
+        let text_font = named_font.as_ref().unwrap_or(&font);
 
         // Scale dimensions for physical rendering
         let padding = (BASE_PADDING as f32 * scale) as u32;
-        let line_height = (BASE_LINE_HEIGHT as f32 * scale) as u32;
+        let line_height = ((BASE_LINE_HEIGHT as f32 * scale) as u32)
+            .max(text_font.render("Ag").text_layout().line_height().ceil() as u32);
         let checkbox_size = (BASE_CHECKBOX_SIZE as f32 * scale) as u32;
 
         // Calculate physical dimensions
@@ -187,49 +369,53 @@ impl TextInfoBuilder {
 
         // Calculate text wrapping - split content into wrapped lines
         let max_text_width = text_area_w - (16.0 * scale) as u32; // Account for scrollbar
-        let mut wrapped_lines: Vec<String> = Vec::new();
-
-        for line in content.lines() {
-            if line.is_empty() {
-                wrapped_lines.push(String::new());
-            } else {
-                // Wrap long lines
-                let mut remaining = line;
-                while !remaining.is_empty() {
-                    let (line_w, _) = font.render(remaining).measure();
-                    if line_w as u32 <= max_text_width {
-                        wrapped_lines.push(remaining.to_string());
-                        break;
-                    }
-
-                    // Find break point
-                    let mut break_at = remaining.len();
-                    for (i, _) in remaining.char_indices().rev() {
-                        let test = &remaining[..i];
-                        let (w, _) = font.render(test).measure();
-                        if w as u32 <= max_text_width {
-                            // Try to break at word boundary
-                            if let Some(space_pos) = test.rfind(|c: char| c.is_whitespace()) {
-                                break_at = space_pos + 1;
-                            } else {
-                                break_at = i;
-                            }
-                            break;
-                        }
-                    }
-
-                    if break_at == 0 {
-                        break_at = 1; // Ensure progress
-                    }
-
-                    wrapped_lines.push(remaining[..break_at].trim_end().to_string());
-                    remaining = remaining[break_at..].trim_start();
-                }
+        let mut wrapped_lines: Vec<Row> = Vec::new();
+
+        // HTML lines can be taller than a row and take several
+        let html_fonts =
+            (self.html && editor.is_none()).then(|| html::Fonts::new(text_font, text_font.size()));
+        let html_lines = match &html_fonts {
+            Some(fonts) => {
+                let doc = html::parse(content);
+                html::layout(&doc, fonts, max_text_width as f32, line_height, scale)
             }
+            None => Vec::new(),
+        };
+        for (i, line) in html_lines.iter().enumerate() {
+            wrapped_lines.extend((0..line.rows).map(|part| {
+                Row::Html {
+                    line: i,
+                    part,
+                }
+            }));
+        }
+
+        // Edited text isn't wrapped, so rows and lines stay the same
+        if let Some(editor) = &editor {
+            wrapped_lines.extend(editor.lines().iter().cloned().map(Row::Text));
+        }
+        // Plain text is wrapped a line at a time, remembering how many rows
+        // each took so the oldest can be dropped past `max_lines`
+        let plain = html_fonts.is_none() && editor.is_none();
+        let max_lines = self.max_lines.filter(|_| plain);
+        let lines: Vec<&str> = match &stream {
+            Some(stream) => stream.received.iter().map(String::as_str).collect(),
+            None => content.lines().collect(),
+        };
+        let skip = max_lines.map_or(0, |max| lines.len().saturating_sub(max));
+        let mut line_rows: VecDeque<usize> = VecDeque::new();
+        for line in lines.iter().skip(skip).filter(|_| plain) {
+            line_rows.push_back(wrap_line(
+                line,
+                text_font,
+                max_text_width,
+                self.url_support,
+                &mut wrapped_lines,
+            ));
         }
 
-        let total_lines = wrapped_lines.len();
-        let visible_lines = (text_area_h / line_height) as usize;
+        let mut total_lines = wrapped_lines.len();
+        let mut visible_lines = (text_area_h / line_height) as usize;
 
         // Button positions (right-aligned)
         let mut bx = physical_width as i32 - padding as i32;
@@ -241,16 +427,34 @@ impl TextInfoBuilder {
         // State
         let mut scroll_offset = 0usize;
         let mut checkbox_checked = false;
+        if let Some((scrolled, checked)) = self.restored.take() {
+            let max_scroll = total_lines.saturating_sub(visible_lines);
+            scroll_offset = ((scrolled * total_lines as f32) as usize).min(max_scroll);
+            checkbox_checked = checked;
+        } else if self.auto_scroll {
+            scroll_offset = total_lines.saturating_sub(visible_lines);
+        }
+        // Following the end of the text, which stops when scrolled away
+        let mut following =
+            self.auto_scroll && scroll_offset >= total_lines.saturating_sub(visible_lines);
         let mut checkbox_hovered = false;
-        let mut scrollbar_hovered = false;
+        let mut scrollbar = Scrollbar::new((text_area_h as f32 - 8.0 * scale) as u32, scale);
+        scrollbar.set_position(
+            text_area_x + text_area_w as i32 - scrollbar.width() as i32,
+            text_area_y + (4.0 * scale) as i32,
+        );
+        scrollbar.set_metrics(total_lines, visible_lines);
+        scrollbar.set_offset(scroll_offset);
+        ok_button.set_enabled(!has_checkbox || checkbox_checked);
 
         // Create canvas at PHYSICAL dimensions
         let mut canvas = Canvas::new(physical_width, physical_height);
 
-        // Pre-render the static chrome (bg + title + text-area) and each text
-        // line ONCE into opaque canvases. Per-frame work then reduces to raw
-        // byte copies (blit_region) instead of re-rasterizing the background and
-        // dozens of text lines every scroll frame.
+        // Pre-render the static chrome (bg + title + text-area) ONCE into an
+        // opaque canvas, and each text line into its own the first time it
+        // scrolls into view. Per-frame work then reduces to raw byte copies
+        // (blit_region) instead of re-rasterizing the background and dozens of
+        // text lines every scroll frame, and long files open immediately.
         let radius = BASE_CORNER_RADIUS * scale;
         let title_font_size = 18.0 * 1.5 * scale;
         let title_font = Font::load_with_size(title_font_size);
@@ -288,29 +492,21 @@ impl TextInfoBuilder {
             colors.input_border,
             1.0,
         );
-        let line_canvases: Vec<Canvas> = wrapped_lines
-            .iter()
-            .map(|line| {
-                if line.is_empty() {
-                    return Canvas::new(1, 1);
-                }
-                let tc = font.render(line).with_color(colors.text).finish();
-                let mut lc = Canvas::new(tc.width().max(1), line_height);
-                lc.fill(colors.input_bg);
-                lc.draw_canvas(&tc, 0, 0);
-                lc
-            })
-            .collect();
+        let mut line_cache: HashMap<usize, Canvas> = HashMap::new();
 
         // Draw function
         let draw = |canvas: &mut Canvas,
                     colors: &Colors,
                     font: &Font,
+                    text_font: &Font,
                     chrome: &Canvas,
-                    line_canvases: &[Canvas],
-                    wrapped_lines: &[String],
+                    line_cache: &mut HashMap<usize, Canvas>,
+                    wrapped_lines: &[Row],
                     scroll_offset: usize,
                     visible_lines: usize,
+                    rows_y: i32,
+                    matches: &[(usize, Range<usize>)],
+                    current_match: Option<usize>,
                     checkbox_text: &Option<String>,
                     checkbox_checked: bool,
                     checkbox_hovered: bool,
@@ -326,26 +522,79 @@ impl TextInfoBuilder {
                     text_area_h: u32,
                     checkbox_y: i32,
                     scale: f32,
-                    scrollbar_hovered: bool| {
+                    scrollbar: &Scrollbar| {
             // Chrome (opaque) - raw byte copy, far faster than re-rasterizing the
             // full dialog background every frame.
             let cw = canvas.width();
             let ch = canvas.height();
             canvas.blit_region(chrome, 0, 0, cw, ch, 0, 0);
 
-            // Visible text lines (opaque, pre-rendered) - raw copy each.
+            // Visible text lines (opaque, cached) - raw copy each. Lines more
+            // than a page out of view are dropped to bound memory.
             let text_padding = (8.0 * scale) as i32;
-            for (i, line_idx) in
-                (scroll_offset..wrapped_lines.len().min(scroll_offset + visible_lines)).enumerate()
-            {
-                let lc = &line_canvases[line_idx];
+            let end = wrapped_lines.len().min(scroll_offset + visible_lines);
+            line_cache
+                .retain(|&i, _| i + visible_lines >= scroll_offset && i < end + visible_lines);
+            for (i, line_idx) in (scroll_offset..end).enumerate() {
+                let lc = line_cache.entry(line_idx).or_insert_with(|| {
+                    // This row's search matches, and whether each is current
+                    let first = matches.partition_point(|(row, _)| *row < line_idx);
+                    let marks: Vec<(Range<usize>, bool)> = matches[first..]
+                        .iter()
+                        .take_while(|(row, _)| *row == line_idx)
+                        .enumerate()
+                        .map(|(i, (_, range))| (range.clone(), current_match == Some(first + i)))
+                        .collect();
+                    match &wrapped_lines[line_idx] {
+                        Row::Text(text) => {
+                            render_line(text, &marks, text_font, colors, line_height, scale)
+                        }
+                        Row::Linked {
+                            text,
+                            links,
+                        } => {
+                            render_linked_line(
+                                text,
+                                links,
+                                &marks,
+                                text_font,
+                                colors,
+                                line_height,
+                                scale,
+                            )
+                        }
+                        Row::Html {
+                            line,
+                            part,
+                        } => {
+                            let full = html_lines[*line].render(
+                                html_fonts.as_ref().unwrap(),
+                                colors,
+                                max_text_width,
+                                line_height,
+                                scale,
+                            );
+                            let mut row = Canvas::new(full.width(), line_height);
+                            row.blit_region(
+                                &full,
+                                0,
+                                *part as u32 * line_height,
+                                full.width(),
+                                line_height,
+                                0,
+                                0,
+                            );
+                            row
+                        }
+                    }
+                });
                 if lc.width() > 1 {
-                    let y = text_area_y + text_padding + (i as u32 * line_height) as i32;
+                    let y = rows_y + (i as u32 * line_height) as i32;
                     canvas.blit_region(
                         lc,
                         0,
                         0,
-                        lc.width(),
+                        lc.width().min(max_text_width),
                         lc.height(),
                         (text_area_x + text_padding) as u32,
                         y as u32,
@@ -353,48 +602,7 @@ impl TextInfoBuilder {
                 }
             }
 
-            // Scrollbar
-            if wrapped_lines.len() > visible_lines {
-                let scrollbar_width = if scrollbar_hovered {
-                    12.0 * scale
-                } else {
-                    8.0 * scale
-                };
-                let sb_x = text_area_x + text_area_w as i32 - scrollbar_width as i32;
-                let sb_y = text_area_y as f32 + 4.0 * scale;
-                let sb_h = text_area_h as f32 - 8.0 * scale;
-                let thumb_h =
-                    (visible_lines as f32 / wrapped_lines.len() as f32 * sb_h).max(20.0 * scale);
-                let max_scroll = wrapped_lines.len().saturating_sub(visible_lines);
-                let thumb_y = if max_scroll > 0 {
-                    scroll_offset as f32 / max_scroll as f32 * (sb_h - thumb_h)
-                } else {
-                    0.0
-                };
-
-                // Track
-                canvas.fill_rounded_rect(
-                    sb_x as f32,
-                    sb_y,
-                    scrollbar_width - 2.0 * scale,
-                    sb_h,
-                    3.0 * scale,
-                    darken(colors.input_bg, 0.05),
-                );
-                // Thumb
-                canvas.fill_rounded_rect(
-                    sb_x as f32,
-                    sb_y + thumb_y,
-                    scrollbar_width - 2.0 * scale,
-                    thumb_h,
-                    3.0 * scale,
-                    if scrollbar_hovered {
-                        colors.input_border_focused
-                    } else {
-                        colors.input_border
-                    },
-                );
-            }
+            scrollbar.draw(canvas, colors);
 
             // Border
             canvas.stroke_rounded_rect(
@@ -462,22 +670,41 @@ impl TextInfoBuilder {
 
         let mut window_dragging = false;
 
-        // Scrollbar thumb dragging state
-        let mut thumb_drag = false;
-        let mut thumb_drag_offset: Option<i32> = None;
         let mut last_cursor_pos: Option<(i32, i32)> = None;
-        let mut clicking_scrollbar: bool;
+        let text_padding = (8.0 * scale) as i32;
+        // Where the first visible row is drawn, moved down while the search
+        // bar is open
+        let mut text_origin = (text_area_x + text_padding, text_area_y + text_padding);
+
+        // Ctrl+F opens a search bar over the top of the text. `matches` holds
+        // every match as a row and a byte range in it, in order, and
+        // `current_match` the one Enter last went to
+        let mut search: Option<TextInput> = None;
+        let mut matches: Vec<(usize, Range<usize>)> = Vec::new();
+        let mut current_match: Option<usize> = None;
+        let bar_margin = (6.0 * scale) as i32;
+        let bar = (
+            text_area_x + 1,
+            text_area_y + 1,
+            text_area_w - scrollbar.width() - 2,
+            TextInput::new(0).height() + bar_margin as u32 * 2,
+        );
+        let full_visible_lines = visible_lines;
 
         // Initial draw
         draw(
             &mut canvas,
             colors,
             &font,
+            text_font,
             &chrome_canvas,
-            &line_canvases,
+            &mut line_cache,
             &wrapped_lines,
             scroll_offset,
             visible_lines,
+            text_origin.1,
+            &matches,
+            current_match,
             &self.checkbox_text,
             checkbox_checked,
             checkbox_hovered,
@@ -492,18 +719,147 @@ impl TextInfoBuilder {
             text_area_h,
             checkbox_y,
             scale,
-            scrollbar_hovered,
+            &scrollbar,
         );
+        if let Some(editor) = &editor {
+            draw_caret(
+                &mut canvas,
+                editor,
+                text_font,
+                colors,
+                scroll_offset,
+                visible_lines,
+                text_origin,
+                line_height,
+                scale,
+            );
+        }
+        if let Some(input) = &search {
+            draw_search_bar(
+                &mut canvas,
+                input,
+                &match_count(input.text(), matches.len(), current_match),
+                &font,
+                colors,
+                bar,
+                scale,
+            );
+        }
         window.set_contents(&canvas)?;
+        if let Some((x, y)) = self.position {
+            window.set_position(x, y)?;
+        }
         window.show()?;
 
+        // The link under the pointer
+        let link_under = |rows: &[Row],
+                          scroll_offset: usize,
+                          visible_lines: usize,
+                          origin: (i32, i32),
+                          (mx, my): (i32, i32)| {
+            link_at(
+                rows,
+                &html_lines,
+                text_font,
+                scroll_offset,
+                visible_lines,
+                mx - origin.0,
+                my - origin.1,
+                line_height,
+            )
+            .map(str::to_string)
+        };
+        let mut over_link = false;
+
         // Event loop
+        // Events the batch loop read, left for the main loop to handle
+        let mut held: VecDeque<WindowEvent> = VecDeque::new();
         loop {
-            let event = window.wait_for_event()?;
-            let mut needs_redraw = false;
+            // Take in the lines stdin has sent since last time
+            let mut received = false;
+            if let Some(stream) = stream.as_mut().filter(|stream| !stream.done) {
+                loop {
+                    match stream.lines.try_recv() {
+                        Ok(line) => {
+                            line_rows.push_back(wrap_line(
+                                &line,
+                                text_font,
+                                max_text_width,
+                                self.url_support,
+                                &mut wrapped_lines,
+                            ));
+                            stream.received.push_back(line);
+                            received = true;
+                        }
+                        Err(TryRecvError::Empty) => break,
+                        Err(TryRecvError::Disconnected) => {
+                            stream.done = true;
+                            break;
+                        }
+                    }
+                }
+            }
+            if received && let Some(stream) = &mut stream {
+                // Past the limit the oldest lines go, and the view stays on
+                // the text it showed
+                let mut dropped = 0;
+                while max_lines.is_some_and(|max| stream.received.len() > max) {
+                    stream.received.pop_front();
+                    dropped += line_rows.pop_front().unwrap_or(0);
+                }
+                wrapped_lines.drain(..dropped);
+                total_lines = wrapped_lines.len();
+                scrollbar.set_metrics(total_lines, visible_lines);
+                scroll_offset = if following {
+                    total_lines.saturating_sub(visible_lines)
+                } else {
+                    scroll_offset.saturating_sub(dropped)
+                };
+                scrollbar.set_offset(scroll_offset);
+                line_cache.clear();
+                if let Some(input) = &search {
+                    let current = current_match.map(|i| matches[i].clone());
+                    matches = search_rows(&wrapped_lines, input.text());
+                    current_match = current.and_then(|(row, range)| {
+                        matches
+                            .iter()
+                            .position(|m| m.0 + dropped == row && m.1 == range)
+                    });
+                }
+            }
+
+            let event = match held.pop_front() {
+                Some(event) => event,
+                None if received => WindowEvent::RedrawRequested,
+                None => {
+                    // While stdin is open, wake up now and then to check it
+                    let check = stream
+                        .as_ref()
+                        .is_some_and(|stream| !stream.done)
+                        .then(|| Instant::now() + STREAM_CHECK);
+                    let until = match (deadline, check) {
+                        (Some(deadline), Some(check)) => Some(deadline.min(check)),
+                        (deadline, check) => deadline.or(check),
+                    };
+                    match wait_for_event_until(&mut window, until)? {
+                        Some(event) => event,
+                        None if deadline.is_none_or(|deadline| Instant::now() < deadline) => {
+                            continue;
+                        }
+                        None => return Ok(Some(TextInfoResult::Timeout)),
+                    }
+                }
+            };
+            let mut needs_redraw = scrollbar.process_event(&event);
+            let mut edited = false;
+            // The search bar opened or closed, its text changed, or Enter
+            // moved to another match
+            let mut search_toggled = false;
+            let mut query_changed = false;
+            let mut match_moved = false;
 
             match &event {
-                WindowEvent::CloseRequested => return Ok(TextInfoResult::Closed),
+                WindowEvent::CloseRequested => return Ok(Some(TextInfoResult::Closed)),
                 WindowEvent::RedrawRequested => needs_redraw = true,
                 WindowEvent::CursorEnter(pos) | WindowEvent::CursorMove(pos) => {
                     if window_dragging {
@@ -517,138 +873,82 @@ impl TextInfoBuilder {
                     // Store current cursor position
                     last_cursor_pos = Some((mx, my));
 
-                    // Handle scrollbar thumb dragging
-                    if thumb_drag && total_lines > visible_lines {
-                        let text_area_my = my - text_area_y;
-
-                        let sb_y_f32 = 4.0 * scale;
-                        let sb_y = sb_y_f32 as i32;
-                        let sb_h_f32 = text_area_h as f32 - 8.0 * scale;
-                        let sb_h = sb_h_f32 as i32;
-
-                        let max_scroll = total_lines.saturating_sub(visible_lines);
-                        if max_scroll > 0 {
-                            let thumb_h_f32 = (visible_lines as f32 / total_lines as f32
-                                * sb_h_f32)
-                                .max(20.0 * scale);
-                            let thumb_h = thumb_h_f32 as i32;
-                            let max_thumb_y = sb_h - thumb_h;
-
-                            let offset = thumb_drag_offset.unwrap_or(thumb_h / 2);
-                            let thumb_y = (text_area_my - sb_y - offset).clamp(0, max_thumb_y);
-                            let scroll_ratio = if max_thumb_y > 0 {
-                                thumb_y as f32 / max_thumb_y as f32
-                            } else {
-                                0.0
-                            };
-                            scroll_offset =
-                                ((scroll_ratio * max_scroll as f32) as usize).clamp(0, max_scroll);
-                            needs_redraw = true;
-                        }
-                    } else {
-                        // Update scrollbar hover state (always, not just when there's a checkbox)
-                        let scrollbar_width = if scrollbar_hovered {
-                            12.0 * scale
+                    // A hand over links, unless dragging the scrollbar
+                    let now_over_link = !scrollbar.is_dragging()
+                        && !scrollbar.contains(mx, my)
+                        && link_under(
+                            &wrapped_lines,
+                            scroll_offset,
+                            visible_lines,
+                            text_origin,
+                            (mx, my),
+                        )
+                        .is_some();
+                    if now_over_link != over_link {
+                        over_link = now_over_link;
+                        let _ = window.set_cursor(if over_link {
+                            CursorShape::Pointer
                         } else {
-                            8.0 * scale
-                        };
-                        let scrollbar_x = text_area_x + text_area_w as i32 - scrollbar_width as i32;
-
-                        scrollbar_hovered = total_lines > visible_lines
-                            && mx >= scrollbar_x
-                            && mx < text_area_x + text_area_w as i32
-                            && my >= text_area_y
-                            && my < text_area_y + text_area_h as i32;
-
-                        if has_checkbox {
-                            // Check if hovering checkbox area (only if not over scrollbar)
-                            let cb_x = padding as i32;
-                            let cb_row_width = checkbox_size as i32 + (8.0 * scale) as i32 + 200; // Approximate label width
-                            let old_hovered = checkbox_hovered;
-                            checkbox_hovered = !scrollbar_hovered
-                                && mx >= cb_x
-                                && mx < cb_x + cb_row_width
-                                && my >= checkbox_y
-                                && my < checkbox_y + checkbox_size as i32;
-
-                            if old_hovered != checkbox_hovered {
-                                needs_redraw = true;
-                            }
+                            CursorShape::Default
+                        });
+                    }
+
+                    if has_checkbox && !scrollbar.is_dragging() {
+                        // Check if hovering checkbox area (only if not over scrollbar)
+                        let cb_x = padding as i32;
+                        let cb_row_width = checkbox_size as i32 + (8.0 * scale) as i32 + 200; // Approximate label width
+                        let old_hovered = checkbox_hovered;
+                        checkbox_hovered = !scrollbar.is_hovered()
+                            && mx >= cb_x
+                            && mx < cb_x + cb_row_width
+                            && my >= checkbox_y
+                            && my < checkbox_y + checkbox_size as i32;
+
+                        if old_hovered != checkbox_hovered {
+                            needs_redraw = true;
                         }
                     }
                 }
                 WindowEvent::ButtonPress(crate::backend::MouseButton::Left, _) => {
-                    window_dragging = true;
-                    clicking_scrollbar = false;
-
-                    // Check if clicking anywhere in scrollbar area (thumb OR track)
-                    if let Some((mx, my)) = last_cursor_pos {
-                        if total_lines > visible_lines {
-                            let scrollbar_width = if scrollbar_hovered {
-                                12.0 * scale
-                            } else {
-                                8.0 * scale
-                            };
-                            let scrollbar_x =
-                                text_area_x + text_area_w as i32 - scrollbar_width as i32;
-
-                            // Block all clicks in scrollbar area
-                            if mx >= scrollbar_x
-                                && mx < text_area_x + text_area_w as i32
-                                && my >= text_area_y
-                                && my < text_area_y + text_area_h as i32
-                            {
-                                clicking_scrollbar = true;
-
-                                // Now check if clicking specifically on the thumb for dragging
-                                let text_area_mx = mx - text_area_x;
-                                let text_area_my = my - text_area_y;
-
-                                let sb_x = text_area_w as i32 - scrollbar_width as i32;
-                                let sb_y_f32 = 4.0 * scale;
-                                let sb_y = sb_y_f32 as i32;
-                                let sb_h_f32 = text_area_h as f32 - 8.0 * scale;
-                                let sb_h = sb_h_f32 as i32;
-
-                                let thumb_h_f32 = (visible_lines as f32 / total_lines as f32
-                                    * sb_h_f32)
-                                    .max(20.0 * scale);
-                                let thumb_h = thumb_h_f32 as i32;
-
-                                let max_scroll = total_lines.saturating_sub(visible_lines);
-                                let thumb_y = if max_scroll > 0 {
-                                    let max_thumb_y = sb_h - thumb_h;
-                                    ((scroll_offset as f32 / max_scroll as f32)
-                                        * max_thumb_y as f32)
-                                        as i32
-                                } else {
-                                    0
-                                };
-
-                                if text_area_mx >= sb_x
-                                    && text_area_mx < sb_x + scrollbar_width as i32
-                                    && text_area_my >= sb_y + thumb_y
-                                    && text_area_my < sb_y + thumb_y + thumb_h
-                                {
-                                    thumb_drag = true;
-                                    thumb_drag_offset = Some(text_area_my - (sb_y + thumb_y));
-                                }
-                            }
-                        }
+                    // Clicks on the scrollbar and the search bar are their own
+                    let clicking_scrollbar =
+                        last_cursor_pos.is_some_and(|(mx, my)| scrollbar.contains(mx, my));
+                    let clicking_bar = search.is_some()
+                        && last_cursor_pos.is_some_and(|(mx, my)| {
+                            mx >= bar.0
+                                && mx < bar.0 + bar.2 as i32
+                                && my >= bar.1
+                                && my < bar.1 + bar.3 as i32
+                        });
+                    window_dragging = !clicking_scrollbar && !clicking_bar;
+
+                    // Links open instead of dragging the window
+                    if !clicking_scrollbar
+                        && !clicking_bar
+                        && let Some(pos) = last_cursor_pos
+                        && let Some(link) = link_under(
+                            &wrapped_lines,
+                            scroll_offset,
+                            visible_lines,
+                            text_origin,
+                            pos,
+                        )
+                    {
+                        html::open_link(&link);
+                        window_dragging = false;
                     }
 
                     // Only process checkbox click if not clicking on scrollbar
                     if !clicking_scrollbar && checkbox_hovered {
                         checkbox_checked = !checkbox_checked;
+                        ok_button.set_enabled(checkbox_checked);
                         needs_redraw = true;
                     }
                 }
                 WindowEvent::ButtonRelease(_, _) => {
                     window_dragging = false;
-                    thumb_drag = false;
-                    thumb_drag_offset = None;
                 }
-                WindowEvent::Scroll(direction) => {
+                WindowEvent::Scroll(direction, _) => {
                     match direction {
                         crate::backend::ScrollDirection::Up => {
                             if scroll_offset > 0 {
@@ -667,57 +967,114 @@ impl TextInfoBuilder {
                     }
                 }
                 WindowEvent::TextInput(c) => {
-                    // Handle space for checkbox toggle (TextInput is sent for printable chars)
-                    if *c == ' ' && has_checkbox {
+                    if let Some(input) = &mut search {
+                        query_changed = input.process_event(&event);
+                    } else if let Some(editor) = &mut editor {
+                        edited = editor.process_event(&event);
+                    } else if *c == ' ' && has_checkbox {
+                        // Handle space for checkbox toggle (TextInput is sent for printable chars)
                         checkbox_checked = !checkbox_checked;
+                        ok_button.set_enabled(checkbox_checked);
                         needs_redraw = true;
                     }
                 }
                 WindowEvent::KeyPress(key_event) => {
+                    if zoom::handle_key(key_event) {
+                        let scrolled = scroll_offset as f32 / total_lines.max(1) as f32;
+                        self.restored = Some((scrolled, checkbox_checked));
+                        self.editor = editor;
+                        return Ok(None);
+                    }
                     let max_scroll = total_lines.saturating_sub(visible_lines);
-
-                    match key_event.keysym {
-                        KEY_UP => {
-                            if scroll_offset > 0 {
-                                scroll_offset = scroll_offset.saturating_sub(1);
-                                needs_redraw = true;
-                            }
+                    if key_event.keysym == KEY_F && key_event.modifiers.contains(Modifiers::CTRL) {
+                        if search.is_none() {
+                            let count_width = (BASE_MATCH_COUNT_WIDTH as f32 * scale) as u32;
+                            let mut input =
+                                TextInput::new(bar.2 - bar_margin as u32 * 2 - count_width)
+                                    .with_placeholder("Find");
+                            input.set_focus(true);
+                            input.set_position(bar.0 + bar_margin, bar.1 + bar_margin);
+                            search = Some(input);
+                            search_toggled = true;
                         }
-                        KEY_DOWN => {
-                            if scroll_offset < max_scroll {
-                                scroll_offset = (scroll_offset + 1).min(max_scroll);
-                                needs_redraw = true;
+                    } else if let Some(input) = &mut search
+                        && !matches!(
+                            key_event.keysym,
+                            KEY_UP | KEY_DOWN | KEY_PAGE_UP | KEY_PAGE_DOWN
+                        )
+                    {
+                        match key_event.keysym {
+                            // The first Escape only closes the search bar
+                            KEY_ESCAPE => {
+                                search = None;
+                                search_toggled = true;
                             }
+                            // Enter goes to the next match, Shift+Enter the previous
+                            KEY_RETURN | KEY_KP_ENTER if !matches.is_empty() => {
+                                let back = key_event.modifiers.contains(Modifiers::SHIFT);
+                                let count = matches.len();
+                                current_match = Some(match current_match {
+                                    Some(i) if back => (i + count - 1) % count,
+                                    Some(i) => (i + 1) % count,
+                                    None => 0,
+                                });
+                                match_moved = true;
+                            }
+                            KEY_RETURN | KEY_KP_ENTER => {}
+                            _ => query_changed = input.process_event(&event),
                         }
-                        KEY_PAGE_UP => {
-                            scroll_offset = scroll_offset.saturating_sub(visible_lines);
-                            needs_redraw = true;
-                        }
-                        KEY_PAGE_DOWN => {
-                            scroll_offset = (scroll_offset + visible_lines).min(max_scroll);
-                            needs_redraw = true;
-                        }
-                        KEY_HOME => {
-                            if scroll_offset > 0 {
-                                scroll_offset = 0;
+                    // Keys the editor doesn't use still scroll and cancel
+                    } else if key_event.keysym != KEY_ESCAPE
+                        && search.is_none()
+                        && let Some(editor) = &mut editor
+                        && editor.process_event(&event)
+                    {
+                        edited = true;
+                    } else {
+                        match key_event.keysym {
+                            KEY_UP => {
+                                if scroll_offset > 0 {
+                                    scroll_offset = scroll_offset.saturating_sub(1);
+                                    needs_redraw = true;
+                                }
+                            }
+                            KEY_DOWN => {
+                                if scroll_offset < max_scroll {
+                                    scroll_offset = (scroll_offset + 1).min(max_scroll);
+                                    needs_redraw = true;
+                                }
+                            }
+                            KEY_PAGE_UP => {
+                                scroll_offset = scroll_offset.saturating_sub(visible_lines);
                                 needs_redraw = true;
                             }
-                        }
-                        KEY_END => {
-                            if scroll_offset < max_scroll {
-                                scroll_offset = max_scroll;
+                            KEY_PAGE_DOWN => {
+                                scroll_offset = (scroll_offset + visible_lines).min(max_scroll);
                                 needs_redraw = true;
                             }
+                            KEY_HOME => {
+                                if scroll_offset > 0 {
+                                    scroll_offset = 0;
+                                    needs_redraw = true;
+                                }
+                            }
+                            KEY_END => {
+                                if scroll_offset < max_scroll {
+                                    scroll_offset = max_scroll;
+                                    needs_redraw = true;
+                                }
+                            }
+                            KEY_RETURN if ok_button.is_enabled() => {
+                                return Ok(Some(TextInfoResult::Ok {
+                                    checkbox_checked,
+                                    text: None,
+                                }));
+                            }
+                            KEY_ESCAPE => {
+                                return Ok(Some(TextInfoResult::Cancelled));
+                            }
+                            _ => {}
                         }
-                        KEY_RETURN => {
-                            return Ok(TextInfoResult::Ok {
-                                checkbox_checked,
-                            });
-                        }
-                        KEY_ESCAPE => {
-                            return Ok(TextInfoResult::Cancelled);
-                        }
-                        _ => {}
                     }
                 }
                 _ => {}
@@ -727,69 +1084,103 @@ impl TextInfoBuilder {
             needs_redraw |= cancel_button.process_event(&event);
 
             if ok_button.was_clicked() {
-                return Ok(TextInfoResult::Ok {
+                return Ok(Some(TextInfoResult::Ok {
                     checkbox_checked,
-                });
+                    text: editor.as_ref().map(TextEditor::text),
+                }));
             }
             if cancel_button.was_clicked() {
-                return Ok(TextInfoResult::Cancelled);
+                return Ok(Some(TextInfoResult::Cancelled));
             }
 
-            // Batch process pending events
-            while let Some(ev) = window.poll_for_event()? {
+            // Batch pending pointer motion down to the last move, and typing
+            // the editor takes, which can come faster than redraws. Anything
+            // else is left for the main loop, after that move
+            let mut last_move = None;
+            while held.is_empty()
+                && let Some(ev) = window.poll_for_event()?
+            {
                 match &ev {
-                    WindowEvent::CloseRequested => {
-                        return Ok(TextInfoResult::Closed);
-                    }
-                    WindowEvent::CursorEnter(pos) | WindowEvent::CursorMove(pos) => {
-                        last_cursor_pos = Some((pos.x as i32, pos.y as i32));
-                    }
-                    WindowEvent::ButtonPress(button, _modifiers)
-                        if *button == crate::backend::MouseButton::Left =>
+                    WindowEvent::CursorMove(_) => last_move = Some(ev),
+                    WindowEvent::KeyPress(key_event)
+                        if key_event.keysym != KEY_ESCAPE
+                            && search.is_none()
+                            && let Some(editor) = &mut editor
+                            && editor.process_event(&ev) =>
                     {
-                        if let Some((mx, my)) = last_cursor_pos {
-                            if total_lines > visible_lines {
-                                let sb_x = text_area_w as i32 - (10.0 * scale) as i32;
-                                let sb_y_f32 = 4.0 * scale;
-                                let sb_y = sb_y_f32 as i32;
-                                let sb_h_f32 = text_area_h as f32 - 8.0 * scale;
-                                let sb_h = sb_h_f32 as i32;
-
-                                let thumb_h_f32 = (visible_lines as f32 / total_lines as f32
-                                    * sb_h_f32)
-                                    .max(20.0 * scale);
-                                let thumb_h = thumb_h_f32 as i32;
-
-                                let max_scroll = total_lines.saturating_sub(visible_lines);
-                                let max_thumb_y = sb_h - thumb_h;
-                                let thumb_y = if max_scroll > 0 {
-                                    ((scroll_offset as f32 / max_scroll as f32)
-                                        * max_thumb_y as f32)
-                                        as i32
-                                } else {
-                                    0
-                                };
-
-                                if mx >= text_area_x + sb_x
-                                    && mx < text_area_x + sb_x + (6.0 * scale) as i32
-                                    && my >= text_area_y + sb_y + thumb_y
-                                    && my < text_area_y + sb_y + thumb_y + thumb_h
-                                {
-                                    thumb_drag = true;
-                                    thumb_drag_offset = Some(my - (text_area_y + sb_y + thumb_y));
-                                }
-                            }
-                        }
+                        edited = true;
                     }
-                    WindowEvent::ButtonRelease(_, _) => {
-                        thumb_drag = false;
-                        thumb_drag_offset = None;
+                    WindowEvent::TextInput(_)
+                        if search.is_none()
+                            && let Some(editor) = &mut editor
+                            && editor.process_event(&ev) =>
+                    {
+                        edited = true;
                     }
-                    _ => {}
+                    _ => held.push_back(ev),
                 }
+            }
+            if let Some(ev) = last_move {
+                held.push_front(ev);
+            }
 
-                needs_redraw |= ok_button.process_event(&ev);
-                needs_redraw |= cancel_button.process_event(&ev);
+            if edited && let Some(editor) = &editor {
+                wrapped_lines = editor.lines().iter().cloned().map(Row::Text).collect();
+                total_lines = wrapped_lines.len();
+                scrollbar.set_metrics(total_lines, visible_lines);
+                line_cache.clear();
+                // Keep the cursor in view
+                let (line, _) = editor.cursor();
+                if line < scroll_offset {
+                    scroll_offset = line;
+                } else if line >= scroll_offset + visible_lines {
+                    scroll_offset = line + 1 - visible_lines;
+                }
+                needs_redraw = true;
+            }
+
+            if search_toggled {
+                // Rows start below the bar while it's open
+                let bar_height = if search.is_some() { bar.3 } else { 0 };
+                text_origin.1 = text_area_y + text_padding + bar_height as i32;
+                visible_lines = (text_area_h - bar_height) as usize / line_height as usize;
+                visible_lines = visible_lines.min(full_visible_lines);
+                scrollbar.set_metrics(total_lines, visible_lines);
+                scroll_offset = scroll_offset.min(total_lines.saturating_sub(visible_lines));
+                query_changed = true;
+                needs_redraw = true;
+            }
+            if query_changed {
+                let query = search.as_ref().map_or("", |input| input.text());
+                matches = search_rows(&wrapped_lines, query);
+                // Typing finds the first match from the top of the view down
+                current_match = (!matches.is_empty()).then(|| {
+                    let first = matches.partition_point(|(row, _)| *row < scroll_offset);
+                    if first < matches.len() { first } else { 0 }
+                });
+                match_moved = current_match.is_some();
+                line_cache.clear();
+                needs_redraw = true;
+            }
+            if match_moved && let Some(i) = current_match {
+                // Bring the current match into view
+                let (row, _) = matches[i];
+                if row < scroll_offset {
+                    scroll_offset = row;
+                } else if row >= scroll_offset + visible_lines {
+                    scroll_offset = row + 1 - visible_lines;
+                }
+                line_cache.clear();
+                needs_redraw = true;
+            }
+
+            if scrollbar.changed() {
+                scroll_offset = scrollbar.offset();
+            } else {
+                scrollbar.set_offset(scroll_offset);
+            }
+            if self.auto_scroll {
+                following = scroll_offset >= total_lines.saturating_sub(visible_lines);
             }
 
             if needs_redraw {
@@ -797,11 +1188,15 @@ impl TextInfoBuilder {
                     &mut canvas,
                     colors,
                     &font,
+                    text_font,
                     &chrome_canvas,
-                    &line_canvases,
+                    &mut line_cache,
                     &wrapped_lines,
                     scroll_offset,
                     visible_lines,
+                    text_origin.1,
+                    &matches,
+                    current_match,
                     &self.checkbox_text,
                     checkbox_checked,
                     checkbox_hovered,
@@ -816,8 +1211,32 @@ impl TextInfoBuilder {
                     text_area_h,
                     checkbox_y,
                     scale,
-                    scrollbar_hovered,
+                    &scrollbar,
                 );
+                if let Some(editor) = &editor {
+                    draw_caret(
+                        &mut canvas,
+                        editor,
+                        text_font,
+                        colors,
+                        scroll_offset,
+                        visible_lines,
+                        text_origin,
+                        line_height,
+                        scale,
+                    );
+                }
+                if let Some(input) = &search {
+                    draw_search_bar(
+                        &mut canvas,
+                        input,
+                        &match_count(input.text(), matches.len(), current_match),
+                        &font,
+                        colors,
+                        bar,
+                        scale,
+                    );
+                }
                 window.set_contents(&canvas)?;
             }
         }
@@ -830,6 +1249,400 @@ impl Default for TextInfoBuilder {
     }
 }
 
+/// Text still arriving on stdin, for [`TextInfoBuilder::auto_scroll`].
+struct Stream {
+    lines: Receiver<String>,
+    /// Whether stdin has closed.
+    done: bool,
+    /// The lines received so far, up to `max_lines` of them, carried over a
+    /// zoom rebuild.
+    received: VecDeque<String>,
+}
+
+/// One row of the text area.
+enum Row {
+    Text(String),
+    /// Plain text with URLs in it: the byte range of each in the row, and
+    /// the whole URL, which may go on in other rows.
+    Linked {
+        text: String,
+        links: Vec<(Range<usize>, Rc<str>)>,
+    },
+    /// Row `part` of HTML line `line`, which can take several rows.
+    Html {
+        line: usize,
+        part: usize,
+    },
+}
+
+/// Wraps a line of plain text into rows at most `max_width` wide, breaking
+/// between words where it can, and appends them to `rows`. Returns how many
+/// it took.
+fn wrap_line(
+    line: &str,
+    font: &Font,
+    max_width: u32,
+    url_support: bool,
+    rows: &mut Vec<Row>,
+) -> usize {
+    if line.is_empty() {
+        rows.push(Row::Text(String::new()));
+        return 1;
+    }
+
+    // URLs are found in whole lines, so one wrapped over several rows
+    // still opens in full from any of them
+    let line_urls: Vec<(Range<usize>, Rc<str>)> = if url_support {
+        url::find_urls(line)
+            .into_iter()
+            .map(|range| (range.clone(), Rc::from(&line[range])))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    // The row holding `text`, a slice of `line`, with its part of each URL
+    let row = |text: &str| {
+        let start = text.as_ptr() as usize - line.as_ptr() as usize;
+        let end = start + text.len();
+        let links: Vec<(Range<usize>, Rc<str>)> = line_urls
+            .iter()
+            .filter(|(range, _)| range.start < end && range.end > start)
+            .map(|(range, url)| {
+                let part = range.start.max(start) - start..range.end.min(end) - start;
+                (part, url.clone())
+            })
+            .collect();
+        if links.is_empty() {
+            Row::Text(text.to_string())
+        } else {
+            Row::Linked {
+                text: text.to_string(),
+                links,
+            }
+        }
+    };
+
+    let first = rows.len();
+    let mut remaining = line;
+    while !remaining.is_empty() {
+        let (line_w, _) = font.render(remaining).measure();
+        if line_w as u32 <= max_width {
+            rows.push(row(remaining));
+            break;
+        }
+
+        // Binary search for the longest prefix that fits
+        let bounds: Vec<usize> = remaining.char_indices().map(|(i, _)| i).collect();
+        let (mut lo, mut hi) = (0, bounds.len());
+        while lo + 1 < hi {
+            let mid = (lo + hi) / 2;
+            let (w, _) = font.render(&remaining[..bounds[mid]]).measure();
+            if w as u32 <= max_width {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        let fit = &remaining[..bounds[lo]];
+        // Try to break at word boundary
+        let mut break_at = match fit.rfind(|c: char| c.is_whitespace()) {
+            Some(space_pos) => space_pos + 1,
+            None => fit.len(),
+        };
+
+        if break_at == 0 {
+            // Ensure progress by at least one character
+            break_at = bounds.get(1).copied().unwrap_or(remaining.len());
+        }
+
+        rows.push(row(remaining[..break_at].trim_end()));
+        remaining = remaining[break_at..].trim_start();
+    }
+    rows.len() - first
+}
+
+/// The link at (`x`, `y`) relative to the first visible row, if any.
+#[allow(clippy::too_many_arguments)]
+fn link_at<'a>(
+    rows: &'a [Row],
+    html_lines: &'a [html::Line],
+    font: &Font,
+    scroll_offset: usize,
+    visible_lines: usize,
+    x: i32,
+    y: i32,
+    line_height: u32,
+) -> Option<&'a str> {
+    if x < 0 || y < 0 || y as usize / line_height as usize >= visible_lines {
+        return None;
+    }
+    match rows.get(scroll_offset + y as usize / line_height as usize)? {
+        Row::Html {
+            line, ..
+        } => html_lines[*line].link_at(x as f32),
+        Row::Linked {
+            text,
+            links,
+        } => {
+            links
+                .iter()
+                .find(|(range, _)| {
+                    let x = x as f32;
+                    x >= text_width(&text[..range.start], font)
+                        && x < text_width(&text[..range.end], font)
+                })
+                .map(|(_, url)| &**url)
+        }
+        Row::Text(_) => None,
+    }
+}
+
+/// Draws the editing cursor, if its line is in view. `origin` is where the
+/// first visible line is drawn.
+#[allow(clippy::too_many_arguments)]
+fn draw_caret(
+    canvas: &mut Canvas,
+    editor: &TextEditor,
+    font: &Font,
+    colors: &Colors,
+    scroll_offset: usize,
+    visible_lines: usize,
+    origin: (i32, i32),
+    line_height: u32,
+    scale: f32,
+) {
+    let (line, column) = editor.cursor();
+    if line < scroll_offset || line >= scroll_offset + visible_lines {
+        return;
+    }
+    let before: String = editor.lines()[line].chars().take(column).collect();
+    let x = if before.is_empty() {
+        0.0
+    } else {
+        font.render(&before).finish().width() as f32
+    };
+    let y = ((line - scroll_offset) as u32 * line_height) as f32;
+    canvas.fill_rect(
+        origin.0 as f32 + x,
+        origin.1 as f32 + y,
+        scale.max(1.0),
+        line_height as f32,
+        colors.text,
+    );
+}
+
+/// Renders one text line onto an opaque text-area background, with search
+/// `marks` behind it.
+fn render_line(
+    line: &str,
+    marks: &[(Range<usize>, bool)],
+    font: &Font,
+    colors: &Colors,
+    line_height: u32,
+    scale: f32,
+) -> Canvas {
+    if line.is_empty() {
+        return Canvas::new(1, 1);
+    }
+    let text = font.render(line).with_color(colors.text);
+    let layout = text.text_layout();
+    let tc = text.finish();
+    let mut lc = Canvas::new(tc.width().max(1), line_height);
+    lc.fill(colors.input_bg);
+    draw_marks(&mut lc, &layout, marks, colors, scale);
+    lc.draw_canvas(&tc, 0, 0);
+    lc
+}
+
+/// Renders a line with URLs in it, underlined in the accent color, with
+/// search `marks` behind it.
+fn render_linked_line(
+    line: &str,
+    links: &[(Range<usize>, Rc<str>)],
+    marks: &[(Range<usize>, bool)],
+    font: &Font,
+    colors: &Colors,
+    line_height: u32,
+    scale: f32,
+) -> Canvas {
+    let mut lc = Canvas::new(text_width(line, font).ceil().max(1.0) as u32, line_height);
+    lc.fill(colors.input_bg);
+    let layout = font.render(line).text_layout();
+    draw_marks(&mut lc, &layout, marks, colors, scale);
+    let px = scale.max(1.0);
+    let baseline = font.render("A").text_layout().line_height() * 0.8;
+    let mut drawn = 0;
+    for (range, _) in links {
+        for (part, color) in [
+            (drawn..range.start, colors.text),
+            (range.clone(), colors.input_border_focused),
+        ] {
+            if part.is_empty() {
+                continue;
+            }
+            // Measured from the start of the line, so kerning across the
+            // joins doesn't shift anything
+            let x = text_width(&line[..part.start], font);
+            let tc = font.render(&line[part.clone()]).with_color(color).finish();
+            lc.draw_canvas(&tc, x.round() as i32, 0);
+            if color == colors.input_border_focused {
+                let width = text_width(&line[..part.end], font) - x;
+                lc.fill_rect(x, baseline + px, width, px, color);
+            }
+        }
+        drawn = range.end;
+    }
+    if drawn < line.len() {
+        let x = text_width(&line[..drawn], font);
+        let tc = font.render(&line[drawn..]).with_color(colors.text).finish();
+        lc.draw_canvas(&tc, x.round() as i32, 0);
+    }
+    lc
+}
+
+/// Highlights search matches, from the glyph positions in `layout`. The
+/// current match is outlined as well.
+fn draw_marks(
+    canvas: &mut Canvas,
+    layout: &TextLayout,
+    marks: &[(Range<usize>, bool)],
+    colors: &Colors,
+    scale: f32,
+) {
+    for (range, current) in marks {
+        for (x, y, w, h) in layout.range_rects(range.clone()) {
+            canvas.fill_rect(x, y, w, h, colors.selection);
+            if *current {
+                canvas.stroke_rounded_rect(
+                    x,
+                    y,
+                    w,
+                    h,
+                    2.0 * scale,
+                    colors.input_border_focused,
+                    scale.max(1.0),
+                );
+            }
+        }
+    }
+}
+
+/// The byte ranges where `query` appears in `text`, ignoring case, without
+/// overlapping. An empty query matches nothing.
+pub(crate) fn find_matches(text: &str, query: &str) -> Vec<Range<usize>> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut found = Vec::new();
+    let mut from = 0;
+    for (start, _) in text.char_indices() {
+        if start < from {
+            continue;
+        }
+        // Lowercasing can turn one character into several, so compare the
+        // lowercased characters as they come and stop on a whole character
+        let mut wanted = query.iter();
+        let mut end = None;
+        for (i, c) in text[start..].char_indices() {
+            if c.to_lowercase().any(|lower| wanted.next() != Some(&lower)) {
+                break;
+            }
+            if wanted.as_slice().is_empty() {
+                end = Some(start + i + c.len_utf8());
+                break;
+            }
+        }
+        if let Some(end) = end {
+            found.push(start..end);
+            from = end;
+        }
+    }
+    found
+}
+
+/// Every match of `query` in the rows, as a row and a byte range in it.
+/// HTML rows aren't searched.
+fn search_rows(rows: &[Row], query: &str) -> Vec<(usize, Range<usize>)> {
+    rows.iter()
+        .enumerate()
+        .flat_map(|(i, row)| {
+            let text = match row {
+                Row::Text(text)
+                | Row::Linked {
+                    text, ..
+                } => text.as_str(),
+                Row::Html {
+                    ..
+                } => "",
+            };
+            find_matches(text, query)
+                .into_iter()
+                .map(move |range| (i, range))
+        })
+        .collect()
+}
+
+/// The search bar's count: "3 of 17", "17 matches" once the current one has
+/// scrolled off the top of a stream, or "No matches".
+fn match_count(query: &str, count: usize, current: Option<usize>) -> String {
+    match current {
+        _ if query.is_empty() => String::new(),
+        Some(i) => format!("{} of {count}", i + 1),
+        None if count > 0 => format!("{count} matches"),
+        None => "No matches".to_string(),
+    }
+}
+
+/// Draws the search bar across the top of the text area, given as
+/// (x, y, width, height): the search field and the match count.
+fn draw_search_bar(
+    canvas: &mut Canvas,
+    input: &TextInput,
+    count: &str,
+    font: &Font,
+    colors: &Colors,
+    (x, y, width, height): (i32, i32, u32, u32),
+    scale: f32,
+) {
+    canvas.fill_rect(
+        x as f32,
+        y as f32,
+        width as f32,
+        height as f32,
+        colors.input_bg,
+    );
+    canvas.fill_rect(
+        x as f32,
+        (y + height as i32) as f32 - scale.max(1.0),
+        width as f32,
+        scale.max(1.0),
+        colors.input_border,
+    );
+    input.draw_to(canvas, colors, font);
+    if !count.is_empty() {
+        let tc = font
+            .render(count)
+            .with_color(colors.input_placeholder)
+            .finish();
+        let margin = (8.0 * scale) as i32;
+        canvas.draw_canvas(
+            &tc,
+            x + width as i32 - margin - tc.width() as i32,
+            y + (height as i32 - tc.height() as i32) / 2,
+        );
+    }
+}
+
+/// How wide `text` is drawn.
+fn text_width(text: &str, font: &Font) -> f32 {
+    if text.is_empty() {
+        0.0
+    } else {
+        font.render(text).measure().0
+    }
+}
+
 fn darken(color: crate::render::Rgba, amount: f32) -> crate::render::Rgba {
     rgb(
         (color.r as f32 * (1.0 - amount)) as u8,
@@ -837,3 +1650,34 @@ fn darken(color: crate::render::Rgba, amount: f32) -> crate::render::Rgba {
         (color.b as f32 * (1.0 - amount)) as u8,
     )
 }
+
+/// Finding what Ctrl+F searches for.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_ignore_case() {
+        assert_eq!(
+            find_matches("Error: error ERROR", "error"),
+            [0..5, 7..12, 13..18]
+        );
+        assert_eq!(find_matches("Größe GRÖSSE", "grö"), [0..4, 8..12]);
+        assert!(find_matches("anything", "").is_empty());
+        assert!(find_matches("short", "shorter").is_empty());
+    }
+
+    #[test]
+    fn matches_do_not_overlap() {
+        assert_eq!(find_matches("aaaa", "aa"), [0..2, 2..4]);
+        assert_eq!(find_matches("abababa", "aba"), [0..3, 4..7]);
+    }
+
+    #[test]
+    fn matches_end_on_whole_characters() {
+        // "İ" lowercases to "i" and a combining dot, so "i" alone isn't it
+        assert!(find_matches("İ", "i").is_empty());
+        assert_eq!(find_matches("İ", "i\u{307}"), vec![0..2]);
+        assert_eq!(find_matches("x İx", "İX"), vec![2..5]);
+    }
+}
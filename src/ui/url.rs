@@ -0,0 +1,118 @@
+//! Finding `http://` and `https://` URLs in plain text, for `--text-info` to
+//! make them clickable.
+//!
+//! A URL runs from its scheme to the next whitespace or `<`, `>` or `"`.
+//! Punctuation that usually ends the sentence around a URL rather than the
+//! URL itself is left off the end: `.`, `,`, `;`, `:`, `!`, `?` and quotes,
+//! and closing brackets without an opening one inside the URL.
+
+use std::ops::Range;
+
+const SCHEMES: [&str; 2] = ["http://", "https://"];
+
+/// The byte ranges of the URLs in `line`, in order.
+pub(crate) fn find_urls(line: &str) -> Vec<Range<usize>> {
+    let mut urls = Vec::new();
+    let mut from = 0;
+    while let Some(start) = next_scheme(line, from) {
+        let rest = &line[start..];
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"'))
+            .unwrap_or(rest.len());
+        let end = start + trim_end(&rest[..len]);
+        let scheme_len = SCHEMES
+            .iter()
+            .find(|scheme| starts_with_ignore_case(rest, scheme))
+            .map_or(0, |scheme| scheme.len());
+        // A scheme alone isn't a URL
+        if end > start + scheme_len {
+            urls.push(start..end);
+        }
+        from = start + len.max(scheme_len);
+    }
+    urls
+}
+
+/// Where the next scheme at or after `from` starts, at the start of a word.
+fn next_scheme(line: &str, from: usize) -> Option<usize> {
+    line[from..]
+        .char_indices()
+        .map(|(i, _)| from + i)
+        .find(|&i| {
+            SCHEMES
+                .iter()
+                .any(|scheme| starts_with_ignore_case(&line[i..], scheme))
+                && !line[..i]
+                    .chars()
+                    .next_back()
+                    .is_some_and(|c| c.is_alphanumeric())
+        })
+}
+
+fn starts_with_ignore_case(text: &str, prefix: &str) -> bool {
+    text.get(..prefix.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+}
+
+/// How much of `url` to keep once trailing punctuation is left off.
+fn trim_end(url: &str) -> usize {
+    let mut end = url.len();
+    while let Some(c) = url[..end].chars().next_back() {
+        let keep = match c {
+            '.' | ',' | ';' | ':' | '!' | '?' | '\'' => false,
+            ')' => url[..end].matches('(').count() >= url[..end].matches(')').count(),
+            ']' => url[..end].matches('[').count() >= url[..end].matches(']').count(),
+            _ => true,
+        };
+        if keep {
+            break;
+        }
+        end -= c.len_utf8();
+    }
+    end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The URLs `find_urls` finds in `line`.
+    fn urls(line: &str) -> Vec<&str> {
+        find_urls(line)
+            .into_iter()
+            .map(|range| &line[range])
+            .collect()
+    }
+
+    #[test]
+    fn urls_run_to_whitespace() {
+        assert_eq!(
+            urls("See https://example.org/a?b=1&c=2#top and http://x.io/"),
+            ["https://example.org/a?b=1&c=2#top", "http://x.io/"]
+        );
+        assert_eq!(urls("HTTPS://EXAMPLE.ORG"), ["HTTPS://EXAMPLE.ORG"]);
+        assert_eq!(urls("<https://example.org/>"), ["https://example.org/"]);
+        assert!(urls("ftp://example.org mailto:someone@example.org").is_empty());
+    }
+
+    #[test]
+    fn sentence_punctuation_is_left_off() {
+        assert_eq!(urls("Go to https://example.org."), ["https://example.org"]);
+        assert_eq!(urls("(https://example.org/a)"), ["https://example.org/a"]);
+        assert_eq!(
+            urls("https://en.wikipedia.org/wiki/Rust_(language)!"),
+            ["https://en.wikipedia.org/wiki/Rust_(language)"]
+        );
+        assert_eq!(urls("'https://example.org/é'"), ["https://example.org/é"]);
+    }
+
+    #[test]
+    fn schemes_must_start_a_word() {
+        assert!(urls("xhttp://example.org https://").is_empty());
+        assert_eq!(urls("url=https://example.org"), ["https://example.org"]);
+        assert_eq!(
+            urls("https://a.org,https://b.org"),
+            ["https://a.org,https://b.org"]
+        );
+    }
+}
@@ -1,8 +1,10 @@
 //! Button widget.
 
+use std::ops::Range;
+
 use super::{Widget, point_in_rect};
 use crate::{
-    backend::{MouseButton, WindowEvent},
+    backend::{Modifiers, MouseButton, WindowEvent},
     render::{Canvas, Font},
     ui::Colors,
 };
@@ -10,6 +12,10 @@ use crate::{
 /// A clickable button widget.
 pub(crate) struct Button {
     label: String,
+    /// Lowercased key that presses the button, marked with `_` in the label.
+    mnemonic: Option<char>,
+    /// Byte range of the mnemonic in `label`, drawn underlined.
+    underline: Option<Range<usize>>,
     x: i32,
     y: i32,
     width: u32,
@@ -18,6 +24,14 @@ pub(crate) struct Button {
     hovered: bool,
     pressed: bool,
     clicked: bool,
+    enabled: bool,
+    /// Drawn pressed to draw attention, e.g. when the dialog is re-presented.
+    flashing: bool,
+    /// Outlined in the accent color as the button Enter activates.
+    is_default: bool,
+    /// Ringed to show it has the keyboard focus.
+    focused: bool,
+    scale: f32,
     /// Cached rendered label canvas (text is static; avoids re-rasterizing every frame).
     label_canvas: std::cell::RefCell<Option<Canvas>>,
 }
@@ -28,17 +42,28 @@ const BASE_BUTTON_RADIUS: f32 = 5.0;
 const BASE_MIN_BUTTON_WIDTH: u32 = 80;
 
 impl Button {
+    /// Creates a button. An underscore in `label` marks the next character
+    /// as the mnemonic, as in GTK; `__` is a literal underscore.
     pub fn new(label: &str, font: &Font, scale: f32) -> Self {
+        let (label, underline) = parse_mnemonic(label);
+        let mnemonic = underline.as_ref().and_then(|range| {
+            label[range.clone()]
+                .chars()
+                .flat_map(char::to_lowercase)
+                .next()
+        });
         let button_padding = (BASE_BUTTON_PADDING as f32 * scale) as u32;
         let button_height = (BASE_BUTTON_HEIGHT as f32 * scale) as u32;
         let min_button_width = (BASE_MIN_BUTTON_WIDTH as f32 * scale) as u32;
         let button_radius = BASE_BUTTON_RADIUS * scale;
 
-        let (text_w, _) = font.render(label).measure();
+        let (text_w, _) = font.render(&label).measure();
         let width = (text_w as u32 + button_padding * 2).max(min_button_width);
 
         Self {
-            label: label.to_string(),
+            label,
+            mnemonic,
+            underline,
             x: 0,
             y: 0,
             width,
@@ -47,10 +72,31 @@ impl Button {
             hovered: false,
             pressed: false,
             clicked: false,
+            enabled: true,
+            flashing: false,
+            is_default: false,
+            focused: false,
+            scale,
             label_canvas: std::cell::RefCell::new(None),
         }
     }
 
+    /// Clicks the button if `event` presses its mnemonic: Alt with the key,
+    /// or the key alone if `bare`, when no text input would take it.
+    pub fn process_mnemonic(&mut self, event: &WindowEvent, bare: bool) {
+        let key = match event {
+            WindowEvent::KeyPress(key) if key.modifiers.contains(Modifiers::ALT) => {
+                char::from_u32(key.keysym)
+            }
+            WindowEvent::TextInput(c) if bare => Some(*c),
+            _ => None,
+        };
+        let key = key.and_then(|c| c.to_lowercase().next());
+        if self.enabled && key.is_some() && key == self.mnemonic {
+            self.clicked = true;
+        }
+    }
+
     /// Returns true if the button was clicked this frame.
     pub fn was_clicked(&mut self) -> bool {
         let clicked = self.clicked;
@@ -58,6 +104,35 @@ impl Button {
         clicked
     }
 
+    /// Enables or disables the button. A disabled button is drawn faded and
+    /// ignores clicks.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.pressed = false;
+            self.clicked = false;
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Marks the button as the one Enter activates, when that isn't obvious.
+    pub fn set_default(&mut self, is_default: bool) {
+        self.is_default = is_default;
+    }
+
+    /// Shows or hides the focus ring.
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    /// Draws the button pressed until turned off again.
+    pub fn set_flashing(&mut self, flashing: bool) {
+        self.flashing = flashing;
+    }
+
     /// Set the button width.
     pub fn set_width(&mut self, width: u32) {
         self.width = width;
@@ -66,7 +141,9 @@ impl Button {
     /// Draws the button to a canvas.
     pub fn draw_to(&self, canvas: &mut Canvas, colors: &Colors, font: &Font) {
         // Determine button color based on state
-        let bg_color = if self.pressed {
+        let bg_color = if !self.enabled {
+            colors.button
+        } else if self.pressed || self.flashing {
             colors.button_pressed
         } else if self.hovered {
             colors.button_hover
@@ -85,30 +162,86 @@ impl Button {
         );
 
         // Draw button outline
+        let (outline, outline_width) = if self.is_default {
+            (colors.input_border_focused, 2.0)
+        } else {
+            (colors.button_outline, 1.0)
+        };
         canvas.stroke_rounded_rect(
             self.x as f32,
             self.y as f32,
             self.width as f32,
             self.height as f32,
             self.radius,
-            colors.button_outline,
-            1.0,
+            outline,
+            outline_width,
         );
 
+        // Focus ring, just outside the outline
+        if self.focused {
+            let gap = 2.0 * self.scale;
+            canvas.stroke_rounded_rect(
+                self.x as f32 - gap,
+                self.y as f32 - gap,
+                self.width as f32 + gap * 2.0,
+                self.height as f32 + gap * 2.0,
+                self.radius + gap,
+                colors.input_border_focused,
+                2.0,
+            );
+        }
+
         // Draw cached button label (rendered once on first use).
         let mut cache = self.label_canvas.borrow_mut();
         if cache.is_none() {
-            *cache = Some(
-                font.render(&self.label)
-                    .with_color(colors.button_text)
-                    .finish(),
-            );
+            let text = font.render(&self.label).with_color(colors.button_text);
+            *cache = Some(match self.underline.clone() {
+                Some(range) => text.with_underline(range).finish(),
+                None => text.finish(),
+            });
         }
         let text_canvas = cache.as_ref().unwrap();
         let text_x = self.x + (self.width as i32 - text_canvas.width() as i32) / 2;
         let text_y = self.y + (self.height as i32 - text_canvas.height() as i32) / 2;
         canvas.draw_canvas(text_canvas, text_x, text_y);
+
+        // Fade a disabled button into the window background
+        if !self.enabled {
+            canvas.fill_rounded_rect(
+                self.x as f32,
+                self.y as f32,
+                self.width as f32,
+                self.height as f32,
+                self.radius,
+                colors.window_bg.with_alpha(140),
+            );
+        }
+    }
+}
+
+/// Strips GTK-style mnemonic markers from `label`, returning the text to show
+/// and the byte range of the character after the first single underscore.
+pub(crate) fn parse_mnemonic(label: &str) -> (String, Option<Range<usize>>) {
+    let mut text = String::with_capacity(label.len());
+    let mut underline = None;
+    let mut chars = label.chars();
+    while let Some(c) = chars.next() {
+        if c != '_' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('_') => text.push('_'),
+            Some(next) => {
+                if underline.is_none() {
+                    underline = Some(text.len()..text.len() + next.len_utf8());
+                }
+                text.push(next);
+            }
+            None => text.push('_'),
+        }
     }
+    (text, underline)
 }
 
 impl Widget for Button {
@@ -154,7 +287,7 @@ impl Widget for Button {
                 self.pressed = false;
                 changed
             }
-            WindowEvent::ButtonPress(MouseButton::Left, _) if self.hovered => {
+            WindowEvent::ButtonPress(MouseButton::Left, _) if self.hovered && self.enabled => {
                 let was_pressed = self.pressed;
                 self.pressed = true;
                 !was_pressed
@@ -0,0 +1,192 @@
+//! Checkbox widget.
+
+use super::{Widget, point_in_rect};
+use crate::{
+    backend::{MouseButton, WindowEvent},
+    render::{Canvas, Font, Rgba, rgb},
+    ui::Colors,
+};
+
+const BASE_BOX_SIZE: u32 = 16;
+const BASE_LABEL_GAP: u32 = 8;
+
+/// A box with a label that toggles when clicked, or with Space while focused.
+pub(crate) struct Checkbox {
+    label: String,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    box_size: u32,
+    scale: f32,
+    checked: bool,
+    hovered: bool,
+    focused: bool,
+    toggled: bool,
+    /// Cached rendered label canvas (text is static).
+    label_canvas: std::cell::RefCell<Option<Canvas>>,
+}
+
+impl Checkbox {
+    pub fn new(label: &str, font: &Font, scale: f32) -> Self {
+        let box_size = (BASE_BOX_SIZE as f32 * scale) as u32;
+        let gap = (BASE_LABEL_GAP as f32 * scale) as u32;
+        let (text_w, text_h) = font.render(label).measure();
+
+        Self {
+            label: label.to_string(),
+            x: 0,
+            y: 0,
+            width: box_size + gap + text_w as u32,
+            height: box_size.max(text_h as u32),
+            box_size,
+            scale,
+            checked: false,
+            hovered: false,
+            focused: false,
+            toggled: false,
+            label_canvas: std::cell::RefCell::new(None),
+        }
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    pub fn set_checked(&mut self, checked: bool) {
+        self.checked = checked;
+    }
+
+    pub fn is_hovered(&self) -> bool {
+        self.hovered
+    }
+
+    /// Lets Space toggle the checkbox.
+    pub fn set_focus(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    /// Returns true if the checkbox was toggled since the last call.
+    pub fn was_toggled(&mut self) -> bool {
+        let toggled = self.toggled;
+        self.toggled = false;
+        toggled
+    }
+
+    fn toggle(&mut self) -> bool {
+        self.checked = !self.checked;
+        self.toggled = true;
+        true
+    }
+
+    /// Draws the checkbox to a canvas.
+    pub fn draw_to(&self, canvas: &mut Canvas, colors: &Colors, font: &Font) {
+        let box_y = self.y + (self.height - self.box_size) as i32 / 2;
+        let bg = if self.hovered {
+            darken(colors.input_bg, 0.06)
+        } else {
+            colors.input_bg
+        };
+        let border = if self.focused {
+            colors.input_border_focused
+        } else {
+            colors.input_border
+        };
+        canvas.fill_rounded_rect(
+            self.x as f32,
+            box_y as f32,
+            self.box_size as f32,
+            self.box_size as f32,
+            3.0 * self.scale,
+            bg,
+        );
+        canvas.stroke_rounded_rect(
+            self.x as f32,
+            box_y as f32,
+            self.box_size as f32,
+            self.box_size as f32,
+            3.0 * self.scale,
+            border,
+            1.0,
+        );
+
+        // Check mark
+        if self.checked {
+            let inset = (3.0 * self.scale) as i32;
+            let size = (self.box_size as i32 - inset * 2) as f32;
+            canvas.fill_rounded_rect(
+                (self.x + inset) as f32,
+                (box_y + inset) as f32,
+                size,
+                size,
+                2.0 * self.scale,
+                colors.input_border_focused,
+            );
+        }
+
+        let mut cache = self.label_canvas.borrow_mut();
+        if cache.is_none() {
+            *cache = Some(font.render(&self.label).with_color(colors.text).finish());
+        }
+        let text_canvas = cache.as_ref().unwrap();
+        let text_x = self.x + (self.box_size + (BASE_LABEL_GAP as f32 * self.scale) as u32) as i32;
+        let text_y = self.y + (self.height as i32 - text_canvas.height() as i32) / 2;
+        canvas.draw_canvas(text_canvas, text_x, text_y);
+    }
+}
+
+impl Widget for Checkbox {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn x(&self) -> i32 {
+        self.x
+    }
+
+    fn y(&self) -> i32 {
+        self.y
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn process_event(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::CursorMove(pos) | WindowEvent::CursorEnter(pos) => {
+                let was_hovered = self.hovered;
+                self.hovered = point_in_rect(
+                    pos.x as i32,
+                    pos.y as i32,
+                    self.x,
+                    self.y,
+                    self.width,
+                    self.height,
+                );
+                self.hovered != was_hovered
+            }
+            WindowEvent::CursorLeave => std::mem::take(&mut self.hovered),
+            WindowEvent::ButtonPress(MouseButton::Left, _) if self.hovered => self.toggle(),
+            WindowEvent::TextInput(' ') if self.focused => self.toggle(),
+            _ => false,
+        }
+    }
+
+    fn draw(&self, _canvas: &mut Canvas, _colors: &Colors) {
+        // Use draw_to instead for font access
+    }
+}
+
+fn darken(color: Rgba, amount: f32) -> Rgba {
+    rgb(
+        (color.r as f32 * (1.0 - amount)) as u8,
+        (color.g as f32 * (1.0 - amount)) as u8,
+        (color.b as f32 * (1.0 - amount)) as u8,
+    )
+}
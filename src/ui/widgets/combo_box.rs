@@ -0,0 +1,344 @@
+//! Drop-down list widget.
+
+use std::cell::RefCell;
+
+use super::{Widget, point_in_rect};
+use crate::{
+    backend::{MouseButton, WindowEvent},
+    render::{Canvas, Font},
+    ui::{Colors, KEY_DOWN, KEY_ESCAPE, KEY_KP_ENTER, KEY_RETURN, KEY_UP},
+};
+
+const BASE_HEIGHT: u32 = 32;
+const BASE_PADDING: u32 = 10;
+const BASE_ARROW_WIDTH: u32 = 22;
+const BASE_ITEM_HEIGHT: u32 = 26;
+const BASE_RADIUS: f32 = 5.0;
+/// Space between the box and its open list.
+const BASE_POPUP_GAP: u32 = 2;
+
+/// A box showing one of several items, picked from a list that drops down
+/// when the box is clicked.
+///
+/// While the list is open it takes every pointer and key event, so dialogs
+/// check [`is_open`](Self::is_open) before handling events themselves. The
+/// list is drawn separately with [`draw_popup`](Self::draw_popup), after
+/// everything it may cover.
+pub(crate) struct ComboBox {
+    items: Vec<String>,
+    selected: usize,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    item_height: u32,
+    padding: u32,
+    gap: u32,
+    radius: f32,
+    scale: f32,
+    cursor: (i32, i32),
+    hovered: bool,
+    focused: bool,
+    open: bool,
+    /// The item under the pointer or picked with the arrow keys, while open.
+    highlighted: usize,
+    popup_above: bool,
+    changed: bool,
+    /// Rendered item labels, in the text color.
+    labels: RefCell<Vec<Canvas>>,
+}
+
+impl ComboBox {
+    /// A box as wide as its longest item, showing the first.
+    pub fn new(items: Vec<String>, font: &Font, scale: f32) -> Self {
+        let padding = (BASE_PADDING as f32 * scale) as u32;
+        let arrow_width = (BASE_ARROW_WIDTH as f32 * scale) as u32;
+        let text_width = items
+            .iter()
+            .map(|item| font.render(item).measure().0 as u32)
+            .max()
+            .unwrap_or(0);
+        Self {
+            items,
+            selected: 0,
+            x: 0,
+            y: 0,
+            width: text_width + padding * 2 + arrow_width,
+            height: (BASE_HEIGHT as f32 * scale) as u32,
+            item_height: (BASE_ITEM_HEIGHT as f32 * scale) as u32,
+            padding,
+            gap: (BASE_POPUP_GAP as f32 * scale) as u32,
+            radius: BASE_RADIUS * scale,
+            scale,
+            cursor: (-1, -1),
+            hovered: false,
+            focused: false,
+            open: false,
+            highlighted: 0,
+            popup_above: false,
+            changed: false,
+            labels: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn set_width(&mut self, width: u32) {
+        self.width = width;
+    }
+
+    /// A focused box takes Up and Down to change the item and Space to open
+    /// the list.
+    pub fn set_focus(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    /// The selected item, or "" if there are none.
+    pub fn selected_text(&self) -> &str {
+        self.items.get(self.selected).map_or("", String::as_str)
+    }
+
+    /// Height of the open list.
+    pub fn popup_height(&self) -> u32 {
+        self.popup_rect().3
+    }
+
+    /// Opens the list above the box instead of below, for boxes near the
+    /// bottom of the window.
+    pub fn set_popup_above(&mut self, above: bool) {
+        self.popup_above = above;
+    }
+
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn set_selected(&mut self, index: usize) {
+        if index < self.items.len() {
+            self.selected = index;
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Returns true if the user picked a different item since the last call.
+    pub fn was_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    /// The open list's bounds: x, y, width, height.
+    fn popup_rect(&self) -> (i32, i32, u32, u32) {
+        let h = self.items.len() as u32 * self.item_height + 2;
+        let y = if self.popup_above {
+            self.y - (h + self.gap) as i32
+        } else {
+            self.y + (self.height + self.gap) as i32
+        };
+        (self.x, y, self.width, h)
+    }
+
+    fn item_at(&self, px: i32, py: i32) -> Option<usize> {
+        let (x, y, w, h) = self.popup_rect();
+        if !point_in_rect(px, py, x, y + 1, w, h - 2) {
+            return None;
+        }
+        Some(((py - y - 1) as u32 / self.item_height) as usize).filter(|&i| i < self.items.len())
+    }
+
+    fn choose(&mut self, index: usize) {
+        if index != self.selected {
+            self.selected = index;
+            self.changed = true;
+        }
+        self.open = false;
+    }
+
+    /// Draws the box with the selected item.
+    pub fn draw_to(&self, canvas: &mut Canvas, colors: &Colors, font: &Font) {
+        let (x, y, w, h) = (
+            self.x as f32,
+            self.y as f32,
+            self.width as f32,
+            self.height as f32,
+        );
+        let bg = if self.hovered || self.open {
+            colors.input_bg_focused
+        } else {
+            colors.input_bg
+        };
+        canvas.fill_rounded_rect(x, y, w, h, self.radius, bg);
+        let border = if self.open || self.focused {
+            colors.input_border_focused
+        } else {
+            colors.input_border
+        };
+        canvas.stroke_rounded_rect(x, y, w, h, self.radius, border, 1.0);
+
+        if let Some(label) = self.label(self.selected, colors, font) {
+            let text_y = self.y + (self.height as i32 - label.height() as i32) / 2;
+            canvas.draw_canvas(&label, self.x + self.padding as i32, text_y);
+        }
+
+        // Down-pointing arrow at the right
+        let size = 4.0 * self.scale;
+        let cx = x + w - self.padding as f32 - size;
+        let cy = y + h / 2.0;
+        canvas.fill_polygon(
+            &[
+                (cx - size, cy - size / 2.0),
+                (cx + size, cy - size / 2.0),
+                (cx, cy + size / 2.0),
+            ],
+            colors.text,
+        );
+    }
+
+    /// Draws the open list, if it is open.
+    pub fn draw_popup(&self, canvas: &mut Canvas, colors: &Colors, font: &Font) {
+        if !self.open {
+            return;
+        }
+        let (x, y, w, h) = self.popup_rect();
+        canvas.fill_rounded_rect(x as f32, y as f32, w as f32, h as f32, 4.0, colors.input_bg);
+        canvas.stroke_rounded_rect(
+            x as f32,
+            y as f32,
+            w as f32,
+            h as f32,
+            4.0,
+            colors.input_border_focused,
+            1.0,
+        );
+        for i in 0..self.items.len() {
+            let item_y = y + 1 + (i as u32 * self.item_height) as i32;
+            if i == self.highlighted {
+                canvas.fill_rect(
+                    (x + 1) as f32,
+                    item_y as f32,
+                    (w - 2) as f32,
+                    self.item_height as f32,
+                    colors.selection,
+                );
+            }
+            if let Some(label) = self.label(i, colors, font) {
+                let text_y = item_y + (self.item_height as i32 - label.height() as i32) / 2;
+                canvas.draw_canvas(&label, x + self.padding as i32, text_y);
+            }
+        }
+    }
+
+    /// The rendered label of item `index`, cut to fit the box.
+    fn label(&self, index: usize, colors: &Colors, font: &Font) -> Option<Canvas> {
+        let mut labels = self.labels.borrow_mut();
+        if labels.is_empty() {
+            *labels = self
+                .items
+                .iter()
+                .map(|item| font.render(item).with_color(colors.text).finish())
+                .collect();
+        }
+        let full = labels.get(index)?;
+        let max_width = self
+            .width
+            .saturating_sub(self.padding * 2 + self.height / 2);
+        let mut label = Canvas::new(full.width().min(max_width).max(1), full.height());
+        label.draw_canvas(full, 0, 0);
+        Some(label)
+    }
+}
+
+impl Widget for ComboBox {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn x(&self) -> i32 {
+        self.x
+    }
+
+    fn y(&self) -> i32 {
+        self.y
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn process_event(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::CursorMove(pos) | WindowEvent::CursorEnter(pos) => {
+                self.cursor = (pos.x as i32, pos.y as i32);
+                let was_hovered = self.hovered;
+                self.hovered = point_in_rect(
+                    self.cursor.0,
+                    self.cursor.1,
+                    self.x,
+                    self.y,
+                    self.width,
+                    self.height,
+                );
+                let highlighted = self.highlighted;
+                if self.open
+                    && let Some(i) = self.item_at(self.cursor.0, self.cursor.1)
+                {
+                    self.highlighted = i;
+                }
+                self.hovered != was_hovered || self.highlighted != highlighted
+            }
+            WindowEvent::CursorLeave => std::mem::take(&mut self.hovered),
+            WindowEvent::ButtonPress(MouseButton::Left, _) if self.open => {
+                match self.item_at(self.cursor.0, self.cursor.1) {
+                    Some(i) => self.choose(i),
+                    // A click elsewhere closes the list
+                    None => self.open = false,
+                }
+                true
+            }
+            WindowEvent::ButtonPress(MouseButton::Left, _)
+                if self.hovered && !self.items.is_empty() =>
+            {
+                self.open = true;
+                self.highlighted = self.selected;
+                true
+            }
+            WindowEvent::KeyPress(key) if self.open => {
+                match key.keysym {
+                    KEY_UP => self.highlighted = self.highlighted.saturating_sub(1),
+                    KEY_DOWN => self.highlighted = (self.highlighted + 1).min(self.items.len() - 1),
+                    KEY_RETURN | KEY_KP_ENTER => self.choose(self.highlighted),
+                    KEY_ESCAPE => self.open = false,
+                    _ => return false,
+                }
+                true
+            }
+            WindowEvent::KeyPress(key) if self.focused && !self.items.is_empty() => {
+                let index = match key.keysym {
+                    KEY_UP => self.selected.saturating_sub(1),
+                    KEY_DOWN => (self.selected + 1).min(self.items.len() - 1),
+                    _ => return false,
+                };
+                self.choose(index);
+                true
+            }
+            WindowEvent::TextInput(' ') if self.focused && !self.items.is_empty() => {
+                self.open = true;
+                self.highlighted = self.selected;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn draw(&self, _canvas: &mut Canvas, _colors: &Colors) {
+        // Use draw_to instead for font access
+    }
+}
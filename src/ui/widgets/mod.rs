@@ -1,21 +1,47 @@
 //! Reusable UI widgets.
 
 pub(crate) mod button;
+pub(crate) mod checkbox;
+pub(crate) mod combo_box;
 pub(crate) mod progress_bar;
+pub(crate) mod scrollbar;
+pub(crate) mod selectable_text;
+pub(crate) mod text_editor;
 pub(crate) mod text_input;
+pub(crate) mod undo;
+
+use std::{any::Any, time::Duration};
 
 use crate::{backend::WindowEvent, render::Canvas, ui::Colors};
 
+/// Presses closer together than this count as a double (or triple) click.
+pub(crate) const MULTI_CLICK_TIME: Duration = Duration::from_millis(400);
+
 /// Trait for UI widgets.
+///
+/// Besides the built-in widgets, this is implemented by application-provided
+/// widgets passed to [`MessageBuilder::custom_content`](crate::MessageBuilder::custom_content).
+/// Widgets are `'static` so they can be recovered with a downcast once the
+/// dialog has closed.
 #[allow(dead_code)]
-pub(crate) trait Widget {
+pub trait Widget: Any {
     fn width(&self) -> u32;
     fn height(&self) -> u32;
     fn x(&self) -> i32;
     fn y(&self) -> i32;
     fn set_position(&mut self, x: i32, y: i32);
+    /// Handles an event. Returns true if the widget needs to be redrawn.
     fn process_event(&mut self, event: &WindowEvent) -> bool;
     fn draw(&self, canvas: &mut Canvas, colors: &Colors);
+
+    /// Preferred size in logical pixels (at scale 1.0), used for dialog layout.
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width(), self.height())
+    }
+
+    /// Called once the window's scale factor is known, before positioning.
+    /// Widgets created at logical size should resize to physical pixels here.
+    fn set_scale(&mut self, _scale: f32) {}
 }
 
 /// Check if a point is within a rectangle.
@@ -1,9 +1,14 @@
 //! Progress bar widget.
 
-use crate::{render::Canvas, ui::Colors};
+use crate::{
+    render::{Canvas, Rgba},
+    ui::Colors,
+};
 
 const BASE_BAR_HEIGHT: u32 = 20;
 const BASE_BAR_RADIUS: f32 = 4.0;
+/// Highlight swept across the fill while the bar is stalled.
+const SHIMMER: Rgba = Rgba::new(255, 255, 255, 60);
 
 /// A progress bar widget.
 pub struct ProgressBar {
@@ -15,6 +20,8 @@ pub struct ProgressBar {
     progress: f32, // 0.0 to 1.0
     pulsating: bool,
     pulse_position: f32, // For pulsating animation
+    stalled: bool,
+    shimmer_position: f32, // For the stalled shimmer
 }
 
 impl ProgressBar {
@@ -28,6 +35,8 @@ impl ProgressBar {
             progress: 0.0,
             pulsating: false,
             pulse_position: 0.0,
+            stalled: false,
+            shimmer_position: 0.0,
         }
     }
 
@@ -51,11 +60,30 @@ impl ProgressBar {
     }
 
     /// Returns true if in pulsating mode.
+    #[allow(dead_code)]
     pub fn is_pulsating(&self) -> bool {
         self.pulsating
     }
 
-    /// Advances the pulse animation. Call this periodically.
+    /// Marks the bar as waiting for updates, which sweeps a shimmer across
+    /// the fill so it doesn't look frozen.
+    pub fn set_stalled(&mut self, stalled: bool) {
+        self.stalled = stalled;
+        if stalled {
+            self.shimmer_position = 0.0;
+        }
+    }
+
+    pub fn is_stalled(&self) -> bool {
+        self.stalled
+    }
+
+    /// Returns true if [`tick`](Self::tick) needs to be called to animate.
+    pub fn is_animating(&self) -> bool {
+        self.pulsating || self.stalled
+    }
+
+    /// Advances the pulse and shimmer animations. Call this periodically.
     pub fn tick(&mut self) {
         if self.pulsating {
             self.pulse_position += 0.02;
@@ -63,6 +91,12 @@ impl ProgressBar {
                 self.pulse_position = 0.0;
             }
         }
+        if self.stalled {
+            self.shimmer_position += 0.01;
+            if self.shimmer_position > 1.0 {
+                self.shimmer_position = 0.0;
+            }
+        }
     }
 
     /// Returns the current progress (0.0 to 1.0).
@@ -123,6 +157,20 @@ impl ProgressBar {
                 self.radius,
                 colors.progress_fill,
             );
+
+            if self.stalled {
+                // Sweep a band across the filled part only
+                let band = (fill_width * 0.25).max(self.radius * 2.0).min(fill_width);
+                let band_x = self.x as f32 + (fill_width - band) * self.shimmer_position;
+                canvas.fill_rounded_rect(
+                    band_x,
+                    self.y as f32,
+                    band,
+                    self.height as f32,
+                    self.radius,
+                    SHIMMER,
+                );
+            }
         }
 
         // Draw border
@@ -0,0 +1,381 @@
+//! Vertical scrollbar widget.
+
+use super::{Widget, point_in_rect};
+use crate::{
+    backend::{CursorPos, MouseButton, WindowEvent},
+    render::{Canvas, Rgba, rgb},
+    ui::Colors,
+};
+
+/// Width of the area the bar takes clicks in, which it fills while hovered.
+const BASE_WIDTH: f32 = 12.0;
+/// How much of that area the bar fills otherwise.
+const BASE_THIN_WIDTH: f32 = 8.0;
+/// Gap between the bar and the right edge of its zone.
+const BASE_MARGIN: f32 = 2.0;
+const BASE_MIN_THUMB: f32 = 20.0;
+
+/// A track with a thumb sized to the share of the content in view.
+///
+/// The scroll position is kept in whatever unit the host uses (lines, rows
+/// or pixels) as `offset` into `content`, of which `viewport` is in view.
+/// Dragging the thumb scrolls to match, and a click above or below it jumps
+/// a page. The host scrolls it with [`set_offset`](Self::set_offset) for the
+/// wheel and keys, and checks [`changed`](Self::changed) after passing it
+/// events. It hides itself when the content fits.
+pub(crate) struct Scrollbar {
+    x: i32,
+    y: i32,
+    height: u32,
+    scale: f32,
+    content: usize,
+    viewport: usize,
+    offset: usize,
+    hovered: bool,
+    /// Where in the thumb the drag grabbed it.
+    drag: Option<f32>,
+    cursor: Option<CursorPos>,
+    changed: bool,
+}
+
+impl Scrollbar {
+    /// A scrollbar `height` physical pixels tall.
+    pub fn new(height: u32, scale: f32) -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            height,
+            scale,
+            content: 0,
+            viewport: 0,
+            offset: 0,
+            hovered: false,
+            drag: None,
+            cursor: None,
+            changed: false,
+        }
+    }
+
+    /// Sets how much content there is and how much of it is in view,
+    /// keeping the offset in range.
+    pub fn set_metrics(&mut self, content: usize, viewport: usize) {
+        self.content = content;
+        self.viewport = viewport;
+        self.offset = self.offset.min(self.max_offset());
+        if !self.is_visible() {
+            self.hovered = false;
+            self.drag = None;
+        }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The furthest the content can scroll.
+    pub fn max_offset(&self) -> usize {
+        self.content.saturating_sub(self.viewport)
+    }
+
+    /// Scrolls to `offset`, kept in range. Returns true if it moved.
+    pub fn set_offset(&mut self, offset: usize) -> bool {
+        let offset = offset.min(self.max_offset());
+        std::mem::replace(&mut self.offset, offset) != offset
+    }
+
+    /// Scrolls by `delta`, kept in range. Returns true if it moved.
+    pub fn scroll_by(&mut self, delta: isize) -> bool {
+        self.set_offset(self.offset.saturating_add_signed(delta))
+    }
+
+    /// Whether there's anything to scroll, and so a bar to show.
+    pub fn is_visible(&self) -> bool {
+        self.content > self.viewport
+    }
+
+    pub fn is_hovered(&self) -> bool {
+        self.hovered
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// Whether (`x`, `y`) is over the bar, so a click there belongs to it.
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        self.is_visible() && point_in_rect(x, y, self.x, self.y, self.width(), self.height)
+    }
+
+    /// Returns true if the user scrolled with the bar since the last call.
+    pub fn changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    /// The thumb's top, relative to the bar, and its length.
+    fn thumb(&self) -> (f32, f32) {
+        let track = self.height as f32;
+        let length = (self.viewport as f32 / self.content.max(1) as f32 * track)
+            .max(BASE_MIN_THUMB * self.scale)
+            .min(track);
+        let max_offset = self.max_offset();
+        let top = if max_offset > 0 {
+            self.offset as f32 / max_offset as f32 * (track - length)
+        } else {
+            0.0
+        };
+        (top, length)
+    }
+
+    /// Scrolls so the thumb's top sits at `top`, relative to the bar.
+    fn drag_to(&mut self, top: f32) {
+        let (_, length) = self.thumb();
+        let room = self.height as f32 - length;
+        let ratio = if room > 0.0 {
+            (top / room).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let offset = (ratio * self.max_offset() as f32).round() as usize;
+        self.changed |= self.set_offset(offset);
+    }
+
+    fn press(&mut self, y: f32) {
+        let (top, length) = self.thumb();
+        if (top..top + length).contains(&y) {
+            self.drag = Some(y - top);
+        } else {
+            let page = self.viewport.max(1) as isize;
+            self.changed |= self.scroll_by(if y < top { -page } else { page });
+        }
+    }
+}
+
+impl Widget for Scrollbar {
+    fn width(&self) -> u32 {
+        (BASE_WIDTH * self.scale).round() as u32
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn x(&self) -> i32 {
+        self.x
+    }
+
+    fn y(&self) -> i32 {
+        self.y
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+    }
+
+    fn process_event(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::CursorEnter(pos) | WindowEvent::CursorMove(pos) => {
+                self.cursor = Some(*pos);
+                if let Some(grab) = self.drag {
+                    let offset = self.offset;
+                    self.drag_to(pos.y as f32 - self.y as f32 - grab);
+                    return self.offset != offset;
+                }
+                let hovered = self.contains(pos.x as i32, pos.y as i32);
+                std::mem::replace(&mut self.hovered, hovered) != hovered
+            }
+            WindowEvent::CursorLeave => {
+                std::mem::replace(&mut self.hovered, false) && self.drag.is_none()
+            }
+            WindowEvent::ButtonPress(MouseButton::Left, _) => {
+                let Some(pos) = self.cursor else {
+                    return false;
+                };
+                if !self.contains(pos.x as i32, pos.y as i32) {
+                    return false;
+                }
+                self.press(pos.y as f32 - self.y as f32);
+                true
+            }
+            WindowEvent::ButtonRelease(MouseButton::Left, _) => self.drag.take().is_some(),
+            _ => false,
+        }
+    }
+
+    fn draw(&self, canvas: &mut Canvas, colors: &Colors) {
+        if !self.is_visible() {
+            return;
+        }
+        let active = self.hovered || self.drag.is_some();
+        let zone = if active { BASE_WIDTH } else { BASE_THIN_WIDTH } * self.scale;
+        let margin = BASE_MARGIN * self.scale;
+        let x = self.x as f32 + self.width() as f32 - zone;
+        let width = zone - margin;
+        let radius = 3.0 * self.scale;
+        let (top, length) = self.thumb();
+        canvas.fill_rounded_rect(
+            x,
+            self.y as f32,
+            width,
+            self.height as f32,
+            radius,
+            darken(colors.input_bg, 0.05),
+        );
+        canvas.fill_rounded_rect(
+            x,
+            self.y as f32 + top,
+            width,
+            length,
+            radius,
+            if active {
+                colors.input_border_focused
+            } else {
+                colors.input_border
+            },
+        );
+    }
+}
+
+fn darken(color: Rgba, amount: f32) -> Rgba {
+    rgb(
+        (color.r as f32 * (1.0 - amount)) as u8,
+        (color.g as f32 * (1.0 - amount)) as u8,
+        (color.b as f32 * (1.0 - amount)) as u8,
+    )
+}
+
+/// The scrollbar widget's thumb, page jumps and drawing.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{backend::Modifiers, ui::THEME_LIGHT};
+
+    fn move_to(bar: &mut Scrollbar, x: i16, y: i16) -> bool {
+        bar.process_event(&WindowEvent::CursorMove(CursorPos {
+            x,
+            y,
+        }))
+    }
+
+    fn press(bar: &mut Scrollbar) -> bool {
+        bar.process_event(&WindowEvent::ButtonPress(
+            MouseButton::Left,
+            Modifiers::empty(),
+        ))
+    }
+
+    fn release(bar: &mut Scrollbar) -> bool {
+        bar.process_event(&WindowEvent::ButtonRelease(
+            MouseButton::Left,
+            Modifiers::empty(),
+        ))
+    }
+
+    /// A 200px bar over 100 lines with 10 in view: a 20px thumb.
+    fn bar() -> Scrollbar {
+        let mut bar = Scrollbar::new(200, 1.0);
+        bar.set_position(100, 0);
+        bar.set_metrics(100, 10);
+        bar
+    }
+
+    #[test]
+    fn dragging_the_thumb_scrolls() {
+        let mut bar = bar();
+        assert!(move_to(&mut bar, 105, 5));
+        assert!(bar.is_hovered());
+        assert!(press(&mut bar));
+        assert!(bar.is_dragging());
+        assert!(!bar.changed());
+
+        // Halfway down the 180px the thumb can travel
+        assert!(move_to(&mut bar, 40, 95));
+        assert_eq!(bar.offset(), 45);
+        assert!(bar.changed());
+        assert!(!bar.changed());
+
+        // Past the end stops at the last page
+        move_to(&mut bar, 105, 1000);
+        assert_eq!(bar.offset(), 90);
+        assert!(release(&mut bar));
+        move_to(&mut bar, 105, 0);
+        assert_eq!(bar.offset(), 90);
+    }
+
+    #[test]
+    fn clicks_beside_the_thumb_jump_a_page() {
+        let mut bar = bar();
+        move_to(&mut bar, 105, 150);
+        press(&mut bar);
+        assert_eq!(bar.offset(), 10);
+        assert!(bar.changed());
+        assert!(!bar.is_dragging());
+
+        bar.set_offset(50);
+        move_to(&mut bar, 105, 10);
+        press(&mut bar);
+        assert_eq!(bar.offset(), 40);
+
+        // Clicks elsewhere are left alone
+        move_to(&mut bar, 50, 10);
+        assert!(!press(&mut bar));
+        assert_eq!(bar.offset(), 40);
+    }
+
+    #[test]
+    fn the_host_scrolls_within_range() {
+        let mut bar = bar();
+        assert!(!bar.scroll_by(-3));
+        assert!(bar.scroll_by(95));
+        assert_eq!(bar.offset(), 90);
+        // Scrolling by the host isn't reported back to it
+        assert!(!bar.changed());
+
+        // Less content pulls the offset back
+        bar.set_metrics(50, 10);
+        assert_eq!(bar.offset(), 40);
+    }
+
+    #[test]
+    fn hidden_when_the_content_fits() {
+        let mut bar = bar();
+        bar.set_metrics(10, 10);
+        assert!(!bar.is_visible());
+        assert!(!bar.contains(105, 5));
+        move_to(&mut bar, 105, 5);
+        assert!(!press(&mut bar));
+
+        let mut canvas = Canvas::new(120, 200);
+        canvas.fill(THEME_LIGHT.window_bg);
+        let before = canvas.pixel(108, 100);
+        bar.draw(&mut canvas, &THEME_LIGHT);
+        assert_eq!(canvas.pixel(108, 100), before);
+    }
+
+    #[test]
+    fn fractional_scales_keep_the_thumb_on_the_track() {
+        let mut bar = Scrollbar::new(150, 1.5);
+        bar.set_position(0, 0);
+        bar.set_metrics(10_000, 10);
+        assert_eq!(bar.width(), 18);
+
+        // The thumb keeps its 30px minimum and reaches the bottom exactly
+        move_to(&mut bar, 10, 10);
+        press(&mut bar);
+        move_to(&mut bar, 10, 130);
+        assert_eq!(bar.offset(), bar.max_offset());
+        release(&mut bar);
+
+        let colors = &THEME_LIGHT;
+        let mut canvas = Canvas::new(18, 150);
+        canvas.fill(colors.window_bg);
+        bar.draw(&mut canvas, colors);
+        // The thumb fills the bottom 30px, the track above it
+        assert_eq!(canvas.pixel(12, 140), Some(colors.input_border_focused));
+        assert_ne!(canvas.pixel(12, 100), Some(colors.input_border_focused));
+    }
+}
@@ -0,0 +1,289 @@
+//! Static text that can be selected with the mouse.
+
+use std::{ops::Range, time::Instant};
+
+use super::{MULTI_CLICK_TIME, Widget, point_in_rect};
+use crate::{
+    backend::{CursorPos, Modifiers, MouseButton, WindowEvent},
+    render::{Canvas, EllipsizeMode, Font, Rgba, TextLayout, TextRenderer},
+    ui::{Colors, KEY_A},
+};
+
+/// Presses within [`MULTI_CLICK_TIME`] count as a double or triple click as
+/// long as the pointer stays within this many pixels.
+const MULTI_CLICK_DISTANCE: i32 = 4;
+
+/// What a drag extends the selection by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Char,
+    Word,
+    Line,
+}
+
+/// A block of (optionally wrapped) text whose contents can be selected.
+///
+/// Drag to select, double-click for a word, triple-click for a line and
+/// Ctrl+A for everything. The dialog owning it decides what to do with
+/// [`selected_text`](Self::selected_text), e.g. copy it on Ctrl+C.
+pub struct SelectableText {
+    x: i32,
+    y: i32,
+    text: String,
+    canvas: Canvas,
+    layout: TextLayout,
+    selection: Range<usize>,
+    /// Range selected by the press that started the current drag.
+    anchor: Range<usize>,
+    unit: Unit,
+    dragging: bool,
+    hovered: bool,
+    cursor: CursorPos,
+    last_click: Option<(Instant, CursorPos)>,
+    clicks: u32,
+}
+
+impl SelectableText {
+    /// Lays out `text`, wrapping at `max_width` physical pixels if given.
+    pub fn new(text: &str, font: &Font, color: Rgba, max_width: Option<f32>) -> Self {
+        let mut renderer = font.render(text).with_color(color);
+        if let Some(max_width) = max_width {
+            renderer = renderer.with_max_width(max_width);
+        }
+        Self::from_renderer(renderer)
+    }
+
+    /// Lays out `text` without wrapping, cutting lines longer than
+    /// `max_width` physical pixels short with "…". Selecting the ellipsis
+    /// selects the text it hides.
+    pub fn ellipsized(text: &str, font: &Font, color: Rgba, max_width: f32) -> Self {
+        let renderer = font
+            .render(text)
+            .with_color(color)
+            .with_max_width(max_width)
+            .with_ellipsize(EllipsizeMode::End);
+        Self::from_renderer(renderer)
+    }
+
+    /// Lays out the text `renderer` draws, as it draws it, e.g. with styled
+    /// [runs](TextRenderer::with_runs).
+    pub fn from_renderer(renderer: TextRenderer) -> Self {
+        let text = renderer.text();
+        let layout = renderer.text_layout();
+        let canvas = renderer.finish();
+
+        Self {
+            x: 0,
+            y: 0,
+            text: text.to_string(),
+            canvas,
+            layout,
+            selection: 0..0,
+            anchor: 0..0,
+            unit: Unit::Char,
+            dragging: false,
+            hovered: false,
+            cursor: CursorPos::default(),
+            last_click: None,
+            clicks: 0,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Byte range of the selection; empty when nothing is selected.
+    pub fn selection(&self) -> Range<usize> {
+        self.selection.clone()
+    }
+
+    /// The selected text, or `None` when nothing is selected.
+    pub fn selected_text(&self) -> Option<&str> {
+        (!self.selection.is_empty()).then(|| &self.text[self.selection.clone()])
+    }
+
+    pub fn select_all(&mut self) {
+        self.selection = 0..self.text.len();
+    }
+
+    /// Whether the pointer is over the text, where an I-beam cursor belongs.
+    pub fn is_hovered(&self) -> bool {
+        self.hovered
+    }
+
+    /// Window coordinates of the character boundary at byte `offset`,
+    /// vertically centered on its line.
+    pub fn point_at(&self, offset: usize) -> CursorPos {
+        let (x, top) = self.layout.position_of(offset);
+        CursorPos {
+            x: (self.x as f32 + x).round() as i16,
+            y: (self.y as f32 + top + self.layout.line_height() / 2.0).round() as i16,
+        }
+    }
+
+    fn contains(&self, pos: CursorPos) -> bool {
+        point_in_rect(
+            pos.x as i32,
+            pos.y as i32,
+            self.x,
+            self.y,
+            self.canvas.width(),
+            self.canvas.height(),
+        )
+    }
+
+    fn offset_at(&self, pos: CursorPos) -> usize {
+        self.layout.offset_at(
+            (pos.x as i32 - self.x) as f32,
+            (pos.y as i32 - self.y) as f32,
+        )
+    }
+
+    /// Range of `unit` around byte `offset`.
+    fn unit_at(&self, offset: usize) -> Range<usize> {
+        match self.unit {
+            Unit::Char => offset..offset,
+            Unit::Word => word_at(&self.text, offset),
+            Unit::Line => line_at(&self.text, offset),
+        }
+    }
+
+    fn press(&mut self) {
+        let now = Instant::now();
+        let repeated = self.last_click.is_some_and(|(at, pos)| {
+            now.duration_since(at) < MULTI_CLICK_TIME
+                && (pos.x as i32 - self.cursor.x as i32).abs() <= MULTI_CLICK_DISTANCE
+                && (pos.y as i32 - self.cursor.y as i32).abs() <= MULTI_CLICK_DISTANCE
+        });
+        self.clicks = if repeated { self.clicks % 3 + 1 } else { 1 };
+        self.last_click = Some((now, self.cursor));
+
+        self.unit = match self.clicks {
+            1 => Unit::Char,
+            2 => Unit::Word,
+            _ => Unit::Line,
+        };
+        self.anchor = self.unit_at(self.offset_at(self.cursor));
+        self.selection = self.anchor.clone();
+        self.dragging = true;
+    }
+
+    fn extend(&mut self) {
+        let head = self.unit_at(self.offset_at(self.cursor));
+        self.selection = if head.start < self.anchor.start {
+            head.start..self.anchor.end
+        } else {
+            self.anchor.start..head.end.max(self.anchor.end)
+        };
+    }
+}
+
+impl Widget for SelectableText {
+    fn width(&self) -> u32 {
+        self.canvas.width()
+    }
+
+    fn height(&self) -> u32 {
+        self.canvas.height()
+    }
+
+    fn x(&self) -> i32 {
+        self.x
+    }
+
+    fn y(&self) -> i32 {
+        self.y
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn process_event(&mut self, event: &WindowEvent) -> bool {
+        let before = self.selection.clone();
+        match event {
+            WindowEvent::CursorEnter(pos) | WindowEvent::CursorMove(pos) => {
+                self.cursor = *pos;
+                self.hovered = self.contains(*pos);
+                if self.dragging {
+                    self.extend();
+                }
+            }
+            WindowEvent::CursorLeave => {
+                self.hovered = false;
+            }
+            WindowEvent::ButtonPress(MouseButton::Left, _) => {
+                if self.hovered {
+                    self.press();
+                } else {
+                    self.selection = 0..0;
+                }
+            }
+            WindowEvent::ButtonRelease(MouseButton::Left, _) => {
+                self.dragging = false;
+            }
+            WindowEvent::KeyPress(key)
+                if key.keysym == KEY_A && key.modifiers.contains(Modifiers::CTRL) =>
+            {
+                self.select_all();
+            }
+            _ => {}
+        }
+        self.selection != before
+    }
+
+    fn draw(&self, canvas: &mut Canvas, colors: &Colors) {
+        for (x, y, w, h) in self.layout.range_rects(self.selection.clone()) {
+            canvas.fill_rect(self.x as f32 + x, self.y as f32 + y, w, h, colors.selection);
+        }
+        canvas.draw_canvas(&self.canvas, self.x, self.y);
+    }
+}
+
+/// Returns the word around byte `offset`: a run of alphanumerics (and `_`),
+/// or of whitespace, or a single other character.
+fn word_at(text: &str, offset: usize) -> Range<usize> {
+    let class = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            1
+        } else if c.is_whitespace() && c != '\n' {
+            2
+        } else {
+            0
+        }
+    };
+    // Prefer the character after the offset, as a click lands before it
+    let Some((start, c)) = text[offset..]
+        .chars()
+        .next()
+        .filter(|&c| c != '\n')
+        .map(|c| (offset, c))
+        .or_else(|| text[..offset].char_indices().next_back())
+    else {
+        return offset..offset;
+    };
+    let kind = class(c);
+    if kind == 0 {
+        return start..start + c.len_utf8();
+    }
+    let begin = text[..start]
+        .char_indices()
+        .rev()
+        .take_while(|&(_, c)| class(c) == kind)
+        .last()
+        .map_or(start, |(i, _)| i);
+    let end = text[start..]
+        .char_indices()
+        .find(|&(_, c)| class(c) != kind)
+        .map_or(text.len(), |(i, _)| start + i);
+    begin..end
+}
+
+/// Returns the line (without its newline) containing byte `offset`.
+fn line_at(text: &str, offset: usize) -> Range<usize> {
+    let start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let end = text[offset..].find('\n').map_or(text.len(), |i| offset + i);
+    start..end
+}
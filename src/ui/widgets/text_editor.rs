@@ -0,0 +1,387 @@
+//! Multi-line text editing for the editable text-info dialog.
+
+use super::text_input::{next_word_boundary, previous_word_boundary};
+use crate::{
+    backend::{Modifiers, WindowEvent},
+    ui::{
+        KEY_BACKSPACE, KEY_DELETE, KEY_DOWN, KEY_END, KEY_HOME, KEY_KP_ENTER, KEY_LEFT, KEY_RETURN,
+        KEY_RIGHT, KEY_UP,
+    },
+};
+
+/// Lines of text with a cursor, edited by key presses and typed text.
+///
+/// This holds the text and handles the keys; the dialog showing it draws
+/// [`lines`](Self::lines) and the [`cursor`](Self::cursor) itself.
+#[derive(Debug, Clone)]
+pub(crate) struct TextEditor {
+    lines: Vec<String>,
+    /// Cursor line.
+    line: usize,
+    /// Cursor position in the line, in characters.
+    column: usize,
+    /// Column that Up and Down try to return to on shorter lines.
+    goal_column: usize,
+}
+
+impl TextEditor {
+    /// Starts editing `text` with the cursor at the beginning.
+    pub fn new(text: &str) -> Self {
+        Self {
+            lines: text.split('\n').map(str::to_string).collect(),
+            line: 0,
+            column: 0,
+            goal_column: 0,
+        }
+    }
+
+    /// The whole text, lines joined with `\n`.
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// The cursor as (line, column in characters).
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.line, self.column)
+    }
+
+    /// Handles a key press or typed text. Returns true if it was an editing
+    /// or cursor key.
+    pub fn process_event(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::TextInput(c) if !c.is_control() => {
+                self.insert(*c);
+                true
+            }
+            WindowEvent::KeyPress(key) => self.handle_key(key.keysym, key.modifiers),
+            _ => false,
+        }
+    }
+
+    fn handle_key(&mut self, keysym: u32, modifiers: Modifiers) -> bool {
+        let (line, column) = self.cursor();
+        let ctrl = modifiers.contains(Modifiers::CTRL);
+        match keysym {
+            KEY_RETURN | KEY_KP_ENTER => self.insert('\n'),
+            // By words within the line; at its ends, like the plain keys
+            KEY_BACKSPACE if ctrl && column > 0 => {
+                let start = previous_word_boundary(&self.lines[line], column);
+                self.delete_columns(start..column);
+                self.column = start;
+            }
+            KEY_DELETE if ctrl && column < self.line_len(line) => {
+                let end = next_word_boundary(&self.lines[line], column);
+                self.delete_columns(column..end);
+            }
+            KEY_LEFT if ctrl && column > 0 => {
+                self.column = previous_word_boundary(&self.lines[line], column);
+            }
+            KEY_RIGHT if ctrl && column < self.line_len(line) => {
+                self.column = next_word_boundary(&self.lines[line], column);
+            }
+            KEY_BACKSPACE => self.delete_before(),
+            KEY_DELETE => self.delete_after(),
+            KEY_LEFT => {
+                if column > 0 {
+                    self.column -= 1;
+                } else if line > 0 {
+                    self.line -= 1;
+                    self.column = self.line_len(self.line);
+                }
+            }
+            KEY_RIGHT => {
+                if column < self.line_len(line) {
+                    self.column += 1;
+                } else if line + 1 < self.lines.len() {
+                    self.line += 1;
+                    self.column = 0;
+                }
+            }
+            KEY_UP if line > 0 => return self.move_vertically(line - 1),
+            KEY_DOWN if line + 1 < self.lines.len() => return self.move_vertically(line + 1),
+            KEY_HOME if ctrl => {
+                self.line = 0;
+                self.column = 0;
+            }
+            KEY_HOME => self.column = 0,
+            KEY_END if ctrl => {
+                self.line = self.lines.len() - 1;
+                self.column = self.line_len(self.line);
+            }
+            KEY_END => self.column = self.line_len(line),
+            _ => return false,
+        }
+        // A cursor key with nowhere to go isn't used
+        if matches!(keysym, KEY_LEFT | KEY_RIGHT | KEY_HOME | KEY_END)
+            && self.cursor() == (line, column)
+        {
+            return false;
+        }
+        self.goal_column = self.column;
+        true
+    }
+
+    /// Moves to `line`, as close to the goal column as it allows.
+    fn move_vertically(&mut self, line: usize) -> bool {
+        self.line = line;
+        self.column = self.goal_column.min(self.line_len(line));
+        true
+    }
+
+    fn insert(&mut self, c: char) {
+        let at = self.byte_index();
+        if c == '\n' {
+            let rest = self.lines[self.line].split_off(at);
+            self.lines.insert(self.line + 1, rest);
+            self.line += 1;
+            self.column = 0;
+        } else {
+            self.lines[self.line].insert(at, c);
+            self.column += 1;
+        }
+    }
+
+    fn delete_before(&mut self) {
+        if self.column > 0 {
+            self.column -= 1;
+            let at = self.byte_index();
+            self.lines[self.line].remove(at);
+        } else if self.line > 0 {
+            // Joins with the line above
+            let rest = self.lines.remove(self.line);
+            self.line -= 1;
+            self.column = self.line_len(self.line);
+            self.lines[self.line].push_str(&rest);
+        }
+    }
+
+    fn delete_after(&mut self) {
+        let at = self.byte_index();
+        if at < self.lines[self.line].len() {
+            self.lines[self.line].remove(at);
+        } else if self.line + 1 < self.lines.len() {
+            let next = self.lines.remove(self.line + 1);
+            self.lines[self.line].push_str(&next);
+        }
+    }
+
+    /// Removes a range of characters from the cursor line.
+    fn delete_columns(&mut self, columns: std::ops::Range<usize>) {
+        let text = &mut self.lines[self.line];
+        let byte = |column| {
+            text.char_indices()
+                .nth(column)
+                .map_or(text.len(), |(i, _)| i)
+        };
+        let range = byte(columns.start)..byte(columns.end);
+        text.drain(range);
+    }
+
+    fn line_len(&self, line: usize) -> usize {
+        self.lines[line].chars().count()
+    }
+
+    /// The cursor's byte offset in its line.
+    fn byte_index(&self) -> usize {
+        let text = &self.lines[self.line];
+        text.char_indices()
+            .nth(self.column)
+            .map_or(text.len(), |(i, _)| i)
+    }
+}
+
+/// Edits text the way the editable text-info dialog does and checks the
+/// result.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backend::{KeyEvent, Modifiers},
+        ui::{
+            KEY_BACKSPACE, KEY_DELETE, KEY_DOWN, KEY_END, KEY_ESCAPE, KEY_HOME, KEY_LEFT,
+            KEY_RETURN, KEY_RIGHT, KEY_UP,
+        },
+    };
+
+    fn press(editor: &mut TextEditor, keysym: u32) -> bool {
+        editor.process_event(&WindowEvent::KeyPress(KeyEvent {
+            keysym,
+            modifiers: Modifiers::empty(),
+        }))
+    }
+
+    fn type_text(editor: &mut TextEditor, text: &str) {
+        for c in text.chars() {
+            assert!(editor.process_event(&WindowEvent::TextInput(c)));
+        }
+    }
+
+    #[test]
+    fn text_round_trips() {
+        for text in ["", "one line", "two\nlines\n", "\n\n", "naïve ünïcode\nok"] {
+            assert_eq!(TextEditor::new(text).text(), text);
+        }
+        assert_eq!(TextEditor::new("a\nb\n").lines(), ["a", "b", ""]);
+    }
+
+    #[test]
+    fn typing_inserts_at_the_cursor() {
+        let mut editor = TextEditor::new("héllo");
+        press(&mut editor, KEY_END);
+        type_text(&mut editor, " world");
+        press(&mut editor, KEY_HOME);
+        press(&mut editor, KEY_RIGHT);
+        press(&mut editor, KEY_RIGHT);
+        type_text(&mut editor, "é");
+        assert_eq!(editor.text(), "hééllo world");
+        assert_eq!(editor.cursor(), (0, 3));
+    }
+
+    #[test]
+    fn return_splits_and_deleting_joins() {
+        let mut editor = TextEditor::new("firstsecond");
+        for _ in 0..5 {
+            press(&mut editor, KEY_RIGHT);
+        }
+        press(&mut editor, KEY_RETURN);
+        assert_eq!(editor.lines(), ["first", "second"]);
+        assert_eq!(editor.cursor(), (1, 0));
+
+        press(&mut editor, KEY_BACKSPACE);
+        assert_eq!(editor.text(), "firstsecond");
+        assert_eq!(editor.cursor(), (0, 5));
+
+        press(&mut editor, KEY_RETURN);
+        press(&mut editor, KEY_LEFT);
+        press(&mut editor, KEY_DELETE);
+        assert_eq!(editor.text(), "firstsecond");
+
+        // Nothing to delete at either end
+        press(&mut editor, KEY_HOME);
+        press(&mut editor, KEY_BACKSPACE);
+        press(&mut editor, KEY_END);
+        press(&mut editor, KEY_DELETE);
+        assert_eq!(editor.text(), "firstsecond");
+    }
+
+    #[test]
+    fn left_and_right_wrap_across_lines() {
+        let mut editor = TextEditor::new("ab\ncd");
+        press(&mut editor, KEY_END);
+        press(&mut editor, KEY_RIGHT);
+        assert_eq!(editor.cursor(), (1, 0));
+        press(&mut editor, KEY_LEFT);
+        assert_eq!(editor.cursor(), (0, 2));
+    }
+
+    #[test]
+    fn up_and_down_keep_the_column() {
+        let mut editor = TextEditor::new("a long line\nab\nanother long one");
+        for _ in 0..8 {
+            press(&mut editor, KEY_RIGHT);
+        }
+        press(&mut editor, KEY_DOWN);
+        assert_eq!(editor.cursor(), (1, 2));
+        press(&mut editor, KEY_DOWN);
+        assert_eq!(editor.cursor(), (2, 8));
+        press(&mut editor, KEY_UP);
+        press(&mut editor, KEY_UP);
+        assert_eq!(editor.cursor(), (0, 8));
+
+        // Past the first and last lines, the key isn't used
+        assert!(!press(&mut editor, KEY_UP));
+        editor.process_event(&WindowEvent::KeyPress(KeyEvent {
+            keysym: KEY_END,
+            modifiers: Modifiers::CTRL,
+        }));
+        assert_eq!(editor.cursor(), (2, 16));
+        assert!(!press(&mut editor, KEY_DOWN));
+        // Nor are the others with nowhere to go
+        assert!(!press(&mut editor, KEY_RIGHT));
+        assert!(!press(&mut editor, KEY_END));
+        assert!(!ctrl(&mut editor, KEY_RIGHT));
+        assert_eq!(editor.cursor(), (2, 16));
+    }
+
+    #[test]
+    fn other_keys_are_left_to_the_dialog() {
+        let mut editor = TextEditor::new("text");
+        assert!(!press(&mut editor, KEY_ESCAPE));
+        assert!(!editor.process_event(&WindowEvent::TextInput('\u{7f}')));
+        assert_eq!(editor.text(), "text");
+    }
+
+    fn ctrl(editor: &mut TextEditor, keysym: u32) -> bool {
+        editor.process_event(&WindowEvent::KeyPress(KeyEvent {
+            keysym,
+            modifiers: Modifiers::CTRL,
+        }))
+    }
+
+    /// Ctrl+Right from the start of the line, as cursor columns.
+    fn word_stops(text: &str) -> Vec<usize> {
+        let mut editor = TextEditor::new(text);
+        let mut stops = Vec::new();
+        loop {
+            let before = editor.cursor();
+            if !ctrl(&mut editor, KEY_RIGHT) || editor.cursor() == before || editor.cursor().0 != 0
+            {
+                return stops;
+            }
+            stops.push(editor.cursor().1);
+        }
+    }
+
+    #[test]
+    fn ctrl_arrows_step_over_words() {
+        // Spaces are skipped along with the word after them
+        assert_eq!(word_stops("one  two   three"), [3, 8, 16]);
+        // A punctuation run is a word of its own; `_` belongs to words
+        assert_eq!(word_stops("path::to_item(x)"), [4, 6, 13, 14, 15, 16]);
+        // Letters of any script, counted in characters
+        assert_eq!(word_stops("naïve 日本語 — ok"), [5, 9, 11, 14]);
+
+        let mut editor = TextEditor::new("first line\nsecond");
+        press(&mut editor, KEY_END);
+        ctrl(&mut editor, KEY_LEFT);
+        assert_eq!(editor.cursor(), (0, 6));
+        ctrl(&mut editor, KEY_LEFT);
+        ctrl(&mut editor, KEY_LEFT);
+        assert_eq!(editor.cursor(), (0, 0));
+        // At the ends of a line they cross to the next one like the plain arrows
+        press(&mut editor, KEY_END);
+        ctrl(&mut editor, KEY_RIGHT);
+        assert_eq!(editor.cursor(), (1, 0));
+    }
+
+    #[test]
+    fn ctrl_backspace_and_delete_remove_words() {
+        let mut editor = TextEditor::new("let x = foo.bar;  ");
+        press(&mut editor, KEY_END);
+        ctrl(&mut editor, KEY_BACKSPACE);
+        assert_eq!(editor.text(), "let x = foo.bar");
+        ctrl(&mut editor, KEY_BACKSPACE);
+        assert_eq!(editor.text(), "let x = foo.");
+        ctrl(&mut editor, KEY_BACKSPACE);
+        assert_eq!(editor.text(), "let x = foo");
+
+        press(&mut editor, KEY_HOME);
+        ctrl(&mut editor, KEY_DELETE);
+        assert_eq!(editor.text(), " x = foo");
+        ctrl(&mut editor, KEY_DELETE);
+        assert_eq!(editor.text(), " = foo");
+        assert_eq!(editor.cursor(), (0, 0));
+
+        // Ctrl+Backspace at the start of a line joins it to the one above
+        let mut editor = TextEditor::new("año\nmás");
+        press(&mut editor, KEY_DOWN);
+        ctrl(&mut editor, KEY_BACKSPACE);
+        assert_eq!(editor.text(), "añomás");
+        ctrl(&mut editor, KEY_BACKSPACE);
+        assert_eq!(editor.text(), "más");
+    }
+}
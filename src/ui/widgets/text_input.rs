@@ -1,12 +1,14 @@
 //! Text input widget for single-line text entry.
 
-use super::Widget;
+use std::ops::Range;
+
+use super::{Widget, point_in_rect, undo::UndoHistory};
 use crate::{
     backend::{Modifiers, WindowEvent},
     render::{Canvas, Font, Rgba},
     ui::{
-        Colors, KEY_BACKSPACE, KEY_DELETE, KEY_END, KEY_HOME, KEY_KP_ENTER, KEY_LEFT, KEY_RETURN,
-        KEY_RIGHT, KEY_TAB,
+        Colors, KEY_A, KEY_BACKSPACE, KEY_C, KEY_CAPITAL_Z, KEY_DELETE, KEY_END, KEY_HOME,
+        KEY_KP_ENTER, KEY_LEFT, KEY_RETURN, KEY_RIGHT, KEY_TAB, KEY_V, KEY_X, KEY_Y, KEY_Z,
     },
 };
 
@@ -22,12 +24,32 @@ pub struct TextInput {
     height: u32,
     text: String,
     cursor_pos: usize,
+    /// Where a selection started (a character position); it ends at the cursor.
+    anchor: Option<usize>,
+    /// Text cut or copied, for the dialog to put on the clipboard.
+    copied: Option<String>,
+    /// Ctrl+V was pressed; the dialog answers with [`paste`](Self::paste).
+    paste_requested: bool,
+    /// Earlier texts and cursor positions, for Ctrl+Z.
+    history: UndoHistory<(String, usize)>,
     focused: bool,
     password: bool,
     placeholder: String,
     submitted: bool,
     completion: Option<String>,
     tab_pressed: bool,
+    invalid: bool,
+    read_only: bool,
+    /// Most characters the text may have.
+    max_length: Option<usize>,
+    /// Only these characters may be typed or pasted, if set.
+    allowed_chars: Option<String>,
+    /// These characters may not be typed or pasted.
+    denied_chars: String,
+    /// A password shows an eye button at the right to reveal its text.
+    reveal_button: bool,
+    revealed: bool,
+    reveal_hovered: bool,
 }
 
 impl TextInput {
@@ -39,12 +61,24 @@ impl TextInput {
             height: INPUT_HEIGHT,
             text: String::new(),
             cursor_pos: 0,
+            anchor: None,
+            copied: None,
+            paste_requested: false,
+            history: UndoHistory::new(),
             focused: false,
             password: false,
             placeholder: String::new(),
             submitted: false,
             completion: None,
             tab_pressed: false,
+            invalid: false,
+            read_only: false,
+            max_length: None,
+            allowed_chars: None,
+            denied_chars: String::new(),
+            reveal_button: false,
+            revealed: false,
+            reveal_hovered: false,
         }
     }
 
@@ -53,6 +87,32 @@ impl TextInput {
         self
     }
 
+    /// Shows text the user can't type into; only Enter and Tab are handled.
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Ignores typing beyond `max_length` characters, and cuts pastes short.
+    pub fn with_max_length(mut self, max_length: Option<usize>) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Only lets `allowed` characters (all if `None`) that aren't `denied`
+    /// be typed or pasted.
+    pub fn with_char_filter(mut self, allowed: Option<&str>, denied: &str) -> Self {
+        self.allowed_chars = allowed.map(str::to_string);
+        self.denied_chars = denied.to_string();
+        self
+    }
+
+    /// Gives a password input an eye button that shows or hides its text.
+    pub fn with_reveal_button(mut self, reveal_button: bool) -> Self {
+        self.reveal_button = reveal_button;
+        self
+    }
+
     pub fn with_placeholder(mut self, placeholder: &str) -> Self {
         self.placeholder = placeholder.to_string();
         self
@@ -73,7 +133,47 @@ impl TextInput {
     pub fn set_text(&mut self, text: &str) {
         self.text = text.to_string();
         self.cursor_pos = self.char_count();
+        self.anchor = None;
+        self.completion = None;
+    }
+
+    /// The selected text, if any.
+    pub fn selected_text(&self) -> Option<&str> {
+        let range = self.selection()?;
+        Some(&self.text[self.byte_position(range.start)..self.byte_position(range.end)])
+    }
+
+    /// Takes the text Ctrl+C or Ctrl+X copied, to put on the clipboard.
+    pub fn take_copied(&mut self) -> Option<String> {
+        self.copied.take()
+    }
+
+    /// Returns true once after Ctrl+V, when the dialog should read the
+    /// clipboard and [`paste`](Self::paste) it.
+    pub fn take_paste_request(&mut self) -> bool {
+        std::mem::take(&mut self.paste_requested)
+    }
+
+    /// Inserts `text` at the cursor in place of the selection. Line breaks
+    /// are dropped, as the input has a single line.
+    /// Characters that don't pass the input's filter are dropped, and the
+    /// rest cut short at its maximum length.
+    pub fn paste(&mut self, text: &str) {
+        let before = (self.text.clone(), self.cursor_pos);
+        let room = self.room();
+        let text: String = text
+            .chars()
+            .filter(|&c| !matches!(c, '\n' | '\r') && self.accepts(c))
+            .take(room)
+            .collect();
+        self.delete_selection();
+        let byte_pos = self.byte_position(self.cursor_pos);
+        self.text.insert_str(byte_pos, &text);
+        self.cursor_pos += text.chars().count();
         self.completion = None;
+        if self.text != before.0 {
+            self.history.record(before, false);
+        }
     }
 
     /// Returns true if Enter was pressed.
@@ -88,6 +188,11 @@ impl TextInput {
         self.completion = completion;
     }
 
+    /// Marks the text as not accepted, drawing the border in the invalid color.
+    pub fn set_invalid(&mut self, invalid: bool) {
+        self.invalid = invalid;
+    }
+
     /// Returns true if Tab was pressed (consumed once per check).
     pub fn was_tab_pressed(&mut self) -> bool {
         let pressed = self.tab_pressed;
@@ -95,9 +200,22 @@ impl TextInput {
         pressed
     }
 
+    /// Whether the text is shown masked.
+    fn masked(&self) -> bool {
+        self.password && !self.revealed
+    }
+
+    /// The eye button's square at the right edge, on password inputs that have one.
+    fn reveal_area(&self) -> Option<(i32, i32, u32)> {
+        (self.password && self.reveal_button).then(|| {
+            let size = self.height;
+            (self.x + self.width as i32 - size as i32, self.y, size)
+        })
+    }
+
     /// Returns the display text (masked if password mode).
     fn display_text(&self) -> String {
-        if self.password {
+        if self.masked() {
             "*".repeat(self.char_count())
         } else {
             self.text.clone()
@@ -118,8 +236,51 @@ impl TextInput {
             .unwrap_or(self.text.len())
     }
 
-    /// Inserts a character at the cursor position.
+    /// The selected character range, if any.
+    fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.anchor?;
+        (anchor != self.cursor_pos)
+            .then(|| anchor.min(self.cursor_pos)..anchor.max(self.cursor_pos))
+    }
+
+    /// Deletes the selected text, if any. Returns true if there was some.
+    fn delete_selection(&mut self) -> bool {
+        let Some(range) = self.selection() else {
+            self.anchor = None;
+            return false;
+        };
+        self.text
+            .drain(self.byte_position(range.start)..self.byte_position(range.end));
+        self.cursor_pos = range.start;
+        self.anchor = None;
+        self.completion = None;
+        true
+    }
+
+    /// Whether the filter lets `c` be typed or pasted.
+    fn accepts(&self, c: char) -> bool {
+        self.allowed_chars
+            .as_ref()
+            .is_none_or(|allowed| allowed.contains(c))
+            && !self.denied_chars.contains(c)
+    }
+
+    /// How many characters can be inserted in place of the selection.
+    fn room(&self) -> usize {
+        let Some(max_length) = self.max_length else {
+            return usize::MAX;
+        };
+        let selected = self.selection().map_or(0, |range| range.len());
+        max_length.saturating_sub(self.char_count() - selected)
+    }
+
+    /// Inserts a character at the cursor position, if the filter and the
+    /// maximum length allow it.
     fn insert_char(&mut self, c: char) {
+        if !self.accepts(c) || self.room() == 0 {
+            return;
+        }
+        self.delete_selection();
         let byte_pos = self.byte_position(self.cursor_pos);
         self.text.insert(byte_pos, c);
         self.cursor_pos += 1;
@@ -128,6 +289,9 @@ impl TextInput {
 
     /// Deletes the character before the cursor (backspace).
     fn delete_before(&mut self) {
+        if self.delete_selection() {
+            return;
+        }
         if self.cursor_pos > 0 {
             let byte_pos = self.byte_position(self.cursor_pos - 1);
             let end_pos = self.byte_position(self.cursor_pos);
@@ -139,6 +303,9 @@ impl TextInput {
 
     /// Deletes the character after the cursor (delete).
     fn delete_after(&mut self) {
+        if self.delete_selection() {
+            return;
+        }
         if self.cursor_pos < self.char_count() {
             let byte_pos = self.byte_position(self.cursor_pos);
             let end_pos = self.byte_position(self.cursor_pos + 1);
@@ -159,6 +326,46 @@ impl TextInput {
         }
     }
 
+    /// Deletes from the cursor back to `start`, or the selection if any.
+    fn delete_back_to(&mut self, start: usize) {
+        if self.delete_selection() || start == self.cursor_pos {
+            return;
+        }
+        self.text
+            .drain(self.byte_position(start)..self.byte_position(self.cursor_pos));
+        self.cursor_pos = start;
+        self.completion = None;
+    }
+
+    /// Deletes from the cursor up to `end`, or the selection if any.
+    fn delete_up_to(&mut self, end: usize) {
+        if self.delete_selection() || end == self.cursor_pos {
+            return;
+        }
+        self.text
+            .drain(self.byte_position(self.cursor_pos)..self.byte_position(end));
+        self.completion = None;
+    }
+
+    /// Goes back to the text before the last edit, or with `redo` forward
+    /// again. Returns true if there was one.
+    fn undo(&mut self, redo: bool) -> bool {
+        let current = (self.text.clone(), self.cursor_pos);
+        let state = if redo {
+            self.history.redo(current)
+        } else {
+            self.history.undo(current)
+        };
+        let Some((text, cursor_pos)) = state else {
+            return false;
+        };
+        self.text = text;
+        self.cursor_pos = cursor_pos;
+        self.anchor = None;
+        self.completion = None;
+        true
+    }
+
     fn move_home(&mut self) {
         self.cursor_pos = 0;
     }
@@ -168,7 +375,47 @@ impl TextInput {
     }
 
     fn handle_key(&mut self, keysym: u32, modifiers: Modifiers) -> bool {
+        // Shift+movement selects; other movement drops the selection
+        if matches!(keysym, KEY_LEFT | KEY_RIGHT | KEY_HOME | KEY_END) {
+            if !modifiers.contains(Modifiers::SHIFT) {
+                self.anchor = None;
+            } else if self.anchor.is_none() {
+                self.anchor = Some(self.cursor_pos);
+            }
+        }
+        let ctrl = modifiers.contains(Modifiers::CTRL);
         match keysym {
+            KEY_A if ctrl => {
+                self.anchor = Some(0);
+                self.cursor_pos = self.char_count();
+                true
+            }
+            // Passwords stay out of the clipboard
+            KEY_C if ctrl => {
+                if !self.password {
+                    self.copied = self.selected_text().map(str::to_string);
+                }
+                false
+            }
+            KEY_X if ctrl => {
+                if self.password || self.read_only {
+                    return false;
+                }
+                self.copied = self.selected_text().map(str::to_string);
+                self.delete_selection()
+            }
+            KEY_V if ctrl => {
+                self.paste_requested = !self.read_only;
+                false
+            }
+            KEY_BACKSPACE if ctrl => {
+                self.delete_back_to(previous_word_boundary(&self.text, self.cursor_pos));
+                true
+            }
+            KEY_DELETE if ctrl => {
+                self.delete_up_to(next_word_boundary(&self.text, self.cursor_pos));
+                true
+            }
             KEY_BACKSPACE => {
                 self.delete_before();
                 true
@@ -178,16 +425,16 @@ impl TextInput {
                 true
             }
             KEY_LEFT => {
-                if modifiers.contains(Modifiers::CTRL) {
-                    self.move_home();
+                if ctrl {
+                    self.cursor_pos = previous_word_boundary(&self.text, self.cursor_pos);
                 } else {
                     self.move_left();
                 }
                 true
             }
             KEY_RIGHT => {
-                if modifiers.contains(Modifiers::CTRL) {
-                    self.move_end();
+                if ctrl {
+                    self.cursor_pos = next_word_boundary(&self.text, self.cursor_pos);
                 } else {
                     self.move_right();
                 }
@@ -236,7 +483,9 @@ impl TextInput {
         );
 
         // Draw border
-        let border_color = if self.focused {
+        let border_color = if self.invalid {
+            colors.input_border_invalid
+        } else if self.focused {
             colors.input_border_focused
         } else {
             colors.input_border
@@ -252,8 +501,62 @@ impl TextInput {
             1.0,
         );
 
-        // Draw text or placeholder
         let display = self.display_text();
+
+        // With a maximum length, a count at the right, which the text stops short of
+        let counter = self.max_length.map(|max_length| {
+            font.render(&format!("{}/{max_length}", self.char_count()))
+                .with_color(colors.input_placeholder)
+                .finish()
+        });
+        let counter_width = counter
+            .as_ref()
+            .map_or(0, |counter| counter.width() as i32 + INPUT_PADDING);
+        // The eye button, and the count left of it
+        let mut right = self.x + self.width as i32;
+        if let Some((x, y, size)) = self.reveal_area() {
+            let color = if self.reveal_hovered {
+                colors.text
+            } else {
+                colors.input_placeholder
+            };
+            draw_eye(canvas, x, y, size, color, bg_color, self.revealed);
+            right = x + INPUT_PADDING;
+        }
+        if let Some(counter) = &counter {
+            canvas.draw_canvas(
+                counter,
+                right - INPUT_PADDING - counter.width() as i32,
+                self.y + (self.height as i32 - counter.height() as i32) / 2,
+            );
+        }
+        let text_right = right - INPUT_PADDING - counter_width;
+
+        // Draw the selection behind the text
+        if self.focused
+            && let Some(range) = self.selection()
+        {
+            let offset = |chars: usize| {
+                let before: String = display.chars().take(chars).collect();
+                if before.is_empty() {
+                    0
+                } else {
+                    font.render(&before).finish().width() as i32
+                }
+            };
+            let text_x = self.x + INPUT_PADDING;
+            let end = (text_x + offset(range.end)).min(text_right);
+            let start = text_x + offset(range.start);
+            canvas.fill_rect(
+                start as f32,
+                (self.y + 6) as f32,
+                (end - start).max(0) as f32,
+                (self.height as i32 - 12) as f32,
+                colors.selection,
+            );
+        }
+
+        // Draw text or placeholder
         let (text_to_render, text_color): (&str, Rgba) = if display.is_empty() && !self.focused {
             (&self.placeholder, colors.input_placeholder)
         } else {
@@ -265,7 +568,7 @@ impl TextInput {
             let text_y = self.y + (self.height as i32 - text_canvas.height() as i32) / 2;
 
             // Clip text to input width
-            let available_width = (self.width as i32 - 2 * INPUT_PADDING) as u32;
+            let available_width = (text_right - self.x - INPUT_PADDING).max(0) as u32;
             if text_canvas.width() > available_width {
                 // Create a sub-pixmap with only the visible portion
                 let mut visible_canvas =
@@ -285,11 +588,11 @@ impl TextInput {
         }
 
         // Draw cursor
-        if self.focused {
+        if self.focused && !self.read_only {
             let cursor_x = if self.cursor_pos == 0 {
                 self.x + INPUT_PADDING
             } else {
-                let before_cursor = if self.password {
+                let before_cursor = if self.masked() {
                     "*".repeat(self.cursor_pos)
                 } else {
                     self.text.chars().take(self.cursor_pos).collect()
@@ -319,7 +622,7 @@ impl TextInput {
                         .finish();
                     let ghost_y = self.y + (self.height as i32 - ghost_canvas.height() as i32) / 2;
                     let ghost_x = cursor_x + 1;
-                    let available = (self.x + self.width as i32 - INPUT_PADDING - ghost_x) as u32;
+                    let available = (text_right - ghost_x).max(0) as u32;
                     if available > 0 {
                         if ghost_canvas.width() > available {
                             let mut clipped =
@@ -374,24 +677,156 @@ impl Widget for TextInput {
     }
 
     fn process_event(&mut self, event: &WindowEvent) -> bool {
-        match event {
+        // Ctrl+Z undoes; Ctrl+Shift+Z and Ctrl+Y redo
+        if let WindowEvent::KeyPress(key) = event
+            && self.focused
+            && !self.read_only
+            && key.modifiers.contains(Modifiers::CTRL)
+            && matches!(key.keysym, KEY_Z | KEY_CAPITAL_Z | KEY_Y)
+        {
+            let redo = key.keysym == KEY_Y || key.modifiers.contains(Modifiers::SHIFT);
+            return self.undo(redo);
+        }
+
+        let before = self.focused.then(|| (self.text.clone(), self.cursor_pos));
+        let redraw = match event {
+            WindowEvent::CursorMove(pos) => {
+                let hovered = self.reveal_area().is_some_and(|(x, y, size)| {
+                    point_in_rect(pos.x as i32, pos.y as i32, x, y, size, size)
+                });
+                std::mem::replace(&mut self.reveal_hovered, hovered) != hovered
+            }
+            // Focus handling is done by the dialog
             WindowEvent::ButtonPress(crate::backend::MouseButton::Left, _) => {
-                // Check if clicked inside
-                // Focus handling is done by the dialog
-                false
+                if self.reveal_hovered {
+                    self.revealed = !self.revealed;
+                }
+                self.reveal_hovered
             }
-            WindowEvent::TextInput(c) if self.focused => {
+            WindowEvent::TextInput(c) if self.focused && !self.read_only => {
                 self.insert_char(*c);
                 true
             }
-            WindowEvent::KeyPress(key_event) if self.focused => {
+            WindowEvent::KeyPress(key_event)
+                if self.focused
+                    && (!self.read_only
+                        || matches!(
+                            key_event.keysym,
+                            KEY_RETURN | KEY_KP_ENTER | KEY_TAB | KEY_A | KEY_C | KEY_V
+                        )) =>
+            {
                 self.handle_key(key_event.keysym, key_event.modifiers)
             }
             _ => false,
+        };
+        // Typed characters run together into one undo step
+        if let Some(before) = before {
+            if self.text != before.0 {
+                self.history
+                    .record(before, matches!(event, WindowEvent::TextInput(_)));
+            } else if self.cursor_pos != before.1 {
+                self.history.break_run();
+            }
         }
+        redraw
     }
 
     fn draw(&self, _canvas: &mut Canvas, _colors: &Colors) {
         // Use draw_to instead for font access
     }
 }
+
+/// Draws an eye in a `size` square at (`x`, `y`), crossed out with `crossed`.
+fn draw_eye(canvas: &mut Canvas, x: i32, y: i32, size: u32, color: Rgba, bg: Rgba, crossed: bool) {
+    let (cx, cy) = (x as f32 + size as f32 / 2.0, y as f32 + size as f32 / 2.0);
+    let half_width = size as f32 * 0.28;
+    let half_height = size as f32 * 0.16;
+    // The outline's upper and lower arcs
+    const STEPS: usize = 12;
+    let arc = |i: usize, sign: f32| {
+        let t = i as f32 / STEPS as f32;
+        let lift = half_height * (t * std::f32::consts::PI).sin();
+        (cx - half_width + 2.0 * half_width * t, cy + sign * lift)
+    };
+    let outline: Vec<_> = (0..=STEPS)
+        .map(|i| arc(i, -1.0))
+        .chain((1..STEPS).rev().map(|i| arc(i, 1.0)))
+        .collect();
+    canvas.fill_polygon(&outline, color);
+    canvas.fill_circle(cx, cy, half_height * 0.8, bg);
+    canvas.fill_circle(cx, cy, half_height * 0.45, color);
+    if crossed {
+        let (dx, dy) = (half_width * 0.9, half_height * 1.6);
+        // A thin bar from bottom left to top right, with a gap against the eye
+        let thickness = (size as f32 / 24.0).max(1.0);
+        for (offset, bar_color) in [(thickness * 1.5, bg), (thickness * 0.5, color)] {
+            canvas.fill_polygon(
+                &[
+                    (cx - dx - offset, cy + dy - offset),
+                    (cx + dx - offset, cy - dy - offset),
+                    (cx + dx + offset, cy - dy + offset),
+                    (cx - dx + offset, cy + dy + offset),
+                ],
+                bar_color,
+            );
+        }
+    }
+}
+
+/// How a character groups with its neighbours when moving by words.
+#[derive(PartialEq)]
+enum CharClass {
+    Space,
+    /// Letters and digits of any script, and `_`.
+    Word,
+    Punctuation,
+}
+
+fn char_class(c: char) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Space
+    } else if c.is_alphanumeric() || c == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punctuation
+    }
+}
+
+/// Where Ctrl+Left moves from character position `pos`: back over any
+/// spaces, then to the start of the word or punctuation run before them.
+///
+/// Shared by [`TextInput`] and [`TextEditor`](super::text_editor::TextEditor)
+/// so every editable field steps through words the same way.
+pub(crate) fn previous_word_boundary(text: &str, pos: usize) -> usize {
+    let chars: Vec<char> = text.chars().take(pos).collect();
+    let mut pos = chars.len();
+    while pos > 0 && char_class(chars[pos - 1]) == CharClass::Space {
+        pos -= 1;
+    }
+    if let Some(&c) = pos.checked_sub(1).and_then(|i| chars.get(i)) {
+        let class = char_class(c);
+        while pos > 0 && char_class(chars[pos - 1]) == class {
+            pos -= 1;
+        }
+    }
+    pos
+}
+
+/// Where Ctrl+Right moves from character position `pos`: over any spaces,
+/// then to the end of the word or punctuation run after them.
+pub(crate) fn next_word_boundary(text: &str, pos: usize) -> usize {
+    let mut chars = text.chars().skip(pos).peekable();
+    let mut pos = pos;
+    while chars
+        .next_if(|&c| char_class(c) == CharClass::Space)
+        .is_some()
+    {
+        pos += 1;
+    }
+    if let Some(class) = chars.peek().map(|&c| char_class(c)) {
+        while chars.next_if(|&c| char_class(c) == class).is_some() {
+            pos += 1;
+        }
+    }
+    pos
+}
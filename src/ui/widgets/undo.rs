@@ -0,0 +1,63 @@
+//! Undo and redo for the text widgets.
+
+use std::collections::VecDeque;
+
+/// Edits remembered beyond this are dropped, oldest first.
+const UNDO_LIMIT: usize = 200;
+
+/// Snapshots of a widget's state to go back and forth between.
+///
+/// The widget [records](Self::record) its state before each edit. Characters
+/// typed one after another make a single step; any other edit, or moving the
+/// cursor in between (see [`break_run`](Self::break_run)), starts a new one.
+#[derive(Debug, Clone)]
+pub(crate) struct UndoHistory<T> {
+    undo: VecDeque<T>,
+    redo: Vec<T>,
+    /// The last step is still being typed into.
+    typing: bool,
+}
+
+impl<T> UndoHistory<T> {
+    pub fn new() -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            typing: false,
+        }
+    }
+
+    /// Remembers `before`, the state an edit is about to change. With
+    /// `typing`, the edit joins a run of typed characters if there is one.
+    pub fn record(&mut self, before: T, typing: bool) {
+        self.redo.clear();
+        if !(typing && self.typing) {
+            if self.undo.len() == UNDO_LIMIT {
+                self.undo.pop_front();
+            }
+            self.undo.push_back(before);
+        }
+        self.typing = typing;
+    }
+
+    /// Ends the current run of typing, so the next edit is a step of its own.
+    pub fn break_run(&mut self) {
+        self.typing = false;
+    }
+
+    /// Returns the state to go back to from `current`, if there is one.
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let previous = self.undo.pop_back()?;
+        self.redo.push(current);
+        self.typing = false;
+        Some(previous)
+    }
+
+    /// Returns the state undone from `current`, if there is one.
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.redo.pop()?;
+        self.undo.push_back(current);
+        self.typing = false;
+        Some(next)
+    }
+}
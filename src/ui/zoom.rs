@@ -0,0 +1,208 @@
+//! Runtime zoom of dialog contents (Ctrl+Plus, Ctrl+Minus, Ctrl+0).
+//!
+//! The zoom multiplies the display scale of every window created after it
+//! is set. Changing it while a dialog is open rebuilds the dialog: the window
+//! is recreated at the new size, with what the user entered carried over.
+//! Once [`remember`] is called, the zoom is also saved in the state
+//! directory, so later dialogs start at the same size.
+
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+};
+
+use crate::{
+    backend::{KeyEvent, Modifiers},
+    ui::{KEY_0, KEY_EQUAL, KEY_KP_0, KEY_KP_ADD, KEY_KP_SUBTRACT, KEY_MINUS, KEY_PLUS},
+};
+
+/// Smallest zoom.
+pub(crate) const MIN_ZOOM: f32 = 0.75;
+/// Largest zoom.
+pub(crate) const MAX_ZOOM: f32 = 2.0;
+/// How much one Ctrl+Plus or Ctrl+Minus changes the zoom.
+pub(crate) const ZOOM_STEP: f32 = 0.1;
+
+/// The zoom as `f32` bits; starts at 1.0.
+static ZOOM: AtomicU32 = AtomicU32::new(0x3f80_0000);
+static REMEMBER: AtomicBool = AtomicBool::new(false);
+
+/// A zoom shortcut.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ZoomKey {
+    /// Ctrl+Plus (or Ctrl+=, the same key unshifted).
+    In,
+    /// Ctrl+Minus.
+    Out,
+    /// Ctrl+0.
+    Reset,
+}
+
+impl ZoomKey {
+    /// The shortcut `event` is, if any, including the keypad keys.
+    pub fn from_key(event: &KeyEvent) -> Option<Self> {
+        if !event.modifiers.contains(Modifiers::CTRL) {
+            return None;
+        }
+        match event.keysym {
+            KEY_PLUS | KEY_EQUAL | KEY_KP_ADD => Some(ZoomKey::In),
+            KEY_MINUS | KEY_KP_SUBTRACT => Some(ZoomKey::Out),
+            KEY_0 | KEY_KP_0 => Some(ZoomKey::Reset),
+            _ => None,
+        }
+    }
+
+    /// The zoom after pressing this shortcut at `zoom`.
+    pub fn apply(self, zoom: f32) -> f32 {
+        match self {
+            ZoomKey::In => clamp(zoom + ZOOM_STEP),
+            ZoomKey::Out => clamp(zoom - ZOOM_STEP),
+            ZoomKey::Reset => 1.0,
+        }
+    }
+}
+
+/// Clamps `zoom` to [`MIN_ZOOM`]..=[`MAX_ZOOM`], rounded to whole percent so
+/// repeated steps don't drift. Anything that isn't a number becomes 1.0.
+pub(crate) fn clamp(zoom: f32) -> f32 {
+    if !zoom.is_finite() {
+        return 1.0;
+    }
+    ((zoom * 100.0).round() / 100.0).clamp(MIN_ZOOM, MAX_ZOOM)
+}
+
+/// The current zoom.
+pub(crate) fn zoom() -> f32 {
+    f32::from_bits(ZOOM.load(Ordering::Relaxed))
+}
+
+/// Sets the zoom for windows created from now on, saving it if
+/// [`remember`] was called.
+pub(crate) fn set_zoom(zoom: f32) {
+    let zoom = clamp(zoom);
+    ZOOM.store(zoom.to_bits(), Ordering::Relaxed);
+    if REMEMBER.load(Ordering::Relaxed)
+        && let Some(path) = state_file()
+    {
+        let saved = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::write(&path, format!("{zoom}\n")));
+        if let Err(e) = saved {
+            eprintln!(
+                "zenity-rs: warning: couldn't save zoom to {}: {e}",
+                path.display()
+            );
+        }
+    }
+}
+
+/// Starts at the saved zoom, if any, and saves every later change.
+pub fn remember() {
+    if let Some(zoom) = state_file()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| parse(&text))
+    {
+        ZOOM.store(zoom.to_bits(), Ordering::Relaxed);
+    }
+    REMEMBER.store(true, Ordering::Relaxed);
+}
+
+/// Parses a saved zoom, clamping it to the allowed range.
+pub(crate) fn parse(text: &str) -> Option<f32> {
+    let zoom: f32 = text.trim().parse().ok()?;
+    zoom.is_finite().then(|| clamp(zoom))
+}
+
+/// Where the zoom is saved: `zenity-rs/zoom` in the XDG state directory.
+pub(crate) fn state_file() -> Option<PathBuf> {
+    crate::xdg::state_dir().map(|dir| dir.join("zenity-rs").join("zoom"))
+}
+
+/// Applies the zoom shortcut in `event`, if it is one that changes the zoom.
+/// Returns whether it did, in which case the dialog should rebuild.
+pub(crate) fn handle_key(event: &KeyEvent) -> bool {
+    let Some(key) = ZoomKey::from_key(event) else {
+        return false;
+    };
+    let current = zoom();
+    let zoom = key.apply(current);
+    if zoom == current {
+        return false;
+    }
+    set_zoom(zoom);
+    true
+}
+
+/// `size` (logical pixels) at the current zoom, truncated the way dialogs
+/// size their canvases so the two agree.
+pub(crate) fn zoomed(size: u16) -> u16 {
+    (size as f32 * zoom()) as u16
+}
+
+/// Runtime zoom (Ctrl+Plus/Minus/0): the shortcuts and the multiplier math.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(keysym: u32, modifiers: Modifiers) -> KeyEvent {
+        KeyEvent {
+            keysym,
+            modifiers,
+        }
+    }
+
+    #[test]
+    fn shortcuts_need_ctrl() {
+        let ctrl = Modifiers::CTRL;
+        for keysym in [0x2b, 0x3d, 0xffab] {
+            assert_eq!(ZoomKey::from_key(&key(keysym, ctrl)), Some(ZoomKey::In));
+        }
+        for keysym in [0x2d, 0xffad] {
+            assert_eq!(ZoomKey::from_key(&key(keysym, ctrl)), Some(ZoomKey::Out));
+        }
+        for keysym in [0x30, 0xffb0] {
+            assert_eq!(ZoomKey::from_key(&key(keysym, ctrl)), Some(ZoomKey::Reset));
+        }
+        // Ctrl+Shift+= is Ctrl+Plus on most layouts
+        let shifted = key(0x2b, Modifiers::CTRL | Modifiers::SHIFT);
+        assert_eq!(ZoomKey::from_key(&shifted), Some(ZoomKey::In));
+        assert_eq!(ZoomKey::from_key(&key(0x2b, Modifiers::empty())), None);
+        assert_eq!(ZoomKey::from_key(&key(0x61, ctrl)), None);
+    }
+
+    #[test]
+    fn steps_are_ten_percent_without_drift() {
+        let mut zoom = 1.0;
+        for expected in [1.1, 1.2, 1.3, 1.4, 1.5, 1.6, 1.7, 1.8, 1.9, 2.0] {
+            zoom = ZoomKey::In.apply(zoom);
+            assert_eq!(zoom, expected);
+        }
+        for expected in [1.9, 1.8, 1.7, 1.6, 1.5, 1.4, 1.3, 1.2, 1.1, 1.0, 0.9, 0.8] {
+            zoom = ZoomKey::Out.apply(zoom);
+            assert_eq!(zoom, expected);
+        }
+        assert_eq!(ZoomKey::Reset.apply(1.7), 1.0);
+    }
+
+    #[test]
+    fn zoom_is_clamped() {
+        assert_eq!(ZoomKey::In.apply(MAX_ZOOM), MAX_ZOOM);
+        assert_eq!(ZoomKey::Out.apply(0.8), MIN_ZOOM);
+        assert_eq!(ZoomKey::Out.apply(MIN_ZOOM), MIN_ZOOM);
+        assert_eq!(ZoomKey::In.apply(MIN_ZOOM), 0.85);
+        assert_eq!(clamp(5.0), MAX_ZOOM);
+        assert_eq!(clamp(0.1), MIN_ZOOM);
+        assert_eq!(clamp(f32::NAN), 1.0);
+    }
+
+    #[test]
+    fn saved_zoom_is_parsed_and_clamped() {
+        assert_eq!(parse("1.3\n"), Some(1.3));
+        assert_eq!(parse(" 9 "), Some(MAX_ZOOM));
+        assert_eq!(parse("0"), Some(MIN_ZOOM));
+        assert_eq!(parse("inf"), None);
+        assert_eq!(parse("big"), None);
+        assert_eq!(parse(""), None);
+    }
+}
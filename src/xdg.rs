@@ -0,0 +1,34 @@
+//! The home directory and XDG base directories, from the environment.
+//!
+//! Only the file chooser needs the user directories (Desktop, Downloads...)
+//! and pulls in `dirs` for them; everything else gets by with these.
+
+use std::path::PathBuf;
+
+/// `$HOME`, if it's set to an absolute path.
+pub(crate) fn home_dir() -> Option<PathBuf> {
+    absolute("HOME")
+}
+
+/// `$XDG_DATA_HOME`, or `~/.local/share`.
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    absolute("XDG_DATA_HOME").or_else(|| home_dir().map(|home| home.join(".local/share")))
+}
+
+/// `$XDG_CONFIG_HOME`, or `~/.config`.
+pub(crate) fn config_dir() -> Option<PathBuf> {
+    absolute("XDG_CONFIG_HOME").or_else(|| home_dir().map(|home| home.join(".config")))
+}
+
+/// `$XDG_STATE_HOME`, or `~/.local/state`.
+pub(crate) fn state_dir() -> Option<PathBuf> {
+    absolute("XDG_STATE_HOME").or_else(|| home_dir().map(|home| home.join(".local/state")))
+}
+
+/// The path in environment variable `name`. Relative paths are ignored, as
+/// the XDG spec says.
+fn absolute(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+}
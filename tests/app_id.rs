@@ -0,0 +1,39 @@
+//! Command-line validation of `--class` and its alias `--name`.
+
+#![cfg(feature = "cli")]
+
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_zenity-rs"))
+        .args(args)
+        .env_remove("WAYLAND_DISPLAY")
+        .env_remove("DISPLAY")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn accepted_by_every_dialog_type() {
+    for dialog in ["--info", "--entry", "--list", "--scale"] {
+        for flag in ["--class=backup-prompt", "--name=backup-prompt"] {
+            // Gets as far as opening the window, which fails without a display
+            let output = run(&[dialog, flag, "--strict"]);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            assert_eq!(output.status.code(), Some(100), "{dialog} {flag}: {stderr}");
+            assert!(
+                !stderr.contains("--class") && !stderr.contains("--name"),
+                "{dialog} {flag}: {stderr}"
+            );
+        }
+    }
+}
+
+#[test]
+fn empty_id_is_an_error() {
+    let output = run(&["--info", "--class="]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(100));
+    assert!(stderr.contains("--class needs a non-empty ID"), "{stderr}");
+}
@@ -0,0 +1,40 @@
+//! `--attach` handles: parsing X11 window IDs and accepting the flags on the
+//! command line.
+
+use zenity_rs::x11_window_id;
+
+#[test]
+fn x11_window_ids_in_decimal_and_hex() {
+    assert_eq!(x11_window_id("62914573"), Some(62914573));
+    assert_eq!(x11_window_id("0x3c0000d"), Some(0x3c0000d));
+    assert_eq!(x11_window_id("0X3C0000D"), Some(0x3c0000d));
+    assert_eq!(x11_window_id(" 42\n"), Some(42));
+}
+
+#[test]
+fn exported_handles_are_not_window_ids() {
+    for handle in ["", "0", "0x", "e3b0c442-98fc-1c14", "wayland-handle", "-5"] {
+        assert_eq!(x11_window_id(handle), None, "{handle:?}");
+    }
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn accepted_by_every_dialog_type() {
+    use std::process::{Command, Stdio};
+
+    for dialog in ["--info", "--entry", "--calendar", "--scale"] {
+        let output = Command::new(env!("CARGO_BIN_EXE_zenity-rs"))
+            .args([dialog, "--attach=0x3c0000d", "--modal", "--strict"])
+            .env_remove("WAYLAND_DISPLAY")
+            .env_remove("DISPLAY")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // Past option parsing, failing only for lack of a display
+        assert_eq!(output.status.code(), Some(100), "{dialog}: {stderr}");
+        assert!(!stderr.contains("--attach"), "{dialog}: {stderr}");
+        assert!(!stderr.contains("--modal"), "{dialog}: {stderr}");
+    }
+}
@@ -0,0 +1,91 @@
+//! The calendar's month and year quick picker, and paging by month or year.
+//!
+//! Needs the scripted backend: `cargo test --test calendar_picker --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "calendar"))]
+
+use zenity_rs::{
+    CalendarResult, Modifiers,
+    mock::{self, KEY_DOWN, KEY_ESCAPE, KEY_PAGE_DOWN, KEY_PAGE_UP, KEY_RETURN, MockEvent},
+};
+
+/// The month and year in the header, 16px in from the window's corner.
+const HEADER: (i16, i16) = (60, 36);
+/// The picker's next year arrow, right of the year.
+const NEXT_YEAR: (i16, i16) = (250, 76);
+
+/// The centre of a month in the picker's 3 by 4 grid, below the spinner.
+const fn month_cell(month: i16) -> (i16, i16) {
+    let index = month - 1;
+    (16 + 42 + (index % 3) * 84, 96 + 25 + (index / 3) * 51)
+}
+
+/// The date accepted with Enter after `events`, starting on 31 January 2024.
+fn pick(events: impl IntoIterator<Item = MockEvent>) -> (u32, u32, u32) {
+    let events: Vec<_> = events.into_iter().collect();
+    mock::script(
+        events
+            .into_iter()
+            .chain([MockEvent::key(KEY_RETURN), MockEvent::Disconnect]),
+    );
+    let result = zenity_rs::calendar()
+        .year(2024)
+        .month(1)
+        .day(31)
+        .show()
+        .unwrap();
+    match result {
+        CalendarResult::Selected {
+            year,
+            month,
+            day,
+            ..
+        } => (year, month, day),
+        result => panic!("{result:?}"),
+    }
+}
+
+#[test]
+fn clicking_a_month_in_the_picker_jumps_to_it() {
+    let mut events = Vec::new();
+    events.extend(MockEvent::click(HEADER));
+    events.extend(MockEvent::click(NEXT_YEAR));
+    events.extend(MockEvent::click(month_cell(6)));
+    // June has no 31st
+    assert_eq!(pick(events), (2025, 6, 30));
+}
+
+#[test]
+fn the_picker_captures_clicks_and_keys() {
+    let mut events = Vec::new();
+    events.extend(MockEvent::click(HEADER));
+    // Down a row from January to April, then picked with Enter
+    events.push(MockEvent::key(KEY_DOWN));
+    events.push(MockEvent::key(KEY_PAGE_UP));
+    events.push(MockEvent::key(KEY_RETURN));
+    assert_eq!(pick(events), (2023, 4, 30));
+
+    // Escape closes it without leaving the dialog or changing the date
+    let mut events = Vec::new();
+    events.extend(MockEvent::click(HEADER));
+    events.extend(MockEvent::click(month_cell(3)).into_iter().take(1));
+    events.push(MockEvent::key(KEY_ESCAPE));
+    assert_eq!(pick(events), (2024, 1, 31));
+}
+
+#[test]
+fn page_keys_move_by_month_and_year() {
+    assert_eq!(pick([MockEvent::key(KEY_PAGE_DOWN)]), (2024, 2, 29));
+    assert_eq!(pick([MockEvent::key(KEY_PAGE_UP)]), (2023, 12, 31));
+    assert_eq!(
+        pick([MockEvent::key_with(KEY_PAGE_DOWN, Modifiers::CTRL)]),
+        (2025, 1, 31)
+    );
+    assert_eq!(
+        pick([
+            MockEvent::key(KEY_PAGE_DOWN),
+            MockEvent::key_with(KEY_PAGE_DOWN, Modifiers::CTRL)
+        ]),
+        (2025, 2, 28)
+    );
+}
@@ -0,0 +1,184 @@
+//! Limiting the calendar to the dates from `min_date` to `max_date`.
+//!
+//! The dialog tests need the scripted backend: `cargo test --test calendar_range --features mock-backend`.
+
+#![cfg(feature = "calendar")]
+
+#[cfg(feature = "cli")]
+mod cli {
+    use std::process::{Command, Stdio};
+
+    fn stderr(args: &[&str]) -> String {
+        let output = Command::new(env!("CARGO_BIN_EXE_zenity-rs"))
+            .arg("--calendar")
+            .args(args)
+            .env_remove("WAYLAND_DISPLAY")
+            .env_remove("DISPLAY")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(100), "{args:?}");
+        String::from_utf8_lossy(&output.stderr).into_owned()
+    }
+
+    #[test]
+    fn dates_must_be_real_days() {
+        for arg in [
+            "--min-date=2024-13-01",
+            "--min-date=2023-02-29",
+            "--max-date=2024-1",
+            "--max-date=tomorrow",
+        ] {
+            let stderr = stderr(&[arg]);
+            assert!(
+                stderr.contains("expected a date as YYYY-MM-DD"),
+                "{arg}: {stderr}"
+            );
+        }
+        // Valid dates get as far as opening the window
+        let stderr = stderr(&["--min-date=2024-02-29", "--max-date=2024-03-01"]);
+        assert!(!stderr.contains("YYYY-MM-DD"), "{stderr}");
+    }
+
+    #[test]
+    fn years_start_at_one() {
+        let rejected = stderr(&["--year=0"]);
+        assert!(rejected.contains("expected a year from 1"), "{rejected}");
+        let accepted = stderr(&["--year=1"]);
+        assert!(!accepted.contains("--year"), "{accepted}");
+    }
+}
+
+#[cfg(feature = "mock-backend")]
+mod dialog {
+    use zenity_rs::{
+        CalendarBuilder, CalendarResult, Modifiers, WeekStart,
+        mock::{
+            self, KEY_ESCAPE, KEY_LEFT, KEY_PAGE_DOWN, KEY_PAGE_UP, KEY_RETURN, KEY_RIGHT, KEY_UP,
+            MockEvent,
+        },
+    };
+
+    /// The header's "<" and ">", and the month and year between them.
+    const PREV_MONTH: (i16, i16) = (24, 36);
+    const NEXT_MONTH: (i16, i16) = (262, 36);
+    const HEADER: (i16, i16) = (60, 36);
+
+    /// The centre of a day of a month starting on `first_weekday` (Sunday
+    /// is 0), in the 36px grid below the header and weekday names.
+    const fn day_cell(first_weekday: i16, day: i16) -> (i16, i16) {
+        let index = first_weekday + day - 1;
+        (16 + 18 + (index % 7) * 36, 84 + 18 + (index / 7) * 36)
+    }
+
+    /// The centre of a month in the quick picker.
+    const fn month_cell(month: i16) -> (i16, i16) {
+        let index = month - 1;
+        (16 + 42 + (index % 3) * 84, 96 + 25 + (index / 3) * 51)
+    }
+
+    fn keys(keysym: u32, count: usize) -> Vec<MockEvent> {
+        vec![MockEvent::key(keysym); count]
+    }
+
+    /// The date accepted with Enter after `events`.
+    fn pick(builder: CalendarBuilder, events: Vec<MockEvent>) -> (u32, u32, u32) {
+        mock::script(
+            events
+                .into_iter()
+                .chain([MockEvent::key(KEY_RETURN), MockEvent::Disconnect]),
+        );
+        match builder.show().unwrap() {
+            CalendarResult::Selected {
+                year,
+                month,
+                day,
+                ..
+            } => (year, month, day),
+            result => panic!("{result:?}"),
+        }
+    }
+
+    /// 10 January to 20 February 2024, starting on 31 January, in a grid
+    /// whose rows start on Sunday.
+    fn mid_month() -> CalendarBuilder {
+        zenity_rs::calendar()
+            .week_start(WeekStart::Sunday)
+            .year(2024)
+            .month(1)
+            .day(31)
+            .min_date(2024, 1, 10)
+            .max_date(2024, 2, 20)
+    }
+
+    /// 5 to 25 March 2024, starting on the 15th.
+    fn one_month() -> CalendarBuilder {
+        zenity_rs::calendar()
+            .year(2024)
+            .month(3)
+            .day(15)
+            .min_date(2024, 3, 5)
+            .max_date(2024, 3, 25)
+    }
+
+    #[test]
+    fn arrow_keys_stop_at_mid_month_boundaries() {
+        assert_eq!(pick(mid_month(), keys(KEY_RIGHT, 40)), (2024, 2, 20));
+        assert_eq!(pick(mid_month(), keys(KEY_LEFT, 40)), (2024, 1, 10));
+        // A week back from the 12th would be the 5th
+        let mut events = keys(KEY_LEFT, 19);
+        events.push(MockEvent::key(KEY_UP));
+        assert_eq!(pick(mid_month(), events), (2024, 1, 10));
+    }
+
+    #[test]
+    fn the_starting_date_is_moved_into_range() {
+        let builder = || zenity_rs::calendar().year(2024).month(1).day(31);
+        assert_eq!(pick(builder().max_date(2024, 1, 15), vec![]), (2024, 1, 15));
+        assert_eq!(pick(builder().min_date(2024, 6, 1), vec![]), (2024, 6, 1));
+    }
+
+    #[test]
+    fn days_outside_the_range_cannot_be_clicked() {
+        // January 2024 starts on a Monday
+        let mut events = MockEvent::click(day_cell(1, 5)).to_vec();
+        assert_eq!(pick(mid_month(), events.clone()), (2024, 1, 31));
+        events.extend(MockEvent::click(day_cell(1, 12)));
+        assert_eq!(pick(mid_month(), events), (2024, 1, 12));
+    }
+
+    #[test]
+    fn months_entirely_outside_the_range_cannot_be_reached() {
+        let mut events = MockEvent::click(PREV_MONTH).to_vec();
+        events.extend(MockEvent::click(NEXT_MONTH));
+        events.extend(MockEvent::click(NEXT_MONTH));
+        assert_eq!(pick(one_month(), events), (2024, 3, 15));
+
+        // Nor picked from the quick picker
+        let mut events = MockEvent::click(HEADER).to_vec();
+        events.extend(MockEvent::click(month_cell(4)));
+        events.push(MockEvent::key(KEY_RETURN));
+        events.push(MockEvent::key(KEY_ESCAPE));
+        assert_eq!(pick(one_month(), events), (2024, 3, 15));
+
+        // Leaving a month with some days in range lands on the nearest
+        let mut events = MockEvent::click(NEXT_MONTH).to_vec();
+        events.extend(MockEvent::click(NEXT_MONTH));
+        assert_eq!(pick(mid_month(), events), (2024, 2, 20));
+    }
+
+    #[test]
+    fn paging_clamps_at_the_boundary() {
+        assert_eq!(
+            pick(one_month(), vec![MockEvent::key(KEY_PAGE_UP)]),
+            (2024, 3, 5)
+        );
+        assert_eq!(
+            pick(
+                one_month(),
+                vec![MockEvent::key_with(KEY_PAGE_DOWN, Modifiers::CTRL)]
+            ),
+            (2024, 3, 25)
+        );
+    }
+}
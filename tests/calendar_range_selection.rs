@@ -0,0 +1,105 @@
+//! Picking a start and an end date in the calendar's range mode.
+//!
+//! Needs the scripted backend: `cargo test --test calendar_range_selection --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "calendar"))]
+
+use zenity_rs::{
+    CalendarBuilder, CalendarResult, WeekStart,
+    mock::{self, KEY_PAGE_DOWN, KEY_RETURN, KEY_RIGHT, MockEvent},
+};
+
+/// Clicks a day of January 2024 in a grid whose rows start on Sunday. The
+/// 1st is a Monday, so day `n` is in cell `n` counting from 0.
+fn click_day(day: i16) -> Vec<MockEvent> {
+    let (x, y) = (16 + 18 + (day % 7) * 36, 84 + 18 + (day / 7) * 36);
+    MockEvent::click((x, y)).to_vec()
+}
+
+/// Starting on 20 January 2024, the result after `events`.
+fn show(builder: CalendarBuilder, events: Vec<MockEvent>) -> CalendarResult {
+    mock::script(events.into_iter().chain([MockEvent::Disconnect]));
+    builder
+        .week_start(WeekStart::Sunday)
+        .year(2024)
+        .month(1)
+        .day(20)
+        .range(true)
+        .show()
+        .unwrap()
+}
+
+fn formatted(result: CalendarResult) -> String {
+    match result {
+        CalendarResult::SelectedRange {
+            formatted, ..
+        } => formatted,
+        result => panic!("{result:?}"),
+    }
+}
+
+#[test]
+fn two_clicks_pick_the_ends_in_either_order() {
+    let mut events = click_day(10);
+    events.extend(click_day(5));
+    events.push(MockEvent::key(KEY_RETURN));
+    let result = show(zenity_rs::calendar(), events);
+    match result {
+        CalendarResult::SelectedRange {
+            start,
+            end,
+            formatted,
+        } => {
+            assert_eq!(start, (2024, 1, 5));
+            assert_eq!(end, (2024, 1, 10));
+            assert_eq!(formatted, "2024-01-05..2024-01-10");
+        }
+        result => panic!("{result:?}"),
+    }
+}
+
+#[test]
+fn a_third_click_starts_again() {
+    let mut events = Vec::new();
+    for day in [10, 12, 15, 17] {
+        events.extend(click_day(day));
+    }
+    events.push(MockEvent::key(KEY_RETURN));
+    assert_eq!(
+        formatted(show(zenity_rs::calendar(), events)),
+        "2024-01-15..2024-01-17"
+    );
+}
+
+#[test]
+fn enter_twice_picks_a_range_from_the_keyboard() {
+    let mut events = vec![MockEvent::key(KEY_RETURN)];
+    events.extend(vec![MockEvent::key(KEY_RIGHT); 3]);
+    events.push(MockEvent::key(KEY_RETURN));
+    assert_eq!(
+        formatted(show(zenity_rs::calendar(), events)),
+        "2024-01-20..2024-01-23"
+    );
+}
+
+#[test]
+fn ranges_cross_months() {
+    let events = vec![
+        MockEvent::key(KEY_RETURN),
+        MockEvent::key(KEY_PAGE_DOWN),
+        MockEvent::key(KEY_RETURN),
+    ];
+    assert_eq!(
+        formatted(show(zenity_rs::calendar(), events)),
+        "2024-01-20..2024-02-20"
+    );
+}
+
+#[test]
+fn both_ends_use_the_date_format() {
+    let mut events = click_day(5);
+    events.extend(click_day(10));
+    events.push(MockEvent::key(KEY_RETURN));
+    let builder = zenity_rs::calendar().date_format("%d %b");
+    assert_eq!(formatted(show(builder, events)), "05 Jan..10 Jan");
+}
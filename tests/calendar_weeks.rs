@@ -0,0 +1,105 @@
+//! The calendar's week number column and first day of the week.
+//!
+//! Needs the scripted backend: `cargo test --test calendar_weeks --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "calendar"))]
+
+use zenity_rs::{
+    CalendarResult, WeekStart,
+    mock::{self, KEY_LEFT, KEY_RETURN, KEY_UP, MockEvent},
+};
+
+/// The centre of the first row's cell in `column`, with the 28px week
+/// number column before it or not.
+const fn first_row(column: i16, week_numbers: bool) -> (i16, i16) {
+    let left = if week_numbers { 16 + 28 } else { 16 };
+    (left + 18 + column * 36, 84 + 18)
+}
+
+/// The date accepted after clicking `at` on a calendar starting on
+/// 20 January 2024, a month whose first day is a Monday.
+fn click(week_start: WeekStart, week_numbers: bool, at: (i16, i16)) -> (u32, u32, u32) {
+    mock::script(
+        MockEvent::click(at)
+            .into_iter()
+            .chain([MockEvent::key(KEY_RETURN), MockEvent::Disconnect]),
+    );
+    let result = zenity_rs::calendar()
+        .year(2024)
+        .month(1)
+        .day(20)
+        .week_start(week_start)
+        .week_numbers(week_numbers)
+        .show()
+        .unwrap();
+    match result {
+        CalendarResult::Selected {
+            year,
+            month,
+            day,
+            ..
+        } => (year, month, day),
+        result => panic!("{result:?}"),
+    }
+}
+
+#[test]
+fn monday_first_weeks_start_with_the_month() {
+    assert_eq!(
+        click(WeekStart::Monday, false, first_row(0, false)),
+        (2024, 1, 1)
+    );
+    // Sunday first leaves the 1st in the second column, after an empty cell
+    assert_eq!(
+        click(WeekStart::Sunday, false, first_row(0, false)),
+        (2024, 1, 20)
+    );
+    assert_eq!(
+        click(WeekStart::Sunday, false, first_row(1, false)),
+        (2024, 1, 1)
+    );
+}
+
+#[test]
+fn week_numbers_push_the_days_right() {
+    assert_eq!(
+        click(WeekStart::Monday, true, first_row(0, true)),
+        (2024, 1, 1)
+    );
+    assert_eq!(
+        click(WeekStart::Monday, true, first_row(6, true)),
+        (2024, 1, 7)
+    );
+    // Clicking the week number itself picks nothing
+    assert_eq!(click(WeekStart::Monday, true, (30, 102)), (2024, 1, 20));
+}
+
+#[test]
+fn weeks_start_at_year_one() {
+    // There is no year 0, so the calendar opens on year 1 and stops there
+    mock::script([
+        MockEvent::key(KEY_LEFT),
+        MockEvent::key(KEY_UP),
+        MockEvent::key(KEY_RETURN),
+        MockEvent::Disconnect,
+    ]);
+    let result = zenity_rs::calendar()
+        .year(0)
+        .month(1)
+        .day(1)
+        .week_numbers(true)
+        .show()
+        .unwrap();
+    assert!(
+        matches!(
+            result,
+            CalendarResult::Selected {
+                year: 1,
+                month: 1,
+                day: 1,
+                ..
+            }
+        ),
+        "{result:?}"
+    );
+}
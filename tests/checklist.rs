@@ -0,0 +1,40 @@
+//! Toggles in checklist and radiolist rows.
+
+#![cfg(feature = "list")]
+
+use zenity_rs::ListMode;
+
+#[test]
+fn checkboxes_flip_independently() {
+    let mut checked = [false, true, false];
+    ListMode::Checklist.toggle(&mut checked, 0);
+    ListMode::Checklist.toggle(&mut checked, 1);
+    assert_eq!(checked, [true, false, false]);
+    ListMode::Checklist.toggle(&mut checked, 2);
+    assert_eq!(checked, [true, false, true]);
+    // Out of range rows are ignored
+    ListMode::Checklist.toggle(&mut checked, 3);
+    assert_eq!(checked, [true, false, true]);
+}
+
+#[test]
+fn radio_buttons_are_exclusive() {
+    let mut checked = [true, false, true];
+    ListMode::Radiolist.toggle(&mut checked, 1);
+    assert_eq!(checked, [false, true, false]);
+    // Activating the checked one keeps it checked
+    ListMode::Radiolist.toggle(&mut checked, 1);
+    assert_eq!(checked, [false, true, false]);
+}
+
+#[test]
+fn only_the_toggle_cell_toggles() {
+    for mode in [ListMode::Checklist, ListMode::Radiolist] {
+        assert!(mode.hits_toggle(0, 40));
+        assert!(mode.hits_toggle(39, 40));
+        assert!(!mode.hits_toggle(40, 40));
+        assert!(!mode.hits_toggle(-1, 40));
+    }
+    assert!(!ListMode::Single.hits_toggle(10, 40));
+    assert!(!ListMode::Multiple.hits_toggle(10, 40));
+}
@@ -0,0 +1,48 @@
+//! Dialogs shown with colors made at runtime rather than a built-in theme.
+//!
+//! Needs the scripted backend: `cargo test --test colors --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "message", feature = "scale"))]
+
+use zenity_rs::{
+    Colors, DialogResult, ScaleResult, THEME_DARK,
+    mock::{self, KEY_RETURN, MockEvent},
+    rgb,
+};
+
+fn press_return() {
+    mock::script([MockEvent::key(KEY_RETURN), MockEvent::Disconnect]);
+}
+
+fn brand() -> Colors {
+    Colors {
+        progress_fill: rgb(200, 60, 120),
+        button: rgb(200, 60, 120),
+        ..THEME_DARK
+    }
+}
+
+#[test]
+fn owned_colors_are_accepted() {
+    press_return();
+    let result = zenity_rs::question("Continue?")
+        .colors(brand())
+        .show()
+        .unwrap();
+    assert_eq!(result, DialogResult::Button(0));
+}
+
+#[test]
+// Passing references is what's being tested
+#[allow(clippy::needless_borrows_for_generic_args)]
+fn borrowed_colors_need_not_be_static() {
+    let colors = brand();
+    press_return();
+    let result = zenity_rs::scale().value(42).colors(&colors).show().unwrap();
+    assert!(matches!(result, ScaleResult::Value(42)), "{result:?}");
+
+    // The built-in themes still work as before
+    press_return();
+    let result = zenity_rs::scale().colors(&THEME_DARK).show().unwrap();
+    assert!(matches!(result, ScaleResult::Value(0)), "{result:?}");
+}
@@ -0,0 +1,215 @@
+//! End-to-end tests that run the real binary under a headless sway.
+//!
+//! Enabled with `cargo test --features compositor-tests`; see README.md.
+
+#![cfg(feature = "compositor-tests")]
+
+mod harness;
+
+use harness::{Compositor, assert_matches_fixture};
+
+#[test]
+fn info_return_accepts() {
+    let sway = Compositor::start();
+    let mut dialog = sway.run(&["--info", "--text=Operation completed"]);
+    dialog.wait_mapped();
+    assert_matches_fixture("info", &sway.screenshot());
+
+    let out = dialog.key("Return").finish();
+    assert_eq!(out.code, Some(0), "{out:?}");
+}
+
+#[test]
+fn question_escape_closes() {
+    let sway = Compositor::start();
+    let mut dialog = sway.run(&["--question", "--text=Continue?"]);
+    dialog.wait_mapped();
+    assert_matches_fixture("question", &sway.screenshot());
+
+    let out = dialog.key("Escape").finish();
+    assert_eq!(out.code, Some(1), "{out:?}");
+}
+
+#[test]
+fn extra_button_prints_its_label() {
+    let sway = Compositor::start();
+    let mut dialog = sway.run(&[
+        "--question",
+        "--switch",
+        "--extra-button=Later",
+        "--text=Update now?",
+    ]);
+    dialog.wait_mapped();
+
+    let out = dialog.key("Return").finish();
+    assert_eq!(out.code, Some(0), "{out:?}");
+    assert_eq!(out.stdout, "Later\n");
+}
+
+#[test]
+fn entry_prints_typed_text() {
+    let sway = Compositor::start();
+    let mut dialog = sway.run(&["--entry", "--text=Name:"]);
+    dialog.wait_mapped();
+    assert_matches_fixture("entry", &sway.screenshot());
+
+    let out = dialog.type_text("hello world").key("Return").finish();
+    assert_eq!(out.code, Some(0), "{out:?}");
+    assert_eq!(out.stdout, "hello world\n");
+}
+
+#[test]
+fn password_with_username() {
+    let sway = Compositor::start();
+    let mut dialog = sway.run(&["--password", "--username"]);
+    dialog.wait_mapped();
+
+    let out = dialog
+        .type_text("me")
+        .key("Tab")
+        .type_text("secret")
+        .key("Return")
+        .finish();
+    assert_eq!(out.code, Some(0), "{out:?}");
+    assert_eq!(out.stdout, "me|secret\n");
+}
+
+#[test]
+fn progress_auto_closes_at_100() {
+    let sway = Compositor::start();
+    let mut dialog = sway.run(&["--progress", "--text=Working", "--auto-close"]);
+    dialog.write_stdin("# Halfway\n50\n").wait_mapped();
+    assert_matches_fixture("progress", &sway.screenshot());
+
+    let out = dialog.write_stdin("100\n").finish();
+    assert_eq!(out.code, Some(0), "{out:?}");
+}
+
+/// Starts a progress dialog from a shell that reports a hangup and the
+/// dialog's exit code.
+const AUTO_KILL_SCRIPT: &str = r#"
+trap 'echo hangup' HUP
+"$0" --progress --pulsate "$@" &
+wait $!
+wait $!
+echo "exit $?"
+"#;
+
+#[test]
+fn progress_auto_kill_hangs_up_the_parent() {
+    let sway = Compositor::start();
+    let mut dialog = sway.run_script(AUTO_KILL_SCRIPT, &["--auto-kill"]);
+    dialog.wait_mapped();
+
+    let out = dialog.key("Escape").finish();
+    assert_eq!(out.stdout, "hangup\nexit 1\n", "{out:?}");
+}
+
+#[test]
+fn progress_cancel_leaves_the_parent_alone() {
+    let sway = Compositor::start();
+    let mut dialog = sway.run_script(AUTO_KILL_SCRIPT, &[]);
+    dialog.wait_mapped();
+
+    let out = dialog.key("Escape").finish();
+    assert_eq!(out.stdout, "exit 1\n", "{out:?}");
+}
+
+#[test]
+fn scale_prints_partial_values() {
+    let sway = Compositor::start();
+    let mut dialog = sway.run(&["--scale", "--value=5", "--print-partial"]);
+    dialog.close_stdin().wait_mapped();
+
+    let out = dialog
+        .key("Right")
+        .key("Right")
+        .key("Left")
+        .key("Return")
+        .finish();
+    assert_eq!(out.code, Some(0), "{out:?}");
+    assert_eq!(out.stdout, "6\n7\n6\n6\n");
+}
+
+#[test]
+fn list_prints_selected_row() {
+    let sway = Compositor::start();
+    let mut dialog = sway.run(&[
+        "--list",
+        "--column=Name",
+        "--column=Size",
+        "a",
+        "12K",
+        "b",
+        "40K",
+    ]);
+    dialog.close_stdin().wait_mapped();
+    assert_matches_fixture("list", &sway.screenshot());
+
+    let out = dialog.key("Down").key("Down").key("Return").finish();
+    assert_eq!(out.code, Some(0), "{out:?}");
+    assert_eq!(out.stdout, "b\n");
+}
+
+#[test]
+fn calendar_prints_initial_date() {
+    let sway = Compositor::start();
+    let mut dialog = sway.run(&["--calendar", "--year=2024", "--month=2", "--day=3"]);
+    dialog.wait_mapped();
+    assert_matches_fixture("calendar", &sway.screenshot());
+
+    let out = dialog.key("Return").finish();
+    assert_eq!(out.code, Some(0), "{out:?}");
+    assert_eq!(out.stdout, "2024-02-03\n");
+}
+
+#[test]
+fn scale_arrow_key_steps() {
+    let sway = Compositor::start();
+    let mut dialog = sway.run(&["--scale", "--value=10", "--step=5"]);
+    dialog.wait_mapped();
+    assert_matches_fixture("scale", &sway.screenshot());
+
+    let out = dialog.key("Right").key("Return").finish();
+    assert_eq!(out.code, Some(0), "{out:?}");
+    assert_eq!(out.stdout, "15\n");
+}
+
+#[test]
+fn forms_prints_fields() {
+    let sway = Compositor::start();
+    let mut dialog = sway.run(&["--forms", "--add-entry=First", "--add-entry=Last"]);
+    dialog.wait_mapped();
+    assert_matches_fixture("forms", &sway.screenshot());
+
+    let out = dialog
+        .type_text("Ada")
+        .key("Tab")
+        .type_text("Lovelace")
+        .key("Return")
+        .finish();
+    assert_eq!(out.code, Some(0), "{out:?}");
+    assert_eq!(out.stdout, "Ada|Lovelace\n");
+}
+
+#[test]
+fn text_info_escape_cancels() {
+    let sway = Compositor::start();
+    let mut dialog = sway.run(&["--text-info"]);
+    dialog.write_stdin("line one\nline two\n").close_stdin();
+    dialog.wait_mapped();
+    assert_matches_fixture("text-info", &sway.screenshot());
+
+    let out = dialog.key("Escape").finish();
+    assert_eq!(out.code, Some(1), "{out:?}");
+}
+
+#[test]
+fn file_selection_escape_cancels() {
+    let sway = Compositor::start();
+    let mut dialog = sway.run(&["--file-selection", "--filename=/"]);
+    dialog.wait_mapped();
+
+    let out = dialog.key("Escape").finish();
+    assert_eq!(out.code, Some(1), "{out:?}");
+}
@@ -0,0 +1,48 @@
+//! Command-line validation of `--default-cancel`.
+//!
+//! The runs have no display, so a dialog that gets past option parsing fails
+//! to open its window and exits with 100 and a display error.
+
+#![cfg(feature = "cli")]
+
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_zenity-rs"))
+        .args(args)
+        .env_remove("WAYLAND_DISPLAY")
+        .env_remove("DISPLAY")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn accepted_by_message_entry_and_calendar_dialogs() {
+    for dialog in [
+        "--question",
+        "--warning",
+        "--entry",
+        "--password",
+        "--calendar",
+    ] {
+        let output = run(&[dialog, "--default-cancel", "--strict", "--text=Delete?"]);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(output.status.code(), Some(100), "{dialog}: {stderr}");
+        assert!(!stderr.contains("--default-cancel"), "{dialog}: {stderr}");
+    }
+}
+
+#[test]
+fn rejected_elsewhere_with_strict() {
+    let output = run(&["--scale", "--default-cancel", "--strict"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(100));
+    assert!(
+        stderr.contains(
+            "--default-cancel is only valid with --info, --warning, --error, \
+             --question, --entry, --password or --calendar"
+        ),
+        "{stderr}"
+    );
+}
@@ -0,0 +1,66 @@
+//! Checks validation of --width and --height.
+//!
+//! The runs have no display, so valid sizes get as far as opening the window
+//! and fail there; invalid ones must be rejected before that.
+
+#![cfg(feature = "cli")]
+
+use std::process::{Command, Stdio};
+
+fn run(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_zenity-rs"))
+        .args(args)
+        .env_remove("WAYLAND_DISPLAY")
+        .env_remove("DISPLAY")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(100), "{args:?}");
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn rejects_non_positive_sizes() {
+    for arg in ["--width=0", "--width=-5", "--height=0", "--height=tall"] {
+        let stderr = run(&["--calendar", arg]);
+        assert!(
+            stderr.contains("expected a positive integer"),
+            "{arg}: {stderr}"
+        );
+    }
+}
+
+#[test]
+fn accepts_sizes_for_every_dialog() {
+    let dialogs: [&[&str]; 6] = [
+        &["--info"],
+        &["--entry"],
+        &["--list", "--column=A"],
+        &["--calendar"],
+        &["--scale"],
+        &["--forms", "--add-entry=Name"],
+    ];
+    for dialog in dialogs {
+        // Smaller than any dialog's content; clamped rather than rejected
+        let stderr = run(&[dialog, &["--width=1", "--height=1", "--strict"]].concat());
+        assert!(
+            !stderr.contains("positive integer") && !stderr.contains("only valid with"),
+            "{dialog:?}: {stderr}"
+        );
+    }
+}
+
+#[test]
+fn text_fitting_flags_combine_with_width() {
+    for flags in [
+        &["--no-wrap"][..],
+        &["--ellipsize"],
+        &["--no-markup"],
+        &["--no-wrap", "--ellipsize", "--no-markup"],
+    ] {
+        let mut args = vec!["--info", "--text=Hello", "--width=200", "--strict"];
+        args.extend(flags);
+        let stderr = run(&args);
+        assert!(!stderr.contains("only valid with"), "{flags:?}: {stderr}");
+    }
+}
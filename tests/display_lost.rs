@@ -0,0 +1,66 @@
+//! A display connection that breaks mid-dialog ends the dialog with
+//! [`Error::DisplayLost`] instead of a panic or a spin.
+//!
+//! Needs the scripted backend: `cargo test --test display_lost --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "cli"))]
+
+use std::time::{Duration, Instant};
+
+use zenity_rs::{
+    Error, WindowEvent,
+    mock::{self, MockEvent},
+};
+
+/// Some ordinary traffic, then the connection drops.
+fn script_disconnect() {
+    mock::script([
+        MockEvent::Event(WindowEvent::RedrawRequested),
+        MockEvent::move_to((10, 10)),
+        MockEvent::Event(WindowEvent::RedrawRequested),
+        MockEvent::Disconnect,
+    ]);
+}
+
+fn assert_lost<T: std::fmt::Debug>(result: Result<T, Error>) {
+    match result {
+        Err(Error::DisplayLost) => {}
+        other => panic!("expected DisplayLost, got {other:?}"),
+    }
+}
+
+#[test]
+fn message_dialog_unwinds() {
+    script_disconnect();
+    assert_lost(zenity_rs::question("Continue?").show());
+}
+
+#[test]
+fn calendar_unwinds() {
+    script_disconnect();
+    assert_lost(zenity_rs::calendar().show());
+}
+
+#[test]
+fn calendar_with_timeout_unwinds() {
+    // A deadline makes the dialog poll instead of block
+    script_disconnect();
+    assert_lost(zenity_rs::calendar().timeout(30).show());
+}
+
+#[test]
+fn progress_unwinds_promptly() {
+    script_disconnect();
+    let start = Instant::now();
+    assert_lost(zenity_rs::progress().pulsate(true).show());
+    // Shutting down the stdin pump doesn't hold the dialog up
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn lost_display_message() {
+    assert_eq!(
+        Error::DisplayLost.to_string(),
+        "lost the connection to the display server"
+    );
+}
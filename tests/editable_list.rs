@@ -0,0 +1,40 @@
+//! Finding the cell under a double-click in an `--editable` list.
+
+#![cfg(feature = "list")]
+
+use zenity_rs::ListMode;
+
+#[test]
+fn plain_columns_start_at_the_row_edge() {
+    let widths = [100, 60];
+    let at = |x| ListMode::Single.column_at(x, 0, &widths, 16);
+    assert_eq!(at(-1), None);
+    assert_eq!(at(0), Some(0));
+    assert_eq!(at(99), Some(0));
+    // The gap between columns belongs to neither
+    assert_eq!(at(100), None);
+    assert_eq!(at(115), None);
+    assert_eq!(at(116), Some(1));
+    assert_eq!(at(175), Some(1));
+    assert_eq!(at(176), None);
+}
+
+#[test]
+fn toggle_columns_come_after_the_toggle_and_a_gap() {
+    let widths = [100];
+    for mode in [ListMode::Checklist, ListMode::Radiolist] {
+        let at = |x| mode.column_at(x, 34, &widths, 16);
+        assert_eq!(at(0), None);
+        assert_eq!(at(49), None);
+        assert_eq!(at(50), Some(0));
+        assert_eq!(at(149), Some(0));
+        assert_eq!(at(150), None);
+    }
+    // Multiple has no toggle, so only the toggle width offsets it
+    assert_eq!(ListMode::Multiple.column_at(34, 34, &widths, 16), Some(0));
+}
+
+#[test]
+fn no_columns_no_cell() {
+    assert_eq!(ListMode::Single.column_at(10, 0, &[], 16), None);
+}
@@ -0,0 +1,73 @@
+//! Selecting, cutting, copying and pasting in the entry dialog.
+//!
+//! The mock window's clipboard only holds what the dialog copied itself.
+//! Needs the scripted backend: `cargo test --test entry_clipboard --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "entry"))]
+
+use zenity_rs::{
+    EntryResult, Modifiers,
+    mock::{self, KEY_END, KEY_HOME, KEY_RETURN, KEY_RIGHT, MockEvent},
+};
+
+fn ctrl(letter: char) -> MockEvent {
+    MockEvent::key_with(letter as u32, Modifiers::CTRL)
+}
+
+fn entered(builder: zenity_rs::EntryBuilder, events: Vec<MockEvent>) -> String {
+    let done = [MockEvent::key(KEY_RETURN), MockEvent::Disconnect];
+    mock::script(events.into_iter().chain(done));
+    match builder.show().unwrap() {
+        EntryResult::Text(text) => text,
+        result => panic!("{result:?}"),
+    }
+}
+
+#[test]
+fn copy_and_paste_at_the_cursor() {
+    let text = entered(
+        zenity_rs::entry().entry_text("token"),
+        vec![ctrl('a'), ctrl('c'), MockEvent::key(KEY_HOME), ctrl('v')],
+    );
+    assert_eq!(text, "tokentoken");
+}
+
+#[test]
+fn shift_selects_and_cut_removes() {
+    let text = entered(
+        zenity_rs::entry().entry_text("abcdef"),
+        vec![
+            MockEvent::key(KEY_HOME),
+            MockEvent::key_with(KEY_RIGHT, Modifiers::SHIFT),
+            MockEvent::key_with(KEY_RIGHT, Modifiers::SHIFT),
+            ctrl('x'),
+            MockEvent::key(KEY_END),
+            ctrl('v'),
+        ],
+    );
+    assert_eq!(text, "cdefab");
+}
+
+#[test]
+fn pasted_line_breaks_are_dropped() {
+    let text = entered(
+        zenity_rs::entry().entry_text("one\ntwo\r\n"),
+        vec![ctrl('a'), ctrl('x'), ctrl('v')],
+    );
+    assert_eq!(text, "onetwo");
+}
+
+#[test]
+fn passwords_are_not_copied() {
+    let text = entered(
+        zenity_rs::password().entry_text("secret"),
+        vec![
+            ctrl('a'),
+            ctrl('c'),
+            ctrl('x'),
+            MockEvent::key(KEY_END),
+            ctrl('v'),
+        ],
+    );
+    assert_eq!(text, "secret");
+}
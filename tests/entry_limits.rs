@@ -0,0 +1,67 @@
+//! Maximum length and character filters of the entry dialog.
+//!
+//! Needs the scripted backend: `cargo test --test entry_limits --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "entry"))]
+
+use zenity_rs::{
+    EntryBuilder, EntryResult, Modifiers,
+    mock::{self, KEY_END, KEY_RETURN, MockEvent},
+};
+
+fn entered(builder: EntryBuilder, events: Vec<MockEvent>) -> String {
+    let done = [MockEvent::key(KEY_RETURN), MockEvent::Disconnect];
+    mock::script(events.into_iter().chain(done));
+    match builder.show().unwrap() {
+        EntryResult::Text(text) => text,
+        result => panic!("{result:?}"),
+    }
+}
+
+#[test]
+fn typing_stops_at_the_maximum_length() {
+    let text = entered(
+        zenity_rs::entry().max_length(4),
+        MockEvent::type_text("ñandú!"),
+    );
+    assert_eq!(text, "ñand");
+
+    // Typing over a selection replaces it even at the limit
+    let mut events = MockEvent::type_text("abcd");
+    events.push(MockEvent::key_with('a' as u32, Modifiers::CTRL));
+    events.extend(MockEvent::type_text("xy"));
+    assert_eq!(entered(zenity_rs::entry().max_length(4), events), "xy");
+}
+
+#[test]
+fn pastes_are_cut_at_the_limit() {
+    let events = vec![
+        MockEvent::key_with('a' as u32, Modifiers::CTRL),
+        MockEvent::key_with('c' as u32, Modifiers::CTRL),
+        MockEvent::key(KEY_END),
+        MockEvent::key_with('v' as u32, Modifiers::CTRL),
+    ];
+    let text = entered(zenity_rs::entry().entry_text("abc").max_length(5), events);
+    assert_eq!(text, "abcab");
+}
+
+#[test]
+fn filters_drop_characters() {
+    let hex = zenity_rs::entry().allow_chars("0123456789abcdef");
+    assert_eq!(entered(hex, MockEvent::type_text("c0ffee-tea")), "c0ffeeea");
+
+    let no_spaces = zenity_rs::entry().deny_chars(" \t");
+    assert_eq!(entered(no_spaces, MockEvent::type_text("a b\tc")), "abc");
+
+    // Pasted text is filtered too
+    let events = vec![
+        MockEvent::key_with('a' as u32, Modifiers::CTRL),
+        MockEvent::key_with('c' as u32, Modifiers::CTRL),
+        MockEvent::key(KEY_END),
+        MockEvent::key_with('v' as u32, Modifiers::CTRL),
+    ];
+    let digits = zenity_rs::entry()
+        .entry_text("a1b2")
+        .allow_chars("0123456789");
+    assert_eq!(entered(digits, events), "a1b212");
+}
@@ -0,0 +1,95 @@
+//! Undo and redo in the entry dialog.
+//!
+//! Needs the scripted backend: `cargo test --test entry_undo --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "entry"))]
+
+use zenity_rs::{
+    EntryResult, Modifiers,
+    mock::{self, KEY_BACKSPACE, KEY_LEFT, KEY_RETURN, MockEvent},
+};
+
+fn undo() -> MockEvent {
+    MockEvent::key_with('z' as u32, Modifiers::CTRL)
+}
+
+fn entered(events: Vec<Vec<MockEvent>>) -> String {
+    let done = [MockEvent::key(KEY_RETURN), MockEvent::Disconnect];
+    mock::script(events.into_iter().flatten().chain(done));
+    match zenity_rs::entry().show().unwrap() {
+        EntryResult::Text(text) => text,
+        result => panic!("{result:?}"),
+    }
+}
+
+#[test]
+fn typing_is_undone_in_one_step() {
+    assert_eq!(
+        entered(vec![MockEvent::type_text("hello"), vec![undo()]]),
+        ""
+    );
+    // Moving the cursor starts a new step
+    assert_eq!(
+        entered(vec![
+            MockEvent::type_text("world"),
+            vec![MockEvent::key(KEY_LEFT)],
+            MockEvent::type_text("ly"),
+            vec![undo()],
+        ]),
+        "world"
+    );
+}
+
+#[test]
+fn deletions_are_steps_of_their_own() {
+    assert_eq!(
+        entered(vec![
+            MockEvent::type_text("abc"),
+            vec![MockEvent::key(KEY_BACKSPACE)],
+            MockEvent::type_text("d"),
+            vec![undo()],
+        ]),
+        "ab"
+    );
+    assert_eq!(
+        entered(vec![
+            MockEvent::type_text("abc"),
+            vec![MockEvent::key(KEY_BACKSPACE), undo()],
+            MockEvent::type_text("!"),
+        ]),
+        "abc!"
+    );
+}
+
+#[test]
+fn redo_restores_what_was_undone() {
+    let redos = [
+        MockEvent::key_with('Z' as u32, Modifiers::CTRL | Modifiers::SHIFT),
+        MockEvent::key_with('z' as u32, Modifiers::CTRL | Modifiers::SHIFT),
+        MockEvent::key_with('y' as u32, Modifiers::CTRL),
+    ];
+    for redo in redos {
+        assert_eq!(
+            entered(vec![
+                MockEvent::type_text("one"),
+                vec![
+                    MockEvent::key_with(KEY_BACKSPACE, Modifiers::CTRL),
+                    undo(),
+                    undo(),
+                    redo
+                ],
+            ]),
+            "one"
+        );
+    }
+    // A new edit drops what could have been redone
+    assert_eq!(
+        entered(vec![
+            MockEvent::type_text("one"),
+            vec![undo()],
+            MockEvent::type_text("two"),
+            vec![MockEvent::key_with('y' as u32, Modifiers::CTRL)],
+        ]),
+        "two"
+    );
+}
@@ -0,0 +1,96 @@
+//! Validating the text of the entry dialog.
+//!
+//! Needs the scripted backend: `cargo test --test entry_validation --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "entry"))]
+
+use zenity_rs::{
+    EntryBuilder, EntryResult,
+    mock::{self, KEY_BACKSPACE, KEY_ESCAPE, KEY_RETURN, MockEvent},
+};
+
+/// Types `text`, presses Enter and then Escape, which only closes the
+/// dialog if Enter didn't accept the text.
+fn submit(builder: EntryBuilder, text: &str) -> EntryResult {
+    let typed = MockEvent::type_text(text);
+    let done = [
+        MockEvent::key(KEY_RETURN),
+        MockEvent::key(KEY_ESCAPE),
+        MockEvent::Disconnect,
+    ];
+    mock::script(typed.into_iter().chain(done));
+    builder.show().unwrap()
+}
+
+fn even_length(text: &str) -> Result<(), String> {
+    if text.len().is_multiple_of(2) {
+        Ok(())
+    } else {
+        Err("Odd".to_string())
+    }
+}
+
+#[test]
+fn enter_ignores_invalid_text() {
+    let result = submit(zenity_rs::entry().validator(even_length), "abc");
+    assert!(matches!(result, EntryResult::Closed), "{result:?}");
+
+    let result = submit(zenity_rs::entry().validator(even_length), "abcd");
+    assert!(
+        matches!(&result, EntryResult::Text(text) if text == "abcd"),
+        "{result:?}"
+    );
+}
+
+#[test]
+fn patterns_match_the_whole_text() {
+    let pattern = || zenity_rs::entry().pattern("v[0-9]*.?");
+    assert!(matches!(submit(pattern(), "v12.x"), EntryResult::Text(_)));
+    assert!(matches!(submit(pattern(), "v12"), EntryResult::Closed));
+    assert!(matches!(submit(pattern(), "x12.x"), EntryResult::Closed));
+}
+
+#[test]
+fn every_validator_must_pass() {
+    let builder = || {
+        zenity_rs::entry()
+            .pattern("*[!0-9]*")
+            .validator(even_length)
+    };
+    assert!(matches!(submit(builder(), "ab"), EntryResult::Text(_)));
+    assert!(matches!(submit(builder(), "12"), EntryResult::Closed));
+    assert!(matches!(submit(builder(), "abc"), EntryResult::Closed));
+}
+
+#[test]
+fn fixing_the_text_accepts_it() {
+    let typed = MockEvent::type_text("abc");
+    let done = [
+        MockEvent::key(KEY_RETURN),
+        MockEvent::key(KEY_BACKSPACE),
+        MockEvent::key(KEY_RETURN),
+        MockEvent::Disconnect,
+    ];
+    mock::script(typed.into_iter().chain(done));
+    let result = zenity_rs::entry().validator(even_length).show().unwrap();
+    assert!(
+        matches!(&result, EntryResult::Text(text) if text == "ab"),
+        "{result:?}"
+    );
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn entry_int_and_entry_float_conflict() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_zenity-rs"))
+        .args(["--entry", "--entry-int", "--entry-float"])
+        .stdin(std::process::Stdio::null())
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(100));
+    assert!(
+        stderr.contains("--entry-int and --entry-float can't be used together"),
+        "{stderr}"
+    );
+}
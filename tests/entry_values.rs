@@ -0,0 +1,66 @@
+//! Suggested entry values: filtering, completion and the exact match a
+//! restricted entry needs.
+
+#![cfg(feature = "entry")]
+
+use zenity_rs::{EntryValues, Error};
+
+fn values(list: &[&str], ignore_case: bool) -> EntryValues {
+    EntryValues::new(list.iter().map(|v| v.to_string()).collect(), ignore_case)
+}
+
+fn matching<'a>(values: &'a EntryValues, text: &str) -> Vec<&'a str> {
+    values.matching(text).map(|(_, v)| v).collect()
+}
+
+#[test]
+fn typing_filters_by_prefix() {
+    let v = values(&["main", "master", "develop"], false);
+    assert_eq!(matching(&v, ""), ["main", "master", "develop"]);
+    assert_eq!(matching(&v, "ma"), ["main", "master"]);
+    assert_eq!(matching(&v, "mas"), ["master"]);
+    assert!(matching(&v, "x").is_empty());
+    let indices: Vec<usize> = v.matching("d").map(|(i, _)| i).collect();
+    assert_eq!(indices, [2]);
+}
+
+#[test]
+fn ambiguous_text_doesnt_complete() {
+    let v = values(&["main", "master", "develop"], false);
+    assert_eq!(v.completion("ma"), None);
+    assert_eq!(v.completion("mas").as_deref(), Some("ter"));
+    assert_eq!(v.completion("d").as_deref(), Some("evelop"));
+    // A prefix of a longer value is still ambiguous
+    let v = values(&["app", "apple"], false);
+    assert_eq!(v.completion("ap"), None);
+    assert_eq!(v.completion("appl").as_deref(), Some("e"));
+    // Nothing left to add
+    assert_eq!(values(&["main"], false).completion("main"), None);
+    assert_eq!(values(&["main"], false).completion("x"), None);
+}
+
+#[test]
+fn only_exact_text_is_accepted() {
+    let v = values(&["main", "master"], false);
+    assert_eq!(v.exact("main"), Some("main"));
+    assert_eq!(v.exact("mai"), None);
+    assert_eq!(v.exact("main "), None);
+    assert_eq!(v.exact("Main"), None);
+    assert_eq!(v.exact(""), None);
+}
+
+#[test]
+fn ignoring_case_folds_both_sides() {
+    let v = values(&["Main", "MASTER"], true);
+    assert_eq!(v.exact("main"), Some("Main"));
+    assert_eq!(v.exact("Master"), Some("MASTER"));
+    assert_eq!(matching(&v, "MA"), ["Main", "MASTER"]);
+    assert_eq!(v.completion("mas").as_deref(), Some("TER"));
+    assert!(matching(&values(&["Main"], false), "ma").is_empty());
+}
+
+#[test]
+fn restricting_without_values_is_an_error() {
+    let result = zenity_rs::entry().restrict_to_values(true).show();
+    assert!(matches!(result, Err(Error::InvalidConfig(_))), "{result:?}");
+}
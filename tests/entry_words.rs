@@ -0,0 +1,47 @@
+//! Moving and deleting by words in the entry dialog.
+//!
+//! Needs the scripted backend: `cargo test --test entry_words --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "entry"))]
+
+use zenity_rs::{
+    EntryResult, Modifiers, WindowEvent,
+    mock::{self, KEY_BACKSPACE, KEY_DELETE, KEY_LEFT, KEY_RETURN, KEY_RIGHT, MockEvent},
+};
+
+fn entered(text: &str, events: Vec<MockEvent>) -> String {
+    let done = [MockEvent::key(KEY_RETURN), MockEvent::Disconnect];
+    mock::script(events.into_iter().chain(done));
+    match zenity_rs::entry().entry_text(text).show().unwrap() {
+        EntryResult::Text(text) => text,
+        result => panic!("{result:?}"),
+    }
+}
+
+#[test]
+fn ctrl_backspace_deletes_the_previous_word() {
+    let text = entered(
+        "user@example.com  ",
+        vec![
+            MockEvent::key_with(KEY_BACKSPACE, Modifiers::CTRL),
+            MockEvent::key_with(KEY_BACKSPACE, Modifiers::CTRL),
+        ],
+    );
+    assert_eq!(text, "user@example");
+}
+
+#[test]
+fn ctrl_arrows_move_by_words() {
+    // The entry starts with the cursor after its text
+    let text = entered(
+        "grüße aus köln",
+        vec![
+            MockEvent::key_with(KEY_LEFT, Modifiers::CTRL),
+            MockEvent::key_with(KEY_LEFT, Modifiers::CTRL),
+            MockEvent::key_with(KEY_DELETE, Modifiers::CTRL),
+            MockEvent::key_with(KEY_RIGHT, Modifiers::CTRL),
+            MockEvent::Event(WindowEvent::TextInput('!')),
+        ],
+    );
+    assert_eq!(text, "grüße  köln!");
+}
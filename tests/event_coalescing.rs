@@ -0,0 +1,151 @@
+//! Coalescing of high-rate pointer motion in the backends' event queues.
+
+use zenity_rs::{CursorPos, Modifiers, MouseButton, PendingEvents, WindowEvent};
+
+fn motion(x: i16, y: i16) -> WindowEvent {
+    WindowEvent::CursorMove(CursorPos {
+        x,
+        y,
+    })
+}
+
+fn press() -> WindowEvent {
+    WindowEvent::ButtonPress(MouseButton::Left, Modifiers::empty())
+}
+
+fn release() -> WindowEvent {
+    WindowEvent::ButtonRelease(MouseButton::Left, Modifiers::empty())
+}
+
+/// Clicks on a 100x100 square at the origin, the way buttons track them:
+/// a press and a release while the pointer is over it.
+#[derive(Default)]
+struct ClickTarget {
+    hovered: bool,
+    pressed: bool,
+    clicks: usize,
+    events_seen: usize,
+}
+
+impl ClickTarget {
+    fn process(&mut self, event: &WindowEvent) {
+        self.events_seen += 1;
+        match event {
+            WindowEvent::CursorMove(pos) => {
+                self.hovered = (0..100).contains(&pos.x) && (0..100).contains(&pos.y);
+            }
+            WindowEvent::ButtonPress(MouseButton::Left, _) => self.pressed = self.hovered,
+            WindowEvent::ButtonRelease(MouseButton::Left, _) => {
+                if self.pressed && self.hovered {
+                    self.clicks += 1;
+                }
+                self.pressed = false;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn drain(queue: &mut PendingEvents) -> Vec<WindowEvent> {
+    std::iter::from_fn(|| queue.pop_front()).collect()
+}
+
+#[test]
+fn motion_flood_with_clicks() {
+    let mut queue = PendingEvents::new();
+    // Wander around outside, end up inside and click; wander off and click
+    // outside (no click); come back and click again
+    for i in 0..5000 {
+        queue.push_back(motion(200 + (i % 300) as i16, 300));
+    }
+    queue.push_back(motion(50, 50));
+    queue.push_back(press());
+    queue.push_back(release());
+    for i in 0..2500 {
+        queue.push_back(motion(150 + (i % 50) as i16, 20));
+    }
+    queue.push_back(press());
+    queue.push_back(release());
+    for i in 0..2500 {
+        queue.push_back(motion((i % 90) as i16, 10));
+    }
+    queue.push_back(press());
+    queue.push_back(release());
+
+    let events = drain(&mut queue);
+    let mut target = ClickTarget::default();
+    for event in &events {
+        target.process(event);
+    }
+
+    assert_eq!(target.clicks, 2);
+    // One motion per run between presses and releases
+    assert_eq!(target.events_seen, 9, "{events:?}");
+}
+
+#[test]
+fn keeps_the_latest_position() {
+    let mut queue = PendingEvents::new();
+    for i in 0..10_000 {
+        queue.push_back(motion(i as i16 % 1000, 7));
+    }
+    assert_eq!(queue.len(), 1);
+    assert!(matches!(
+        queue.pop_front(),
+        Some(WindowEvent::CursorMove(CursorPos {
+            x: 999,
+            y: 7
+        }))
+    ));
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn never_merges_across_other_events() {
+    let mut queue = PendingEvents::new();
+    queue.push_back(motion(1, 1));
+    queue.push_back(motion(2, 2));
+    queue.push_back(press());
+    queue.push_back(motion(3, 3));
+    queue.push_back(release());
+    queue.push_back(motion(4, 4));
+    queue.push_back(motion(5, 5));
+
+    let events = drain(&mut queue);
+    let positions: Vec<_> = events
+        .iter()
+        .map(|event| {
+            match event {
+                WindowEvent::CursorMove(pos) => format!("move {},{}", pos.x, pos.y),
+                WindowEvent::ButtonPress(..) => "press".to_string(),
+                WindowEvent::ButtonRelease(..) => "release".to_string(),
+                other => format!("{other:?}"),
+            }
+        })
+        .collect();
+    assert_eq!(
+        positions,
+        ["move 2,2", "press", "move 3,3", "release", "move 5,5"]
+    );
+}
+
+#[test]
+fn pending_motion_reports_where_the_pointer_ends_up() {
+    let mut queue = PendingEvents::new();
+    assert!(queue.pending_motion().is_none());
+
+    queue.push_back(motion(10, 20));
+    queue.push_back(press());
+    let pos = queue.pending_motion().unwrap();
+    assert_eq!((pos.x, pos.y), (10, 20));
+
+    queue.push_back(WindowEvent::CursorLeave);
+    assert!(queue.pending_motion().is_none());
+
+    queue.push_back(WindowEvent::CursorEnter(CursorPos {
+        x: 3,
+        y: 4,
+    }));
+    let pos = queue.pending_motion().unwrap();
+    assert_eq!((pos.x, pos.y), (3, 4));
+}
@@ -0,0 +1,76 @@
+//! Checks that each dialog feature exposes its builder on its own.
+//!
+//! Run for one feature at a time like CI does, e.g.
+//! `cargo test --test features --no-default-features --features x11,list`.
+
+#[cfg(feature = "message")]
+#[test]
+fn message() {
+    let _ = zenity_rs::message().text("hi").icon(zenity_rs::Icon::Info);
+    let _ = zenity_rs::question("Continue?");
+}
+
+#[cfg(feature = "entry")]
+#[test]
+fn entry() {
+    let _ = zenity_rs::entry().entry_text("default");
+    let _ = zenity_rs::password().username(true);
+}
+
+#[cfg(feature = "calendar")]
+#[test]
+fn calendar() {
+    let _ = zenity_rs::calendar().year(2024).month(2).day(29);
+}
+
+#[cfg(feature = "files")]
+#[test]
+fn files() {
+    let _ = zenity_rs::file_select().add_filter(zenity_rs::FileFilter {
+        name: "Rust".to_string(),
+        patterns: vec!["*.rs".to_string()],
+    });
+}
+
+#[cfg(feature = "forms")]
+#[test]
+fn forms() {
+    let _ = zenity_rs::forms().add_entry("Name");
+}
+
+#[cfg(feature = "list")]
+#[test]
+fn list() {
+    let _ = zenity_rs::list().column("A").row(vec!["a".to_string()]);
+}
+
+#[cfg(feature = "progress")]
+#[test]
+fn progress() {
+    let _ = zenity_rs::progress().percentage(50).stall_timeout(0);
+}
+
+#[cfg(feature = "textinfo")]
+#[test]
+fn textinfo() {
+    let _ = zenity_rs::text_info().checkbox("I agree");
+}
+
+#[cfg(feature = "scale")]
+#[test]
+fn scale() {
+    let _ = zenity_rs::scale().min_value(0).max_value(10).value(5);
+}
+
+#[cfg(feature = "portal")]
+#[test]
+fn portal() {
+    let _ = zenity_rs::file_select().portal(true);
+}
+
+/// Geometry, themes and widgets don't depend on any dialog feature.
+#[test]
+fn shared_api() {
+    let _: zenity_rs::Geometry = "400x300".parse().unwrap();
+    let _ = zenity_rs::THEME_LIGHT;
+}
@@ -0,0 +1,71 @@
+//! Matches file names against `--file-filter` globs.
+
+#![cfg(feature = "files")]
+
+use zenity_rs::FileFilter;
+
+fn filter(name: &str, patterns: &[&str]) -> FileFilter {
+    FileFilter {
+        name: name.to_string(),
+        patterns: patterns.iter().map(|p| p.to_string()).collect(),
+    }
+}
+
+fn matches(pattern: &str, name: &str) -> bool {
+    filter("", &[pattern]).matches(name)
+}
+
+#[test]
+fn star_matches_any_run() {
+    assert!(matches("*", "anything.txt"));
+    assert!(matches("*", ""));
+    assert!(matches("*.png", "photo.png"));
+    assert!(matches("*.png", ".png"));
+    assert!(!matches("*.png", "photo.png.bak"));
+    assert!(matches("report*.txt", "report-2024.final.txt"));
+    assert!(matches("*a*b*", "xxaxxbxx"));
+    assert!(!matches("*a*b*", "xxbxxaxx"));
+    assert!(matches("**.rs", "main.rs"));
+}
+
+#[test]
+fn question_mark_matches_one_character() {
+    assert!(matches("img??.jpg", "img01.jpg"));
+    assert!(!matches("img??.jpg", "img1.jpg"));
+    assert!(matches("?", "é"));
+    assert!(!matches("?", ""));
+}
+
+#[test]
+fn sets_and_ranges() {
+    assert!(matches("file[0-9].txt", "file7.txt"));
+    assert!(!matches("file[0-9].txt", "filex.txt"));
+    assert!(matches("*.[ch]", "main.h"));
+    assert!(matches("*.[!ch]", "main.o"));
+    assert!(!matches("*.[!ch]", "main.c"));
+    assert!(matches("[]]", "]"));
+    // An unclosed set is a literal bracket
+    assert!(matches("a[b", "a[b"));
+    assert!(!matches("a[b", "ab"));
+}
+
+#[test]
+fn case_is_ignored() {
+    assert!(matches("*.JPG", "holiday.jpg"));
+    assert!(matches("*.jpg", "HOLIDAY.JPG"));
+    assert!(matches("[a-c]*", "Banana"));
+}
+
+#[test]
+fn any_pattern_matches() {
+    let images = filter("Images", &["*.png", "*.jpg", "*.webp"]);
+    assert!(images.matches("a.webp"));
+    assert!(images.matches("b.jpg"));
+    assert!(!images.matches("c.gif"));
+}
+
+#[test]
+fn unnamed_filters_are_labelled_by_their_patterns() {
+    assert_eq!(filter("Images", &["*.png", "*.jpg"]).label(), "Images");
+    assert_eq!(filter("", &["*.png", "*.jpg"]).label(), "*.png *.jpg");
+}
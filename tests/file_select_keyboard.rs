@@ -0,0 +1,183 @@
+//! Moving through the file list with the keyboard, typeahead, and typing a
+//! path into the location bar.
+//!
+//! Needs the scripted backend: `cargo test --test file_select_keyboard --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "files"))]
+
+mod harness;
+
+use std::path::Path;
+
+use harness::TempDir;
+use zenity_rs::{
+    FileSelectResult, Modifiers,
+    mock::{
+        self, KEY_A, KEY_BACKSPACE, KEY_DOWN, KEY_END, KEY_ESCAPE, KEY_L, KEY_PAGE_DOWN,
+        KEY_PAGE_UP, KEY_RETURN, KEY_TAB, KEY_UP, MockEvent,
+    },
+};
+
+/// Three files, and a folder holding one more.
+fn fixture(name: &str) -> TempDir {
+    let dir = TempDir::new(&format!("keys-{name}"));
+    std::fs::create_dir(dir.path().join("docs")).unwrap();
+    for name in ["apple.txt", "banana.txt", "cherry.txt", "docs/notes.txt"] {
+        std::fs::write(dir.path().join(name), "").unwrap();
+    }
+    dir
+}
+
+/// The name of the file picked after `events`, starting in `dir`.
+fn pick(dir: &Path, events: Vec<MockEvent>) -> String {
+    mock::script(events.into_iter().chain([MockEvent::Disconnect]));
+    let result = zenity_rs::file_select()
+        .filename(dir.to_str().unwrap())
+        .show()
+        .unwrap();
+    match result {
+        FileSelectResult::Selected(path) => {
+            path.file_name().unwrap().to_string_lossy().into_owned()
+        }
+        result => panic!("{result:?}"),
+    }
+}
+
+#[test]
+fn arrows_and_end_move_the_cursor() {
+    let dir = fixture("arrows");
+    // `..`, then the folder, then the files
+    let down = |n| vec![MockEvent::key(KEY_DOWN); n];
+    assert_eq!(
+        pick(
+            dir.path(),
+            [down(3), vec![MockEvent::key(KEY_RETURN)]].concat()
+        ),
+        "apple.txt"
+    );
+    assert_eq!(
+        pick(
+            dir.path(),
+            vec![MockEvent::key(KEY_END), MockEvent::key(KEY_RETURN)]
+        ),
+        "cherry.txt"
+    );
+    let events = vec![
+        MockEvent::key(KEY_END),
+        MockEvent::key(KEY_UP),
+        MockEvent::key(KEY_RETURN),
+    ];
+    assert_eq!(pick(dir.path(), events), "banana.txt");
+    // Enter opens the folder; Down then picks its file past `..`
+    let events = [
+        down(2),
+        vec![MockEvent::key(KEY_RETURN)],
+        down(2),
+        vec![MockEvent::key(KEY_RETURN)],
+    ]
+    .concat();
+    assert_eq!(pick(dir.path(), events), "notes.txt");
+}
+
+#[test]
+fn typing_jumps_to_a_name() {
+    let dir = fixture("typeahead");
+    let events = MockEvent::type_text("b")
+        .into_iter()
+        .chain([MockEvent::key(KEY_RETURN)])
+        .collect();
+    assert_eq!(pick(dir.path(), events), "banana.txt");
+    let events = MockEvent::type_text("CH")
+        .into_iter()
+        .chain([MockEvent::key(KEY_RETURN)])
+        .collect();
+    assert_eq!(pick(dir.path(), events), "cherry.txt");
+    // Nothing starts with "cx", so the cursor stays on cherry
+    let events = MockEvent::type_text("cx")
+        .into_iter()
+        .chain([MockEvent::key(KEY_RETURN)])
+        .collect();
+    assert_eq!(pick(dir.path(), events), "cherry.txt");
+}
+
+#[test]
+fn backspace_and_alt_up_go_to_the_parent() {
+    let dir = fixture("parent");
+    for up in [
+        MockEvent::key(KEY_BACKSPACE),
+        MockEvent::key_with(KEY_UP, Modifiers::ALT),
+    ] {
+        let events = [up]
+            .into_iter()
+            .chain(MockEvent::type_text("a"))
+            .chain([MockEvent::key(KEY_RETURN)])
+            .collect();
+        assert_eq!(pick(&dir.path().join("docs"), events), "apple.txt");
+    }
+}
+
+#[test]
+fn page_keys_move_by_a_viewport() {
+    let dir = fixture("pages");
+    for i in 0..30 {
+        std::fs::write(dir.path().join(format!("f{i:02}.txt")), "").unwrap();
+    }
+    // `..`, docs and the three fruit come before f00 to f29; eleven
+    // rows fit
+    for (keys, picked) in [
+        (vec![KEY_DOWN, KEY_PAGE_DOWN], "f06.txt"),
+        (vec![KEY_DOWN, KEY_PAGE_DOWN, KEY_PAGE_DOWN], "f17.txt"),
+        (vec![KEY_END, KEY_PAGE_UP], "f18.txt"),
+        (vec![KEY_END, KEY_PAGE_UP, KEY_PAGE_UP], "f07.txt"),
+    ] {
+        let events = keys
+            .iter()
+            .map(|&k| MockEvent::key(k))
+            .chain([MockEvent::key(KEY_RETURN)])
+            .collect();
+        assert_eq!(pick(dir.path(), events), picked, "{keys:x?}");
+    }
+}
+
+#[test]
+fn the_location_bar_completes_and_goes_to_paths() {
+    let dir = fixture("location");
+    let ctrl = |k| MockEvent::key_with(k, Modifiers::CTRL);
+    // Relative to the current folder, completing with Tab
+    let events = [ctrl(KEY_L)]
+        .into_iter()
+        .chain(MockEvent::type_text("d"))
+        .chain([MockEvent::key(KEY_TAB)])
+        .chain(MockEvent::type_text("n"))
+        .chain([
+            MockEvent::key(KEY_TAB),
+            MockEvent::key(KEY_RETURN),
+            MockEvent::key(KEY_RETURN),
+        ])
+        .collect();
+    assert_eq!(pick(dir.path(), events), "notes.txt");
+
+    // A whole absolute path, replacing the text
+    let other = fixture("location-other");
+    let events = [ctrl(KEY_L), ctrl(KEY_A)]
+        .into_iter()
+        .chain(MockEvent::type_text(
+            other.path().join("cherry.txt").to_str().unwrap(),
+        ))
+        .chain([MockEvent::key(KEY_RETURN), MockEvent::key(KEY_RETURN)])
+        .collect();
+    assert_eq!(pick(dir.path(), events), "cherry.txt");
+}
+
+#[test]
+fn a_missing_location_keeps_the_bar_open() {
+    let dir = fixture("missing");
+    let events = [MockEvent::key_with(KEY_L, Modifiers::CTRL)]
+        .into_iter()
+        .chain(MockEvent::type_text("zzz"))
+        .chain([MockEvent::key(KEY_RETURN), MockEvent::key(KEY_ESCAPE)])
+        .chain(MockEvent::type_text("b"))
+        .chain([MockEvent::key(KEY_RETURN)])
+        .collect();
+    assert_eq!(pick(dir.path(), events), "banana.txt");
+}
@@ -0,0 +1,113 @@
+//! Creating a folder from the save dialog's New Folder button.
+//!
+//! Needs the scripted backend: `cargo test --test file_select_new_folder --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "files"))]
+
+mod harness;
+
+use std::path::{Path, PathBuf};
+
+use harness::TempDir;
+use zenity_rs::{
+    FileSelectResult,
+    mock::{self, KEY_ESCAPE, KEY_RETURN, MockEvent},
+};
+
+/// Left of Save and Cancel, at the bottom right.
+const NEW_FOLDER: (i16, i16) = (480, 472);
+
+fn click_new_folder() -> Vec<MockEvent> {
+    MockEvent::click(NEW_FOLDER).to_vec()
+}
+
+/// The path chosen after `events`, in a dialog starting in `dir`.
+fn pick(dir: &Path, save: bool, events: Vec<Vec<MockEvent>>) -> PathBuf {
+    mock::script(events.into_iter().flatten().chain([MockEvent::Disconnect]));
+    let result = zenity_rs::file_select()
+        .save(save)
+        .filename(&format!("{}/", dir.display()))
+        .show()
+        .unwrap();
+    match result {
+        FileSelectResult::Selected(path) => path,
+        result => panic!("{result:?}"),
+    }
+}
+
+#[test]
+fn the_new_folder_is_created_and_opened() {
+    let dir = TempDir::new("mkdir-create");
+    let events = vec![
+        click_new_folder(),
+        MockEvent::type_text("reports"),
+        vec![MockEvent::key(KEY_RETURN)],
+        // The filename field has focus again
+        MockEvent::type_text("q3.txt"),
+        vec![MockEvent::key(KEY_RETURN)],
+    ];
+    assert_eq!(
+        pick(dir.path(), true, events),
+        dir.path().join("reports/q3.txt")
+    );
+    assert!(dir.path().join("reports").is_dir());
+}
+
+#[test]
+fn failures_keep_the_row_open_until_fixed_or_escaped() {
+    let dir = TempDir::new("mkdir-exists");
+    std::fs::create_dir(dir.path().join("reports")).unwrap();
+
+    // Escape gives up, back in the same folder
+    let events = vec![
+        click_new_folder(),
+        MockEvent::type_text("reports"),
+        vec![MockEvent::key(KEY_RETURN), MockEvent::key(KEY_ESCAPE)],
+        MockEvent::type_text("a.txt"),
+        vec![MockEvent::key(KEY_RETURN)],
+    ];
+    assert_eq!(pick(dir.path(), true, events), dir.path().join("a.txt"));
+
+    // Or the name can be changed and tried again
+    let events = vec![
+        click_new_folder(),
+        MockEvent::type_text("reports"),
+        vec![MockEvent::key(KEY_RETURN)],
+        MockEvent::type_text("2"),
+        vec![MockEvent::key(KEY_RETURN)],
+        MockEvent::type_text("b.txt"),
+        vec![MockEvent::key(KEY_RETURN)],
+    ];
+    assert_eq!(
+        pick(dir.path(), true, events),
+        dir.path().join("reports2/b.txt")
+    );
+
+    // Names with a slash aren't made
+    let events = vec![
+        click_new_folder(),
+        MockEvent::type_text("x/y"),
+        vec![MockEvent::key(KEY_RETURN), MockEvent::key(KEY_ESCAPE)],
+        MockEvent::type_text("c.txt"),
+        vec![MockEvent::key(KEY_RETURN)],
+    ];
+    assert_eq!(pick(dir.path(), true, events), dir.path().join("c.txt"));
+    assert!(!dir.path().join("x").exists());
+}
+
+#[test]
+fn open_mode_has_no_new_folder_button() {
+    let dir = TempDir::new("mkdir-open");
+    std::fs::write(dir.path().join("banana.txt"), "").unwrap();
+    // Typing jumps to the file rather than naming a folder
+    let events = vec![
+        click_new_folder(),
+        MockEvent::type_text("b"),
+        vec![MockEvent::key(KEY_RETURN)],
+    ];
+    assert_eq!(
+        pick(dir.path(), false, events),
+        dir.path().join("banana.txt")
+    );
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+}
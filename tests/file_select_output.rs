@@ -0,0 +1,183 @@
+//! Round-trips awkward file names through every file selection output format.
+
+#![cfg(feature = "files")]
+
+mod harness;
+
+use std::{
+    ffi::OsStr,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+};
+
+use harness::TempDir;
+use zenity_rs::{FileSelectResult, PathFormat};
+
+const NAMES: &[&str] = &[
+    "plain.txt",
+    "a|b.txt",
+    "line\nbreak.txt",
+    "tab\there.txt",
+    "quote\"back\\slash.txt",
+    "ünïcødé 日本語 🎉.txt",
+];
+
+/// Creates every file in [`NAMES`] in `dir`.
+fn create_files(dir: &TempDir) -> Vec<PathBuf> {
+    NAMES
+        .iter()
+        .map(|name| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, b"").unwrap();
+            path
+        })
+        .collect()
+}
+
+fn write(result: &FileSelectResult, format: PathFormat<'_>) -> Vec<u8> {
+    let mut out = Vec::new();
+    result.write_paths(&mut out, format).unwrap();
+    out
+}
+
+fn parse_separated(out: &[u8], separator: &str) -> Vec<PathBuf> {
+    let out = out.strip_suffix(b"\n").expect("trailing newline");
+    let text = std::str::from_utf8(out).unwrap();
+    text.split(separator).map(PathBuf::from).collect()
+}
+
+fn parse_null(out: &[u8]) -> Vec<PathBuf> {
+    let out = out.strip_suffix(b"\0").expect("trailing NUL");
+    out.split(|&b| b == 0)
+        .map(|p| PathBuf::from(OsStr::from_bytes(p)))
+        .collect()
+}
+
+/// Parses a single-line JSON array of strings.
+fn parse_json(out: &[u8]) -> Vec<PathBuf> {
+    let text = std::str::from_utf8(out).unwrap();
+    let text = text.strip_suffix('\n').expect("trailing newline");
+    assert!(!text.contains('\n'), "JSON output spans lines: {text:?}");
+    let mut chars = text.chars();
+    assert_eq!(chars.next(), Some('['));
+
+    let mut paths = Vec::new();
+    loop {
+        match chars.next() {
+            Some('"') => {}
+            Some(']') if paths.is_empty() => break,
+            other => panic!("expected string, got {other:?}"),
+        }
+        let mut s = String::new();
+        loop {
+            match chars.next().expect("unterminated string") {
+                '"' => break,
+                '\\' => {
+                    match chars.next().expect("unterminated escape") {
+                        'n' => s.push('\n'),
+                        'r' => s.push('\r'),
+                        't' => s.push('\t'),
+                        'u' => {
+                            let hex: String = chars.by_ref().take(4).collect();
+                            let code = u32::from_str_radix(&hex, 16).unwrap();
+                            s.push(char::from_u32(code).unwrap());
+                        }
+                        c => s.push(c),
+                    }
+                }
+                c => {
+                    assert!(c >= ' ', "unescaped control character {c:?}");
+                    s.push(c);
+                }
+            }
+        }
+        paths.push(PathBuf::from(s));
+        match chars.next() {
+            Some(',') => {}
+            Some(']') => break,
+            other => panic!("expected ',' or ']', got {other:?}"),
+        }
+    }
+    assert_eq!(chars.next(), None);
+    paths
+}
+
+#[test]
+fn separated_round_trips_with_custom_separator() {
+    let dir = TempDir::new("output-separated");
+    let paths = create_files(&dir);
+    let result = FileSelectResult::SelectedMultiple(paths.clone());
+
+    let out = write(&result, PathFormat::Separated("::"));
+    assert_eq!(parse_separated(&out, "::"), paths);
+}
+
+#[test]
+fn default_separator_is_ambiguous_for_pipe_names() {
+    let dir = TempDir::new("output-ambiguous");
+    let paths = create_files(&dir);
+    let result = FileSelectResult::SelectedMultiple(paths.clone());
+
+    let out = write(&result, PathFormat::Separated("|"));
+    assert_ne!(parse_separated(&out, "|"), paths);
+}
+
+#[test]
+fn null_round_trips() {
+    let dir = TempDir::new("output-null");
+    let paths = create_files(&dir);
+    let result = FileSelectResult::SelectedMultiple(paths.clone());
+
+    let out = write(&result, PathFormat::Null);
+    assert!(!out.ends_with(b"\n"));
+    assert_eq!(parse_null(&out), paths);
+    assert!(parse_null(&out).iter().all(|p| p.exists()));
+}
+
+#[test]
+fn null_preserves_non_utf8_names() {
+    let path = Path::new(OsStr::from_bytes(b"/tmp/invalid-\xff.txt")).to_path_buf();
+    let result = FileSelectResult::Selected(path.clone());
+
+    let out = write(&result, PathFormat::Null);
+    assert_eq!(parse_null(&out), vec![path]);
+}
+
+#[test]
+fn json_round_trips() {
+    let dir = TempDir::new("output-json");
+    let paths = create_files(&dir);
+    let result = FileSelectResult::SelectedMultiple(paths.clone());
+
+    let out = write(&result, PathFormat::Json);
+    assert_eq!(parse_json(&out), paths);
+}
+
+#[test]
+fn single_selection_in_every_format() {
+    let dir = TempDir::new("output-single");
+    let path = create_files(&dir).remove(1);
+    let result = FileSelectResult::Selected(path.clone());
+
+    assert_eq!(
+        parse_separated(&write(&result, PathFormat::Separated("::")), "::"),
+        vec![path.clone()]
+    );
+    assert_eq!(
+        parse_null(&write(&result, PathFormat::Null)),
+        vec![path.clone()]
+    );
+    assert_eq!(parse_json(&write(&result, PathFormat::Json)), vec![path]);
+}
+
+#[test]
+fn cancelled_writes_nothing() {
+    for format in [
+        PathFormat::Separated("|"),
+        PathFormat::Null,
+        PathFormat::Json,
+    ] {
+        assert!(write(&FileSelectResult::Cancelled, format).is_empty());
+        assert!(write(&FileSelectResult::Closed, format).is_empty());
+    }
+}
@@ -0,0 +1,306 @@
+//! File selection through the desktop portal's `FileChooser`, and falling
+//! back to the built-in dialog when there's no portal.
+//!
+//! Runs against a stand-in session bus playing the portal.
+
+#![cfg(feature = "portal")]
+
+mod harness;
+
+use std::{
+    io::{Read, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::PathBuf,
+    thread::JoinHandle,
+};
+
+use harness::TempDir;
+use zenity_rs::{Error, FileFilter, FileSelectResult, file_uri_path};
+
+const HANDLE: &str = "/org/freedesktop/portal/desktop/request/1_42/zenity_rs";
+
+#[test]
+fn file_uris_become_paths() {
+    let path = |uri| file_uri_path(uri).map(|p: PathBuf| p.to_string_lossy().into_owned());
+    assert_eq!(
+        path("file:///home/me/a.txt").as_deref(),
+        Some("/home/me/a.txt")
+    );
+    assert_eq!(
+        path("file:///tmp/two%20words").as_deref(),
+        Some("/tmp/two words")
+    );
+    assert_eq!(
+        path("file://localhost/etc/hosts").as_deref(),
+        Some("/etc/hosts")
+    );
+    assert_eq!(path("file:///caf%C3%A9").as_deref(), Some("/café"));
+    assert_eq!(path("file://server/share/a"), None);
+    assert_eq!(path("https://example.com/a"), None);
+}
+
+/// Marshals little-endian D-Bus values.
+#[derive(Default)]
+struct Buf(Vec<u8>);
+
+impl Buf {
+    fn align(&mut self, n: usize) {
+        self.0.resize(self.0.len().next_multiple_of(n), 0);
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.align(4);
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn string(&mut self, value: &str) {
+        self.u32(value.len() as u32);
+        self.0.extend_from_slice(value.as_bytes());
+        self.0.push(0);
+    }
+
+    fn signature(&mut self, value: &str) {
+        self.0.push(value.len() as u8);
+        self.0.extend_from_slice(value.as_bytes());
+        self.0.push(0);
+    }
+
+    /// An array of elements aligned to `alignment`, written by `elements`.
+    fn array(&mut self, alignment: usize, elements: impl FnOnce(&mut Self)) {
+        self.u32(0);
+        let len_pos = self.0.len() - 4;
+        self.align(alignment);
+        let start = self.0.len();
+        elements(self);
+        let len = (self.0.len() - start) as u32;
+        self.0[len_pos..len_pos + 4].copy_from_slice(&len.to_le_bytes());
+    }
+}
+
+/// A whole message of type `kind`, with `(code, signature, value)` header
+/// fields.
+fn message(
+    kind: u8,
+    fields: &[(u8, &str, &str)],
+    reply_serial: Option<u32>,
+    body: &[u8],
+) -> Vec<u8> {
+    let mut buf = Buf(vec![b'l', kind, 0, 1]);
+    buf.u32(body.len() as u32);
+    buf.u32(1);
+    buf.array(8, |buf| {
+        for &(code, signature, value) in fields {
+            buf.align(8);
+            buf.0.push(code);
+            buf.signature(signature);
+            match signature {
+                "g" => buf.signature(value),
+                _ => buf.string(value),
+            }
+        }
+        if let Some(serial) = reply_serial {
+            buf.align(8);
+            buf.0.push(5);
+            buf.signature("u");
+            buf.u32(serial);
+        }
+    });
+    buf.align(8);
+    buf.0.extend_from_slice(body);
+    buf.0
+}
+
+/// Reads one message and returns its serial and raw bytes.
+fn read_message(stream: &mut UnixStream) -> (u32, Vec<u8>) {
+    let mut head = [0u8; 16];
+    stream.read_exact(&mut head).unwrap();
+    let u32_at = |i: usize| u32::from_le_bytes(head[i..i + 4].try_into().unwrap());
+    let (body_len, serial, fields_len) = (u32_at(4), u32_at(8), u32_at(12));
+    let rest = (16 + fields_len as usize).next_multiple_of(8) - 16 + body_len as usize;
+    let mut message = vec![0u8; rest];
+    stream.read_exact(&mut message).unwrap();
+    (serial, message)
+}
+
+/// How the stand-in portal answers the file chooser call.
+enum Answer {
+    /// A response code and the URIs chosen.
+    Response(u32, &'static [&'static str]),
+    /// An error reply, as when no portal is installed.
+    Error,
+}
+
+/// Accepts one client, checks its file chooser call contains each of
+/// `expected`, and answers it.
+fn serve(listener: UnixListener, expected: &'static [&'static str], answer: Answer) {
+    let (mut stream, _) = listener.accept().unwrap();
+    let mut line = Vec::new();
+    let mut byte = [0u8];
+    while !line.ends_with(b"\r\n") {
+        stream.read_exact(&mut byte).unwrap();
+        line.push(byte[0]);
+    }
+    stream.write_all(b"OK 0123456789abcdef\r\n").unwrap();
+    let mut begin = [0u8; 7];
+    stream.read_exact(&mut begin).unwrap();
+    assert_eq!(&begin, b"BEGIN\r\n");
+
+    // Hello, then AddMatch
+    for _ in 0..2 {
+        let (serial, _) = read_message(&mut stream);
+        stream
+            .write_all(&message(2, &[], Some(serial), &[]))
+            .unwrap();
+    }
+
+    let (serial, call) = read_message(&mut stream);
+    let text = String::from_utf8_lossy(&call);
+    for expected in expected {
+        assert!(text.contains(expected), "{expected:?} in {text:?}");
+    }
+    let (code, uris) = match answer {
+        Answer::Response(code, uris) => (code, uris),
+        Answer::Error => {
+            let fields = [(4, "s", "org.freedesktop.DBus.Error.ServiceUnknown")];
+            stream
+                .write_all(&message(3, &fields, Some(serial), &[]))
+                .unwrap();
+            return;
+        }
+    };
+    let mut handle = Buf::default();
+    handle.string(HANDLE);
+    let fields = [(8, "g", "o")];
+    stream
+        .write_all(&message(2, &fields, Some(serial), &handle.0))
+        .unwrap();
+
+    // The results hold the URIs, and the filter used, which is skipped
+    let mut body = Buf::default();
+    body.u32(code);
+    body.array(8, |body| {
+        body.align(8);
+        body.string("current_filter");
+        body.signature("(sa(us))");
+        body.align(8);
+        body.string("Images");
+        body.array(8, |body| {
+            body.align(8);
+            body.u32(0);
+            body.string("*.png");
+        });
+        body.align(8);
+        body.string("uris");
+        body.signature("as");
+        body.array(4, |body| uris.iter().for_each(|uri| body.string(uri)));
+    });
+    let fields = [
+        (1, "o", HANDLE),
+        (2, "s", "org.freedesktop.portal.Request"),
+        (3, "s", "Response"),
+        (8, "g", "ua{sv}"),
+    ];
+    stream
+        .write_all(&message(4, &fields, None, &body.0))
+        .unwrap();
+    // Wait for the client to hang up
+    let _ = stream.read(&mut [0u8; 1]);
+}
+
+/// Points the session bus at a new stand-in portal listening in `dir`.
+fn portal(
+    dir: &TempDir,
+    name: &str,
+    expected: &'static [&'static str],
+    answer: Answer,
+) -> JoinHandle<()> {
+    let socket = dir.path().join(name);
+    let listener = UnixListener::bind(&socket).unwrap();
+    // SAFETY: only the one test in this binary touches the environment
+    unsafe {
+        std::env::set_var(
+            "DBUS_SESSION_BUS_ADDRESS",
+            format!("unix:path={}", socket.display()),
+        );
+    }
+    std::thread::spawn(move || serve(listener, expected, answer))
+}
+
+#[test]
+fn the_portal_answers_in_place_of_the_dialog() {
+    let dir = TempDir::new("portal");
+    let images = || {
+        FileFilter {
+            name: "Images".to_string(),
+            patterns: vec!["*.png".to_string()],
+        }
+    };
+
+    let server = portal(
+        &dir,
+        "open",
+        &[
+            "OpenFile",
+            "Pick",
+            "multiple",
+            "directory",
+            "Images",
+            "*.png",
+        ],
+        Answer::Response(0, &["file:///tmp/a.png", "file:///tmp/b%20c.png"]),
+    );
+    let result = zenity_rs::file_select()
+        .portal(true)
+        .title("Pick")
+        .multiple(true)
+        .add_filter(images())
+        .show()
+        .unwrap();
+    server.join().unwrap();
+    match result {
+        FileSelectResult::SelectedMultiple(paths) => {
+            assert_eq!(
+                paths,
+                [PathBuf::from("/tmp/a.png"), PathBuf::from("/tmp/b c.png")]
+            )
+        }
+        result => panic!("{result:?}"),
+    }
+
+    let server = portal(
+        &dir,
+        "save",
+        &["SaveFile", "Save File", "current_name", "out.txt"],
+        Answer::Response(0, &["file:///tmp/out.txt"]),
+    );
+    let filename = dir.path().join("out.txt");
+    let result = zenity_rs::file_select()
+        .portal(true)
+        .save(true)
+        .filename(filename.to_str().unwrap())
+        .show()
+        .unwrap();
+    server.join().unwrap();
+    assert!(
+        matches!(&result, FileSelectResult::Selected(path) if path == &PathBuf::from("/tmp/out.txt")),
+        "{result:?}"
+    );
+
+    // The user cancelled
+    let server = portal(&dir, "cancel", &["OpenFile"], Answer::Response(1, &[]));
+    let result = zenity_rs::file_select().portal(true).show().unwrap();
+    server.join().unwrap();
+    assert!(matches!(result, FileSelectResult::Cancelled), "{result:?}");
+
+    // No portal: the built-in dialog opens instead, and with no display
+    // here it fails to, rather than with the portal's error
+    let server = portal(&dir, "missing", &["OpenFile"], Answer::Error);
+    #[cfg(feature = "mock-backend")]
+    zenity_rs::mock::script([zenity_rs::mock::MockEvent::Disconnect]);
+    let result = zenity_rs::file_select().portal(true).show();
+    server.join().unwrap();
+    assert!(
+        matches!(&result, Err(err) if !matches!(err, Error::DBus(_))),
+        "{result:?}"
+    );
+}
@@ -0,0 +1,49 @@
+//! Save mode returns the typed name in the folder being shown.
+//!
+//! Needs the scripted backend: `cargo test --test file_select_save --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "files"))]
+
+mod harness;
+
+use std::path::Path;
+
+use harness::TempDir;
+use zenity_rs::{
+    FileSelectResult,
+    mock::{self, KEY_RETURN, MockEvent},
+};
+
+/// Shows a save dialog for `filename` and presses Enter in the name field.
+fn save(filename: &Path, confirm_overwrite: bool) -> FileSelectResult {
+    mock::script([MockEvent::key(KEY_RETURN), MockEvent::Disconnect]);
+    zenity_rs::file_select()
+        .save(true)
+        .confirm_overwrite(confirm_overwrite)
+        .filename(filename.to_str().unwrap())
+        .show()
+        .unwrap()
+}
+
+#[test]
+fn new_name_is_returned_without_asking() {
+    let dir = TempDir::new("save-new");
+    let path = dir.path().join("report.txt");
+    let result = save(&path, true);
+    assert!(
+        matches!(&result, FileSelectResult::Selected(p) if *p == path),
+        "{result:?}"
+    );
+}
+
+#[test]
+fn existing_file_is_returned_without_confirm_overwrite() {
+    let dir = TempDir::new("save-existing");
+    let path = dir.path().join("report.txt");
+    std::fs::write(&path, b"old").unwrap();
+    let result = save(&path, false);
+    assert!(
+        matches!(&result, FileSelectResult::Selected(p) if *p == path),
+        "{result:?}"
+    );
+}
@@ -0,0 +1,107 @@
+//! Sorting the file list by name, size or modification time.
+//!
+//! Needs the scripted backend: `cargo test --test file_select_sort --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "files"))]
+
+mod harness;
+
+use std::{
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use harness::TempDir;
+use zenity_rs::{
+    FileSelectResult, FileSort,
+    mock::{self, MockEvent},
+};
+
+/// The column headers, and the file list's rows below them.
+const HEADER_Y: i16 = 105;
+const fn row_y(row: i16) -> i16 {
+    134 + row * 28
+}
+
+/// Files of different sizes and ages, and a folder, which sorts first.
+fn fixture(name: &str) -> TempDir {
+    let dir = TempDir::new(&format!("sort-{name}"));
+    std::fs::create_dir(dir.path().join("zdir")).unwrap();
+    let now = SystemTime::now();
+    for (name, size, days_old) in [
+        ("big.txt", 5000, 2),
+        ("mid.txt", 300, 9),
+        ("small.txt", 10, 5),
+    ] {
+        let file = std::fs::File::create(dir.path().join(name)).unwrap();
+        file.set_len(size).unwrap();
+        file.set_modified(now - Duration::from_secs(days_old * 86400))
+            .unwrap();
+    }
+    dir
+}
+
+fn clicks(x: i16, y: i16, count: usize) -> Vec<MockEvent> {
+    let mut events = vec![MockEvent::move_to((x, y))];
+    for _ in 0..count {
+        events.push(MockEvent::mouse_down());
+        events.push(MockEvent::mouse_up());
+    }
+    events
+}
+
+/// The file picked by double-clicking the first file, after `..` and
+/// the folder.
+fn first_file(dir: &Path, builder: zenity_rs::FileSelectBuilder, header_clicks: &[i16]) -> String {
+    let mut events = Vec::new();
+    for &x in header_clicks {
+        events.extend(clicks(x, HEADER_Y, 1));
+    }
+    events.extend(clicks(400, row_y(2), 2));
+    events.push(MockEvent::Disconnect);
+    mock::script(events);
+    let result = builder.filename(dir.to_str().unwrap()).show().unwrap();
+    match result {
+        FileSelectResult::Selected(path) => {
+            path.file_name().unwrap().to_string_lossy().into_owned()
+        }
+        result => panic!("{result:?}"),
+    }
+}
+
+#[test]
+fn builder_sorts() {
+    let dir = fixture("builder");
+    for (sort, descending, first) in [
+        (FileSort::Name, false, "big.txt"),
+        (FileSort::Name, true, "small.txt"),
+        (FileSort::Size, false, "small.txt"),
+        (FileSort::Size, true, "big.txt"),
+        (FileSort::Modified, false, "mid.txt"),
+        (FileSort::Modified, true, "big.txt"),
+    ] {
+        let builder = zenity_rs::file_select().sort_by(sort, descending);
+        assert_eq!(
+            first_file(dir.path(), builder, &[]),
+            first,
+            "{sort:?} {descending}"
+        );
+    }
+}
+
+#[test]
+fn header_clicks_sort_and_flip() {
+    let dir = fixture("headers");
+    // Name, then Size, then Modified
+    let (name, size, modified) = (250, 500, 600);
+    for (header_clicks, first) in [
+        (&[name][..], "small.txt"),
+        (&[size], "small.txt"),
+        (&[size, size], "big.txt"),
+        (&[modified], "mid.txt"),
+        (&[size, modified, modified], "big.txt"),
+    ] {
+        let picked = first_file(dir.path(), zenity_rs::file_select(), header_clicks);
+        assert_eq!(picked, first, "{header_clicks:?}");
+    }
+}
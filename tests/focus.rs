@@ -0,0 +1,85 @@
+//! Tab focus and keyboard activation of dialog buttons.
+//!
+//! Needs the scripted backend: `cargo test --test focus --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "message", feature = "calendar"))]
+
+use zenity_rs::{
+    CalendarResult, DialogResult, WindowEvent,
+    mock::{self, KEY_ISO_LEFT_TAB, KEY_RETURN, KEY_RIGHT, KEY_TAB, MockEvent},
+};
+
+fn answer(events: Vec<MockEvent>) -> DialogResult {
+    mock::script(events.into_iter().chain([MockEvent::Disconnect]));
+    zenity_rs::question("Delete?").show().unwrap()
+}
+
+#[test]
+fn tab_focuses_the_buttons_left_to_right() {
+    // Yes sits right of No
+    assert_eq!(
+        answer(vec![MockEvent::key(KEY_RETURN)]),
+        DialogResult::Button(0)
+    );
+    assert_eq!(
+        answer(vec![MockEvent::key(KEY_TAB), MockEvent::key(KEY_RETURN)]),
+        DialogResult::Button(1)
+    );
+    assert_eq!(
+        answer(vec![
+            MockEvent::key(KEY_TAB),
+            MockEvent::key(KEY_TAB),
+            MockEvent::key(KEY_RETURN)
+        ]),
+        DialogResult::Button(0)
+    );
+    assert_eq!(
+        answer(vec![
+            MockEvent::key(KEY_ISO_LEFT_TAB),
+            MockEvent::key(KEY_RETURN)
+        ]),
+        DialogResult::Button(0)
+    );
+}
+
+#[test]
+fn arrows_and_space_on_a_focused_button() {
+    assert_eq!(
+        answer(vec![
+            MockEvent::key(KEY_TAB),
+            MockEvent::key(KEY_RIGHT),
+            MockEvent::Event(WindowEvent::TextInput(' ')),
+        ]),
+        DialogResult::Button(0)
+    );
+    assert_eq!(
+        answer(vec![
+            MockEvent::key(KEY_TAB),
+            MockEvent::Event(WindowEvent::TextInput(' ')),
+        ]),
+        DialogResult::Button(1)
+    );
+}
+
+#[test]
+fn calendar_buttons_take_the_focus_from_the_grid() {
+    mock::script([
+        MockEvent::key(KEY_TAB),
+        MockEvent::key(KEY_TAB),
+        MockEvent::key(KEY_RETURN),
+        MockEvent::Disconnect,
+    ]);
+    let result = zenity_rs::calendar().show().unwrap();
+    assert!(matches!(result, CalendarResult::Cancelled), "{result:?}");
+
+    mock::script([
+        MockEvent::key(KEY_TAB),
+        MockEvent::key(KEY_RETURN),
+        MockEvent::Disconnect,
+    ]);
+    let result = zenity_rs::calendar().show().unwrap();
+    assert!(
+        matches!(result, CalendarResult::Selected { .. }),
+        "{result:?}"
+    );
+}
@@ -0,0 +1,39 @@
+//! Forms print every field, whatever its kind, in declaration order.
+//!
+//! Needs the scripted backend: `cargo test --test forms_fields --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "forms"))]
+
+use zenity_rs::{
+    FormsResult,
+    mock::{self, KEY_RETURN, MockEvent},
+};
+
+fn submit(builder: zenity_rs::FormsBuilder) -> FormsResult {
+    mock::script([MockEvent::key(KEY_RETURN), MockEvent::Disconnect]);
+    builder.show().unwrap()
+}
+
+#[test]
+fn combo_prints_its_first_item_until_another_is_picked() {
+    let result = submit(
+        zenity_rs::forms()
+            .add_entry("Task")
+            .add_calendar("Due date")
+            .add_combo("Priority", &["low", "medium", "high"])
+            .add_password("Token"),
+    );
+    assert!(
+        matches!(&result, FormsResult::Values(values) if values == &["", "", "low", ""]),
+        "{result:?}"
+    );
+}
+
+#[test]
+fn combo_without_items_prints_nothing() {
+    let result = submit(zenity_rs::forms().add_combo("Empty", &[] as &[&str]));
+    assert!(
+        matches!(&result, FormsResult::Values(values) if values == &[""]),
+        "{result:?}"
+    );
+}
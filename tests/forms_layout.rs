@@ -0,0 +1,43 @@
+//! The size of a forms window: clamped to a minimum width, and as tall as
+//! its labels once they've wrapped.
+//!
+//! Needs the scripted backend: `cargo test --test forms_layout --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "forms"))]
+
+use zenity_rs::{
+    FormsBuilder,
+    mock::{self, KEY_RETURN, MockEvent},
+};
+
+/// The size of the window `builder` shows.
+fn window_size(builder: FormsBuilder) -> (u16, u16) {
+    mock::script([MockEvent::key(KEY_RETURN), MockEvent::Disconnect]);
+    builder.show().unwrap();
+    mock::window_size().unwrap()
+}
+
+fn form() -> FormsBuilder {
+    zenity_rs::forms().add_entry("Name").add_password("Token")
+}
+
+#[test]
+fn a_narrow_width_is_clamped() {
+    let tiny = window_size(form().width(1));
+    assert_eq!(window_size(form().width(60)), tiny);
+    assert!(window_size(form().width(900)).0 > tiny.0);
+    // Room for the 120px label column, its 10px gutter and a 100px input,
+    // though narrower than the 420px a form gets by default
+    assert!((230..420).contains(&tiny.0), "{tiny:?}");
+}
+
+#[test]
+fn the_height_counts_wrapped_labels() {
+    let label = "word ".repeat(40);
+    let short = window_size(form());
+    let long = window_size(form().add_entry(label.trim_end()));
+    let one_more = window_size(form().add_entry("Notes"));
+    // The label wraps in its column rather than widening the window
+    assert_eq!(long.0, short.0);
+    assert!(long.1 > one_more.1, "{long:?} vs {one_more:?}");
+}
@@ -0,0 +1,143 @@
+//! Checks `--geometry` parsing for every combination of size, sign and
+//! omitted parts.
+
+use zenity_rs::{Geometry, Offset};
+
+fn parse(s: &str) -> Geometry {
+    s.parse()
+        .unwrap_or_else(|e| panic!("{s:?} should parse: {e}"))
+}
+
+fn error(s: &str) -> String {
+    match s.parse::<Geometry>() {
+        Ok(g) => panic!("{s:?} should not parse, got {g:?}"),
+        Err(e) => e.to_string(),
+    }
+}
+
+#[test]
+fn size_only() {
+    assert_eq!(
+        parse("400x300"),
+        Geometry {
+            width: Some(400),
+            height: Some(300),
+            position: None,
+        }
+    );
+    assert_eq!(parse("400X300"), parse("400x300"));
+    assert_eq!(
+        parse("400"),
+        Geometry {
+            width: Some(400),
+            height: None,
+            position: None,
+        }
+    );
+    assert_eq!(
+        parse("x300"),
+        Geometry {
+            width: None,
+            height: Some(300),
+            position: None,
+        }
+    );
+}
+
+#[test]
+fn position_only() {
+    let cases = [
+        ("+10+20", Offset::Start(10), Offset::Start(20)),
+        ("-10+20", Offset::End(10), Offset::Start(20)),
+        ("+10-20", Offset::Start(10), Offset::End(20)),
+        ("-10-20", Offset::End(10), Offset::End(20)),
+        ("-0-0", Offset::End(0), Offset::End(0)),
+        ("+0+0", Offset::Start(0), Offset::Start(0)),
+    ];
+    for (s, x, y) in cases {
+        assert_eq!(
+            parse(s),
+            Geometry {
+                width: None,
+                height: None,
+                position: Some((x, y)),
+            },
+            "{s}"
+        );
+    }
+}
+
+#[test]
+fn size_and_position() {
+    assert_eq!(
+        parse("400x300-10+20"),
+        Geometry {
+            width: Some(400),
+            height: Some(300),
+            position: Some((Offset::End(10), Offset::Start(20))),
+        }
+    );
+    assert_eq!(
+        parse("400+0-0"),
+        Geometry {
+            width: Some(400),
+            height: None,
+            position: Some((Offset::Start(0), Offset::End(0))),
+        }
+    );
+    assert_eq!(
+        parse("x300+5+5"),
+        Geometry {
+            width: None,
+            height: Some(300),
+            position: Some((Offset::Start(5), Offset::Start(5))),
+        }
+    );
+}
+
+#[test]
+fn leading_equals_is_accepted() {
+    assert_eq!(parse("=400x300"), parse("400x300"));
+    assert_eq!(parse("=-0-0"), parse("-0-0"));
+}
+
+#[test]
+fn rejects_malformed_input() {
+    for s in [
+        "",
+        "=",
+        "400x",
+        "0x300",
+        "400x0",
+        "x",
+        "400x300+1+2+3",
+        "400x300+",
+        "+10+",
+        "400x300+a+b",
+        "400x300 +10+20",
+        "4OO",
+    ] {
+        assert!(s.parse::<Geometry>().is_err(), "{s:?} should not parse");
+    }
+}
+
+#[test]
+fn suggests_likely_fixes() {
+    assert!(error("400*300").ends_with("(did you mean \"400x300\"?)"));
+    assert!(error("400×300").ends_with("(did you mean \"400x300\"?)"));
+    assert!(error("400, 300").ends_with("(did you mean \"400x300\"?)"));
+    assert!(error("+10").ends_with("(did you mean \"+10+0\"?)"));
+    assert!(error("400x300-5").ends_with("(did you mean \"400x300-5+0\"?)"));
+    // Nothing sensible to offer
+    assert!(!error("big").contains("did you mean"));
+    assert!(error("big").starts_with("invalid geometry \"big\""));
+}
+
+#[test]
+fn offsets_resolve_against_output() {
+    assert_eq!(Offset::Start(10).resolve(1920, 400), 10);
+    assert_eq!(Offset::End(0).resolve(1920, 400), 1520);
+    assert_eq!(Offset::End(20).resolve(1080, 300), 760);
+    // A window larger than the output ends up partly off screen
+    assert_eq!(Offset::End(0).resolve(800, 1000), -200);
+}
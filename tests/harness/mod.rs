@@ -0,0 +1,15 @@
+//! Helpers shared by the integration tests.
+//!
+//! [`TempDir`] is available to every test; the sway harness only with
+//! `--features compositor-tests`, since it drives the built binary.
+
+#![allow(dead_code)]
+
+#[cfg(feature = "compositor-tests")]
+mod sway;
+mod temp_dir;
+
+#[cfg(feature = "compositor-tests")]
+#[allow(unused_imports)]
+pub use sway::*;
+pub use temp_dir::TempDir;
@@ -0,0 +1,373 @@
+//! Running the real binary under a headless sway instance.
+//!
+//! Each [`Compositor`] owns a private `XDG_RUNTIME_DIR` with its own sway,
+//! so tests can run in parallel. Input is injected with `wtype` (virtual
+//! keyboard protocol) and screenshots are taken with `grim` (wlr screencopy).
+
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process::{Child, ChildStdin, Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use tiny_skia::Pixmap;
+
+use super::TempDir;
+
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+const MAP_TIMEOUT: Duration = Duration::from_secs(5);
+const EXIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Fixed output size so screenshots are comparable across runs.
+const OUTPUT_RESOLUTION: &str = "800x600";
+
+/// Per-channel difference below which two pixels are considered equal.
+const PIXEL_TOLERANCE: u8 = 8;
+/// Fraction of pixels allowed to differ beyond [`PIXEL_TOLERANCE`].
+const MAX_DIFF_RATIO: f64 = 0.005;
+
+/// A headless sway instance.
+pub struct Compositor {
+    sway: Child,
+    runtime_dir: TempDir,
+    wayland_display: String,
+    swaysock: PathBuf,
+}
+
+impl Compositor {
+    /// Starts sway with the headless backend and waits for its sockets.
+    pub fn start() -> Self {
+        let runtime_dir = TempDir::new("test");
+        set_private(runtime_dir.path());
+
+        let config = runtime_dir.path().join("sway.config");
+        std::fs::write(
+            &config,
+            format!(
+                "output * bg #202020 solid_color\n\
+                 output * resolution {OUTPUT_RESOLUTION}\n\
+                 seat * hide_cursor 1\n\
+                 default_border none\n\
+                 for_window [app_id=\".*\"] floating enable\n"
+            ),
+        )
+        .expect("write sway config");
+
+        let sway = Command::new("sway")
+            .arg("--config")
+            .arg(&config)
+            .env("XDG_RUNTIME_DIR", runtime_dir.path())
+            .env("WLR_BACKENDS", "headless")
+            .env("WLR_LIBINPUT_NO_DEVICES", "1")
+            .env("WLR_RENDERER", "pixman")
+            .env_remove("WAYLAND_DISPLAY")
+            .env_remove("DISPLAY")
+            .env_remove("SWAYSOCK")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("failed to start sway (is it installed?)");
+
+        let deadline = Instant::now() + STARTUP_TIMEOUT;
+        let (wayland_display, swaysock) = loop {
+            let found = find_sockets(runtime_dir.path());
+            if let (Some(display), Some(sock)) = found {
+                break (display, sock);
+            }
+            if Instant::now() > deadline {
+                panic!("sway did not create its sockets in {STARTUP_TIMEOUT:?}");
+            }
+            thread::sleep(Duration::from_millis(50));
+        };
+
+        Self {
+            sway,
+            runtime_dir,
+            wayland_display,
+            swaysock,
+        }
+    }
+
+    /// Spawns the dialog binary inside this compositor.
+    pub fn run(&self, args: &[&str]) -> Dialog<'_> {
+        let child = self
+            .command(env!("CARGO_BIN_EXE_zenity-rs"))
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("failed to start zenity-rs");
+        Dialog {
+            compositor: self,
+            child,
+        }
+    }
+
+    /// Runs `script` with `sh -c` inside this compositor, with the dialog
+    /// binary as `$0` and `args` as `$@`, so dialogs it starts are children
+    /// of the shell.
+    pub fn run_script(&self, script: &str, args: &[&str]) -> Dialog<'_> {
+        let child = self
+            .command("sh")
+            .args(["-c", script, env!("CARGO_BIN_EXE_zenity-rs")])
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("failed to start sh");
+        Dialog {
+            compositor: self,
+            child,
+        }
+    }
+
+    /// Captures the whole output.
+    pub fn screenshot(&self) -> Pixmap {
+        let path = self.runtime_dir.path().join("screenshot.png");
+        let status = self
+            .command("grim")
+            .arg(&path)
+            .status()
+            .expect("failed to run grim (is it installed?)");
+        assert!(status.success(), "grim failed: {status}");
+        Pixmap::load_png(&path).expect("decode screenshot")
+    }
+
+    /// Returns sway's layout tree as JSON.
+    pub fn tree(&self) -> String {
+        let output = self
+            .command("swaymsg")
+            .args(["-t", "get_tree"])
+            .output()
+            .expect("failed to run swaymsg");
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    }
+
+    fn command(&self, program: &str) -> Command {
+        let mut cmd = Command::new(program);
+        cmd.env("XDG_RUNTIME_DIR", self.runtime_dir.path())
+            .env("WAYLAND_DISPLAY", &self.wayland_display)
+            .env("SWAYSOCK", &self.swaysock)
+            .env_remove("DISPLAY")
+            .env_remove("GTK_THEME");
+        cmd
+    }
+}
+
+impl Drop for Compositor {
+    fn drop(&mut self) {
+        let _ = self.sway.kill();
+        let _ = self.sway.wait();
+    }
+}
+
+/// A running dialog process.
+pub struct Dialog<'a> {
+    compositor: &'a Compositor,
+    child: Child,
+}
+
+/// Exit status and output of a finished dialog.
+#[derive(Debug)]
+pub struct DialogOutput {
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl Dialog<'_> {
+    /// Waits until the dialog's window is mapped and has had time to draw.
+    /// The window may belong to the spawned process or, for a script, to
+    /// one of its children.
+    pub fn wait_mapped(&mut self) -> &mut Self {
+        let deadline = Instant::now() + MAP_TIMEOUT;
+        loop {
+            let tree = self.compositor.tree();
+            if self
+                .pids()
+                .iter()
+                .any(|pid| tree.contains(&format!("\"pid\": {pid},")))
+            {
+                break;
+            }
+            if let Ok(Some(status)) = self.child.try_wait() {
+                panic!("dialog exited before mapping a window: {status}");
+            }
+            if Instant::now() > deadline {
+                panic!("dialog window was not mapped in {MAP_TIMEOUT:?}");
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        // Give the first frame time to be committed
+        thread::sleep(Duration::from_millis(300));
+        self
+    }
+
+    /// Types text through the virtual keyboard.
+    pub fn type_text(&mut self, text: &str) -> &mut Self {
+        self.wtype(&[text]);
+        self
+    }
+
+    /// Presses and releases a key by its XKB name (e.g. `Return`, `Tab`).
+    pub fn key(&mut self, name: &str) -> &mut Self {
+        self.wtype(&["-k", name]);
+        self
+    }
+
+    /// Writes to the dialog's stdin.
+    pub fn write_stdin(&mut self, data: &str) -> &mut Self {
+        let stdin: &mut ChildStdin = self.child.stdin.as_mut().expect("stdin already closed");
+        stdin.write_all(data.as_bytes()).expect("write stdin");
+        stdin.flush().expect("flush stdin");
+        self
+    }
+
+    /// Closes the dialog's stdin (EOF).
+    pub fn close_stdin(&mut self) -> &mut Self {
+        drop(self.child.stdin.take());
+        self
+    }
+
+    /// Waits for the dialog to exit and collects its output.
+    pub fn finish(&mut self) -> DialogOutput {
+        self.close_stdin();
+        let deadline = Instant::now() + EXIT_TIMEOUT;
+        let status = loop {
+            if let Some(status) = self.child.try_wait().expect("wait for dialog") {
+                break status;
+            }
+            if Instant::now() > deadline {
+                let _ = self.child.kill();
+                panic!("dialog did not exit in {EXIT_TIMEOUT:?}");
+            }
+            thread::sleep(Duration::from_millis(20));
+        };
+
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+        if let Some(mut out) = self.child.stdout.take() {
+            let _ = out.read_to_string(&mut stdout);
+        }
+        if let Some(mut err) = self.child.stderr.take() {
+            let _ = err.read_to_string(&mut stderr);
+        }
+        DialogOutput {
+            code: status.code(),
+            stdout,
+            stderr,
+        }
+    }
+
+    /// The spawned process and its direct children.
+    fn pids(&self) -> Vec<u32> {
+        let pid = self.child.id();
+        let children =
+            std::fs::read_to_string(format!("/proc/{pid}/task/{pid}/children")).unwrap_or_default();
+        std::iter::once(pid)
+            .chain(children.split_whitespace().filter_map(|p| p.parse().ok()))
+            .collect()
+    }
+
+    fn wtype(&self, args: &[&str]) {
+        let status = self
+            .compositor
+            .command("wtype")
+            .args(args)
+            .status()
+            .expect("failed to run wtype (is it installed?)");
+        assert!(status.success(), "wtype {args:?} failed: {status}");
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+impl Drop for Dialog<'_> {
+    fn drop(&mut self) {
+        if let Ok(None) = self.child.try_wait() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
+
+/// Compares a screenshot against `tests/fixtures/<name>.png`.
+///
+/// With `ZENITY_RS_BLESS=1` set the screenshot is recorded as the fixture
+/// instead; without it, a missing fixture fails the test.
+pub fn assert_matches_fixture(name: &str, actual: &Pixmap) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let path = dir.join(format!("{name}.png"));
+    let bless = std::env::var_os("ZENITY_RS_BLESS").is_some_and(|v| v == "1");
+
+    if bless {
+        std::fs::create_dir_all(&dir).expect("create fixtures dir");
+        actual.save_png(&path).expect("write fixture");
+        eprintln!("recorded fixture {}", path.display());
+        return;
+    }
+    if !path.exists() {
+        let failed = dir.join(format!("{name}.actual.png"));
+        let _ = std::fs::create_dir_all(&dir);
+        let _ = actual.save_png(&failed);
+        panic!(
+            "no fixture {}; record it with ZENITY_RS_BLESS=1 (screenshot written to {})",
+            path.display(),
+            failed.display()
+        );
+    }
+
+    let expected = Pixmap::load_png(&path).expect("decode fixture");
+    assert_eq!(
+        (expected.width(), expected.height()),
+        (actual.width(), actual.height()),
+        "screenshot size differs from fixture {name}"
+    );
+
+    let differing = expected
+        .data()
+        .chunks_exact(4)
+        .zip(actual.data().chunks_exact(4))
+        .filter(|(e, a)| {
+            e.iter()
+                .zip(*a)
+                .any(|(e, a)| e.abs_diff(*a) > PIXEL_TOLERANCE)
+        })
+        .count();
+    let total = (expected.width() * expected.height()) as usize;
+    let ratio = differing as f64 / total as f64;
+    if ratio > MAX_DIFF_RATIO {
+        let failed = dir.join(format!("{name}.actual.png"));
+        let _ = actual.save_png(&failed);
+        panic!(
+            "screenshot differs from fixture {name} in {differing}/{total} pixels; \
+             actual image written to {}",
+            failed.display()
+        );
+    }
+}
+
+fn find_sockets(runtime_dir: &Path) -> (Option<String>, Option<PathBuf>) {
+    let mut display = None;
+    let mut swaysock = None;
+    if let Ok(entries) = std::fs::read_dir(runtime_dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with("wayland-") && !name.ends_with(".lock") {
+                display = Some(name);
+            } else if name.starts_with("sway-ipc.") && name.ends_with(".sock") {
+                swaysock = Some(entry.path());
+            }
+        }
+    }
+    (display, swaysock)
+}
+
+#[cfg(unix)]
+fn set_private(dir: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+    let _ = std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700));
+}
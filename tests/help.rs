@@ -0,0 +1,91 @@
+//! The help screens, generated from the same option table the parser checks
+//! options against.
+
+#![cfg(feature = "cli")]
+
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_zenity-rs"))
+        .args(args)
+        .env_remove("WAYLAND_DISPLAY")
+        .env_remove("DISPLAY")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap()
+}
+
+fn help(section: &str) -> String {
+    let output = run(&[section]);
+    assert_eq!(output.status.code(), Some(0), "{section}");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Whether `help` has an entry for `--option`.
+fn lists(help: &str, option: &str) -> bool {
+    help.lines().any(|line| {
+        line.split_whitespace()
+            .next()
+            .and_then(|name| name.split('=').next())
+            == Some(option)
+    })
+}
+
+#[test]
+fn every_parsed_option_is_documented() {
+    let help = help("--help-all");
+    let source = include_str!("../src/main.rs");
+    let mut count = 0;
+    for (_, rest) in source
+        .match_indices("Long(\"")
+        .map(|(i, _)| source.split_at(i + 6))
+    {
+        let name = &rest[..rest.find('"').unwrap()];
+        assert!(lists(&help, &format!("--{name}")), "--{name} is missing");
+        count += 1;
+    }
+    assert!(count > 50, "{count}");
+}
+
+#[test]
+fn dialog_help_lists_only_its_options() {
+    let list = help("--help-list");
+    assert!(lists(&list, "--list"));
+    assert!(lists(&list, "--column"));
+    assert!(lists(&list, "--multiple"));
+    assert!(!lists(&list, "--pulsate"));
+    assert!(!lists(&list, "--title"));
+
+    let general = help("--help-general");
+    assert!(lists(&general, "--title"));
+    assert!(!lists(&general, "--column"));
+
+    let files = help("--help-file-selection");
+    assert!(lists(&files, "--file-filter"));
+    assert!(!lists(&files, "--info"));
+}
+
+#[test]
+fn unknown_help_section_is_an_error() {
+    let output = run(&["--help-bogus"]);
+    assert_eq!(output.status.code(), Some(100));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--help-bogus"));
+}
+
+#[test]
+fn options_of_other_dialogs_name_the_right_ones() {
+    let output = run(&["--info", "--column=Name", "--strict"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(100));
+    assert!(
+        stderr.contains("--column is only valid with --list"),
+        "{stderr}"
+    );
+
+    let output = run(&["--scale", "--multiple", "--strict"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--multiple is only valid with --file-selection or --list"),
+        "{stderr}"
+    );
+}
@@ -0,0 +1,170 @@
+//! Tinting of monochrome custom icons to the theme's text color.
+
+mod harness;
+
+use std::path::{Path, PathBuf};
+
+use harness::TempDir;
+use tiny_skia::{Color, Paint, PathBuilder, Pixmap, Rect, Transform};
+use zenity_rs::{Canvas, Colors, Icon, Rgba, THEME_DARK, THEME_LIGHT};
+
+const SIZE: u32 = 32;
+
+/// Writes a PNG into `dir` and returns its path.
+fn write_png(dir: &TempDir, pixmap: &Pixmap) -> PathBuf {
+    let path = dir.path().join("icon.png");
+    pixmap.save_png(&path).unwrap();
+    path
+}
+
+/// A black disc on a transparent background, like a symbolic icon.
+fn symbolic_png(dir: &TempDir) -> PathBuf {
+    let mut pixmap = Pixmap::new(SIZE, SIZE).unwrap();
+    let mut paint = Paint::default();
+    paint.set_color(Color::BLACK);
+    paint.anti_alias = true;
+    let circle = PathBuilder::from_circle(16.0, 16.0, 12.0).unwrap();
+    pixmap.fill_path(
+        &circle,
+        &paint,
+        tiny_skia::FillRule::Winding,
+        Transform::identity(),
+        None,
+    );
+    write_png(dir, &pixmap)
+}
+
+/// Opaque red, green and blue stripes.
+fn colorful_png(dir: &TempDir) -> PathBuf {
+    let mut pixmap = Pixmap::new(SIZE, SIZE).unwrap();
+    for (i, color) in [
+        Color::from_rgba8(220, 40, 40, 255),
+        Color::from_rgba8(40, 200, 60, 255),
+        Color::from_rgba8(40, 80, 220, 255),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let mut paint = Paint::default();
+        paint.set_color(color);
+        let rect = Rect::from_xywh(0.0, i as f32 * 11.0, SIZE as f32, 11.0).unwrap();
+        pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+    }
+    write_png(dir, &pixmap)
+}
+
+fn load(path: &Path, colors: &Colors, recolor: bool) -> Canvas {
+    Icon::Custom(path.to_string_lossy().into_owned())
+        .load_image(SIZE, colors, recolor)
+        .expect("icon should load")
+}
+
+/// Opaque pixels of an image.
+fn opaque_pixels(image: &Canvas) -> Vec<Rgba> {
+    (0..image.height())
+        .flat_map(|y| (0..image.width()).map(move |x| (x, y)))
+        .filter_map(|(x, y)| image.pixel(x, y))
+        .filter(|c| c.a == 255)
+        .collect()
+}
+
+fn mean_luma(pixels: &[Rgba]) -> f32 {
+    let sum: f32 = pixels
+        .iter()
+        .map(|c| 0.299 * c.r as f32 + 0.587 * c.g as f32 + 0.114 * c.b as f32)
+        .sum();
+    sum / pixels.len() as f32
+}
+
+#[test]
+fn symbolic_icon_takes_the_theme_text_color() {
+    let dir = TempDir::new("icon-symbolic");
+    let path = symbolic_png(&dir);
+    for colors in [&THEME_DARK, &THEME_LIGHT] {
+        let image = load(&path, colors, true);
+        assert!(image.is_monochrome());
+        let pixels = opaque_pixels(&image);
+        assert!(!pixels.is_empty());
+        for c in pixels {
+            assert_eq!(c, colors.text);
+        }
+    }
+}
+
+#[test]
+fn symbolic_icon_is_light_on_dark_and_dark_on_light() {
+    let dir = TempDir::new("icon-contrast");
+    let path = symbolic_png(&dir);
+    let dark = mean_luma(&opaque_pixels(&load(&path, &THEME_DARK, true)));
+    let light = mean_luma(&opaque_pixels(&load(&path, &THEME_LIGHT, true)));
+    assert!(dark > 150.0, "{dark}");
+    assert!(light < 100.0, "{light}");
+}
+
+#[test]
+fn tinting_keeps_alpha() {
+    let dir = TempDir::new("icon-alpha");
+    let path = symbolic_png(&dir);
+    let original = load(&path, &THEME_DARK, false);
+    let tinted = load(&path, &THEME_DARK, true);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            assert_eq!(
+                original.pixel(x, y).unwrap().a,
+                tinted.pixel(x, y).unwrap().a,
+                "alpha at {x},{y}"
+            );
+        }
+    }
+}
+
+#[test]
+fn opting_out_keeps_the_original_color() {
+    let dir = TempDir::new("icon-opt-out");
+    let path = symbolic_png(&dir);
+    let image = load(&path, &THEME_DARK, false);
+    for c in opaque_pixels(&image) {
+        assert_eq!((c.r, c.g, c.b), (0, 0, 0));
+    }
+}
+
+#[test]
+fn colorful_icon_passes_through() {
+    let dir = TempDir::new("icon-colorful");
+    let path = colorful_png(&dir);
+    let original = load(&path, &THEME_DARK, false);
+    assert!(!original.is_monochrome());
+    for colors in [&THEME_DARK, &THEME_LIGHT] {
+        let image = load(&path, colors, true);
+        assert_eq!(opaque_pixels(&image), opaque_pixels(&original));
+    }
+}
+
+#[test]
+fn paths_are_told_apart_from_icon_names() {
+    for path in ["./logo.png", "/usr/share/pixmaps/app", "Logo.PNG"] {
+        assert!(Icon::Custom(path.into()).is_path(), "{path}");
+    }
+    for name in ["dialog-password", "org.gnome.Settings", "face-smile"] {
+        assert!(!Icon::Custom(name.into()).is_path(), "{name}");
+    }
+    assert!(!Icon::Info.is_path());
+}
+
+#[test]
+fn only_png_files_load() {
+    assert!(Icon::Info.load_image(SIZE, &THEME_DARK, true).is_none());
+    assert!(
+        Icon::Custom("dialog-password".into())
+            .load_image(SIZE, &THEME_DARK, true)
+            .is_none()
+    );
+    let dir = TempDir::new("icon-bogus");
+    let path = dir.path().join("bogus.png");
+    std::fs::write(&path, b"not a png").unwrap();
+    assert!(
+        Icon::Custom(path.to_string_lossy().into_owned())
+            .load_image(SIZE, &THEME_DARK, true)
+            .is_none()
+    );
+}
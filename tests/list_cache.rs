@@ -0,0 +1,67 @@
+//! Rendering long lists a screen at a time.
+//!
+//! Needs the scripted backend: `cargo test --test list_cache --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "list"))]
+
+use zenity_rs::{
+    ListResult,
+    mock::{self, KEY_DOWN, KEY_ESCAPE, KEY_PAGE_DOWN, KEY_PAGE_UP, KEY_RETURN, MockEvent},
+};
+
+fn long_list() -> zenity_rs::ListBuilder {
+    (0..2_000).fold(zenity_rs::list().column("N"), |builder, i| {
+        builder.row(vec![i.to_string()])
+    })
+}
+
+fn pick(events: Vec<MockEvent>) -> usize {
+    let builder = long_list();
+    mock::script(events.into_iter().chain([MockEvent::Disconnect]));
+    match builder.show().unwrap() {
+        ListResult::Selected(values) => values.concat().parse::<usize>().unwrap(),
+        result => panic!("{result:?}"),
+    }
+}
+
+/// The dialog's row cache hit rate after playing `events` and cancelling.
+fn hit_rate_after(events: impl IntoIterator<Item = MockEvent>) -> f64 {
+    let builder = long_list();
+    mock::script(events.into_iter().chain([MockEvent::key(KEY_ESCAPE)]));
+    assert!(matches!(builder.show().unwrap(), ListResult::Cancelled));
+    mock::list_cache_hit_rate().unwrap()
+}
+
+#[test]
+fn page_keys_move_through_a_long_list() {
+    // A page is the rows in sight
+    let page = pick(vec![
+        MockEvent::key(KEY_DOWN),
+        MockEvent::key(KEY_PAGE_DOWN),
+        MockEvent::key(KEY_RETURN),
+    ]);
+    assert!(page > 1);
+    let two = pick(vec![
+        MockEvent::key(KEY_DOWN),
+        MockEvent::key(KEY_PAGE_DOWN),
+        MockEvent::key(KEY_PAGE_DOWN),
+        MockEvent::key(KEY_RETURN),
+    ]);
+    assert_eq!(two, page * 2);
+    let back = pick(vec![
+        MockEvent::key(KEY_DOWN),
+        MockEvent::key(KEY_PAGE_DOWN),
+        MockEvent::key(KEY_PAGE_DOWN),
+        MockEvent::key(KEY_PAGE_UP),
+        MockEvent::key(KEY_RETURN),
+    ]);
+    assert_eq!(back, page);
+}
+
+#[test]
+fn scrolling_reuses_the_rows_in_sight() {
+    // Each step down repaints the row leaving the selection and the one
+    // taking it; the rest of the screen comes from the cache
+    let rate = hit_rate_after(std::iter::repeat_n(MockEvent::key(KEY_DOWN), 500));
+    assert!(rate > 0.75, "{rate}");
+}
@@ -0,0 +1,56 @@
+//! Resizing list columns by dragging header boundaries.
+//!
+//! Needs the scripted backend: `cargo test --test list_columns --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "list"))]
+
+use zenity_rs::{
+    ListResult,
+    mock::{self, KEY_DOWN, KEY_RETURN, MockEvent},
+};
+
+/// Without a title or text the header row starts 16px down.
+const HEADER_Y: i16 = 25;
+
+/// The ID printed after optionally dragging the boundary between the
+/// columns, then sorting descending by whichever column is at x = 150.
+fn drag_then_sort(drag: bool) -> String {
+    let mut events = Vec::new();
+    if drag {
+        // The 100px ID column ends 16px in, plus half the 16px gap
+        events.extend([
+            MockEvent::move_to((124, HEADER_Y)),
+            MockEvent::mouse_down(),
+            MockEvent::move_to((200, HEADER_Y)),
+            MockEvent::mouse_up(),
+        ]);
+    }
+    events.push(MockEvent::move_to((150, HEADER_Y)));
+    for _ in 0..2 {
+        events.extend([MockEvent::mouse_down(), MockEvent::mouse_up()]);
+    }
+    events.extend([
+        MockEvent::key(KEY_DOWN),
+        MockEvent::key(KEY_RETURN),
+        MockEvent::Disconnect,
+    ]);
+    mock::script(events);
+    let builder = [("10", "pear"), ("9", "Apple"), ("100", "fig")]
+        .into_iter()
+        .fold(
+            zenity_rs::list().column("ID").column("Name"),
+            |builder, (id, name)| builder.row(vec![id.to_string(), name.to_string()]),
+        );
+    match builder.show().unwrap() {
+        ListResult::Selected(values) => values.join("|"),
+        result => panic!("{result:?}"),
+    }
+}
+
+#[test]
+fn dragging_a_boundary_widens_the_column() {
+    // Sorted by name, descending
+    assert_eq!(drag_then_sort(false), "10");
+    // The wider ID column now reaches x = 150
+    assert_eq!(drag_then_sort(true), "100");
+}
@@ -0,0 +1,105 @@
+//! Accepting a list row by double-clicking it.
+//!
+//! Needs the scripted backend: `cargo test --test list_double_click --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "list"))]
+
+use zenity_rs::{
+    ListResult,
+    mock::{self, KEY_RETURN, MockEvent},
+};
+
+/// Without a title or text the header starts 16px down; each row is 28px
+/// tall below it and a 1px rule.
+const HEADER_Y: i16 = 25;
+const fn row_y(row: i16) -> i16 {
+    59 + row * 28
+}
+
+fn clicks(x: i16, y: i16, count: usize) -> Vec<MockEvent> {
+    let mut events = vec![MockEvent::move_to((x, y))];
+    for _ in 0..count {
+        events.push(MockEvent::mouse_down());
+        events.push(MockEvent::mouse_up());
+    }
+    events
+}
+
+fn fruit(builder: zenity_rs::ListBuilder) -> zenity_rs::ListBuilder {
+    ["apple", "pear", "fig"]
+        .into_iter()
+        .fold(builder.column("Name").height(400), |builder, name| {
+            builder.row(vec![name.to_string()])
+        })
+}
+
+fn show(builder: zenity_rs::ListBuilder, events: Vec<MockEvent>) -> ListResult {
+    mock::script(events.into_iter().chain([MockEvent::Disconnect]));
+    builder.show().unwrap()
+}
+
+fn selected(result: &ListResult) -> String {
+    match result {
+        ListResult::Selected(values) => values.join("|"),
+        result => panic!("{result:?}"),
+    }
+}
+
+#[test]
+fn double_clicking_a_row_accepts_it() {
+    for builder in [zenity_rs::list(), zenity_rs::list().multiple()] {
+        let result = show(fruit(builder), clicks(100, row_y(1), 2));
+        assert_eq!(selected(&result), "pear");
+    }
+}
+
+#[test]
+fn clicks_on_different_rows_are_not_a_double_click() {
+    let mut events = clicks(100, row_y(0), 1);
+    events.extend(clicks(100, row_y(2), 1));
+    events.push(MockEvent::key(KEY_RETURN));
+    let result = show(fruit(zenity_rs::list()), events);
+    assert_eq!(selected(&result), "fig");
+}
+
+#[test]
+fn header_and_empty_area_do_not_accept() {
+    // Two header clicks sort by name descending
+    for (y, last) in [(HEADER_Y, "apple"), (row_y(5), "fig")] {
+        let mut events = clicks(100, y, 2);
+        events.extend(clicks(100, row_y(2), 1));
+        events.push(MockEvent::key(KEY_RETURN));
+        let result = show(fruit(zenity_rs::list()), events);
+        assert_eq!(selected(&result), last, "y = {y}");
+    }
+}
+
+#[test]
+fn double_clicking_an_editable_row_edits_it() {
+    let mut events = clicks(100, row_y(1), 2);
+    events.extend(MockEvent::type_text("s"));
+    events.push(MockEvent::key(KEY_RETURN));
+    events.push(MockEvent::key(KEY_RETURN));
+    let result = show(fruit(zenity_rs::list().editable(true)), events);
+    assert_eq!(selected(&result), "pears");
+}
+
+#[test]
+fn double_clicking_a_checklist_toggle_only_toggles() {
+    let builder = || {
+        zenity_rs::list()
+            .checklist()
+            .column("")
+            .column("Name")
+            .row(vec!["TRUE".into(), "apple".into()])
+            .row(vec!["FALSE".into(), "pear".into()])
+    };
+    // Twice on the toggle leaves it unchecked, then once more checks it
+    let mut events = clicks(30, row_y(1), 3);
+    events.push(MockEvent::key(KEY_RETURN));
+    assert_eq!(selected(&show(builder(), events)), "apple|pear");
+
+    // Double-clicking the rest of the row accepts the checked rows
+    let result = show(builder(), clicks(150, row_y(1), 2));
+    assert_eq!(selected(&result), "apple");
+}
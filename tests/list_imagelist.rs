@@ -0,0 +1,93 @@
+//! The `--imagelist` column of images.
+//!
+//! Needs the scripted backend: `cargo test --test list_imagelist --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "list"))]
+
+mod harness;
+
+use harness::TempDir;
+use tiny_skia::{Color, Pixmap};
+use zenity_rs::{
+    ListResult, PrintColumn,
+    mock::{self, KEY_DOWN, KEY_RETURN, MockEvent},
+};
+
+/// Writes a solid PNG, larger than a row, into `dir`.
+fn png(dir: &TempDir) -> String {
+    let mut pixmap = Pixmap::new(300, 200).unwrap();
+    pixmap.fill(Color::from_rgba8(40, 120, 220, 255));
+    let path = dir.path().join("image.png");
+    pixmap.save_png(&path).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+/// Rows whose images are a real PNG, a missing file and no path at all.
+fn fruit(dir: &TempDir) -> zenity_rs::ListBuilder {
+    let image = png(dir);
+    [
+        (image.as_str(), "apple", "red"),
+        ("/nonexistent/pear.png", "pear", "green"),
+        ("", "fig", "purple"),
+    ]
+    .into_iter()
+    .fold(
+        zenity_rs::list()
+            .imagelist(true)
+            .column("Icon")
+            .column("Name")
+            .column("Color"),
+        |builder, (image, name, color)| builder.row(vec![image.into(), name.into(), color.into()]),
+    )
+}
+
+/// Moves the cursor down `down` rows from the first and presses Enter.
+fn pick(builder: zenity_rs::ListBuilder, down: usize) -> ListResult {
+    let mut events = vec![MockEvent::key(KEY_DOWN); down + 1];
+    events.extend([MockEvent::key(KEY_RETURN), MockEvent::Disconnect]);
+    mock::script(events);
+    builder.show().unwrap()
+}
+
+fn selected(result: &ListResult) -> String {
+    match result {
+        ListResult::Selected(values) => values.join("|"),
+        result => panic!("{result:?}"),
+    }
+}
+
+#[test]
+fn the_image_column_is_not_printed() {
+    let dir = TempDir::new("imagelist-printed");
+    for row in 0..3 {
+        let result = pick(fruit(&dir), row);
+        assert_eq!(selected(&result), ["apple", "pear", "fig"][row]);
+    }
+    let result = pick(fruit(&dir).print_column(PrintColumn::All), 1);
+    assert_eq!(selected(&result), "pear|green");
+}
+
+#[test]
+fn print_columns_count_the_image_column() {
+    let dir = TempDir::new("imagelist-columns");
+    let result = pick(fruit(&dir).print_column(PrintColumn::Column(3)), 0);
+    assert_eq!(selected(&result), "red");
+    let result = pick(fruit(&dir).print_column(PrintColumn::Column(1)), 0);
+    assert!(matches!(result, ListResult::Cancelled), "{result:?}");
+}
+
+#[test]
+fn checklists_put_images_after_the_toggles() {
+    let dir = TempDir::new("imagelist-checklist");
+    let image = png(&dir);
+    let builder = zenity_rs::list()
+        .checklist()
+        .imagelist(true)
+        .column("")
+        .column("Icon")
+        .column("Name")
+        .row(vec!["TRUE".into(), image, "apple".into()])
+        .row(vec!["TRUE".into(), "missing.png".into(), "pear".into()]);
+    mock::script([MockEvent::key(KEY_RETURN), MockEvent::Disconnect]);
+    assert_eq!(selected(&builder.show().unwrap()), "apple|pear");
+}
@@ -0,0 +1,115 @@
+//! Typeahead search in the list dialog.
+//!
+//! Needs the scripted backend: `cargo test --test list_search --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "list"))]
+
+use zenity_rs::{
+    ListResult,
+    mock::{self, KEY_BACKSPACE, KEY_DOWN, KEY_ESCAPE, KEY_RETURN, MockEvent},
+};
+
+fn fruits() -> zenity_rs::ListBuilder {
+    ["apple", "apricot", "banana", "avocado", "Cherry pie"]
+        .into_iter()
+        .enumerate()
+        .fold(
+            zenity_rs::list().column("ID").column("Fruit"),
+            |builder, (i, fruit)| builder.row(vec![(i + 1).to_string(), fruit.to_string()]),
+        )
+}
+
+/// The printed values, or `None` when cancelled.
+fn pick(builder: zenity_rs::ListBuilder, events: Vec<MockEvent>) -> Option<Vec<String>> {
+    mock::script(events.into_iter().chain([MockEvent::Disconnect]));
+    match builder.show().unwrap() {
+        ListResult::Selected(values) => Some(values),
+        ListResult::Cancelled => None,
+        result => panic!("{result:?}"),
+    }
+}
+
+fn selected(values: &[&str]) -> Option<Vec<String>> {
+    Some(values.iter().map(|v| v.to_string()).collect())
+}
+
+#[test]
+fn typing_selects_the_first_match() {
+    let mut events = MockEvent::type_text("ch");
+    events.push(MockEvent::key(KEY_RETURN));
+    assert_eq!(pick(fruits(), events), selected(&["5"]));
+
+    let mut events = MockEvent::type_text("BAN");
+    events.push(MockEvent::key(KEY_RETURN));
+    assert_eq!(pick(fruits(), events), selected(&["3"]));
+}
+
+#[test]
+fn arrows_move_within_the_matches() {
+    // "a" matches apple, apricot and avocado; banana is skipped
+    let mut events = MockEvent::type_text("a");
+    events.extend([
+        MockEvent::key(KEY_DOWN),
+        MockEvent::key(KEY_DOWN),
+        MockEvent::key(KEY_DOWN),
+        MockEvent::key(KEY_RETURN),
+    ]);
+    assert_eq!(pick(fruits(), events), selected(&["4"]));
+}
+
+#[test]
+fn matches_start_cells_unless_mid_search() {
+    let events = || {
+        MockEvent::type_text("pie")
+            .into_iter()
+            .chain([MockEvent::key(KEY_RETURN)])
+            .collect()
+    };
+    assert_eq!(pick(fruits(), events()), None);
+    assert_eq!(pick(fruits().mid_search(true), events()), selected(&["5"]));
+}
+
+#[test]
+fn escape_clears_the_search_before_cancelling() {
+    let mut events = MockEvent::type_text("av");
+    events.extend([
+        MockEvent::key(KEY_ESCAPE),
+        MockEvent::key(KEY_DOWN),
+        MockEvent::key(KEY_RETURN),
+    ]);
+    assert_eq!(pick(fruits(), events), selected(&["5"]));
+
+    let mut events = MockEvent::type_text("av");
+    events.extend([MockEvent::key(KEY_ESCAPE), MockEvent::key(KEY_ESCAPE)]);
+    assert_eq!(pick(fruits(), events), None);
+}
+
+#[test]
+fn backspace_widens_the_search() {
+    let mut events = MockEvent::type_text("ba");
+    events.extend([
+        MockEvent::key(KEY_BACKSPACE),
+        MockEvent::key(KEY_BACKSPACE),
+        MockEvent::key(KEY_RETURN),
+    ]);
+    assert_eq!(pick(fruits(), events), selected(&["3"]));
+}
+
+#[test]
+fn checklist_rows_are_found_by_their_cells() {
+    let builder = zenity_rs::list()
+        .checklist()
+        .column("Pick")
+        .column("Fruit")
+        .row(vec!["FALSE".into(), "apple".into()])
+        .row(vec!["TRUE".into(), "banana".into()])
+        .row(vec!["FALSE".into(), "cherry".into()]);
+    // Escape leaves the cursor on the match for Space to check
+    let mut events = MockEvent::type_text("c");
+    events.extend([
+        MockEvent::key(KEY_ESCAPE),
+        MockEvent::key(0x20),
+        MockEvent::key(KEY_RETURN),
+    ]);
+    assert_eq!(pick(builder, events), selected(&["banana", "cherry"]));
+}
@@ -0,0 +1,102 @@
+//! Sorting list rows by a column.
+//!
+//! Needs the scripted backend: `cargo test --test list_sort --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "list"))]
+
+use zenity_rs::{
+    ListResult,
+    mock::{self, KEY_DOWN, KEY_RETURN, MockEvent},
+};
+
+/// Clicks the header `x` pixels from the window's left edge. Without a
+/// title or text the header row starts 16px down.
+fn click_header(x: i16) -> Vec<MockEvent> {
+    MockEvent::click((x, 25)).to_vec()
+}
+
+fn people() -> zenity_rs::ListBuilder {
+    [("10", "pear"), ("9", "Apple"), ("100", "fig")]
+        .into_iter()
+        .fold(
+            zenity_rs::list().column("ID").column("Name"),
+            |builder, (id, name)| builder.row(vec![id.to_string(), name.to_string()]),
+        )
+}
+
+/// The ID printed after `events`.
+fn pick(builder: zenity_rs::ListBuilder, events: Vec<MockEvent>) -> String {
+    mock::script(events.into_iter().chain([MockEvent::Disconnect]));
+    match builder.show().unwrap() {
+        ListResult::Selected(values) => values.join("|"),
+        result => panic!("{result:?}"),
+    }
+}
+
+#[test]
+fn numbers_sort_by_value() {
+    let first = vec![MockEvent::key(KEY_DOWN), MockEvent::key(KEY_RETURN)];
+    assert_eq!(pick(people().sort_by_column(1, false), first.clone()), "9");
+    assert_eq!(pick(people().sort_by_column(1, true), first), "100");
+}
+
+#[test]
+fn text_sorts_ignoring_case() {
+    let first = vec![MockEvent::key(KEY_DOWN), MockEvent::key(KEY_RETURN)];
+    assert_eq!(pick(people().sort_by_column(2, false), first.clone()), "9");
+    assert_eq!(pick(people().sort_by_column(2, true), first), "10");
+
+    // One cell that isn't a number makes the whole column text
+    let mixed = people().row(vec!["b".into(), "kiwi".into()]);
+    let events = vec![MockEvent::key(KEY_DOWN), MockEvent::key(KEY_RETURN)];
+    assert_eq!(pick(mixed.sort_by_column(1, false), events), "10");
+}
+
+#[test]
+fn header_clicks_sort_then_reverse() {
+    let mut events = click_header(30);
+    events.extend([MockEvent::key(KEY_DOWN), MockEvent::key(KEY_RETURN)]);
+    assert_eq!(pick(people(), events), "9");
+
+    let mut events = click_header(30);
+    events.extend(click_header(30));
+    events.extend([MockEvent::key(KEY_DOWN), MockEvent::key(KEY_RETURN)]);
+    assert_eq!(pick(people(), events), "100");
+
+    // The ID column is 100px wide, then a 16px gap
+    let mut events = click_header(150);
+    events.extend([MockEvent::key(KEY_DOWN), MockEvent::key(KEY_RETURN)]);
+    assert_eq!(pick(people(), events), "9");
+}
+
+#[test]
+fn the_selection_follows_its_row() {
+    // pear is selected, then sorted from first to last
+    let mut events = vec![MockEvent::key(KEY_DOWN)];
+    events.extend(click_header(150));
+    events.extend([MockEvent::key(KEY_DOWN), MockEvent::key(KEY_RETURN)]);
+    assert_eq!(pick(people(), events), "10");
+
+    // pear starts first by name, then sits between 9 and 100 by ID
+    let mut events = vec![MockEvent::key(KEY_DOWN)];
+    events.extend(click_header(30));
+    events.extend([MockEvent::key(KEY_DOWN), MockEvent::key(KEY_RETURN)]);
+    assert_eq!(pick(people().sort_by_column(2, true), events), "100");
+}
+
+#[test]
+fn toggle_columns_count() {
+    let builder = zenity_rs::list()
+        .checklist()
+        .column("Pick")
+        .column("Name")
+        .row(vec!["FALSE".into(), "pear".into()])
+        .row(vec!["FALSE".into(), "apple".into()])
+        .sort_by_column(2, false);
+    let events = vec![
+        MockEvent::key(KEY_DOWN),
+        MockEvent::key(0x20),
+        MockEvent::key(KEY_RETURN),
+    ];
+    assert_eq!(pick(builder, events), "apple");
+}
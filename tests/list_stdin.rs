@@ -0,0 +1,50 @@
+//! Reading list values from stdin, by line or with `--stdin-null`.
+//!
+//! The runs have no display, so well-formed lists get as far as opening the
+//! window and fail there; the row count is checked before that.
+
+#![cfg(feature = "cli")]
+
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+fn run(args: &[&str], stdin: &[u8]) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_zenity-rs"))
+        .args(args)
+        .env_remove("WAYLAND_DISPLAY")
+        .env_remove("DISPLAY")
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(100), "{args:?}");
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+const TWO_COLUMNS: &[&str] = &["--list", "--column=A", "--column=B"];
+
+#[test]
+fn lines_are_values() {
+    let stderr = run(TWO_COLUMNS, b"a\nb\r\nc\nd");
+    assert!(!stderr.contains("values for"), "{stderr}");
+    let stderr = run(TWO_COLUMNS, b"a\nb\nc\n");
+    assert!(stderr.contains("got 3 values for 2 columns"), "{stderr}");
+}
+
+#[test]
+fn nul_ended_values_can_span_lines() {
+    let stderr = run(&[TWO_COLUMNS, &["--stdin-null"]].concat(), b"a\nb\0c\0d\0");
+    assert!(stderr.contains("got 3 values for 2 columns"), "{stderr}");
+    let stderr = run(&[TWO_COLUMNS, &["--stdin-null"]].concat(), b"a\nb\0c\0");
+    assert!(!stderr.contains("values for"), "{stderr}");
+}
+
+#[test]
+fn positional_values_come_first() {
+    let stderr = run(&[TWO_COLUMNS, &["x"]].concat(), b"y\nz\n");
+    assert!(stderr.contains("got 3 values for 2 columns"), "{stderr}");
+}
@@ -0,0 +1,94 @@
+//! Mapping message dialog button indices to results.
+
+use zenity_rs::{ButtonOrder, ButtonPreset, DialogOutcome, DialogResult};
+
+#[test]
+fn preset_buttons_come_first() {
+    let presets = ButtonPreset::YesNo.labels().len();
+    assert_eq!(
+        DialogResult::from_index(0, presets),
+        DialogResult::Button(0)
+    );
+    assert_eq!(
+        DialogResult::from_index(1, presets),
+        DialogResult::Button(1)
+    );
+    assert_eq!(DialogResult::from_index(2, presets), DialogResult::Extra(0));
+    assert_eq!(DialogResult::from_index(3, presets), DialogResult::Extra(1));
+}
+
+#[test]
+fn switch_mode_has_only_extra_buttons() {
+    assert_eq!(DialogResult::from_index(0, 0), DialogResult::Extra(0));
+    assert_eq!(DialogResult::from_index(2, 0), DialogResult::Extra(2));
+}
+
+#[test]
+fn extra_buttons_exit_with_one() {
+    assert_eq!(DialogResult::Extra(0).exit_code(), 1);
+    assert_eq!(DialogResult::Extra(5).exit_code(), 1);
+    assert_eq!(DialogResult::Button(0).exit_code(), 0);
+}
+
+fn labels(labels: &[&str]) -> Vec<String> {
+    labels.iter().map(|label| label.to_string()).collect()
+}
+
+#[test]
+fn buttons_are_laid_out_in_reverse() {
+    let order = ButtonOrder::new(labels(&["Yes", "No"]), &labels(&["Later", "Never"]));
+    assert_eq!(
+        order.laid_out().collect::<Vec<_>>(),
+        ["Never", "Later", "No", "Yes"]
+    );
+    assert_eq!(order.position(0), Some(3));
+    assert_eq!(order.position(3), Some(0));
+    assert_eq!(order.position(4), None);
+}
+
+#[test]
+fn clicked_position_names_the_button() {
+    let order = ButtonOrder::new(labels(&["Yes", "No"]), &labels(&["Later", "Never"]));
+    let outcome = order.outcome_at(0);
+    assert_eq!(outcome.result, DialogResult::Extra(1));
+    assert_eq!(outcome.label.as_deref(), Some("Never"));
+    assert!(outcome.is_extra());
+
+    let outcome = order.outcome_at(2);
+    assert_eq!(outcome.result, DialogResult::Button(1));
+    assert_eq!(outcome.label.as_deref(), Some("No"));
+    assert!(!outcome.is_extra());
+    assert_eq!(outcome.exit_code(), 1);
+
+    // Every position maps back to the button laid out there
+    for (position, label) in order.laid_out().enumerate() {
+        assert_eq!(order.outcome_at(position).label.as_deref(), Some(label));
+    }
+}
+
+#[test]
+fn switch_mode_outcomes_are_extra_buttons() {
+    let order = ButtonOrder::new(Vec::new(), &labels(&["Left", "Right"]));
+    let outcome = order.outcome_at(1);
+    assert_eq!(outcome.result, DialogResult::Extra(0));
+    assert_eq!(outcome.label.as_deref(), Some("Left"));
+}
+
+#[test]
+fn closing_has_no_label() {
+    let outcome = DialogOutcome::from(DialogResult::Closed);
+    assert_eq!(outcome.label, None);
+    assert_eq!(outcome.exit_code(), 1);
+    assert_eq!(DialogOutcome::from(DialogResult::Timeout).exit_code(), 5);
+}
+
+#[test]
+fn preset_mnemonics_are_not_reported() {
+    let order = ButtonOrder::new(ButtonPreset::YesNo.labels(), &labels(&["Save__as"]));
+    assert_eq!(
+        order.laid_out().collect::<Vec<_>>(),
+        ["Save__as", "_No", "_Yes"]
+    );
+    assert_eq!(order.outcome(0).label.as_deref(), Some("Yes"));
+    assert_eq!(order.outcome(2).label.as_deref(), Some("Save_as"));
+}
@@ -0,0 +1,50 @@
+//! The "don't ask again" checkbox of message dialogs.
+//!
+//! Needs the scripted backend: `cargo test --test message_checkbox --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "message"))]
+
+use zenity_rs::{
+    DialogResult,
+    mock::{self, KEY_RETURN, MockEvent},
+};
+
+fn type_then_return(text: &str) {
+    let mut events = MockEvent::type_text(text);
+    events.push(MockEvent::key(KEY_RETURN));
+    events.push(MockEvent::Disconnect);
+    mock::script(events);
+}
+
+#[test]
+fn space_toggles_the_checkbox() {
+    type_then_return("");
+    let outcome = zenity_rs::question("Delete?")
+        .checkbox("Do not ask again")
+        .show_detailed()
+        .unwrap();
+    assert_eq!(outcome.result, DialogResult::Button(0));
+    assert_eq!(outcome.checked, Some(false));
+
+    type_then_return(" ");
+    let outcome = zenity_rs::question("Delete?")
+        .checkbox("Do not ask again")
+        .show_detailed()
+        .unwrap();
+    assert_eq!(outcome.checked, Some(true));
+
+    type_then_return("  ");
+    let outcome = zenity_rs::question("Delete?")
+        .checkbox("Do not ask again")
+        .show_detailed()
+        .unwrap();
+    assert_eq!(outcome.checked, Some(false));
+}
+
+#[test]
+fn no_state_without_a_checkbox() {
+    type_then_return(" ");
+    let outcome = zenity_rs::question("Delete?").show_detailed().unwrap();
+    assert_eq!(outcome.result, DialogResult::Button(0));
+    assert_eq!(outcome.checked, None);
+}
@@ -0,0 +1,34 @@
+//! `show_detailed` reports the label of the button Enter chose.
+//!
+//! Needs the scripted backend: `cargo test --test message_outcome --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "message"))]
+
+use zenity_rs::{
+    DialogResult,
+    mock::{self, KEY_RETURN, MockEvent},
+};
+
+fn press_return() {
+    mock::script([MockEvent::key(KEY_RETURN), MockEvent::Disconnect]);
+}
+
+#[test]
+fn default_button_label_is_reported() {
+    press_return();
+    let outcome = zenity_rs::question("Delete?")
+        .extra_button("Ask later")
+        .show_detailed()
+        .unwrap();
+    assert_eq!(outcome.result, DialogResult::Button(0));
+    assert_eq!(outcome.label.as_deref(), Some("Yes"));
+
+    press_return();
+    let outcome = zenity_rs::question("Delete?")
+        .extra_button("Ask later")
+        .default_cancel(true)
+        .show_detailed()
+        .unwrap();
+    assert_eq!(outcome.result, DialogResult::Button(1));
+    assert_eq!(outcome.label.as_deref(), Some("No"));
+}
@@ -0,0 +1,71 @@
+//! Pressing buttons by the underlined letter of their label.
+//!
+//! Needs the scripted backend: `cargo test --test mnemonics --features mock-backend`.
+
+#![cfg(all(
+    feature = "mock-backend",
+    feature = "message",
+    feature = "entry",
+    feature = "calendar"
+))]
+
+use zenity_rs::{
+    CalendarResult, DialogResult, EntryResult, Modifiers,
+    mock::{self, KEY_RETURN, MockEvent},
+};
+
+fn question(events: impl IntoIterator<Item = MockEvent>) -> zenity_rs::DialogOutcome {
+    mock::script(events.into_iter().chain([MockEvent::Disconnect]));
+    zenity_rs::question("Delete?")
+        .extra_button("_Later")
+        .show_detailed()
+        .unwrap()
+}
+
+#[test]
+fn letters_press_message_buttons() {
+    let outcome = question(MockEvent::type_text("n"));
+    assert_eq!(outcome.result, DialogResult::Button(1));
+    assert_eq!(outcome.label.as_deref(), Some("No"));
+
+    let outcome = question([MockEvent::key_with('y' as u32, Modifiers::ALT)]);
+    assert_eq!(outcome.result, DialogResult::Button(0));
+
+    // Extra buttons take mnemonics too, and report the label as shown
+    let outcome = question(MockEvent::type_text("L"));
+    assert_eq!(outcome.result, DialogResult::Extra(0));
+    assert_eq!(outcome.label.as_deref(), Some("Later"));
+}
+
+#[test]
+fn letters_without_alt_go_to_the_entry() {
+    mock::script(
+        MockEvent::type_text("c")
+            .into_iter()
+            .chain([MockEvent::key(KEY_RETURN)])
+            .chain([MockEvent::Disconnect]),
+    );
+    let result = zenity_rs::entry().show().unwrap();
+    assert!(
+        matches!(&result, EntryResult::Text(text) if text == "c"),
+        "{result:?}"
+    );
+
+    mock::script([
+        MockEvent::key_with('c' as u32, Modifiers::ALT),
+        MockEvent::Disconnect,
+    ]);
+    let result = zenity_rs::entry().show().unwrap();
+    assert!(matches!(result, EntryResult::Cancelled), "{result:?}");
+}
+
+#[test]
+fn calendar_buttons_have_mnemonics() {
+    mock::script(
+        MockEvent::type_text("c")
+            .into_iter()
+            .chain([MockEvent::Disconnect]),
+    );
+    let result = zenity_rs::calendar().show().unwrap();
+    assert!(matches!(result, CalendarResult::Cancelled), "{result:?}");
+}
@@ -0,0 +1,101 @@
+//! Typing a new password twice.
+//!
+//! Needs the scripted backend: `cargo test --test password_confirm --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "entry"))]
+
+use zenity_rs::{
+    EntryResult,
+    mock::{self, KEY_ESCAPE, KEY_ISO_LEFT_TAB, KEY_RETURN, KEY_TAB, MockEvent},
+};
+
+/// Runs the script, then Escape, which only closes the dialog if it
+/// didn't accept anything before.
+fn confirm(events: impl IntoIterator<Item = MockEvent>) -> EntryResult {
+    mock::script(
+        events
+            .into_iter()
+            .chain([MockEvent::key(KEY_ESCAPE), MockEvent::Disconnect]),
+    );
+    zenity_rs::password().confirm(true).show().unwrap()
+}
+
+#[test]
+fn matching_passwords_are_returned_once() {
+    let result = confirm(
+        MockEvent::type_text("s3cret")
+            .into_iter()
+            .chain([MockEvent::key(KEY_TAB)])
+            .chain(MockEvent::type_text("s3cret"))
+            .chain([MockEvent::key(KEY_RETURN)]),
+    );
+    assert!(
+        matches!(&result, EntryResult::Text(text) if text == "s3cret"),
+        "{result:?}"
+    );
+
+    // Enter in the first field moves on to the second
+    let result = confirm(
+        MockEvent::type_text("pw")
+            .into_iter()
+            .chain([MockEvent::key(KEY_RETURN)])
+            .chain(MockEvent::type_text("pw"))
+            .chain([MockEvent::key(KEY_RETURN)]),
+    );
+    assert!(matches!(result, EntryResult::Text(_)), "{result:?}");
+}
+
+#[test]
+fn mismatches_and_empty_passwords_are_refused() {
+    let result = confirm(
+        MockEvent::type_text("one")
+            .into_iter()
+            .chain([MockEvent::key(KEY_TAB)])
+            .chain(MockEvent::type_text("two"))
+            .chain([MockEvent::key(KEY_RETURN)]),
+    );
+    assert!(matches!(result, EntryResult::Closed), "{result:?}");
+
+    let result = confirm([MockEvent::key(KEY_TAB), MockEvent::key(KEY_RETURN)]);
+    assert!(matches!(result, EntryResult::Closed), "{result:?}");
+}
+
+#[test]
+fn shift_tab_goes_back_to_fix_the_first_field() {
+    let result = confirm(
+        MockEvent::type_text("pass")
+            .into_iter()
+            .chain([MockEvent::key(KEY_TAB)])
+            .chain(MockEvent::type_text("password"))
+            .chain([MockEvent::key(KEY_ISO_LEFT_TAB)])
+            .chain(MockEvent::type_text("word"))
+            .chain([MockEvent::key(KEY_TAB), MockEvent::key(KEY_RETURN)]),
+    );
+    assert!(
+        matches!(&result, EntryResult::Text(text) if text == "password"),
+        "{result:?}"
+    );
+}
+
+#[test]
+fn usernames_come_with_a_confirmed_password() {
+    mock::script(
+        MockEvent::type_text("ann")
+            .into_iter()
+            .chain([MockEvent::key(KEY_TAB)])
+            .chain(MockEvent::type_text("pw"))
+            .chain([MockEvent::key(KEY_TAB)])
+            .chain(MockEvent::type_text("pw"))
+            .chain([MockEvent::key(KEY_RETURN), MockEvent::Disconnect]),
+    );
+    let result = zenity_rs::password()
+        .username(true)
+        .confirm(true)
+        .show()
+        .unwrap();
+    assert!(
+        matches!(&result, EntryResult::Credentials { username, password }
+            if username == "ann" && password == "pw"),
+        "{result:?}"
+    );
+}
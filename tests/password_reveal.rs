@@ -0,0 +1,51 @@
+//! The password dialog's eye button and strength bar.
+//!
+//! Needs the scripted backend: `cargo test --test password_reveal --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "entry"))]
+
+use zenity_rs::{
+    EntryResult, Modifiers,
+    mock::{self, KEY_END, KEY_RETURN, MockEvent},
+};
+
+/// Clicks at (`x`, `y`), types `text` and presses Enter.
+fn click_then_type(x: i16, y: i16, text: &str) -> Vec<MockEvent> {
+    let mut events = MockEvent::click((x, y)).to_vec();
+    events.extend(MockEvent::type_text(text));
+    events.push(MockEvent::key(KEY_RETURN));
+    events.push(MockEvent::Disconnect);
+    events
+}
+
+#[test]
+fn revealing_keeps_the_password() {
+    // The eye sits at the right end of the field, 20px in from the window
+    for revealed in [false, true] {
+        let (x, y) = if revealed { (304, 36) } else { (100, 36) };
+        mock::script(click_then_type(x, y, "hunter2"));
+        let result = zenity_rs::password().show_strength(true).show().unwrap();
+        assert!(
+            matches!(&result, EntryResult::Text(text) if text == "hunter2"),
+            "{result:?}"
+        );
+    }
+}
+
+#[test]
+fn copying_a_revealed_password_is_still_refused() {
+    let mut events = click_then_type(304, 36, "");
+    events.truncate(3);
+    for letter in ['a', 'c'] {
+        events.push(MockEvent::key_with(letter as u32, Modifiers::CTRL));
+    }
+    events.push(MockEvent::key(KEY_END));
+    events.push(MockEvent::key_with('v' as u32, Modifiers::CTRL));
+    events.extend(click_then_type(100, 36, ""));
+    mock::script(events);
+    let result = zenity_rs::password().entry_text("secret").show().unwrap();
+    assert!(
+        matches!(&result, EntryResult::Text(text) if text == "secret"),
+        "{result:?}"
+    );
+}
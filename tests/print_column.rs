@@ -0,0 +1,58 @@
+//! `--print-column` choosing what a selected list row prints.
+
+#![cfg(feature = "list")]
+
+use zenity_rs::{ListMode, PrintColumn};
+
+fn row(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+#[test]
+fn names_parse() {
+    assert_eq!(PrintColumn::from_name("2"), Some(PrintColumn::Column(2)));
+    assert_eq!(PrintColumn::from_name("ALL"), Some(PrintColumn::All));
+    assert_eq!(PrintColumn::from_name("all"), Some(PrintColumn::All));
+    assert_eq!(PrintColumn::from_name("0"), None);
+    assert_eq!(PrintColumn::from_name("-1"), None);
+    assert_eq!(PrintColumn::from_name("first"), None);
+}
+
+#[test]
+fn plain_lists_count_from_the_first_column() {
+    // An ID column, typically hidden, then a name
+    let r = row(&["42", "Alice", "admin"]);
+    assert_eq!(PrintColumn::Default.values(&r, ListMode::Single), ["42"]);
+    assert_eq!(PrintColumn::Column(1).values(&r, ListMode::Single), ["42"]);
+    assert_eq!(
+        PrintColumn::Column(3).values(&r, ListMode::Multiple),
+        ["admin"]
+    );
+    assert_eq!(
+        PrintColumn::All.values(&r, ListMode::Single),
+        ["42", "Alice", "admin"]
+    );
+    assert!(
+        PrintColumn::Column(4)
+            .values(&r, ListMode::Single)
+            .is_empty()
+    );
+    assert!(
+        PrintColumn::Column(0)
+            .values(&r, ListMode::Single)
+            .is_empty()
+    );
+}
+
+#[test]
+fn toggle_lists_count_the_toggle_column() {
+    // The TRUE/FALSE column is stripped from the row before printing
+    let r = row(&["Option A", "first"]);
+    for mode in [ListMode::Checklist, ListMode::Radiolist] {
+        assert_eq!(PrintColumn::Default.values(&r, mode), ["Option A"]);
+        assert_eq!(PrintColumn::Column(1).values(&r, mode), ["TRUE"]);
+        assert_eq!(PrintColumn::Column(2).values(&r, mode), ["Option A"]);
+        assert_eq!(PrintColumn::Column(3).values(&r, mode), ["first"]);
+        assert_eq!(PrintColumn::All.values(&r, mode), ["Option A", "first"]);
+    }
+}
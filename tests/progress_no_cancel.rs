@@ -0,0 +1,35 @@
+//! A progress dialog without Cancel can't be closed by the user.
+//!
+//! Needs the scripted backend: `cargo test --test progress_no_cancel --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "progress"))]
+
+use zenity_rs::{
+    Error, ProgressResult, WindowEvent,
+    mock::{self, KEY_ESCAPE, MockEvent},
+};
+
+fn script_close_attempts() {
+    mock::script([
+        MockEvent::key(KEY_ESCAPE),
+        MockEvent::Event(WindowEvent::CloseRequested),
+        MockEvent::Disconnect,
+    ]);
+}
+
+#[test]
+fn escape_and_close_are_ignored() {
+    script_close_attempts();
+    let result = zenity_rs::progress().pulsate(true).no_cancel(true).show();
+    assert!(matches!(result, Err(Error::DisplayLost)), "{result:?}");
+}
+
+#[test]
+fn escape_cancels_otherwise() {
+    script_close_attempts();
+    let result = zenity_rs::progress().pulsate(true).show();
+    assert!(
+        matches!(result, Ok(ProgressResult::Cancelled)),
+        "{result:?}"
+    );
+}
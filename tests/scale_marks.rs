@@ -0,0 +1,90 @@
+//! The scale's tick marks, snapping to steps, and the mouse wheel.
+//!
+//! Needs the scripted backend: `cargo test --test scale_marks --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "scale"))]
+
+use zenity_rs::{
+    Modifiers, ScaleBuilder, ScaleResult, ScrollDirection, WindowEvent,
+    mock::{self, KEY_LEFT, KEY_RETURN, KEY_RIGHT, MockEvent},
+};
+
+/// The 300px slider of the 350px window, less half the 20px thumb at
+/// each end, and the middle of the thumb's row.
+const TRACK_START: i16 = 35;
+const TRACK_WIDTH: i16 = 280;
+const THUMB_Y: i16 = 30;
+
+fn wheel(direction: ScrollDirection, modifiers: Modifiers) -> MockEvent {
+    MockEvent::Event(WindowEvent::Scroll(direction, modifiers))
+}
+
+/// The value accepted with Enter after `events`.
+fn value(builder: ScaleBuilder, events: Vec<MockEvent>) -> i32 {
+    mock::script(
+        events
+            .into_iter()
+            .chain([MockEvent::key(KEY_RETURN), MockEvent::Disconnect]),
+    );
+    match builder.show().unwrap() {
+        ScaleResult::Value(value) => value,
+        result => panic!("{result:?}"),
+    }
+}
+
+/// 0 to 7 in steps of 3, starting at 3.
+fn odd_range() -> ScaleBuilder {
+    zenity_rs::scale()
+        .min_value(0)
+        .max_value(7)
+        .step(3)
+        .value(3)
+        .marks(true)
+}
+
+#[test]
+fn arrow_keys_stop_at_each_step_and_the_maximum() {
+    assert_eq!(value(odd_range(), vec![MockEvent::key(KEY_RIGHT)]), 6);
+    assert_eq!(value(odd_range(), vec![MockEvent::key(KEY_RIGHT); 2]), 7);
+    assert_eq!(value(odd_range(), vec![MockEvent::key(KEY_RIGHT); 3]), 7);
+    let mut events = vec![MockEvent::key(KEY_RIGHT); 2];
+    events.push(MockEvent::key(KEY_LEFT));
+    assert_eq!(value(odd_range(), events), 6);
+}
+
+#[test]
+fn the_wheel_moves_a_step_or_ten_with_shift() {
+    let builder = || zenity_rs::scale().value(50).step(2);
+    let over = MockEvent::move_to((TRACK_START + TRACK_WIDTH / 2, THUMB_Y));
+    let up = wheel(ScrollDirection::Up, Modifiers::empty());
+    let down = wheel(ScrollDirection::Down, Modifiers::empty());
+    assert_eq!(value(builder(), vec![over.clone(), up.clone(), up]), 54);
+    assert_eq!(value(builder(), vec![over.clone(), down]), 48);
+    let shift_down = wheel(ScrollDirection::Down, Modifiers::SHIFT);
+    assert_eq!(value(builder(), vec![over.clone(), shift_down]), 30);
+    let shift_up = wheel(ScrollDirection::Up, Modifiers::SHIFT);
+    assert_eq!(value(builder(), vec![over, shift_up.clone(), shift_up]), 90);
+
+    // Away from the slider the wheel does nothing
+    let away = MockEvent::move_to((TRACK_START, 150));
+    let up = wheel(ScrollDirection::Up, Modifiers::empty());
+    assert_eq!(value(builder(), vec![away, up]), 50);
+}
+
+#[test]
+fn clicking_the_track_snaps_to_a_step() {
+    let builder = || zenity_rs::scale().step(10).marks(true);
+    let click_at = |fraction: f32| {
+        vec![
+            MockEvent::move_to((
+                TRACK_START + (TRACK_WIDTH as f32 * fraction) as i16,
+                THUMB_Y,
+            )),
+            MockEvent::mouse_down(),
+            MockEvent::mouse_up(),
+        ]
+    };
+    assert_eq!(value(builder(), click_at(0.34)), 30);
+    assert_eq!(value(builder(), click_at(0.36)), 40);
+    assert_eq!(value(builder(), click_at(1.0)), 100);
+}
@@ -0,0 +1,104 @@
+//! The scale running up the window instead of across it.
+//!
+//! Needs the scripted backend: `cargo test --test scale_vertical --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "scale"))]
+
+use zenity_rs::{
+    Modifiers, Orientation, ScaleBuilder, ScaleResult, ScrollDirection, WindowEvent,
+    mock::{self, KEY_DOWN, KEY_LEFT, KEY_RETURN, KEY_UP, MockEvent},
+};
+
+/// In a 300px wide window the 20px thumb's column is centred, and its
+/// centre travels 280px up from 10px above the bottom of the 300px track.
+const TRACK_X: i16 = 150;
+const BOTTOM: i16 = 20 + 300 - 10;
+const TRAVEL: i16 = 280;
+
+/// The point on the track for a value out of 100.
+fn at(value: i16) -> MockEvent {
+    MockEvent::move_to((TRACK_X, BOTTOM - TRAVEL * value / 100))
+}
+
+/// 0 to 100 up a 300px wide window, starting at 50.
+fn vertical() -> ScaleBuilder {
+    zenity_rs::scale()
+        .orientation(Orientation::Vertical)
+        .width(300)
+        .value(50)
+        .marks(true)
+}
+
+/// The value accepted with Enter after `events`.
+fn value(builder: ScaleBuilder, events: Vec<MockEvent>) -> i32 {
+    mock::script(
+        events
+            .into_iter()
+            .chain([MockEvent::key(KEY_RETURN), MockEvent::Disconnect]),
+    );
+    match builder.show().unwrap() {
+        ScaleResult::Value(value) => value,
+        result => panic!("{result:?}"),
+    }
+}
+
+#[test]
+fn up_and_down_keys_step_the_value() {
+    assert_eq!(value(vertical(), vec![MockEvent::key(KEY_UP); 3]), 53);
+    assert_eq!(
+        value(
+            vertical(),
+            vec![MockEvent::key(KEY_DOWN), MockEvent::key(KEY_LEFT)]
+        ),
+        48
+    );
+}
+
+#[test]
+fn higher_on_the_track_is_larger() {
+    assert_eq!(
+        value(
+            vertical(),
+            vec![at(75), MockEvent::mouse_down(), MockEvent::mouse_up()]
+        ),
+        75
+    );
+    assert_eq!(
+        value(
+            vertical(),
+            vec![at(20), MockEvent::mouse_down(), MockEvent::mouse_up()]
+        ),
+        20
+    );
+    // Past the ends
+    let top = MockEvent::move_to((TRACK_X, 21));
+    assert_eq!(
+        value(
+            vertical(),
+            vec![top, MockEvent::mouse_down(), MockEvent::mouse_up()]
+        ),
+        100
+    );
+}
+
+#[test]
+fn dragging_follows_the_pointer_up_and_down() {
+    let events = vec![
+        at(50),
+        MockEvent::mouse_down(),
+        at(90),
+        at(10),
+        at(30),
+        MockEvent::mouse_up(),
+    ];
+    assert_eq!(value(vertical(), events), 30);
+}
+
+#[test]
+fn the_wheel_works_over_the_track() {
+    let up = MockEvent::Event(WindowEvent::Scroll(ScrollDirection::Up, Modifiers::empty()));
+    assert_eq!(value(vertical(), vec![at(0), up.clone(), up.clone()]), 52);
+    // Beside it, where the value is shown, it doesn't
+    let beside = MockEvent::move_to((TRACK_X - 40, BOTTOM - TRAVEL / 2));
+    assert_eq!(value(vertical(), vec![beside, up]), 50);
+}
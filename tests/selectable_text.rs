@@ -0,0 +1,159 @@
+//! Scripts pointer input against selectable dialog text and checks what
+//! would be copied.
+
+use zenity_rs::{
+    CursorPos, Font, KeyEvent, Modifiers, MouseButton, SelectableText, Widget, WindowEvent, rgb,
+};
+
+const TEXT: &str = "The backup of /home finished with 3 warnings.\nSee the log for details.";
+
+fn widget(max_width: Option<f32>) -> SelectableText {
+    let font = Font::load(1.0);
+    let mut text = SelectableText::new(TEXT, &font, rgb(0, 0, 0), max_width);
+    text.set_position(20, 30);
+    text
+}
+
+fn offset(needle: &str) -> usize {
+    TEXT.find(needle).unwrap()
+}
+
+fn click(text: &mut SelectableText, at: CursorPos) {
+    text.process_event(&WindowEvent::CursorMove(at));
+    text.process_event(&WindowEvent::ButtonPress(
+        MouseButton::Left,
+        Modifiers::empty(),
+    ));
+    text.process_event(&WindowEvent::ButtonRelease(
+        MouseButton::Left,
+        Modifiers::empty(),
+    ));
+}
+
+/// Drags from one byte offset to another.
+fn drag(text: &mut SelectableText, from: usize, to: usize) {
+    let (from, to) = (text.point_at(from), text.point_at(to));
+    text.process_event(&WindowEvent::CursorMove(from));
+    text.process_event(&WindowEvent::ButtonPress(
+        MouseButton::Left,
+        Modifiers::empty(),
+    ));
+    text.process_event(&WindowEvent::CursorMove(to));
+    text.process_event(&WindowEvent::ButtonRelease(
+        MouseButton::Left,
+        Modifiers::empty(),
+    ));
+}
+
+#[test]
+fn drag_across_wrapped_lines() {
+    let mut text = widget(Some(150.0));
+    let start = offset("backup");
+    let end = offset("warnings") + 4;
+    // The first source line must wrap for this to test anything
+    assert!(text.point_at(start).y < text.point_at(end).y);
+
+    drag(&mut text, start, end);
+    assert_eq!(
+        text.selected_text(),
+        Some("backup of /home finished with 3 warn")
+    );
+}
+
+#[test]
+fn drag_backwards() {
+    let mut text = widget(Some(150.0));
+    let start = offset("details");
+    let end = offset("/home");
+    drag(&mut text, start, end);
+    assert_eq!(
+        text.selected_text(),
+        Some("/home finished with 3 warnings.\nSee the log for ")
+    );
+}
+
+#[test]
+fn drag_without_wrapping() {
+    let mut text = widget(None);
+    let start = offset("finished");
+    let end = offset("See") + 3;
+    assert!(text.point_at(start).y < text.point_at(end).y);
+    drag(&mut text, start, end);
+    assert_eq!(text.selected_text(), Some("finished with 3 warnings.\nSee"));
+}
+
+#[test]
+fn double_click_selects_word() {
+    let mut text = widget(Some(150.0));
+    let at = text.point_at(offset("finished") + 3);
+    click(&mut text, at);
+    click(&mut text, at);
+    assert_eq!(text.selected_text(), Some("finished"));
+}
+
+#[test]
+fn triple_click_selects_line() {
+    let mut text = widget(Some(150.0));
+    let at = text.point_at(offset("log"));
+    click(&mut text, at);
+    click(&mut text, at);
+    click(&mut text, at);
+    assert_eq!(text.selected_text(), Some("See the log for details."));
+
+    // The whole source line, even where it was wrapped
+    let at = text.point_at(offset("warnings"));
+    click(
+        &mut text,
+        CursorPos {
+            x: 0,
+            y: 0,
+        },
+    );
+    click(&mut text, at);
+    click(&mut text, at);
+    click(&mut text, at);
+    assert_eq!(
+        text.selected_text(),
+        Some("The backup of /home finished with 3 warnings.")
+    );
+}
+
+#[test]
+fn click_clears_and_ctrl_a_selects_all() {
+    let mut text = widget(Some(150.0));
+    let (a, b) = (offset("backup"), offset("home"));
+    drag(&mut text, a, b);
+    assert!(text.selected_text().is_some());
+
+    // A single click on the text, or anywhere else, drops the selection
+    let at = text.point_at(b);
+    click(&mut text, at);
+    assert_eq!(text.selected_text(), None);
+    drag(&mut text, a, b);
+    click(
+        &mut text,
+        CursorPos {
+            x: 1,
+            y: 1,
+        },
+    );
+    assert_eq!(text.selected_text(), None);
+
+    text.process_event(&WindowEvent::KeyPress(KeyEvent {
+        keysym: 'a' as u32,
+        modifiers: Modifiers::CTRL,
+    }));
+    assert_eq!(text.selected_text(), Some(TEXT));
+}
+
+#[test]
+fn hover_tracks_text_area() {
+    let mut text = widget(Some(150.0));
+    text.process_event(&WindowEvent::CursorMove(text.point_at(offset("log"))));
+    assert!(text.is_hovered());
+    text.process_event(&WindowEvent::CursorMove(CursorPos {
+        x: 1,
+        y: 1,
+    }));
+    assert!(!text.is_hovered());
+}
@@ -0,0 +1,72 @@
+//! Dialogs can be built on one thread and shown on another, several at once.
+
+#![cfg(feature = "cli")]
+
+mod harness;
+
+use std::thread;
+
+use harness::TempDir;
+use zenity_rs::{
+    CalendarBuilder, CalendarResult, Canvas, DialogResult, EntryBuilder, EntryResult, Error,
+    FileSelectBuilder, FileSelectResult, Font, FormsBuilder, FormsResult, ListBuilder, ListResult,
+    MessageBuilder, ProgressBuilder, ProgressResult, ScaleBuilder, ScaleResult, TextInfoBuilder,
+    TextInfoResult, rgb,
+};
+
+const _: () = {
+    const fn assert_send<T: Send>() {}
+    assert_send::<Canvas>();
+    assert_send::<Font>();
+    assert_send::<Error>();
+    assert_send::<DialogResult>();
+    assert_send::<MessageBuilder>();
+    assert_send::<EntryBuilder>();
+    assert_send::<EntryResult>();
+    assert_send::<CalendarBuilder>();
+    assert_send::<CalendarResult>();
+    assert_send::<FileSelectBuilder>();
+    assert_send::<FileSelectResult>();
+    assert_send::<FormsBuilder>();
+    assert_send::<FormsResult>();
+    assert_send::<ListBuilder>();
+    assert_send::<ListResult>();
+    assert_send::<ProgressBuilder>();
+    assert_send::<ProgressResult>();
+    assert_send::<ScaleBuilder>();
+    assert_send::<ScaleResult>();
+    assert_send::<TextInfoBuilder>();
+    assert_send::<TextInfoResult>();
+};
+
+#[test]
+fn eight_concurrent_headless_dialogs() {
+    // The only test in this binary, so nothing else reads the environment
+    let runtime = TempDir::new("send");
+    unsafe {
+        std::env::remove_var("WAYLAND_DISPLAY");
+        std::env::remove_var("WAYLAND_SOCKET");
+        std::env::remove_var("DISPLAY");
+        std::env::set_var("XDG_RUNTIME_DIR", runtime.path());
+    }
+
+    let dialogs: Vec<_> = (0..8)
+        .map(|i| {
+            let builder = zenity_rs::question(&format!("Dialog {i}")).title("Send");
+            thread::spawn(move || {
+                let result = builder.show();
+                let canvas = Font::load(1.0)
+                    .render(&format!("Dialog {i}"))
+                    .with_color(rgb(0, 0, 0))
+                    .finish();
+                (result, canvas)
+            })
+        })
+        .collect();
+
+    for dialog in dialogs {
+        let (result, canvas) = dialog.join().expect("dialog thread panicked");
+        assert!(result.is_err(), "{result:?}");
+        assert!(canvas.width() > 0 && canvas.height() > 0);
+    }
+}
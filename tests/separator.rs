@@ -0,0 +1,60 @@
+//! Joining of multi-value output with `--separator`.
+
+#![cfg(feature = "cli")]
+
+use zenity_rs::{FormsResult, ListResult};
+
+fn rows(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+#[test]
+fn list_rows_are_joined_verbatim() {
+    let result = ListResult::Selected(rows(&["a", "b", "c"]));
+    assert_eq!(result.joined("|").as_deref(), Some("a|b|c"));
+    assert_eq!(result.joined("\n").as_deref(), Some("a\nb\nc"));
+    assert_eq!(result.joined(", ").as_deref(), Some("a, b, c"));
+    // Not an escape sequence
+    assert_eq!(result.joined("\\n").as_deref(), Some("a\\nb\\nc"));
+    assert_eq!(result.joined("").as_deref(), Some("abc"));
+}
+
+#[test]
+fn values_containing_the_separator_are_not_escaped() {
+    let result = ListResult::Selected(rows(&["a|b", "c"]));
+    assert_eq!(result.joined("|").as_deref(), Some("a|b|c"));
+    let result = FormsResult::Values(rows(&["Doe, John", ""]));
+    assert_eq!(result.joined(",").as_deref(), Some("Doe, John,"));
+}
+
+#[test]
+fn nothing_is_printed_without_values() {
+    for result in [
+        ListResult::Cancelled,
+        ListResult::Closed,
+        ListResult::Timeout,
+    ] {
+        assert_eq!(result.joined("|"), None);
+    }
+    assert_eq!(
+        ListResult::Selected(Vec::new()).joined("|").as_deref(),
+        Some("")
+    );
+    assert_eq!(FormsResult::Cancelled.joined("|"), None);
+    assert_eq!(
+        FormsResult::Values(rows(&["x"])).joined("\n").as_deref(),
+        Some("x")
+    );
+}
+
+#[test]
+fn separator_applies_to_every_dialog() {
+    let out = std::process::Command::new(env!("CARGO_BIN_EXE_zenity-rs"))
+        .args(["--info", "--strict", "--separator=,", "--text=x"])
+        .env_remove("WAYLAND_DISPLAY")
+        .env_remove("DISPLAY")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(!stderr.contains("only valid with"), "{stderr}");
+}
@@ -0,0 +1,181 @@
+//! Checks --single-instance against a lock held by another process.
+//!
+//! The runs have no display, so an instance that gets past the lock fails
+//! to open its window and exits with 100 instead of 4.
+
+#![cfg(feature = "cli")]
+
+mod harness;
+
+use std::{
+    fs::File,
+    io::Read,
+    os::{fd::AsRawFd, unix::net::UnixListener},
+    process::{Child, Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use harness::TempDir;
+
+const EXIT_ALREADY_RUNNING: i32 = 4;
+const EXIT_ERROR: i32 = 100;
+
+/// A private XDG_RUNTIME_DIR removed on drop.
+struct RuntimeDir(TempDir);
+
+impl RuntimeDir {
+    fn new(name: &str) -> Self {
+        Self(TempDir::new(&format!("instance-{name}")))
+    }
+
+    /// Holds the lock for `key` like a running dialog would.
+    fn hold(&self, key: &str) -> File {
+        let file = File::create(self.0.path().join(format!("zenity-rs-{key}.lock"))).unwrap();
+        assert_eq!(unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) }, 0);
+        file
+    }
+
+    /// Listens for activation requests like a running dialog would.
+    fn listen(&self, key: &str) -> UnixListener {
+        UnixListener::bind(self.0.path().join(format!("zenity-rs-{key}.sock"))).unwrap()
+    }
+
+    fn command(&self, args: &[&str]) -> Command {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_zenity-rs"));
+        command
+            .args(["--info", "--text=hello"])
+            .args(args)
+            .env("XDG_RUNTIME_DIR", self.0.path())
+            .env_remove("WAYLAND_DISPLAY")
+            .env_remove("DISPLAY")
+            .env_remove("XDG_ACTIVATION_TOKEN")
+            .env_remove("DESKTOP_STARTUP_ID")
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        command
+    }
+
+    fn spawn(&self, args: &[&str]) -> Child {
+        self.command(args).spawn().unwrap()
+    }
+}
+
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> Option<i32> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            return status.code();
+        }
+        if Instant::now() > deadline {
+            let _ = child.kill();
+            panic!("zenity-rs did not exit in {timeout:?}");
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[test]
+fn exits_when_key_is_held() {
+    let dir = RuntimeDir::new("exit");
+    let _held = dir.hold("backup");
+
+    let mut second = dir.spawn(&["--single-instance=backup"]);
+    assert_eq!(
+        wait_with_timeout(&mut second, Duration::from_secs(5)),
+        Some(EXIT_ALREADY_RUNNING)
+    );
+}
+
+#[test]
+fn activates_the_running_dialog_with_our_token() {
+    let dir = RuntimeDir::new("activate");
+    let _held = dir.hold("backup");
+    let listener = dir.listen("backup");
+
+    let mut second = dir
+        .command(&["--single-instance=backup"])
+        .env("XDG_ACTIVATION_TOKEN", "token-123")
+        .spawn()
+        .unwrap();
+    let (mut stream, _) = listener.accept().unwrap();
+    let mut token = String::new();
+    stream.read_to_string(&mut token).unwrap();
+    assert_eq!(token, "token-123");
+    assert_eq!(
+        wait_with_timeout(&mut second, Duration::from_secs(5)),
+        Some(EXIT_ALREADY_RUNNING)
+    );
+}
+
+#[test]
+fn other_keys_are_independent() {
+    let dir = RuntimeDir::new("other");
+    let _held = dir.hold("backup");
+
+    let mut second = dir.spawn(&["--single-instance=sync"]);
+    assert_eq!(
+        wait_with_timeout(&mut second, Duration::from_secs(5)),
+        Some(EXIT_ERROR)
+    );
+}
+
+#[test]
+fn waits_for_holder_to_close() {
+    let dir = RuntimeDir::new("wait");
+    let held = dir.hold("backup");
+
+    let mut second = dir.spawn(&["--single-instance=backup", "--single-instance-wait"]);
+    thread::sleep(Duration::from_millis(300));
+    assert!(
+        second.try_wait().unwrap().is_none(),
+        "second instance did not wait for the lock"
+    );
+
+    drop(held);
+    assert_eq!(
+        wait_with_timeout(&mut second, Duration::from_secs(5)),
+        Some(EXIT_ERROR)
+    );
+}
+
+#[test]
+fn lock_is_released_when_holder_exits() {
+    let dir = RuntimeDir::new("release");
+
+    let mut first = dir.spawn(&["--single-instance=backup"]);
+    assert_eq!(
+        wait_with_timeout(&mut first, Duration::from_secs(5)),
+        Some(EXIT_ERROR)
+    );
+
+    // The first run failed after taking the lock; it must not be left held
+    let mut second = dir.spawn(&["--single-instance=backup"]);
+    assert_eq!(
+        wait_with_timeout(&mut second, Duration::from_secs(5)),
+        Some(EXIT_ERROR)
+    );
+}
+
+#[test]
+fn wait_requires_key() {
+    let dir = RuntimeDir::new("requires");
+
+    let mut child = dir.spawn(&["--single-instance-wait"]);
+    assert_eq!(
+        wait_with_timeout(&mut child, Duration::from_secs(5)),
+        Some(EXIT_ERROR)
+    );
+}
+
+#[test]
+fn the_activation_socket_is_removed_on_exit() {
+    let dir = RuntimeDir::new("cleanup");
+    let mut first = dir.spawn(&["--single-instance=backup"]);
+    assert_eq!(
+        wait_with_timeout(&mut first, Duration::from_secs(5)),
+        Some(EXIT_ERROR)
+    );
+    assert!(!dir.0.path().join("zenity-rs-backup.sock").exists());
+}
@@ -0,0 +1,104 @@
+//! The same command lines with and without --strict: a flag the dialog
+//! ignores, or a value out of its range, is a warning otherwise and an error
+//! with it.
+//!
+//! The runs have no display, so a permissive run gets as far as opening the
+//! window and fails there; a strict one must stop before that.
+
+#![cfg(feature = "cli")]
+
+use std::process::{Command, Stdio};
+
+fn run(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_zenity-rs"))
+        .args(args)
+        .env_remove("WAYLAND_DISPLAY")
+        .env_remove("DISPLAY")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(100), "{args:?}");
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+/// Runs `args` both ways: `message` is a warning, followed by `then`, and the
+/// run goes on; with --strict it's the only error.
+fn assert_strict_only(args: &[&str], flag: &str, message: &str, then: &str) {
+    let permissive = run(args);
+    assert!(
+        permissive.contains(&format!("zenity-rs: warning: {message}, {then}\n")),
+        "{args:?}: {permissive}"
+    );
+    let last = permissive.lines().last().unwrap();
+    assert!(
+        !last.contains(flag),
+        "{args:?} stopped at {flag}: {permissive}"
+    );
+
+    let strict = run(&[args, &["--strict"]].concat());
+    assert_eq!(strict, format!("zenity-rs: {message}\n"), "{args:?}");
+}
+
+#[test]
+fn a_flag_for_another_dialog() {
+    assert_strict_only(
+        &["--info", "--percentage=5"],
+        "--percentage",
+        "--percentage is only valid with --progress, not --info",
+        "ignoring",
+    );
+    assert_strict_only(
+        &["--scale", "--multiple"],
+        "--multiple",
+        "--multiple is only valid with --file-selection or --list, not --scale",
+        "ignoring",
+    );
+}
+
+#[test]
+fn a_progress_percentage_out_of_range() {
+    assert_strict_only(
+        &["--progress", "--percentage=150"],
+        "--percentage",
+        "--percentage=150 is out of range for --progress (0..=100)",
+        "using 100",
+    );
+}
+
+#[test]
+fn a_calendar_date_out_of_range() {
+    assert_strict_only(
+        &["--calendar", "--month=13"],
+        "--month",
+        "--month=13 is out of range for --calendar (1..=12)",
+        "using 12",
+    );
+    assert_strict_only(
+        &["--calendar", "--day=0"],
+        "--day",
+        "--day=0 is out of range for --calendar (1..=31)",
+        "using 1",
+    );
+}
+
+#[test]
+fn a_scale_value_out_of_range() {
+    assert_strict_only(
+        &["--scale", "--value=500"],
+        "--value",
+        "--value=500 is out of range for --scale (0..=100)",
+        "using 100",
+    );
+    assert_strict_only(
+        &["--scale", "--step=0"],
+        "--step",
+        &format!("--step=0 is out of range for --scale (1..={})", i32::MAX),
+        "using 1",
+    );
+    assert_strict_only(
+        &["--scale", "--min-value=9", "--max-value=1", "--value=5"],
+        "--min-value",
+        "--min-value=9 is greater than --max-value=1",
+        "swapping them",
+    );
+}
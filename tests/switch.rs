@@ -0,0 +1,42 @@
+//! Command-line validation of `--switch`.
+//!
+//! The runs have no display, so a dialog that gets past option parsing fails
+//! to open its window and exits with 100 and a display error.
+
+#![cfg(feature = "cli")]
+
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_zenity-rs"))
+        .args(args)
+        .env_remove("WAYLAND_DISPLAY")
+        .env_remove("DISPLAY")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn requires_an_extra_button() {
+    let output = run(&["--question", "--switch", "--text=Pick one"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(100));
+    assert!(
+        stderr.contains("--switch requires at least one --extra-button"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn accepted_with_extra_buttons() {
+    let output = run(&[
+        "--question",
+        "--switch",
+        "--extra-button=A",
+        "--extra-button=B",
+        "--extra-button=C",
+    ]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("--switch requires"), "{stderr}");
+}
@@ -0,0 +1,92 @@
+//! Aligning each line of text left, center or right within the max width.
+
+use zenity_rs::{Align, Font, TextLayout};
+
+const PARAGRAPH: &str = "Aligned text places every wrapped line on its own, \
+                         so a centered paragraph has each of its lines centered";
+const MAX_WIDTH: f32 = 200.0;
+
+/// The (left, right) edges of each visual line of `text`.
+fn line_edges(layout: &TextLayout, text: &str) -> Vec<(f32, f32)> {
+    let rects = layout.range_rects(0..text.len());
+    let last = rects.len() - 1;
+    rects
+        .iter()
+        .enumerate()
+        .map(|(i, &(x, _, w, _))| {
+            // Lines a range carries on past are drawn a little longer
+            let carry = if i < last {
+                layout.line_height() / 4.0
+            } else {
+                0.0
+            };
+            (x, x + w - carry)
+        })
+        .collect()
+}
+
+fn aligned(font: &Font, text: &str, align: Align) -> TextLayout {
+    font.render(text)
+        .with_max_width(MAX_WIDTH)
+        .with_align(align)
+        .text_layout()
+}
+
+#[test]
+fn aligned_text_spans_the_max_width() {
+    let font = Font::load(1.0);
+    let (natural_w, _) = font.render("OK").measure();
+    for align in [Align::Left, Align::Center, Align::Right] {
+        let renderer = font
+            .render("OK")
+            .with_max_width(MAX_WIDTH)
+            .with_align(align);
+        let (w, _) = renderer.measure();
+        assert!((w - MAX_WIDTH).abs() <= 1.0, "{align:?}: {w}");
+        assert_eq!(renderer.finish().width(), w.ceil() as u32 + 2, "{align:?}");
+    }
+    // Unaligned text is as wide as it is
+    let (w, _) = font.render("OK").with_max_width(MAX_WIDTH).measure();
+    assert_eq!(w, natural_w);
+}
+
+#[test]
+fn each_wrapped_line_is_aligned_on_its_own() {
+    let font = Font::load(1.0);
+
+    let left = line_edges(&aligned(&font, PARAGRAPH, Align::Left), PARAGRAPH);
+    assert!(left.len() >= 3, "{left:?}");
+    let origin = left[0].0;
+    assert!(left.iter().all(|&(x, _)| x == origin), "{left:?}");
+
+    let right = line_edges(&aligned(&font, PARAGRAPH, Align::Right), PARAGRAPH);
+    for &(_, end) in &right {
+        assert!((end - (origin + MAX_WIDTH)).abs() <= 1.0, "{right:?}");
+    }
+
+    let center = line_edges(&aligned(&font, PARAGRAPH, Align::Center), PARAGRAPH);
+    assert_eq!(center.len(), left.len());
+    for &(start, end) in &center {
+        let middle = (start + end) / 2.0;
+        assert!(
+            (middle - (origin + MAX_WIDTH / 2.0)).abs() <= 1.0,
+            "{center:?}"
+        );
+    }
+    // Lines of different lengths start in different places
+    assert!(center.windows(2).any(|pair| pair[0].0 != pair[1].0));
+}
+
+#[test]
+fn without_a_max_width_lines_align_to_the_widest() {
+    let font = Font::load(1.0);
+    let text = "Short\nA rather longer line";
+    let (widest, _) = font.render("A rather longer line").measure();
+    let renderer = font.render(text).with_align(Align::Right);
+    let (w, _) = renderer.measure();
+    assert!((w - widest).abs() <= 2.0, "{w} vs {widest}");
+
+    let edges = line_edges(&renderer.text_layout(), text);
+    assert!((edges[0].1 - edges[1].1).abs() <= 1.0, "{edges:?}");
+    assert!(edges[0].0 > edges[1].0, "{edges:?}");
+}
@@ -0,0 +1,158 @@
+//! How dialog text is fitted to a width: wrapped, left alone (`--no-wrap`)
+//! or ellipsized (`--ellipsize`).
+
+use std::ops::Range;
+
+use zenity_rs::{EllipsizeMode, Font, SelectableText, TextLayout, Widget, rgb};
+
+const LONG: &str = "The quick brown fox jumps over the lazy dog and keeps on running";
+const MAX_WIDTH: f32 = 150.0;
+
+#[test]
+fn ellipsized_line_fits_the_width_on_one_line() {
+    let font = Font::load(1.0);
+    let (natural_w, line_h) = font.render(LONG).measure();
+    assert!(natural_w > MAX_WIDTH);
+
+    let (w, h) = font
+        .render(LONG)
+        .with_max_width(MAX_WIDTH)
+        .with_ellipsize(EllipsizeMode::End)
+        .measure();
+    assert!(w <= MAX_WIDTH + 1.0, "{w}");
+    assert!(w > MAX_WIDTH / 2.0, "{w}");
+    assert!((h - line_h).abs() < 1.0, "{h} vs {line_h}");
+}
+
+#[test]
+fn wrapping_is_the_default() {
+    let font = Font::load(1.0);
+    let (_, line_h) = font.render(LONG).measure();
+    let (w, h) = font.render(LONG).with_max_width(MAX_WIDTH).measure();
+    assert!(w <= MAX_WIDTH + 1.0, "{w}");
+    assert!(h > line_h * 2.0, "{h}");
+}
+
+#[test]
+fn short_text_is_not_ellipsized() {
+    let font = Font::load(1.0);
+    let plain = font.render("Done").measure();
+    let ellipsized = font
+        .render("Done")
+        .with_max_width(MAX_WIDTH)
+        .with_ellipsize(EllipsizeMode::End)
+        .measure();
+    assert_eq!(plain, ellipsized);
+}
+
+#[test]
+fn each_line_is_ellipsized_separately() {
+    let font = Font::load(1.0);
+    let text = format!("Short\n{LONG}");
+    let (_, wrapped_h) = font.render(&text).with_max_width(MAX_WIDTH).measure();
+    let (_, two_lines) = font.render("Short\nThe quick").measure();
+    let (w, h) = font
+        .render(&text)
+        .with_max_width(MAX_WIDTH)
+        .with_ellipsize(EllipsizeMode::End)
+        .measure();
+    assert!(w <= MAX_WIDTH + 1.0, "{w}");
+    assert!(h < wrapped_h, "{h} vs wrapped {wrapped_h}");
+    assert!((h - two_lines).abs() < 4.0, "{h} vs two lines {two_lines}");
+}
+
+#[test]
+fn selecting_the_ellipsis_selects_the_hidden_text() {
+    let font = Font::load(1.0);
+    let mut text = SelectableText::ellipsized(LONG, &font, rgb(0, 0, 0), MAX_WIDTH);
+    assert!(text.width() as f32 <= MAX_WIDTH + 3.0);
+    assert_eq!(text.text(), LONG);
+
+    text.select_all();
+    assert_eq!(text.selected_text(), Some(LONG));
+
+    // The end of the line maps to the end of the text, past the hidden part
+    let end = text.point_at(LONG.len());
+    assert!((end.x as f32) <= MAX_WIDTH + 3.0, "{end:?}");
+}
+
+/// The bytes of `text` an ellipsis hides in `layout`: the characters all
+/// laid out at the ellipsis. `text` mustn't have spaces or zero-width
+/// characters, whose boundaries share an x with the character before.
+fn hidden(layout: &TextLayout, text: &str) -> Range<usize> {
+    let boundaries: Vec<usize> = (0..=text.len())
+        .filter(|&i| text.is_char_boundary(i))
+        .collect();
+    let x = |offset: usize| layout.position_of(offset).0;
+    let start = boundaries
+        .windows(2)
+        .find(|pair| x(pair[0]) == x(pair[1]))
+        .map(|pair| pair[0])
+        .expect("nothing hidden");
+    let end = boundaries
+        .iter()
+        .copied()
+        .find(|&i| i > start && x(i) > x(start))
+        .unwrap_or(text.len());
+    start..end
+}
+
+#[test]
+fn the_ellipsis_can_go_at_the_end_middle_or_start() {
+    const WORD: &str = "Antidisestablishmentarianism-and-supercalifragilistic";
+    let font = Font::load(1.0);
+    for (mode, check) in [
+        (
+            EllipsizeMode::End,
+            (|r: &Range<usize>| r.start > 0 && r.end == WORD.len()) as fn(&_) -> bool,
+        ),
+        (EllipsizeMode::Middle, |r| r.start > 0 && r.end < WORD.len()),
+        (EllipsizeMode::Start, |r| r.start == 0 && r.end < WORD.len()),
+    ] {
+        let renderer = font
+            .render(WORD)
+            .with_max_width(MAX_WIDTH)
+            .with_ellipsize(mode);
+        let (w, _) = renderer.measure();
+        assert!(w <= MAX_WIDTH + 1.0, "{mode:?}: {w}");
+        assert!(w > MAX_WIDTH * 0.75, "{mode:?}: {w}");
+        let hidden = hidden(&renderer.text_layout(), WORD);
+        assert!(check(&hidden), "{mode:?}: {hidden:?}");
+    }
+
+    // The middle keeps about as much from each end
+    let layout = font
+        .render(WORD)
+        .with_max_width(MAX_WIDTH)
+        .with_ellipsize(EllipsizeMode::Middle)
+        .text_layout();
+    let hidden = hidden(&layout, WORD);
+    let (head, tail) = (hidden.start, WORD.len() - hidden.end);
+    assert!(head.abs_diff(tail) <= 4, "{head} vs {tail}");
+}
+
+#[test]
+fn flags_are_never_split() {
+    // Each flag is a pair of regional indicators, four bytes each
+    let flags = "🇫🇷🇩🇪🇮🇹🇪🇸🇵🇹🇳🇱🇧🇪🇸🇪🇳🇴🇫🇮🇩🇰🇵🇱🇬🇷🇮🇪🇦🇹🇨🇭";
+    let font = Font::load(1.0);
+    let (natural_w, _) = font.render(flags).measure();
+    for mode in [
+        EllipsizeMode::End,
+        EllipsizeMode::Middle,
+        EllipsizeMode::Start,
+    ] {
+        for max_width in (20..natural_w as u32).step_by(7) {
+            let layout = font
+                .render(flags)
+                .with_max_width(max_width as f32)
+                .with_ellipsize(mode)
+                .text_layout();
+            let hidden = hidden(&layout, flags);
+            assert!(
+                hidden.start.is_multiple_of(8) && hidden.end.is_multiple_of(8),
+                "{mode:?} at {max_width}: {hidden:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,10 @@
+//! Loading the `--text-info` font by family name.
+
+use zenity_rs::Font;
+
+#[test]
+fn a_named_font_keeps_its_size_without_a_match() {
+    let font = Font::load_named("No Such Family", Some(20.0), 2.0);
+    assert_eq!(font.size(), 40.0);
+    assert_eq!(Font::load_named("No Such Family", None, 1.0).size(), 15.0);
+}
@@ -0,0 +1,100 @@
+//! Searching the text of a `--text-info` dialog with Ctrl+F.
+//!
+//! Needs the scripted backend: `cargo test --test text_info_search --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "textinfo"))]
+
+mod harness;
+
+use std::fs;
+
+use harness::TempDir;
+use zenity_rs::{
+    Modifiers, TextInfoBuilder, TextInfoResult, WindowEvent,
+    mock::{self, KEY_ESCAPE, KEY_F, KEY_RETURN, MockEvent},
+};
+
+fn find() -> MockEvent {
+    MockEvent::key_with(KEY_F, Modifiers::CTRL)
+}
+
+/// A dialog showing a hundred numbered lines, with `events` sent.
+fn show(builder: TextInfoBuilder, events: Vec<MockEvent>) -> TextInfoResult {
+    let dir = TempDir::new("search");
+    let file = dir.path().join("lines.txt");
+    let text: String = (1..=100).map(|i| format!("line {i}\n")).collect();
+    fs::write(&file, text).unwrap();
+    mock::script(events.into_iter().chain([MockEvent::Disconnect]));
+    builder.filename(file.to_str().unwrap()).show().unwrap()
+}
+
+fn accepted(result: TextInfoResult) -> bool {
+    match result {
+        TextInfoResult::Ok {
+            ..
+        } => true,
+        TextInfoResult::Cancelled => false,
+        result => panic!("{result:?}"),
+    }
+}
+
+#[test]
+fn escape_closes_the_bar_before_the_dialog() {
+    let dialog = zenity_rs::text_info;
+    let events = vec![
+        find(),
+        MockEvent::key(KEY_ESCAPE),
+        MockEvent::key(KEY_RETURN),
+    ];
+    assert!(accepted(show(dialog(), events)));
+    let mut events = vec![find()];
+    events.extend(MockEvent::type_text("line 9"));
+    events.extend([
+        MockEvent::key(KEY_RETURN),
+        MockEvent::key(KEY_ESCAPE),
+        MockEvent::key(KEY_RETURN),
+    ]);
+    assert!(accepted(show(dialog(), events)));
+    let events = vec![
+        find(),
+        MockEvent::key(KEY_ESCAPE),
+        MockEvent::key(KEY_ESCAPE),
+    ];
+    assert!(!accepted(show(dialog(), events)));
+}
+
+#[test]
+fn enter_steps_through_matches_instead_of_accepting() {
+    let mut events = vec![find()];
+    events.extend(MockEvent::type_text("LINE 5"));
+    events.extend([
+        MockEvent::key(KEY_RETURN),
+        MockEvent::key(KEY_RETURN),
+        MockEvent::key_with(KEY_RETURN, Modifiers::SHIFT),
+        // No matches
+        MockEvent::Event(WindowEvent::TextInput('x')),
+        MockEvent::key(KEY_RETURN),
+        MockEvent::key(KEY_ESCAPE),
+        MockEvent::key(KEY_ESCAPE),
+    ]);
+    assert!(!accepted(show(zenity_rs::text_info(), events)));
+}
+
+#[test]
+fn the_bar_takes_typing() {
+    // A space otherwise checks the checkbox, which lets Enter accept
+    let checkbox = || zenity_rs::text_info().checkbox("I agree");
+    let events = vec![
+        MockEvent::Event(WindowEvent::TextInput(' ')),
+        MockEvent::key(KEY_RETURN),
+    ];
+    assert!(accepted(show(checkbox(), events)));
+    let events = vec![
+        find(),
+        MockEvent::Event(WindowEvent::TextInput(' ')),
+        MockEvent::key(KEY_ESCAPE),
+        MockEvent::key(KEY_RETURN),
+        MockEvent::key(KEY_ESCAPE),
+    ];
+    assert!(!accepted(show(checkbox(), events)));
+}
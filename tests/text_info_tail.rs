@@ -0,0 +1,57 @@
+//! Following text from stdin with `--auto-scroll`, and `--max-lines`.
+
+#![cfg(all(feature = "textinfo", feature = "cli"))]
+
+use std::{
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+fn text_info(args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_zenity-rs"));
+    command
+        .arg("--text-info")
+        .args(args)
+        .env_remove("WAYLAND_DISPLAY")
+        .env_remove("DISPLAY");
+    command
+}
+
+#[test]
+fn max_lines_must_be_a_positive_integer() {
+    for value in ["0", "-5", "many", ""] {
+        let arg = format!("--max-lines={value}");
+        let output = text_info(&[&arg]).stdin(Stdio::null()).output().unwrap();
+        assert_eq!(output.status.code(), Some(100), "{arg}");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains("expected a positive integer"),
+            "{arg}: {stderr}"
+        );
+    }
+}
+
+#[test]
+fn following_opens_the_window_before_stdin_closes() {
+    // Stdin stays open for the whole test, so only a dialog that reads it
+    // in the background gets as far as finding there's no display
+    let mut child = text_info(&["--auto-scroll", "--max-lines=10"])
+        .stdin(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let _stdin = child.stdin.take();
+    let until = Instant::now() + Duration::from_secs(10);
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break status;
+        }
+        if Instant::now() >= until {
+            child.kill().unwrap();
+            panic!("still waiting for stdin");
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+    assert_eq!(status.code(), Some(100));
+}
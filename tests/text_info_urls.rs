@@ -0,0 +1,97 @@
+//! Opening URLs in `--text-info` text when they're clicked.
+//!
+//! Needs the scripted backend: `cargo test --test text_info_urls --features mock-backend`.
+
+#![cfg(all(feature = "mock-backend", feature = "textinfo"))]
+
+mod harness;
+
+use std::{
+    fs,
+    os::unix::fs::PermissionsExt,
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
+
+use harness::TempDir;
+use zenity_rs::{
+    TextInfoResult,
+    mock::{self, KEY_RETURN, MockEvent},
+};
+
+/// Clicks 6px into the text on row `row`, counting from 0.
+fn click_row(row: i16) -> Vec<MockEvent> {
+    // Text starts 8px inside the 16px-padded text area, 20px per row
+    vec![
+        MockEvent::move_to((30, 24 + row * 20 + 10)),
+        MockEvent::mouse_down(),
+        MockEvent::mouse_up(),
+    ]
+}
+
+/// What the fake `xdg-open` was asked to open, waiting a while for it.
+fn opened(file: &Path) -> Option<String> {
+    let until = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < until {
+        if let Ok(text) = fs::read_to_string(file)
+            && text.ends_with('\n')
+        {
+            return Some(text.trim_end().to_string());
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    None
+}
+
+#[test]
+fn clicking_any_row_of_a_wrapped_url_opens_all_of_it() {
+    let scratch = TempDir::new("urls");
+    let dir = scratch.path();
+    let opened_file = dir.join("opened");
+    let script = dir.join("xdg-open");
+    fs::write(
+        &script,
+        format!("#!/bin/sh\necho \"$1\" > {:?}\n", opened_file),
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    // SAFETY: the only test in this binary that touches the environment
+    unsafe {
+        std::env::set_var(
+            "PATH",
+            format!("{}:{}", dir.display(), std::env::var("PATH").unwrap()),
+        );
+    }
+
+    // Far too long for one row, so it starts on the second and goes on
+    // to the third
+    let url = format!("https://example.org/{}", "a".repeat(150));
+    let text = dir.join("text");
+    fs::write(&text, format!("See {url} for more.")).unwrap();
+    let run = |url_support: bool, events: Vec<MockEvent>| {
+        let _ = fs::remove_file(&opened_file);
+        mock::script(
+            events
+                .into_iter()
+                .chain([MockEvent::key(KEY_RETURN), MockEvent::Disconnect]),
+        );
+        let result = zenity_rs::text_info()
+            .filename(text.to_str().unwrap())
+            .url_support(url_support)
+            .show()
+            .unwrap();
+        assert!(matches!(result, TextInfoResult::Ok { .. }), "{result:?}");
+    };
+
+    run(true, click_row(2));
+    assert_eq!(opened(&opened_file).as_deref(), Some(url.as_str()));
+    run(true, click_row(1));
+    assert_eq!(opened(&opened_file).as_deref(), Some(url.as_str()));
+
+    // "See" isn't part of it
+    run(true, click_row(0));
+    run(false, click_row(1));
+    thread::sleep(Duration::from_millis(300));
+    assert!(!opened_file.exists());
+}
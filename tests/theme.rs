@@ -0,0 +1,61 @@
+//! Choosing the colors with `--theme` and `RASK_THEME`.
+
+#![cfg(feature = "cli")]
+
+use std::process::{Command, Output, Stdio};
+
+use zenity_rs::{THEME_DARK, THEME_LIGHT, Theme};
+
+fn run(args: &[&str], env: Option<&str>) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_zenity-rs"));
+    command
+        .args(args)
+        .env_remove("WAYLAND_DISPLAY")
+        .env_remove("DISPLAY")
+        .env_remove("RASK_THEME")
+        .stdin(Stdio::null());
+    if let Some(value) = env {
+        command.env("RASK_THEME", value);
+    }
+    command.output().unwrap()
+}
+
+#[test]
+fn names_pick_the_colors() {
+    assert!(std::ptr::eq(
+        Theme::from_name("light").unwrap().colors(),
+        &THEME_LIGHT
+    ));
+    assert!(std::ptr::eq(
+        Theme::from_name("dark").unwrap().colors(),
+        &THEME_DARK
+    ));
+    assert_eq!(Theme::from_name("auto"), Some(Theme::Auto));
+    assert_eq!(Theme::from_name("Dark"), None);
+    assert_eq!(Theme::from_name(""), None);
+}
+
+#[test]
+fn invalid_theme_fails_before_a_window_opens() {
+    let output = run(&["--info", "--text=Hi", "--theme=purple"], None);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(100));
+    assert!(stderr.contains("--theme"), "{stderr}");
+    assert!(!stderr.contains("display"), "{stderr}");
+
+    let output = run(&["--info", "--text=Hi"], Some("purple"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(100));
+    assert!(stderr.contains("RASK_THEME"), "{stderr}");
+}
+
+#[test]
+fn flag_overrides_the_environment() {
+    // Gets as far as opening the window, which fails without a display
+    let output = run(&["--info", "--text=Hi", "--theme=dark"], Some("purple"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("RASK_THEME"), "{stderr}");
+    let output = run(&["--info", "--text=Hi"], Some(""));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("RASK_THEME"), "{stderr}");
+}
@@ -0,0 +1,55 @@
+//! Checks that `--timeout` is accepted by every dialog type.
+//!
+//! The runs have no display, so a dialog that gets past option parsing fails
+//! to open its window and exits with 100; what matters is that `--strict`
+//! doesn't reject the flag first.
+
+#![cfg(feature = "cli")]
+
+use std::process::{Command, Stdio};
+
+const DIALOGS: &[&[&str]] = &[
+    &["--info"],
+    &["--question"],
+    &["--entry"],
+    &["--password"],
+    &["--progress"],
+    &["--file-selection"],
+    &["--list", "--column=A", "a"],
+    &["--calendar"],
+    &["--text-info"],
+    &["--scale"],
+    &["--forms", "--add-entry=Name"],
+];
+
+#[test]
+fn accepted_by_every_dialog() {
+    for args in DIALOGS {
+        let output = Command::new(env!("CARGO_BIN_EXE_zenity-rs"))
+            .args(*args)
+            .args(["--timeout=1", "--strict"])
+            .env_remove("WAYLAND_DISPLAY")
+            .env_remove("DISPLAY")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            !stderr.contains("only valid with"),
+            "{args:?} rejected --timeout: {stderr}"
+        );
+        assert_eq!(output.status.code(), Some(100), "{args:?}: {stderr}");
+    }
+}
+
+#[test]
+fn rejects_non_numeric_value() {
+    let output = Command::new(env!("CARGO_BIN_EXE_zenity-rs"))
+        .args(["--entry", "--timeout=soon"])
+        .env_remove("WAYLAND_DISPLAY")
+        .env_remove("DISPLAY")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(100));
+}